@@ -35,10 +35,10 @@ chmod 0600 "$PGDATA/server.key"
 
 cat > "$PGDATA/server.crt" <<-EOCERT
 -----BEGIN CERTIFICATE-----
-MIIDkzCCAnugAwIBAgIUTF4DePeZT01AB1mAluKLZDeRGtUwDQYJKoZIhvcNAQEL
+MIID5TCCAs2gAwIBAgIUVpsPFyd2RfiOTddR2aHQPJi84WMwDQYJKoZIhvcNAQEL
 BQAwWTELMAkGA1UEBhMCQVUxEzARBgNVBAgMClNvbWUtU3RhdGUxITAfBgNVBAoM
 GEludGVybmV0IFdpZGdpdHMgUHR5IEx0ZDESMBAGA1UEAwwJbG9jYWxob3N0MB4X
-DTI2MDYwOTA4NDY1MVoXDTM2MDYwNjA4NDY1MVowWTELMAkGA1UEBhMCQVUxEzAR
+DTI2MTAxNjE1MTY1MVoXDTM2MTAxMzE1MTY1MVowWTELMAkGA1UEBhMCQVUxEzAR
 BgNVBAgMClNvbWUtU3RhdGUxITAfBgNVBAoMGEludGVybmV0IFdpZGdpdHMgUHR5
 IEx0ZDESMBAGA1UEAwwJbG9jYWxob3N0MIIBIjANBgkqhkiG9w0BAQEFAAOCAQ8A
 MIIBCgKCAQEAoBuQbHbDf5DXwzAugtcvs3/URbYWQ5XMSOOfgWn/SRI6XeaQKry+
@@ -46,15 +46,16 @@ MIIBCgKCAQEAoBuQbHbDf5DXwzAugtcvs3/URbYWQ5XMSOOfgWn/SRI6XeaQKry+
 oN6+vXekYrVcviLXYx55T/RK0NROzNszh8aKbNGjxQKLigw/ipcjy8qUk0rgnfra
 C3BaC5pW5Su773L/U3GOfQN+PLEfDf0fqIiBfJoBRGThzdn9857xMh2hC8MMxktE
 fm4huFdWRghJrqIl+AMv+3zAYWFtWOMlSgRXKAIn0Q6yBMfWSE25sTMjnGhk07IZ
-MvFGlWqGWVByxJ1cG3BMd6RLta02NYv5KwIDAQABo1MwUTAdBgNVHQ4EFgQUpiCS
-JHcnxUjr4GKKg0Icbmk4W14wHwYDVR0jBBgwFoAUpiCSJHcnxUjr4GKKg0Icbmk4
-W14wDwYDVR0TAQH/BAUwAwEB/zANBgkqhkiG9w0BAQsFAAOCAQEAgZK5UPdkhgI8
-BEeV7gm/ZmDiX3Dhp8l+PjagpDSpXkV0LmCEeA4jxskL5FdIr349lT4CZ7QWPkyv
-OEmwG4N6qWU5H0fNmSHwBPVhQy6EknmCAhs0LsVXoy235ONHFbdgi3SVfJ5pMYgK
-BVX/ADXErM4ifJzQoZFuQ6S8P3IstDDybeT+aPWOi5Fh2o7wYp9GqBFL/G+Nh9SI
-xPs3x8oYzw/qDTXHduL1Onb8+ZTOSkMC9ONDUgmQUlIlpd/SCC1M8wzleyH5atMc
-h/kmDuWEegS1ohJrZ1ATA5u66hyWU2eDFHi1NCIYwIuEfoULKihSqABDSuwe3wXS
-RkTYLofsiw==
+MvFGlWqGWVByxJ1cG3BMd6RLta02NYv5KwIDAQABo4GkMIGhMB0GA1UdDgQWBBSm
+IJIkdyfFSOvgYoqDQhxuaThbXjAfBgNVHSMEGDAWgBSmIJIkdyfFSOvgYoqDQhxu
+aThbXjAsBgNVHREEJTAjgglsb2NhbGhvc3SHBH8AAAGHEAAAAAAAAAAAAAAAAAAA
+AAEwDAYDVR0TAQH/BAIwADAOBgNVHQ8BAf8EBAMCBaAwEwYDVR0lBAwwCgYIKwYB
+BQUHAwEwDQYJKoZIhvcNAQELBQADggEBAE7hNfb34k1LGgPaiiPaU6zAibbO2K2N
+CENCaFc4nmRXkjD0I3n46vAUF23XbpmDtbmVHXYyha9MX1ZGKZvMhhzlXTuOqcRu
+hB8MndsVMywjP+XvqYd340O6Qe9w+971vPB7rrU620iB7sR/w4TEtNCwku5mtZ06
+y67qcmnPmdRKBlrlLQHDAvHE2Y5NvQ55/fBY1V2/Yt4iPk7476C0xk4D7lmOPjAO
+FR6uYV67cQgy82gThdZqL+BoQXYVwADc4caP7Vf06uM2vkexcn/LV6BFwXeQYU9D
+2izGbJPkcz0kOBtsEpsKj8mygzkMJrtSzv5ZCW7nKkrZo0iWVaLhk8c=
 -----END CERTIFICATE-----
 EOCERT
 
@@ -96,4 +97,5 @@ psql -v ON_ERROR_STOP=1 --username "$POSTGRES_USER" <<-EOSQL
     CREATE EXTENSION hstore;
     CREATE EXTENSION citext;
     CREATE EXTENSION ltree;
+    CREATE EXTENSION cube;
 EOSQL
@@ -0,0 +1,22 @@
+use postgres::FromRow;
+
+#[derive(FromRow)]
+#[postgres(crate = "postgres")]
+struct Author {
+    id: i32,
+}
+
+#[derive(FromRow)]
+#[postgres(crate = "postgres")]
+struct Post {
+    #[postgres(default, prefix = "author_")]
+    author: Author,
+}
+
+#[derive(FromRow)]
+struct Foo {
+    #[postgres(crate = "postgres")]
+    id: i32,
+}
+
+fn main() {}
@@ -132,6 +132,130 @@ fn rename_all_overrides() {
     );
 }
 
+#[test]
+fn ignore_case() {
+    #[derive(FromSql, ToSql, Debug, PartialEq)]
+    #[postgres(name = "inventory_item", ignore_case)]
+    struct InventoryItem {
+        name: String,
+        supplier_id: i32,
+        price: Option<f64>,
+    }
+
+    let mut conn = Client::connect("user=postgres host=localhost port=5433", NoTls).unwrap();
+    conn.batch_execute(
+        "CREATE TYPE pg_temp.inventory_item AS (
+            \"Name\" TEXT,
+            \"SUPPLIER_ID\" INT,
+            price DOUBLE PRECISION
+        );",
+    )
+    .unwrap();
+
+    let item = InventoryItem {
+        name: "foobar".to_owned(),
+        supplier_id: 100,
+        price: Some(15.50),
+    };
+
+    test_type(
+        &mut conn,
+        "inventory_item",
+        &[(item, "ROW('foobar', 100, 15.50)")],
+    );
+}
+
+#[test]
+fn borrowed() {
+    #[derive(FromSql, ToSql, Debug, PartialEq)]
+    #[postgres(name = "attachment")]
+    struct Attachment<'a> {
+        name: &'a str,
+        data: &'a [u8],
+        size: i32,
+    }
+
+    let mut conn = Client::connect("user=postgres host=localhost port=5433", NoTls).unwrap();
+    conn.batch_execute(
+        "CREATE TYPE pg_temp.attachment AS (
+            name TEXT,
+            data BYTEA,
+            size INT
+        );",
+    )
+    .unwrap();
+
+    let attachment = Attachment {
+        name: "hello.txt",
+        data: b"hello",
+        size: 5,
+    };
+
+    let row = conn
+        .query_one(
+            "SELECT ROW('hello.txt', 'hello'::BYTEA, 5)::attachment",
+            &[],
+        )
+        .unwrap();
+    assert_eq!(row.get::<_, Attachment<'_>>(0), attachment);
+
+    let row = conn
+        .query_one("SELECT $1::attachment", &[&attachment])
+        .unwrap();
+    assert_eq!(row.get::<_, Attachment<'_>>(0), attachment);
+}
+
+#[test]
+fn positional() {
+    #[derive(FromSql, ToSql, Debug, PartialEq)]
+    #[postgres(name = "inventory_item", positional)]
+    struct InventoryItem {
+        name: String,
+        supplier_id: i32,
+        price: Option<f64>,
+    }
+
+    let mut conn = Client::connect("user=postgres host=localhost port=5433", NoTls).unwrap();
+    conn.batch_execute(
+        "CREATE TYPE pg_temp.inventory_item AS (
+            item_name TEXT,
+            supplier INT,
+            unit_price DOUBLE PRECISION
+        );",
+    )
+    .unwrap();
+
+    let item = InventoryItem {
+        name: "foobar".to_owned(),
+        supplier_id: 100,
+        price: Some(15.50),
+    };
+    test_type(
+        &mut conn,
+        "inventory_item",
+        &[(item, "ROW('foobar', 100, 15.50)")],
+    );
+
+    let row = conn
+        .query_one("SELECT ROW('foobar', 100, 15.50::DOUBLE PRECISION)", &[])
+        .unwrap();
+    assert_eq!(
+        row.get::<_, InventoryItem>(0),
+        InventoryItem {
+            name: "foobar".to_owned(),
+            supplier_id: 100,
+            price: Some(15.50),
+        }
+    );
+
+    let err = conn
+        .query_one("SELECT ROW('foobar', 100, 15.50)", &[])
+        .unwrap()
+        .try_get::<_, InventoryItem>(0)
+        .unwrap_err();
+    assert!(err.source().unwrap().is::<WrongType>());
+}
+
 #[test]
 fn wrong_name() {
     #[derive(FromSql, ToSql, Debug, PartialEq)]
@@ -227,6 +351,95 @@ fn missing_field() {
     assert!(err.source().unwrap().is::<WrongType>());
 }
 
+#[test]
+fn default_field() {
+    #[derive(FromSql, ToSql, Debug, PartialEq)]
+    #[postgres(name = "inventory_item")]
+    struct InventoryItem {
+        name: String,
+        supplier_id: i32,
+        #[postgres(default)]
+        price: Option<f64>,
+    }
+
+    let mut conn = Client::connect("user=postgres host=localhost port=5433", NoTls).unwrap();
+    conn.batch_execute(
+        "CREATE TYPE pg_temp.inventory_item AS (
+            name TEXT,
+            supplier_id INT
+        );",
+    )
+    .unwrap();
+
+    let item = InventoryItem {
+        name: "foobar".to_owned(),
+        supplier_id: 100,
+        price: None,
+    };
+    test_type(&mut conn, "inventory_item", &[(item, "ROW('foobar', 100)")]);
+
+    conn.batch_execute("ALTER TYPE pg_temp.inventory_item ADD ATTRIBUTE price DOUBLE PRECISION")
+        .unwrap();
+    conn.clear_type_cache();
+
+    let item = InventoryItem {
+        name: "foobar".to_owned(),
+        supplier_id: 100,
+        price: Some(15.50),
+    };
+    test_type(
+        &mut conn,
+        "inventory_item",
+        &[(item, "ROW('foobar', 100, 15.50)")],
+    );
+
+    conn.batch_execute("ALTER TYPE pg_temp.inventory_item DROP ATTRIBUTE supplier_id")
+        .unwrap();
+    conn.clear_type_cache();
+
+    let err = conn
+        .query_one("SELECT ROW('foobar', 15.50)::inventory_item", &[])
+        .unwrap()
+        .try_get::<_, InventoryItem>(0)
+        .unwrap_err();
+    assert!(err.source().unwrap().is::<WrongType>());
+}
+
+#[test]
+fn skip_field() {
+    #[derive(FromSql, ToSql, Debug, PartialEq)]
+    #[postgres(name = "inventory_item")]
+    struct InventoryItem {
+        name: String,
+        supplier_id: i32,
+        #[postgres(skip)]
+        label: Option<String>,
+    }
+
+    let mut conn = Client::connect("user=postgres host=localhost port=5433", NoTls).unwrap();
+    conn.batch_execute(
+        "CREATE TYPE pg_temp.inventory_item AS (
+            name TEXT,
+            supplier_id INT
+        );",
+    )
+    .unwrap();
+
+    let item = InventoryItem {
+        name: "foobar".to_owned(),
+        supplier_id: 100,
+        label: Some("foobar #100".to_owned()),
+    };
+    test_type_asymmetric(
+        &mut conn,
+        "inventory_item",
+        &[(item, "ROW('foobar', 100)")],
+        |t: &InventoryItem, f: &InventoryItem| {
+            t.name == f.name && t.supplier_id == f.supplier_id && f.label.is_none()
+        },
+    );
+}
+
 #[test]
 fn wrong_type() {
     #[derive(FromSql, ToSql, Debug, PartialEq)]
@@ -294,7 +507,7 @@ fn generics() {
         price: Option<U>,
     }
 
-    // doesn't make sense to implement derived FromSql on a type with borrows
+    // `&'a T` fields can't be borrowed from a row, so only ToSql can be derived
     #[derive(ToSql, Debug, PartialEq)]
     #[postgres(name = "InventoryItem")]
     struct InventoryItemRef<'a, T: 'a + Clone, U>
@@ -347,6 +560,33 @@ fn generics() {
     );
 }
 
+#[test]
+fn bound_override() {
+    use std::marker::PhantomData;
+
+    #[derive(Debug, PartialEq)]
+    struct Supplier;
+
+    // the default bounds would require `M: FromSql + ToSql`
+    #[derive(FromSql, ToSql, Debug, PartialEq)]
+    #[postgres(name = "tagged_id", bound = "M: std::fmt::Debug")]
+    struct TaggedId<M> {
+        id: i32,
+        #[postgres(skip)]
+        marker: PhantomData<M>,
+    }
+
+    let mut conn = Client::connect("user=postgres host=localhost port=5433", NoTls).unwrap();
+    conn.batch_execute("CREATE TYPE pg_temp.tagged_id AS (id INT);")
+        .unwrap();
+
+    let id = TaggedId::<Supplier> {
+        id: 100,
+        marker: PhantomData,
+    };
+    test_type(&mut conn, "tagged_id", &[(id, "ROW(100)")]);
+}
+
 #[test]
 fn duplicate_composite_field_name_does_not_panic() {
     use postgres_types::{Field, Kind, Type};
@@ -82,6 +82,71 @@ fn rename_all_overrides() {
     );
 }
 
+#[test]
+fn ignore_case_and_underscores() {
+    #[derive(Debug, ToSql, FromSql, PartialEq)]
+    #[postgres(name = "mood", ignore_case, ignore_underscores)]
+    enum Mood {
+        Sad,
+        Ok,
+        VeryHappy,
+    }
+
+    let mut conn = Client::connect("user=postgres host=localhost port=5433", NoTls).unwrap();
+    conn.execute(
+        "CREATE TYPE pg_temp.mood AS ENUM ('SAD', 'ok', 'Very_Happy')",
+        &[],
+    )
+    .unwrap();
+
+    test_type(
+        &mut conn,
+        "mood",
+        &[
+            (Mood::Sad, "'SAD'"),
+            (Mood::Ok, "'ok'"),
+            (Mood::VeryHappy, "'Very_Happy'"),
+        ],
+    );
+}
+
+#[test]
+fn integer() {
+    #[derive(Debug, ToSql, FromSql, PartialEq)]
+    #[postgres(integer)]
+    enum State {
+        Pending = 0,
+        Active = 1,
+        Closed = -1,
+    }
+
+    let mut conn = Client::connect("user=postgres host=localhost port=5433", NoTls).unwrap();
+
+    for sql_type in ["INT2", "INT4", "INT8"] {
+        test_type(
+            &mut conn,
+            sql_type,
+            &[
+                (State::Pending, "0"),
+                (State::Active, "1"),
+                (State::Closed, "-1"),
+            ],
+        );
+    }
+
+    let err = conn
+        .query_one("SELECT 2::INT4", &[])
+        .unwrap()
+        .try_get::<_, State>(0)
+        .unwrap_err();
+    assert_eq!(err.source().unwrap().to_string(), "invalid variant `2`");
+
+    let err = conn
+        .execute("SELECT $1::TEXT", &[&State::Active])
+        .unwrap_err();
+    assert!(err.source().unwrap().is::<WrongType>());
+}
+
 #[test]
 fn wrong_name() {
     #[derive(Debug, ToSql, FromSql, PartialEq)]
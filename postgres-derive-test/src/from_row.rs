@@ -0,0 +1,195 @@
+use postgres::{Client, FromRow, NoTls};
+
+#[test]
+fn basic() {
+    #[derive(FromRow, Debug, PartialEq)]
+    #[postgres(crate = "postgres")]
+    #[postgres(rename_all = "camelCase")]
+    struct Post {
+        id: i32,
+        #[postgres(name = "heading")]
+        title: String,
+        view_count: Option<i64>,
+        #[postgres(skip)]
+        cached: Vec<u8>,
+    }
+
+    let mut conn = Client::connect("user=postgres host=localhost port=5433", NoTls).unwrap();
+    let row = conn
+        .query_one(
+            "SELECT 1::INT AS id, 'hello'::TEXT AS heading, NULL::BIGINT AS \"viewCount\"",
+            &[],
+        )
+        .unwrap();
+
+    assert_eq!(
+        Post::from_row(&row).unwrap(),
+        Post {
+            id: 1,
+            title: "hello".to_owned(),
+            view_count: None,
+            cached: vec![],
+        }
+    );
+
+    let row = conn.query_one("SELECT 1::INT AS id", &[]).unwrap();
+    assert!(Post::from_row(&row).is_err());
+}
+
+#[test]
+fn prefix() {
+    #[derive(FromRow, Debug, PartialEq)]
+    #[postgres(crate = "postgres")]
+    struct Author {
+        id: i32,
+        name: String,
+    }
+
+    #[derive(FromRow, Debug, PartialEq)]
+    #[postgres(crate = "postgres")]
+    struct Post {
+        id: i32,
+        title: String,
+        #[postgres(prefix = "author_")]
+        author: Author,
+        #[postgres(prefix = "editor_")]
+        editor: Author,
+    }
+
+    let mut conn = Client::connect("user=postgres host=localhost port=5433", NoTls).unwrap();
+    let row = conn
+        .query_one(
+            "SELECT 1::INT AS id, 'hello'::TEXT AS title,
+                2::INT AS author_id, 'alice'::TEXT AS author_name,
+                3::INT AS editor_id, 'bob'::TEXT AS editor_name",
+            &[],
+        )
+        .unwrap();
+
+    assert_eq!(
+        Post::from_row(&row).unwrap(),
+        Post {
+            id: 1,
+            title: "hello".to_owned(),
+            author: Author {
+                id: 2,
+                name: "alice".to_owned(),
+            },
+            editor: Author {
+                id: 3,
+                name: "bob".to_owned(),
+            },
+        }
+    );
+}
+
+#[test]
+fn nested_prefix() {
+    #[derive(FromRow, Debug, PartialEq)]
+    #[postgres(crate = "postgres")]
+    struct Country {
+        code: String,
+    }
+
+    #[derive(FromRow, Debug, PartialEq)]
+    #[postgres(crate = "postgres")]
+    struct Author {
+        name: String,
+        #[postgres(prefix = "country_")]
+        country: Country,
+    }
+
+    #[derive(FromRow, Debug, PartialEq)]
+    #[postgres(crate = "postgres")]
+    struct Post {
+        title: String,
+        #[postgres(prefix = "author_")]
+        author: Author,
+    }
+
+    let mut conn = Client::connect("user=postgres host=localhost port=5433", NoTls).unwrap();
+    let row = conn
+        .query_one(
+            "SELECT 'hello'::TEXT AS title, 'alice'::TEXT AS author_name,
+                'NZ'::TEXT AS author_country_code",
+            &[],
+        )
+        .unwrap();
+
+    assert_eq!(
+        Post::from_row(&row).unwrap(),
+        Post {
+            title: "hello".to_owned(),
+            author: Author {
+                name: "alice".to_owned(),
+                country: Country {
+                    code: "NZ".to_owned(),
+                },
+            },
+        }
+    );
+}
+
+#[test]
+fn flatten() {
+    #[derive(FromRow, Debug, PartialEq)]
+    #[postgres(crate = "postgres")]
+    struct Timestamps {
+        created: i64,
+        updated: i64,
+    }
+
+    #[derive(FromRow, Debug, PartialEq)]
+    #[postgres(crate = "postgres")]
+    struct Post {
+        id: i32,
+        #[postgres(flatten)]
+        timestamps: Timestamps,
+    }
+
+    let mut conn = Client::connect("user=postgres host=localhost port=5433", NoTls).unwrap();
+    let row = conn
+        .query_one(
+            "SELECT 1::INT AS id, 10::BIGINT AS created, 20::BIGINT AS updated",
+            &[],
+        )
+        .unwrap();
+
+    assert_eq!(
+        Post::from_row(&row).unwrap(),
+        Post {
+            id: 1,
+            timestamps: Timestamps {
+                created: 10,
+                updated: 20,
+            },
+        }
+    );
+}
+
+#[test]
+fn default() {
+    #[derive(FromRow, Debug, PartialEq)]
+    #[postgres(crate = "postgres")]
+    struct Post {
+        id: i32,
+        #[postgres(default)]
+        view_count: Option<i64>,
+        #[postgres(default)]
+        tags: Vec<String>,
+    }
+
+    let mut conn = Client::connect("user=postgres host=localhost port=5433", NoTls).unwrap();
+    let row = conn
+        .query_one("SELECT 1::INT AS id, 5::BIGINT AS view_count", &[])
+        .unwrap();
+
+    assert_eq!(
+        Post::from_row(&row).unwrap(),
+        Post {
+            id: 1,
+            view_count: Some(5),
+            tags: vec![],
+        }
+    );
+}
@@ -5,6 +5,7 @@ use syn::Ident;
 
 use crate::composites::Field;
 use crate::enums::Variant;
+use crate::matching::NameMatching;
 
 pub fn transparent_body(field: &syn::Field) -> TokenStream {
     let ty = &field.ty;
@@ -31,9 +32,15 @@ pub fn domain_body(name: &str, field: &syn::Field) -> TokenStream {
     }
 }
 
-pub fn enum_body(name: &str, variants: &[Variant], allow_mismatch: bool) -> TokenStream {
+pub fn enum_body(
+    name: &str,
+    variants: &[Variant],
+    allow_mismatch: bool,
+    matching: NameMatching,
+) -> TokenStream {
     let num_variants = variants.len();
     let variant_names = variants.iter().map(|v| &v.name);
+    let variant = matching.normalize_expr(quote!(&**v));
 
     if allow_mismatch {
         quote! {
@@ -52,7 +59,7 @@ pub fn enum_body(name: &str, variants: &[Variant], allow_mismatch: bool) -> Toke
                     }
 
                     variants.iter().all(|v| {
-                        match &**v {
+                        match #variant {
                             #(
                                 #variant_names => true,
                             )*
@@ -66,12 +73,39 @@ pub fn enum_body(name: &str, variants: &[Variant], allow_mismatch: bool) -> Toke
     }
 }
 
-pub fn composite_body(name: &str, trait_: &str, fields: &[Field]) -> TokenStream {
+pub fn integer_enum_body() -> TokenStream {
+    quote! {
+        *type_ == ::postgres_types::Type::INT2
+            || *type_ == ::postgres_types::Type::INT4
+            || *type_ == ::postgres_types::Type::INT8
+    }
+}
+
+pub fn composite_body(
+    name: &str,
+    trait_: &str,
+    fields: &[Field],
+    matching: NameMatching,
+) -> TokenStream {
     let num_fields = fields.len();
+    let num_required = fields.iter().filter(|f| !f.default).count();
     let trait_ = Ident::new(trait_, Span::call_site());
     let traits = iter::repeat(&trait_);
     let field_names = fields.iter().map(|f| &f.name);
     let field_types = fields.iter().map(|f| &f.type_);
+    let field_name = matching.normalize_expr(quote!(f.name()));
+
+    // with defaulted fields the composite may have fewer fields than the struct, so check the required ones are there
+    let required_body = if num_required == num_fields {
+        quote!()
+    } else {
+        let required_names = fields.iter().filter(|f| !f.default).map(|f| &f.name);
+        quote! {
+            if ![#(#required_names),*].iter().all(|n| fields.iter().any(|f| #field_name == *n)) {
+                return false;
+            }
+        }
+    };
 
     quote! {
         if type_.name() != #name {
@@ -80,12 +114,14 @@ pub fn composite_body(name: &str, trait_: &str, fields: &[Field]) -> TokenStream
 
         match *type_.kind() {
             ::postgres_types::Kind::Composite(ref fields) => {
-                if fields.len() != #num_fields {
+                if fields.len() < #num_required || fields.len() > #num_fields {
                     return false;
                 }
 
+                #required_body
+
                 fields.iter().all(|f| {
-                    match f.name() {
+                    match #field_name {
                         #(
                             #field_names => {
                                 <#field_types as ::postgres_types::#traits>::accepts(f.type_())
@@ -99,3 +135,45 @@ pub fn composite_body(name: &str, trait_: &str, fields: &[Field]) -> TokenStream
         }
     }
 }
+
+pub fn positional_composite_body(
+    name: &str,
+    trait_: &str,
+    fields: &[Field],
+    allow_record: bool,
+) -> TokenStream {
+    let num_fields = fields.len();
+    let trait_ = Ident::new(trait_, Span::call_site());
+    let traits = iter::repeat(&trait_);
+    let indices = 0..num_fields;
+    let field_types = fields.iter().map(|f| &f.type_);
+
+    // the fields of an anonymous record are only known once a value is decoded
+    let record_body = if allow_record {
+        quote! {
+            if *type_ == ::postgres_types::Type::RECORD {
+                return true;
+            }
+        }
+    } else {
+        quote!()
+    };
+
+    quote! {
+        #record_body
+
+        if type_.name() != #name {
+            return false;
+        }
+
+        match *type_.kind() {
+            ::postgres_types::Kind::Composite(ref fields) => {
+                fields.len() == #num_fields
+                    #(
+                        && <#field_types as ::postgres_types::#traits>::accepts(fields[#indices].type_())
+                    )*
+            }
+            _ => false,
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use proc_macro2::Span;
 use syn::{
-    Error, GenericParam, Generics, Ident, Path, PathSegment, Type, TypeParamBound,
-    punctuated::Punctuated,
+    Error, GenericParam, Generics, Ident, Path, PathSegment, Token, Type, TypeParamBound,
+    WherePredicate, punctuated::Punctuated,
 };
 
 use crate::{case::RenameRule, overrides::Overrides};
@@ -10,6 +10,10 @@ pub struct Field {
     pub name: String,
     pub ident: Ident,
     pub type_: Type,
+    pub default: bool,
+    pub skip: bool,
+    pub flatten: bool,
+    pub prefix: Option<String>,
 }
 
 impl Field {
@@ -35,10 +39,25 @@ impl Field {
             name,
             ident,
             type_: raw.ty.clone(),
+            default: overrides.default,
+            skip: overrides.skip,
+            flatten: overrides.flatten,
+            prefix: overrides.prefix,
         })
     }
 }
 
+/// Rejects fields which can't be matched by position.
+pub(crate) fn check_positional(fields: &[Field]) -> Result<(), Error> {
+    match fields.iter().find(|f| f.default) {
+        Some(field) => Err(Error::new_spanned(
+            &field.ident,
+            "#[postgres(default)] is not allowed with #[postgres(positional)]",
+        )),
+        None => Ok(()),
+    }
+}
+
 pub(crate) fn append_generic_bound(mut generics: Generics, bound: &TypeParamBound) -> Generics {
     for param in &mut generics.params {
         if let GenericParam::Type(param) = param {
@@ -48,6 +67,17 @@ pub(crate) fn append_generic_bound(mut generics: Generics, bound: &TypeParamBoun
     generics
 }
 
+pub(crate) fn append_where_predicates(
+    mut generics: Generics,
+    predicates: &Punctuated<WherePredicate, Token![,]>,
+) -> Generics {
+    generics
+        .make_where_clause()
+        .predicates
+        .extend(predicates.iter().cloned());
+    generics
+}
+
 pub(crate) fn new_derive_path(last: PathSegment) -> Path {
     let mut path = Path {
         leading_colon: None,
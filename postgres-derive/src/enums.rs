@@ -1,4 +1,4 @@
-use syn::{Error, Fields, Ident};
+use syn::{DataEnum, Error, Fields, Ident};
 
 use crate::{case::RenameRule, overrides::Overrides};
 
@@ -31,3 +31,19 @@ impl Variant {
         })
     }
 }
+
+/// Parses the variants of an enum stored as an integer, which must all have explicit discriminants.
+pub fn parse_integer_variants(data: &DataEnum) -> Result<Vec<Variant>, Error> {
+    data.variants
+        .iter()
+        .map(|variant| {
+            if variant.discriminant.is_none() {
+                return Err(Error::new_spanned(
+                    variant,
+                    "#[postgres(integer)] requires explicit discriminants",
+                ));
+            }
+            Variant::parse(variant, None)
+        })
+        .collect()
+}
@@ -0,0 +1,98 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DataStruct, DeriveInput, Error, Fields};
+
+use crate::composites::{Field, append_where_predicates};
+use crate::overrides::Overrides;
+
+pub fn expand_derive_fromrow(input: DeriveInput) -> Result<TokenStream, Error> {
+    let overrides = Overrides::extract(&input.attrs, true)?;
+
+    let fields = match input.data {
+        Data::Struct(DataStruct {
+            fields: Fields::Named(ref fields),
+            ..
+        }) => fields
+            .named
+            .iter()
+            .map(|field| Field::parse(field, overrides.rename_all))
+            .collect::<Result<Vec<_>, _>>()?,
+        _ => {
+            return Err(Error::new_spanned(
+                input,
+                "#[derive(FromRow)] may only be applied to structs with named fields",
+            ));
+        }
+    };
+
+    if let Some(field) = fields
+        .iter()
+        .find(|f| f.default && (f.flatten || f.prefix.is_some()))
+    {
+        return Err(Error::new_spanned(
+            &field.ident,
+            "#[postgres(default)] is not allowed with #[postgres(flatten)] or #[postgres(prefix)]",
+        ));
+    }
+
+    let krate = match overrides.crate_path {
+        Some(path) => quote!(#path),
+        None => quote!(tokio_postgres),
+    };
+
+    let field_values = fields.iter().map(|field| {
+        let ident = &field.ident;
+        let ty = &field.type_;
+        let name = &field.name;
+        if field.skip {
+            quote!(#ident: std::default::Default::default())
+        } else if field.flatten || field.prefix.is_some() {
+            let prefix = field.prefix.as_deref().unwrap_or("");
+            quote! {
+                #ident: <#ty as #krate::FromRow>::from_row_prefixed(row, &column(#prefix))?
+            }
+        } else if field.default {
+            quote! {
+                #ident: {
+                    let name = column(#name);
+                    if row.columns().iter().any(|c| c.name() == name) {
+                        row.try_get::<_, #ty>(&*name)?
+                    } else {
+                        std::default::Default::default()
+                    }
+                }
+            }
+        } else {
+            quote!(#ident: row.try_get::<_, #ty>(&*column(#name))?)
+        }
+    });
+
+    let generics = match &overrides.bound {
+        Some(bound) => append_where_predicates(input.generics.to_owned(), bound),
+        None => input.generics.to_owned(),
+    };
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let out = quote! {
+        impl #impl_generics #krate::FromRow for #ident #ty_generics #where_clause {
+            fn from_row_prefixed(row: &#krate::Row, prefix: &str)
+                                 -> std::result::Result<#ident #ty_generics, #krate::Error> {
+                let column = |name: &'static str| -> std::borrow::Cow<'static, str> {
+                    if prefix.is_empty() {
+                        std::borrow::Cow::Borrowed(name)
+                    } else {
+                        std::borrow::Cow::Owned(format!("{}{}", prefix, name))
+                    }
+                };
+
+                std::result::Result::Ok(#ident {
+                    #(
+                        #field_values,
+                    )*
+                })
+            }
+        }
+    };
+
+    Ok(out)
+}
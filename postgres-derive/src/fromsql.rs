@@ -3,15 +3,16 @@ use quote::{format_ident, quote};
 use std::iter;
 use syn::{
     AngleBracketedGenericArguments, Data, DataStruct, DeriveInput, Error, Fields, GenericArgument,
-    GenericParam, Generics, Ident, Lifetime, PathArguments, PathSegment, punctuated::Punctuated,
-    token,
+    GenericParam, Generics, Ident, Lifetime, PathArguments, PathSegment, Token, WherePredicate,
+    punctuated::Punctuated, token,
 };
 use syn::{LifetimeParam, TraitBound, TraitBoundModifier, TypeParamBound};
 
 use crate::accepts;
-use crate::composites::Field;
-use crate::composites::{append_generic_bound, new_derive_path};
-use crate::enums::Variant;
+use crate::composites::{Field, check_positional};
+use crate::composites::{append_generic_bound, append_where_predicates, new_derive_path};
+use crate::enums::{self, Variant};
+use crate::matching::NameMatching;
 use crate::overrides::Overrides;
 
 pub fn expand_derive_fromsql(input: DeriveInput) -> Result<TokenStream, Error> {
@@ -24,6 +25,13 @@ pub fn expand_derive_fromsql(input: DeriveInput) -> Result<TokenStream, Error> {
         ));
     }
 
+    if overrides.integer && (overrides.transparent || overrides.allow_mismatch) {
+        return Err(Error::new_spanned(
+            &input,
+            "#[postgres(integer)] is not allowed with #[postgres(transparent)] or #[postgres(allow_mismatch)]",
+        ));
+    }
+
     let name = overrides
         .name
         .clone()
@@ -48,17 +56,39 @@ pub fn expand_derive_fromsql(input: DeriveInput) -> Result<TokenStream, Error> {
                 ));
             }
         }
+    } else if overrides.integer {
+        match input.data {
+            Data::Enum(ref data) => {
+                let variants = enums::parse_integer_variants(data)?;
+                (
+                    accepts::integer_enum_body(),
+                    integer_enum_body(&input.ident, &variants),
+                )
+            }
+            _ => {
+                return Err(Error::new_spanned(
+                    input,
+                    "#[postgres(integer)] may only be applied to enums",
+                ));
+            }
+        }
     } else if overrides.allow_mismatch {
         match input.data {
             Data::Enum(ref data) => {
-                let variants = data
+                let mut variants = data
                     .variants
                     .iter()
                     .map(|variant| Variant::parse(variant, overrides.rename_all))
                     .collect::<Result<Vec<_>, _>>()?;
+                overrides.name_matching.apply_to_variants(&mut variants)?;
                 (
-                    accepts::enum_body(&name, &variants, overrides.allow_mismatch),
-                    enum_body(&input.ident, &variants),
+                    accepts::enum_body(
+                        &name,
+                        &variants,
+                        overrides.allow_mismatch,
+                        overrides.name_matching,
+                    ),
+                    enum_body(&input.ident, &variants, overrides.name_matching),
                 )
             }
             _ => {
@@ -71,14 +101,20 @@ pub fn expand_derive_fromsql(input: DeriveInput) -> Result<TokenStream, Error> {
     } else {
         match input.data {
             Data::Enum(ref data) => {
-                let variants = data
+                let mut variants = data
                     .variants
                     .iter()
                     .map(|variant| Variant::parse(variant, overrides.rename_all))
                     .collect::<Result<Vec<_>, _>>()?;
+                overrides.name_matching.apply_to_variants(&mut variants)?;
                 (
-                    accepts::enum_body(&name, &variants, overrides.allow_mismatch),
-                    enum_body(&input.ident, &variants),
+                    accepts::enum_body(
+                        &name,
+                        &variants,
+                        overrides.allow_mismatch,
+                        overrides.name_matching,
+                    ),
+                    enum_body(&input.ident, &variants, overrides.name_matching),
                 )
             }
             Data::Struct(DataStruct {
@@ -100,10 +136,21 @@ pub fn expand_derive_fromsql(input: DeriveInput) -> Result<TokenStream, Error> {
                     .iter()
                     .map(|field| Field::parse(field, overrides.rename_all))
                     .collect::<Result<Vec<_>, _>>()?;
-                (
-                    accepts::composite_body(&name, "FromSql", &fields),
-                    composite_body(&input.ident, &fields),
-                )
+                let (skipped, mut fields): (Vec<_>, Vec<_>) =
+                    fields.into_iter().partition(|f| f.skip);
+                if overrides.positional {
+                    check_positional(&fields)?;
+                    (
+                        accepts::positional_composite_body(&name, "FromSql", &fields, true),
+                        positional_composite_body(&input.ident, &fields, &skipped),
+                    )
+                } else {
+                    overrides.name_matching.apply_to_fields(&mut fields)?;
+                    (
+                        accepts::composite_body(&name, "FromSql", &fields, overrides.name_matching),
+                        composite_body(&input.ident, &fields, &skipped, overrides.name_matching),
+                    )
+                }
             }
             _ => {
                 return Err(Error::new_spanned(
@@ -115,9 +162,9 @@ pub fn expand_derive_fromsql(input: DeriveInput) -> Result<TokenStream, Error> {
     };
 
     let ident = &input.ident;
-    let (generics, lifetime) = build_generics(&input.generics);
-    let (impl_generics, _, _) = generics.split_for_impl();
-    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+    let (generics, lifetime) = build_generics(&input.generics, overrides.bound.as_ref())?;
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
     let out = quote! {
         impl #impl_generics postgres_types::FromSql<#lifetime> for #ident #ty_generics #where_clause {
             fn from_sql(_type: &postgres_types::Type, buf: &#lifetime [u8])
@@ -144,13 +191,15 @@ fn transparent_body(ident: &Ident, field: &syn::Field) -> TokenStream {
     }
 }
 
-fn enum_body(ident: &Ident, variants: &[Variant]) -> TokenStream {
+fn enum_body(ident: &Ident, variants: &[Variant], matching: NameMatching) -> TokenStream {
     let variant_names = variants.iter().map(|v| &v.name);
     let idents = iter::repeat(ident);
     let variant_idents = variants.iter().map(|v| &v.ident);
 
+    let label = matching.normalize_expr(quote!(std::str::from_utf8(buf)?));
+
     quote! {
-        match std::str::from_utf8(buf)? {
+        match #label {
             #(
                 #variant_names => std::result::Result::Ok(#idents::#variant_idents),
             )*
@@ -162,6 +211,27 @@ fn enum_body(ident: &Ident, variants: &[Variant]) -> TokenStream {
     }
 }
 
+fn integer_enum_body(ident: &Ident, variants: &[Variant]) -> TokenStream {
+    let idents = iter::repeat(ident);
+    let variant_idents = variants.iter().map(|v| &v.ident);
+
+    quote! {
+        let value = match *_type {
+            postgres_types::Type::INT2 => <i16 as postgres_types::FromSql>::from_sql(_type, buf)? as i64,
+            postgres_types::Type::INT4 => <i32 as postgres_types::FromSql>::from_sql(_type, buf)? as i64,
+            _ => <i64 as postgres_types::FromSql>::from_sql(_type, buf)?,
+        };
+
+        #(
+            if value == #idents::#variant_idents as i64 {
+                return std::result::Result::Ok(#idents::#variant_idents);
+            }
+        )*
+
+        std::result::Result::Err(std::convert::Into::into(format!("invalid variant `{}`", value)))
+    }
+}
+
 // Domains are sometimes but not always just represented by the bare type (!?)
 fn domain_accepts_body(name: &str, field: &syn::Field) -> TokenStream {
     let ty = &field.ty;
@@ -186,13 +256,29 @@ fn domain_body(ident: &Ident, field: &syn::Field) -> TokenStream {
     }
 }
 
-fn composite_body(ident: &Ident, fields: &[Field]) -> TokenStream {
+fn composite_body(
+    ident: &Ident,
+    fields: &[Field],
+    skipped: &[Field],
+    matching: NameMatching,
+) -> TokenStream {
     let temp_vars = &fields
         .iter()
         .map(|f| format_ident!("__{}", f.ident))
         .collect::<Vec<_>>();
     let field_names = &fields.iter().map(|f| &f.name).collect::<Vec<_>>();
     let field_idents = &fields.iter().map(|f| &f.ident).collect::<Vec<_>>();
+    let skipped_idents = skipped.iter().map(|f| &f.ident);
+    let field_name = matching.normalize_expr(quote!(field.name()));
+    let field_values = temp_vars.iter().zip(fields).map(|(temp_var, f)| {
+        if f.default {
+            quote!(#temp_var.unwrap_or_default())
+        } else {
+            // A field is left unset if the server's composite type omitted it
+            // (e.g. reported a duplicate field name); error rather than panic.
+            quote!(#temp_var.ok_or("composite type is missing a field")?)
+        }
+    });
 
     quote! {
         let fields = match *_type.kind() {
@@ -217,7 +303,7 @@ fn composite_body(ident: &Ident, fields: &[Field]) -> TokenStream {
                 return std::result::Result::Err(std::convert::Into::into("unexpected OID"));
             }
 
-            match field.name() {
+            match #field_name {
                 #(
                     #field_names => {
                         #temp_vars = std::option::Option::Some(
@@ -230,25 +316,90 @@ fn composite_body(ident: &Ident, fields: &[Field]) -> TokenStream {
 
         std::result::Result::Ok(#ident {
             #(
-                // A field is left unset if the server's composite type omitted it
-                // (e.g. reported a duplicate field name); error rather than panic.
-                #field_idents: #temp_vars.ok_or("composite type is missing a field")?,
+                #field_idents: #field_values,
+            )*
+            #(
+                #skipped_idents: std::default::Default::default(),
+            )*
+        })
+    }
+}
+
+fn positional_composite_body(ident: &Ident, fields: &[Field], skipped: &[Field]) -> TokenStream {
+    let num_fields = fields.len();
+    let indices = 0..num_fields;
+    let temp_vars = &fields
+        .iter()
+        .map(|f| format_ident!("__{}", f.ident))
+        .collect::<Vec<_>>();
+    let field_idents = fields.iter().map(|f| &f.ident);
+    let field_types = fields.iter().map(|f| &f.type_);
+    let skipped_idents = skipped.iter().map(|f| &f.ident);
+
+    quote! {
+        let mut buf = buf;
+        let num_fields = postgres_types::private::read_be_i32(&mut buf)?;
+        if num_fields as usize != #num_fields {
+            return std::result::Result::Err(
+                std::convert::Into::into(format!("invalid field count: {} vs {}", num_fields, #num_fields)));
+        }
+
+        #(
+            let #temp_vars = {
+                let type_ = postgres_types::private::read_field_type(_type, #indices, &mut buf)?;
+                if !<#field_types as postgres_types::FromSql>::accepts(&type_) {
+                    return std::result::Result::Err(std::boxed::Box::new(
+                        postgres_types::WrongType::new::<#field_types>(type_)));
+                }
+                postgres_types::private::read_value(&type_, &mut buf)?
+            };
+        )*
+
+        std::result::Result::Ok(#ident {
+            #(
+                #field_idents: #temp_vars,
+            )*
+            #(
+                #skipped_idents: std::default::Default::default(),
             )*
         })
     }
 }
 
-fn build_generics(source: &Generics) -> (Generics, Lifetime) {
-    // don't worry about lifetime name collisions, it doesn't make sense to derive FromSql on a struct with a lifetime
+fn build_generics(
+    source: &Generics,
+    bound: Option<&Punctuated<WherePredicate, Token![,]>>,
+) -> Result<(Generics, Lifetime), Error> {
+    // an explicit bound replaces the generated `T: FromSql<'a>` bounds
+    let append_bounds = |generics: Generics, lifetime: &Lifetime| match bound {
+        Some(bound) => append_where_predicates(generics, bound),
+        None => append_generic_bound(generics, &new_fromsql_bound(lifetime)),
+    };
+
+    // a struct with a lifetime borrows its fields from the buffer being decoded
+    let mut lifetimes = source.lifetimes();
+    if let Some(param) = lifetimes.next() {
+        if let Some(extra) = lifetimes.next() {
+            return Err(Error::new_spanned(
+                extra,
+                "#[derive(FromSql)] may only be applied to types with at most one lifetime parameter",
+            ));
+        }
+
+        let lifetime = param.lifetime.clone();
+        let out = append_bounds(source.to_owned(), &lifetime);
+        return Ok((out, lifetime));
+    }
+
     let lifetime = Lifetime::new("'a", Span::call_site());
 
-    let mut out = append_generic_bound(source.to_owned(), &new_fromsql_bound(&lifetime));
+    let mut out = append_bounds(source.to_owned(), &lifetime);
     out.params.insert(
         0,
         GenericParam::Lifetime(LifetimeParam::new(lifetime.to_owned())),
     );
 
-    (out, lifetime)
+    Ok((out, lifetime))
 }
 
 fn new_fromsql_bound(lifetime: &Lifetime) -> TypeParamBound {
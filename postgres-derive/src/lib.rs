@@ -10,7 +10,9 @@ mod accepts;
 mod case;
 mod composites;
 mod enums;
+mod fromrow;
 mod fromsql;
+mod matching;
 mod overrides;
 mod tosql;
 
@@ -31,3 +33,12 @@ pub fn derive_fromsql(input: TokenStream) -> TokenStream {
         .unwrap_or_else(|e| e.to_compile_error())
         .into()
 }
+
+#[proc_macro_derive(FromRow, attributes(postgres))]
+pub fn derive_fromrow(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input);
+
+    fromrow::expand_derive_fromrow(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
@@ -0,0 +1,87 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Error, Ident};
+
+use crate::composites::Field;
+use crate::enums::Variant;
+
+/// How the names of enum variants and composite fields are compared to those of the Postgres type.
+#[derive(Copy, Clone, Default)]
+pub struct NameMatching {
+    pub ignore_case: bool,
+    pub ignore_underscores: bool,
+}
+
+impl NameMatching {
+    pub fn is_exact(self) -> bool {
+        !self.ignore_case && !self.ignore_underscores
+    }
+
+    /// Normalizes a name at expansion time. This must match `postgres_types::private::normalize_name`.
+    pub fn normalize(self, name: &str) -> String {
+        let name = if self.ignore_underscores {
+            name.replace('_', "")
+        } else {
+            name.to_string()
+        };
+        if self.ignore_case {
+            name.to_lowercase()
+        } else {
+            name
+        }
+    }
+
+    /// Returns an expression normalizing the `&str` produced by `expr` at runtime.
+    pub fn normalize_expr(self, expr: TokenStream) -> TokenStream {
+        if self.is_exact() {
+            return expr;
+        }
+
+        let ignore_case = self.ignore_case;
+        let ignore_underscores = self.ignore_underscores;
+        quote! {
+            &*postgres_types::private::normalize_name(#expr, #ignore_case, #ignore_underscores)
+        }
+    }
+
+    /// Normalizes the names of enum variants, rejecting names which become ambiguous.
+    pub fn apply_to_variants(self, variants: &mut [Variant]) -> Result<(), Error> {
+        self.apply(
+            variants
+                .iter_mut()
+                .map(|Variant { ident, name }| (&*ident, name)),
+        )
+    }
+
+    /// Normalizes the names of composite fields, rejecting names which become ambiguous.
+    pub fn apply_to_fields(self, fields: &mut [Field]) -> Result<(), Error> {
+        self.apply(
+            fields
+                .iter_mut()
+                .map(|Field { ident, name, .. }| (&*ident, name)),
+        )
+    }
+
+    fn apply<'a, I>(self, names: I) -> Result<(), Error>
+    where
+        I: Iterator<Item = (&'a Ident, &'a mut String)>,
+    {
+        if self.is_exact() {
+            return Ok(());
+        }
+
+        let mut seen: Vec<(&Ident, String)> = vec![];
+        for (ident, name) in names {
+            *name = self.normalize(name);
+            if let Some((other, _)) = seen.iter().find(|(_, n)| n == name) {
+                return Err(Error::new_spanned(
+                    ident,
+                    format!("`{ident}` matches the same Postgres name as `{other}`"),
+                ));
+            }
+            seen.push((ident, name.clone()));
+        }
+
+        Ok(())
+    }
+}
@@ -1,13 +1,24 @@
+use syn::parse::Parser;
 use syn::punctuated::Punctuated;
-use syn::{Attribute, Error, Expr, ExprLit, Lit, Meta, Token};
+use syn::{Attribute, Error, Expr, ExprLit, Lit, Meta, Path, Token, WherePredicate};
 
 use crate::case::{RENAME_RULES, RenameRule};
+use crate::matching::NameMatching;
 
 pub struct Overrides {
     pub name: Option<String>,
     pub rename_all: Option<RenameRule>,
     pub transparent: bool,
     pub allow_mismatch: bool,
+    pub default: bool,
+    pub skip: bool,
+    pub name_matching: NameMatching,
+    pub integer: bool,
+    pub bound: Option<Punctuated<WherePredicate, Token![,]>>,
+    pub positional: bool,
+    pub prefix: Option<String>,
+    pub flatten: bool,
+    pub crate_path: Option<Path>,
 }
 
 impl Overrides {
@@ -17,6 +28,15 @@ impl Overrides {
             rename_all: None,
             transparent: false,
             allow_mismatch: false,
+            default: false,
+            skip: false,
+            name_matching: NameMatching::default(),
+            integer: false,
+            bound: None,
+            positional: false,
+            prefix: None,
+            flatten: false,
+            crate_path: None,
         };
 
         for attr in attrs {
@@ -36,13 +56,39 @@ impl Overrides {
                     Meta::NameValue(meta) => {
                         let name_override = meta.path.is_ident("name");
                         let rename_all_override = meta.path.is_ident("rename_all");
+                        let bound_override = meta.path.is_ident("bound");
+                        let prefix_override = meta.path.is_ident("prefix");
+                        let crate_override = meta.path.is_ident("crate");
                         if !container_attr && rename_all_override {
                             return Err(Error::new_spanned(
                                 &meta.path,
                                 "rename_all is a container attribute",
                             ));
                         }
-                        if !name_override && !rename_all_override {
+                        if !container_attr && bound_override {
+                            return Err(Error::new_spanned(
+                                &meta.path,
+                                "bound is a container attribute",
+                            ));
+                        }
+                        if !container_attr && crate_override {
+                            return Err(Error::new_spanned(
+                                &meta.path,
+                                "crate is a container attribute",
+                            ));
+                        }
+                        if container_attr && prefix_override {
+                            return Err(Error::new_spanned(
+                                &meta.path,
+                                "prefix is a field attribute",
+                            ));
+                        }
+                        if !name_override
+                            && !rename_all_override
+                            && !bound_override
+                            && !prefix_override
+                            && !crate_override
+                        {
                             return Err(Error::new_spanned(&meta.path, "unknown override"));
                         }
 
@@ -57,6 +103,17 @@ impl Overrides {
 
                         if name_override {
                             overrides.name = Some(value);
+                        } else if prefix_override {
+                            overrides.prefix = Some(value);
+                        } else if crate_override {
+                            let path = syn::parse_str::<Path>(&value)
+                                .map_err(|e| Error::new_spanned(&meta.value, e))?;
+                            overrides.crate_path = Some(path);
+                        } else if bound_override {
+                            let bound = Punctuated::<WherePredicate, Token![,]>::parse_terminated
+                                .parse_str(&value)
+                                .map_err(|e| Error::new_spanned(&meta.value, e))?;
+                            overrides.bound = Some(bound);
                         } else if rename_all_override {
                             let rename_rule = RenameRule::from_str(&value).ok_or_else(|| {
                                 Error::new_spanned(
@@ -92,6 +149,60 @@ impl Overrides {
                                 ));
                             }
                             overrides.allow_mismatch = true;
+                        } else if path.is_ident("default") {
+                            if container_attr {
+                                return Err(Error::new_spanned(
+                                    path,
+                                    "default is a field attribute",
+                                ));
+                            }
+                            overrides.default = true;
+                        } else if path.is_ident("skip") {
+                            if container_attr {
+                                return Err(Error::new_spanned(path, "skip is a field attribute"));
+                            }
+                            overrides.skip = true;
+                        } else if path.is_ident("flatten") {
+                            if container_attr {
+                                return Err(Error::new_spanned(
+                                    path,
+                                    "flatten is a field attribute",
+                                ));
+                            }
+                            overrides.flatten = true;
+                        } else if path.is_ident("integer") {
+                            if !container_attr {
+                                return Err(Error::new_spanned(
+                                    path,
+                                    "integer is a container attribute",
+                                ));
+                            }
+                            overrides.integer = true;
+                        } else if path.is_ident("positional") {
+                            if !container_attr {
+                                return Err(Error::new_spanned(
+                                    path,
+                                    "positional is a container attribute",
+                                ));
+                            }
+                            overrides.positional = true;
+                        } else if path.is_ident("ignore_case")
+                            || path.is_ident("ignore_underscores")
+                        {
+                            if !container_attr {
+                                return Err(Error::new_spanned(
+                                    &path,
+                                    format!(
+                                        "{} is a container attribute",
+                                        path.get_ident().unwrap()
+                                    ),
+                                ));
+                            }
+                            if path.is_ident("ignore_case") {
+                                overrides.name_matching.ignore_case = true;
+                            } else {
+                                overrides.name_matching.ignore_underscores = true;
+                            }
                         } else {
                             return Err(Error::new_spanned(path, "unknown override"));
                         }
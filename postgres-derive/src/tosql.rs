@@ -7,9 +7,10 @@ use syn::{
 };
 
 use crate::accepts;
-use crate::composites::Field;
-use crate::composites::{append_generic_bound, new_derive_path};
-use crate::enums::Variant;
+use crate::composites::{Field, check_positional};
+use crate::composites::{append_generic_bound, append_where_predicates, new_derive_path};
+use crate::enums::{self, Variant};
+use crate::matching::NameMatching;
 use crate::overrides::Overrides;
 
 pub fn expand_derive_tosql(input: DeriveInput) -> Result<TokenStream, Error> {
@@ -22,6 +23,13 @@ pub fn expand_derive_tosql(input: DeriveInput) -> Result<TokenStream, Error> {
         ));
     }
 
+    if overrides.integer && (overrides.transparent || overrides.allow_mismatch) {
+        return Err(Error::new_spanned(
+            &input,
+            "#[postgres(integer)] is not allowed with #[postgres(transparent)] or #[postgres(allow_mismatch)]",
+        ));
+    }
+
     let name = overrides
         .name
         .clone()
@@ -44,17 +52,39 @@ pub fn expand_derive_tosql(input: DeriveInput) -> Result<TokenStream, Error> {
                 ));
             }
         }
+    } else if overrides.integer {
+        match input.data {
+            Data::Enum(ref data) => {
+                let variants = enums::parse_integer_variants(data)?;
+                (
+                    accepts::integer_enum_body(),
+                    integer_enum_body(&input.ident, &variants),
+                )
+            }
+            _ => {
+                return Err(Error::new_spanned(
+                    input,
+                    "#[postgres(integer)] may only be applied to enums",
+                ));
+            }
+        }
     } else if overrides.allow_mismatch {
         match input.data {
             Data::Enum(ref data) => {
-                let variants = data
+                let mut variants = data
                     .variants
                     .iter()
                     .map(|variant| Variant::parse(variant, overrides.rename_all))
                     .collect::<Result<Vec<_>, _>>()?;
+                overrides.name_matching.apply_to_variants(&mut variants)?;
                 (
-                    accepts::enum_body(&name, &variants, overrides.allow_mismatch),
-                    enum_body(&input.ident, &variants),
+                    accepts::enum_body(
+                        &name,
+                        &variants,
+                        overrides.allow_mismatch,
+                        overrides.name_matching,
+                    ),
+                    enum_body(&input.ident, &variants, overrides.name_matching),
                 )
             }
             _ => {
@@ -67,14 +97,20 @@ pub fn expand_derive_tosql(input: DeriveInput) -> Result<TokenStream, Error> {
     } else {
         match input.data {
             Data::Enum(ref data) => {
-                let variants = data
+                let mut variants = data
                     .variants
                     .iter()
                     .map(|variant| Variant::parse(variant, overrides.rename_all))
                     .collect::<Result<Vec<_>, _>>()?;
+                overrides.name_matching.apply_to_variants(&mut variants)?;
                 (
-                    accepts::enum_body(&name, &variants, overrides.allow_mismatch),
-                    enum_body(&input.ident, &variants),
+                    accepts::enum_body(
+                        &name,
+                        &variants,
+                        overrides.allow_mismatch,
+                        overrides.name_matching,
+                    ),
+                    enum_body(&input.ident, &variants, overrides.name_matching),
                 )
             }
             Data::Struct(DataStruct {
@@ -89,15 +125,25 @@ pub fn expand_derive_tosql(input: DeriveInput) -> Result<TokenStream, Error> {
                 fields: Fields::Named(ref fields),
                 ..
             }) => {
-                let fields = fields
+                let mut fields = fields
                     .named
                     .iter()
                     .map(|field| Field::parse(field, overrides.rename_all))
                     .collect::<Result<Vec<_>, _>>()?;
-                (
-                    accepts::composite_body(&name, "ToSql", &fields),
-                    composite_body(&fields),
-                )
+                fields.retain(|f| !f.skip);
+                if overrides.positional {
+                    check_positional(&fields)?;
+                    (
+                        accepts::positional_composite_body(&name, "ToSql", &fields, false),
+                        composite_body(&fields, overrides.name_matching, true),
+                    )
+                } else {
+                    overrides.name_matching.apply_to_fields(&mut fields)?;
+                    (
+                        accepts::composite_body(&name, "ToSql", &fields, overrides.name_matching),
+                        composite_body(&fields, overrides.name_matching, false),
+                    )
+                }
             }
             _ => {
                 return Err(Error::new_spanned(
@@ -109,7 +155,10 @@ pub fn expand_derive_tosql(input: DeriveInput) -> Result<TokenStream, Error> {
     };
 
     let ident = &input.ident;
-    let generics = append_generic_bound(input.generics.to_owned(), &new_tosql_bound());
+    let generics = match &overrides.bound {
+        Some(bound) => append_where_predicates(input.generics.to_owned(), bound),
+        None => append_generic_bound(input.generics.to_owned(), &new_tosql_bound()),
+    };
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
     let out = quote! {
         impl #impl_generics postgres_types::ToSql for #ident #ty_generics #where_clause {
@@ -140,17 +189,35 @@ fn transparent_body() -> TokenStream {
     }
 }
 
-fn enum_body(ident: &Ident, variants: &[Variant]) -> TokenStream {
+fn enum_body(ident: &Ident, variants: &[Variant], matching: NameMatching) -> TokenStream {
     let idents = iter::repeat(ident);
     let variant_idents = variants.iter().map(|v| &v.ident);
     let variant_names = variants.iter().map(|v| &v.name);
 
+    // the names are normalized, so look up the label the type actually uses
+    let label = if matching.is_exact() {
+        quote!()
+    } else {
+        let variant = matching.normalize_expr(quote!(v));
+        quote! {
+            let s = match *_type.kind() {
+                postgres_types::Kind::Enum(ref variants) => variants
+                    .iter()
+                    .map(|v| &**v)
+                    .find(|v| #variant == s)
+                    .unwrap_or(s),
+                _ => s,
+            };
+        }
+    };
+
     quote! {
         let s = match *self {
             #(
                 #idents::#variant_idents => #variant_names,
             )*
         };
+        #label
 
         buf.extend_from_slice(s.as_bytes());
         std::result::Result::Ok(postgres_types::IsNull::No)
@@ -168,9 +235,47 @@ fn domain_body() -> TokenStream {
     }
 }
 
-fn composite_body(fields: &[Field]) -> TokenStream {
-    let field_names = fields.iter().map(|f| &f.name);
+fn integer_enum_body(ident: &Ident, variants: &[Variant]) -> TokenStream {
+    let idents = iter::repeat(ident);
+    let variant_idents = variants.iter().map(|v| &v.ident);
+
+    quote! {
+        let value = match *self {
+            #(
+                #idents::#variant_idents => #idents::#variant_idents as i64,
+            )*
+        };
+
+        match *_type {
+            postgres_types::Type::INT2 => {
+                let value: i16 = std::convert::TryFrom::try_from(value)?;
+                postgres_types::ToSql::to_sql(&value, _type, buf)
+            }
+            postgres_types::Type::INT4 => {
+                let value: i32 = std::convert::TryFrom::try_from(value)?;
+                postgres_types::ToSql::to_sql(&value, _type, buf)
+            }
+            _ => postgres_types::ToSql::to_sql(&value, _type, buf),
+        }
+    }
+}
+
+fn composite_body(fields: &[Field], matching: NameMatching, positional: bool) -> TokenStream {
     let field_idents = fields.iter().map(|f| &f.ident);
+    let (fields_iter, field_key, field_patterns) = if positional {
+        (
+            quote!((i, field) in fields.iter().enumerate()),
+            quote!(i),
+            (0..fields.len()).map(|i| quote!(#i)).collect::<Vec<_>>(),
+        )
+    } else {
+        let field_names = fields.iter().map(|f| &f.name);
+        (
+            quote!(field in fields),
+            matching.normalize_expr(quote!(field.name())),
+            field_names.map(|name| quote!(#name)).collect(),
+        )
+    };
 
     quote! {
         let fields = match *_type.kind() {
@@ -180,14 +285,14 @@ fn composite_body(fields: &[Field]) -> TokenStream {
 
         buf.extend_from_slice(&(fields.len() as i32).to_be_bytes());
 
-        for field in fields {
+        for #fields_iter {
             buf.extend_from_slice(&field.type_().oid().to_be_bytes());
 
             let base = buf.len();
             buf.extend_from_slice(&[0; 4]);
-            let r = match field.name() {
+            let r = match #field_key {
                 #(
-                    #field_names => postgres_types::ToSql::to_sql(&self.#field_idents, field.type_(), buf),
+                    #field_patterns => postgres_types::ToSql::to_sql(&self.#field_idents, field.type_(), buf),
                 )*
                 _ => unreachable!(),
             };
@@ -1,5 +1,6 @@
 use futures_util::FutureExt;
 use native_tls::{self, Certificate};
+use std::error::Error;
 use tokio::net::TcpStream;
 use tokio_postgres::tls::TlsConnect;
 
@@ -87,6 +88,114 @@ async fn scram_user() {
     .await;
 }
 
+#[tokio::test]
+async fn verify_full() {
+    let connector = native_tls::TlsConnector::new().unwrap();
+    smoke_test(
+        "user=ssl_user dbname=postgres sslmode=verify-full sslrootcert=../test/server.crt",
+        TlsConnector::new(connector, "localhost"),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn verify_full_wrong_host() {
+    let connector = native_tls::TlsConnector::new().unwrap();
+    let connector = TlsConnector::new(connector, "otherhost");
+
+    let stream = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+    let builder =
+        "user=ssl_user dbname=postgres sslmode=verify-full sslrootcert=../test/server.crt"
+            .parse::<tokio_postgres::Config>()
+            .unwrap();
+    builder.connect_raw(stream, connector).await.err().unwrap();
+}
+
+#[tokio::test]
+async fn verify_ca() {
+    let connector = native_tls::TlsConnector::new().unwrap();
+    smoke_test(
+        "user=ssl_user dbname=postgres sslmode=verify-ca sslrootcert=../test/server.crt",
+        TlsConnector::new(connector, "otherhost"),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn verify_ca_untrusted() {
+    let connector = native_tls::TlsConnector::builder()
+        .danger_accept_invalid_certs(true)
+        .build()
+        .unwrap();
+    let connector = TlsConnector::new(connector, "localhost");
+
+    let stream = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+    let builder = "user=ssl_user dbname=postgres sslmode=verify-ca sslrootcert=../test/client.crt"
+        .parse::<tokio_postgres::Config>()
+        .unwrap();
+    builder.connect_raw(stream, connector).await.err().unwrap();
+}
+
+#[tokio::test]
+async fn verify_ca_system_roots() {
+    let connector = native_tls::TlsConnector::new().unwrap();
+    let connector = TlsConnector::new(connector, "localhost");
+
+    let stream = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+    let builder = "user=ssl_user dbname=postgres sslmode=verify-ca sslrootcert=system"
+        .parse::<tokio_postgres::Config>()
+        .unwrap();
+    let err = builder.connect_raw(stream, connector).await.err().unwrap();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        "sslmode \"verify-ca\" requires a root certificate file"
+    );
+}
+
+#[tokio::test]
+async fn client_cert() {
+    let connector = native_tls::TlsConnector::new().unwrap();
+    smoke_test(
+        "user=ssl_user dbname=postgres sslmode=verify-full sslrootcert=../test/server.crt \
+         sslcert=../test/client.crt sslkey=../test/client.key",
+        TlsConnector::new(connector, "localhost"),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn client_cert_without_verification() {
+    let connector = native_tls::TlsConnector::new().unwrap();
+    let connector = TlsConnector::new(connector, "localhost");
+
+    let stream = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+    let builder = "user=ssl_user dbname=postgres sslmode=require \
+                   sslcert=../test/client.crt sslkey=../test/client.key"
+        .parse::<tokio_postgres::Config>()
+        .unwrap();
+    let err = builder.connect_raw(stream, connector).await.err().unwrap();
+    assert!(
+        err.source()
+            .unwrap()
+            .to_string()
+            .starts_with("sslcert is only supported with sslmode"),
+        "{err}"
+    );
+}
+
+#[tokio::test]
+async fn client_cert_encrypted_key() {
+    let connector = native_tls::TlsConnector::new().unwrap();
+    let connector = TlsConnector::new(connector, "localhost");
+
+    let stream = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+    let builder = "user=ssl_user dbname=postgres sslmode=verify-full sslrootcert=../test/server.crt \
+                   sslcert=../test/client.crt sslkey=../test/client-encrypted.key sslpassword=password"
+        .parse::<tokio_postgres::Config>()
+        .unwrap();
+    builder.connect_raw(stream, connector).await.err().unwrap();
+}
+
 #[tokio::test]
 #[cfg(feature = "runtime")]
 async fn runtime() {
@@ -113,3 +222,103 @@ async fn runtime() {
     assert_eq!(rows.len(), 1);
     assert_eq!(rows[0].get::<_, i32>(0), 1);
 }
+
+#[tokio::test]
+#[cfg(feature = "runtime")]
+async fn runtime_system_roots_untrusted() {
+    let connector = MakeTlsConnector::with_system_roots().unwrap();
+
+    tokio_postgres::connect(
+        "host=localhost port=5433 user=postgres sslmode=require",
+        connector,
+    )
+    .await
+    .err()
+    .unwrap();
+}
+
+#[tokio::test]
+async fn tls_info() {
+    let connector = native_tls::TlsConnector::builder()
+        .add_root_certificate(
+            Certificate::from_pem(include_bytes!("../../test/server.crt")).unwrap(),
+        )
+        .build()
+        .unwrap();
+    let tls = TlsConnector::new(connector, "localhost");
+
+    let stream = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+    let config = "user=ssl_user dbname=postgres sslmode=require"
+        .parse::<tokio_postgres::Config>()
+        .unwrap();
+    let (client, connection) = config.connect_raw(stream, tls).await.unwrap();
+
+    let info = connection.tls_info().unwrap();
+    assert_eq!(
+        info.peer_certificate(),
+        Some(&include_bytes!("../../test/server.der")[..])
+    );
+
+    let info = client.tls_info().unwrap();
+    assert!(info.peer_certificate().is_some());
+}
+
+#[tokio::test]
+#[cfg(feature = "runtime")]
+async fn runtime_rotating_client_cert() {
+    use native_tls::Identity;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let loads = Arc::new(AtomicUsize::new(0));
+    let connector = MakeTlsConnector::with_callback({
+        let loads = loads.clone();
+        move |_| {
+            loads.fetch_add(1, Ordering::SeqCst);
+            let identity = Identity::from_pkcs8(
+                include_bytes!("../../test/client.crt"),
+                include_bytes!("../../test/client.key"),
+            )?;
+            native_tls::TlsConnector::builder()
+                .add_root_certificate(Certificate::from_pem(include_bytes!(
+                    "../../test/server.crt"
+                ))?)
+                .identity(identity)
+                .build()
+        }
+    });
+
+    for _ in 0..2 {
+        let (client, connection) = tokio_postgres::connect(
+            "host=localhost port=5433 user=ssl_user dbname=postgres sslmode=require",
+            connector.clone(),
+        )
+        .await
+        .unwrap();
+        let connection = connection.map(|r| r.unwrap());
+        tokio::spawn(connection);
+
+        client.simple_query("SELECT 1").await.unwrap();
+    }
+    assert_eq!(loads.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+#[cfg(feature = "runtime")]
+async fn runtime_server_name() {
+    let connector = native_tls::TlsConnector::new().unwrap();
+    let mut connector = MakeTlsConnector::new(connector);
+    connector.set_server_name("db.example.com", "localhost");
+
+    let (client, connection) = tokio_postgres::connect(
+        "host=db.example.com hostaddr=127.0.0.1 port=5433 user=ssl_user dbname=postgres \
+         sslmode=verify-full sslrootcert=../test/server.crt",
+        connector,
+    )
+    .await
+    .unwrap();
+    let connection = connection.map(|r| r.unwrap());
+    tokio::spawn(connection);
+
+    client.simple_query("SELECT 1").await.unwrap();
+}
@@ -0,0 +1,23 @@
+use std::env;
+
+fn main() {
+    println!("cargo::rustc-check-cfg=cfg(keylog)");
+
+    // `openssl` provides `SslContextBuilder::set_keylog_callback` for OpenSSL 1.1.1 and newer, BoringSSL, and AWS-LC,
+    // but not LibreSSL. `openssl-sys` only reports `version_number` for OpenSSL itself.
+    let boringssl = [
+        "DEP_OPENSSL_BORINGSSL",
+        "DEP_OPENSSL_AWSLC",
+        "DEP_OPENSSL_AWSLC_FIPS",
+    ]
+    .iter()
+    .any(|var| env::var_os(var).is_some());
+    let ossl111 = env::var_os("DEP_OPENSSL_LIBRESSL").is_none()
+        && env::var("DEP_OPENSSL_VERSION_NUMBER")
+            .ok()
+            .and_then(|version| u64::from_str_radix(&version, 16).ok())
+            .is_some_and(|version| version >= 0x1010_1000);
+    if boringssl || ossl111 {
+        println!("cargo::rustc-cfg=keylog");
+    }
+}
@@ -47,28 +47,43 @@
 //! ```
 #![warn(rust_2018_idioms, clippy::all, missing_docs)]
 
-#[cfg(feature = "runtime")]
 use openssl::error::ErrorStack;
 use openssl::hash::MessageDigest;
 use openssl::nid::Nid;
+use openssl::pkey::PKey;
+use openssl::ssl::{self, ConnectConfiguration, SslConnectorBuilder, SslRef, SslVerifyMode};
+#[cfg(feature = "runtime")]
+use openssl::ssl::{SslConnector, SslFiletype, SslMethod};
+use openssl::stack::Stack;
 #[cfg(feature = "runtime")]
-use openssl::ssl::SslConnector;
-use openssl::ssl::{self, ConnectConfiguration, SslConnectorBuilder, SslRef};
-use openssl::x509::X509VerifyResult;
+use openssl::x509::store::X509Lookup;
+use openssl::x509::store::{X509Store, X509StoreBuilder};
+use openssl::x509::verify::X509VerifyParam;
+use openssl::x509::{X509, X509StoreContext, X509StoreContextRef, X509VerifyResult};
+#[cfg(feature = "runtime")]
+use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::{self, Debug};
+use std::fs;
+#[cfg(keylog)]
+use std::fs::OpenOptions;
 use std::future::Future;
 use std::io;
+#[cfg(keylog)]
+use std::io::Write;
+use std::path::Path;
 use std::pin::Pin;
 #[cfg(feature = "runtime")]
 use std::sync::Arc;
+#[cfg(keylog)]
+use std::sync::Mutex;
 use std::task::{Context, Poll};
 use tokio::io::{AsyncRead, AsyncWrite, BufReader, ReadBuf};
 use tokio_openssl::SslStream;
 use tokio_postgres::tls;
 #[cfg(feature = "runtime")]
 use tokio_postgres::tls::MakeTlsConnect;
-use tokio_postgres::tls::{ChannelBinding, TlsConnect};
+use tokio_postgres::tls::{ChannelBinding, ClientCertificate, TlsConnect, TlsInfo, Verification};
 
 #[cfg(test)]
 mod test;
@@ -84,6 +99,7 @@ type ConfigCallback =
 pub struct MakeTlsConnector {
     connector: SslConnector,
     config: Arc<ConfigCallback>,
+    server_names: HashMap<String, String>,
 }
 
 #[cfg(feature = "runtime")]
@@ -93,18 +109,50 @@ impl MakeTlsConnector {
         MakeTlsConnector {
             connector,
             config: Arc::new(|_, _| Ok(())),
+            server_names: HashMap::new(),
+        }
+    }
+
+    /// Creates a new connector which verifies servers against the platform's trusted root certificates.
+    ///
+    /// The system's certificate file and directories are located with the `openssl-probe` crate, which respects the
+    /// `SSL_CERT_FILE` and `SSL_CERT_DIR` environment variables.
+    pub fn with_system_roots() -> Result<MakeTlsConnector, ErrorStack> {
+        let mut builder = SslConnector::builder(SslMethod::tls())?;
+        let probe = openssl_probe::probe();
+        if let Some(file) = &probe.cert_file {
+            builder.set_ca_file(file)?;
         }
+        for dir in probe.cert_dir.iter().filter_map(|dir| dir.to_str()) {
+            builder
+                .cert_store_mut()
+                .add_lookup(X509Lookup::hash_dir())?
+                .add_dir(dir, SslFiletype::PEM)?;
+        }
+        Ok(MakeTlsConnector::new(builder.build()))
     }
 
     /// Sets a callback used to apply per-connection configuration.
     ///
-    /// The the callback is provided the domain name along with the `ConnectConfiguration`.
+    /// The the callback is provided the domain name along with the `ConnectConfiguration`. Since it is called for each
+    /// connection, it can be used to present a client certificate which is rotated while the process is running, by
+    /// loading the current certificate and key and passing them to `set_certificate` and `set_private_key`.
     pub fn set_callback<F>(&mut self, f: F)
     where
         F: Fn(&mut ConnectConfiguration, &str) -> Result<(), ErrorStack> + 'static + Sync + Send,
     {
         self.config = Arc::new(f);
     }
+
+    /// Overrides the name used for SNI and certificate hostname verification when connecting to a host.
+    ///
+    /// By default, the name of the host in the `Config` is used. This can be used when a host is reached through an
+    /// address or load balancer whose certificate carries a different name. To connect to an IP address while
+    /// verifying a name, set both `host` and `hostaddr` in the `Config` instead.
+    pub fn set_server_name(&mut self, host: &str, server_name: &str) {
+        self.server_names
+            .insert(host.to_string(), server_name.to_string());
+    }
 }
 
 #[cfg(feature = "runtime")]
@@ -117,6 +165,7 @@ where
     type Error = ErrorStack;
 
     fn make_tls_connect(&mut self, domain: &str) -> Result<TlsConnector, ErrorStack> {
+        let domain = self.server_names.get(domain).map_or(domain, String::as_str);
         let mut ssl = self.connector.configure()?;
         (self.config)(&mut ssl, domain)?;
         Ok(TlsConnector::new(ssl, domain))
@@ -148,6 +197,73 @@ where
     #[allow(clippy::type_complexity)]
     type Future = Pin<Box<dyn Future<Output = Result<TlsStream<S>, Self::Error>> + Send>>;
 
+    fn set_verification(
+        &mut self,
+        verification: &Verification<'_>,
+    ) -> Result<(), Box<dyn Error + Sync + Send>> {
+        self.ssl.set_verify_hostname(verification.verify_hostname());
+
+        let Some(path) = verification.root_cert() else {
+            self.ssl.set_verify(SslVerifyMode::PEER);
+            return Ok(());
+        };
+
+        let mut store = X509StoreBuilder::new()?;
+        let certs = read(path, "root certificate")?;
+        for cert in X509::stack_from_pem(&certs)? {
+            store.add_cert(cert)?;
+        }
+        if verification.verify_hostname() {
+            let mut param = X509VerifyParam::new()?;
+            param.set_host(&self.domain)?;
+            store.set_param(&param)?;
+        }
+        let store = store.build();
+
+        // The certificate store of an individual connection can't be replaced on all supported OpenSSL versions, so
+        // the chain is instead checked against the root certificates once verification reaches the server's
+        // certificate, overriding the result of the connector's own checks.
+        self.ssl
+            .set_verify_callback(SslVerifyMode::PEER, move |_, ctx| {
+                if ctx.error_depth() != 0 {
+                    return true;
+                }
+                let result =
+                    verify_chain(&store, ctx).unwrap_or(X509VerifyResult::APPLICATION_VERIFICATION);
+                ctx.set_error(result);
+                result == X509VerifyResult::OK
+            });
+        Ok(())
+    }
+
+    fn set_client_certificate(
+        &mut self,
+        certificate: &ClientCertificate<'_>,
+    ) -> Result<(), Box<dyn Error + Sync + Send>> {
+        let data = read(certificate.cert(), "client certificate")?;
+        let cert = if is_pem(&data) {
+            X509::from_pem(&data)?
+        } else {
+            X509::from_der(&data)?
+        };
+
+        let data = match certificate.key() {
+            Some(path) => read(path, "private key")?,
+            None => data,
+        };
+        // Encrypted PEM keys are decrypted with the configured password, and never prompt for one.
+        let key = match (is_pem(&data), certificate.password()) {
+            (true, Some(password)) => PKey::private_key_from_pem_passphrase(&data, password)?,
+            (true, None) => PKey::private_key_from_pem_callback(&data, |_| Ok(0))?,
+            (false, Some(password)) => PKey::private_key_from_pkcs8_passphrase(&data, password)?,
+            (false, None) => PKey::private_key_from_der(&data)?,
+        };
+
+        self.ssl.set_certificate(&cert)?;
+        self.ssl.set_private_key(&key)?;
+        Ok(())
+    }
+
     fn connect(self, stream: S) -> Self::Future {
         let stream = BufReader::with_capacity(8192, stream);
         let future = async move {
@@ -166,6 +282,42 @@ where
     }
 }
 
+fn read(path: &Path, description: &str) -> Result<Vec<u8>, Box<dyn Error + Sync + Send>> {
+    fs::read(path).map_err(|e| {
+        format!(
+            "unable to read {} file {}: {}",
+            description,
+            path.display(),
+            e
+        )
+        .into()
+    })
+}
+
+fn is_pem(data: &[u8]) -> bool {
+    data.windows(10).any(|w| w == b"-----BEGIN")
+}
+
+fn verify_chain(
+    store: &X509Store,
+    ctx: &X509StoreContextRef,
+) -> Result<X509VerifyResult, ErrorStack> {
+    let (Some(cert), Some(chain)) = (ctx.current_cert(), ctx.chain()) else {
+        return Ok(X509VerifyResult::APPLICATION_VERIFICATION);
+    };
+
+    let mut untrusted = Stack::new()?;
+    for cert in chain.iter().skip(1) {
+        untrusted.push(cert.to_owned())?;
+    }
+
+    let mut ctx = X509StoreContext::new()?;
+    ctx.init(store, cert, &untrusted, |ctx| {
+        ctx.verify_cert()?;
+        Ok(ctx.error())
+    })
+}
+
 #[derive(Debug)]
 struct ConnectError {
     error: ssl::Error,
@@ -238,6 +390,15 @@ where
             None => ChannelBinding::none(),
         }
     }
+
+    fn tls_info(&self) -> TlsInfo {
+        let ssl = self.0.ssl();
+        TlsInfo::new(
+            Some(ssl.version_str().to_string()),
+            ssl.current_cipher().map(|c| c.name().to_string()),
+            ssl.peer_certificate().and_then(|c| c.to_der().ok()),
+        )
+    }
 }
 
 fn tls_server_end_point(ssl: &SslRef) -> Option<Vec<u8>> {
@@ -257,3 +418,41 @@ fn tls_server_end_point(ssl: &SslRef) -> Option<Vec<u8>> {
 pub fn set_postgresql_alpn(builder: &mut SslConnectorBuilder) -> Result<(), ErrorStack> {
     builder.set_alpn_protos(b"\x0apostgresql")
 }
+
+/// Configures a `SslConnectorBuilder` to log the secrets of each TLS session to a file in the `SSLKEYLOGFILE` format.
+///
+/// This allows packet captures of connections to be decrypted by tools like Wireshark. It should only be used for
+/// debugging, since anyone who can read the file can decrypt the logged sessions. Lines are appended to the file,
+/// which is created if it doesn't exist.
+///
+/// Requires OpenSSL 1.1.1 or newer, BoringSSL, or AWS-LC. An error of kind [`io::ErrorKind::Unsupported`] is returned
+/// if the linked TLS library doesn't support key logging, such as LibreSSL or older versions of OpenSSL.
+pub fn set_keylog_file<P>(builder: &mut SslConnectorBuilder, path: P) -> io::Result<()>
+where
+    P: AsRef<Path>,
+{
+    #[cfg(not(keylog))]
+    {
+        let _ = (builder, path);
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "the linked TLS library does not support key logging",
+        ))
+    }
+
+    #[cfg(keylog)]
+    {
+        let mut options = OpenOptions::new();
+        options.create(true).append(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let file = Mutex::new(options.open(path)?);
+
+        builder.set_keylog_callback(move |_, line| {
+            if let Ok(mut file) = file.lock() {
+                let _ = writeln!(file, "{line}");
+            }
+        });
+        Ok(())
+    }
+}
@@ -1,5 +1,5 @@
 use futures_util::FutureExt;
-use openssl::ssl::{SslConnector, SslMethod};
+use openssl::ssl::{SslConnector, SslMethod, SslVerifyMode};
 use tokio::net::TcpStream;
 use tokio_postgres::tls::TlsConnect;
 
@@ -100,6 +100,116 @@ async fn require_channel_binding_ok() {
     .await;
 }
 
+#[tokio::test]
+async fn verify_full() {
+    let ctx = SslConnector::builder(SslMethod::tls()).unwrap().build();
+    smoke_test(
+        "user=ssl_user dbname=postgres sslmode=verify-full sslrootcert=../test/server.crt",
+        TlsConnector::new(ctx.configure().unwrap(), "localhost"),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn verify_full_wrong_host() {
+    let ctx = SslConnector::builder(SslMethod::tls()).unwrap().build();
+    let connector = TlsConnector::new(ctx.configure().unwrap(), "otherhost");
+
+    let stream = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+    let builder =
+        "user=ssl_user dbname=postgres sslmode=verify-full sslrootcert=../test/server.crt"
+            .parse::<tokio_postgres::Config>()
+            .unwrap();
+    builder.connect_raw(stream, connector).await.err().unwrap();
+}
+
+#[tokio::test]
+async fn verify_ca() {
+    let ctx = SslConnector::builder(SslMethod::tls()).unwrap().build();
+    smoke_test(
+        "user=ssl_user dbname=postgres sslmode=verify-ca sslrootcert=../test/server.crt",
+        TlsConnector::new(ctx.configure().unwrap(), "otherhost"),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn verify_ca_untrusted() {
+    let mut builder = SslConnector::builder(SslMethod::tls()).unwrap();
+    builder.set_verify(SslVerifyMode::NONE);
+    let ctx = builder.build();
+    let connector = TlsConnector::new(ctx.configure().unwrap(), "localhost");
+
+    let stream = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+    let builder = "user=ssl_user dbname=postgres sslmode=verify-ca sslrootcert=../test/client.crt"
+        .parse::<tokio_postgres::Config>()
+        .unwrap();
+    builder.connect_raw(stream, connector).await.err().unwrap();
+}
+
+#[tokio::test]
+async fn verify_ca_system_roots() {
+    let ctx = SslConnector::builder(SslMethod::tls()).unwrap().build();
+    let connector = TlsConnector::new(ctx.configure().unwrap(), "localhost");
+
+    let stream = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+    let builder = "user=ssl_user dbname=postgres sslmode=verify-ca sslrootcert=system"
+        .parse::<tokio_postgres::Config>()
+        .unwrap();
+    let err = builder.connect_raw(stream, connector).await.err().unwrap();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        "sslmode \"verify-ca\" requires a root certificate file"
+    );
+}
+
+#[tokio::test]
+async fn client_cert() {
+    let ctx = SslConnector::builder(SslMethod::tls()).unwrap().build();
+    smoke_test(
+        "user=ssl_user dbname=postgres sslmode=verify-full sslrootcert=../test/server.crt \
+         sslcert=../test/client.crt sslkey=../test/client.key",
+        TlsConnector::new(ctx.configure().unwrap(), "localhost"),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn client_cert_der() {
+    let ctx = SslConnector::builder(SslMethod::tls()).unwrap().build();
+    smoke_test(
+        "user=ssl_user dbname=postgres sslmode=verify-full sslrootcert=../test/server.crt \
+         sslcert=../test/client.der sslkey=../test/client.key",
+        TlsConnector::new(ctx.configure().unwrap(), "localhost"),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn client_cert_encrypted_key() {
+    let ctx = SslConnector::builder(SslMethod::tls()).unwrap().build();
+    smoke_test(
+        "user=ssl_user dbname=postgres sslmode=verify-full sslrootcert=../test/server.crt \
+         sslcert=../test/client.crt sslkey=../test/client-encrypted.key sslpassword=password",
+        TlsConnector::new(ctx.configure().unwrap(), "localhost"),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn client_cert_wrong_password() {
+    let ctx = SslConnector::builder(SslMethod::tls()).unwrap().build();
+    let connector = TlsConnector::new(ctx.configure().unwrap(), "localhost");
+
+    let stream = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+    let builder =
+        "user=ssl_user dbname=postgres sslmode=verify-full sslrootcert=../test/server.crt \
+                   sslcert=../test/client.crt sslkey=../test/client-encrypted.key sslpassword=wrong"
+            .parse::<tokio_postgres::Config>()
+            .unwrap();
+    builder.connect_raw(stream, connector).await.err().unwrap();
+}
+
 #[tokio::test]
 #[cfg(feature = "runtime")]
 async fn runtime() {
@@ -122,3 +232,122 @@ async fn runtime() {
     assert_eq!(rows.len(), 1);
     assert_eq!(rows[0].get::<_, i32>(0), 1);
 }
+
+#[tokio::test]
+#[cfg(feature = "runtime")]
+async fn runtime_system_roots_untrusted() {
+    let connector = MakeTlsConnector::with_system_roots().unwrap();
+
+    tokio_postgres::connect(
+        "host=localhost port=5433 user=postgres sslmode=require",
+        connector,
+    )
+    .await
+    .err()
+    .unwrap();
+}
+
+#[tokio::test]
+async fn tls_info() {
+    let mut builder = SslConnector::builder(SslMethod::tls()).unwrap();
+    builder.set_ca_file("../test/server.crt").unwrap();
+    let ctx = builder.build();
+    let tls = TlsConnector::new(ctx.configure().unwrap(), "localhost");
+
+    let stream = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+    let config = "user=ssl_user dbname=postgres sslmode=require"
+        .parse::<tokio_postgres::Config>()
+        .unwrap();
+    let (client, connection) = config.connect_raw(stream, tls).await.unwrap();
+
+    let info = connection.tls_info().unwrap();
+    assert_eq!(
+        info.peer_certificate(),
+        Some(&include_bytes!("../../test/server.der")[..])
+    );
+    assert!(info.protocol_version().unwrap().starts_with("TLSv1."));
+    assert!(info.cipher_suite().is_some());
+
+    let info = client.tls_info().unwrap();
+    assert!(info.peer_certificate().is_some());
+}
+
+#[tokio::test]
+#[cfg(feature = "runtime")]
+async fn runtime_rotating_client_cert() {
+    use openssl::pkey::PKey;
+    use openssl::x509::X509;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let mut builder = SslConnector::builder(SslMethod::tls()).unwrap();
+    builder.set_ca_file("../test/server.crt").unwrap();
+    let mut connector = MakeTlsConnector::new(builder.build());
+    let loads = Arc::new(AtomicUsize::new(0));
+    connector.set_callback({
+        let loads = loads.clone();
+        move |config, _| {
+            loads.fetch_add(1, Ordering::SeqCst);
+            let cert = X509::from_pem(include_bytes!("../../test/client.crt"))?;
+            let key = PKey::private_key_from_pem(include_bytes!("../../test/client.key"))?;
+            config.set_certificate(&cert)?;
+            config.set_private_key(&key)
+        }
+    });
+
+    for _ in 0..2 {
+        let (client, connection) = tokio_postgres::connect(
+            "host=localhost port=5433 user=ssl_user dbname=postgres sslmode=require",
+            connector.clone(),
+        )
+        .await
+        .unwrap();
+        let connection = connection.map(|r| r.unwrap());
+        tokio::spawn(connection);
+
+        client.simple_query("SELECT 1").await.unwrap();
+    }
+    assert_eq!(loads.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+#[cfg(feature = "runtime")]
+async fn runtime_server_name() {
+    let builder = SslConnector::builder(SslMethod::tls()).unwrap();
+    let mut connector = MakeTlsConnector::new(builder.build());
+    connector.set_server_name("db.example.com", "localhost");
+
+    let (client, connection) = tokio_postgres::connect(
+        "host=db.example.com hostaddr=127.0.0.1 port=5433 user=ssl_user dbname=postgres \
+         sslmode=verify-full sslrootcert=../test/server.crt",
+        connector,
+    )
+    .await
+    .unwrap();
+    let connection = connection.map(|r| r.unwrap());
+    tokio::spawn(connection);
+
+    client.simple_query("SELECT 1").await.unwrap();
+}
+
+#[tokio::test]
+#[cfg(keylog)]
+async fn keylog_file() {
+    let path = std::env::temp_dir().join(format!("postgres-openssl-keylog-{}", std::process::id()));
+
+    let mut builder = SslConnector::builder(SslMethod::tls()).unwrap();
+    builder.set_ca_file("../test/server.crt").unwrap();
+    set_keylog_file(&mut builder, &path).unwrap();
+    let ctx = builder.build();
+    smoke_test(
+        "user=ssl_user dbname=postgres sslmode=require",
+        TlsConnector::new(ctx.configure().unwrap(), "localhost"),
+    )
+    .await;
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(!contents.is_empty());
+    for line in contents.lines() {
+        assert_eq!(line.split(' ').count(), 3, "{line}");
+    }
+}
@@ -0,0 +1,74 @@
+//! A connection manager for the [`bb8`](https://crates.io/crates/bb8) pool.
+//!
+//! Requires the `with-bb8-0_9` Cargo feature.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use postgres_pool::bb8::PostgresConnectionManager;
+//! use tokio_postgres::NoTls;
+//!
+//! # async fn f() -> Result<(), Box<dyn std::error::Error>> {
+//! let config = "host=localhost user=postgres".parse()?;
+//! let manager = PostgresConnectionManager::new(config, NoTls);
+//! let pool = bb8_09::Pool::builder().max_size(16).build(manager).await?;
+//!
+//! let client = pool.get().await?;
+//! client.execute("SELECT 1", &[]).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{connect, end_transaction};
+use bb8_09::ManageConnection;
+use std::fmt;
+use tokio_postgres::tls::{MakeTlsConnect, TlsConnect};
+use tokio_postgres::{Client, Config, Error, Socket};
+
+/// A `bb8::ManageConnection` implementation for `tokio-postgres` clients.
+///
+/// Connections are validated on checkout by ending any transaction left open by their previous user and then issuing
+/// a lightweight ping. Connections which have been closed are discarded when they're returned to the pool.
+pub struct PostgresConnectionManager<T> {
+    config: Config,
+    tls: T,
+}
+
+impl<T> PostgresConnectionManager<T> {
+    /// Creates a new manager which opens connections to the database described by `config`.
+    pub fn new(config: Config, tls: T) -> PostgresConnectionManager<T> {
+        PostgresConnectionManager { config, tls }
+    }
+}
+
+impl<T> fmt::Debug for PostgresConnectionManager<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PostgresConnectionManager")
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+impl<T> ManageConnection for PostgresConnectionManager<T>
+where
+    T: MakeTlsConnect<Socket> + Clone + Sync + Send + 'static,
+    T::Stream: Send,
+    T::TlsConnect: Send,
+    <T::TlsConnect as TlsConnect<Socket>>::Future: Send,
+{
+    type Connection = Client;
+    type Error = Error;
+
+    async fn connect(&self) -> Result<Client, Error> {
+        connect(&self.config, self.tls.clone()).await
+    }
+
+    async fn is_valid(&self, client: &mut Client) -> Result<(), Error> {
+        end_transaction(client).await?;
+        client.check_connection().await
+    }
+
+    fn has_broken(&self, client: &mut Client) -> bool {
+        client.is_closed()
+    }
+}
@@ -0,0 +1,102 @@
+//! A connection manager for the [`deadpool`](https://crates.io/crates/deadpool) pool.
+//!
+//! Requires the `with-deadpool-0_12` Cargo feature.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use postgres_pool::deadpool::{Manager, Pool, RecyclingMethod};
+//! use tokio_postgres::NoTls;
+//!
+//! # async fn f() -> Result<(), Box<dyn std::error::Error>> {
+//! let config = "host=localhost user=postgres".parse()?;
+//! let manager = Manager::new(config, NoTls, RecyclingMethod::Verified);
+//! let pool = Pool::builder(manager).max_size(16).build()?;
+//!
+//! let client = pool.get().await?;
+//! client.execute("SELECT 1", &[]).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{connect, end_transaction};
+use deadpool_012::managed::{self, Metrics, RecycleError, RecycleResult};
+use std::fmt;
+use tokio_postgres::tls::{MakeTlsConnect, TlsConnect};
+use tokio_postgres::{Client, Config, Error, Socket};
+
+/// A `deadpool` pool of `tokio-postgres` clients.
+pub type Pool<T> = managed::Pool<Manager<T>>;
+
+/// The checks performed on a connection before it's reused.
+///
+/// Regardless of the method, closed connections are discarded and any transaction left open by the previous user of
+/// a connection is rolled back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum RecyclingMethod {
+    /// Performs no further checks.
+    #[default]
+    Fast,
+    /// Verifies the connection with a lightweight ping.
+    Verified,
+    /// Discards all session state with [`Client::reset`], which also deallocates prepared statements.
+    Clean,
+}
+
+/// A `deadpool::managed::Manager` implementation for `tokio-postgres` clients.
+pub struct Manager<T> {
+    config: Config,
+    tls: T,
+    recycling_method: RecyclingMethod,
+}
+
+impl<T> Manager<T> {
+    /// Creates a new manager which opens connections to the database described by `config`.
+    pub fn new(config: Config, tls: T, recycling_method: RecyclingMethod) -> Manager<T> {
+        Manager {
+            config,
+            tls,
+            recycling_method,
+        }
+    }
+}
+
+impl<T> fmt::Debug for Manager<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Manager")
+            .field("config", &self.config)
+            .field("recycling_method", &self.recycling_method)
+            .finish()
+    }
+}
+
+impl<T> managed::Manager for Manager<T>
+where
+    T: MakeTlsConnect<Socket> + Clone + Sync + Send + 'static,
+    T::Stream: Send,
+    T::TlsConnect: Send,
+    <T::TlsConnect as TlsConnect<Socket>>::Future: Send,
+{
+    type Type = Client;
+    type Error = Error;
+
+    async fn create(&self) -> Result<Client, Error> {
+        connect(&self.config, self.tls.clone()).await
+    }
+
+    async fn recycle(&self, client: &mut Client, _: &Metrics) -> RecycleResult<Error> {
+        if client.is_closed() {
+            return Err(RecycleError::message("connection closed"));
+        }
+
+        end_transaction(client).await?;
+        match self.recycling_method {
+            RecyclingMethod::Fast => {}
+            RecyclingMethod::Verified => client.check_connection().await?,
+            RecyclingMethod::Clean => client.reset().await?,
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,576 @@
+//! An asynchronous connection pool for `tokio-postgres`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use postgres_pool::Pool;
+//! use tokio_postgres::NoTls;
+//!
+//! # async fn f() -> Result<(), Box<dyn std::error::Error>> {
+//! let config = "host=localhost user=postgres".parse()?;
+//! let pool = Pool::builder(config).max_size(16).build(NoTls);
+//!
+//! let client = pool.get().await?;
+//! let statement = client.prepare_cached("SELECT $1::INT").await?;
+//! let row = client.query_one(&statement, &[&1i32]).await?;
+//! assert_eq!(row.get::<_, i32>(0), 1);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Read/write splitting
+//!
+//! When the configuration lists several hosts, such as a primary server and its hot standbys, [`Pool::get_write`]
+//! checks out connections to a host which allows writes and [`Pool::get_read`] prefers a read-only host. Each role
+//! keeps its own set of idle connections, but all of them count towards the pool's maximum size.
+//!
+//! # Features
+//!
+//! The following features can be enabled from `Cargo.toml`:
+//!
+//! | Feature | Description | Extra dependencies | Default |
+//! | ------- | ----------- | ------------------ | ------- |
+//! | `with-bb8-0_9` | Enable a connection manager for the `bb8` pool. | [bb8](https://crates.io/crates/bb8) 0.9 | no |
+//! | `with-deadpool-0_12` | Enable a connection manager for the `deadpool` pool. | [deadpool](https://crates.io/crates/deadpool) 0.12 | no |
+#![warn(rust_2018_idioms, clippy::all, missing_docs)]
+
+use futures_util::future::BoxFuture;
+use log::{debug, warn};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::error;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio_postgres::config::TargetSessionAttrs;
+use tokio_postgres::tls::{MakeTlsConnect, TlsConnect};
+use tokio_postgres::{Client, Config, Error, Socket, Statement, TransactionStatus, TypeCache};
+
+#[cfg(feature = "with-bb8-0_9")]
+pub mod bb8;
+#[cfg(feature = "with-deadpool-0_12")]
+pub mod deadpool;
+#[cfg(test)]
+mod test;
+
+type ConnectFn = dyn Fn() -> BoxFuture<'static, Result<Client, Error>> + Sync + Send;
+
+/// The number of kinds of connections managed by a pool: any, read and write.
+const ROLES: usize = 3;
+
+/// An error returned when checking a connection out of a [`Pool`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum PoolError {
+    /// No connection became available before the acquire timeout elapsed.
+    Timeout,
+    /// An error opening a new connection.
+    Backend(Error),
+}
+
+impl fmt::Display for PoolError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PoolError::Timeout => fmt.write_str("timed out waiting for a connection"),
+            PoolError::Backend(_) => fmt.write_str("error opening a new connection"),
+        }
+    }
+}
+
+impl error::Error for PoolError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            PoolError::Timeout => None,
+            PoolError::Backend(e) => Some(e),
+        }
+    }
+}
+
+impl From<Error> for PoolError {
+    fn from(e: Error) -> PoolError {
+        PoolError::Backend(e)
+    }
+}
+
+/// A builder for a [`Pool`].
+pub struct Builder {
+    config: Config,
+    max_size: usize,
+    acquire_timeout: Option<Duration>,
+    idle_timeout: Option<Duration>,
+    test_on_checkout: bool,
+    warm_up: Vec<String>,
+    type_cache: TypeCache,
+}
+
+impl Builder {
+    /// Sets the maximum number of connections managed by the pool.
+    ///
+    /// Defaults to 10.
+    pub fn max_size(mut self, max_size: usize) -> Builder {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Sets the maximum amount of time to wait when checking out a connection, including the time taken to open a
+    /// new one.
+    ///
+    /// Defaults to 30 seconds. If `None`, [`Pool::get`] waits indefinitely.
+    pub fn acquire_timeout(mut self, acquire_timeout: Option<Duration>) -> Builder {
+        self.acquire_timeout = acquire_timeout;
+        self
+    }
+
+    /// Sets the amount of time a connection may sit idle in the pool before it is closed.
+    ///
+    /// Defaults to 10 minutes. If `None`, idle connections are kept open indefinitely.
+    pub fn idle_timeout(mut self, idle_timeout: Option<Duration>) -> Builder {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    /// Determines if idle connections are checked with a lightweight ping before being handed out.
+    ///
+    /// Connections which fail the check are discarded. Defaults to `true`.
+    pub fn test_on_checkout(mut self, test_on_checkout: bool) -> Builder {
+        self.test_on_checkout = test_on_checkout;
+        self
+    }
+
+    /// Adds a statement which is prepared on every new connection as soon as it's opened.
+    ///
+    /// Warmed up statements are placed in the connection's statement cache, so the first call to
+    /// [`PooledClient::prepare_cached`] with the same query doesn't have to wait for the server. Since replacements
+    /// for broken connections are new connections, they're warmed up as well. If a statement fails to prepare, the
+    /// new connection is discarded and the error is returned from [`Pool::get`].
+    pub fn warm_up(mut self, query: &str) -> Builder {
+        self.warm_up.push(query.to_string());
+        self
+    }
+
+    /// Sets the cache of user-defined type information shared by the pool's connections.
+    ///
+    /// Every connection opened by the pool, including replacements for broken ones, uses this cache, so a type
+    /// looked up by one connection is available to all of them without querying the catalog again. By default each
+    /// pool has its own cache; setting one allows it to be shared with other pools or clients connected to the same
+    /// database.
+    pub fn type_cache(mut self, type_cache: TypeCache) -> Builder {
+        self.type_cache = type_cache;
+        self
+    }
+
+    /// Creates the pool.
+    ///
+    /// No connections are opened until they are requested. The pool spawns the `Connection` half of each connection
+    /// onto the Tokio runtime, so it must be used from within one.
+    pub fn build<T>(self, tls: T) -> Pool
+    where
+        T: MakeTlsConnect<Socket> + Clone + Sync + Send + 'static,
+        T::Stream: Send,
+        T::TlsConnect: Send,
+        <T::TlsConnect as TlsConnect<Socket>>::Future: Send,
+    {
+        let mut write_config = self.config.clone();
+        write_config.target_session_attrs(TargetSessionAttrs::ReadWrite);
+        let write = {
+            let tls = tls.clone();
+            move || -> BoxFuture<'static, Result<Client, Error>> {
+                let config = write_config.clone();
+                let tls = tls.clone();
+                Box::pin(async move { connect(&config, tls).await })
+            }
+        };
+
+        let mut read_config = self.config.clone();
+        read_config.target_session_attrs(TargetSessionAttrs::ReadOnly);
+        let fallback_config = self.config.clone();
+        let read = {
+            let tls = tls.clone();
+            move || -> BoxFuture<'static, Result<Client, Error>> {
+                let config = read_config.clone();
+                let fallback_config = fallback_config.clone();
+                let tls = tls.clone();
+                Box::pin(async move {
+                    match connect(&config, tls.clone()).await {
+                        Ok(client) => Ok(client),
+                        Err(e) => {
+                            debug!("no standby available, falling back to any host: {}", e);
+                            connect(&fallback_config, tls).await
+                        }
+                    }
+                })
+            }
+        };
+
+        let config = self.config.clone();
+        let any = move || -> BoxFuture<'static, Result<Client, Error>> {
+            let config = config.clone();
+            let tls = tls.clone();
+            Box::pin(async move { connect(&config, tls).await })
+        };
+
+        let semaphore = Arc::new(Semaphore::new(self.max_size));
+        let idle = Arc::new([(); ROLES].map(|_| Mutex::new(Vec::new())));
+        Pool {
+            inner: self.inner(Box::new(any), &semaphore, &idle, 0),
+            read: self.inner(Box::new(read), &semaphore, &idle, 1),
+            write: self.inner(Box::new(write), &semaphore, &idle, 2),
+        }
+    }
+
+    fn inner(
+        &self,
+        connect: Box<ConnectFn>,
+        semaphore: &Arc<Semaphore>,
+        idle: &Arc<[Mutex<Vec<IdleConn>>; ROLES]>,
+        role: usize,
+    ) -> Arc<Inner> {
+        Arc::new(Inner {
+            connect,
+            semaphore: semaphore.clone(),
+            idle: idle.clone(),
+            role,
+            max_size: self.max_size,
+            acquire_timeout: self.acquire_timeout,
+            idle_timeout: self.idle_timeout,
+            test_on_checkout: self.test_on_checkout,
+            warm_up: self.warm_up.clone(),
+            type_cache: self.type_cache.clone(),
+        })
+    }
+}
+
+/// Opens a connection, spawning its `Connection` half onto the Tokio runtime.
+pub(crate) async fn connect<T>(config: &Config, tls: T) -> Result<Client, Error>
+where
+    T: MakeTlsConnect<Socket>,
+    T::Stream: Send + 'static,
+{
+    let (client, connection) = config.connect(tls).await?;
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            warn!("pooled connection failed: {}", e);
+        }
+    });
+    Ok(client)
+}
+
+/// Ends any transaction left open by the previous user of a pooled connection.
+pub(crate) async fn end_transaction(client: &Client) -> Result<(), Error> {
+    if client.transaction_status() == TransactionStatus::Idle {
+        return Ok(());
+    }
+
+    // dropping a `Transaction` queues a rollback, so wait for any outstanding requests to finish before checking again
+    client.check_connection().await?;
+    if client.transaction_status() != TransactionStatus::Idle {
+        client.batch_execute("ROLLBACK").await?;
+    }
+    Ok(())
+}
+
+struct Conn {
+    client: Client,
+    statements: Mutex<HashMap<String, Statement>>,
+}
+
+struct IdleConn {
+    conn: Conn,
+    since: Instant,
+}
+
+/// The state of one role of a pool.
+///
+/// The semaphore and the idle connections of every role are shared, so that the limit applies to the pool as a whole.
+struct Inner {
+    connect: Box<ConnectFn>,
+    semaphore: Arc<Semaphore>,
+    idle: Arc<[Mutex<Vec<IdleConn>>; ROLES]>,
+    role: usize,
+    max_size: usize,
+    acquire_timeout: Option<Duration>,
+    idle_timeout: Option<Duration>,
+    test_on_checkout: bool,
+    warm_up: Vec<String>,
+    type_cache: TypeCache,
+}
+
+impl Inner {
+    fn idle(&self) -> &Mutex<Vec<IdleConn>> {
+        &self.idle[self.role]
+    }
+
+    /// Closes an idle connection of another role if opening a new connection would exceed the pool's maximum size.
+    fn make_room(&self) {
+        let in_use = self.max_size - self.semaphore.available_permits();
+        let idle = self.idle.iter().map(|i| i.lock().len()).sum::<usize>();
+        if in_use + idle <= self.max_size {
+            return;
+        }
+
+        for (role, idle) in self.idle.iter().enumerate() {
+            let mut idle = idle.lock();
+            if role != self.role && !idle.is_empty() {
+                debug!("closing idle connection to make room");
+                // the oldest connection is at the front
+                idle.remove(0);
+                return;
+            }
+        }
+    }
+
+    fn is_expired(&self, idle: &IdleConn) -> bool {
+        self.idle_timeout
+            .is_some_and(|timeout| idle.since.elapsed() >= timeout)
+    }
+
+    fn evict_expired(&self, idle: &mut Vec<IdleConn>) {
+        let len = idle.len();
+        idle.retain(|c| !self.is_expired(c) && !c.conn.client.is_closed());
+        if idle.len() != len {
+            debug!("evicted {} idle connections", len - idle.len());
+        }
+    }
+
+    async fn get(self: &Arc<Self>) -> Result<PooledClient, PoolError> {
+        match self.acquire_timeout {
+            Some(timeout) => tokio::time::timeout(timeout, self.get_inner())
+                .await
+                .unwrap_or(Err(PoolError::Timeout)),
+            None => self.get_inner().await,
+        }
+    }
+
+    async fn get_inner(self: &Arc<Self>) -> Result<PooledClient, PoolError> {
+        let permit = self
+            .semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("pool semaphore closed");
+
+        loop {
+            let idle = {
+                let mut idle = self.idle().lock();
+                self.evict_expired(&mut idle);
+                idle.pop()
+            };
+            let Some(idle) = idle else {
+                break;
+            };
+
+            if let Err(e) = end_transaction(&idle.conn.client).await {
+                debug!("discarding idle connection: {}", e);
+                continue;
+            }
+
+            if self.test_on_checkout {
+                if let Err(e) = idle.conn.client.check_connection().await {
+                    debug!("discarding broken idle connection: {}", e);
+                    continue;
+                }
+            }
+
+            return Ok(PooledClient::new(idle.conn, self.clone(), permit));
+        }
+
+        self.make_room();
+        debug!("opening new pooled connection");
+        let client = (self.connect)().await?;
+        client.set_type_cache(self.type_cache.clone());
+        let mut statements = HashMap::new();
+        for query in &self.warm_up {
+            let statement = client.prepare(query).await?;
+            statements.insert(query.clone(), statement);
+        }
+        let conn = Conn {
+            client,
+            statements: Mutex::new(statements),
+        };
+        Ok(PooledClient::new(conn, self.clone(), permit))
+    }
+}
+
+/// A snapshot of the state of a [`Pool`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Status {
+    /// The maximum number of connections managed by the pool.
+    pub max_size: usize,
+    /// The number of connections currently checked out of the pool.
+    pub in_use: usize,
+    /// The number of idle connections in the pool.
+    pub idle: usize,
+}
+
+/// An asynchronous pool of connections.
+///
+/// Pools are cheap to clone; all clones share the same set of connections.
+#[derive(Clone)]
+pub struct Pool {
+    inner: Arc<Inner>,
+    read: Arc<Inner>,
+    write: Arc<Inner>,
+}
+
+impl fmt::Debug for Pool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pool")
+            .field("status", &self.status())
+            .finish()
+    }
+}
+
+impl Pool {
+    /// Returns a builder for a pool of connections to the database described by `config`.
+    pub fn builder(config: Config) -> Builder {
+        Builder {
+            config,
+            max_size: 10,
+            acquire_timeout: Some(Duration::from_secs(30)),
+            idle_timeout: Some(Duration::from_secs(10 * 60)),
+            test_on_checkout: true,
+            warm_up: vec![],
+            type_cache: TypeCache::new(),
+        }
+    }
+
+    /// Checks a connection out of the pool, opening a new one if no idle connections are available.
+    ///
+    /// The connection is returned to the pool when the [`PooledClient`] is dropped.
+    pub async fn get(&self) -> Result<PooledClient, PoolError> {
+        self.inner.get().await
+    }
+
+    /// Checks out a connection suitable for read-only queries.
+    ///
+    /// Connections are opened to the first host in the configuration which is read-only, such as a hot standby,
+    /// falling back to any host if none is. Idle read connections are kept separately from those returned by
+    /// [`get`](Pool::get) and [`get_write`](Pool::get_write), but count towards the same maximum size.
+    pub async fn get_read(&self) -> Result<PooledClient, PoolError> {
+        self.read.get().await
+    }
+
+    /// Checks out a connection which allows writes.
+    ///
+    /// Connections are opened to the first host in the configuration which allows writes, such as a primary server.
+    /// Idle write connections are kept separately from those returned by [`get`](Pool::get) and
+    /// [`get_read`](Pool::get_read), but count towards the same maximum size.
+    pub async fn get_write(&self) -> Result<PooledClient, PoolError> {
+        self.write.get().await
+    }
+
+    /// Returns the cache of user-defined type information shared by the pool's connections.
+    ///
+    /// See [`Builder::type_cache`] for details.
+    pub fn type_cache(&self) -> TypeCache {
+        self.inner.type_cache.clone()
+    }
+
+    /// Returns a snapshot of the pool's state.
+    ///
+    /// The counts include read and write connections.
+    pub fn status(&self) -> Status {
+        Status {
+            max_size: self.inner.max_size,
+            in_use: self.inner.max_size - self.inner.semaphore.available_permits(),
+            idle: self.inner.idle.iter().map(|i| i.lock().len()).sum(),
+        }
+    }
+}
+
+/// A connection checked out of a [`Pool`].
+///
+/// It dereferences to a [`Client`], and returns the connection to the pool when dropped. Connections which have been
+/// closed are discarded instead, and any transaction left open is rolled back before the connection is handed out
+/// again.
+pub struct PooledClient {
+    conn: Option<Conn>,
+    inner: Arc<Inner>,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl PooledClient {
+    fn new(conn: Conn, inner: Arc<Inner>, permit: OwnedSemaphorePermit) -> PooledClient {
+        PooledClient {
+            conn: Some(conn),
+            inner,
+            _permit: permit,
+        }
+    }
+
+    fn conn(&self) -> &Conn {
+        self.conn.as_ref().unwrap()
+    }
+
+    /// Like [`Client::prepare`], but caches the statement on the underlying connection.
+    ///
+    /// The cache outlives this checkout, so later checkouts of the same connection reuse the statement without
+    /// preparing it again. Lookups are reported to the connection's [`ClientMetrics`](tokio_postgres::ClientMetrics),
+    /// if it has any.
+    pub async fn prepare_cached(&self, query: &str) -> Result<Statement, Error> {
+        let metrics = self.conn().client.metrics();
+        if let Some(statement) = self.conn().statements.lock().get(query) {
+            if let Some(metrics) = metrics {
+                metrics.prepare_cache_hit(query);
+            }
+            return Ok(statement.clone());
+        }
+
+        if let Some(metrics) = metrics {
+            metrics.prepare_cache_miss(query);
+        }
+        let statement = self.conn().client.prepare(query).await?;
+        self.conn()
+            .statements
+            .lock()
+            .insert(query.to_string(), statement.clone());
+        Ok(statement)
+    }
+
+    /// Clears the statement cache of the underlying connection.
+    pub fn clear_statement_cache(&self) {
+        self.conn().statements.lock().clear();
+    }
+}
+
+impl Deref for PooledClient {
+    type Target = Client;
+
+    fn deref(&self) -> &Client {
+        &self.conn().client
+    }
+}
+
+impl DerefMut for PooledClient {
+    fn deref_mut(&mut self) -> &mut Client {
+        &mut self.conn.as_mut().unwrap().client
+    }
+}
+
+impl fmt::Debug for PooledClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PooledClient").finish()
+    }
+}
+
+impl Drop for PooledClient {
+    fn drop(&mut self) {
+        let Some(conn) = self.conn.take() else {
+            return;
+        };
+
+        if conn.client.is_closed() {
+            debug!("discarding closed pooled connection");
+            return;
+        }
+
+        let mut idle = self.inner.idle().lock();
+        idle.push(IdleConn {
+            conn,
+            since: Instant::now(),
+        });
+        self.inner.evict_expired(&mut idle);
+    }
+}
@@ -0,0 +1,363 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio_postgres::error::SqlState;
+use tokio_postgres::{ClientMetrics, NoTls};
+
+use super::*;
+
+fn builder() -> Builder {
+    Pool::builder("host=localhost port=5433 user=postgres".parse().unwrap())
+}
+
+async fn backend_pid(client: &Client) -> i32 {
+    client
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await
+        .unwrap()
+        .get(0)
+}
+
+#[tokio::test]
+async fn reuse() {
+    let pool = builder().build(NoTls);
+
+    let client = pool.get().await.unwrap();
+    let pid = backend_pid(&client).await;
+    assert_eq!(
+        pool.status(),
+        Status {
+            max_size: 10,
+            in_use: 1,
+            idle: 0
+        }
+    );
+    drop(client);
+    assert_eq!(
+        pool.status(),
+        Status {
+            max_size: 10,
+            in_use: 0,
+            idle: 1
+        }
+    );
+
+    let client = pool.get().await.unwrap();
+    assert_eq!(backend_pid(&client).await, pid);
+}
+
+#[tokio::test]
+async fn acquire_timeout() {
+    let pool = builder()
+        .max_size(1)
+        .acquire_timeout(Some(Duration::from_millis(100)))
+        .build(NoTls);
+
+    let client = pool.get().await.unwrap();
+    match pool.get().await {
+        Err(PoolError::Timeout) => {}
+        r => panic!("unexpected result {:?}", r),
+    }
+
+    drop(client);
+    pool.get().await.unwrap();
+}
+
+#[tokio::test]
+async fn idle_timeout() {
+    let pool = builder()
+        .idle_timeout(Some(Duration::from_millis(10)))
+        .build(NoTls);
+
+    let client = pool.get().await.unwrap();
+    let pid = backend_pid(&client).await;
+    drop(client);
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let client = pool.get().await.unwrap();
+    assert_ne!(backend_pid(&client).await, pid);
+}
+
+#[tokio::test]
+async fn test_on_checkout() {
+    let pool = builder().build(NoTls);
+
+    let client = pool.get().await.unwrap();
+    let pid = backend_pid(&client).await;
+    drop(client);
+
+    let killer = builder().build(NoTls);
+    killer
+        .get()
+        .await
+        .unwrap()
+        .execute("SELECT pg_terminate_backend($1)", &[&pid])
+        .await
+        .unwrap();
+
+    let client = pool.get().await.unwrap();
+    assert_ne!(backend_pid(&client).await, pid);
+}
+
+#[tokio::test]
+async fn prepare_cached() {
+    async fn prepared_count(client: &Client) -> i64 {
+        client
+            .query_one(
+                "SELECT COUNT(*) FROM pg_prepared_statements WHERE statement = 'SELECT $1::INT'",
+                &[],
+            )
+            .await
+            .unwrap()
+            .get(0)
+    }
+
+    let pool = builder().max_size(1).build(NoTls);
+
+    let client = pool.get().await.unwrap();
+    let _statement = client.prepare_cached("SELECT $1::INT").await.unwrap();
+    drop(client);
+
+    let client = pool.get().await.unwrap();
+    let cached = client.prepare_cached("SELECT $1::INT").await.unwrap();
+    let row = client.query_one(&cached, &[&1i32]).await.unwrap();
+    assert_eq!(row.get::<_, i32>(0), 1);
+    assert_eq!(prepared_count(&client).await, 1);
+
+    client.clear_statement_cache();
+    let _prepared = client.prepare_cached("SELECT $1::INT").await.unwrap();
+    assert_eq!(prepared_count(&client).await, 2);
+}
+
+#[tokio::test]
+async fn prepare_cache_metrics() {
+    #[derive(Default)]
+    struct Counts {
+        hits: AtomicUsize,
+        misses: AtomicUsize,
+    }
+
+    impl ClientMetrics for Counts {
+        fn prepare_cache_hit(&self, _: &str) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn prepare_cache_miss(&self, _: &str) {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    let counts = Arc::new(Counts::default());
+    let mut config = "host=localhost port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap();
+    config.metrics(counts.clone());
+    let pool = Pool::builder(config).max_size(1).build(NoTls);
+
+    let client = pool.get().await.unwrap();
+    client.prepare_cached("SELECT $1::INT").await.unwrap();
+    client.prepare_cached("SELECT $1::INT").await.unwrap();
+    client.prepare_cached("SELECT $1::TEXT").await.unwrap();
+
+    assert_eq!(counts.hits.load(Ordering::Relaxed), 1);
+    assert_eq!(counts.misses.load(Ordering::Relaxed), 2);
+}
+
+#[tokio::test]
+async fn open_transaction() {
+    let pool = builder().max_size(1).build(NoTls);
+
+    let client = pool.get().await.unwrap();
+    client.batch_execute("BEGIN").await.unwrap();
+    drop(client);
+
+    let mut client = pool.get().await.unwrap();
+    assert_eq!(client.transaction_status(), TransactionStatus::Idle);
+
+    let transaction = client.transaction().await.unwrap();
+    transaction.batch_execute("SELECT 1").await.unwrap();
+    drop(transaction);
+    drop(client);
+
+    let client = pool.get().await.unwrap();
+    assert_eq!(client.transaction_status(), TransactionStatus::Idle);
+}
+
+#[cfg(feature = "with-bb8-0_9")]
+#[tokio::test]
+async fn bb8() {
+    use crate::bb8::PostgresConnectionManager;
+
+    let manager = PostgresConnectionManager::new(
+        "host=localhost port=5433 user=postgres".parse().unwrap(),
+        NoTls,
+    );
+    let pool = bb8_09::Pool::builder()
+        .max_size(1)
+        .build(manager)
+        .await
+        .unwrap();
+
+    let client = pool.get().await.unwrap();
+    let pid = backend_pid(&client).await;
+    client.batch_execute("BEGIN").await.unwrap();
+    drop(client);
+
+    let client = pool.get().await.unwrap();
+    assert_eq!(backend_pid(&client).await, pid);
+    assert_eq!(client.transaction_status(), TransactionStatus::Idle);
+}
+
+#[cfg(feature = "with-deadpool-0_12")]
+#[tokio::test]
+async fn deadpool() {
+    use crate::deadpool::{Manager, Pool, RecyclingMethod};
+
+    let manager = Manager::new(
+        "host=localhost port=5433 user=postgres".parse().unwrap(),
+        NoTls,
+        RecyclingMethod::Clean,
+    );
+    let pool = Pool::builder(manager).max_size(1).build().unwrap();
+
+    let client = pool.get().await.unwrap();
+    let pid = backend_pid(&client).await;
+    client
+        .batch_execute("BEGIN; SET application_name = 'deadpool'")
+        .await
+        .unwrap();
+    drop(client);
+
+    let client = pool.get().await.unwrap();
+    assert_eq!(backend_pid(&client).await, pid);
+    assert_eq!(client.transaction_status(), TransactionStatus::Idle);
+    assert_eq!(client.parameter("application_name").as_deref(), Some(""));
+}
+
+#[tokio::test]
+async fn warm_up() {
+    let pool = builder().warm_up("SELECT $1::INT").build(NoTls);
+
+    let client = pool.get().await.unwrap();
+    let count = || async {
+        client
+            .query_one(
+                "SELECT COUNT(*) FROM pg_prepared_statements WHERE statement = 'SELECT $1::INT'",
+                &[],
+            )
+            .await
+            .unwrap()
+            .get::<_, i64>(0)
+    };
+    assert_eq!(count().await, 1);
+
+    client.prepare_cached("SELECT $1::INT").await.unwrap();
+    assert_eq!(count().await, 1);
+}
+
+#[tokio::test]
+async fn warm_up_error() {
+    let pool = builder()
+        .warm_up("SELECT * FROM warm_up_missing_table")
+        .build(NoTls);
+
+    match pool.get().await {
+        Err(PoolError::Backend(e)) => assert_eq!(e.code(), Some(&SqlState::UNDEFINED_TABLE)),
+        r => panic!("unexpected result {:?}", r),
+    }
+    assert_eq!(pool.status().in_use, 0);
+}
+
+#[tokio::test]
+async fn read_write() {
+    async fn read_only(client: &Client) -> String {
+        client
+            .query_one("SHOW transaction_read_only", &[])
+            .await
+            .unwrap()
+            .get(0)
+    }
+
+    let pool = builder().build(NoTls);
+
+    let write = pool.get_write().await.unwrap();
+    assert_eq!(read_only(&write).await, "off");
+
+    // the test server has no standbys, so reads fall back to the primary
+    let read = pool.get_read().await.unwrap();
+    assert_eq!(read_only(&read).await, "off");
+    assert_ne!(backend_pid(&read).await, backend_pid(&write).await);
+    assert_eq!(pool.status().in_use, 2);
+}
+
+#[tokio::test]
+async fn read_write_max_size() {
+    let pool = builder()
+        .max_size(2)
+        .acquire_timeout(Some(Duration::from_millis(100)))
+        .build(NoTls);
+
+    let read = pool.get_read().await.unwrap();
+    let write = pool.get_write().await.unwrap();
+    match pool.get().await {
+        Err(PoolError::Timeout) => {}
+        r => panic!("unexpected result {:?}", r),
+    }
+    match pool.get_read().await {
+        Err(PoolError::Timeout) => {}
+        r => panic!("unexpected result {:?}", r),
+    }
+    assert_eq!(
+        pool.status(),
+        Status {
+            max_size: 2,
+            in_use: 2,
+            idle: 0
+        }
+    );
+
+    // opening a new connection closes an idle connection of another role
+    drop(read);
+    drop(write);
+    let _a = pool.get().await.unwrap();
+    let _b = pool.get().await.unwrap();
+    assert_eq!(
+        pool.status(),
+        Status {
+            max_size: 2,
+            in_use: 2,
+            idle: 0
+        }
+    );
+}
+
+#[tokio::test]
+async fn shared_type_cache() {
+    let pool = builder().build(NoTls);
+
+    let first = pool.get().await.unwrap();
+    let second = pool.get().await.unwrap();
+    first
+        .batch_execute(
+            "DROP TYPE IF EXISTS pool_mood;
+             CREATE TYPE pool_mood AS ENUM ('ok', 'meh')",
+        )
+        .await
+        .unwrap();
+    assert!(pool.type_cache().is_empty());
+
+    let statement = first.prepare("SELECT $1::pool_mood").await.unwrap();
+    let type_ = statement.params()[0].clone();
+    assert_eq!(pool.type_cache().get(type_.oid()), Some(type_.clone()));
+
+    let cached = pool.type_cache().len();
+    let statement = second.prepare("SELECT $1::pool_mood").await.unwrap();
+    assert_eq!(statement.params()[0], type_);
+    assert_eq!(pool.type_cache().len(), cached);
+
+    // resetting a connection keeps the types cached for the rest of the pool
+    second.reset().await.unwrap();
+    assert_eq!(pool.type_cache().len(), cached);
+
+    second.clear_type_cache();
+    assert!(pool.type_cache().is_empty());
+}
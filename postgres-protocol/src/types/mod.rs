@@ -444,6 +444,23 @@ pub fn macaddr_from_sql(buf: &[u8]) -> Result<[u8; 6], StdBox<dyn Error + Sync +
     Ok(out)
 }
 
+/// Serializes a `MACADDR8` value.
+#[inline]
+pub fn macaddr8_to_sql(v: [u8; 8], buf: &mut BytesMut) {
+    buf.put_slice(&v);
+}
+
+/// Deserializes a `MACADDR8` value.
+#[inline]
+pub fn macaddr8_from_sql(buf: &[u8]) -> Result<[u8; 8], StdBox<dyn Error + Sync + Send>> {
+    if buf.len() != 8 {
+        return Err("invalid message length: macaddr8 length mismatch".into());
+    }
+    let mut out = [0; 8];
+    out.copy_from_slice(buf);
+    Ok(out)
+}
+
 /// Serializes a `UUID` value.
 #[inline]
 pub fn uuid_to_sql(v: [u8; 16], buf: &mut BytesMut) {
@@ -612,7 +629,7 @@ impl FallibleIterator for ArrayDimensions<'_> {
 }
 
 /// Information about a dimension of an array.
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct ArrayDimension {
     /// The length of this dimension.
     pub len: i32,
@@ -1118,3 +1135,22 @@ pub fn ltxtquery_from_sql(buf: &[u8]) -> Result<&str, StdBox<dyn Error + Sync +
         _ => Err("ltxtquery version 1 only supported".into()),
     }
 }
+
+/// Serializes a Postgres jsonpath string
+#[inline]
+pub fn jsonpath_to_sql(v: &str, buf: &mut BytesMut) {
+    // A version number is prepended to a jsonpath string per spec
+    buf.put_u8(1);
+    // Append the rest of the path
+    buf.put_slice(v.as_bytes());
+}
+
+/// Deserialize a Postgres jsonpath string
+#[inline]
+pub fn jsonpath_from_sql(buf: &[u8]) -> Result<&str, StdBox<dyn Error + Sync + Send>> {
+    match buf {
+        // Remove the version number from the front of the jsonpath per spec
+        [1u8, rest @ ..] => Ok(str::from_utf8(rest)?),
+        _ => Err("jsonpath version 1 only supported".into()),
+    }
+}
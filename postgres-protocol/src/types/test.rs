@@ -257,3 +257,34 @@ fn ltxtquery_wrong_version() {
 
     assert!(ltree_from_sql(query.as_slice()).is_err())
 }
+
+#[test]
+fn jsonpath_sql() {
+    let mut query = vec![1u8];
+    query.extend_from_slice("$.a[*] ? (@ > 1)".as_bytes());
+
+    let mut buf = BytesMut::new();
+
+    jsonpath_to_sql("$.a[*] ? (@ > 1)", &mut buf);
+
+    assert_eq!(query.as_slice(), buf.chunk());
+}
+
+#[test]
+fn jsonpath_str() {
+    let mut query = vec![1u8];
+    query.extend_from_slice("$.a[*] ? (@ > 1)".as_bytes());
+
+    assert_eq!(
+        jsonpath_from_sql(query.as_slice()).unwrap(),
+        "$.a[*] ? (@ > 1)"
+    );
+}
+
+#[test]
+fn jsonpath_wrong_version() {
+    let mut query = vec![2u8];
+    query.extend_from_slice("$.a".as_bytes());
+
+    assert!(jsonpath_from_sql(query.as_slice()).is_err())
+}
@@ -0,0 +1,157 @@
+#![cfg(all(test, check_queries))]
+
+use postgres_query_macros::query;
+use tokio_postgres::{Client, NoTls};
+
+async fn connect() -> Client {
+    let (client, connection) =
+        tokio_postgres::connect("user=postgres host=localhost port=5433", NoTls)
+            .await
+            .unwrap();
+    tokio::spawn(connection);
+    client
+}
+
+#[tokio::test]
+async fn fetch_one() {
+    let client = connect().await;
+
+    let record = query!(
+        "SELECT $1::INT4 + 1 AS sum, 'hello'::TEXT AS greeting",
+        41i32
+    )
+    .fetch_one(&client)
+    .await
+    .unwrap();
+
+    let sum: Option<i32> = record.sum;
+    let greeting: Option<String> = record.greeting;
+    assert_eq!(sum, Some(42));
+    assert_eq!(greeting.as_deref(), Some("hello"));
+}
+
+#[tokio::test]
+async fn not_null_columns() {
+    let client = connect().await;
+
+    let record = query!(
+        "SELECT oid, typname, typlen FROM pg_catalog.pg_type WHERE typname = $1",
+        "int4",
+    )
+    .fetch_one(&client)
+    .await
+    .unwrap();
+
+    let oid: u32 = record.oid;
+    let typname: String = record.typname;
+    let typlen: i16 = record.typlen;
+    assert_eq!(oid, 23);
+    assert_eq!(typname, "int4");
+    assert_eq!(typlen, 4);
+}
+
+#[tokio::test]
+async fn overrides() {
+    let client = connect().await;
+
+    let record = query!(
+        r#"SELECT 1::INT8 AS "count!", t.typname AS "name?", 'a'::TEXT AS "letter!: Box<str>"
+        FROM (SELECT NULL::NAME AS typname) t"#,
+    )
+    .fetch_one(&client)
+    .await
+    .unwrap();
+
+    let count: i64 = record.count;
+    let name: Option<String> = record.name;
+    let letter: Box<str> = record.letter;
+    assert_eq!(count, 1);
+    assert_eq!(name, None);
+    assert_eq!(&*letter, "a");
+}
+
+#[tokio::test]
+async fn arrays() {
+    let client = connect().await;
+
+    let record = query!("SELECT ARRAY[1, NULL, 3]::INT4[] AS values")
+        .fetch_one(&client)
+        .await
+        .unwrap();
+
+    assert_eq!(record.values, Some(vec![Some(1), None, Some(3)]));
+
+    let record = query!(
+        "SELECT cardinality($1::INT4[]) AS a, cardinality($2::INT4[]) AS b",
+        vec![1i32, 2],
+        &[Some(1i32), None][..],
+    )
+    .fetch_one(&client)
+    .await
+    .unwrap();
+
+    assert_eq!(record.a, Some(2));
+    assert_eq!(record.b, Some(2));
+}
+
+#[tokio::test]
+async fn fetch_all_and_optional() {
+    let client = connect().await;
+
+    let records = query!("SELECT generate_series(1, $1::INT4) AS n", 3i32)
+        .fetch_all(&client)
+        .await
+        .unwrap();
+    let values = records.into_iter().map(|r| r.n).collect::<Vec<_>>();
+    assert_eq!(values, [Some(1), Some(2), Some(3)]);
+
+    let record = query!("SELECT 1 AS n WHERE $1", false)
+        .fetch_optional(&client)
+        .await
+        .unwrap();
+    assert!(record.is_none());
+}
+
+#[tokio::test]
+async fn execute() {
+    let mut client = connect().await;
+
+    let transaction = client.transaction().await.unwrap();
+    let rows = query!("SELECT 1 AS one")
+        .execute(&transaction)
+        .await
+        .unwrap();
+    assert_eq!(rows, 1);
+}
+
+#[tokio::test]
+async fn param_types() {
+    let client = connect().await;
+
+    // the literal is inferred to be an `i64` from the parameter's type
+    let record = query!("SELECT $1::INT8 AS n, $2::TEXT AS s", 42, None::<&str>)
+        .fetch_one(&client)
+        .await
+        .unwrap();
+    assert_eq!(record.n, Some(42i64));
+    assert_eq!(record.s, None);
+
+    // the compile-time check is skipped, leaving it to `ToSql` when the query is executed
+    let record = query!("SELECT $1::INT2 AS n", 1i16 as _)
+        .fetch_one(&client)
+        .await
+        .unwrap();
+    assert_eq!(record.n, Some(1));
+}
+
+#[tokio::test]
+async fn borrowed_params() {
+    let client = connect().await;
+
+    let name = String::from("bool");
+    let record = query!("SELECT $1::TEXT AS name", &name)
+        .fetch_one(&client)
+        .await
+        .unwrap();
+    assert_eq!(record.name.as_ref(), Some(&name));
+}
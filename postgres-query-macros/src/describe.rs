@@ -0,0 +1,71 @@
+use postgres::types::Type;
+use postgres::{Client, NoTls};
+use std::env;
+use std::sync::Mutex;
+
+// Every invocation of the macro in a crate is expanded by the same compiler process, so the connection is shared
+// between them.
+static CLIENT: Mutex<Option<Client>> = Mutex::new(None);
+
+pub struct Description {
+    pub params: Vec<Type>,
+    pub columns: Vec<DescribedColumn>,
+}
+
+pub struct DescribedColumn {
+    pub name: String,
+    pub type_: Type,
+    pub not_null: bool,
+}
+
+/// Prepares a query, returning the types of its parameters and the columns of its results.
+pub fn describe(query: &str) -> Result<Description, String> {
+    let mut guard = CLIENT.lock().unwrap_or_else(|e| e.into_inner());
+    let client = match &mut *guard {
+        Some(client) if !client.is_closed() => client,
+        slot => slot.insert(connect()?),
+    };
+
+    let statement = client.prepare(query).map_err(|e| match e.as_db_error() {
+        Some(e) => match e.location(query) {
+            Some(location) => format!(
+                "error preparing query: {} at {location}\n{}",
+                e.message(),
+                location.excerpt(),
+            ),
+            None => format!("error preparing query: {}", e.message()),
+        },
+        None => format!("error preparing query: {e}"),
+    })?;
+
+    let mut columns = vec![];
+    for column in statement.columns() {
+        let not_null = match (column.table_oid(), column.column_id()) {
+            (Some(table_oid), Some(column_id)) => client
+                .query_opt(
+                    "SELECT attnotnull FROM pg_catalog.pg_attribute WHERE attrelid = $1 AND attnum = $2",
+                    &[&table_oid, &column_id],
+                )
+                .map_err(|e| format!("error looking up column `{}`: {e}", column.name()))?
+                .is_some_and(|row| row.get(0)),
+            _ => false,
+        };
+
+        columns.push(DescribedColumn {
+            name: column.name().to_string(),
+            type_: column.type_().clone(),
+            not_null,
+        });
+    }
+
+    Ok(Description {
+        params: statement.params().to_vec(),
+        columns,
+    })
+}
+
+fn connect() -> Result<Client, String> {
+    let url = env::var("DATABASE_URL")
+        .map_err(|_| "DATABASE_URL must be set to check queries at compile time".to_string())?;
+    Client::connect(&url, NoTls).map_err(|e| format!("error connecting to DATABASE_URL: {e}"))
+}
@@ -0,0 +1,222 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{Error, Expr, ExprCast, Ident, LitStr, Token, Type};
+
+use crate::describe::{self, DescribedColumn};
+use crate::types;
+
+pub struct QueryInput {
+    sql: LitStr,
+    args: Punctuated<Expr, Token![,]>,
+}
+
+impl Parse for QueryInput {
+    fn parse(input: ParseStream<'_>) -> syn::Result<QueryInput> {
+        let sql = input.parse()?;
+        let args = if input.is_empty() {
+            Punctuated::new()
+        } else {
+            input.parse::<Token![,]>()?;
+            Punctuated::parse_terminated(input)?
+        };
+
+        Ok(QueryInput { sql, args })
+    }
+}
+
+struct Field {
+    ident: Ident,
+    type_: TokenStream,
+}
+
+impl Field {
+    // Column names have the form `name`, optionally followed by `!` or `?` to override the inferred nullability and
+    // `: Type` to override the Rust type.
+    fn parse(column: &DescribedColumn, span: Span) -> Result<Field, Error> {
+        let (name, type_override) = match column.name.split_once(':') {
+            Some((name, type_)) => {
+                let type_ = syn::parse_str::<Type>(type_).map_err(|_| {
+                    Error::new(
+                        span,
+                        format!("invalid Rust type in the name of column `{}`", column.name),
+                    )
+                })?;
+                (name.trim(), Some(quote!(#type_)))
+            }
+            None => (column.name.as_str(), None),
+        };
+
+        let (name, not_null) = if let Some(name) = name.strip_suffix('!') {
+            (name, true)
+        } else if let Some(name) = name.strip_suffix('?') {
+            (name, false)
+        } else {
+            (name, column.not_null)
+        };
+
+        let ident = syn::parse_str::<Ident>(name).map_err(|_| {
+            Error::new(
+                span,
+                format!(
+                    "column `{}` is not a valid Rust identifier, rename it with `AS`",
+                    column.name
+                ),
+            )
+        })?;
+
+        let type_ = match type_override {
+            Some(type_) => type_,
+            None => types::rust_type(&column.type_).ok_or_else(|| {
+                Error::new(
+                    span,
+                    format!(
+                        "unsupported type `{}` of column `{name}`, specify a Rust type with `AS \"{name}: Type\"`",
+                        column.type_,
+                    ),
+                )
+            })?,
+        };
+        let type_ = if not_null {
+            type_
+        } else {
+            quote!(std::option::Option<#type_>)
+        };
+
+        Ok(Field { ident, type_ })
+    }
+}
+
+pub fn expand_query(input: QueryInput) -> Result<TokenStream, Error> {
+    let sql = &input.sql;
+    let description = describe::describe(&sql.value()).map_err(|e| Error::new_spanned(sql, e))?;
+
+    if description.params.len() != input.args.len() {
+        return Err(Error::new_spanned(
+            sql,
+            format!(
+                "expected {} parameters but got {}",
+                description.params.len(),
+                input.args.len(),
+            ),
+        ));
+    }
+
+    let fields = description
+        .columns
+        .iter()
+        .map(|column| Field::parse(column, sql.span()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let field_idents = fields.iter().map(|f| &f.ident).collect::<Vec<_>>();
+    let field_types = fields.iter().map(|f| &f.type_);
+    let column_indices = 0..fields.len();
+
+    let param_types = (0..input.args.len())
+        .map(|i| format_ident!("P{}", i))
+        .collect::<Vec<_>>();
+    let param_indices = (0..input.args.len())
+        .map(syn::Index::from)
+        .collect::<Vec<_>>();
+    let num_params = input.args.len();
+
+    // Each argument must be compatible with the type of its parameter, unless it's written as `value as _` or the
+    // parameter's type has no default Rust type.
+    let mut args = vec![];
+    let mut param_checks = vec![];
+    for ((arg, param), param_type) in input.args.iter().zip(&description.params).zip(&param_types) {
+        let arg = match arg {
+            Expr::Cast(ExprCast { expr, ty, .. }) if matches!(**ty, Type::Infer(_)) => &**expr,
+            arg => {
+                if let Some(expected) = types::param_type(param) {
+                    param_checks.push(quote!(#param_type: Param<#expected>));
+                }
+                arg
+            }
+        };
+        args.push(arg);
+    }
+    let param_trait = if param_checks.is_empty() {
+        quote!()
+    } else {
+        types::param_trait()
+    };
+
+    let out = quote! {
+        {
+            #[derive(Debug)]
+            #[allow(dead_code)]
+            struct Record {
+                #(
+                    pub #field_idents: #field_types,
+                )*
+            }
+
+            struct Query<#(#param_types),*>(#(#param_types),*);
+
+            #[allow(dead_code)]
+            impl<#(#param_types),*> Query<#(#param_types),*>
+            where
+                #(
+                    #param_types: tokio_postgres::types::ToSql + Sync,
+                )*
+            {
+                fn params(&self) -> [&(dyn tokio_postgres::types::ToSql + Sync); #num_params] {
+                    [#(&self.#param_indices),*]
+                }
+
+                fn record(row: &tokio_postgres::Row) -> std::result::Result<Record, tokio_postgres::Error> {
+                    std::result::Result::Ok(Record {
+                        #(
+                            #field_idents: row.try_get(#column_indices)?,
+                        )*
+                    })
+                }
+
+                pub async fn fetch_all<C>(&self, client: &C) -> std::result::Result<std::vec::Vec<Record>, tokio_postgres::Error>
+                where
+                    C: tokio_postgres::GenericClient,
+                {
+                    client.query(#sql, &self.params()).await?.iter().map(Self::record).collect()
+                }
+
+                pub async fn fetch_one<C>(&self, client: &C) -> std::result::Result<Record, tokio_postgres::Error>
+                where
+                    C: tokio_postgres::GenericClient,
+                {
+                    let row = client.query_one(#sql, &self.params()).await?;
+                    Self::record(&row)
+                }
+
+                pub async fn fetch_optional<C>(&self, client: &C) -> std::result::Result<std::option::Option<Record>, tokio_postgres::Error>
+                where
+                    C: tokio_postgres::GenericClient,
+                {
+                    let row = client.query_opt(#sql, &self.params()).await?;
+                    row.as_ref().map(Self::record).transpose()
+                }
+
+                pub async fn execute<C>(&self, client: &C) -> std::result::Result<u64, tokio_postgres::Error>
+                where
+                    C: tokio_postgres::GenericClient,
+                {
+                    client.execute(#sql, &self.params()).await
+                }
+            }
+
+            #param_trait
+
+            fn check_params<#(#param_types),*>(_: &Query<#(#param_types),*>)
+            where
+                #(#param_checks,)*
+            {
+            }
+
+            let query = Query(#(#args),*);
+            check_params(&query);
+            query
+        }
+    };
+
+    Ok(out)
+}
@@ -0,0 +1,78 @@
+//! Compile-time checked queries for `tokio-postgres`.
+//!
+//! The [`query!`] macro prepares its SQL against a live database while the crate is being compiled. Syntax errors,
+//! references to missing tables or columns, and the wrong number or types of parameters are reported as compile
+//! errors, and the types of the result columns are used to generate a record type for the rows the query returns.
+//!
+//! The database is located by the `DATABASE_URL` environment variable, which may be either a URL or a key-value
+//! connection string. The connection is made without TLS. Since the database is consulted at build time, the crate
+//! must be rebuilt for changes to the schema to be picked up.
+//!
+//! The generated code refers to `tokio_postgres`, so it must be a dependency of the crate using the macro.
+#![warn(rust_2018_idioms, clippy::all, missing_docs)]
+
+use proc_macro::TokenStream;
+use syn::parse_macro_input;
+
+mod describe;
+mod expand;
+mod types;
+
+/// Creates a query whose SQL is checked against the database at compile time.
+///
+/// The first argument is a string literal containing the query, and the remaining arguments are its parameters.
+/// Each parameter is moved into the query, so borrow values which are still needed afterwards. The macro evaluates
+/// to a value with the following methods, all of which accept any [`GenericClient`]:
+///
+/// * `fetch_all` returns a `Vec` of records.
+/// * `fetch_one` returns exactly one record, and fails if the query returns any other number of rows.
+/// * `fetch_optional` returns at most one record.
+/// * `execute` returns the number of rows modified.
+///
+/// Records are instances of a struct with a public field for each column of the result, in order. Column names
+/// must be valid Rust identifiers, so computed columns must be named with `AS`. Column types are mapped to the
+/// corresponding Rust types: `bool`, `"char"` (`i8`), the integer and floating point types, `oid` (`u32`), the text
+/// types (`String`), `bytea` (`Vec<u8>`), and arrays of those (`Vec<Option<T>>`, since elements may be null).
+///
+/// A column which comes directly from a table column declared `NOT NULL` is not wrapped in an `Option`; all others
+/// are. Postgres doesn't report when an outer join makes such a column nullable, so the inference can be overridden
+/// by ending the column name with `!` (never null) or `?` (nullable). The Rust type of a column can be specified
+/// after a colon, which is required for types the macro doesn't know about, such as enums or types provided by
+/// optional features of `postgres-types`. The specified type must implement `FromSql`.
+///
+/// Parameters of the types above are checked at compile time against the types Postgres inferred for them. A
+/// parameter may be passed by value or by reference, wrapped in an `Option`, or as the borrowed form of the type, such
+/// as `&str` for text or `&[i32]` for `int4[]`. The check can be skipped for a parameter by writing it as
+/// `value as _`, which is useful for types implementing `ToSql` for types the macro doesn't know about. Parameters of
+/// other types are only checked when the query is executed, as with the methods of `Client`.
+///
+/// # Examples
+///
+/// ```ignore
+/// use postgres_query_macros::query;
+///
+/// # async fn f(client: &tokio_postgres::Client) -> Result<(), tokio_postgres::Error> {
+/// let author = query!(
+///     r#"SELECT id, name, email AS "email?", mood AS "mood!: Mood" FROM authors WHERE id = $1"#,
+///     42,
+/// )
+/// .fetch_one(client)
+/// .await?;
+///
+/// let id: i32 = author.id;
+/// let name: String = author.name;
+/// let email: Option<String> = author.email;
+/// let mood: Mood = author.mood;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`GenericClient`]: https://docs.rs/tokio-postgres/latest/tokio_postgres/trait.GenericClient.html
+#[proc_macro]
+pub fn query(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input);
+
+    expand::expand_query(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
@@ -0,0 +1,88 @@
+use postgres::types::{Kind, Type};
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Returns the Rust type a column of the Postgres type is read as by default.
+///
+/// The elements of arrays may be null, so arrays are read as `Vec`s of `Option`s.
+pub fn rust_type(type_: &Type) -> Option<TokenStream> {
+    if let Kind::Array(member) = type_.kind() {
+        let member = rust_type(member)?;
+        return Some(quote!(std::vec::Vec<std::option::Option<#member>>));
+    }
+
+    scalar_type(type_)
+}
+
+/// Returns the Rust type parameters of the Postgres type are checked against.
+///
+/// Arrays are checked against `Vec`s of their element type; elements which are `Option`s are accepted by the
+/// generated impls.
+pub fn param_type(type_: &Type) -> Option<TokenStream> {
+    if let Kind::Array(member) = type_.kind() {
+        let member = param_type(member)?;
+        return Some(quote!(std::vec::Vec<#member>));
+    }
+
+    scalar_type(type_)
+}
+
+fn scalar_type(type_: &Type) -> Option<TokenStream> {
+    let type_ = match *type_ {
+        Type::BOOL => quote!(bool),
+        Type::CHAR => quote!(i8),
+        Type::INT2 => quote!(i16),
+        Type::INT4 => quote!(i32),
+        Type::INT8 => quote!(i64),
+        Type::OID => quote!(u32),
+        Type::FLOAT4 => quote!(f32),
+        Type::FLOAT8 => quote!(f64),
+        Type::TEXT | Type::VARCHAR | Type::BPCHAR | Type::NAME | Type::UNKNOWN => {
+            quote!(std::string::String)
+        }
+        Type::BYTEA => quote!(std::vec::Vec<u8>),
+        _ => return None,
+    };
+
+    Some(type_)
+}
+
+/// Returns the definition of the `Param` trait, which relates the Rust types accepted as parameters to the type
+/// returned by `param_type` for the parameter.
+///
+/// This mirrors the `ToSql` implementations of `postgres-types` for the types `param_type` can return.
+pub fn param_trait() -> TokenStream {
+    quote! {
+        #[diagnostic::on_unimplemented(
+            message = "`{Self}` cannot be passed as a parameter of Rust type `{T}`",
+            note = "write the parameter as `value as _` to skip the compile-time check"
+        )]
+        trait Param<T> {}
+
+        impl<T, U> Param<U> for &T where T: ?Sized + Param<U> {}
+        impl<T, U> Param<U> for std::boxed::Box<T> where T: ?Sized + Param<U> {}
+        impl<T, U> Param<U> for std::option::Option<T> where T: Param<U> {}
+
+        impl Param<bool> for bool {}
+        impl Param<i8> for i8 {}
+        impl Param<i16> for i16 {}
+        impl Param<i32> for i32 {}
+        impl Param<i64> for i64 {}
+        impl Param<u32> for u32 {}
+        impl Param<f32> for f32 {}
+        impl Param<f64> for f64 {}
+
+        impl Param<std::string::String> for std::string::String {}
+        impl Param<std::string::String> for str {}
+        impl Param<std::string::String> for std::borrow::Cow<'_, str> {}
+
+        impl Param<std::vec::Vec<u8>> for std::vec::Vec<u8> {}
+        impl Param<std::vec::Vec<u8>> for [u8] {}
+        impl<const N: usize> Param<std::vec::Vec<u8>> for [u8; N] {}
+        impl Param<std::vec::Vec<u8>> for std::borrow::Cow<'_, [u8]> {}
+
+        impl<T, U> Param<std::vec::Vec<U>> for std::vec::Vec<T> where T: Param<U> {}
+        impl<T, U> Param<std::vec::Vec<U>> for [T] where T: Param<U> {}
+        impl<T, U, const N: usize> Param<std::vec::Vec<U>> for [T; N] where T: Param<U> {}
+    }
+}
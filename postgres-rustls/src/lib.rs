@@ -0,0 +1,508 @@
+//! TLS support for `tokio-postgres` and `postgres` via `rustls`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # #[cfg(feature = "runtime")]
+//! use postgres_rustls::MakeTlsConnector;
+//! use rustls::pki_types::CertificateDer;
+//! use rustls::pki_types::pem::PemObject;
+//! use rustls::{ClientConfig, RootCertStore};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # #[cfg(feature = "runtime")] {
+//! let mut roots = RootCertStore::empty();
+//! roots.add(CertificateDer::from_pem_file("database_cert.pem")?)?;
+//! let config = ClientConfig::builder()
+//!     .with_root_certificates(roots)
+//!     .with_no_client_auth();
+//! let connector = MakeTlsConnector::new(config);
+//!
+//! let connect_future = tokio_postgres::connect(
+//!     "host=localhost user=postgres sslmode=require",
+//!     connector,
+//! );
+//! # }
+//!
+//! // ...
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ```no_run
+//! # #[cfg(feature = "runtime")]
+//! use postgres_rustls::MakeTlsConnector;
+//! use rustls::pki_types::CertificateDer;
+//! use rustls::pki_types::pem::PemObject;
+//! use rustls::{ClientConfig, RootCertStore};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # #[cfg(feature = "runtime")] {
+//! let mut roots = RootCertStore::empty();
+//! roots.add(CertificateDer::from_pem_file("database_cert.pem")?)?;
+//! let config = ClientConfig::builder()
+//!     .with_root_certificates(roots)
+//!     .with_no_client_auth();
+//! let connector = MakeTlsConnector::new(config);
+//!
+//! let client = postgres::Client::connect(
+//!     "host=localhost user=postgres sslmode=require",
+//!     connector,
+//! )?;
+//! # }
+//! # Ok(())
+//! # }
+//! ```
+#![warn(rust_2018_idioms, clippy::all, missing_docs)]
+
+use ring::digest;
+use rustls::client::WebPkiServerVerifier;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
+use rustls::sign::{CertifiedKey, SingleCertAndKey};
+use rustls::{
+    CertificateError, ClientConfig, DigitallySignedStruct, KeyLog, ProtocolVersion, RootCertStore,
+    SignatureScheme,
+};
+#[cfg(feature = "runtime")]
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs::{self, File, OpenOptions};
+use std::future::Future;
+use std::io::{self, Write};
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, BufReader, ReadBuf};
+use tokio_postgres::tls;
+#[cfg(feature = "runtime")]
+use tokio_postgres::tls::MakeTlsConnect;
+use tokio_postgres::tls::{ChannelBinding, ClientCertificate, TlsConnect, TlsInfo, Verification};
+use x509_cert::der::Decode;
+use x509_cert::spki::ObjectIdentifier;
+
+#[cfg(test)]
+mod test;
+
+#[cfg(feature = "runtime")]
+type ConfigCallback =
+    dyn Fn(&str) -> Result<Arc<ClientConfig>, Box<dyn Error + Sync + Send>> + Sync + Send;
+
+/// A `MakeTlsConnect` implementation using `rustls`.
+///
+/// Requires the `runtime` Cargo feature (enabled by default).
+#[cfg(feature = "runtime")]
+#[derive(Clone)]
+pub struct MakeTlsConnector {
+    config: Arc<ConfigCallback>,
+    server_names: HashMap<String, String>,
+}
+
+#[cfg(feature = "runtime")]
+impl MakeTlsConnector {
+    /// Creates a new connector.
+    pub fn new(config: ClientConfig) -> MakeTlsConnector {
+        let config = Arc::new(config);
+        MakeTlsConnector::with_callback(move |_| Ok(config.clone()))
+    }
+
+    /// Creates a new connector which calls a function to obtain the `ClientConfig` for each connection.
+    ///
+    /// The callback is provided the domain name. This can be used to present a client certificate which is rotated
+    /// while the process is running, by building a configuration from the current certificate each time a connection
+    /// is made. Alternatively, a configuration can be created with a `ResolvesClientCert` implementation which does
+    /// the same during each handshake.
+    pub fn with_callback<F>(f: F) -> MakeTlsConnector
+    where
+        F: Fn(&str) -> Result<Arc<ClientConfig>, Box<dyn Error + Sync + Send>>
+            + 'static
+            + Sync
+            + Send,
+    {
+        MakeTlsConnector {
+            config: Arc::new(f),
+            server_names: HashMap::new(),
+        }
+    }
+
+    /// Creates a new connector which verifies servers against the platform's trusted root certificates.
+    ///
+    /// The certificates are loaded with the `rustls-native-certs` crate, which reads them from SChannel on Windows,
+    /// the Keychain on macOS, and OpenSSL's certificate locations (respecting `SSL_CERT_FILE` and `SSL_CERT_DIR`)
+    /// elsewhere. An error is returned if no certificates could be loaded.
+    pub fn with_system_roots() -> Result<MakeTlsConnector, Box<dyn Error + Sync + Send>> {
+        let native = rustls_native_certs::load_native_certs();
+        let mut roots = RootCertStore::empty();
+        roots.add_parsable_certificates(native.certs);
+        if roots.is_empty() {
+            return Err(match native.errors.into_iter().next() {
+                Some(e) => format!("unable to load system root certificates: {e}").into(),
+                None => "no system root certificates found".into(),
+            });
+        }
+
+        let config =
+            ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+                .with_safe_default_protocol_versions()?
+                .with_root_certificates(roots)
+                .with_no_client_auth();
+        Ok(MakeTlsConnector::new(config))
+    }
+
+    /// Overrides the name used for SNI and certificate hostname verification when connecting to a host.
+    ///
+    /// By default, the name of the host in the `Config` is used. This can be used when a host is reached through an
+    /// address or load balancer whose certificate carries a different name. To connect to an IP address while
+    /// verifying a name, set both `host` and `hostaddr` in the `Config` instead.
+    pub fn set_server_name(&mut self, host: &str, server_name: &str) {
+        self.server_names
+            .insert(host.to_string(), server_name.to_string());
+    }
+}
+
+#[cfg(feature = "runtime")]
+impl<S> MakeTlsConnect<S> for MakeTlsConnector
+where
+    S: AsyncRead + AsyncWrite + Unpin + 'static + Send,
+{
+    type Stream = TlsStream<S>;
+    type TlsConnect = TlsConnector;
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn make_tls_connect(&mut self, domain: &str) -> Result<TlsConnector, Self::Error> {
+        let domain = self.server_names.get(domain).map_or(domain, String::as_str);
+        let config = (self.config)(domain)?;
+        Ok(TlsConnector::new(config, domain))
+    }
+}
+
+/// A `TlsConnect` implementation using `rustls`.
+///
+/// When the `verify-ca` or `verify-full` SSL mode is used with a root certificate file, the configuration's
+/// certificate verifier is replaced with one trusting only the certificates in that file. If no root certificate file
+/// is configured, the `verify-full` mode uses the configuration's own verifier, and the `verify-ca` mode is rejected.
+///
+/// Client certificates configured with `sslcert` replace the configuration's client authentication settings. Private
+/// keys must be unencrypted.
+pub struct TlsConnector {
+    config: Arc<ClientConfig>,
+    domain: String,
+}
+
+impl TlsConnector {
+    /// Creates a new connector configured to connect to the specified domain.
+    pub fn new(config: Arc<ClientConfig>, domain: &str) -> TlsConnector {
+        TlsConnector {
+            config,
+            domain: domain.to_string(),
+        }
+    }
+}
+
+impl<S> TlsConnect<S> for TlsConnector
+where
+    S: AsyncRead + AsyncWrite + Unpin + 'static + Send,
+{
+    type Stream = TlsStream<S>;
+    type Error = Box<dyn Error + Send + Sync>;
+    #[allow(clippy::type_complexity)]
+    type Future = Pin<Box<dyn Future<Output = Result<TlsStream<S>, Self::Error>> + Send>>;
+
+    fn set_verification(
+        &mut self,
+        verification: &Verification<'_>,
+    ) -> Result<(), Box<dyn Error + Sync + Send>> {
+        let Some(path) = verification.root_cert() else {
+            if verification.verify_hostname() {
+                return Ok(());
+            }
+            return Err("sslmode \"verify-ca\" requires a root certificate file".into());
+        };
+
+        let mut roots = RootCertStore::empty();
+        let certs = read(path, "root certificate")?;
+        for cert in CertificateDer::pem_slice_iter(&certs) {
+            roots.add(cert?)?;
+        }
+        if roots.is_empty() {
+            return Err(format!("no certificates found in {}", path.display()).into());
+        }
+
+        let provider = self.config.crypto_provider().clone();
+        let verifier =
+            WebPkiServerVerifier::builder_with_provider(Arc::new(roots), provider).build()?;
+        let verifier: Arc<dyn ServerCertVerifier> = if verification.verify_hostname() {
+            verifier
+        } else {
+            Arc::new(NoHostnameVerifier(verifier))
+        };
+        Arc::make_mut(&mut self.config)
+            .dangerous()
+            .set_certificate_verifier(verifier);
+        Ok(())
+    }
+
+    fn set_client_certificate(
+        &mut self,
+        certificate: &ClientCertificate<'_>,
+    ) -> Result<(), Box<dyn Error + Sync + Send>> {
+        let data = read(certificate.cert(), "client certificate")?;
+        let certs = if is_pem(&data) {
+            CertificateDer::pem_slice_iter(&data).collect::<Result<Vec<_>, _>>()?
+        } else {
+            vec![CertificateDer::from(data.clone())]
+        };
+        if certs.is_empty() {
+            return Err(
+                format!("no certificates found in {}", certificate.cert().display()).into(),
+            );
+        }
+
+        let data = match certificate.key() {
+            Some(path) => read(path, "private key")?,
+            None => data,
+        };
+        if data
+            .windows(b"-----BEGIN ENCRYPTED".len())
+            .any(|w| w == b"-----BEGIN ENCRYPTED")
+        {
+            return Err("encrypted private keys are not supported by rustls".into());
+        }
+        let key = if is_pem(&data) {
+            PrivateKeyDer::from_pem_slice(&data)?
+        } else {
+            PrivateKeyDer::try_from(data)?
+        };
+
+        let key = CertifiedKey::from_der(certs, key, self.config.crypto_provider())?;
+        Arc::make_mut(&mut self.config).client_auth_cert_resolver =
+            Arc::new(SingleCertAndKey::from(key));
+        Ok(())
+    }
+
+    fn connect(self, stream: S) -> Self::Future {
+        let stream = BufReader::with_capacity(8192, stream);
+        let future = async move {
+            let domain = ServerName::try_from(self.domain)?;
+            let stream = tokio_rustls::TlsConnector::from(self.config)
+                .connect(domain, stream)
+                .await?;
+
+            Ok(TlsStream(stream))
+        };
+
+        Box::pin(future)
+    }
+}
+
+/// A verifier performing the checks of the `verify-ca` SSL mode, which does not require the server's certificate to
+/// match its hostname.
+#[derive(Debug)]
+struct NoHostnameVerifier(Arc<WebPkiServerVerifier>);
+
+impl ServerCertVerifier for NoHostnameVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        // The hostname is only checked once the certificate chain has been verified.
+        match self
+            .0
+            .verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now)
+        {
+            Err(rustls::Error::InvalidCertificate(
+                CertificateError::NotValidForName | CertificateError::NotValidForNameContext { .. },
+            )) => Ok(ServerCertVerified::assertion()),
+            r => r,
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.0.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.0.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.supported_verify_schemes()
+    }
+}
+
+fn read(path: &Path, description: &str) -> Result<Vec<u8>, Box<dyn Error + Sync + Send>> {
+    fs::read(path).map_err(|e| {
+        format!(
+            "unable to read {} file {}: {}",
+            description,
+            path.display(),
+            e
+        )
+        .into()
+    })
+}
+
+fn is_pem(data: &[u8]) -> bool {
+    data.windows(10).any(|w| w == b"-----BEGIN")
+}
+
+/// The stream returned by `TlsConnector`.
+pub struct TlsStream<S>(tokio_rustls::client::TlsStream<BufReader<S>>);
+
+impl<S> AsyncRead for TlsStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_read(cx, buf)
+    }
+}
+
+impl<S> AsyncWrite for TlsStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.0).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_shutdown(cx)
+    }
+}
+
+impl<S> tls::TlsStream for TlsStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn channel_binding(&self) -> ChannelBinding {
+        let (_, session) = self.0.get_ref();
+        match session
+            .peer_certificates()
+            .and_then(|certs| certs.first())
+            .and_then(|cert| tls_server_end_point(cert))
+        {
+            Some(buf) => ChannelBinding::tls_server_end_point(buf),
+            None => ChannelBinding::none(),
+        }
+    }
+
+    fn tls_info(&self) -> TlsInfo {
+        let (_, session) = self.0.get_ref();
+        let protocol_version = session.protocol_version().map(|v| match v {
+            ProtocolVersion::TLSv1_2 => "TLSv1.2".to_string(),
+            ProtocolVersion::TLSv1_3 => "TLSv1.3".to_string(),
+            v => format!("{v:?}"),
+        });
+        let cipher_suite = session
+            .negotiated_cipher_suite()
+            .map(|s| format!("{:?}", s.suite()));
+        let peer_certificate = session
+            .peer_certificates()
+            .and_then(|certs| certs.first())
+            .map(|cert| cert.to_vec());
+        TlsInfo::new(protocol_version, cipher_suite, peer_certificate)
+    }
+}
+
+/// Computes the `tls-server-end-point` channel binding data of a certificate, as defined in RFC 5929.
+///
+/// The certificate is hashed with the digest of its signature algorithm, with MD5 and SHA-1 upgraded to SHA-256.
+/// `None` is returned for signature algorithms without an associated digest.
+fn tls_server_end_point(cert: &CertificateDer<'_>) -> Option<Vec<u8>> {
+    const MD5_WITH_RSA: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.4");
+    const SHA1_WITH_RSA: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.5");
+    const SHA256_WITH_RSA: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.11");
+    const SHA384_WITH_RSA: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.12");
+    const SHA512_WITH_RSA: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.13");
+    const ECDSA_WITH_SHA1: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.4.1");
+    const ECDSA_WITH_SHA256: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.4.3.2");
+    const ECDSA_WITH_SHA384: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.4.3.3");
+    const ECDSA_WITH_SHA512: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.4.3.4");
+
+    let parsed = x509_cert::Certificate::from_der(cert).ok()?;
+    let algorithm = match parsed.signature_algorithm.oid {
+        MD5_WITH_RSA | SHA1_WITH_RSA | SHA256_WITH_RSA | ECDSA_WITH_SHA1 | ECDSA_WITH_SHA256 => {
+            &digest::SHA256
+        }
+        SHA384_WITH_RSA | ECDSA_WITH_SHA384 => &digest::SHA384,
+        SHA512_WITH_RSA | ECDSA_WITH_SHA512 => &digest::SHA512,
+        _ => return None,
+    };
+
+    Some(digest::digest(algorithm, cert).as_ref().to_vec())
+}
+
+/// Set ALPN for `ClientConfig`
+///
+/// This is required when using `sslnegotiation=direct`
+pub fn set_postgresql_alpn(config: &mut ClientConfig) {
+    config.alpn_protocols = vec![b"postgresql".to_vec()];
+}
+
+/// Configures a `ClientConfig` to log the secrets of each TLS session to a file in the `SSLKEYLOGFILE` format.
+///
+/// This allows packet captures of connections to be decrypted by tools like Wireshark. It should only be used for
+/// debugging, since anyone who can read the file can decrypt the logged sessions. Lines are appended to the file,
+/// which is created if it doesn't exist.
+pub fn set_keylog_file<P>(config: &mut ClientConfig, path: P) -> io::Result<()>
+where
+    P: AsRef<Path>,
+{
+    let mut options = OpenOptions::new();
+    options.create(true).append(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let file = options.open(path)?;
+
+    config.key_log = Arc::new(KeyLogWriter(Mutex::new(file)));
+    Ok(())
+}
+
+#[derive(Debug)]
+struct KeyLogWriter(Mutex<File>);
+
+impl KeyLog for KeyLogWriter {
+    fn log(&self, label: &str, client_random: &[u8], secret: &[u8]) {
+        let mut line = format!("{label} ");
+        for b in client_random {
+            line.push_str(&format!("{b:02x}"));
+        }
+        line.push(' ');
+        for b in secret {
+            line.push_str(&format!("{b:02x}"));
+        }
+
+        if let Ok(mut file) = self.0.lock() {
+            let _ = writeln!(file, "{line}");
+        }
+    }
+}
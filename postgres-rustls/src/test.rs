@@ -0,0 +1,321 @@
+use futures_util::FutureExt;
+use rustls::pki_types::CertificateDer;
+use rustls::pki_types::pem::PemObject;
+use rustls::{ClientConfig, RootCertStore};
+use std::sync::Arc;
+use tokio::net::TcpStream;
+use tokio_postgres::tls::TlsConnect;
+
+#[cfg(feature = "runtime")]
+use crate::MakeTlsConnector;
+use crate::{TlsConnector, set_keylog_file, set_postgresql_alpn};
+
+fn config(roots: &[u8]) -> ClientConfig {
+    let mut store = RootCertStore::empty();
+    for cert in CertificateDer::pem_slice_iter(roots) {
+        store.add(cert.unwrap()).unwrap();
+    }
+    ClientConfig::builder()
+        .with_root_certificates(store)
+        .with_no_client_auth()
+}
+
+fn connector(roots: &[u8], domain: &str) -> TlsConnector {
+    TlsConnector::new(Arc::new(config(roots)), domain)
+}
+
+async fn smoke_test<T>(s: &str, tls: T)
+where
+    T: TlsConnect<TcpStream>,
+    T::Stream: 'static + Send,
+{
+    let stream = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+
+    let builder = s.parse::<tokio_postgres::Config>().unwrap();
+    let (client, connection) = builder.connect_raw(stream, tls).await.unwrap();
+
+    let connection = connection.map(|r| r.unwrap());
+    tokio::spawn(connection);
+
+    let stmt = client.prepare("SELECT $1::INT4").await.unwrap();
+    let rows = client.query(&stmt, &[&1i32]).await.unwrap();
+
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].get::<_, i32>(0), 1);
+}
+
+async fn connect_err<T>(s: &str, tls: T)
+where
+    T: TlsConnect<TcpStream>,
+{
+    let stream = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+    let builder = s.parse::<tokio_postgres::Config>().unwrap();
+    builder.connect_raw(stream, tls).await.err().unwrap();
+}
+
+#[tokio::test]
+async fn require() {
+    smoke_test(
+        "user=ssl_user dbname=postgres sslmode=require",
+        connector(include_bytes!("../../test/server.crt"), "localhost"),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn direct() {
+    let mut config = config(include_bytes!("../../test/server.crt"));
+    set_postgresql_alpn(&mut config);
+    smoke_test(
+        "user=ssl_user dbname=postgres sslmode=require sslnegotiation=direct",
+        TlsConnector::new(Arc::new(config), "localhost"),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn prefer() {
+    smoke_test(
+        "user=ssl_user dbname=postgres",
+        connector(include_bytes!("../../test/server.crt"), "localhost"),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn scram_user() {
+    smoke_test(
+        "user=scram_user password=password dbname=postgres sslmode=require",
+        connector(include_bytes!("../../test/server.crt"), "localhost"),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn require_channel_binding_err() {
+    connect_err(
+        "user=pass_user password=password dbname=postgres channel_binding=require",
+        connector(include_bytes!("../../test/server.crt"), "localhost"),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn require_channel_binding_ok() {
+    smoke_test(
+        "user=scram_user password=password dbname=postgres channel_binding=require",
+        connector(include_bytes!("../../test/server.crt"), "localhost"),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn untrusted() {
+    connect_err(
+        "user=ssl_user dbname=postgres sslmode=require",
+        connector(b"", "localhost"),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn verify_full() {
+    smoke_test(
+        "user=ssl_user dbname=postgres sslmode=verify-full sslrootcert=../test/server.crt",
+        connector(b"", "localhost"),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn verify_full_wrong_host() {
+    connect_err(
+        "user=ssl_user dbname=postgres sslmode=verify-full sslrootcert=../test/server.crt",
+        connector(b"", "otherhost"),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn verify_ca() {
+    smoke_test(
+        "user=ssl_user dbname=postgres sslmode=verify-ca sslrootcert=../test/server.crt",
+        connector(b"", "otherhost"),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn verify_ca_untrusted() {
+    connect_err(
+        "user=ssl_user dbname=postgres sslmode=verify-ca sslrootcert=../test/client.crt",
+        connector(include_bytes!("../../test/server.crt"), "localhost"),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn client_cert() {
+    smoke_test(
+        "user=ssl_user dbname=postgres sslmode=verify-full sslrootcert=../test/server.crt \
+         sslcert=../test/client.crt sslkey=../test/client.key",
+        connector(b"", "localhost"),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn client_cert_der() {
+    smoke_test(
+        "user=ssl_user dbname=postgres sslmode=verify-full sslrootcert=../test/server.crt \
+         sslcert=../test/client.der sslkey=../test/client.key",
+        connector(b"", "localhost"),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn client_cert_encrypted_key() {
+    connect_err(
+        "user=ssl_user dbname=postgres sslmode=verify-full sslrootcert=../test/server.crt \
+         sslcert=../test/client.crt sslkey=../test/client-encrypted.key sslpassword=password",
+        connector(b"", "localhost"),
+    )
+    .await;
+}
+
+#[tokio::test]
+#[cfg(feature = "runtime")]
+async fn runtime() {
+    let connector = MakeTlsConnector::new(config(include_bytes!("../../test/server.crt")));
+
+    let (client, connection) = tokio_postgres::connect(
+        "host=localhost port=5433 user=postgres sslmode=require",
+        connector,
+    )
+    .await
+    .unwrap();
+    let connection = connection.map(|r| r.unwrap());
+    tokio::spawn(connection);
+
+    let stmt = client.prepare("SELECT $1::INT4").await.unwrap();
+    let rows = client.query(&stmt, &[&1i32]).await.unwrap();
+
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].get::<_, i32>(0), 1);
+}
+
+#[tokio::test]
+#[cfg(feature = "runtime")]
+async fn runtime_system_roots_untrusted() {
+    let connector = MakeTlsConnector::with_system_roots().unwrap();
+
+    tokio_postgres::connect(
+        "host=localhost port=5433 user=postgres sslmode=require",
+        connector,
+    )
+    .await
+    .err()
+    .unwrap();
+}
+
+#[tokio::test]
+async fn tls_info() {
+    let tls = connector(include_bytes!("../../test/server.crt"), "localhost");
+
+    let stream = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+    let config = "user=ssl_user dbname=postgres sslmode=require"
+        .parse::<tokio_postgres::Config>()
+        .unwrap();
+    let (client, connection) = config.connect_raw(stream, tls).await.unwrap();
+
+    let info = connection.tls_info().unwrap();
+    assert_eq!(
+        info.peer_certificate(),
+        Some(&include_bytes!("../../test/server.der")[..])
+    );
+    assert_eq!(info.protocol_version(), Some("TLSv1.3"));
+    assert!(info.cipher_suite().unwrap().starts_with("TLS13_"));
+
+    let info = client.tls_info().unwrap();
+    assert!(info.peer_certificate().is_some());
+}
+
+#[tokio::test]
+#[cfg(feature = "runtime")]
+async fn runtime_rotating_client_cert() {
+    use rustls::pki_types::PrivateKeyDer;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let loads = Arc::new(AtomicUsize::new(0));
+    let connector = MakeTlsConnector::with_callback({
+        let loads = loads.clone();
+        move |_| {
+            loads.fetch_add(1, Ordering::SeqCst);
+            let certs = CertificateDer::pem_slice_iter(include_bytes!("../../test/client.crt"))
+                .collect::<Result<Vec<_>, _>>()?;
+            let key = PrivateKeyDer::from_pem_slice(include_bytes!("../../test/client.key"))?;
+            let mut store = RootCertStore::empty();
+            for cert in CertificateDer::pem_slice_iter(include_bytes!("../../test/server.crt")) {
+                store.add(cert?)?;
+            }
+            let config = ClientConfig::builder()
+                .with_root_certificates(store)
+                .with_client_auth_cert(certs, key)?;
+            Ok(Arc::new(config))
+        }
+    });
+
+    for _ in 0..2 {
+        let (client, connection) = tokio_postgres::connect(
+            "host=localhost port=5433 user=ssl_user dbname=postgres sslmode=require",
+            connector.clone(),
+        )
+        .await
+        .unwrap();
+        let connection = connection.map(|r| r.unwrap());
+        tokio::spawn(connection);
+
+        client.simple_query("SELECT 1").await.unwrap();
+    }
+    assert_eq!(loads.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+#[cfg(feature = "runtime")]
+async fn runtime_server_name() {
+    let mut connector = MakeTlsConnector::new(config(b""));
+    connector.set_server_name("db.example.com", "localhost");
+
+    let (client, connection) = tokio_postgres::connect(
+        "host=db.example.com hostaddr=127.0.0.1 port=5433 user=ssl_user dbname=postgres \
+         sslmode=verify-full sslrootcert=../test/server.crt",
+        connector,
+    )
+    .await
+    .unwrap();
+    let connection = connection.map(|r| r.unwrap());
+    tokio::spawn(connection);
+
+    client.simple_query("SELECT 1").await.unwrap();
+}
+
+#[tokio::test]
+async fn keylog_file() {
+    let path = std::env::temp_dir().join(format!("postgres-rustls-keylog-{}", std::process::id()));
+
+    let mut config = config(include_bytes!("../../test/server.crt"));
+    set_keylog_file(&mut config, &path).unwrap();
+    smoke_test(
+        "user=ssl_user dbname=postgres sslmode=require",
+        TlsConnector::new(Arc::new(config), "localhost"),
+    )
+    .await;
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(!contents.is_empty());
+    for line in contents.lines() {
+        assert_eq!(line.split(' ').count(), 3, "{line}");
+    }
+}
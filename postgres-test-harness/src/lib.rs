@@ -0,0 +1,340 @@
+//! Throwaway Postgres servers for integration tests.
+//!
+//! [`TestServer::start`] initializes a new database cluster in a temporary directory with `initdb`, starts a server
+//! for it with `pg_ctl`, and returns a [`Config`] for connecting to it. The server is stopped and its data directory
+//! deleted when the `TestServer` is dropped, so each test can have a fresh server without relying on external
+//! scripts or containers.
+//!
+//! The harness uses an existing Postgres installation rather than downloading one. The directory containing its
+//! binaries is taken from [`Builder::bin_dir`], the `PG_BIN_DIR` environment variable, or `pg_config --bindir`,
+//! falling back to looking up `initdb` and `pg_ctl` on the `PATH`. Postgres refuses to run as root, so neither can
+//! the harness; [`running_as_root`] can be used to skip tests in that case.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use postgres_test_harness::TestServer;
+//! use tokio_postgres::NoTls;
+//!
+//! # async fn f() -> Result<(), Box<dyn std::error::Error>> {
+//! let server = TestServer::start()?;
+//! let (client, connection) = server.config().connect(NoTls).await?;
+//! tokio::spawn(connection);
+//!
+//! client.batch_execute("CREATE TABLE foo (id INT)").await?;
+//! # Ok(())
+//! # }
+//! ```
+#![warn(rust_2018_idioms, clippy::all, missing_docs)]
+
+use log::{debug, warn};
+use std::env;
+use std::ffi::OsString;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::net::{Ipv4Addr, TcpListener};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tempfile::TempDir;
+use tokio_postgres::Config;
+
+#[cfg(test)]
+mod test;
+
+/// A builder for a [`TestServer`].
+#[derive(Debug, Clone)]
+pub struct Builder {
+    bin_dir: Option<PathBuf>,
+    user: String,
+    port: Option<u16>,
+    settings: Vec<(String, String)>,
+}
+
+impl Default for Builder {
+    fn default() -> Builder {
+        Builder::new()
+    }
+}
+
+impl Builder {
+    /// Creates a new builder with the default settings.
+    pub fn new() -> Builder {
+        Builder {
+            bin_dir: None,
+            user: "postgres".to_string(),
+            port: None,
+            settings: vec![],
+        }
+    }
+
+    /// Sets the directory containing the `initdb` and `pg_ctl` binaries.
+    pub fn bin_dir<P>(&mut self, bin_dir: P) -> &mut Builder
+    where
+        P: Into<PathBuf>,
+    {
+        self.bin_dir = Some(bin_dir.into());
+        self
+    }
+
+    /// Sets the name of the superuser created in the new cluster.
+    ///
+    /// Defaults to `postgres`.
+    pub fn user(&mut self, user: &str) -> &mut Builder {
+        self.user = user.to_string();
+        self
+    }
+
+    /// Sets the TCP port the server listens on.
+    ///
+    /// Defaults to an unused port chosen by the operating system.
+    pub fn port(&mut self, port: u16) -> &mut Builder {
+        self.port = Some(port);
+        self
+    }
+
+    /// Sets a server configuration parameter, such as `max_connections` or `wal_level`.
+    ///
+    /// The value is written to the cluster's `postgresql.auto.conf` as a quoted string, so it may contain any
+    /// characters.
+    pub fn setting(&mut self, name: &str, value: &str) -> &mut Builder {
+        self.settings.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Initializes a new cluster and starts a server for it.
+    ///
+    /// Returns an error of kind `PermissionDenied` if the process is running as root.
+    pub fn start(&self) -> io::Result<TestServer> {
+        for (name, _) in &self.settings {
+            if name.is_empty()
+                || !name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+            {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("invalid setting name `{name}`"),
+                ));
+            }
+        }
+
+        if running_as_root() {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "postgres can't be run as root",
+            ));
+        }
+
+        let bin_dir = match &self.bin_dir {
+            Some(bin_dir) => Some(bin_dir.clone()),
+            None => find_bin_dir(),
+        };
+        let bin = |name: &str| match &bin_dir {
+            Some(bin_dir) => bin_dir.join(name).into_os_string(),
+            None => OsString::from(name),
+        };
+
+        let dir = tempfile::Builder::new()
+            .prefix("postgres-test-harness")
+            .tempdir()?;
+        let data_dir = dir.path().join("data");
+
+        let mut initdb = Command::new(bin("initdb"));
+        initdb
+            .arg("--pgdata")
+            .arg(&data_dir)
+            .arg("--username")
+            .arg(&self.user)
+            .args(["--auth", "trust", "--encoding", "UTF8", "--no-sync"]);
+        run(initdb)?;
+
+        let port = match self.port {
+            Some(port) => port,
+            None => unused_port()?,
+        };
+        // The server also listens on a Unix socket, which is placed in the temporary directory so that the harness
+        // doesn't need access to the system-wide socket directory. Settings are written to the configuration file
+        // rather than passed on pg_ctl's command line, which is run through a shell.
+        let socket_dir = dir.path().to_string_lossy();
+        let settings = [
+            ("listen_addresses", "127.0.0.1"),
+            ("port", &*port.to_string()),
+            ("unix_socket_directories", &*socket_dir),
+            ("fsync", "off"),
+        ];
+        let mut conf = OpenOptions::new()
+            .append(true)
+            .open(data_dir.join("postgresql.auto.conf"))?;
+        for (name, value) in settings
+            .into_iter()
+            .chain(self.settings.iter().map(|(n, v)| (&**n, &**v)))
+        {
+            writeln!(conf, "{name} = {}", quote_setting(value))?;
+        }
+        drop(conf);
+
+        let mut pg_ctl = Command::new(bin("pg_ctl"));
+        pg_ctl
+            .arg("start")
+            .arg("--pgdata")
+            .arg(&data_dir)
+            .arg("--log")
+            .arg(dir.path().join("server.log"))
+            .arg("--wait");
+        run(pg_ctl)?;
+        debug!("started test server on port {port}");
+
+        let mut config = Config::new();
+        config
+            .host("127.0.0.1")
+            .port(port)
+            .user(&self.user)
+            .dbname("postgres");
+
+        Ok(TestServer {
+            pg_ctl: bin("pg_ctl"),
+            data_dir,
+            config,
+            dir: Some(dir),
+        })
+    }
+}
+
+/// A Postgres server running in a temporary data directory.
+///
+/// The server is stopped and the directory deleted when the value is dropped.
+#[derive(Debug)]
+pub struct TestServer {
+    pg_ctl: OsString,
+    data_dir: PathBuf,
+    config: Config,
+    dir: Option<TempDir>,
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        if self.dir.is_some() {
+            if let Err(e) = self.stop_inner() {
+                warn!("error stopping test server: {e}");
+            }
+        }
+    }
+}
+
+impl TestServer {
+    /// Returns a builder for a server.
+    pub fn builder() -> Builder {
+        Builder::new()
+    }
+
+    /// Initializes a new cluster and starts a server for it with the default settings.
+    pub fn start() -> io::Result<TestServer> {
+        Builder::new().start()
+    }
+
+    /// Returns a configuration which connects to the server as the superuser.
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Returns the TCP port the server listens on.
+    pub fn port(&self) -> u16 {
+        self.config.get_ports()[0]
+    }
+
+    /// Returns the server's data directory.
+    pub fn data_dir(&self) -> &Path {
+        &self.data_dir
+    }
+
+    /// Stops the server and deletes its data directory, returning any error.
+    ///
+    /// Dropping the server does the same, but only logs errors.
+    pub fn stop(mut self) -> io::Result<()> {
+        self.stop_inner()
+    }
+
+    fn stop_inner(&mut self) -> io::Result<()> {
+        let mut pg_ctl = Command::new(&self.pg_ctl);
+        pg_ctl
+            .arg("stop")
+            .arg("--pgdata")
+            .arg(&self.data_dir)
+            .args(["--mode", "immediate", "--wait"]);
+        let result = run(pg_ctl);
+
+        // Delete the directory even if the server couldn't be stopped cleanly.
+        match self.dir.take() {
+            Some(dir) => result.and(dir.close()),
+            None => result,
+        }
+    }
+}
+
+/// Determines if the process is running as root, which Postgres refuses to run as.
+pub fn running_as_root() -> bool {
+    #[cfg(unix)]
+    {
+        // SAFETY: geteuid has no preconditions and can't fail.
+        unsafe { libc::geteuid() == 0 }
+    }
+    #[cfg(not(unix))]
+    {
+        false
+    }
+}
+
+// Quotes a value for a configuration file, in which backslashes start escape sequences.
+fn quote_setting(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('\'');
+    for c in value.chars() {
+        match c {
+            '\'' => quoted.push_str("''"),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('\'');
+    quoted
+}
+
+fn find_bin_dir() -> Option<PathBuf> {
+    if let Some(bin_dir) = env::var_os("PG_BIN_DIR") {
+        return Some(bin_dir.into());
+    }
+
+    let output = Command::new("pg_config").arg("--bindir").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let bin_dir = String::from_utf8(output.stdout).ok()?;
+    Some(bin_dir.trim().into())
+}
+
+fn unused_port() -> io::Result<u16> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    Ok(listener.local_addr()?.port())
+}
+
+fn run(mut command: Command) -> io::Result<()> {
+    let output = command.output().map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!("error running {:?}: {e}", command.get_program()),
+        )
+    })?;
+
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "{:?} failed with {}: {}",
+            command.get_program(),
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim(),
+        )));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use tokio_postgres::NoTls;
+
+use super::*;
+
+// Postgres can't be run as root, which is how the test suite is run in some containers, so the tests which start a
+// server are ignored by default and run separately with `--ignored`.
+fn assert_not_root() {
+    assert!(
+        !running_as_root(),
+        "postgres can't be run as root; run the ignored tests as another user"
+    );
+}
+
+#[tokio::test]
+#[ignore = "starts a postgres server"]
+async fn start_and_stop() {
+    assert_not_root();
+
+    let server = Builder::new().start().unwrap();
+    let data_dir = server.data_dir().to_path_buf();
+    assert!(data_dir.join("PG_VERSION").exists());
+
+    let (client, connection) = server.config().connect(NoTls).await.unwrap();
+    tokio::spawn(connection);
+    let row = client.query_one("SELECT current_user", &[]).await.unwrap();
+    assert_eq!(row.get::<_, &str>(0), "postgres");
+    drop(client);
+
+    server.stop().unwrap();
+    assert!(!data_dir.exists());
+}
+
+#[tokio::test]
+#[ignore = "starts a postgres server"]
+async fn settings() {
+    assert_not_root();
+
+    let mut builder = TestServer::builder();
+    builder
+        .user("harness")
+        .setting("application_name", "harness 'test' \\ -c fsync=on");
+    let server = builder.start().unwrap();
+
+    let (client, connection) = server.config().connect(NoTls).await.unwrap();
+    tokio::spawn(connection);
+    let row = client
+        .query_one(
+            "SELECT current_user, current_setting('port'), current_setting('application_name')",
+            &[],
+        )
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, &str>(0), "harness");
+    assert_eq!(row.get::<_, &str>(1), server.port().to_string());
+    assert_eq!(row.get::<_, &str>(2), "harness 'test' \\ -c fsync=on");
+}
+
+#[test]
+fn invalid_setting_name() {
+    let err = TestServer::builder()
+        .setting("port = 1\nfsync", "off")
+        .start()
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn quoting() {
+    assert_eq!(quote_setting("plain"), "'plain'");
+    assert_eq!(quote_setting("it's"), "'it''s'");
+    assert_eq!(quote_setting("a\\b\nc"), "'a\\\\b\\nc'");
+}
+
+#[test]
+#[ignore = "starts a postgres server"]
+fn drop_removes_data_dir() {
+    assert_not_root();
+
+    let server = Builder::new().start().unwrap();
+    let data_dir = server.data_dir().to_path_buf();
+
+    drop(server);
+    assert!(!data_dir.exists());
+}
@@ -0,0 +1,641 @@
+//! Generates Rust types for the user-defined types of a Postgres database.
+//!
+//! The generated code declares an enum for each Postgres enum, a struct for each composite type and a newtype for
+//! each domain, deriving `ToSql` and `FromSql` with the `derive` feature of `postgres-types`. Names are converted to
+//! Rust conventions, and `#[postgres(...)]` attributes are added where the derived names would otherwise differ from
+//! those in the database, preferring a single `rename_all` rule over per-item names when one fits.
+//!
+//! Composite fields are always wrapped in `Option`, since Postgres allows any field of a composite value to be null.
+//! Types provided by optional features of `postgres-types`, such as `timestamptz`, `numeric`, `uuid` or `jsonb`, have
+//! no default Rust type, so the type to use for them must be specified with [`Generator::type_override`]. Composites
+//! and domains using a type with no Rust type are skipped, along with the types using them, and reported as
+//! warnings.
+//!
+//! The crate also provides a `postgres-typegen` binary which prints the generated code:
+//!
+//! ```text
+//! postgres-typegen "host=localhost user=postgres" --schema public --type uuid=uuid::Uuid --output src/db_types.rs
+//! ```
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use postgres::{Client, NoTls};
+//! use postgres_typegen::Generator;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut client = Client::connect("host=localhost user=postgres", NoTls)?;
+//! let generated = Generator::new()
+//!     .schema("public")
+//!     .type_override("timestamptz", "chrono::DateTime<chrono::Utc>")
+//!     .generate(&mut client)?;
+//! for warning in generated.warnings() {
+//!     eprintln!("warning: {warning}");
+//! }
+//! std::fs::write("src/db_types.rs", generated.code())?;
+//! # Ok(())
+//! # }
+//! ```
+#![warn(rust_2018_idioms, clippy::all, missing_docs)]
+
+use heck::{
+    ToKebabCase, ToLowerCamelCase, ToShoutyKebabCase, ToShoutySnakeCase, ToSnakeCase, ToTrainCase,
+    ToUpperCamelCase,
+};
+use postgres::GenericClient;
+use postgres::types::{Kind, Oid, Type};
+use std::collections::{HashMap, HashSet};
+use std::error;
+use std::fmt::{self, Write};
+
+#[cfg(test)]
+mod test;
+
+type RenameRule = (&'static str, fn(&str) -> String);
+
+// The rules supported by `#[postgres(rename_all = "...")]`, in the order they're tried.
+const RENAME_RULES: &[RenameRule] = &[
+    ("snake_case", |s| s.to_snake_case()),
+    ("lowercase", |s| s.to_lowercase()),
+    ("UPPERCASE", |s| s.to_uppercase()),
+    ("SCREAMING_SNAKE_CASE", |s| s.to_shouty_snake_case()),
+    ("kebab-case", |s| s.to_kebab_case()),
+    ("SCREAMING-KEBAB-CASE", |s| s.to_shouty_kebab_case()),
+    ("camelCase", |s| s.to_lower_camel_case()),
+    ("PascalCase", |s| s.to_upper_camel_case()),
+    ("Train-Case", |s| s.to_train_case()),
+];
+
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let",
+    "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+    "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+    "virtual", "where", "while", "yield",
+];
+
+/// An error generating types.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum GenerateError {
+    /// An error querying the database catalog.
+    Backend(postgres::Error),
+    /// A type used by a composite field or domain has no corresponding Rust type.
+    ///
+    /// This is reported as a warning by [`Generated::warnings`], and the composite or domain is skipped.
+    UnsupportedType {
+        /// The name of the unsupported type.
+        type_name: String,
+        /// The composite field or domain using it.
+        used_by: String,
+    },
+    /// Two Postgres names were converted to the same Rust name.
+    NameConflict {
+        /// The conflicting Rust name.
+        rust_name: String,
+    },
+}
+
+impl fmt::Display for GenerateError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GenerateError::Backend(_) => fmt.write_str("error querying the database catalog"),
+            GenerateError::UnsupportedType { type_name, used_by } => {
+                write!(fmt, "unsupported type `{type_name}` used by {used_by}")
+            }
+            GenerateError::NameConflict { rust_name } => {
+                write!(fmt, "multiple names map to the Rust name `{rust_name}`")
+            }
+        }
+    }
+}
+
+impl error::Error for GenerateError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            GenerateError::Backend(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<postgres::Error> for GenerateError {
+    fn from(e: postgres::Error) -> GenerateError {
+        GenerateError::Backend(e)
+    }
+}
+
+/// The output of a [`Generator`].
+#[derive(Debug)]
+pub struct Generated {
+    code: String,
+    warnings: Vec<GenerateError>,
+}
+
+impl Generated {
+    /// Returns the source of the generated module.
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    /// Consumes the output, returning the source of the generated module.
+    pub fn into_code(self) -> String {
+        self.code
+    }
+
+    /// Returns the reasons composites and domains were skipped.
+    pub fn warnings(&self) -> &[GenerateError] {
+        &self.warnings
+    }
+}
+
+/// A generator of Rust types for the user-defined types of a database.
+#[derive(Debug, Clone, Default)]
+pub struct Generator {
+    schemas: Vec<String>,
+    type_overrides: Vec<(String, String)>,
+}
+
+impl Generator {
+    /// Creates a new generator.
+    pub fn new() -> Generator {
+        Generator::default()
+    }
+
+    /// Adds a schema whose types will be generated.
+    ///
+    /// Defaults to `public` if no schemas are specified.
+    pub fn schema(&mut self, schema: &str) -> &mut Generator {
+        self.schemas.push(schema.to_string());
+        self
+    }
+
+    /// Sets the Rust type used for a Postgres type, by the type's name.
+    ///
+    /// The override applies to the type in any schema and to arrays of it, and takes precedence over the default Rust
+    /// type of a built-in type and over types generated for the schemas. The Rust type is written to the generated
+    /// code as is, so it should be a path which resolves in the module the code is included in, and must implement
+    /// `ToSql` and `FromSql`.
+    pub fn type_override(&mut self, postgres_type: &str, rust_type: &str) -> &mut Generator {
+        self.type_overrides
+            .push((postgres_type.to_string(), rust_type.to_string()));
+        self
+    }
+
+    /// Queries the database catalog and returns the source of a Rust module declaring its types.
+    pub fn generate<C>(&self, client: &mut C) -> Result<Generated, GenerateError>
+    where
+        C: GenericClient,
+    {
+        let schemas = if self.schemas.is_empty() {
+            vec!["public".to_string()]
+        } else {
+            self.schemas.clone()
+        };
+
+        let mut catalog = Catalog::load(client, &schemas, &self.type_overrides)?;
+        let warnings = catalog.remove_unsupported();
+        let mut out = String::new();
+        writeln!(
+            out,
+            "// Generated by postgres-typegen. Do not edit by hand."
+        )
+        .unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "use postgres_types::{{FromSql, ToSql}};").unwrap();
+
+        for enum_ in &catalog.enums {
+            writeln!(out).unwrap();
+            write_enum(&mut out, enum_)?;
+        }
+        for composite in &catalog.composites {
+            writeln!(out).unwrap();
+            write_composite(&mut out, &catalog, composite)?;
+        }
+        for domain in &catalog.domains {
+            writeln!(out).unwrap();
+            write_domain(&mut out, &catalog, domain)?;
+        }
+
+        Ok(Generated {
+            code: out,
+            warnings,
+        })
+    }
+}
+
+struct TypeInfo {
+    oid: Oid,
+    name: String,
+    array_oid: Oid,
+}
+
+struct EnumInfo {
+    info: TypeInfo,
+    labels: Vec<String>,
+}
+
+struct CompositeInfo {
+    info: TypeInfo,
+    // (name, type OID, formatted type name)
+    fields: Vec<(String, Oid, String)>,
+}
+
+struct DomainInfo {
+    info: TypeInfo,
+    base_oid: Oid,
+    base_name: String,
+}
+
+struct Catalog {
+    enums: Vec<EnumInfo>,
+    composites: Vec<CompositeInfo>,
+    domains: Vec<DomainInfo>,
+    // Rust names of the generated types and arrays of them, keyed by OID.
+    rust_types: HashMap<Oid, String>,
+}
+
+impl Catalog {
+    fn load<C>(
+        client: &mut C,
+        schemas: &[String],
+        type_overrides: &[(String, String)],
+    ) -> Result<Catalog, GenerateError>
+    where
+        C: GenericClient,
+    {
+        let type_info = |row: &postgres::Row| TypeInfo {
+            oid: row.get(0),
+            name: row.get(1),
+            array_oid: row.get(2),
+        };
+
+        let enums = client
+            .query(
+                "SELECT t.oid, t.typname::TEXT, t.typarray,
+                    array_agg(e.enumlabel::TEXT ORDER BY e.enumsortorder)
+                FROM pg_catalog.pg_type t
+                JOIN pg_catalog.pg_namespace n ON n.oid = t.typnamespace
+                JOIN pg_catalog.pg_enum e ON e.enumtypid = t.oid
+                WHERE n.nspname = ANY($1)
+                GROUP BY t.oid, t.typname, t.typarray
+                ORDER BY t.typname, t.oid",
+                &[&schemas],
+            )?
+            .iter()
+            .map(|row| EnumInfo {
+                info: type_info(row),
+                labels: row.get(3),
+            })
+            .collect::<Vec<_>>();
+
+        let mut composites = vec![];
+        for row in client.query(
+            "SELECT t.oid, t.typname::TEXT, t.typarray, t.typrelid
+            FROM pg_catalog.pg_type t
+            JOIN pg_catalog.pg_namespace n ON n.oid = t.typnamespace
+            JOIN pg_catalog.pg_class c ON c.oid = t.typrelid
+            WHERE t.typtype = 'c' AND c.relkind = 'c' AND n.nspname = ANY($1)
+            ORDER BY t.typname, t.oid",
+            &[&schemas],
+        )? {
+            let relid: Oid = row.get(3);
+            let fields = client
+                .query(
+                    "SELECT attname::TEXT, atttypid, pg_catalog.format_type(atttypid, atttypmod)
+                    FROM pg_catalog.pg_attribute
+                    WHERE attrelid = $1 AND attnum > 0 AND NOT attisdropped
+                    ORDER BY attnum",
+                    &[&relid],
+                )?
+                .iter()
+                .map(|row| (row.get(0), row.get(1), row.get(2)))
+                .collect();
+
+            composites.push(CompositeInfo {
+                info: type_info(&row),
+                fields,
+            });
+        }
+
+        let domains = client
+            .query(
+                "SELECT t.oid, t.typname::TEXT, t.typarray, t.typbasetype,
+                    pg_catalog.format_type(t.typbasetype, t.typtypmod)
+                FROM pg_catalog.pg_type t
+                JOIN pg_catalog.pg_namespace n ON n.oid = t.typnamespace
+                WHERE t.typtype = 'd' AND n.nspname = ANY($1)
+                ORDER BY t.typname, t.oid",
+                &[&schemas],
+            )?
+            .iter()
+            .map(|row| DomainInfo {
+                info: type_info(row),
+                base_oid: row.get(3),
+                base_name: row.get(4),
+            })
+            .collect::<Vec<_>>();
+
+        let mut rust_types = HashMap::new();
+        let mut names = HashSet::new();
+        let infos = enums
+            .iter()
+            .map(|e| &e.info)
+            .chain(composites.iter().map(|c| &c.info))
+            .chain(domains.iter().map(|d| &d.info));
+        for info in infos {
+            let rust_name = type_ident(&info.name);
+            if !names.insert(rust_name.clone()) {
+                return Err(GenerateError::NameConflict { rust_name });
+            }
+            rust_types.insert(info.array_oid, format!("Vec<{rust_name}>"));
+            rust_types.insert(info.oid, rust_name);
+        }
+
+        if !type_overrides.is_empty() {
+            let names = type_overrides
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>();
+            for row in client.query(
+                "SELECT oid, typname::TEXT, typarray FROM pg_catalog.pg_type WHERE typname = ANY($1)",
+                &[&names],
+            )? {
+                let name: String = row.get(1);
+                // later overrides of the same name take precedence
+                let (_, rust_type) = type_overrides
+                    .iter()
+                    .rev()
+                    .find(|(n, _)| *n == name)
+                    .unwrap();
+                let array_oid: Oid = row.get(2);
+                if array_oid != 0 {
+                    rust_types.insert(array_oid, format!("Vec<{rust_type}>"));
+                }
+                rust_types.insert(row.get(0), rust_type.clone());
+            }
+        }
+
+        Ok(Catalog {
+            enums,
+            composites,
+            domains,
+            rust_types,
+        })
+    }
+
+    // Removes the composites and domains using types with no Rust type, along with the types using those in turn.
+    fn remove_unsupported(&mut self) -> Vec<GenerateError> {
+        let mut warnings = vec![];
+        loop {
+            let len = warnings.len();
+            let rust_types = &mut self.rust_types;
+
+            self.composites.retain(|composite| {
+                let unsupported = composite.fields.iter().find_map(|(name, oid, type_name)| {
+                    rust_type(rust_types, *oid, type_name, || {
+                        format!("field `{name}` of composite `{}`", composite.info.name)
+                    })
+                    .err()
+                });
+                match unsupported {
+                    Some(e) => {
+                        rust_types.remove(&composite.info.oid);
+                        rust_types.remove(&composite.info.array_oid);
+                        warnings.push(e);
+                        false
+                    }
+                    None => true,
+                }
+            });
+
+            self.domains.retain(|domain| {
+                match rust_type(rust_types, domain.base_oid, &domain.base_name, || {
+                    format!("domain `{}`", domain.info.name)
+                }) {
+                    Ok(_) => true,
+                    Err(e) => {
+                        rust_types.remove(&domain.info.oid);
+                        rust_types.remove(&domain.info.array_oid);
+                        warnings.push(e);
+                        false
+                    }
+                }
+            });
+
+            if warnings.len() == len {
+                return warnings;
+            }
+        }
+    }
+
+    fn rust_type(
+        &self,
+        oid: Oid,
+        type_name: &str,
+        used_by: impl FnOnce() -> String,
+    ) -> Result<String, GenerateError> {
+        rust_type(&self.rust_types, oid, type_name, used_by)
+    }
+}
+
+fn rust_type(
+    rust_types: &HashMap<Oid, String>,
+    oid: Oid,
+    type_name: &str,
+    used_by: impl FnOnce() -> String,
+) -> Result<String, GenerateError> {
+    if let Some(rust_type) = rust_types.get(&oid) {
+        return Ok(rust_type.clone());
+    }
+
+    Type::from_oid(oid)
+        .and_then(|type_| builtin_rust_type(&type_))
+        .ok_or_else(|| GenerateError::UnsupportedType {
+            type_name: type_name.to_string(),
+            used_by: used_by(),
+        })
+}
+
+fn builtin_rust_type(type_: &Type) -> Option<String> {
+    if let Kind::Array(member) = type_.kind() {
+        return builtin_rust_type(member).map(|member| format!("Vec<{member}>"));
+    }
+
+    let rust_type = match *type_ {
+        Type::BOOL => "bool",
+        Type::CHAR => "i8",
+        Type::INT2 => "i16",
+        Type::INT4 => "i32",
+        Type::INT8 => "i64",
+        Type::OID => "u32",
+        Type::FLOAT4 => "f32",
+        Type::FLOAT8 => "f64",
+        Type::TEXT | Type::VARCHAR | Type::BPCHAR | Type::NAME => "String",
+        Type::BYTEA => "Vec<u8>",
+        Type::TIMESTAMP | Type::TIMESTAMPTZ => "std::time::SystemTime",
+        Type::INET => "std::net::IpAddr",
+        _ => return None,
+    };
+
+    Some(rust_type.to_string())
+}
+
+fn type_ident(name: &str) -> String {
+    sanitize(name.to_upper_camel_case(), "Empty")
+}
+
+fn variant_ident(label: &str) -> String {
+    sanitize(label.to_upper_camel_case(), "Empty")
+}
+
+fn field_ident(name: &str) -> String {
+    let ident = sanitize(name.to_snake_case(), "empty");
+    match ident.as_str() {
+        "self" | "super" | "crate" => format!("{ident}_"),
+        _ if KEYWORDS.contains(&&*ident) => format!("r#{ident}"),
+        _ => ident,
+    }
+}
+
+fn sanitize(ident: String, empty: &str) -> String {
+    if ident.is_empty() {
+        empty.to_string()
+    } else if ident.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{ident}")
+    } else {
+        ident
+    }
+}
+
+enum Naming {
+    Exact,
+    RenameAll(&'static str),
+    Individual,
+}
+
+// Picks how the derived names of a set of (Rust identifier, Postgres name) pairs should be overridden.
+fn naming(names: &[(String, String)]) -> Naming {
+    let unraw = |ident: &str| ident.strip_prefix("r#").unwrap_or(ident).to_string();
+
+    if names.iter().all(|(ident, name)| unraw(ident) == *name) {
+        return Naming::Exact;
+    }
+
+    RENAME_RULES
+        .iter()
+        .find(|(_, rule)| {
+            names
+                .iter()
+                .all(|(ident, name)| rule(&unraw(ident)) == *name)
+        })
+        .map_or(Naming::Individual, |(rule, _)| Naming::RenameAll(rule))
+}
+
+fn check_conflicts(names: &[(String, String)]) -> Result<(), GenerateError> {
+    for (i, (ident, _)) in names.iter().enumerate() {
+        if names[..i].iter().any(|(other, _)| other == ident) {
+            return Err(GenerateError::NameConflict {
+                rust_name: ident.clone(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+fn write_container_attrs(out: &mut String, info: &TypeInfo, naming: &Naming) {
+    let rust_name = type_ident(&info.name);
+    let mut attrs = vec![];
+    if rust_name != info.name {
+        attrs.push(format!("name = {:?}", info.name));
+    }
+    if let Naming::RenameAll(rule) = naming {
+        attrs.push(format!("rename_all = {rule:?}"));
+    }
+    if !attrs.is_empty() {
+        writeln!(out, "#[postgres({})]", attrs.join(", ")).unwrap();
+    }
+}
+
+fn write_enum(out: &mut String, enum_: &EnumInfo) -> Result<(), GenerateError> {
+    let variants = enum_
+        .labels
+        .iter()
+        .map(|label| (variant_ident(label), label.clone()))
+        .collect::<Vec<_>>();
+    check_conflicts(&variants)?;
+    let naming = naming(&variants);
+
+    writeln!(
+        out,
+        "#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ToSql, FromSql)]"
+    )
+    .unwrap();
+    write_container_attrs(out, &enum_.info, &naming);
+    writeln!(out, "pub enum {} {{", type_ident(&enum_.info.name)).unwrap();
+    for (ident, label) in &variants {
+        if let Naming::Individual = naming {
+            if *ident != *label {
+                writeln!(out, "    #[postgres(name = {label:?})]").unwrap();
+            }
+        }
+        writeln!(out, "    {ident},").unwrap();
+    }
+    writeln!(out, "}}").unwrap();
+
+    Ok(())
+}
+
+fn write_composite(
+    out: &mut String,
+    catalog: &Catalog,
+    composite: &CompositeInfo,
+) -> Result<(), GenerateError> {
+    let names = composite
+        .fields
+        .iter()
+        .map(|(name, _, _)| (field_ident(name), name.clone()))
+        .collect::<Vec<_>>();
+    check_conflicts(&names)?;
+    let naming = naming(&names);
+
+    writeln!(out, "#[derive(Debug, Clone, PartialEq, ToSql, FromSql)]").unwrap();
+    write_container_attrs(out, &composite.info, &naming);
+    writeln!(out, "pub struct {} {{", type_ident(&composite.info.name)).unwrap();
+    for ((ident, name), (_, oid, type_name)) in names.iter().zip(&composite.fields) {
+        let rust_type = catalog.rust_type(*oid, type_name, || {
+            format!("field `{name}` of composite `{}`", composite.info.name)
+        })?;
+        if let Naming::Individual = naming {
+            if ident.strip_prefix("r#").unwrap_or(ident) != name {
+                writeln!(out, "    #[postgres(name = {name:?})]").unwrap();
+            }
+        }
+        writeln!(out, "    pub {ident}: Option<{rust_type}>,").unwrap();
+    }
+    writeln!(out, "}}").unwrap();
+
+    Ok(())
+}
+
+fn write_domain(
+    out: &mut String,
+    catalog: &Catalog,
+    domain: &DomainInfo,
+) -> Result<(), GenerateError> {
+    let rust_type = catalog.rust_type(domain.base_oid, &domain.base_name, || {
+        format!("domain `{}`", domain.info.name)
+    })?;
+
+    writeln!(out, "#[derive(Debug, Clone, PartialEq, ToSql, FromSql)]").unwrap();
+    write_container_attrs(out, &domain.info, &Naming::Exact);
+    writeln!(
+        out,
+        "pub struct {}(pub {rust_type});",
+        type_ident(&domain.info.name)
+    )
+    .unwrap();
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use postgres::{Client, NoTls};
+use postgres_typegen::Generator;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::process;
+
+const USAGE: &str = "usage: postgres-typegen <connection string> [--schema <name>]... \
+     [--type <postgres type>=<rust type>]... [--output <file>]";
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("error: {e}");
+        let mut source = e.source();
+        while let Some(e) = source {
+            eprintln!("caused by: {e}");
+            source = e.source();
+        }
+        process::exit(1);
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let mut args = env::args().skip(1);
+    let mut config = None;
+    let mut output = None;
+    let mut generator = Generator::new();
+
+    while let Some(arg) = args.next() {
+        match &*arg {
+            "--schema" => {
+                generator.schema(&args.next().ok_or(USAGE)?);
+            }
+            "--type" => {
+                let arg = args.next().ok_or(USAGE)?;
+                let (postgres_type, rust_type) = arg.split_once('=').ok_or(USAGE)?;
+                generator.type_override(postgres_type, rust_type);
+            }
+            "--output" => output = Some(args.next().ok_or(USAGE)?),
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Ok(());
+            }
+            _ if config.is_none() && !arg.starts_with("--") => config = Some(arg),
+            _ => return Err(USAGE.into()),
+        }
+    }
+
+    let config = config.ok_or(USAGE)?;
+    let mut client = Client::connect(&config, NoTls)?;
+    let generated = generator.generate(&mut client)?;
+    for warning in generated.warnings() {
+        eprintln!("warning: {warning}");
+    }
+
+    match output {
+        Some(path) => fs::write(path, generated.code())?,
+        None => print!("{}", generated.code()),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,182 @@
+use postgres::{Client, NoTls};
+
+use super::*;
+
+fn connect() -> Client {
+    Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap()
+}
+
+#[test]
+fn generate() {
+    let mut client = connect();
+    let mut transaction = client.transaction().unwrap();
+    transaction
+        .batch_execute(
+            "CREATE SCHEMA typegen;
+            CREATE TYPE typegen.mood AS ENUM ('sad', 'ok', 'happy');
+            CREATE TYPE typegen.\"Color\" AS ENUM ('RED', 'Dark Blue');
+            CREATE TYPE typegen.inventory_item AS (
+                name TEXT,
+                supplier_id INT,
+                moods typegen.mood[],
+                type TEXT
+            );
+            CREATE TYPE typegen.\"Point\" AS (\"xCoord\" FLOAT8, \"yCoord\" FLOAT8);
+            CREATE DOMAIN typegen.session_id AS BYTEA CHECK (octet_length(VALUE) = 16);",
+        )
+        .unwrap();
+
+    let generated = Generator::new()
+        .schema("typegen")
+        .generate(&mut transaction)
+        .unwrap();
+
+    assert!(generated.warnings().is_empty());
+    assert_eq!(
+        generated.code(),
+        r#"// Generated by postgres-typegen. Do not edit by hand.
+
+use postgres_types::{FromSql, ToSql};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ToSql, FromSql)]
+pub enum Color {
+    #[postgres(name = "RED")]
+    Red,
+    #[postgres(name = "Dark Blue")]
+    DarkBlue,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ToSql, FromSql)]
+#[postgres(name = "mood", rename_all = "snake_case")]
+pub enum Mood {
+    Sad,
+    Ok,
+    Happy,
+}
+
+#[derive(Debug, Clone, PartialEq, ToSql, FromSql)]
+#[postgres(rename_all = "camelCase")]
+pub struct Point {
+    pub x_coord: Option<f64>,
+    pub y_coord: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, ToSql, FromSql)]
+#[postgres(name = "inventory_item")]
+pub struct InventoryItem {
+    pub name: Option<String>,
+    pub supplier_id: Option<i32>,
+    pub moods: Option<Vec<Mood>>,
+    pub r#type: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, ToSql, FromSql)]
+#[postgres(name = "session_id")]
+pub struct SessionId(pub Vec<u8>);
+"#
+    );
+}
+
+#[test]
+fn unsupported_type() {
+    let mut client = connect();
+    let mut transaction = client.transaction().unwrap();
+    transaction
+        .batch_execute(
+            "CREATE SCHEMA typegen;
+            CREATE TYPE typegen.price AS (amount NUMERIC(10, 2));
+            CREATE TYPE typegen.item AS (name TEXT, price typegen.price);
+            CREATE DOMAIN typegen.prices AS typegen.price[];
+            CREATE TYPE typegen.event AS (at TIMESTAMPTZ);",
+        )
+        .unwrap();
+
+    let generated = Generator::new()
+        .schema("typegen")
+        .generate(&mut transaction)
+        .unwrap();
+    let warnings = generated
+        .warnings()
+        .iter()
+        .map(|w| w.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        warnings,
+        [
+            "unsupported type `numeric(10,2)` used by field `amount` of composite `price`",
+            "unsupported type `typegen.price[]` used by domain `prices`",
+            "unsupported type `typegen.price` used by field `price` of composite `item`",
+        ]
+    );
+    assert_eq!(
+        generated.code(),
+        r#"// Generated by postgres-typegen. Do not edit by hand.
+
+use postgres_types::{FromSql, ToSql};
+
+#[derive(Debug, Clone, PartialEq, ToSql, FromSql)]
+#[postgres(name = "event")]
+pub struct Event {
+    pub at: Option<std::time::SystemTime>,
+}
+"#
+    );
+}
+
+#[test]
+fn type_override() {
+    let mut client = connect();
+    let mut transaction = client.transaction().unwrap();
+    transaction
+        .batch_execute(
+            "CREATE SCHEMA typegen;
+            CREATE TYPE typegen.price AS (amount NUMERIC(10, 2), history NUMERIC[]);
+            CREATE DOMAIN typegen.event_time AS TIMESTAMPTZ;",
+        )
+        .unwrap();
+
+    let generated = Generator::new()
+        .schema("typegen")
+        .type_override("numeric", "rust_decimal::Decimal")
+        .type_override("timestamptz", "time::OffsetDateTime")
+        .generate(&mut transaction)
+        .unwrap();
+
+    assert!(generated.warnings().is_empty());
+    assert_eq!(
+        generated.code(),
+        r#"// Generated by postgres-typegen. Do not edit by hand.
+
+use postgres_types::{FromSql, ToSql};
+
+#[derive(Debug, Clone, PartialEq, ToSql, FromSql)]
+#[postgres(name = "price")]
+pub struct Price {
+    pub amount: Option<rust_decimal::Decimal>,
+    pub history: Option<Vec<rust_decimal::Decimal>>,
+}
+
+#[derive(Debug, Clone, PartialEq, ToSql, FromSql)]
+#[postgres(name = "event_time")]
+pub struct EventTime(pub time::OffsetDateTime);
+"#
+    );
+}
+
+#[test]
+fn name_conflict() {
+    let mut client = connect();
+    let mut transaction = client.transaction().unwrap();
+    transaction
+        .batch_execute(
+            "CREATE SCHEMA typegen;
+            CREATE TYPE typegen.status AS ENUM ('in_progress', 'in-progress');",
+        )
+        .unwrap();
+
+    let err = Generator::new()
+        .schema("typegen")
+        .generate(&mut transaction)
+        .unwrap_err();
+    assert!(matches!(err, GenerateError::NameConflict { rust_name } if rust_name == "InProgress"));
+}
@@ -0,0 +1,221 @@
+//! Access control list types.
+
+use std::error::Error;
+use std::fmt;
+use std::ops::{BitOr, BitOrAssign};
+use std::str::FromStr;
+
+// The privilege letters of the text format, in bit order.
+const PRIVILEGE_CHARS: &[u8] = b"arwdDxtXUCTcsAm";
+
+/// A set of privileges in an `AclItem`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct AclPrivileges(u16);
+
+impl AclPrivileges {
+    /// The `INSERT` privilege, `a`.
+    pub const INSERT: AclPrivileges = AclPrivileges(1 << 0);
+    /// The `SELECT` privilege, `r`.
+    pub const SELECT: AclPrivileges = AclPrivileges(1 << 1);
+    /// The `UPDATE` privilege, `w`.
+    pub const UPDATE: AclPrivileges = AclPrivileges(1 << 2);
+    /// The `DELETE` privilege, `d`.
+    pub const DELETE: AclPrivileges = AclPrivileges(1 << 3);
+    /// The `TRUNCATE` privilege, `D`.
+    pub const TRUNCATE: AclPrivileges = AclPrivileges(1 << 4);
+    /// The `REFERENCES` privilege, `x`.
+    pub const REFERENCES: AclPrivileges = AclPrivileges(1 << 5);
+    /// The `TRIGGER` privilege, `t`.
+    pub const TRIGGER: AclPrivileges = AclPrivileges(1 << 6);
+    /// The `EXECUTE` privilege, `X`.
+    pub const EXECUTE: AclPrivileges = AclPrivileges(1 << 7);
+    /// The `USAGE` privilege, `U`.
+    pub const USAGE: AclPrivileges = AclPrivileges(1 << 8);
+    /// The `CREATE` privilege, `C`.
+    pub const CREATE: AclPrivileges = AclPrivileges(1 << 9);
+    /// The `TEMPORARY` privilege, `T`.
+    pub const TEMPORARY: AclPrivileges = AclPrivileges(1 << 10);
+    /// The `CONNECT` privilege, `c`.
+    pub const CONNECT: AclPrivileges = AclPrivileges(1 << 11);
+    /// The `SET` privilege on configuration parameters, `s`.
+    pub const SET: AclPrivileges = AclPrivileges(1 << 12);
+    /// The `ALTER SYSTEM` privilege on configuration parameters, `A`.
+    pub const ALTER_SYSTEM: AclPrivileges = AclPrivileges(1 << 13);
+    /// The `MAINTAIN` privilege, `m`.
+    pub const MAINTAIN: AclPrivileges = AclPrivileges(1 << 14);
+
+    /// Returns an empty set of privileges.
+    pub fn empty() -> AclPrivileges {
+        AclPrivileges(0)
+    }
+
+    /// Creates a set of privileges from Postgres's bit representation.
+    pub fn from_bits(bits: u16) -> AclPrivileges {
+        AclPrivileges(bits)
+    }
+
+    /// Returns Postgres's bit representation of the privileges.
+    pub fn bits(self) -> u16 {
+        self.0
+    }
+
+    /// Determines if the set contains no privileges.
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Determines if the set contains all of the specified privileges.
+    pub fn contains(self, other: AclPrivileges) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for AclPrivileges {
+    type Output = AclPrivileges;
+
+    fn bitor(self, rhs: AclPrivileges) -> AclPrivileges {
+        AclPrivileges(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for AclPrivileges {
+    fn bitor_assign(&mut self, rhs: AclPrivileges) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// An entry of an access control list, as stored in the `relacl` column of `pg_class` and similar catalog columns.
+///
+/// Postgres does not support the `ACLITEM` type in the binary format, so it cannot be read directly. Instead, cast the
+/// list to `TEXT[]` in the query and parse the entries:
+///
+/// ```rust
+/// use postgres_types::{AclItem, AclPrivileges};
+///
+/// // SELECT relacl::text[] FROM pg_class WHERE oid = 'orders'::regclass
+/// let item = "=r/alice".parse::<AclItem>().unwrap();
+/// assert_eq!(item.grantee, None);
+/// assert_eq!(item.grantor, "alice");
+/// assert!(item.privileges.contains(AclPrivileges::SELECT));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AclItem {
+    /// The role the privileges are granted to, or `None` for `PUBLIC`.
+    pub grantee: Option<String>,
+    /// The role which granted the privileges.
+    pub grantor: String,
+    /// The privileges granted.
+    pub privileges: AclPrivileges,
+    /// The privileges which the grantee may grant to others. This is always a subset of `privileges`.
+    pub grant_options: AclPrivileges,
+}
+
+/// Error parsing an `AclItem`.
+#[derive(Debug)]
+pub struct ParseAclItemError(());
+
+impl fmt::Display for ParseAclItemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid aclitem")
+    }
+}
+
+impl Error for ParseAclItemError {}
+
+impl FromStr for AclItem {
+    type Err = ParseAclItemError;
+
+    fn from_str(s: &str) -> Result<AclItem, ParseAclItemError> {
+        let (grantee, s) = parse_role(s)?;
+        let s = s.strip_prefix('=').ok_or(ParseAclItemError(()))?;
+
+        let mut privileges = AclPrivileges::empty();
+        let mut grant_options = AclPrivileges::empty();
+        let mut last = None;
+        let mut chars = s.char_indices();
+        let s = loop {
+            match chars.next() {
+                Some((i, '/')) => break &s[i + 1..],
+                Some((_, '*')) => grant_options |= last.take().ok_or(ParseAclItemError(()))?,
+                Some((_, c)) => {
+                    let bit = PRIVILEGE_CHARS
+                        .iter()
+                        .position(|&p| char::from(p) == c)
+                        .ok_or(ParseAclItemError(()))?;
+                    let privilege = AclPrivileges(1 << bit);
+                    privileges |= privilege;
+                    last = Some(privilege);
+                }
+                None => return Err(ParseAclItemError(())),
+            }
+        };
+
+        let (grantor, s) = parse_role(s)?;
+        if !s.is_empty() {
+            return Err(ParseAclItemError(()));
+        }
+
+        Ok(AclItem {
+            grantee: if grantee.is_empty() {
+                None
+            } else {
+                Some(grantee)
+            },
+            grantor,
+            privileges,
+            grant_options,
+        })
+    }
+}
+
+// Parses a possibly quoted role name, returning it and the remainder of the string.
+fn parse_role(s: &str) -> Result<(String, &str), ParseAclItemError> {
+    let Some(mut rest) = s.strip_prefix('"') else {
+        let end = s.find(['=', '/']).unwrap_or(s.len());
+        return Ok((s[..end].to_string(), &s[end..]));
+    };
+
+    let mut name = String::new();
+    loop {
+        let end = rest.find('"').ok_or(ParseAclItemError(()))?;
+        name.push_str(&rest[..end]);
+        rest = &rest[end + 1..];
+        match rest.strip_prefix('"') {
+            Some(r) => {
+                name.push('"');
+                rest = r;
+            }
+            None => return Ok((name, rest)),
+        }
+    }
+}
+
+impl fmt::Display for AclItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(grantee) = &self.grantee {
+            write_role(grantee, f)?;
+        }
+        f.write_str("=")?;
+        for (bit, &c) in PRIVILEGE_CHARS.iter().enumerate() {
+            let privilege = AclPrivileges(1 << bit);
+            if self.privileges.contains(privilege) {
+                write!(f, "{}", char::from(c))?;
+                if self.grant_options.contains(privilege) {
+                    f.write_str("*")?;
+                }
+            }
+        }
+        f.write_str("/")?;
+        write_role(&self.grantor, f)
+    }
+}
+
+fn write_role(name: &str, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        return f.write_str(name);
+    }
+
+    f.write_str("\"")?;
+    f.write_str(&name.replace('"', "\"\""))?;
+    f.write_str("\"")
+}
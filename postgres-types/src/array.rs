@@ -0,0 +1,157 @@
+//! Arrays with explicit dimensions.
+
+use bytes::BytesMut;
+use fallible_iterator::FallibleIterator;
+use postgres_protocol::types;
+use std::error::Error;
+
+use crate::{ArrayDimension, FromSql, IsNull, Kind, ToSql, Type};
+
+/// A Postgres array, preserving its dimensions and their lower bounds.
+///
+/// `Vec<T>` only supports one dimensional arrays, and always uses a lower bound of 1 when sending values. `PgArray`
+/// supports any number of dimensions, and round trips lower bounds like the 0 in `'[0:2]={1,2,3}'`.
+///
+/// Elements are stored in row-major order.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PgArray<T> {
+    dimensions: Vec<ArrayDimension>,
+    elements: Vec<T>,
+}
+
+impl<T> PgArray<T> {
+    /// Creates an array from its dimensions and elements in row-major order.
+    ///
+    /// Returns `None` if a dimension has a negative length or the number of elements does not match the dimensions.
+    pub fn new(dimensions: Vec<ArrayDimension>, elements: Vec<T>) -> Option<PgArray<T>> {
+        let len = if dimensions.is_empty() {
+            0
+        } else {
+            dimensions.iter().try_fold(1usize, |len, d| {
+                usize::try_from(d.len).ok().and_then(|n| len.checked_mul(n))
+            })?
+        };
+        if len != elements.len() {
+            return None;
+        }
+
+        Some(PgArray {
+            dimensions,
+            elements,
+        })
+    }
+
+    /// Creates a one dimensional array with the specified lower bound.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of elements does not fit in an `i32`.
+    pub fn from_vec(elements: Vec<T>, lower_bound: i32) -> PgArray<T> {
+        let dimensions = if elements.is_empty() {
+            vec![]
+        } else {
+            vec![ArrayDimension {
+                len: i32::try_from(elements.len()).expect("array too large"),
+                lower_bound,
+            }]
+        };
+
+        PgArray {
+            dimensions,
+            elements,
+        }
+    }
+
+    /// Returns the dimensions of the array.
+    ///
+    /// Empty arrays have no dimensions.
+    pub fn dimensions(&self) -> &[ArrayDimension] {
+        &self.dimensions
+    }
+
+    /// Returns the elements of the array in row-major order.
+    pub fn elements(&self) -> &[T] {
+        &self.elements
+    }
+
+    /// Consumes the array, returning its elements in row-major order.
+    pub fn into_elements(self) -> Vec<T> {
+        self.elements
+    }
+
+    /// Returns the element at the specified subscripts, using the array's lower bounds.
+    ///
+    /// Returns `None` if the number of subscripts does not match the number of dimensions or a subscript is out of
+    /// bounds.
+    pub fn get(&self, subscripts: &[i32]) -> Option<&T> {
+        if subscripts.len() != self.dimensions.len() || self.dimensions.is_empty() {
+            return None;
+        }
+
+        let mut idx = 0;
+        for (subscript, dimension) in subscripts.iter().zip(&self.dimensions) {
+            let offset = subscript.checked_sub(dimension.lower_bound)?;
+            if offset < 0 || offset >= dimension.len {
+                return None;
+            }
+            idx = idx * dimension.len as usize + offset as usize;
+        }
+
+        self.elements.get(idx)
+    }
+}
+
+impl<'a, T: FromSql<'a>> FromSql<'a> for PgArray<T> {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<PgArray<T>, Box<dyn Error + Sync + Send>> {
+        let member_type = match *ty.kind() {
+            Kind::Array(ref member) => member,
+            _ => panic!("expected array type"),
+        };
+
+        let array = types::array_from_sql(raw)?;
+        let dimensions = array.dimensions().collect()?;
+        let elements = array
+            .values()
+            .map(|v| T::from_sql_nullable(member_type, v))
+            .collect()?;
+
+        PgArray::new(dimensions, elements).ok_or_else(|| "array dimensions mismatch".into())
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        match *ty.kind() {
+            Kind::Array(ref inner) => T::accepts(inner),
+            _ => false,
+        }
+    }
+}
+
+impl<T: ToSql> ToSql for PgArray<T> {
+    fn to_sql(&self, ty: &Type, w: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        let member_type = match *ty.kind() {
+            Kind::Array(ref member) => member,
+            _ => panic!("expected array type"),
+        };
+
+        types::array_to_sql(
+            self.dimensions.iter().copied(),
+            member_type.oid(),
+            &self.elements,
+            |e, w| match e.to_sql(member_type, w)? {
+                IsNull::No => Ok(postgres_protocol::IsNull::No),
+                IsNull::Yes => Ok(postgres_protocol::IsNull::Yes),
+            },
+            w,
+        )?;
+        Ok(IsNull::No)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        match *ty.kind() {
+            Kind::Array(ref member) => T::accepts(member),
+            _ => false,
+        }
+    }
+
+    to_sql_checked!();
+}
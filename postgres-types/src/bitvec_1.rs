@@ -0,0 +1,43 @@
+use bitvec_1::order::{BitOrder, Msb0};
+use bitvec_1::slice::BitSlice;
+use bitvec_1::store::BitStore;
+use bitvec_1::vec::BitVec;
+use bytes::BytesMut;
+use postgres_protocol::types;
+use std::error::Error;
+
+use crate::{FromSql, IsNull, ToSql, Type};
+
+// Postgres stores bits most significant first, so values are converted through a `BitSlice<u8, Msb0>` to support
+// any storage type and bit ordering.
+
+impl<'a, T, O> FromSql<'a> for BitVec<T, O>
+where
+    T: BitStore,
+    O: BitOrder,
+{
+    fn from_sql(_: &Type, raw: &[u8]) -> Result<BitVec<T, O>, Box<dyn Error + Sync + Send>> {
+        let varbit = types::varbit_from_sql(raw)?;
+        let bits = &BitSlice::<u8, Msb0>::from_slice(varbit.bytes())[..varbit.len()];
+        Ok(bits.iter().by_vals().collect())
+    }
+
+    accepts!(BIT, VARBIT);
+}
+
+impl<T, O> ToSql for BitVec<T, O>
+where
+    T: BitStore,
+    O: BitOrder,
+{
+    fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        let mut bits = BitVec::<u8, Msb0>::with_capacity(self.len());
+        bits.extend(self.iter().by_vals());
+        bits.set_uninitialized(false);
+        types::varbit_to_sql(self.len(), bits.as_raw_slice().iter().copied(), out)?;
+        Ok(IsNull::No)
+    }
+
+    accepts!(BIT, VARBIT);
+    to_sql_checked!();
+}
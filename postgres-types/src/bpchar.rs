@@ -0,0 +1,87 @@
+//! The blank-padded `CHARACTER(n)` type.
+
+use bytes::BytesMut;
+use postgres_protocol::types;
+use std::error::Error;
+use std::fmt;
+
+use crate::{FromSql, IsNull, ToSql, Type};
+
+/// Postgres `CHARACTER(n)` type, a string padded with spaces to a fixed length.
+///
+/// Postgres sends `CHARACTER(n)` values with their padding, so reading one into a `String` returns something like
+/// `"ab   "`. `BpChar` strips trailing spaces when it is created, matching how Postgres itself compares these values.
+/// Postgres pads values sent to it back to the column's length, and rejects values which are too long.
+///
+/// # Examples
+///
+/// ```rust
+/// use postgres_types::BpChar;
+///
+/// assert_eq!(BpChar::new("ab   "), BpChar::new("ab"));
+/// assert_eq!(BpChar::new("ab   ").as_str(), "ab");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BpChar(String);
+
+impl BpChar {
+    /// Creates a new value, stripping any trailing spaces.
+    pub fn new(s: impl Into<String>) -> BpChar {
+        let mut s = s.into();
+        s.truncate(s.trim_end_matches(' ').len());
+        BpChar(s)
+    }
+
+    /// Returns the value without its padding.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Consumes the value, returning it without its padding.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl From<String> for BpChar {
+    fn from(s: String) -> BpChar {
+        BpChar::new(s)
+    }
+}
+
+impl From<&str> for BpChar {
+    fn from(s: &str) -> BpChar {
+        BpChar::new(s)
+    }
+}
+
+impl AsRef<str> for BpChar {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for BpChar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl<'a> FromSql<'a> for BpChar {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<BpChar, Box<dyn Error + Sync + Send>> {
+        types::text_from_sql(raw).map(BpChar::new)
+    }
+
+    accepts!(BPCHAR);
+}
+
+impl ToSql for BpChar {
+    fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        types::text_to_sql(&self.0, out);
+        Ok(IsNull::No)
+    }
+
+    accepts!(BPCHAR);
+
+    to_sql_checked!();
+}
@@ -0,0 +1,128 @@
+use bytes::BytesMut;
+use chrono_04::{DateTime, Utc};
+use chrono_tz_010::Tz;
+use std::error::Error;
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::{FromSql, IsNull, ToSql, Type};
+
+/// Reads `TIMESTAMP WITH TIME ZONE` values in UTC.
+///
+/// Postgres does not store the time zone of a value, so the zone cannot be recovered when reading it. Use
+/// [`InZone`] to convert values to a specific zone as they are read.
+impl<'a> FromSql<'a> for DateTime<Tz> {
+    fn from_sql(type_: &Type, raw: &[u8]) -> Result<DateTime<Tz>, Box<dyn Error + Sync + Send>> {
+        let utc = DateTime::<Utc>::from_sql(type_, raw)?;
+        Ok(utc.with_timezone(&Tz::UTC))
+    }
+
+    accepts!(TIMESTAMPTZ);
+}
+
+impl ToSql for DateTime<Tz> {
+    fn to_sql(
+        &self,
+        type_: &Type,
+        w: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        self.with_timezone(&Utc).to_sql(type_, w)
+    }
+
+    accepts!(TIMESTAMPTZ);
+    to_sql_checked!();
+}
+
+/// A time zone known at compile time, used with [`InZone`].
+///
+/// # Examples
+///
+/// ```
+/// # extern crate chrono_tz_010 as chrono_tz;
+/// use chrono_tz::Tz;
+/// use postgres_types::Zone;
+///
+/// struct London;
+///
+/// impl Zone for London {
+///     const TZ: Tz = Tz::Europe__London;
+/// }
+/// ```
+pub trait Zone {
+    /// The time zone.
+    const TZ: Tz;
+}
+
+/// A `TIMESTAMP WITH TIME ZONE` value converted to the time zone `Z` when read.
+///
+/// With the `London` zone from the [`Zone`] example, `row.get::<_, InZone<London>>(0)` returns a value in the
+/// `Europe/London` time zone.
+pub struct InZone<Z> {
+    value: DateTime<Tz>,
+    _zone: PhantomData<fn() -> Z>,
+}
+
+impl<Z: Zone> InZone<Z> {
+    /// Creates a new value, converting it to the time zone `Z`.
+    pub fn new(value: DateTime<Tz>) -> InZone<Z> {
+        InZone {
+            value: value.with_timezone(&Z::TZ),
+            _zone: PhantomData,
+        }
+    }
+}
+
+impl<Z> InZone<Z> {
+    /// Returns a reference to the inner value.
+    pub fn get(&self) -> &DateTime<Tz> {
+        &self.value
+    }
+
+    /// Consumes the wrapper, returning the inner value.
+    pub fn into_inner(self) -> DateTime<Tz> {
+        self.value
+    }
+}
+
+impl<Z> fmt::Debug for InZone<Z> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.value, f)
+    }
+}
+
+impl<Z> Clone for InZone<Z> {
+    fn clone(&self) -> InZone<Z> {
+        *self
+    }
+}
+
+impl<Z> Copy for InZone<Z> {}
+
+impl<Z> PartialEq for InZone<Z> {
+    fn eq(&self, other: &InZone<Z>) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<Z> Eq for InZone<Z> {}
+
+impl<'a, Z: Zone> FromSql<'a> for InZone<Z> {
+    fn from_sql(type_: &Type, raw: &[u8]) -> Result<InZone<Z>, Box<dyn Error + Sync + Send>> {
+        DateTime::<Tz>::from_sql(type_, raw).map(InZone::new)
+    }
+
+    accepts!(TIMESTAMPTZ);
+}
+
+impl<Z> ToSql for InZone<Z> {
+    fn to_sql(
+        &self,
+        type_: &Type,
+        w: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        self.value.to_sql(type_, w)
+    }
+
+    accepts!(TIMESTAMPTZ);
+    to_sql_checked!();
+}
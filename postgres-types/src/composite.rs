@@ -0,0 +1,138 @@
+//! A dynamically typed composite value.
+
+use std::error::Error;
+
+use crate::private::read_be_i32;
+use crate::{Field, FromSql, Kind, Type, WrongType};
+
+/// A value of any composite type, with fields decoded on demand.
+///
+/// This is intended for code which does not know the structure of a composite type at compile time. Types which are
+/// known ahead of time are better represented by a struct deriving `FromSql`.
+#[derive(Debug, Clone)]
+pub struct Composite<'a> {
+    type_: Type,
+    values: Vec<Option<&'a [u8]>>,
+}
+
+impl<'a> Composite<'a> {
+    /// Returns the composite type of the value.
+    pub fn type_(&self) -> &Type {
+        &self.type_
+    }
+
+    /// Returns the fields of the composite type.
+    pub fn fields(&self) -> &[Field] {
+        match self.type_.kind() {
+            Kind::Composite(fields) => fields,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns the number of fields in the value.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Determines if the value has no fields.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the index of the field with the specified name, if it exists.
+    pub fn field_index(&self, name: &str) -> Option<usize> {
+        self.fields().iter().position(|f| f.name() == name)
+    }
+
+    /// Deserializes the field with the specified name.
+    ///
+    /// Returns an error if the field does not exist, or if it cannot be converted to the requested Rust type.
+    pub fn try_get<T>(&self, name: &str) -> Result<T, Box<dyn Error + Sync + Send>>
+    where
+        T: FromSql<'a>,
+    {
+        let idx = self
+            .field_index(name)
+            .ok_or_else(|| format!("invalid field `{name}`"))?;
+        self.try_get_idx(idx)
+    }
+
+    /// Deserializes the field at the specified index.
+    ///
+    /// Returns an error if the index is out of bounds, or if the field cannot be converted to the requested Rust
+    /// type.
+    pub fn try_get_idx<T>(&self, idx: usize) -> Result<T, Box<dyn Error + Sync + Send>>
+    where
+        T: FromSql<'a>,
+    {
+        let value = *self
+            .values
+            .get(idx)
+            .ok_or_else(|| format!("invalid field index {idx}"))?;
+        let ty = self.fields()[idx].type_();
+        if !T::accepts(ty) {
+            return Err(Box::new(WrongType::new::<T>(ty.clone())));
+        }
+
+        T::from_sql_nullable(ty, value)
+    }
+
+    /// Returns the raw binary representation of the field at the specified index, or `None` if it is `NULL`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    pub fn raw(&self, idx: usize) -> Option<&'a [u8]> {
+        self.values[idx]
+    }
+}
+
+impl<'a> FromSql<'a> for Composite<'a> {
+    fn from_sql(
+        ty: &Type,
+        mut raw: &'a [u8],
+    ) -> Result<Composite<'a>, Box<dyn Error + Sync + Send>> {
+        let fields = match ty.kind() {
+            Kind::Composite(fields) => fields,
+            _ => return Err("expected composite type".into()),
+        };
+
+        let num_fields = read_be_i32(&mut raw)?;
+        if num_fields as usize != fields.len() {
+            return Err(format!("invalid field count: {} vs {}", num_fields, fields.len()).into());
+        }
+
+        let mut values = Vec::with_capacity(fields.len());
+        for field in fields {
+            let oid = read_be_i32(&mut raw)? as u32;
+            if oid != field.type_().oid() {
+                return Err("unexpected OID".into());
+            }
+
+            let len = read_be_i32(&mut raw)?;
+            let value = if len < 0 {
+                None
+            } else {
+                let (head, tail) = raw
+                    .split_at_checked(len as usize)
+                    .ok_or("invalid buffer size")?;
+                raw = tail;
+                Some(head)
+            };
+            values.push(value);
+        }
+
+        if !raw.is_empty() {
+            return Err("invalid message length".into());
+        }
+
+        Ok(Composite {
+            type_: ty.clone(),
+            values,
+        })
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        matches!(ty.kind(), Kind::Composite(_))
+    }
+}
@@ -0,0 +1,127 @@
+//! The `cube` extension type.
+
+use bytes::{BufMut, BytesMut};
+use std::error::Error;
+
+use crate::{FromSql, IsNull, ToSql, Type};
+
+const POINT_BIT: u32 = 0x8000_0000;
+const DIM_MASK: u32 = 0x7fff_ffff;
+
+/// The maximum number of dimensions of a cube.
+const CUBE_MAX_DIM: usize = 100;
+
+/// Postgres `cube` type, an N-dimensional point or box.
+///
+/// A box is represented by two opposite corners. A point is a box whose corners are equal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PgCube {
+    corner1: Vec<f64>,
+    corner2: Vec<f64>,
+}
+
+impl PgCube {
+    /// Creates a point cube with the specified coordinates.
+    pub fn point(coordinates: Vec<f64>) -> PgCube {
+        PgCube {
+            corner2: coordinates.clone(),
+            corner1: coordinates,
+        }
+    }
+
+    /// Creates a box cube with the specified opposite corners.
+    ///
+    /// Returns `None` if the corners have different numbers of dimensions.
+    pub fn new(corner1: Vec<f64>, corner2: Vec<f64>) -> Option<PgCube> {
+        if corner1.len() != corner2.len() {
+            return None;
+        }
+
+        Some(PgCube { corner1, corner2 })
+    }
+
+    /// Returns the number of dimensions of the cube.
+    pub fn dimensions(&self) -> usize {
+        self.corner1.len()
+    }
+
+    /// Determines if the cube is a point.
+    pub fn is_point(&self) -> bool {
+        self.corner1 == self.corner2
+    }
+
+    /// Returns the coordinates of the first corner of the cube.
+    pub fn corner1(&self) -> &[f64] {
+        &self.corner1
+    }
+
+    /// Returns the coordinates of the second corner of the cube.
+    pub fn corner2(&self) -> &[f64] {
+        &self.corner2
+    }
+}
+
+impl<'a> FromSql<'a> for PgCube {
+    fn from_sql(_: &Type, mut raw: &'a [u8]) -> Result<PgCube, Box<dyn Error + Sync + Send>> {
+        let (header, rest) = raw.split_first_chunk().ok_or("invalid message length")?;
+        raw = rest;
+        let header = u32::from_be_bytes(*header);
+
+        let dim = (header & DIM_MASK) as usize;
+        let nitems = if header & POINT_BIT == 0 {
+            dim * 2
+        } else {
+            dim
+        };
+        if raw.len() != nitems * 8 {
+            return Err("invalid message length".into());
+        }
+
+        let coordinates = raw
+            .chunks_exact(8)
+            .map(|c| f64::from_be_bytes(c.try_into().unwrap()))
+            .collect::<Vec<_>>();
+        if header & POINT_BIT == 0 {
+            let (corner1, corner2) = coordinates.split_at(dim);
+            Ok(PgCube {
+                corner1: corner1.to_vec(),
+                corner2: corner2.to_vec(),
+            })
+        } else {
+            Ok(PgCube::point(coordinates))
+        }
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        ty.name() == "cube"
+    }
+}
+
+impl ToSql for PgCube {
+    fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        if self.dimensions() > CUBE_MAX_DIM {
+            return Err(format!("a cube cannot have more than {CUBE_MAX_DIM} dimensions").into());
+        }
+
+        let dim = self.dimensions() as u32;
+        if self.is_point() {
+            out.put_u32(POINT_BIT | dim);
+            for &c in &self.corner1 {
+                out.put_f64(c);
+            }
+        } else {
+            out.put_u32(dim);
+            for &c in self.corner1.iter().chain(&self.corner2) {
+                out.put_f64(c);
+            }
+        }
+
+        Ok(IsNull::No)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        ty.name() == "cube"
+    }
+
+    to_sql_checked!();
+}
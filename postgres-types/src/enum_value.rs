@@ -0,0 +1,81 @@
+//! A dynamically typed enum value.
+
+use bytes::{BufMut, BytesMut};
+use std::error::Error;
+use std::fmt;
+
+use crate::{FromSql, IsNull, Kind, ToSql, Type};
+
+/// A value of any enum type, represented by its label.
+///
+/// This is intended for code which does not know the variants of an enum type at compile time. Types which are known
+/// ahead of time are better represented by a Rust enum deriving `FromSql` and `ToSql`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EnumValue(pub String);
+
+impl EnumValue {
+    /// Returns the label of the variant.
+    pub fn label(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for EnumValue {
+    fn from(s: String) -> EnumValue {
+        EnumValue(s)
+    }
+}
+
+impl From<&str> for EnumValue {
+    fn from(s: &str) -> EnumValue {
+        EnumValue(s.to_string())
+    }
+}
+
+impl AsRef<str> for EnumValue {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for EnumValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl<'a> FromSql<'a> for EnumValue {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<EnumValue, Box<dyn Error + Sync + Send>> {
+        Ok(EnumValue(std::str::from_utf8(raw)?.to_string()))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        matches!(ty.kind(), Kind::Enum(_))
+    }
+}
+
+/// Returns an error if the label is not one of the enum type's variants.
+impl ToSql for EnumValue {
+    fn to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        let variants = match ty.kind() {
+            Kind::Enum(variants) => variants,
+            _ => return Err("expected enum type".into()),
+        };
+        if !variants.contains(&self.0) {
+            return Err(format!("invalid variant `{}` for enum `{}`", self.0, ty.name()).into());
+        }
+
+        out.put_slice(self.0.as_bytes());
+        Ok(IsNull::No)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        matches!(ty.kind(), Kind::Enum(_))
+    }
+
+    to_sql_checked!();
+}
@@ -0,0 +1,25 @@
+use bytes::BytesMut;
+use ipnetwork_021::IpNetwork;
+use postgres_protocol::types;
+use std::error::Error;
+
+use crate::{FromSql, IsNull, ToSql, Type};
+
+impl<'a> FromSql<'a> for IpNetwork {
+    fn from_sql(_: &Type, raw: &[u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        let inet = types::inet_from_sql(raw)?;
+        Ok(IpNetwork::new(inet.addr(), inet.netmask())?)
+    }
+
+    accepts!(INET, CIDR);
+}
+
+impl ToSql for IpNetwork {
+    fn to_sql(&self, _: &Type, w: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        types::inet_to_sql(self.ip(), self.prefix(), w);
+        Ok(IsNull::No)
+    }
+
+    accepts!(INET, CIDR);
+    to_sql_checked!();
+}
@@ -0,0 +1,58 @@
+//! The `jsonpath` type.
+
+use bytes::BytesMut;
+use postgres_protocol::types;
+use std::error::Error;
+use std::fmt;
+
+use crate::{FromSql, IsNull, ToSql, Type};
+
+/// Postgres `JSONPATH` type, an SQL/JSON path expression like `$.track.segments[*] ? (@.HR > 130)`.
+///
+/// The expression is not parsed locally; Postgres validates it when it is bound as a parameter and returns it in a
+/// normalized form.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct JsonPath(pub String);
+
+impl From<String> for JsonPath {
+    fn from(s: String) -> JsonPath {
+        JsonPath(s)
+    }
+}
+
+impl From<&str> for JsonPath {
+    fn from(s: &str) -> JsonPath {
+        JsonPath(s.to_string())
+    }
+}
+
+impl AsRef<str> for JsonPath {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for JsonPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl<'a> FromSql<'a> for JsonPath {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<JsonPath, Box<dyn Error + Sync + Send>> {
+        types::jsonpath_from_sql(raw).map(|s| JsonPath(s.to_string()))
+    }
+
+    accepts!(JSONPATH);
+}
+
+impl ToSql for JsonPath {
+    fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        types::jsonpath_to_sql(&self.0, out);
+        Ok(IsNull::No)
+    }
+
+    accepts!(JSONPATH);
+
+    to_sql_checked!();
+}
@@ -0,0 +1,81 @@
+//! Lenient numeric conversions.
+
+use std::error::Error;
+
+use crate::{FromSql, PgNumeric, Type};
+
+/// A wrapper which reads a number from any of the Postgres numeric types.
+///
+/// The plain Rust numeric types only accept the Postgres type of the same size, so reading a `BIGINT` column
+/// into an `i32` fails even when the value would fit. `Lenient<T>` relaxes that for reads:
+///
+/// * `Lenient<i16>`, `Lenient<i32>` and `Lenient<i64>` accept `SMALLINT`, `INT`, `BIGINT` and `NUMERIC`, and
+///   return an error if the value is out of range or, for `NUMERIC`, has a fractional part.
+/// * `Lenient<f64>` accepts `SMALLINT`, `INT`, `BIGINT`, `REAL`, `DOUBLE PRECISION` and `NUMERIC`, rounding
+///   to the nearest representable value.
+///
+/// # Examples
+///
+/// ```rust
+/// use postgres_types::{FromSql, Lenient, Type};
+///
+/// let raw = 5i64.to_be_bytes();
+/// assert_eq!(Lenient::<i32>::from_sql(&Type::INT8, &raw).unwrap(), Lenient(5));
+///
+/// let raw = i64::MAX.to_be_bytes();
+/// assert!(Lenient::<i32>::from_sql(&Type::INT8, &raw).is_err());
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Lenient<T>(pub T);
+
+impl<T> Lenient<T> {
+    /// Consumes the wrapper, returning the inner value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+macro_rules! lenient_int {
+    ($($t:ty),*) => {
+        $(
+            impl<'a> FromSql<'a> for Lenient<$t> {
+                fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+                    let v = match *ty {
+                        Type::INT2 => narrow(i16::from_sql(ty, raw)?)?,
+                        Type::INT4 => narrow(i32::from_sql(ty, raw)?)?,
+                        Type::INT8 => narrow(i64::from_sql(ty, raw)?)?,
+                        _ => <$t>::try_from(PgNumeric::from_sql(ty, raw)?)?,
+                    };
+                    Ok(Lenient(v))
+                }
+
+                accepts!(INT2, INT4, INT8, NUMERIC);
+            }
+        )*
+    };
+}
+
+lenient_int!(i16, i32, i64);
+
+fn narrow<T, U>(v: T) -> Result<U, Box<dyn Error + Sync + Send>>
+where
+    U: TryFrom<T>,
+{
+    U::try_from(v).map_err(|_| "value out of range".into())
+}
+
+impl<'a> FromSql<'a> for Lenient<f64> {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        let v = match *ty {
+            Type::INT2 => f64::from(i16::from_sql(ty, raw)?),
+            Type::INT4 => f64::from(i32::from_sql(ty, raw)?),
+            Type::INT8 => i64::from_sql(ty, raw)? as f64,
+            Type::FLOAT4 => f64::from(f32::from_sql(ty, raw)?),
+            Type::FLOAT8 => f64::from_sql(ty, raw)?,
+            _ => f64::from(PgNumeric::from_sql(ty, raw)?),
+        };
+        Ok(Lenient(v))
+    }
+
+    accepts!(INT2, INT4, INT8, FLOAT4, FLOAT8, NUMERIC);
+}
@@ -95,6 +95,65 @@
 //! }
 //! ```
 //!
+//! The `#[postgres(default)]` attribute marks a field as optional in the Postgres type. If the composite type
+//! doesn't have the field, it is set to its `Default` value when reading and left out when writing. This allows a
+//! struct to be deployed before the matching `ALTER TYPE ... ADD ATTRIBUTE`:
+//!
+//! ```rust
+//! # #[cfg(feature = "derive")]
+//! use postgres_types::{ToSql, FromSql};
+//!
+//! # #[cfg(feature = "derive")]
+//! #[derive(Debug, ToSql, FromSql)]
+//! struct InventoryItem {
+//!     name: String,
+//!     supplier_id: i32,
+//!     price: Option<f64>,
+//!     #[postgres(default)]
+//!     discontinued: bool,
+//! }
+//! ```
+//!
+//! Fields which only exist on the Rust side, such as cached or computed values, can be marked with
+//! `#[postgres(skip)]`. They are ignored when matching the struct against the composite type, set to their `Default`
+//! value when reading, and not written:
+//!
+//! ```rust
+//! # #[cfg(feature = "derive")]
+//! use postgres_types::{ToSql, FromSql};
+//!
+//! # #[cfg(feature = "derive")]
+//! #[derive(Debug, ToSql, FromSql)]
+//! struct InventoryItem {
+//!     name: String,
+//!     supplier_id: i32,
+//!     price: Option<f64>,
+//!     #[postgres(skip)]
+//!     display_name: Option<String>,
+//! }
+//! ```
+//!
+//! A struct may have a single lifetime parameter, in which case the derived [`FromSql`] implementation borrows fields
+//! such as `&'a str` and `&'a [u8]` from the row rather than allocating them:
+//!
+//! ```rust
+//! # #[cfg(feature = "derive")]
+//! use postgres_types::{ToSql, FromSql};
+//!
+//! # #[cfg(feature = "derive")]
+//! #[derive(Debug, ToSql, FromSql)]
+//! struct InventoryItem<'a> {
+//!     name: &'a str,
+//!     supplier_id: i32,
+//!     price: Option<f64>,
+//! }
+//! ```
+//!
+//! The `#[postgres(positional)]` attribute matches fields by their position rather than their name. This allows the
+//! derived [`FromSql`] implementation to also read anonymous records, such as the result of
+//! `SELECT ROW('widget', 42, 9.99::FLOAT8)`, as long as the type of each value is built in. `#[postgres(default)]`
+//! cannot be used with positional matching.
+//!
 //! ## Naming
 //!
 //! The derived implementations will enforce exact matches of type, field, and variant names between the Rust and
@@ -155,6 +214,32 @@
 //! - `"SCREAMING-KEBAB-CASE"`
 //! - `"Train-Case"`
 //!
+//! For databases whose naming isn't consistent enough for `rename_all`, the `#[postgres(ignore_case)]` attribute
+//! compares variant and field names case-insensitively, and `#[postgres(ignore_underscores)]` ignores underscores in
+//! them. The names are still compared after any renaming, and the name of the type itself must match exactly:
+//!
+//! ```sql
+//! CREATE TYPE mood AS ENUM (
+//!     'SAD',
+//!     'ok',
+//!     'Very_Happy'
+//! );
+//! ```
+//!
+//! ```rust
+//! # #[cfg(feature = "derive")]
+//! use postgres_types::{ToSql, FromSql};
+//!
+//! # #[cfg(feature = "derive")]
+//! #[derive(Debug, ToSql, FromSql)]
+//! #[postgres(name = "mood", ignore_case, ignore_underscores)]
+//! enum Mood {
+//!     Sad,
+//!     Ok,
+//!     VeryHappy,
+//! }
+//! ```
+//!
 //! ## Allowing Enum Mismatches
 //!
 //! By default the generated implementation of [`ToSql`] & [`FromSql`] for enums will require an exact match of the enum
@@ -181,9 +266,51 @@
 //!    Meh,
 //! }
 //! ```
+//!
+//! ## Integer Enums
+//!
+//! Enums are often stored as integer columns rather than Postgres enum types. The `#[postgres(integer)]` attribute
+//! maps a fieldless enum to `INT2`, `INT4`, or `INT8` values using its discriminants, which must all be explicit:
+//!
+//! ```rust
+//! # #[cfg(feature = "derive")]
+//! use postgres_types::{ToSql, FromSql};
+//!
+//! # #[cfg(feature = "derive")]
+//! #[derive(Debug, ToSql, FromSql)]
+//! #[postgres(integer)]
+//! enum OrderState {
+//!     Pending = 0,
+//!     Shipped = 1,
+//!     Cancelled = -1,
+//! }
+//! ```
+//!
+//! Reading a value which doesn't match any discriminant is an error.
+//!
+//! ## Generic Bounds
+//!
+//! The derived implementations for generic types require each type parameter to implement the derived trait. The
+//! `#[postgres(bound = "...")]` attribute replaces those bounds with the given `where` predicates, which may be empty.
+//! For [`FromSql`], the implementation's lifetime is the type's own lifetime parameter, or `'a` if it has none:
+//!
+//! ```rust
+//! # #[cfg(feature = "derive")]
+//! use postgres_types::{ToSql, FromSql};
+//! use std::marker::PhantomData;
+//!
+//! # #[cfg(feature = "derive")]
+//! #[derive(Debug, ToSql, FromSql)]
+//! #[postgres(name = "tagged_id", bound = "M: std::fmt::Debug")]
+//! struct TaggedId<M> {
+//!     id: i32,
+//!     #[postgres(skip)]
+//!     marker: PhantomData<M>,
+//! }
+//! ```
 #![warn(clippy::all, rust_2018_idioms, missing_docs)]
 use fallible_iterator::FallibleIterator;
-use postgres_protocol::types::{self, ArrayDimension};
+use postgres_protocol::types;
 use std::any::type_name;
 use std::borrow::Cow;
 use std::collections::HashMap;
@@ -197,18 +324,40 @@ use std::time::{Duration, SystemTime, UNIX_EPOCH};
 #[cfg(feature = "derive")]
 pub use postgres_derive::{FromSql, ToSql};
 
+#[cfg(feature = "with-chrono-tz-0_10")]
+pub use crate::chrono_tz_010::{InZone, Zone};
 #[cfg(feature = "with-serde_json-1")]
 pub use crate::serde_json_1::Json;
 use crate::type_gen::{Inner, Other};
 
 #[doc(inline)]
 pub use postgres_protocol::Oid;
+#[doc(inline)]
+pub use postgres_protocol::types::ArrayDimension;
 
+pub use crate::acl::{AclItem, AclPrivileges, ParseAclItemError};
 #[doc(inline)]
-pub use pg_lsn::PgLsn;
+pub use pg_lsn::{ParseLsnError, PgLsn};
+
+pub use crate::array::PgArray;
+pub use crate::bpchar::BpChar;
+pub use crate::composite::Composite;
+pub use crate::cube::PgCube;
+pub use crate::enum_value::EnumValue;
+pub use crate::json_path::JsonPath;
+pub use crate::lenient::Lenient;
+pub use crate::ltree::{LQuery, LTree, LTxtQuery};
+pub use crate::numeric::{NumericSign, ParseNumericError, PgNumeric, TryFromNumericError};
+pub use crate::range::PgRange;
+pub use crate::reg::{
+    RegClass, RegCollation, RegConfig, RegDictionary, RegNamespace, RegOper, RegOperator, RegProc,
+    RegProcedure, RegRole, RegType,
+};
+pub use crate::snapshot::PgSnapshot;
+pub use crate::text_search::{Lexeme, LexemePosition, TsQuery, TsVector, TsWeight};
 
 pub use crate::special::{Date, Timestamp};
-use bytes::BytesMut;
+use bytes::{Bytes, BytesMut};
 
 // Number of seconds from 1970-01-01 to 2000-01-01
 const TIME_SEC_CONVERSION: u64 = 946_684_800;
@@ -270,12 +419,18 @@ mod bit_vec_07;
 mod bit_vec_08;
 #[cfg(feature = "with-bit-vec-0_9")]
 mod bit_vec_09;
+#[cfg(feature = "with-bitvec-1")]
+mod bitvec_1;
 #[cfg(feature = "with-chrono-0_4")]
 mod chrono_04;
+#[cfg(feature = "with-chrono-tz-0_10")]
+mod chrono_tz_010;
 #[cfg(feature = "with-cidr-0_2")]
 mod cidr_02;
 #[cfg(feature = "with-cidr-0_3")]
 mod cidr_03;
+#[cfg(feature = "with-compact_str-0_9")]
+mod compact_str_09;
 #[cfg(feature = "with-eui48-0_4")]
 mod eui48_04;
 #[cfg(feature = "with-eui48-1")]
@@ -284,12 +439,20 @@ mod eui48_1;
 mod geo_types_06;
 #[cfg(feature = "with-geo-types-0_7")]
 mod geo_types_07;
+#[cfg(feature = "with-ipnetwork-0_21")]
+mod ipnetwork_021;
 #[cfg(feature = "with-jiff-0_1")]
 mod jiff_01;
 #[cfg(feature = "with-jiff-0_2")]
 mod jiff_02;
+#[cfg(feature = "with-macaddr-1")]
+mod macaddr_1;
 #[cfg(feature = "with-serde_json-1")]
 mod serde_json_1;
+#[cfg(feature = "with-simd-json-0_15")]
+mod simd_json_015;
+#[cfg(feature = "with-smallvec-1")]
+mod smallvec_1;
 #[cfg(feature = "with-smol_str-01")]
 mod smol_str_01;
 #[cfg(feature = "with-time-0_2")]
@@ -305,10 +468,24 @@ mod uuid_1;
 #[cfg(feature = "with-time-0_2")]
 extern crate time_02 as time;
 
+mod acl;
+mod array;
+mod bpchar;
+mod composite;
+mod cube;
+mod enum_value;
+mod json_path;
+mod lenient;
+mod ltree;
+mod numeric;
 mod pg_lsn;
 #[doc(hidden)]
 pub mod private;
+mod range;
+mod reg;
+mod snapshot;
 mod special;
+mod text_search;
 mod type_gen;
 
 /// A Postgres type.
@@ -475,12 +652,14 @@ impl WrongType {
 /// | `i16`                             | SMALLINT, SMALLSERIAL                         |
 /// | `i32`                             | INT, SERIAL                                   |
 /// | `u32`                             | OID                                           |
+/// | `u64`                             | BIGINT, NUMERIC                               |
+/// | `u128`                            | NUMERIC                                       |
 /// | `i64`                             | BIGINT, BIGSERIAL                             |
 /// | `f32`                             | REAL                                          |
 /// | `f64`                             | DOUBLE PRECISION                              |
 /// | `&str`/`String`                   | VARCHAR, CHAR(n), TEXT, CITEXT, NAME, UNKNOWN |
 /// |                                   | LTREE, LQUERY, LTXTQUERY                      |
-/// | `&[u8]`/`Vec<u8>`                 | BYTEA                                         |
+/// | `&[u8]`/`Vec<u8>`/`Bytes`         | BYTEA                                         |
 /// | `HashMap<String, Option<String>>` | HSTORE                                        |
 /// | `SystemTime`                      | TIMESTAMP, TIMESTAMP WITH TIME ZONE           |
 /// | `IpAddr`                          | INET                                          |
@@ -497,10 +676,12 @@ impl WrongType {
 /// | `chrono::DateTime<Utc>`         | TIMESTAMP WITH TIME ZONE            |
 /// | `chrono::DateTime<Local>`       | TIMESTAMP WITH TIME ZONE            |
 /// | `chrono::DateTime<FixedOffset>` | TIMESTAMP WITH TIME ZONE            |
+/// | `chrono::DateTime<chrono_tz::Tz>` | TIMESTAMP WITH TIME ZONE          |
 /// | `chrono::NaiveDate`             | DATE                                |
 /// | `chrono::NaiveTime`             | TIME                                |
 /// | `cidr::IpCidr`                  | CIDR                                |
 /// | `cidr::IpInet`                  | INET                                |
+/// | `ipnetwork::IpNetwork`          | INET, CIDR                          |
 /// | `time::PrimitiveDateTime`       | TIMESTAMP                           |
 /// | `time::OffsetDateTime`          | TIMESTAMP WITH TIME ZONE            |
 /// | `time::Date`                    | DATE                                |
@@ -510,18 +691,27 @@ impl WrongType {
 /// | `jiff::civil::Time`             | TIME                                |
 /// | `jiff::Timestamp`               | TIMESTAMP WITH TIME ZONE            |
 /// | `eui48::MacAddress`             | MACADDR                             |
+/// | `macaddr::MacAddr6`             | MACADDR                             |
+/// | `macaddr::MacAddr8`             | MACADDR8                            |
 /// | `geo_types::Point<f64>`         | POINT                               |
 /// | `geo_types::Rect<f64>`          | BOX                                 |
 /// | `geo_types::LineString<f64>`    | PATH                                |
 /// | `serde_json::Value`             | JSON, JSONB                         |
+/// | `&serde_json::value::RawValue`  | JSON, JSONB                         |
+/// | `simd_json::OwnedValue`         | JSON, JSONB                         |
+/// | `simd_json::BorrowedValue`      | JSON, JSONB                         |
 /// | `uuid::Uuid`                    | UUID                                |
 /// | `bit_vec::BitVec`               | BIT, VARBIT                         |
+/// | `bitvec::vec::BitVec`           | BIT, VARBIT                         |
 /// | `eui48::MacAddress`             | MACADDR                             |
 /// | `cidr::InetCidr`                | CIDR                                |
 /// | `cidr::InetAddr`                | INET                                |
 /// | `smol_str::SmolStr`             | VARCHAR, CHAR(n), TEXT, CITEXT,     |
 /// |                                 | NAME, UNKNOWN, LTREE, LQUERY,       |
 /// |                                 | LTXTQUERY                           |
+/// | `compact_str::CompactString`    | VARCHAR, CHAR(n), TEXT, CITEXT,     |
+/// |                                 | NAME, UNKNOWN, LTREE, LQUERY,       |
+/// |                                 | LTXTQUERY                           |
 ///
 /// # Nullability
 ///
@@ -529,13 +719,25 @@ impl WrongType {
 /// `Option<T>` where `T` implements `FromSql`. An `Option<T>` represents a
 /// nullable Postgres value.
 ///
+/// # Infinity
+///
+/// The date and time types above cannot represent the special `infinity` and
+/// `-infinity` values. Most fail to convert them, while `SystemTime` maps them
+/// to distant times. Wrap them in [`Date<T>`] or [`Timestamp<T>`] to handle
+/// those values, for example with `Timestamp<chrono::DateTime<Utc>>`.
+///
 /// # Arrays
 ///
-/// `FromSql` is implemented for `Vec<T>`, `Box<[T]>` and `[T; N]` where `T`
-/// implements `FromSql`, and corresponds to one-dimensional Postgres arrays.
+/// `FromSql` is implemented for `Vec<T>`, `Box<[T]>`, `[T; N]` and
+/// `smallvec::SmallVec<[T; N]>` where `T` implements `FromSql`, and corresponds
+/// to one-dimensional Postgres arrays.
+/// Multi-dimensional arrays and arrays with lower bounds other than 1 can be
+/// read with `PgArray<T>`. The `OIDVECTOR` and `INT2VECTOR` types used by the
+/// system catalogs are read the same way, into `Vec<u32>` and `Vec<i16>`.
 ///
 /// **Note:** the impl for arrays only exist when the Cargo feature `array-impls`
-/// is enabled.
+/// is enabled, and the impl for `SmallVec` only exists when the Cargo feature
+/// `with-smallvec-1` is enabled.
 pub trait FromSql<'a>: Sized {
     /// Creates a new value of this type from a buffer of data of the specified
     /// Postgres `Type` in its binary format.
@@ -694,6 +896,16 @@ impl<'a> FromSql<'a> for &'a [u8] {
     accepts!(BYTEA);
 }
 
+/// The value is copied out of the row. `Row::get_bytes` in `tokio-postgres` returns a `Bytes` which shares the row's
+/// buffer instead.
+impl<'a> FromSql<'a> for Bytes {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Bytes, Box<dyn Error + Sync + Send>> {
+        Ok(Bytes::copy_from_slice(types::bytea_from_sql(raw)))
+    }
+
+    accepts!(BYTEA);
+}
+
 impl<'a> FromSql<'a> for String {
     fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<String, Box<dyn Error + Sync + Send>> {
         <&str as FromSql>::from_sql(ty, raw).map(ToString::to_string)
@@ -846,12 +1058,15 @@ pub enum IsNull {
 /// | `i16`                             | SMALLINT, SMALLSERIAL                |
 /// | `i32`                             | INT, SERIAL                          |
 /// | `u32`                             | OID                                  |
+/// | `u64`                             | BIGINT, NUMERIC                      |
+/// | `u128`                            | NUMERIC                              |
 /// | `i64`                             | BIGINT, BIGSERIAL                    |
 /// | `f32`                             | REAL                                 |
 /// | `f64`                             | DOUBLE PRECISION                     |
 /// | `&str`/`String`                   | VARCHAR, CHAR(n), TEXT, CITEXT, NAME |
 /// |                                   | LTREE, LQUERY, LTXTQUERY             |
 /// | `&[u8]`/`Vec<u8>`/`[u8; N]`       | BYTEA                                |
+/// | `Bytes`                           | BYTEA                                |
 /// | `HashMap<String, Option<String>>` | HSTORE                               |
 /// | `SystemTime`                      | TIMESTAMP, TIMESTAMP WITH TIME ZONE  |
 /// | `IpAddr`                          | INET                                 |
@@ -868,22 +1083,36 @@ pub enum IsNull {
 /// | `chrono::DateTime<Utc>`         | TIMESTAMP WITH TIME ZONE            |
 /// | `chrono::DateTime<Local>`       | TIMESTAMP WITH TIME ZONE            |
 /// | `chrono::DateTime<FixedOffset>` | TIMESTAMP WITH TIME ZONE            |
+/// | `chrono::DateTime<chrono_tz::Tz>` | TIMESTAMP WITH TIME ZONE          |
 /// | `chrono::NaiveDate`             | DATE                                |
 /// | `chrono::NaiveTime`             | TIME                                |
 /// | `cidr::IpCidr`                  | CIDR                                |
 /// | `cidr::IpInet`                  | INET                                |
+/// | `ipnetwork::IpNetwork`          | INET, CIDR                          |
 /// | `time::PrimitiveDateTime`       | TIMESTAMP                           |
 /// | `time::OffsetDateTime`          | TIMESTAMP WITH TIME ZONE            |
 /// | `time::Date`                    | DATE                                |
 /// | `time::Time`                    | TIME                                |
 /// | `eui48::MacAddress`             | MACADDR                             |
+/// | `macaddr::MacAddr6`             | MACADDR                             |
+/// | `macaddr::MacAddr8`             | MACADDR8                            |
 /// | `geo_types::Point<f64>`         | POINT                               |
 /// | `geo_types::Rect<f64>`          | BOX                                 |
 /// | `geo_types::LineString<f64>`    | PATH                                |
 /// | `serde_json::Value`             | JSON, JSONB                         |
+/// | `&serde_json::value::RawValue`  | JSON, JSONB                         |
+/// | `simd_json::OwnedValue`         | JSON, JSONB                         |
+/// | `simd_json::BorrowedValue`      | JSON, JSONB                         |
 /// | `uuid::Uuid`                    | UUID                                |
 /// | `bit_vec::BitVec`               | BIT, VARBIT                         |
+/// | `bitvec::vec::BitVec`           | BIT, VARBIT                         |
 /// | `eui48::MacAddress`             | MACADDR                             |
+/// | `smol_str::SmolStr`             | VARCHAR, CHAR(n), TEXT, CITEXT,     |
+/// |                                 | NAME, UNKNOWN, LTREE, LQUERY,       |
+/// |                                 | LTXTQUERY                           |
+/// | `compact_str::CompactString`    | VARCHAR, CHAR(n), TEXT, CITEXT,     |
+/// |                                 | NAME, UNKNOWN, LTREE, LQUERY,       |
+/// |                                 | LTXTQUERY                           |
 ///
 /// # Nullability
 ///
@@ -891,17 +1120,24 @@ pub enum IsNull {
 /// `Option<T>` where `T` implements `ToSql`. An `Option<T>` represents a
 /// nullable Postgres value.
 ///
+/// # Infinity
+///
+/// The special `infinity` and `-infinity` date and time values can be sent by
+/// wrapping the types above in [`Date<T>`] or [`Timestamp<T>`].
+///
 /// # Arrays
 ///
-/// `ToSql` is implemented for `[u8; N]`, `Vec<T>`, `&[T]`, `Box<[T]>` and `[T; N]`
-/// where `T` implements `ToSql` and `N` is const usize, and corresponds to one-dimensional
-/// Postgres arrays with an index offset of 1.
+/// `ToSql` is implemented for `[u8; N]`, `Vec<T>`, `&[T]`, `Box<[T]>`, `[T; N]` and
+/// `smallvec::SmallVec<[T; N]>` where `T` implements `ToSql` and `N` is const usize, and corresponds to one-dimensional
+/// Postgres arrays with an index offset of 1. `PgArray<T>` can be used to send
+/// multi-dimensional arrays or arrays with other lower bounds.
 /// To make conversion work correctly for `WHERE ... IN` clauses, for example
 /// `WHERE col IN ($1)`, you may instead have to use the construct
 /// `WHERE col = ANY ($1)` which expects an array.
 ///
 /// **Note:** the impl for arrays only exist when the Cargo feature `array-impls`
-/// is enabled.
+/// is enabled, and the impl for `SmallVec` only exists when the Cargo feature
+/// `with-smallvec-1` is enabled.
 pub trait ToSql: fmt::Debug {
     /// Converts the value of `self` into the binary format of the specified
     /// Postgres `Type`, appending it to `out`.
@@ -1135,6 +1371,18 @@ impl ToSql for Vec<u8> {
     to_sql_checked!();
 }
 
+impl ToSql for Bytes {
+    fn to_sql(&self, ty: &Type, w: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        <&[u8] as ToSql>::to_sql(&&**self, ty, w)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        <&[u8] as ToSql>::accepts(ty)
+    }
+
+    to_sql_checked!();
+}
+
 impl ToSql for &str {
     fn to_sql(&self, ty: &Type, w: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
         match ty.name() {
@@ -0,0 +1,98 @@
+//! Types from the `ltree` extension.
+
+use bytes::BytesMut;
+use postgres_protocol::types;
+use std::error::Error;
+use std::fmt;
+
+use crate::{FromSql, IsNull, ToSql, Type};
+
+macro_rules! ltree_type {
+    ($(#[$attr:meta])* $name:ident, $sql_name:literal, $from_sql:ident, $to_sql:ident) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub struct $name(pub String);
+
+        impl From<String> for $name {
+            fn from(s: String) -> $name {
+                $name(s)
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(s: &str) -> $name {
+                $name(s.to_string())
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl<'a> FromSql<'a> for $name {
+            fn from_sql(_: &Type, raw: &'a [u8]) -> Result<$name, Box<dyn Error + Sync + Send>> {
+                types::$from_sql(raw).map(|s| $name(s.to_string()))
+            }
+
+            fn accepts(ty: &Type) -> bool {
+                ty.name() == $sql_name
+            }
+        }
+
+        impl ToSql for $name {
+            fn to_sql(
+                &self,
+                _: &Type,
+                out: &mut BytesMut,
+            ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+                types::$to_sql(&self.0, out);
+                Ok(IsNull::No)
+            }
+
+            fn accepts(ty: &Type) -> bool {
+                ty.name() == $sql_name
+            }
+
+            to_sql_checked!();
+        }
+    };
+}
+
+ltree_type!(
+    /// Postgres `ltree` type, a label path like `Top.Science.Astronomy`.
+    LTree,
+    "ltree",
+    ltree_from_sql,
+    ltree_to_sql
+);
+
+ltree_type!(
+    /// Postgres `lquery` type, a pattern matching `ltree` values like `*.Astronomy.*`.
+    LQuery,
+    "lquery",
+    lquery_from_sql,
+    lquery_to_sql
+);
+
+ltree_type!(
+    /// Postgres `ltxtquery` type, a full-text-search-like pattern matching `ltree` values like `Europe & Russia*`.
+    LTxtQuery,
+    "ltxtquery",
+    ltxtquery_from_sql,
+    ltxtquery_to_sql
+);
+
+impl LTree {
+    /// Returns an iterator over the labels of the path.
+    pub fn labels(&self) -> impl Iterator<Item = &str> {
+        self.0.split('.').filter(|label| !label.is_empty())
+    }
+}
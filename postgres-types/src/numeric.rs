@@ -0,0 +1,536 @@
+//! Arbitrary precision `NUMERIC` values.
+
+use bytes::{Buf, BufMut, BytesMut};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{FromSql, IsNull, ToSql, Type};
+
+const NBASE: u128 = 10000;
+const DEC_DIGITS: usize = 4;
+const MAX_SCALE: u16 = 0x3fff;
+
+const SIGN_POS: u16 = 0x0000;
+const SIGN_NEG: u16 = 0x4000;
+const SIGN_NAN: u16 = 0xc000;
+const SIGN_PINF: u16 = 0xd000;
+const SIGN_NINF: u16 = 0xf000;
+
+/// The sign of a `PgNumeric`, which also distinguishes its special values.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum NumericSign {
+    /// A positive value, or zero.
+    Positive,
+    /// A negative value.
+    Negative,
+    /// `NaN`.
+    NaN,
+    /// `Infinity`.
+    PositiveInfinity,
+    /// `-Infinity`.
+    NegativeInfinity,
+}
+
+/// Postgres `NUMERIC` type.
+///
+/// Values are stored in the same form as Postgres's binary representation: a sign, a list of base-10000 digits, the
+/// weight of the first digit (the power of 10000 it is multiplied by), and the display scale (the number of decimal
+/// digits shown after the decimal point). Leading and trailing zero digits are not stored.
+///
+/// Equality compares the representation rather than the numeric value, so `1.0` and `1.00` are not equal.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct PgNumeric {
+    sign: NumericSign,
+    weight: i16,
+    scale: u16,
+    digits: Vec<i16>,
+}
+
+/// Error parsing a `PgNumeric`.
+#[derive(Debug)]
+pub struct ParseNumericError(());
+
+impl fmt::Display for ParseNumericError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid numeric value")
+    }
+}
+
+impl Error for ParseNumericError {}
+
+/// Error converting a `PgNumeric` to an integer.
+#[derive(Debug)]
+pub struct TryFromNumericError(());
+
+impl fmt::Display for TryFromNumericError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("numeric value is not representable by the integer type")
+    }
+}
+
+impl Error for TryFromNumericError {}
+
+impl PgNumeric {
+    /// Creates a `PgNumeric` from its raw representation.
+    ///
+    /// Returns `None` if a digit is outside the range `0..10000` or the scale is greater than 16383. Leading and
+    /// trailing zero digits are removed, adjusting the weight accordingly. The weight and digits are ignored for the
+    /// special values.
+    pub fn new(sign: NumericSign, weight: i16, scale: u16, digits: Vec<i16>) -> Option<PgNumeric> {
+        if scale > MAX_SCALE || digits.iter().any(|d| !(0..10000).contains(d)) {
+            return None;
+        }
+
+        match sign {
+            NumericSign::Positive | NumericSign::Negative => {}
+            sign => return Some(PgNumeric::special(sign)),
+        }
+
+        let Some(start) = digits.iter().position(|&d| d != 0) else {
+            return Some(PgNumeric {
+                sign: NumericSign::Positive,
+                weight: 0,
+                scale,
+                digits: vec![],
+            });
+        };
+        let end = digits.iter().rposition(|&d| d != 0).unwrap() + 1;
+        let weight = i16::try_from(i32::from(weight) - start as i32).ok()?;
+
+        Some(PgNumeric {
+            sign,
+            weight,
+            scale,
+            digits: digits[start..end].to_vec(),
+        })
+    }
+
+    /// Returns `NaN`.
+    pub fn nan() -> PgNumeric {
+        PgNumeric::special(NumericSign::NaN)
+    }
+
+    /// Returns `Infinity`.
+    ///
+    /// Infinite values are supported by Postgres 14 and newer.
+    pub fn infinity() -> PgNumeric {
+        PgNumeric::special(NumericSign::PositiveInfinity)
+    }
+
+    /// Returns `-Infinity`.
+    ///
+    /// Infinite values are supported by Postgres 14 and newer.
+    pub fn neg_infinity() -> PgNumeric {
+        PgNumeric::special(NumericSign::NegativeInfinity)
+    }
+
+    fn special(sign: NumericSign) -> PgNumeric {
+        PgNumeric {
+            sign,
+            weight: 0,
+            scale: 0,
+            digits: vec![],
+        }
+    }
+
+    /// Returns the sign of the value.
+    pub fn sign(&self) -> NumericSign {
+        self.sign
+    }
+
+    /// Returns the weight of the first digit.
+    ///
+    /// The value of the first digit is `digit * 10000^weight`.
+    pub fn weight(&self) -> i16 {
+        self.weight
+    }
+
+    /// Returns the display scale, the number of decimal digits after the decimal point.
+    pub fn scale(&self) -> u16 {
+        self.scale
+    }
+
+    /// Returns the base-10000 digits of the value, most significant first.
+    pub fn digits(&self) -> &[i16] {
+        &self.digits
+    }
+
+    /// Determines if the value is `NaN`.
+    pub fn is_nan(&self) -> bool {
+        self.sign == NumericSign::NaN
+    }
+
+    /// Determines if the value is `Infinity` or `-Infinity`.
+    pub fn is_infinite(&self) -> bool {
+        matches!(
+            self.sign,
+            NumericSign::PositiveInfinity | NumericSign::NegativeInfinity
+        )
+    }
+
+    fn from_magnitude(negative: bool, mut magnitude: u128) -> PgNumeric {
+        let mut digits = vec![];
+        while magnitude != 0 {
+            digits.push((magnitude % NBASE) as i16);
+            magnitude /= NBASE;
+        }
+        digits.reverse();
+        let weight = digits.len() as i16 - 1;
+
+        let sign = if negative {
+            NumericSign::Negative
+        } else {
+            NumericSign::Positive
+        };
+        PgNumeric::new(sign, weight, 0, digits).unwrap()
+    }
+
+    /// Returns the sign and magnitude of an integral value.
+    fn to_magnitude(&self) -> Result<(bool, u128), TryFromNumericError> {
+        let negative = match self.sign {
+            NumericSign::Positive => false,
+            NumericSign::Negative => true,
+            _ => return Err(TryFromNumericError(())),
+        };
+        if self.digits.len() as i32 > i32::from(self.weight) + 1 {
+            return Err(TryFromNumericError(()));
+        }
+
+        let mut magnitude = 0u128;
+        for i in 0..=i32::from(self.weight) {
+            let digit = self.digits.get(i as usize).copied().unwrap_or(0);
+            magnitude = magnitude
+                .checked_mul(NBASE)
+                .and_then(|m| m.checked_add(digit as u128))
+                .ok_or(TryFromNumericError(()))?;
+        }
+
+        Ok((negative, magnitude))
+    }
+}
+
+impl FromStr for PgNumeric {
+    type Err = ParseNumericError;
+
+    fn from_str(s: &str) -> Result<PgNumeric, ParseNumericError> {
+        let s = s.trim();
+        let (negative, unsigned) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+
+        if unsigned.eq_ignore_ascii_case("nan") && unsigned.len() == s.len() {
+            return Ok(PgNumeric::nan());
+        }
+        if unsigned.eq_ignore_ascii_case("infinity") || unsigned.eq_ignore_ascii_case("inf") {
+            return Ok(if negative {
+                PgNumeric::neg_infinity()
+            } else {
+                PgNumeric::infinity()
+            });
+        }
+
+        let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+            Some(i) => {
+                let exponent = unsigned[i + 1..]
+                    .parse::<i32>()
+                    .map_err(|_| ParseNumericError(()))?;
+                (&unsigned[..i], exponent)
+            }
+            None => (unsigned, 0),
+        };
+        let (int, frac) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        if (int.is_empty() && frac.is_empty())
+            || !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit())
+        {
+            return Err(ParseNumericError(()));
+        }
+
+        // the position of the decimal point within the decimal digits
+        let point = (int.len() as i64)
+            .checked_add(i64::from(exponent))
+            .ok_or(ParseNumericError(()))?;
+        let decimals = int.bytes().chain(frac.bytes()).map(|b| i16::from(b - b'0'));
+        let scale = (int.len() + frac.len()) as i64 - point;
+        let scale = u16::try_from(scale.max(0)).map_err(|_| ParseNumericError(()))?;
+
+        // pad the digits on the left so that the decimal point falls on a digit boundary
+        let pad = point.rem_euclid(DEC_DIGITS as i64);
+        let pad = if pad == 0 { 0 } else { DEC_DIGITS as i64 - pad };
+        let weight = (point + pad) / DEC_DIGITS as i64 - 1;
+        let weight = i16::try_from(weight).map_err(|_| ParseNumericError(()))?;
+
+        let decimals = (0..pad).map(|_| 0).chain(decimals).collect::<Vec<_>>();
+        let digits = decimals
+            .chunks(DEC_DIGITS)
+            .map(|chunk| {
+                let mut digit = 0;
+                for i in 0..DEC_DIGITS {
+                    digit = digit * 10 + chunk.get(i).copied().unwrap_or(0);
+                }
+                digit
+            })
+            .collect();
+
+        let sign = if negative {
+            NumericSign::Negative
+        } else {
+            NumericSign::Positive
+        };
+        PgNumeric::new(sign, weight, scale, digits).ok_or(ParseNumericError(()))
+    }
+}
+
+impl fmt::Display for PgNumeric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.sign {
+            NumericSign::NaN => return f.write_str("NaN"),
+            NumericSign::PositiveInfinity => return f.write_str("Infinity"),
+            NumericSign::NegativeInfinity => return f.write_str("-Infinity"),
+            NumericSign::Negative => f.write_str("-")?,
+            NumericSign::Positive => {}
+        }
+
+        let digit = |i: i32| {
+            usize::try_from(i)
+                .ok()
+                .and_then(|i| self.digits.get(i))
+                .copied()
+                .unwrap_or(0)
+        };
+
+        if self.weight < 0 {
+            f.write_str("0")?;
+        } else {
+            write!(f, "{}", digit(0))?;
+            for i in 1..=i32::from(self.weight) {
+                write!(f, "{:04}", digit(i))?;
+            }
+        }
+
+        if self.scale > 0 {
+            f.write_str(".")?;
+            let mut remaining = usize::from(self.scale);
+            let mut i = i32::from(self.weight) + 1;
+            while remaining > 0 {
+                let s = format!("{:04}", digit(i));
+                let n = remaining.min(DEC_DIGITS);
+                f.write_str(&s[..n])?;
+                remaining -= n;
+                i += 1;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for PgNumeric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+macro_rules! from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for PgNumeric {
+                fn from(v: $t) -> PgNumeric {
+                    PgNumeric::from_magnitude(v < 0, v.unsigned_abs() as u128)
+                }
+            }
+
+            impl TryFrom<PgNumeric> for $t {
+                type Error = TryFromNumericError;
+
+                fn try_from(v: PgNumeric) -> Result<$t, TryFromNumericError> {
+                    let (negative, magnitude) = v.to_magnitude()?;
+                    let v = if negative {
+                        if magnitude > i128::MIN.unsigned_abs() {
+                            return Err(TryFromNumericError(()));
+                        }
+                        (magnitude as i128).wrapping_neg()
+                    } else {
+                        i128::try_from(magnitude).map_err(|_| TryFromNumericError(()))?
+                    };
+                    <$t>::try_from(v).map_err(|_| TryFromNumericError(()))
+                }
+            }
+        )*
+    };
+}
+
+from_int!(i8, i16, i32, i64, i128);
+
+macro_rules! from_uint {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for PgNumeric {
+                fn from(v: $t) -> PgNumeric {
+                    PgNumeric::from_magnitude(false, v as u128)
+                }
+            }
+
+            impl TryFrom<PgNumeric> for $t {
+                type Error = TryFromNumericError;
+
+                fn try_from(v: PgNumeric) -> Result<$t, TryFromNumericError> {
+                    match v.to_magnitude()? {
+                        (false, magnitude) => {
+                            <$t>::try_from(magnitude).map_err(|_| TryFromNumericError(()))
+                        }
+                        (true, _) => Err(TryFromNumericError(())),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+from_uint!(u8, u16, u32, u64, u128);
+
+macro_rules! from_float {
+    ($($t:ty),*) => {
+        $(
+            /// The conversion uses the shortest decimal representation which round-trips to the same value.
+            impl From<$t> for PgNumeric {
+                fn from(v: $t) -> PgNumeric {
+                    if v.is_nan() {
+                        PgNumeric::nan()
+                    } else if v == <$t>::INFINITY {
+                        PgNumeric::infinity()
+                    } else if v == <$t>::NEG_INFINITY {
+                        PgNumeric::neg_infinity()
+                    } else {
+                        v.to_string().parse().unwrap()
+                    }
+                }
+            }
+
+            /// The conversion rounds to the nearest representable value.
+            impl From<PgNumeric> for $t {
+                fn from(v: PgNumeric) -> $t {
+                    v.to_string().parse().unwrap()
+                }
+            }
+        )*
+    };
+}
+
+from_float!(f32, f64);
+
+impl<'a> FromSql<'a> for PgNumeric {
+    fn from_sql(_: &Type, mut raw: &'a [u8]) -> Result<PgNumeric, Box<dyn Error + Sync + Send>> {
+        if raw.len() < 8 {
+            return Err("invalid buffer size".into());
+        }
+        let ndigits = raw.get_i16();
+        let weight = raw.get_i16();
+        let sign = match raw.get_u16() {
+            SIGN_POS => NumericSign::Positive,
+            SIGN_NEG => NumericSign::Negative,
+            SIGN_NAN => NumericSign::NaN,
+            SIGN_PINF => NumericSign::PositiveInfinity,
+            SIGN_NINF => NumericSign::NegativeInfinity,
+            _ => return Err("invalid numeric sign".into()),
+        };
+        let scale = raw.get_u16();
+
+        let ndigits = usize::try_from(ndigits).map_err(|_| "invalid digit count")?;
+        if raw.len() != ndigits * 2 {
+            return Err("invalid buffer size".into());
+        }
+        let digits = (0..ndigits).map(|_| raw.get_i16()).collect();
+
+        PgNumeric::new(sign, weight, scale, digits).ok_or_else(|| "invalid numeric digits".into())
+    }
+
+    accepts!(NUMERIC);
+}
+
+impl ToSql for PgNumeric {
+    fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        let sign = match self.sign {
+            NumericSign::Positive => SIGN_POS,
+            NumericSign::Negative => SIGN_NEG,
+            NumericSign::NaN => SIGN_NAN,
+            NumericSign::PositiveInfinity => SIGN_PINF,
+            NumericSign::NegativeInfinity => SIGN_NINF,
+        };
+        let ndigits =
+            i16::try_from(self.digits.len()).map_err(|_| "value too large to transmit")?;
+
+        out.put_i16(ndigits);
+        out.put_i16(self.weight);
+        out.put_u16(sign);
+        out.put_u16(self.scale);
+        for &digit in &self.digits {
+            out.put_i16(digit);
+        }
+        Ok(IsNull::No)
+    }
+
+    accepts!(NUMERIC);
+
+    to_sql_checked!();
+}
+
+/// Accepts `BIGINT` and `NUMERIC` values. Returns an error if the value is negative or, for `NUMERIC`, is not an
+/// integer or is larger than `u64::MAX`.
+impl<'a> FromSql<'a> for u64 {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<u64, Box<dyn Error + Sync + Send>> {
+        if *ty == Type::INT8 {
+            let v = i64::from_sql(ty, raw)?;
+            return u64::try_from(v).map_err(|_| "value out of range".into());
+        }
+
+        u64::try_from(PgNumeric::from_sql(ty, raw)?).map_err(Into::into)
+    }
+
+    accepts!(INT8, NUMERIC);
+}
+
+/// Returns an error if the value is larger than `i64::MAX` and the parameter is a `BIGINT`.
+impl ToSql for u64 {
+    fn to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        if *ty == Type::INT8 {
+            let v = i64::try_from(*self).map_err(|_| "value too large to transmit")?;
+            return v.to_sql(ty, out);
+        }
+
+        PgNumeric::from(*self).to_sql(ty, out)
+    }
+
+    accepts!(INT8, NUMERIC);
+
+    to_sql_checked!();
+}
+
+/// Returns an error if the value is negative or not an integer.
+impl<'a> FromSql<'a> for u128 {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<u128, Box<dyn Error + Sync + Send>> {
+        u128::try_from(PgNumeric::from_sql(ty, raw)?).map_err(Into::into)
+    }
+
+    accepts!(NUMERIC);
+}
+
+impl ToSql for u128 {
+    fn to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        PgNumeric::from(*self).to_sql(ty, out)
+    }
+
+    accepts!(NUMERIC);
+
+    to_sql_checked!();
+}
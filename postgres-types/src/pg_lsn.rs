@@ -10,13 +10,21 @@ use std::str::FromStr;
 use crate::{FromSql, IsNull, ToSql, Type};
 
 /// Postgres `PG_LSN` type.
-#[derive(Clone, Copy, Eq, Ord, PartialEq, PartialOrd)]
+#[derive(Clone, Copy, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct PgLsn(u64);
 
 /// Error parsing LSN.
 #[derive(Debug)]
 pub struct ParseLsnError(());
 
+impl fmt::Display for ParseLsnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid LSN")
+    }
+}
+
+impl Error for ParseLsnError {}
+
 impl From<u64> for PgLsn {
     fn from(lsn_u64: u64) -> Self {
         PgLsn(lsn_u64)
@@ -37,10 +45,10 @@ impl FromStr for PgLsn {
             return Err(ParseLsnError(()));
         };
         let (hi, lo) = (
-            u64::from_str_radix(split_hi, 16).map_err(|_| ParseLsnError(()))?,
-            u64::from_str_radix(split_lo, 16).map_err(|_| ParseLsnError(()))?,
+            u32::from_str_radix(split_hi, 16).map_err(|_| ParseLsnError(()))?,
+            u32::from_str_radix(split_lo, 16).map_err(|_| ParseLsnError(()))?,
         );
-        Ok(PgLsn((hi << 32) | lo))
+        Ok(PgLsn((u64::from(hi) << 32) | u64::from(lo)))
     }
 }
 
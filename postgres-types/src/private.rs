@@ -1,4 +1,4 @@
-use crate::{FromSql, Type};
+use crate::{FromSql, Kind, Oid, Type};
 pub use bytes::BytesMut;
 use std::error::Error;
 
@@ -31,3 +31,36 @@ where
     };
     T::from_sql_nullable(type_, value)
 }
+
+pub fn normalize_name(name: &str, ignore_case: bool, ignore_underscores: bool) -> String {
+    let name = if ignore_underscores {
+        name.replace('_', "")
+    } else {
+        name.to_string()
+    };
+    if ignore_case {
+        name.to_lowercase()
+    } else {
+        name
+    }
+}
+
+pub fn read_field_type(
+    type_: &Type,
+    index: usize,
+    buf: &mut &[u8],
+) -> Result<Type, Box<dyn Error + Sync + Send>> {
+    let oid = read_be_i32(buf)? as Oid;
+    match type_.kind() {
+        Kind::Composite(fields) => {
+            let field = fields.get(index).ok_or("invalid field count")?;
+            if field.type_().oid() != oid {
+                return Err("unexpected OID".into());
+            }
+            Ok(field.type_().clone())
+        }
+        _ => {
+            Type::from_oid(oid).ok_or_else(|| format!("unsupported record field OID {oid}").into())
+        }
+    }
+}
@@ -0,0 +1,192 @@
+//! Range types.
+
+use bytes::BytesMut;
+use postgres_protocol::types;
+use std::error::Error;
+use std::ops::{self, Bound};
+
+use crate::{FromSql, IsNull, Kind, ToSql, Type};
+
+/// A Postgres range value, such as an `INT4RANGE`, `TSTZRANGE`, or a custom range type.
+///
+/// Postgres canonicalizes the bounds of ranges over discrete types like integers and dates to the form `[lower,
+/// upper)`, so a range sent as `[1, 10]` is returned as `[1, 11)`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PgRange<T> {
+    /// An empty range.
+    Empty,
+    /// A nonempty range, with its lower and upper bounds.
+    Nonempty(Bound<T>, Bound<T>),
+}
+
+impl<T> PgRange<T> {
+    /// Creates a range with the specified bounds.
+    pub fn new(lower: Bound<T>, upper: Bound<T>) -> PgRange<T> {
+        PgRange::Nonempty(lower, upper)
+    }
+
+    /// Determines if the range is the empty range.
+    ///
+    /// Only ranges received from the server are guaranteed to be canonical. A range like `[2, 1)` created locally is
+    /// not reported as empty.
+    pub fn is_empty(&self) -> bool {
+        matches!(self, PgRange::Empty)
+    }
+
+    /// Returns the lower bound of the range, or `None` if it is empty.
+    pub fn lower(&self) -> Option<Bound<&T>> {
+        match self {
+            PgRange::Empty => None,
+            PgRange::Nonempty(lower, _) => Some(lower.as_ref()),
+        }
+    }
+
+    /// Returns the upper bound of the range, or `None` if it is empty.
+    pub fn upper(&self) -> Option<Bound<&T>> {
+        match self {
+            PgRange::Empty => None,
+            PgRange::Nonempty(_, upper) => Some(upper.as_ref()),
+        }
+    }
+
+    /// Determines if the range contains a value.
+    pub fn contains(&self, value: &T) -> bool
+    where
+        T: PartialOrd,
+    {
+        match self {
+            PgRange::Empty => false,
+            PgRange::Nonempty(lower, upper) => {
+                ops::RangeBounds::contains(&(lower.as_ref(), upper.as_ref()), value)
+            }
+        }
+    }
+}
+
+impl<T> From<ops::Range<T>> for PgRange<T> {
+    fn from(range: ops::Range<T>) -> PgRange<T> {
+        PgRange::new(Bound::Included(range.start), Bound::Excluded(range.end))
+    }
+}
+
+impl<T> From<ops::RangeInclusive<T>> for PgRange<T> {
+    fn from(range: ops::RangeInclusive<T>) -> PgRange<T> {
+        let (start, end) = range.into_inner();
+        PgRange::new(Bound::Included(start), Bound::Included(end))
+    }
+}
+
+impl<T> From<ops::RangeFrom<T>> for PgRange<T> {
+    fn from(range: ops::RangeFrom<T>) -> PgRange<T> {
+        PgRange::new(Bound::Included(range.start), Bound::Unbounded)
+    }
+}
+
+impl<T> From<ops::RangeTo<T>> for PgRange<T> {
+    fn from(range: ops::RangeTo<T>) -> PgRange<T> {
+        PgRange::new(Bound::Unbounded, Bound::Excluded(range.end))
+    }
+}
+
+impl<T> From<ops::RangeToInclusive<T>> for PgRange<T> {
+    fn from(range: ops::RangeToInclusive<T>) -> PgRange<T> {
+        PgRange::new(Bound::Unbounded, Bound::Included(range.end))
+    }
+}
+
+impl<T> From<ops::RangeFull> for PgRange<T> {
+    fn from(_: ops::RangeFull) -> PgRange<T> {
+        PgRange::new(Bound::Unbounded, Bound::Unbounded)
+    }
+}
+
+/// Converts a range of the form `[lower, upper)`, returning the original range if it has any other form.
+impl<T> TryFrom<PgRange<T>> for ops::Range<T> {
+    type Error = PgRange<T>;
+
+    fn try_from(range: PgRange<T>) -> Result<ops::Range<T>, PgRange<T>> {
+        match range {
+            PgRange::Nonempty(Bound::Included(start), Bound::Excluded(end)) => Ok(start..end),
+            range => Err(range),
+        }
+    }
+}
+
+impl<'a, T: FromSql<'a>> FromSql<'a> for PgRange<T> {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<PgRange<T>, Box<dyn Error + Sync + Send>> {
+        let member_type = match *ty.kind() {
+            Kind::Range(ref member) => member,
+            _ => panic!("expected range type"),
+        };
+
+        let bound = |bound| match bound {
+            types::RangeBound::Inclusive(v) => {
+                T::from_sql_nullable(member_type, v).map(Bound::Included)
+            }
+            types::RangeBound::Exclusive(v) => {
+                T::from_sql_nullable(member_type, v).map(Bound::Excluded)
+            }
+            types::RangeBound::Unbounded => Ok(Bound::Unbounded),
+        };
+
+        match types::range_from_sql(raw)? {
+            types::Range::Empty => Ok(PgRange::Empty),
+            types::Range::Nonempty(lower, upper) => Ok(PgRange::new(bound(lower)?, bound(upper)?)),
+        }
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        match *ty.kind() {
+            Kind::Range(ref inner) => T::accepts(inner),
+            _ => false,
+        }
+    }
+}
+
+impl<T: ToSql> ToSql for PgRange<T> {
+    fn to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        let member_type = match *ty.kind() {
+            Kind::Range(ref member) => member,
+            _ => panic!("expected range type"),
+        };
+
+        let (lower, upper) = match self {
+            PgRange::Empty => {
+                types::empty_range_to_sql(out);
+                return Ok(IsNull::No);
+            }
+            PgRange::Nonempty(lower, upper) => (lower, upper),
+        };
+
+        let bound = |bound: &Bound<T>, out: &mut BytesMut| {
+            let value = match bound {
+                Bound::Included(v) | Bound::Excluded(v) => v,
+                Bound::Unbounded => return Ok(types::RangeBound::Unbounded),
+            };
+            let is_null = match value.to_sql(member_type, out)? {
+                IsNull::No => postgres_protocol::IsNull::No,
+                IsNull::Yes => postgres_protocol::IsNull::Yes,
+            };
+            match bound {
+                Bound::Included(_) => Ok(types::RangeBound::Inclusive(is_null)),
+                _ => Ok(types::RangeBound::Exclusive(is_null)),
+            }
+        };
+
+        types::range_to_sql(|out| bound(lower, out), |out| bound(upper, out), out)?;
+        Ok(IsNull::No)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        match *ty.kind() {
+            Kind::Range(ref inner) => T::accepts(inner),
+            _ => false,
+        }
+    }
+
+    to_sql_checked!();
+}
@@ -0,0 +1,90 @@
+//! Object identifier alias types.
+
+use bytes::BytesMut;
+use postgres_protocol::Oid;
+use postgres_protocol::types;
+use std::error::Error;
+use std::fmt;
+
+use crate::{FromSql, IsNull, ToSql, Type};
+
+macro_rules! reg_type {
+    ($(#[$doc:meta] $name:ident, $ty:ident;)*) => {
+        $(
+            #[$doc]
+            ///
+            /// The value is sent as the object's OID. Cast it to `TEXT` in the query to get the object's name instead.
+            #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+            pub struct $name(pub Oid);
+
+            impl $name {
+                /// Returns the OID of the object.
+                pub fn oid(self) -> Oid {
+                    self.0
+                }
+            }
+
+            impl From<Oid> for $name {
+                fn from(oid: Oid) -> $name {
+                    $name(oid)
+                }
+            }
+
+            impl From<$name> for Oid {
+                fn from(v: $name) -> Oid {
+                    v.0
+                }
+            }
+
+            impl fmt::Display for $name {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    fmt::Display::fmt(&self.0, f)
+                }
+            }
+
+            impl<'a> FromSql<'a> for $name {
+                fn from_sql(_: &Type, raw: &'a [u8]) -> Result<$name, Box<dyn Error + Sync + Send>> {
+                    types::oid_from_sql(raw).map($name)
+                }
+
+                accepts!($ty);
+            }
+
+            impl ToSql for $name {
+                fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+                    types::oid_to_sql(self.0, out);
+                    Ok(IsNull::No)
+                }
+
+                accepts!($ty);
+
+                to_sql_checked!();
+            }
+        )*
+    };
+}
+
+reg_type! {
+    /// Postgres `REGCLASS` type, a relation such as a table, index or view.
+    RegClass, REGCLASS;
+    /// Postgres `REGCOLLATION` type, a collation.
+    RegCollation, REGCOLLATION;
+    /// Postgres `REGCONFIG` type, a text search configuration.
+    RegConfig, REGCONFIG;
+    /// Postgres `REGDICTIONARY` type, a text search dictionary.
+    RegDictionary, REGDICTIONARY;
+    /// Postgres `REGNAMESPACE` type, a schema.
+    RegNamespace, REGNAMESPACE;
+    /// Postgres `REGOPER` type, an operator identified by name.
+    RegOper, REGOPER;
+    /// Postgres `REGOPERATOR` type, an operator identified by name and argument types.
+    RegOperator, REGOPERATOR;
+    /// Postgres `REGPROC` type, a function identified by name.
+    RegProc, REGPROC;
+    /// Postgres `REGPROCEDURE` type, a function identified by name and argument types.
+    RegProcedure, REGPROCEDURE;
+    /// Postgres `REGROLE` type, a role.
+    RegRole, REGROLE;
+    /// Postgres `REGTYPE` type, a data type.
+    RegType, REGTYPE;
+}
@@ -0,0 +1,77 @@
+use crate::{FromSql, IsNull, ToSql, Type};
+use bytes::{BufMut, BytesMut};
+use simd_json_015::prelude::Writable;
+use simd_json_015::{BorrowedValue, OwnedValue};
+use std::error::Error;
+
+// simd-json parses its input in place, so the value is copied out of the row first.
+fn json_buf(ty: &Type, raw: &[u8]) -> Result<Vec<u8>, Box<dyn Error + Sync + Send>> {
+    let raw = if *ty == Type::JSONB {
+        let (version, rest) = raw.split_first().ok_or("invalid message length")?;
+        // We only support version 1 of the jsonb binary format
+        if *version != 1 {
+            return Err("unsupported JSONB encoding version".into());
+        }
+        rest
+    } else {
+        raw
+    };
+    Ok(raw.to_vec())
+}
+
+impl<'a> FromSql<'a> for OwnedValue {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<OwnedValue, Box<dyn Error + Sync + Send>> {
+        let mut buf = json_buf(ty, raw)?;
+        simd_json_015::to_owned_value(&mut buf).map_err(Into::into)
+    }
+
+    accepts!(JSON, JSONB);
+}
+
+impl<'a> FromSql<'a> for BorrowedValue<'a> {
+    fn from_sql(
+        ty: &Type,
+        raw: &'a [u8],
+    ) -> Result<BorrowedValue<'a>, Box<dyn Error + Sync + Send>> {
+        let mut buf = json_buf(ty, raw)?;
+        simd_json_015::to_borrowed_value(&mut buf)
+            .map(BorrowedValue::into_static)
+            .map_err(Into::into)
+    }
+
+    accepts!(JSON, JSONB);
+}
+
+impl ToSql for OwnedValue {
+    fn to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        if *ty == Type::JSONB {
+            out.put_u8(1);
+        }
+        self.write(&mut out.writer())?;
+        Ok(IsNull::No)
+    }
+
+    accepts!(JSON, JSONB);
+    to_sql_checked!();
+}
+
+impl ToSql for BorrowedValue<'_> {
+    fn to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        if *ty == Type::JSONB {
+            out.put_u8(1);
+        }
+        self.write(&mut out.writer())?;
+        Ok(IsNull::No)
+    }
+
+    accepts!(JSON, JSONB);
+    to_sql_checked!();
+}
@@ -0,0 +1,56 @@
+use bytes::BytesMut;
+use fallible_iterator::FallibleIterator;
+use postgres_protocol::types;
+use smallvec_1::{Array, SmallVec};
+use std::error::Error;
+
+use crate::{FromSql, IsNull, Kind, ToSql, Type};
+
+impl<'a, A> FromSql<'a> for SmallVec<A>
+where
+    A: Array,
+    A::Item: FromSql<'a>,
+{
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<SmallVec<A>, Box<dyn Error + Sync + Send>> {
+        let member_type = match *ty.kind() {
+            Kind::Array(ref member) => member,
+            _ => panic!("expected array type"),
+        };
+
+        let array = types::array_from_sql(raw)?;
+        if array.dimensions().count()? > 1 {
+            return Err("array contains too many dimensions".into());
+        }
+
+        let mut out = SmallVec::new();
+        let mut values = array.values();
+        while let Some(v) = values.next()? {
+            out.push(A::Item::from_sql_nullable(member_type, v)?);
+        }
+
+        Ok(out)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        match *ty.kind() {
+            Kind::Array(ref inner) => A::Item::accepts(inner),
+            _ => false,
+        }
+    }
+}
+
+impl<A> ToSql for SmallVec<A>
+where
+    A: Array,
+    A::Item: ToSql,
+{
+    fn to_sql(&self, ty: &Type, w: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        <&[A::Item] as ToSql>::to_sql(&&**self, ty, w)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        <&[A::Item] as ToSql>::accepts(ty)
+    }
+
+    to_sql_checked!();
+}
@@ -0,0 +1,73 @@
+//! The transaction snapshot type.
+
+use bytes::{Buf, BufMut, BytesMut};
+use std::error::Error;
+
+use crate::{FromSql, IsNull, ToSql, Type};
+
+/// Postgres `PG_SNAPSHOT` type, a snapshot of the transaction IDs in progress, as returned by
+/// `pg_current_snapshot()`.
+///
+/// The older `TXID_SNAPSHOT` type has the same representation and is also supported.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PgSnapshot {
+    /// The earliest transaction ID still active. Earlier transactions are either committed and visible, or rolled
+    /// back.
+    pub xmin: u64,
+    /// One past the latest completed transaction ID. Transactions from this one on are not visible.
+    pub xmax: u64,
+    /// The transaction IDs between `xmin` and `xmax` which were active at the time of the snapshot, in ascending
+    /// order.
+    pub xip: Vec<u64>,
+}
+
+impl PgSnapshot {
+    /// Determines if a transaction ID is visible in the snapshot, like Postgres's `pg_visible_in_snapshot()`.
+    pub fn is_visible(&self, xid: u64) -> bool {
+        if xid < self.xmin {
+            true
+        } else if xid >= self.xmax {
+            false
+        } else {
+            self.xip.binary_search(&xid).is_err()
+        }
+    }
+}
+
+impl<'a> FromSql<'a> for PgSnapshot {
+    fn from_sql(_: &Type, mut raw: &'a [u8]) -> Result<PgSnapshot, Box<dyn Error + Sync + Send>> {
+        if raw.len() < 20 {
+            return Err("invalid message length".into());
+        }
+        let nxip = raw.get_i32();
+        let xmin = raw.get_u64();
+        let xmax = raw.get_u64();
+
+        let nxip = usize::try_from(nxip).map_err(|_| "invalid snapshot size")?;
+        if raw.len() != nxip * 8 {
+            return Err("invalid message length".into());
+        }
+        let xip = (0..nxip).map(|_| raw.get_u64()).collect();
+
+        Ok(PgSnapshot { xmin, xmax, xip })
+    }
+
+    accepts!(PG_SNAPSHOT, TXID_SNAPSHOT);
+}
+
+impl ToSql for PgSnapshot {
+    fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        out.put_i32(i32::try_from(self.xip.len()).map_err(|_| "value too large to transmit")?);
+        out.put_u64(self.xmin);
+        out.put_u64(self.xmax);
+        for &xid in &self.xip {
+            out.put_u64(xid);
+        }
+
+        Ok(IsNull::No)
+    }
+
+    accepts!(PG_SNAPSHOT, TXID_SNAPSHOT);
+
+    to_sql_checked!();
+}
@@ -0,0 +1,340 @@
+//! Full text search types.
+
+use bytes::{BufMut, BytesMut};
+use std::error::Error;
+
+use crate::{FromSql, IsNull, ToSql, Type};
+
+const MAX_POSITION: u16 = 0x3fff;
+
+const QI_VAL: u8 = 1;
+const QI_OPR: u8 = 2;
+
+const OP_NOT: u8 = 1;
+const OP_AND: u8 = 2;
+const OP_OR: u8 = 3;
+const OP_PHRASE: u8 = 4;
+
+/// The weight of a lexeme position, used to mark words from different parts of a document.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TsWeight {
+    /// Weight `A`, the highest.
+    A,
+    /// Weight `B`.
+    B,
+    /// Weight `C`.
+    C,
+    /// Weight `D`, the lowest and the default.
+    D,
+}
+
+impl TsWeight {
+    const ALL: [TsWeight; 4] = [TsWeight::A, TsWeight::B, TsWeight::C, TsWeight::D];
+
+    // the value stored in the top two bits of a position
+    fn position_bits(self) -> u16 {
+        match self {
+            TsWeight::A => 3,
+            TsWeight::B => 2,
+            TsWeight::C => 1,
+            TsWeight::D => 0,
+        }
+    }
+
+    // the bit representing the weight in a query operand's weight mask
+    fn mask_bit(self) -> u8 {
+        1 << self.position_bits()
+    }
+}
+
+/// A position of a lexeme within a document.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct LexemePosition {
+    /// The position, between 1 and 16383.
+    pub position: u16,
+    /// The weight of the position.
+    pub weight: TsWeight,
+}
+
+/// A lexeme of a `TsVector`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Lexeme {
+    /// The normalized word.
+    pub word: String,
+    /// The positions of the word within the document, if known.
+    pub positions: Vec<LexemePosition>,
+}
+
+/// Postgres `TSVECTOR` type, a document preprocessed for text search.
+///
+/// Postgres sorts the lexemes and removes duplicates, and positions are sent in ascending order.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TsVector {
+    /// The lexemes of the document.
+    pub lexemes: Vec<Lexeme>,
+}
+
+/// Postgres `TSQUERY` type, a text search query.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TsQuery {
+    /// A query with no lexemes, as produced when a query contains only stop words.
+    ///
+    /// Postgres does not accept empty queries in the binary format, so this value cannot be used as a parameter.
+    Empty,
+    /// A lexeme to match.
+    Lexeme {
+        /// The normalized word.
+        word: String,
+        /// The weights of the positions the lexeme must have to match. Any weight matches if empty.
+        weights: Vec<TsWeight>,
+        /// Determines if the word matches as a prefix of a lexeme, as in `word:*`.
+        prefix: bool,
+    },
+    /// The `!` operator.
+    Not(Box<TsQuery>),
+    /// The `&` operator.
+    And(Box<TsQuery>, Box<TsQuery>),
+    /// The `|` operator.
+    Or(Box<TsQuery>, Box<TsQuery>),
+    /// The `<N>` operator, matching the second query `N` positions after the first. `<->` has a distance of 1.
+    Phrase(Box<TsQuery>, Box<TsQuery>, u16),
+}
+
+impl<'a> FromSql<'a> for TsVector {
+    fn from_sql(_: &Type, mut raw: &'a [u8]) -> Result<TsVector, Box<dyn Error + Sync + Send>> {
+        let count = read_u32(&mut raw)?;
+        let mut lexemes = Vec::with_capacity(count.min(1024) as usize);
+        for _ in 0..count {
+            let word = read_cstr(&mut raw)?;
+            let npos = read_u16(&mut raw)?;
+            let positions = (0..npos)
+                .map(|_| {
+                    let pos = read_u16(&mut raw)?;
+                    let weight = TsWeight::ALL
+                        .into_iter()
+                        .find(|w| w.position_bits() == pos >> 14)
+                        .unwrap();
+                    Ok(LexemePosition {
+                        position: pos & MAX_POSITION,
+                        weight,
+                    })
+                })
+                .collect::<Result<_, Box<dyn Error + Sync + Send>>>()?;
+            lexemes.push(Lexeme { word, positions });
+        }
+
+        if !raw.is_empty() {
+            return Err("invalid message length".into());
+        }
+
+        Ok(TsVector { lexemes })
+    }
+
+    accepts!(TS_VECTOR);
+}
+
+impl ToSql for TsVector {
+    fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        out.put_u32(u32::try_from(self.lexemes.len())?);
+        for lexeme in &self.lexemes {
+            write_cstr(&lexeme.word, out)?;
+
+            let mut positions = lexeme
+                .positions
+                .iter()
+                .map(|p| {
+                    if p.position == 0 || p.position > MAX_POSITION {
+                        return Err(format!("invalid lexeme position {}", p.position));
+                    }
+                    Ok(p.weight.position_bits() << 14 | p.position)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            positions.sort_unstable_by_key(|p| p & MAX_POSITION);
+
+            out.put_u16(u16::try_from(positions.len())?);
+            for position in positions {
+                out.put_u16(position);
+            }
+        }
+
+        Ok(IsNull::No)
+    }
+
+    accepts!(TS_VECTOR);
+
+    to_sql_checked!();
+}
+
+/// A `TsQuery` item, in the order they are sent.
+enum Item {
+    Operand(TsQuery),
+    Operator(u8, u16),
+}
+
+impl<'a> FromSql<'a> for TsQuery {
+    fn from_sql(_: &Type, mut raw: &'a [u8]) -> Result<TsQuery, Box<dyn Error + Sync + Send>> {
+        let count = read_u32(&mut raw)?;
+        let mut items = Vec::with_capacity(count.min(1024) as usize);
+        for _ in 0..count {
+            let item = match read_u8(&mut raw)? {
+                QI_VAL => {
+                    let mask = read_u8(&mut raw)?;
+                    let prefix = read_u8(&mut raw)? != 0;
+                    let word = read_cstr(&mut raw)?;
+                    let weights = TsWeight::ALL
+                        .into_iter()
+                        .filter(|w| mask & w.mask_bit() != 0)
+                        .collect();
+                    Item::Operand(TsQuery::Lexeme {
+                        word,
+                        weights,
+                        prefix,
+                    })
+                }
+                QI_OPR => {
+                    let oper = read_u8(&mut raw)?;
+                    let distance = match oper {
+                        OP_PHRASE => read_u16(&mut raw)?,
+                        OP_NOT | OP_AND | OP_OR => 0,
+                        _ => return Err(format!("invalid tsquery operator {oper}").into()),
+                    };
+                    Item::Operator(oper, distance)
+                }
+                ty => return Err(format!("invalid tsquery item type {ty}").into()),
+            };
+            items.push(item);
+        }
+
+        if !raw.is_empty() {
+            return Err("invalid message length".into());
+        }
+        if items.is_empty() {
+            return Ok(TsQuery::Empty);
+        }
+
+        let mut items = items.into_iter();
+        let query = build_query(&mut items)?;
+        if items.next().is_some() {
+            return Err("malformed tsquery".into());
+        }
+
+        Ok(query)
+    }
+
+    accepts!(TSQUERY);
+}
+
+// Items are sent in prefix order, with the right operand of binary operators before the left.
+fn build_query(
+    items: &mut impl Iterator<Item = Item>,
+) -> Result<TsQuery, Box<dyn Error + Sync + Send>> {
+    let (oper, distance) = match items.next().ok_or("malformed tsquery")? {
+        Item::Operand(query) => return Ok(query),
+        Item::Operator(oper, distance) => (oper, distance),
+    };
+
+    let right = Box::new(build_query(items)?);
+    if oper == OP_NOT {
+        return Ok(TsQuery::Not(right));
+    }
+    let left = Box::new(build_query(items)?);
+
+    match oper {
+        OP_AND => Ok(TsQuery::And(left, right)),
+        OP_OR => Ok(TsQuery::Or(left, right)),
+        _ => Ok(TsQuery::Phrase(left, right, distance)),
+    }
+}
+
+impl ToSql for TsQuery {
+    fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        if *self == TsQuery::Empty {
+            return Err("empty tsquery values cannot be sent to Postgres".into());
+        }
+
+        let base = out.len();
+        out.put_u32(0);
+        let count = write_query(self, out)?;
+        out[base..base + 4].copy_from_slice(&count.to_be_bytes());
+
+        Ok(IsNull::No)
+    }
+
+    accepts!(TSQUERY);
+
+    to_sql_checked!();
+}
+
+// Writes a query's items, returning the number written.
+fn write_query(query: &TsQuery, out: &mut BytesMut) -> Result<u32, Box<dyn Error + Sync + Send>> {
+    let (oper, left, right, distance) = match query {
+        TsQuery::Empty => return Ok(0),
+        TsQuery::Lexeme {
+            word,
+            weights,
+            prefix,
+        } => {
+            out.put_u8(QI_VAL);
+            out.put_u8(weights.iter().fold(0, |mask, w| mask | w.mask_bit()));
+            out.put_u8(u8::from(*prefix));
+            write_cstr(word, out)?;
+            return Ok(1);
+        }
+        TsQuery::Not(operand) => (OP_NOT, None, operand, None),
+        TsQuery::And(left, right) => (OP_AND, Some(left), right, None),
+        TsQuery::Or(left, right) => (OP_OR, Some(left), right, None),
+        TsQuery::Phrase(left, right, distance) => (OP_PHRASE, Some(left), right, Some(*distance)),
+    };
+
+    out.put_u8(QI_OPR);
+    out.put_u8(oper);
+    if let Some(distance) = distance {
+        out.put_u16(distance);
+    }
+
+    let mut count = 1;
+    for operand in Some(right).into_iter().chain(left) {
+        if **operand == TsQuery::Empty {
+            return Err("empty tsquery values cannot be used as operands".into());
+        }
+        count += write_query(operand, out)?;
+    }
+    Ok(count)
+}
+
+fn read_u8(buf: &mut &[u8]) -> Result<u8, Box<dyn Error + Sync + Send>> {
+    let (v, rest) = buf.split_first().ok_or("invalid message length")?;
+    *buf = rest;
+    Ok(*v)
+}
+
+fn read_u16(buf: &mut &[u8]) -> Result<u16, Box<dyn Error + Sync + Send>> {
+    let (v, rest) = buf.split_first_chunk().ok_or("invalid message length")?;
+    *buf = rest;
+    Ok(u16::from_be_bytes(*v))
+}
+
+fn read_u32(buf: &mut &[u8]) -> Result<u32, Box<dyn Error + Sync + Send>> {
+    let (v, rest) = buf.split_first_chunk().ok_or("invalid message length")?;
+    *buf = rest;
+    Ok(u32::from_be_bytes(*v))
+}
+
+fn read_cstr(buf: &mut &[u8]) -> Result<String, Box<dyn Error + Sync + Send>> {
+    let end = buf
+        .iter()
+        .position(|&b| b == 0)
+        .ok_or("unexpected EOF reading lexeme")?;
+    let s = std::str::from_utf8(&buf[..end])?.to_string();
+    *buf = &buf[end + 1..];
+    Ok(s)
+}
+
+fn write_cstr(s: &str, out: &mut BytesMut) -> Result<(), Box<dyn Error + Sync + Send>> {
+    if s.contains('\0') {
+        return Err("lexemes may not contain null characters".into());
+    }
+    out.put_slice(s.as_bytes());
+    out.put_u8(0);
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use crate::Error;
+use crate::connection::ConnectionRef;
+use crate::types::BorrowToSql;
+
+/// A builder for inserting many rows with multi-row `INSERT` statements.
+///
+/// See [`tokio_postgres::BulkInsert`] for details.
+pub struct BulkInsert<'a> {
+    connection: ConnectionRef<'a>,
+    builder: tokio_postgres::BulkInsert<'a>,
+}
+
+impl<'a> BulkInsert<'a> {
+    pub(crate) fn new(
+        connection: ConnectionRef<'a>,
+        builder: tokio_postgres::BulkInsert<'a>,
+    ) -> BulkInsert<'a> {
+        BulkInsert {
+            connection,
+            builder,
+        }
+    }
+
+    /// Sets an `ON CONFLICT` clause for the statements, such as `(id) DO NOTHING`.
+    ///
+    /// The clause is inserted verbatim after `ON CONFLICT`.
+    pub fn on_conflict(mut self, clause: &str) -> Self {
+        self.builder = self.builder.on_conflict(clause);
+        self
+    }
+
+    /// Sets the maximum number of rows inserted by each statement.
+    ///
+    /// Statements are also always limited to 65535 parameters. Defaults to no limit beyond that.
+    pub fn max_rows_per_statement(mut self, max_rows: usize) -> Self {
+        self.builder = self.builder.max_rows_per_statement(max_rows);
+        self
+    }
+
+    /// Inserts the rows, returning the number of rows inserted.
+    ///
+    /// Each row must contain one value for each column. Rows skipped by an `ON CONFLICT DO NOTHING` clause aren't
+    /// counted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no columns were specified.
+    pub fn execute<I, R, P>(self, rows: I) -> Result<u64, Error>
+    where
+        I: IntoIterator<Item = R>,
+        R: IntoIterator<Item = P>,
+        P: BorrowToSql,
+    {
+        let BulkInsert {
+            mut connection,
+            builder,
+        } = self;
+        connection.block_on(builder.execute(rows))
+    }
+}
@@ -1,13 +1,20 @@
 use crate::connection::Connection;
 use crate::{
-    CancelToken, Config, CopyInWriter, CopyOutReader, Notifications, RowIter, Statement,
-    ToStatement, Transaction, TransactionBuilder,
+    BulkInsert, CancelToken, Config, CopyInWriter, CopyOutReader, Notifications, RowIter,
+    Statement, ToStatement, Transaction, TransactionBuilder,
 };
+use futures_util::future::{self, Either};
+use std::pin::pin;
 use std::task::Poll;
 use std::time::Duration;
-use tokio_postgres::tls::{MakeTlsConnect, TlsConnect};
+#[cfg(feature = "explain")]
+use tokio_postgres::explain::{Explain, ExplainOptions};
+use tokio_postgres::tls::{MakeTlsConnect, TlsConnect, TlsInfo};
 use tokio_postgres::types::{BorrowToSql, ToSql, Type};
-use tokio_postgres::{Error, Row, SimpleQueryMessage, Socket};
+use tokio_postgres::{
+    CustomParam, Error, Row, SimpleQueryMessage, Socket, TransactionStatus, TypeCache, TypeHandler,
+    TypeKey,
+};
 
 /// A synchronous PostgreSQL client.
 pub struct Client {
@@ -125,6 +132,52 @@ impl Client {
             .block_on(self.client.execute_typed(query, params))
     }
 
+    /// Executes a statement once for each of several sets of parameters, returning the number of rows modified by each
+    /// execution.
+    ///
+    /// All of the executions are sent to the server together and complete in a single round trip. They run in a single
+    /// implicit transaction unless they are already inside an explicit one, so if any of them fails, none of their
+    /// changes are applied.
+    ///
+    /// See [`tokio_postgres::Client::execute_many`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use postgres::{Client, NoTls};
+    /// use postgres::types::ToSql;
+    ///
+    /// # fn main() -> Result<(), postgres::Error> {
+    /// let mut client = Client::connect("host=localhost user=postgres", NoTls)?;
+    ///
+    /// let statement = client.prepare("UPDATE people SET name = $2 WHERE id = $1")?;
+    /// let rows = client.execute_many(
+    ///     &statement,
+    ///     [
+    ///         [&1i32 as &(dyn ToSql + Sync), &"john"],
+    ///         [&2i32, &"jane"],
+    ///     ],
+    /// )?;
+    /// assert_eq!(rows.len(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn execute_many<T, I, J, P>(
+        &mut self,
+        statement: &T,
+        param_sets: I,
+    ) -> Result<Vec<u64>, Error>
+    where
+        T: ?Sized + ToStatement,
+        I: IntoIterator<Item = J>,
+        J: IntoIterator<Item = P>,
+        J::IntoIter: ExactSizeIterator,
+        P: BorrowToSql,
+    {
+        self.connection
+            .block_on(self.client.execute_many(statement, param_sets))
+    }
+
     /// Executes a statement, returning the resulting rows.
     ///
     /// A statement may contain parameters, specified by `$n`, where `n` is the index of the parameter of the list
@@ -466,6 +519,52 @@ impl Client {
             .block_on(self.client.prepare_typed(query, types))
     }
 
+    /// Runs `EXPLAIN (FORMAT JSON)` on a statement and returns its typed query plan.
+    ///
+    /// See [`tokio_postgres::Client::explain`] for details.
+    #[cfg(feature = "explain")]
+    pub fn explain(
+        &mut self,
+        query: &str,
+        params: &[&(dyn ToSql + Sync)],
+        options: ExplainOptions,
+    ) -> Result<Explain, Error> {
+        self.connection
+            .block_on(self.client.explain(query, params, options))
+    }
+
+    /// Returns a builder for inserting many rows into a table with multi-row `INSERT` statements.
+    ///
+    /// See [`tokio_postgres::Client::bulk_insert`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use postgres::{Client, NoTls};
+    /// use postgres::types::ToSql;
+    ///
+    /// # fn main() -> Result<(), postgres::Error> {
+    /// let mut client = Client::connect("host=localhost user=postgres", NoTls)?;
+    ///
+    /// let people = vec![(1i32, "john"), (2, "jane")];
+    /// let inserted = client
+    ///     .bulk_insert("people", &["id", "name"])
+    ///     .on_conflict("(id) DO NOTHING")
+    ///     .execute(
+    ///         people
+    ///             .iter()
+    ///             .map(|(id, name)| [id as &(dyn ToSql + Sync), name]),
+    ///     )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn bulk_insert(&mut self, table: &str, columns: &[&str]) -> BulkInsert<'_> {
+        BulkInsert::new(
+            self.connection.as_ref(),
+            self.client.bulk_insert(table, columns),
+        )
+    }
+
     /// Executes a `COPY FROM STDIN` statement, returning the number of rows created.
     ///
     /// The `query` argument can either be a `Statement`, or a raw query string. The data in the provided reader is
@@ -549,12 +648,13 @@ impl Client {
     /// If the specified timeout is reached before the backend responds, an error will be returned.
     pub fn is_valid(&mut self, timeout: Duration) -> Result<(), Error> {
         let inner_client = &self.client;
+        let sleep = self.connection.sleep(timeout);
         self.connection.block_on(async {
-            let trivial_query = inner_client.simple_query("");
-            tokio::time::timeout(timeout, trivial_query)
-                .await
-                .map_err(|_| Error::__private_api_timeout())?
-                .map(|_| ())
+            let trivial_query = pin!(inner_client.simple_query(""));
+            match future::select(trivial_query, sleep).await {
+                Either::Left((result, _)) => result.map(|_| ()),
+                Either::Right(_) => Err(Error::__private_api_timeout()),
+            }
         })
     }
 
@@ -577,6 +677,27 @@ impl Client {
         self.connection.block_on(self.client.check_connection())
     }
 
+    /// Sends a notification to all sessions listening on a channel.
+    ///
+    /// See the documentation of [`tokio_postgres::Client::notify`] for details.
+    pub fn notify(&mut self, channel: &str, payload: &str) -> Result<(), Error> {
+        self.connection
+            .block_on(self.client.notify(channel, payload))
+    }
+
+    /// Resets the session to a pristine state.
+    ///
+    /// This executes `DISCARD ALL` and discards any buffered notifications. The client's type information cache is
+    /// kept. Connection pools can use this to safely hand a connection to a new user.
+    ///
+    /// Any `Statement`s prepared on this client before the reset can no longer be used. `DISCARD ALL` cannot be
+    /// executed inside of a transaction block.
+    pub fn reset(&mut self) -> Result<(), Error> {
+        self.connection.block_on(self.client.reset())?;
+        self.connection.notifications_mut().clear();
+        Ok(())
+    }
+
     /// Begins a new database transaction.
     ///
     /// The transaction will roll back by default - use the `commit` method to commit it.
@@ -626,7 +747,44 @@ impl Client {
     /// # }
     /// ```
     pub fn build_transaction(&mut self) -> TransactionBuilder<'_> {
-        TransactionBuilder::new(self.connection.as_ref(), self.client.build_transaction())
+        TransactionBuilder::new(self.connection.as_ref(), &mut self.client)
+    }
+
+    /// Runs a closure inside of a new database transaction.
+    ///
+    /// The transaction is committed if the closure returns `Ok`, and rolled back if it returns `Err`. Use
+    /// [`build_transaction`](Client::build_transaction) and [`TransactionBuilder::run`] or
+    /// [`TransactionBuilder::run_retrying`] to configure the transaction or retry it on serialization failures.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use postgres::{Client, IsolationLevel, NoTls};
+    ///
+    /// # fn main() -> Result<(), postgres::Error> {
+    /// let mut client = Client::connect("host=localhost user=postgres", NoTls)?;
+    ///
+    /// let balance = client.with_transaction(|transaction| {
+    ///     transaction.execute("UPDATE accounts SET balance = balance - 10 WHERE id = 1", &[])?;
+    ///     let row = transaction.query_one("SELECT balance FROM accounts WHERE id = 1", &[])?;
+    ///     Ok::<i64, postgres::Error>(row.get(0))
+    /// })?;
+    ///
+    /// client.build_transaction()
+    ///     .isolation_level(IsolationLevel::Serializable)
+    ///     .max_retries(3)
+    ///     .run(|transaction| {
+    ///         transaction.execute("UPDATE accounts SET balance = balance + $1 WHERE id = 2", &[&balance])
+    ///     })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_transaction<F, T, E>(&mut self, f: F) -> Result<T, E>
+    where
+        F: FnMut(&mut Transaction<'_>) -> Result<T, E>,
+        E: From<Error>,
+    {
+        self.build_transaction().run_retrying(|_| false, f)
     }
 
     /// Returns a structure providing access to asynchronous notifications.
@@ -676,6 +834,39 @@ impl Client {
         CancelToken::new(self.client.cancel_token())
     }
 
+    /// Returns the current value of a runtime parameter reported by the server.
+    ///
+    /// See the documentation of [`tokio_postgres::Client::parameter`] for details.
+    pub fn parameter(&self, name: &str) -> Option<String> {
+        self.client.parameter(name)
+    }
+
+    /// Returns the transaction status of the session.
+    ///
+    /// See the documentation of [`tokio_postgres::Client::transaction_status`] for details.
+    pub fn transaction_status(&self) -> TransactionStatus {
+        self.client.transaction_status()
+    }
+
+    /// Returns the process ID of the backend server process handling this connection.
+    pub fn backend_pid(&self) -> i32 {
+        self.client.backend_pid()
+    }
+
+    /// Returns details of the TLS session negotiated with the server.
+    ///
+    /// See the documentation of [`tokio_postgres::Client::tls_info`] for details.
+    pub fn tls_info(&self) -> Option<&TlsInfo> {
+        self.client.tls_info()
+    }
+
+    /// Returns the version of the server as a `(major, minor)` pair.
+    ///
+    /// See the documentation of [`tokio_postgres::Client::server_version`] for details.
+    pub fn server_version(&self) -> Option<(u32, u32)> {
+        self.client.server_version()
+    }
+
     /// Clears the client's type information cache.
     ///
     /// When user-defined types are used in a query, the client loads their definitions from the database and caches
@@ -685,6 +876,37 @@ impl Client {
         self.client.clear_type_cache();
     }
 
+    /// Returns a handle to the client's type information cache.
+    ///
+    /// See the documentation of [`tokio_postgres::Client::type_cache`] for details.
+    pub fn type_cache(&self) -> TypeCache {
+        self.client.type_cache()
+    }
+
+    /// Replaces the client's type information cache with a shared one.
+    ///
+    /// See the documentation of [`tokio_postgres::Client::set_type_cache`] for details.
+    pub fn set_type_cache(&self, cache: TypeCache) {
+        self.client.set_type_cache(cache);
+    }
+
+    /// Registers a handler converting values of a Postgres type at runtime.
+    ///
+    /// See the documentation of [`tokio_postgres::Client::register_type_handler`] for details.
+    pub fn register_type_handler<K, H>(&self, key: K, handler: H)
+    where
+        K: Into<TypeKey>,
+        H: TypeHandler,
+    {
+        self.client.register_type_handler(key, handler);
+    }
+
+    /// Wraps a value so that it is passed as a query parameter using the [`TypeHandler`] registered for the
+    /// parameter's type.
+    pub fn custom_param<T>(&self, value: T) -> CustomParam<T> {
+        self.client.custom_param(value)
+    }
+
     /// Determines if the client's connection has already closed.
     ///
     /// If this returns `true`, the client is no longer usable.
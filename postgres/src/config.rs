@@ -3,22 +3,26 @@
 #![allow(clippy::doc_overindented_list_items)]
 
 use crate::Client;
-use crate::connection::Connection;
+use crate::connection::{CancelFuture, Connection, ConnectionRuntime, QueryTimeout};
 use log::info;
 use std::fmt;
+use std::io;
 use std::net::IpAddr;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, OnceLock};
 use std::time::Duration;
-use tokio::runtime;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::runtime::{self, Handle, Runtime, RuntimeFlavor};
 #[doc(inline)]
 pub use tokio_postgres::config::{
-    ChannelBinding, Host, LoadBalanceHosts, SslMode, SslNegotiation, TargetSessionAttrs,
+    ChannelBinding, DnsResolver, Host, LoadBalanceHosts, Proxy, SocketRef, SslMode, SslNegotiation,
+    TargetSessionAttrs,
 };
 use tokio_postgres::error::DbError;
 use tokio_postgres::tls::{MakeTlsConnect, TlsConnect};
-use tokio_postgres::{Error, Socket};
+use tokio_postgres::types::{ToSql, Type};
+use tokio_postgres::{ClientMetrics, Error, Socket, rt};
 
 /// Connection configuration.
 ///
@@ -33,11 +37,26 @@ use tokio_postgres::{Error, Socket};
 ///
 /// * `user` - The username to authenticate with. Defaults to the user executing this process.
 /// * `password` - The password to authenticate with.
+/// * `passfile` - The path of the file to look up passwords in when none is configured. Defaults to the value of the
+///     `PGPASSFILE` environment variable, or `~/.pgpass` (`%APPDATA%\postgresql\pgpass.conf` on Windows).
 /// * `dbname` - The name of the database to connect to. Defaults to the username.
 /// * `options` - Command line options used to configure the server.
 /// * `application_name` - Sets the `application_name` parameter on the server.
 /// * `sslmode` - Controls usage of TLS. If set to `disable`, TLS will not be used. If set to `prefer`, TLS will be used
-///     if available, but not used otherwise. If set to `require`, TLS will be forced to be used. Defaults to `prefer`.
+///     if available, but not used otherwise. If set to `require`, TLS will be forced to be used. If set to `verify-ca`,
+///     TLS will be forced to be used and the server's certificate must be signed by a trusted certificate authority.
+///     If set to `verify-full`, the certificate must additionally match the server's hostname. The verification modes
+///     must be supported by the TLS connector. Defaults to `prefer`.
+/// * `sslrootcert` - The path of a PEM file containing the certificate authorities trusted by the `verify-ca` and
+///     `verify-full` modes. If set to `system`, the TLS connector's default trust roots are used, which is only
+///     allowed with `verify-full`. Defaults to `~/.postgresql/root.crt` (`%APPDATA%\postgresql\root.crt` on
+///     Windows) if it exists; otherwise `verify-full` uses the TLS connector's default trust roots and `verify-ca`
+///     fails.
+/// * `sslcert` - The path of the client certificate to present to the server, in PEM or DER format. Client
+///     certificates must be supported by the TLS connector.
+/// * `sslkey` - The path of the private key of the client certificate, in PEM or DER format. Defaults to the
+///     `sslcert` file, which must then be in PEM format and contain the key.
+/// * `sslpassword` - The password used to decrypt the private key of the client certificate.
 /// * `host` - The host to connect to. On Unix platforms, if the host starts with a `/` character it is treated as the
 ///     path to the directory containing Unix domain sockets. Otherwise, it is treated as a hostname. Multiple hosts
 ///     can be specified, separated by commas. Each host will be tried in turn when connecting. Required if connecting
@@ -63,6 +82,11 @@ use tokio_postgres::{Error, Socket};
 ///     omitted or the empty string.
 /// * `connect_timeout` - The time limit in seconds applied to each socket-level connection attempt. Note that hostnames
 ///     can resolve to multiple IP addresses, and this limit is applied to each address. Defaults to no timeout.
+/// * `total_connect_timeout` - The time limit in seconds applied to the entire connection process, across all hosts
+///     and retries. Defaults to no timeout.
+/// * `connect_retries` - The number of times a failed connection attempt to each host is retried before moving on to
+///     the next host. Only I/O errors and timeouts are retried; errors reported by the server, such as an
+///     authentication failure, are not. Defaults to 0.
 /// * `tcp_user_timeout` - The time limit that transmitted data may remain unacknowledged before a connection is forcibly closed.
 ///     This is ignored for Unix domain socket connections. It is only supported on systems where TCP_USER_TIMEOUT is available
 ///     and will default to the system default if omitted or set to 0; on other systems, it has no effect.
@@ -80,6 +104,11 @@ use tokio_postgres::{Error, Socket};
 /// * `channel_binding` - Controls usage of channel binding in the authentication process. If set to `disable`, channel
 ///     binding will not be used. If set to `prefer`, channel binding will be used if available, but not used otherwise.
 ///     If set to `require`, the authentication process will fail if channel binding is not used. Defaults to `prefer`.
+/// * `service` - The name of a service in the connection service file to take settings from. Settings specified
+///     directly take precedence over those from the service.
+/// * `servicefile` - The path of the connection service file. Defaults to the value of the `PGSERVICEFILE` environment
+///     variable, or `~/.pg_service.conf` (`%APPDATA%\postgresql\.pg_service.conf` on Windows). If the service is not
+///     defined there, `pg_service.conf` in the directory named by the `PGSYSCONFDIR` environment variable is used.
 /// * `load_balance_hosts` - Controls the order in which the client tries to connect to the available hosts and
 ///     addresses. Once a connection attempt is successful no other hosts and addresses will be tried. This parameter
 ///     is typically used in combination with multiple host names or a DNS record that returns multiple IPs. If set to
@@ -129,20 +158,43 @@ use tokio_postgres::{Error, Socket};
 /// ```not_rust
 /// postgresql:///mydb?user=user&host=/var/run/postgresql
 /// ```
+///
+/// # Serde
+///
+/// With the `with-serde-1` Cargo feature, `Config` implements `Serialize` and `Deserialize`. See
+/// [`tokio_postgres::Config`] for details of the format. Settings specific to this crate, such as the query timeout,
+/// are not serialized.
 #[derive(Clone)]
 pub struct Config {
     config: tokio_postgres::Config,
     notice_callback: Arc<dyn Fn(DbError) + Send + Sync>,
+    runtime: RuntimeConfig,
+    query_timeout: Option<Duration>,
+}
+
+#[derive(Clone)]
+enum RuntimeConfig {
+    Owned,
+    Shared,
+    Handle(Handle),
 }
 
 impl fmt::Debug for Config {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt.debug_struct("Config")
             .field("config", &self.config)
+            .field("query_timeout", &self.query_timeout)
             .finish()
     }
 }
 
+/// Formats the configuration as a key-value connection string, omitting the password and `sslpassword`.
+impl fmt::Display for Config {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.config, fmt)
+    }
+}
+
 impl Default for Config {
     fn default() -> Config {
         Config::new()
@@ -155,6 +207,21 @@ impl Config {
         tokio_postgres::Config::new().into()
     }
 
+    /// Creates a new configuration from the environment variables used by libpq.
+    ///
+    /// See [`tokio_postgres::Config::from_env`] for the supported variables.
+    pub fn from_env() -> Result<Config, Error> {
+        tokio_postgres::Config::from_env().map(Config::from)
+    }
+
+    /// Fills in settings which have not been configured from the environment variables used by libpq.
+    ///
+    /// See [`tokio_postgres::Config::apply_env`] for details.
+    pub fn apply_env(&mut self) -> Result<&mut Config, Error> {
+        self.config.apply_env()?;
+        Ok(self)
+    }
+
     /// Sets the user to authenticate with.
     ///
     /// If the user is not set, then this defaults to the user executing this process.
@@ -184,6 +251,19 @@ impl Config {
         self.config.get_password()
     }
 
+    /// Sets the path of the password file.
+    ///
+    /// See [`tokio_postgres::Config::passfile`] for details.
+    pub fn passfile(&mut self, passfile: impl Into<PathBuf>) -> &mut Config {
+        self.config.passfile(passfile);
+        self
+    }
+
+    /// Gets the path of the password file, if one has been configured with the `passfile` method.
+    pub fn get_passfile(&self) -> Option<&Path> {
+        self.config.get_passfile()
+    }
+
     /// Sets the name of the database to connect to.
     ///
     /// Defaults to the user.
@@ -246,6 +326,62 @@ impl Config {
         self.config.get_ssl_negotiation()
     }
 
+    /// Sets the path of the file containing the certificate authorities trusted by the `verify-ca` and `verify-full`
+    /// SSL modes.
+    ///
+    /// See [`tokio_postgres::Config::ssl_root_cert`] for details.
+    pub fn ssl_root_cert(&mut self, ssl_root_cert: impl Into<PathBuf>) -> &mut Config {
+        self.config.ssl_root_cert(ssl_root_cert);
+        self
+    }
+
+    /// Gets the path of the file containing trusted certificate authorities, if one has been set with the
+    /// `ssl_root_cert` method.
+    pub fn get_ssl_root_cert(&self) -> Option<&Path> {
+        self.config.get_ssl_root_cert()
+    }
+
+    /// Sets the path of the client certificate to present to the server.
+    ///
+    /// See [`tokio_postgres::Config::ssl_cert`] for details.
+    pub fn ssl_cert(&mut self, ssl_cert: impl Into<PathBuf>) -> &mut Config {
+        self.config.ssl_cert(ssl_cert);
+        self
+    }
+
+    /// Gets the path of the client certificate, if one has been set with the `ssl_cert` method.
+    pub fn get_ssl_cert(&self) -> Option<&Path> {
+        self.config.get_ssl_cert()
+    }
+
+    /// Sets the path of the private key of the client certificate.
+    ///
+    /// See [`tokio_postgres::Config::ssl_key`] for details.
+    pub fn ssl_key(&mut self, ssl_key: impl Into<PathBuf>) -> &mut Config {
+        self.config.ssl_key(ssl_key);
+        self
+    }
+
+    /// Gets the path of the private key of the client certificate, if one has been set with the `ssl_key` method.
+    pub fn get_ssl_key(&self) -> Option<&Path> {
+        self.config.get_ssl_key()
+    }
+
+    /// Sets the password used to decrypt the private key of the client certificate.
+    pub fn ssl_password<T>(&mut self, ssl_password: T) -> &mut Config
+    where
+        T: AsRef<[u8]>,
+    {
+        self.config.ssl_password(ssl_password);
+        self
+    }
+
+    /// Gets the password used to decrypt the private key of the client certificate, if one has been set with the
+    /// `ssl_password` method.
+    pub fn get_ssl_password(&self) -> Option<&[u8]> {
+        self.config.get_ssl_password()
+    }
+
     /// Adds a host to the configuration.
     ///
     /// Multiple hosts can be specified by calling this method multiple times, and each will be tried in order. On Unix
@@ -317,6 +453,50 @@ impl Config {
         self.config.get_connect_timeout()
     }
 
+    /// Sets the timeout applied to the entire connection process.
+    ///
+    /// Unlike `connect_timeout`, this limit covers every host, address, and retry, including authentication and
+    /// session checks. Defaults to no limit.
+    pub fn total_connect_timeout(&mut self, total_connect_timeout: Duration) -> &mut Config {
+        self.config.total_connect_timeout(total_connect_timeout);
+        self
+    }
+
+    /// Gets the total connection timeout, if one has been set with the
+    /// `total_connect_timeout` method.
+    pub fn get_total_connect_timeout(&self) -> Option<&Duration> {
+        self.config.get_total_connect_timeout()
+    }
+
+    /// Sets the number of times a failed connection attempt to each host is retried before moving on to the next host.
+    ///
+    /// Only attempts which fail to reach the server, with an I/O error or a timeout, are retried. Errors reported by
+    /// the server, such as an authentication failure or a nonexistent database, move on to the next host immediately.
+    ///
+    /// Defaults to 0.
+    pub fn connect_retries(&mut self, connect_retries: u32) -> &mut Config {
+        self.config.connect_retries(connect_retries);
+        self
+    }
+
+    /// Gets the number of times a failed connection attempt to each host is retried.
+    pub fn get_connect_retries(&self) -> u32 {
+        self.config.get_connect_retries()
+    }
+
+    /// Sets the delay before the first retry of a failed connection attempt to a host.
+    ///
+    /// The delay doubles after each subsequent failed attempt to the same host. Defaults to 100 milliseconds.
+    pub fn connect_retry_backoff(&mut self, connect_retry_backoff: Duration) -> &mut Config {
+        self.config.connect_retry_backoff(connect_retry_backoff);
+        self
+    }
+
+    /// Gets the delay before the first retry of a failed connection attempt to a host.
+    pub fn get_connect_retry_backoff(&self) -> Duration {
+        self.config.get_connect_retry_backoff()
+    }
+
     /// Sets the TCP user timeout.
     ///
     /// This is ignored for Unix domain socket connections. It is only supported on systems where
@@ -430,6 +610,168 @@ impl Config {
         self.config.get_load_balance_hosts()
     }
 
+    /// Controls whether errors record the text of the statement which caused them.
+    ///
+    /// See [`tokio_postgres::Config::query_in_errors`] for details.
+    pub fn query_in_errors(&mut self, query_in_errors: bool) -> &mut Config {
+        self.config.query_in_errors(query_in_errors);
+        self
+    }
+
+    /// Gets whether errors record the text of the statement which caused them.
+    pub fn get_query_in_errors(&self) -> bool {
+        self.config.get_query_in_errors()
+    }
+
+    /// Controls whether errors record the values of the parameters bound to the statement which caused them.
+    ///
+    /// See [`tokio_postgres::Config::params_in_errors`] for details.
+    pub fn params_in_errors(&mut self, params_in_errors: bool) -> &mut Config {
+        self.config.params_in_errors(params_in_errors);
+        self
+    }
+
+    /// Gets whether errors record the values of the parameters bound to the statement which caused them.
+    pub fn get_params_in_errors(&self) -> bool {
+        self.config.get_params_in_errors()
+    }
+
+    /// Controls whether tracing spans record the text of the statements they cover.
+    ///
+    /// See [`tokio_postgres::Config::trace_query_text`] for details.
+    pub fn trace_query_text(&mut self, trace_query_text: bool) -> &mut Config {
+        self.config.trace_query_text(trace_query_text);
+        self
+    }
+
+    /// Gets whether tracing spans record the text of the statements they cover.
+    pub fn get_trace_query_text(&self) -> bool {
+        self.config.get_trace_query_text()
+    }
+
+    /// Sets a function which renders parameters recorded in errors.
+    ///
+    /// See [`tokio_postgres::Config::param_redactor`] for details.
+    pub fn param_redactor<F>(&mut self, f: F) -> &mut Config
+    where
+        F: Fn(usize, &Type, &dyn ToSql) -> Option<String> + Send + Sync + 'static,
+    {
+        self.config.param_redactor(f);
+        self
+    }
+
+    /// Sets the hooks which are notified of queries, errors, and network traffic on the connection.
+    ///
+    /// See [`tokio_postgres::ClientMetrics`] for details.
+    pub fn metrics<M>(&mut self, metrics: M) -> &mut Config
+    where
+        M: ClientMetrics + 'static,
+    {
+        self.config.metrics(metrics);
+        self
+    }
+
+    /// Gets the metrics hooks, if they have been set with the `metrics` method.
+    pub fn get_metrics(&self) -> Option<&dyn ClientMetrics> {
+        self.config.get_metrics()
+    }
+
+    /// Sets a function which is called with each statement which takes at least `threshold` to run.
+    ///
+    /// See [`tokio_postgres::Config::slow_query_handler`] for details.
+    pub fn slow_query_handler<F>(&mut self, threshold: Duration, f: F) -> &mut Config
+    where
+        F: Fn(&str, Duration, Option<u64>) + Send + Sync + 'static,
+    {
+        self.config.slow_query_handler(threshold, f);
+        self
+    }
+
+    /// Gets the slow query threshold, if a handler has been set with the `slow_query_handler` method.
+    pub fn get_slow_query_threshold(&self) -> Option<Duration> {
+        self.config.get_slow_query_threshold()
+    }
+
+    /// Sets a function which returns tags to attach to statements as sqlcommenter comments.
+    ///
+    /// See [`tokio_postgres::Config::sql_commenter`] for details.
+    pub fn sql_commenter<F>(&mut self, f: F) -> &mut Config
+    where
+        F: Fn() -> Vec<(String, String)> + Send + Sync + 'static,
+    {
+        self.config.sql_commenter(f);
+        self
+    }
+
+    /// Sets the name of a service in the connection service file to take settings from.
+    ///
+    /// See [`tokio_postgres::Config::service`] for details.
+    pub fn service(&mut self, service: impl Into<String>) -> &mut Config {
+        self.config.service(service);
+        self
+    }
+
+    /// Gets the name of the connection service, if one has been configured with the `service` method.
+    pub fn get_service(&self) -> Option<&str> {
+        self.config.get_service()
+    }
+
+    /// Sets the path of the connection service file.
+    ///
+    /// See [`tokio_postgres::Config::servicefile`] for details.
+    pub fn servicefile(&mut self, servicefile: impl Into<PathBuf>) -> &mut Config {
+        self.config.servicefile(servicefile);
+        self
+    }
+
+    /// Gets the path of the connection service file, if one has been configured with the `servicefile` method.
+    pub fn get_servicefile(&self) -> Option<&Path> {
+        self.config.get_servicefile()
+    }
+
+    /// Sets the resolver used to look up the addresses of TCP hosts.
+    ///
+    /// Addresses specified with `hostaddr` are used directly and are not resolved.
+    ///
+    /// Defaults to the system resolver.
+    pub fn dns_resolver<R>(&mut self, resolver: R) -> &mut Config
+    where
+        R: DnsResolver + 'static,
+    {
+        self.config.dns_resolver(resolver);
+        self
+    }
+
+    /// Gets the resolver used to look up the addresses of TCP hosts, if one has been set.
+    pub fn get_dns_resolver(&self) -> Option<&dyn DnsResolver> {
+        self.config.get_dns_resolver()
+    }
+
+    /// Sets a proxy server through which connections to TCP hosts are made.
+    ///
+    /// Connections to Unix sockets do not use the proxy. Hostnames are resolved by the proxy, so any
+    /// [DNS resolver](Config::dns_resolver) is not used.
+    pub fn proxy(&mut self, proxy: Proxy) -> &mut Config {
+        self.config.proxy(proxy);
+        self
+    }
+
+    /// Gets the proxy server through which connections are made, if one has been set.
+    pub fn get_proxy(&self) -> Option<&Proxy> {
+        self.config.get_proxy()
+    }
+
+    /// Sets a callback which is invoked with each newly connected socket before the Postgres handshake begins.
+    ///
+    /// See the documentation of [`tokio_postgres::Config::socket_callback`] for details.
+    pub fn socket_callback<F>(&mut self, f: F) -> &mut Config
+    where
+        F: Fn(SocketRef<'_>) -> io::Result<()> + Send + Sync + 'static,
+    {
+        self.config.socket_callback(f);
+        self
+    }
+
     /// Sets the notice callback.
     ///
     /// This callback will be invoked with the contents of every
@@ -449,6 +791,118 @@ impl Config {
         self
     }
 
+    /// Sets the default time limit for requests made by a client.
+    ///
+    /// When a query, or any other request to the server, takes longer than this, the client asks the server to cancel
+    /// it and the request fails with a [`QUERY_CANCELED`](tokio_postgres::error::SqlState::QUERY_CANCELED) error.
+    /// Clients connected with [`connect_raw`](Config::connect_raw) have no way to cancel requests, so they instead
+    /// return a timeout error while the request keeps running on the server.
+    ///
+    /// The timeout is applied to each call to the client separately. Defaults to no timeout.
+    pub fn query_timeout(&mut self, query_timeout: Duration) -> &mut Config {
+        self.query_timeout = Some(query_timeout);
+        self
+    }
+
+    /// Gets the default request timeout, if one has been set with the `query_timeout` method.
+    pub fn get_query_timeout(&self) -> Option<&Duration> {
+        self.query_timeout.as_ref()
+    }
+
+    /// Sets a runtime other than Tokio used to connect and to drive connections.
+    ///
+    /// See [`tokio_postgres::rt`] for details. Requests are executed on the thread which makes them, without a Tokio
+    /// runtime: the connection is polled on that thread and timers, such as the query timeout, come from `runtime`.
+    /// Takes precedence over [`runtime_handle`](Config::runtime_handle) and
+    /// [`shared_runtime`](Config::shared_runtime).
+    pub fn runtime<R>(&mut self, runtime: R) -> &mut Config
+    where
+        R: rt::Runtime + 'static,
+    {
+        self.config.runtime(runtime);
+        self
+    }
+
+    /// Sets the Tokio runtime used to drive connections.
+    ///
+    /// By default, each client creates its own single-threaded runtime. Applications with many clients can instead
+    /// drive all of them with an existing runtime, avoiding the cost of a runtime per connection. Requests are still
+    /// executed on the thread which makes them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the handle belongs to a current-thread runtime, which cannot drive I/O for other threads.
+    pub fn runtime_handle(&mut self, handle: Handle) -> &mut Config {
+        assert!(
+            handle.runtime_flavor() != RuntimeFlavor::CurrentThread,
+            "connections cannot be driven by a current-thread runtime",
+        );
+        self.runtime = RuntimeConfig::Handle(handle);
+        self
+    }
+
+    /// Controls the use of a global runtime shared by all clients.
+    ///
+    /// If enabled, connections are driven by a multi-threaded runtime with a single worker thread, which is created
+    /// the first time it is needed and shared by every client configured this way. Defaults to `false`.
+    pub fn shared_runtime(&mut self, shared_runtime: bool) -> &mut Config {
+        self.runtime = if shared_runtime {
+            RuntimeConfig::Shared
+        } else {
+            RuntimeConfig::Owned
+        };
+        self
+    }
+
+    /// Formats the configuration as a key-value connection string, including the password.
+    ///
+    /// See [`tokio_postgres::Config::to_connection_string`] for details.
+    pub fn to_connection_string(&self) -> String {
+        self.config.to_connection_string()
+    }
+
+    /// Formats the configuration as a `postgresql://` URL, including the password.
+    ///
+    /// See [`tokio_postgres::Config::to_url`] for details.
+    pub fn to_url(&self) -> String {
+        self.config.to_url()
+    }
+
+    fn connection_runtime(&self) -> Result<ConnectionRuntime, Error> {
+        if let Some(runtime) = self.config.__private_api_runtime() {
+            return Ok(ConnectionRuntime::Custom(runtime));
+        }
+
+        let runtime = match &self.runtime {
+            RuntimeConfig::Owned => ConnectionRuntime::Owned(
+                runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .map_err(Error::__private_api_io)?,
+            ),
+            RuntimeConfig::Shared => {
+                static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+                let runtime = match RUNTIME.get() {
+                    Some(runtime) => runtime,
+                    None => {
+                        let runtime = runtime::Builder::new_multi_thread()
+                            .worker_threads(1)
+                            .thread_name("postgres-runtime")
+                            .enable_all()
+                            .build()
+                            .map_err(Error::__private_api_io)?;
+                        // only a runtime which was built successfully is cached, and if another thread got there
+                        // first this one is dropped
+                        RUNTIME.get_or_init(|| runtime)
+                    }
+                };
+                ConnectionRuntime::Shared(runtime.handle().clone())
+            }
+            RuntimeConfig::Handle(handle) => ConnectionRuntime::Shared(handle.clone()),
+        };
+        Ok(runtime)
+    }
+
     /// Opens a connection to a PostgreSQL database.
     pub fn connect<T>(&self, tls: T) -> Result<Client, Error>
     where
@@ -457,18 +911,92 @@ impl Config {
         T::Stream: Send,
         <T::TlsConnect as TlsConnect<Socket>>::Future: Send,
     {
-        let runtime = runtime::Builder::new_current_thread()
-            .enable_all()
-            .build()
-            .unwrap(); // FIXME don't unwrap
+        let runtime = self.connection_runtime()?;
+        let tls = SharedTls(Arc::new(Mutex::new(tls)));
+        let (client, connection) = runtime.block_on(self.config.connect(tls.clone()))?;
 
-        let (client, connection) = runtime.block_on(self.config.connect(tls))?;
+        let query_timeout = self.query_timeout.map(|timeout| {
+            let cancel_token = client.cancel_token();
+            let cancel = move || -> CancelFuture {
+                let cancel_token = cancel_token.clone();
+                let tls = tls.clone();
+                Box::pin(async move { cancel_token.cancel_query(tls).await })
+            };
+            QueryTimeout {
+                timeout,
+                cancel: Some(Box::new(cancel)),
+            }
+        });
 
-        let connection = Connection::new(runtime, connection, self.notice_callback.clone());
+        let connection = Connection::new(
+            runtime,
+            connection,
+            self.notice_callback.clone(),
+            query_timeout,
+        );
+        Ok(Client::new(connection, client))
+    }
+
+    /// Connects to a PostgreSQL database over an arbitrary stream.
+    ///
+    /// All of the settings other than `user`, `password`, `dbname`, `options`, and `application_name` are ignored.
+    ///
+    /// The stream is driven by the client's runtime. Streams which don't depend on a Tokio reactor, like
+    /// [`tokio::io::DuplexStream`] or a custom transport, can be used freely. Streams which are registered with a
+    /// reactor, like [`tokio::net::TcpStream`], only make progress while the runtime they were created in is running,
+    /// so they are best used with a [shared runtime](Config::runtime_handle).
+    ///
+    /// The stream must implement Tokio's [`AsyncRead`] and [`AsyncWrite`] traits. Blocking streams which only
+    /// implement [`std::io::Read`] and [`std::io::Write`], like [`std::net::TcpStream`], are not supported, since the
+    /// connection is polled without a thread of its own to block on them.
+    pub fn connect_raw<S, T>(&self, stream: S, tls: T) -> Result<Client, Error>
+    where
+        S: AsyncRead + AsyncWrite + Unpin + 'static + Send,
+        T: TlsConnect<S>,
+        T::Stream: Send + 'static,
+    {
+        let runtime = self.connection_runtime()?;
+        let (client, connection) = runtime.block_on(self.config.connect_raw(stream, tls))?;
+
+        let query_timeout = self.query_timeout.map(|timeout| QueryTimeout {
+            timeout,
+            cancel: None,
+        });
+        let connection = Connection::new(
+            runtime,
+            connection,
+            self.notice_callback.clone(),
+            query_timeout,
+        );
         Ok(Client::new(connection, client))
     }
 }
 
+/// A TLS connector which can be used both to connect and to cancel queries later on.
+struct SharedTls<T>(Arc<Mutex<T>>);
+
+impl<T> Clone for SharedTls<T> {
+    fn clone(&self) -> SharedTls<T> {
+        SharedTls(self.0.clone())
+    }
+}
+
+impl<T> MakeTlsConnect<Socket> for SharedTls<T>
+where
+    T: MakeTlsConnect<Socket>,
+{
+    type Stream = T::Stream;
+    type TlsConnect = T::TlsConnect;
+    type Error = T::Error;
+
+    fn make_tls_connect(&mut self, domain: &str) -> Result<T::TlsConnect, T::Error> {
+        self.0
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .make_tls_connect(domain)
+    }
+}
+
 impl FromStr for Config {
     type Err = Error;
 
@@ -477,6 +1005,26 @@ impl FromStr for Config {
     }
 }
 
+#[cfg(feature = "with-serde-1")]
+impl serde_1::Serialize for Config {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde_1::Serializer,
+    {
+        self.config.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "with-serde-1")]
+impl<'de> serde_1::Deserialize<'de> for Config {
+    fn deserialize<D>(deserializer: D) -> Result<Config, D::Error>
+    where
+        D: serde_1::Deserializer<'de>,
+    {
+        tokio_postgres::Config::deserialize(deserializer).map(Config::from)
+    }
+}
+
 impl From<tokio_postgres::Config> for Config {
     fn from(config: tokio_postgres::Config) -> Config {
         Config {
@@ -484,6 +1032,8 @@ impl From<tokio_postgres::Config> for Config {
             notice_callback: Arc::new(|notice| {
                 info!("{}: {}", notice.severity(), notice.message())
             }),
+            runtime: RuntimeConfig::Owned,
+            query_timeout: None,
         }
     }
 }
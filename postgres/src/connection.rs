@@ -1,28 +1,82 @@
 use crate::{Error, Notification};
 use futures_util::Stream;
+use log::debug;
 use std::collections::VecDeque;
 use std::future::{self, Future};
 use std::ops::{Deref, DerefMut};
 use std::pin::{Pin, pin};
 use std::sync::Arc;
 use std::task::{Context, Poll};
+use std::time::Duration;
 use tokio::io::{AsyncRead, AsyncWrite};
-use tokio::runtime::Runtime;
-use tokio_postgres::AsyncMessage;
+use tokio::runtime::{EnterGuard, Handle, Runtime};
 use tokio_postgres::error::DbError;
+use tokio_postgres::{AsyncMessage, rt};
+
+/// The runtime which drives a connection.
+pub enum ConnectionRuntime {
+    Owned(Runtime),
+    Shared(Handle),
+    /// A non-Tokio runtime; futures are polled on the calling thread and timers come from the runtime.
+    Custom(Arc<dyn rt::Runtime>),
+}
+
+impl ConnectionRuntime {
+    pub fn block_on<F>(&self, future: F) -> F::Output
+    where
+        F: Future,
+    {
+        match self {
+            ConnectionRuntime::Owned(runtime) => runtime.block_on(future),
+            ConnectionRuntime::Shared(handle) => handle.block_on(future),
+            ConnectionRuntime::Custom(_) => futures_executor::block_on(future),
+        }
+    }
+
+    fn enter(&self) -> Option<EnterGuard<'_>> {
+        match self {
+            ConnectionRuntime::Owned(runtime) => Some(runtime.enter()),
+            ConnectionRuntime::Shared(handle) => Some(handle.enter()),
+            ConnectionRuntime::Custom(_) => None,
+        }
+    }
+
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        match self {
+            ConnectionRuntime::Custom(runtime) => runtime.sleep(duration),
+            _ => {
+                let _guard = self.enter();
+                Box::pin(tokio::time::sleep(duration))
+            }
+        }
+    }
+}
+
+pub type CancelFuture = Pin<Box<dyn Future<Output = Result<(), Error>>>>;
+
+pub type CancelFn = dyn Fn() -> CancelFuture + Send;
+
+/// The time limit applied to each request made over a connection.
+pub struct QueryTimeout {
+    pub timeout: Duration,
+    /// Cancels the request in progress, if the connection supports it.
+    pub cancel: Option<Box<CancelFn>>,
+}
 
 pub struct Connection {
-    runtime: Runtime,
+    runtime: ConnectionRuntime,
     connection: Pin<Box<dyn Stream<Item = Result<AsyncMessage, Error>> + Send>>,
     notifications: VecDeque<Notification>,
     notice_callback: Arc<dyn Fn(DbError) + Sync + Send>,
+    query_timeout: Option<QueryTimeout>,
 }
 
 impl Connection {
     pub fn new<S, T>(
-        runtime: Runtime,
+        runtime: ConnectionRuntime,
         connection: tokio_postgres::Connection<S, T>,
         notice_callback: Arc<dyn Fn(DbError) + Sync + Send>,
+        query_timeout: Option<QueryTimeout>,
     ) -> Connection
     where
         S: AsyncRead + AsyncWrite + Unpin + 'static + Send,
@@ -33,6 +87,7 @@ impl Connection {
             connection: Box::pin(ConnectionStream { connection }),
             notifications: VecDeque::new(),
             notice_callback,
+            query_timeout,
         }
     }
 
@@ -40,12 +95,9 @@ impl Connection {
         ConnectionRef { connection: self }
     }
 
-    pub fn enter<F, T>(&self, f: F) -> T
-    where
-        F: FnOnce() -> T,
-    {
-        let _guard = self.runtime.enter();
-        f()
+    /// Returns a future which completes after a duration has elapsed, using the connection's runtime.
+    pub fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        self.runtime.sleep(duration)
     }
 
     pub fn block_on<F, T>(&mut self, future: F) -> Result<T, Error>
@@ -53,17 +105,34 @@ impl Connection {
         F: Future<Output = Result<T, Error>>,
     {
         let mut future = pin!(future);
-        self.poll_block_on(|cx, _, _| future.as_mut().poll(cx))
+        self.poll_with_timeout(true, |cx, _, _| future.as_mut().poll(cx))
     }
 
-    pub fn poll_block_on<F, T>(&mut self, mut f: F) -> Result<T, Error>
+    pub fn poll_block_on<F, T>(&mut self, f: F) -> Result<T, Error>
+    where
+        F: FnMut(&mut Context<'_>, &mut VecDeque<Notification>, bool) -> Poll<Result<T, Error>>,
+    {
+        self.poll_with_timeout(false, f)
+    }
+
+    /// Drives the connection until `f` completes.
+    ///
+    /// If `request` is set, `f` is waiting on a request, which is subject to the query timeout. A request which times
+    /// out is cancelled so that it fails on its own, or fails with a timeout error if the connection can't cancel
+    /// requests. The timer and the cancellation are only created once they're needed.
+    fn poll_with_timeout<F, T>(&mut self, request: bool, mut f: F) -> Result<T, Error>
     where
         F: FnMut(&mut Context<'_>, &mut VecDeque<Notification>, bool) -> Poll<Result<T, Error>>,
     {
         let connection = &mut self.connection;
         let notifications = &mut self.notifications;
         let notice_callback = &mut self.notice_callback;
-        self.runtime.block_on({
+        let query_timeout = self.query_timeout.as_ref().filter(|_| request);
+        let runtime = &self.runtime;
+        let mut sleep = None;
+        let mut cancellation: Option<CancelFuture> = None;
+        let mut timed_out = false;
+        runtime.block_on({
             future::poll_fn(|cx| {
                 let done = loop {
                     match connection.as_mut().poll_next(cx) {
@@ -80,7 +149,35 @@ impl Connection {
                     }
                 };
 
-                f(cx, notifications, done)
+                if let Poll::Ready(result) = f(cx, notifications, done) {
+                    return Poll::Ready(result);
+                }
+
+                let Some(query_timeout) = query_timeout else {
+                    return Poll::Pending;
+                };
+                if !timed_out {
+                    let sleep = sleep.get_or_insert_with(|| runtime.sleep(query_timeout.timeout));
+                    if sleep.as_mut().poll(cx).is_pending() {
+                        return Poll::Pending;
+                    }
+                    debug!("request timed out");
+                    timed_out = true;
+                }
+
+                let Some(cancel) = &query_timeout.cancel else {
+                    return Poll::Ready(Err(Error::__private_api_timeout()));
+                };
+                // once the cancellation is delivered the request fails on its own
+                let cancellation = cancellation.get_or_insert_with(|| cancel());
+                match cancellation.as_mut().poll(cx) {
+                    Poll::Ready(Ok(())) => {
+                        *cancellation = Box::pin(future::pending());
+                        Poll::Pending
+                    }
+                    Poll::Ready(Err(e)) => Poll::Ready(Err(e)),
+                    Poll::Pending => Poll::Pending,
+                }
             })
         })
     }
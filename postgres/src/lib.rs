@@ -53,16 +53,28 @@
 //!
 //! | Feature | Description | Extra dependencies | Default |
 //! | ------- | ----------- | ------------------ | ------- |
+//! | `derive` | Enable `#[derive(FromRow)]`. | - | no |
+//! | `test-util` | Enable constructors for `Row`, `SimpleQueryRow`, `Statement` and `Column` for use in tests. | - | no |
+//! | `explain` | Enable `Client::explain` for parsed `EXPLAIN (FORMAT JSON)` plans. | [serde](https://crates.io/crates/serde) 1.0, [serde_json](https://crates.io/crates/serde_json) 1.0 | no |
 //! | `with-bit-vec-0_6` | Enable support for the `bit-vec` crate. | [bit-vec](https://crates.io/crates/bit-vec) 0.6 | no |
 //! | `with-bit-vec-0_7` | Enable support for the `bit-vec` crate. | [bit-vec](https://crates.io/crates/bit-vec) 0.7 | no |
 //! | `with-bit-vec-0_8` | Enable support for the `bit-vec` crate. | [bit-vec](https://crates.io/crates/bit-vec) 0.8 | no |
 //! | `with-bit-vec-0_9` | Enable support for the `bit-vec` crate. | [bit-vec](https://crates.io/crates/bit-vec) 0.9 | no |
+//! | `with-bitvec-1` | Enable support for the `bitvec` crate. | [bitvec](https://crates.io/crates/bitvec) 1.0 | no |
 //! | `with-chrono-0_4` | Enable support for the `chrono` crate. | [chrono](https://crates.io/crates/chrono) 0.4 | no |
+//! | `with-chrono-tz-0_10` | Enable support for the `chrono-tz` crate. | [chrono-tz](https://crates.io/crates/chrono-tz) 0.10 | no |
+//! | `with-compact_str-0_9` | Enable support for the `compact_str` crate. | [compact_str](https://crates.io/crates/compact_str) 0.9 | no |
 //! | `with-eui48-0_4` | Enable support for the 0.4 version of the `eui48` crate. This is deprecated and will be removed. | [eui48](https://crates.io/crates/eui48) 0.4 | no |
 //! | `with-eui48-1` | Enable support for the 1.0 version of the `eui48` crate. | [eui48](https://crates.io/crates/eui48) 1.0 | no |
 //! | `with-geo-types-0_6` | Enable support for the 0.6 version of the `geo-types` crate. | [geo-types](https://crates.io/crates/geo-types/0.6.0) 0.6 | no |
 //! | `with-geo-types-0_7` | Enable support for the 0.7 version of the `geo-types` crate. | [geo-types](https://crates.io/crates/geo-types/0.7.0) 0.7 | no |
+//! | `with-macaddr-1` | Enable support for the `macaddr` crate. | [macaddr](https://crates.io/crates/macaddr) 1.0 | no |
+//! | `with-ipnetwork-0_21` | Enable support for the 0.21 version of the `ipnetwork` crate. | [ipnetwork](https://crates.io/crates/ipnetwork) 0.21 | no |
+//! | `with-serde-1` | Enable `Serialize` and `Deserialize` implementations for `Config`. | [serde](https://crates.io/crates/serde) 1.0 | no |
 //! | `with-serde_json-1` | Enable support for the `serde_json` crate. | [serde_json](https://crates.io/crates/serde_json) 1.0 | no |
+//! | `with-smol_str-01` | Enable support for the `smol_str` crate. | [smol_str](https://crates.io/crates/smol_str) 0.1 | no |
+//! | `with-simd-json-0_15` | Enable support for the `simd-json` crate. | [simd-json](https://crates.io/crates/simd-json) 0.15 | no |
+//! | `with-smallvec-1` | Enable support for the `smallvec` crate. | [smallvec](https://crates.io/crates/smallvec) 1.0 | no |
 //! | `with-uuid-0_8` | Enable support for the `uuid` crate. | [uuid](https://crates.io/crates/uuid) 0.8 | no |
 //! | `with-uuid-1` | Enable support for the `uuid` crate. | [uuid](https://crates.io/crates/uuid) 1.0 | no |
 //! | `with-time-0_2` | Enable support for the 0.2 version of the `time` crate. | [time](https://crates.io/crates/time/0.2.0) 0.2 | no |
@@ -70,11 +82,15 @@
 #![warn(clippy::all, rust_2018_idioms, missing_docs)]
 
 pub use fallible_iterator;
+#[cfg(feature = "explain")]
+pub use tokio_postgres::explain;
 pub use tokio_postgres::{
-    Column, IsolationLevel, Notification, Portal, SimpleQueryMessage, Socket, Statement,
-    ToStatement, error, row, tls, types,
+    ClientMetrics, Column, CustomParam, FromRow, IsolationLevel, Notification, Portal,
+    SimpleQueryMessage, Socket, Statement, ToStatement, TransactionStatus, TypeCache, TypeHandler,
+    TypeKey, error, row, tls, types,
 };
 
+pub use crate::bulk_insert::BulkInsert;
 pub use crate::cancel_token::CancelToken;
 pub use crate::client::*;
 pub use crate::config::Config;
@@ -85,6 +101,7 @@ pub use crate::error::Error;
 pub use crate::generic_client::GenericClient;
 #[doc(inline)]
 pub use crate::notifications::Notifications;
+pub use crate::reconnecting_client::ReconnectingClient;
 #[doc(no_inline)]
 pub use crate::row::{Row, SimpleQueryRow};
 pub use crate::row_iter::RowIter;
@@ -94,6 +111,7 @@ pub use crate::transaction::*;
 pub use crate::transaction_builder::TransactionBuilder;
 
 pub mod binary_copy;
+mod bulk_insert;
 mod cancel_token;
 mod client;
 pub mod config;
@@ -103,7 +121,10 @@ mod copy_out_reader;
 mod generic_client;
 mod lazy_pin;
 pub mod notifications;
+pub mod pool;
+mod reconnecting_client;
 mod row_iter;
+pub mod text_copy;
 mod transaction;
 mod transaction_builder;
 
@@ -4,10 +4,10 @@ use crate::connection::ConnectionRef;
 use crate::{Error, Notification};
 use fallible_iterator::FallibleIterator;
 use futures_util::FutureExt;
+use std::future::Future;
 use std::pin::Pin;
 use std::task::{Poll, ready};
 use std::time::Duration;
-use tokio::time::{self, Instant, Sleep};
 
 /// Notifications from a PostgreSQL backend.
 pub struct Notifications<'a> {
@@ -65,7 +65,7 @@ impl<'a> Notifications<'a> {
     /// This iterator may start returning `Some` after previously returning `None` if more notifications are received.
     pub fn timeout_iter(&mut self, timeout: Duration) -> TimeoutIter<'_> {
         TimeoutIter {
-            delay: Box::pin(self.connection.enter(|| time::sleep(timeout))),
+            delay: self.connection.sleep(timeout),
             timeout,
             connection: self.connection.as_ref(),
         }
@@ -125,7 +125,7 @@ impl FallibleIterator for BlockingIter<'_> {
 /// A time-limited blocking iterator over pending notifications.
 pub struct TimeoutIter<'a> {
     connection: ConnectionRef<'a>,
-    delay: Pin<Box<Sleep>>,
+    delay: Pin<Box<dyn Future<Output = ()> + Send>>,
     timeout: Duration,
 }
 
@@ -135,25 +135,26 @@ impl FallibleIterator for TimeoutIter<'_> {
 
     fn next(&mut self) -> Result<Option<Self::Item>, Self::Error> {
         if let Some(notification) = self.connection.notifications_mut().pop_front() {
-            self.delay.as_mut().reset(Instant::now() + self.timeout);
+            self.delay = self.connection.sleep(self.timeout);
             return Ok(Some(notification));
         }
 
         let delay = &mut self.delay;
-        let timeout = self.timeout;
-        self.connection.poll_block_on(|cx, notifications, done| {
+        let notification = self.connection.poll_block_on(|cx, notifications, done| {
             match notifications.pop_front() {
-                Some(notification) => {
-                    delay.as_mut().reset(Instant::now() + timeout);
-                    return Poll::Ready(Ok(Some(notification)));
-                }
+                Some(notification) => return Poll::Ready(Ok(Some(notification))),
                 None if done => return Poll::Ready(Ok(None)),
                 None => {}
             }
 
             ready!(delay.poll_unpin(cx));
             Poll::Ready(Ok(None))
-        })
+        })?;
+
+        if notification.is_some() {
+            self.delay = self.connection.sleep(self.timeout);
+        }
+        Ok(notification)
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
@@ -0,0 +1,376 @@
+//! A blocking connection pool.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use postgres::NoTls;
+//! use postgres::pool::Pool;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let config = "host=localhost user=postgres".parse()?;
+//! let pool = Pool::builder(config).max_size(16).build(NoTls);
+//!
+//! let mut client = pool.get()?;
+//! let row = client.query_one("SELECT 1::INT", &[])?;
+//! assert_eq!(row.get::<_, i32>(0), 1);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The pool does not run any background threads. Connections which have sat idle for longer than the idle timeout
+//! are closed the next time the pool is used.
+
+use crate::tls::{MakeTlsConnect, TlsConnect};
+use crate::{Client, Config, Error, Socket, TransactionStatus};
+use log::debug;
+use std::error;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+type ConnectFn = dyn Fn() -> Result<Client, Error> + Sync + Send;
+
+/// An error returned when checking a connection out of a [`Pool`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum PoolError {
+    /// No connection became available before the checkout timeout elapsed.
+    Timeout,
+    /// An error opening a new connection.
+    Backend(Error),
+}
+
+impl fmt::Display for PoolError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PoolError::Timeout => fmt.write_str("timed out waiting for a connection"),
+            PoolError::Backend(_) => fmt.write_str("error opening a new connection"),
+        }
+    }
+}
+
+impl error::Error for PoolError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            PoolError::Timeout => None,
+            PoolError::Backend(e) => Some(e),
+        }
+    }
+}
+
+impl From<Error> for PoolError {
+    fn from(e: Error) -> PoolError {
+        PoolError::Backend(e)
+    }
+}
+
+/// A builder for a [`Pool`].
+pub struct Builder {
+    config: Config,
+    max_size: usize,
+    checkout_timeout: Option<Duration>,
+    idle_timeout: Option<Duration>,
+    test_on_checkout: bool,
+}
+
+impl Builder {
+    /// Sets the maximum number of connections managed by the pool.
+    ///
+    /// Defaults to 10.
+    pub fn max_size(mut self, max_size: usize) -> Builder {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Sets the maximum amount of time to wait for a connection to be returned to the pool when all of them are in
+    /// use.
+    ///
+    /// The time taken to open a new connection is limited by the configuration's connect timeout instead.
+    ///
+    /// Defaults to 30 seconds. If `None`, [`Pool::get`] waits indefinitely.
+    pub fn checkout_timeout(mut self, checkout_timeout: Option<Duration>) -> Builder {
+        self.checkout_timeout = checkout_timeout;
+        self
+    }
+
+    /// Sets the amount of time a connection may sit idle in the pool before it is closed.
+    ///
+    /// Defaults to 10 minutes. If `None`, idle connections are kept open indefinitely.
+    pub fn idle_timeout(mut self, idle_timeout: Option<Duration>) -> Builder {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    /// Determines if idle connections are checked with a lightweight ping before being handed out.
+    ///
+    /// Connections which fail the check are discarded. Defaults to `true`.
+    pub fn test_on_checkout(mut self, test_on_checkout: bool) -> Builder {
+        self.test_on_checkout = test_on_checkout;
+        self
+    }
+
+    /// Creates the pool.
+    ///
+    /// No connections are opened until they are requested.
+    pub fn build<T>(self, tls: T) -> Pool
+    where
+        T: MakeTlsConnect<Socket> + Clone + Sync + Send + 'static,
+        T::TlsConnect: Send,
+        T::Stream: Send,
+        <T::TlsConnect as TlsConnect<Socket>>::Future: Send,
+    {
+        let config = self.config;
+        let connect = move || config.connect(tls.clone());
+
+        Pool {
+            inner: Arc::new(Inner {
+                connect: Box::new(connect),
+                state: Mutex::new(State {
+                    idle: vec![],
+                    in_use: 0,
+                }),
+                available: Condvar::new(),
+                max_size: self.max_size,
+                checkout_timeout: self.checkout_timeout,
+                idle_timeout: self.idle_timeout,
+                test_on_checkout: self.test_on_checkout,
+            }),
+        }
+    }
+}
+
+struct IdleConn {
+    client: Client,
+    since: Instant,
+}
+
+struct State {
+    idle: Vec<IdleConn>,
+    in_use: usize,
+}
+
+struct Inner {
+    connect: Box<ConnectFn>,
+    state: Mutex<State>,
+    available: Condvar,
+    max_size: usize,
+    checkout_timeout: Option<Duration>,
+    idle_timeout: Option<Duration>,
+    test_on_checkout: bool,
+}
+
+impl Inner {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Removes closed and expired connections from the idle set.
+    ///
+    /// Closing a connection blocks, so they are returned to be dropped after the lock is released.
+    fn take_expired(&self, idle: &mut Vec<IdleConn>) -> Vec<IdleConn> {
+        let (expired, live) = idle.drain(..).partition(|c| {
+            c.client.is_closed()
+                || self
+                    .idle_timeout
+                    .is_some_and(|timeout| c.since.elapsed() >= timeout)
+        });
+        *idle = live;
+        if !expired.is_empty() {
+            debug!("evicted {} idle connections", expired.len());
+        }
+        expired
+    }
+
+    /// Gives up a checked out slot, waking a thread waiting for one.
+    fn release(&self) {
+        self.lock().in_use -= 1;
+        self.available.notify_one();
+    }
+
+    fn get(self: &Arc<Self>) -> Result<PooledClient, PoolError> {
+        let deadline = self
+            .checkout_timeout
+            .map(|timeout| Instant::now() + timeout);
+
+        let mut expired = vec![];
+        let mut state = self.lock();
+        loop {
+            expired.extend(self.take_expired(&mut state.idle));
+
+            if let Some(idle) = state.idle.pop() {
+                state.in_use += 1;
+                drop(state);
+
+                let mut client = idle.client;
+                if self.test_on_checkout {
+                    if let Err(e) = client.check_connection() {
+                        debug!("discarding broken idle connection: {}", e);
+                        self.release();
+                        state = self.lock();
+                        continue;
+                    }
+                }
+
+                return Ok(PooledClient::new(client, self.clone()));
+            }
+
+            if state.in_use < self.max_size {
+                state.in_use += 1;
+                drop(state);
+
+                debug!("opening new pooled connection");
+                return match (self.connect)() {
+                    Ok(client) => Ok(PooledClient::new(client, self.clone())),
+                    Err(e) => {
+                        self.release();
+                        Err(PoolError::Backend(e))
+                    }
+                };
+            }
+
+            state = match deadline {
+                Some(deadline) => {
+                    let Some(timeout) = deadline.checked_duration_since(Instant::now()) else {
+                        return Err(PoolError::Timeout);
+                    };
+                    self.available
+                        .wait_timeout(state, timeout)
+                        .unwrap_or_else(|e| e.into_inner())
+                        .0
+                }
+                None => self
+                    .available
+                    .wait(state)
+                    .unwrap_or_else(|e| e.into_inner()),
+            };
+        }
+    }
+}
+
+/// A snapshot of the state of a [`Pool`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Status {
+    /// The maximum number of connections managed by the pool.
+    pub max_size: usize,
+    /// The number of connections currently checked out of the pool.
+    pub in_use: usize,
+    /// The number of idle connections in the pool.
+    pub idle: usize,
+}
+
+/// A blocking pool of connections.
+///
+/// Pools are cheap to clone; all clones share the same set of connections.
+#[derive(Clone)]
+pub struct Pool {
+    inner: Arc<Inner>,
+}
+
+impl fmt::Debug for Pool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pool")
+            .field("status", &self.status())
+            .finish()
+    }
+}
+
+impl Pool {
+    /// Returns a builder for a pool of connections to the database described by `config`.
+    pub fn builder(config: Config) -> Builder {
+        Builder {
+            config,
+            max_size: 10,
+            checkout_timeout: Some(Duration::from_secs(30)),
+            idle_timeout: Some(Duration::from_secs(10 * 60)),
+            test_on_checkout: true,
+        }
+    }
+
+    /// Checks a connection out of the pool, opening a new one if no idle connections are available.
+    ///
+    /// If the pool is at its maximum size, this blocks until another connection is returned. The connection is
+    /// returned to the pool when the [`PooledClient`] is dropped.
+    pub fn get(&self) -> Result<PooledClient, PoolError> {
+        self.inner.get()
+    }
+
+    /// Returns a snapshot of the pool's state.
+    pub fn status(&self) -> Status {
+        let state = self.inner.lock();
+        Status {
+            max_size: self.inner.max_size,
+            in_use: state.in_use,
+            idle: state.idle.len(),
+        }
+    }
+}
+
+/// A connection checked out of a [`Pool`].
+///
+/// It dereferences to a [`Client`], and returns the connection to the pool when dropped. Connections which have been
+/// closed, or which are not idle at that point because a transaction was left open or a query is still in progress,
+/// are considered broken and are discarded instead.
+pub struct PooledClient {
+    client: Option<Client>,
+    inner: Arc<Inner>,
+}
+
+impl PooledClient {
+    fn new(client: Client, inner: Arc<Inner>) -> PooledClient {
+        PooledClient {
+            client: Some(client),
+            inner,
+        }
+    }
+}
+
+impl Deref for PooledClient {
+    type Target = Client;
+
+    fn deref(&self) -> &Client {
+        self.client.as_ref().unwrap()
+    }
+}
+
+impl DerefMut for PooledClient {
+    fn deref_mut(&mut self) -> &mut Client {
+        self.client.as_mut().unwrap()
+    }
+}
+
+impl fmt::Debug for PooledClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PooledClient").finish()
+    }
+}
+
+impl Drop for PooledClient {
+    fn drop(&mut self) {
+        let Some(client) = self.client.take() else {
+            return;
+        };
+
+        let mut state = self.inner.lock();
+        state.in_use -= 1;
+        let discarded = if client.is_closed() {
+            debug!("discarding closed pooled connection");
+            Some(client)
+        } else if client.transaction_status() != TransactionStatus::Idle {
+            debug!("discarding pooled connection which is not idle");
+            Some(client)
+        } else {
+            state.idle.push(IdleConn {
+                client,
+                since: Instant::now(),
+            });
+            None
+        };
+        let expired = self.inner.take_expired(&mut state.idle);
+        drop(state);
+        self.inner.available.notify_one();
+
+        drop(discarded);
+        drop(expired);
+    }
+}
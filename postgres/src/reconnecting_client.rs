@@ -0,0 +1,178 @@
+use crate::tls::{MakeTlsConnect, TlsConnect};
+use crate::types::ToSql;
+use crate::{Client, Config, Error, Row, SimpleQueryMessage, Socket};
+use log::{debug, warn};
+use std::thread;
+use std::time::Duration;
+
+/// A wrapper around a [`Client`] which reestablishes the connection to the server when it dies.
+///
+/// Queries which are in flight when the connection fails return an error as normal. The next request made through
+/// the `ReconnectingClient` opens a new connection, retrying with exponential backoff if the server can't be reached.
+///
+/// Since prepared statements and other session state are tied to a single connection, the convenience methods on this
+/// type only accept query strings. Use [`client`](ReconnectingClient::client) to access the full `Client` API, keeping
+/// in mind that any `Statement`s it returns are invalidated by a reconnect.
+///
+/// # Examples
+///
+/// ```no_run
+/// use postgres::{NoTls, ReconnectingClient};
+///
+/// # fn main() -> Result<(), postgres::Error> {
+/// let config = "host=localhost user=postgres".parse()?;
+/// let mut client = ReconnectingClient::new(config, NoTls);
+///
+/// let row = client.query_one("SELECT 1::INT", &[])?;
+/// assert_eq!(row.get::<_, i32>(0), 1);
+/// # Ok(())
+/// # }
+/// ```
+pub struct ReconnectingClient<T> {
+    config: Config,
+    tls: T,
+    client: Option<Client>,
+    max_attempts: u32,
+    min_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl<T> ReconnectingClient<T>
+where
+    T: MakeTlsConnect<Socket> + Clone + 'static + Send,
+    T::TlsConnect: Send,
+    T::Stream: Send,
+    <T::TlsConnect as TlsConnect<Socket>>::Future: Send,
+{
+    /// Creates a new client which will connect to the database described by `config`.
+    ///
+    /// No connection is made until the client is first used.
+    pub fn new(config: Config, tls: T) -> ReconnectingClient<T> {
+        ReconnectingClient {
+            config,
+            tls,
+            client: None,
+            max_attempts: 5,
+            min_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+
+    /// Sets the maximum number of connection attempts made by a single request before giving up.
+    ///
+    /// Defaults to 5.
+    pub fn max_attempts(&mut self, max_attempts: u32) -> &mut ReconnectingClient<T> {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Sets the range of delays between consecutive connection attempts.
+    ///
+    /// The delay starts at `min` and doubles after each failed attempt up to `max`.
+    ///
+    /// Defaults to 100 milliseconds and 10 seconds.
+    pub fn backoff(&mut self, min: Duration, max: Duration) -> &mut ReconnectingClient<T> {
+        self.min_backoff = min;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Determines if the client currently holds an open connection.
+    pub fn is_connected(&self) -> bool {
+        self.client.as_ref().is_some_and(|c| !c.is_closed())
+    }
+
+    /// Returns the underlying client, connecting first if necessary.
+    pub fn client(&mut self) -> Result<&mut Client, Error> {
+        if !self.is_connected() {
+            self.client = None;
+            let client = self.connect()?;
+            self.client = Some(client);
+        }
+
+        Ok(self.client.as_mut().unwrap())
+    }
+
+    /// Executes a statement, returning the number of rows modified.
+    ///
+    /// See [`Client::execute`].
+    pub fn execute(&mut self, query: &str, params: &[&(dyn ToSql + Sync)]) -> Result<u64, Error> {
+        self.run(|client| client.execute(query, params))
+    }
+
+    /// Executes a statement, returning the resulting rows.
+    ///
+    /// See [`Client::query`].
+    pub fn query(
+        &mut self,
+        query: &str,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Vec<Row>, Error> {
+        self.run(|client| client.query(query, params))
+    }
+
+    /// Executes a statement which returns a single row, returning it.
+    ///
+    /// See [`Client::query_one`].
+    pub fn query_one(&mut self, query: &str, params: &[&(dyn ToSql + Sync)]) -> Result<Row, Error> {
+        self.run(|client| client.query_one(query, params))
+    }
+
+    /// Executes a statement which returns zero or one rows, returning it.
+    ///
+    /// See [`Client::query_opt`].
+    pub fn query_opt(
+        &mut self,
+        query: &str,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Option<Row>, Error> {
+        self.run(|client| client.query_opt(query, params))
+    }
+
+    /// Executes a sequence of SQL statements using the simple query protocol, returning the resulting rows.
+    ///
+    /// See [`Client::simple_query`].
+    pub fn simple_query(&mut self, query: &str) -> Result<Vec<SimpleQueryMessage>, Error> {
+        self.run(|client| client.simple_query(query))
+    }
+
+    /// Executes a sequence of SQL statements using the simple query protocol.
+    ///
+    /// See [`Client::batch_execute`].
+    pub fn batch_execute(&mut self, query: &str) -> Result<(), Error> {
+        self.run(|client| client.batch_execute(query))
+    }
+
+    fn run<F, R>(&mut self, f: F) -> Result<R, Error>
+    where
+        F: FnOnce(&mut Client) -> Result<R, Error>,
+    {
+        let client = self.client()?;
+        let result = f(client);
+        if result.is_err() && client.is_closed() {
+            debug!("connection closed, reconnecting on next use");
+            self.client = None;
+        }
+        result
+    }
+
+    fn connect(&self) -> Result<Client, Error> {
+        let mut backoff = self.min_backoff;
+        let mut attempt = 1;
+        loop {
+            match self.config.connect(self.tls.clone()) {
+                Ok(client) => return Ok(client),
+                Err(e) if attempt >= self.max_attempts => return Err(e),
+                Err(e) => {
+                    warn!(
+                        "connection attempt {} failed, retrying in {:?}: {}",
+                        attempt, backoff, e
+                    );
+                    thread::sleep(backoff);
+                    backoff = (backoff * 2).min(self.max_backoff);
+                    attempt += 1;
+                }
+            }
+        }
+    }
+}
@@ -2,13 +2,14 @@ use std::io::{Read, Write};
 use std::str::FromStr;
 use std::sync::mpsc;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio_postgres::NoTls;
 use tokio_postgres::error::SqlState;
-use tokio_postgres::types::Type;
+use tokio_postgres::types::{ToSql, Type};
 
 use super::*;
 use crate::binary_copy::{BinaryCopyInWriter, BinaryCopyOutIter};
+use crate::text_copy::{TextCopyFormat, TextCopyInWriter, TextCopyOutIter};
 use fallible_iterator::FallibleIterator;
 
 #[test]
@@ -271,6 +272,67 @@ fn copy_in_abort() {
     assert_eq!(rows.len(), 0);
 }
 
+#[test]
+fn bulk_insert() {
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+
+    client
+        .simple_query("CREATE TEMPORARY TABLE foo (id INT PRIMARY KEY, name TEXT)")
+        .unwrap();
+
+    let inserted = client
+        .bulk_insert("foo", &["id", "name"])
+        .on_conflict("(id) DO NOTHING")
+        .execute([
+            [&1i32 as &(dyn ToSql + Sync), &"steven"],
+            [&2i32, &"timothy"],
+            [&1i32, &"duplicate"],
+        ])
+        .unwrap();
+    assert_eq!(inserted, 2);
+
+    let rows = client
+        .query("SELECT id, name FROM foo ORDER BY id", &[])
+        .unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].get::<_, &str>(1), "steven");
+    assert_eq!(rows[1].get::<_, &str>(1), "timothy");
+}
+
+#[test]
+fn execute_many() {
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+
+    client
+        .simple_query("CREATE TEMPORARY TABLE foo (id INT PRIMARY KEY, name TEXT)")
+        .unwrap();
+
+    let mut transaction = client.transaction().unwrap();
+    let rows = transaction
+        .execute_many(
+            "INSERT INTO foo (id, name) VALUES ($1, $2)",
+            [
+                [&1i32 as &(dyn ToSql + Sync), &"steven"],
+                [&2i32, &"timothy"],
+            ],
+        )
+        .unwrap();
+    assert_eq!(rows, [1, 1]);
+    transaction.commit().unwrap();
+
+    let rows = client
+        .execute_many(
+            "DELETE FROM foo WHERE id = $1",
+            [[&2i32 as &(dyn ToSql + Sync)], [&3i32]],
+        )
+        .unwrap();
+    assert_eq!(rows, [1, 0]);
+
+    let rows = client.query("SELECT name FROM foo", &[]).unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].get::<_, &str>(0), "steven");
+}
+
 #[test]
 fn binary_copy_in() {
     let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
@@ -296,6 +358,31 @@ fn binary_copy_in() {
     assert_eq!(rows[1].get::<_, &str>(1), "timothy");
 }
 
+#[test]
+fn text_copy_in() {
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+
+    client
+        .simple_query("CREATE TEMPORARY TABLE foo (id INT, name TEXT)")
+        .unwrap();
+
+    let writer = client.copy_in("COPY foo FROM stdin (FORMAT csv)").unwrap();
+    let mut writer = TextCopyInWriter::new(writer, &[Type::INT4, Type::TEXT], TextCopyFormat::Csv);
+    writer.write(&[&1i32, &"steven, \"steve\""]).unwrap();
+    writer.write(&[&2i32, &None::<&str>]).unwrap();
+    writer.finish().unwrap();
+
+    let rows = client
+        .query("SELECT id, name FROM foo ORDER BY id", &[])
+        .unwrap();
+
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].get::<_, i32>(0), 1);
+    assert_eq!(rows[0].get::<_, &str>(1), "steven, \"steve\"");
+    assert_eq!(rows[1].get::<_, i32>(0), 2);
+    assert_eq!(rows[1].get::<_, Option<&str>>(1), None);
+}
+
 #[test]
 fn copy_out() {
     let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
@@ -317,6 +404,33 @@ fn copy_out() {
     client.simple_query("SELECT 1").unwrap();
 }
 
+#[test]
+fn text_copy_out() {
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+
+    client
+        .simple_query(
+            "CREATE TEMPORARY TABLE foo (id INT, name TEXT);
+             INSERT INTO foo (id, name) VALUES (1, 'steven'), (2, NULL);",
+        )
+        .unwrap();
+
+    let reader = client
+        .copy_out("COPY (SELECT id, name FROM foo ORDER BY id) TO STDOUT")
+        .unwrap();
+    let rows = TextCopyOutIter::new(reader, TextCopyFormat::Text)
+        .collect::<Vec<_>>()
+        .unwrap();
+
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].parse::<i32>(0), Some(1));
+    assert_eq!(rows[0].get(1), Some("steven"));
+    assert_eq!(rows[1].parse::<i32>(0), Some(2));
+    assert_eq!(rows[1].get(1), None);
+
+    client.simple_query("SELECT 1").unwrap();
+}
+
 #[test]
 fn binary_copy_out() {
     let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
@@ -529,3 +643,393 @@ fn is_closed() {
     client.check_connection().unwrap_err();
     assert!(client.is_closed());
 }
+
+#[test]
+fn reset() {
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+
+    client
+        .batch_execute(
+            "\
+        LISTEN reset;
+        NOTIFY reset, 'hello';
+        SET application_name = 'reset';
+    ",
+        )
+        .unwrap();
+    assert_eq!(client.notifications().len(), 1);
+
+    client.reset().unwrap();
+    assert!(client.notifications().is_empty());
+
+    let row = client.query_one("SHOW application_name", &[]).unwrap();
+    assert_eq!(row.get::<_, &str>(0), "");
+
+    client.batch_execute("NOTIFY reset, 'world'").unwrap();
+    let notifications = client.notifications().iter().collect::<Vec<_>>().unwrap();
+    assert!(notifications.is_empty());
+}
+
+#[test]
+fn reconnecting_client() {
+    let config = "host=localhost port=5433 user=postgres application_name=reconnecting_client"
+        .parse()
+        .unwrap();
+    let mut client = ReconnectingClient::new(config, NoTls);
+    client.backoff(Duration::from_millis(10), Duration::from_millis(100));
+    assert!(!client.is_connected());
+
+    let pid = client
+        .query_one("SELECT pg_backend_pid()", &[])
+        .unwrap()
+        .get::<_, i32>(0);
+    assert!(client.is_connected());
+
+    let mut killer = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+    killer
+        .execute("SELECT pg_terminate_backend($1)", &[&pid])
+        .unwrap();
+
+    client.batch_execute("SELECT 1").unwrap_err();
+    assert!(!client.is_connected());
+
+    let new_pid = client
+        .query_one("SELECT pg_backend_pid()", &[])
+        .unwrap()
+        .get::<_, i32>(0);
+    assert_ne!(pid, new_pid);
+}
+
+#[test]
+fn connect_raw() {
+    let (stream, mut server) = tokio::io::duplex(8192);
+
+    // forward the in-memory stream to the database from a separate runtime
+    thread::spawn(move || {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(async move {
+                let mut upstream = tokio::net::TcpStream::connect("localhost:5433")
+                    .await
+                    .unwrap();
+                let _ = tokio::io::copy_bidirectional(&mut server, &mut upstream).await;
+            });
+    });
+
+    let config = "user=postgres".parse::<Config>().unwrap();
+    let mut client = config.connect_raw(stream, NoTls).unwrap();
+    let row = client.query_one("SELECT 1::INT", &[]).unwrap();
+    assert_eq!(row.get::<_, i32>(0), 1);
+}
+
+#[test]
+fn runtime_handle() {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .enable_all()
+        .build()
+        .unwrap();
+
+    let mut config = "host=localhost port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap();
+    config.runtime_handle(runtime.handle().clone());
+
+    let clients = (0..2)
+        .map(|_| config.connect(NoTls).unwrap())
+        .collect::<Vec<_>>();
+    for mut client in clients {
+        let row = client.query_one("SELECT 1::INT", &[]).unwrap();
+        assert_eq!(row.get::<_, i32>(0), 1);
+    }
+}
+
+#[test]
+#[should_panic(expected = "current-thread runtime")]
+fn runtime_handle_current_thread() {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    Config::new().runtime_handle(runtime.handle().clone());
+}
+
+#[test]
+fn shared_runtime() {
+    let mut config = "host=localhost port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap();
+    config.shared_runtime(true);
+
+    let mut clients = (0..2)
+        .map(|_| config.connect(NoTls).unwrap())
+        .collect::<Vec<_>>();
+    let handles = clients
+        .drain(..)
+        .map(|mut client| {
+            thread::spawn(move || {
+                let row = client.query_one("SELECT 1::INT", &[]).unwrap();
+                row.get::<_, i32>(0)
+            })
+        })
+        .collect::<Vec<_>>();
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), 1);
+    }
+}
+
+#[test]
+#[cfg(feature = "with-smol-2")]
+fn custom_runtime() {
+    let mut config = "host=localhost port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap();
+    config
+        .runtime(tokio_postgres::rt::Smol)
+        .query_timeout(Duration::from_millis(100));
+    let mut client = config.connect(NoTls).unwrap();
+
+    let row = client.query_one("SELECT 1::INT", &[]).unwrap();
+    assert_eq!(row.get::<_, i32>(0), 1);
+
+    let err = client.batch_execute("SELECT pg_sleep(10)").unwrap_err();
+    assert_eq!(err.code(), Some(&SqlState::QUERY_CANCELED));
+
+    client.batch_execute("LISTEN custom_runtime").unwrap();
+    let mut notifications = client.notifications();
+    let mut it = notifications.timeout_iter(Duration::from_millis(10));
+    assert!(it.next().unwrap().is_none());
+}
+
+#[test]
+fn pool() {
+    use crate::pool::{Pool, Status};
+
+    let pool = Pool::builder("host=localhost port=5433 user=postgres".parse().unwrap())
+        .max_size(1)
+        .checkout_timeout(Some(Duration::from_millis(100)))
+        .build(NoTls);
+
+    let client = pool.get().unwrap();
+    let pid = client.backend_pid();
+    assert_eq!(
+        pool.status(),
+        Status {
+            max_size: 1,
+            in_use: 1,
+            idle: 0
+        }
+    );
+    assert!(matches!(pool.get(), Err(pool::PoolError::Timeout)));
+
+    // a connection returned from another thread wakes up waiters
+    let waiter = thread::spawn({
+        let pool = pool.clone();
+        move || pool.get().map(|c| c.backend_pid())
+    });
+    thread::sleep(Duration::from_millis(20));
+    drop(client);
+    assert_eq!(waiter.join().unwrap().unwrap(), pid);
+
+    let client = pool.get().unwrap();
+    assert_eq!(client.backend_pid(), pid);
+}
+
+#[test]
+fn pool_idle_timeout() {
+    use crate::pool::Pool;
+
+    let pool = Pool::builder("host=localhost port=5433 user=postgres".parse().unwrap())
+        .idle_timeout(Some(Duration::from_millis(10)))
+        .build(NoTls);
+
+    let client = pool.get().unwrap();
+    let pid = client.backend_pid();
+    drop(client);
+
+    thread::sleep(Duration::from_millis(50));
+    let client = pool.get().unwrap();
+    assert_ne!(client.backend_pid(), pid);
+    assert_eq!(pool.status().idle, 0);
+}
+
+#[test]
+fn pool_broken_connection() {
+    use crate::pool::Pool;
+
+    let pool = Pool::builder("host=localhost port=5433 user=postgres".parse().unwrap())
+        .max_size(1)
+        .build(NoTls);
+
+    // connections left in a transaction are discarded
+    let mut client = pool.get().unwrap();
+    let pid = client.backend_pid();
+    client.batch_execute("BEGIN").unwrap();
+    drop(client);
+    assert_eq!(pool.status().idle, 0);
+
+    let mut client = pool.get().unwrap();
+    assert_ne!(client.backend_pid(), pid);
+    assert_eq!(client.transaction_status(), TransactionStatus::Idle);
+    client.transaction().unwrap();
+    let pid = client.backend_pid();
+    drop(client);
+
+    // as are idle connections which have been terminated
+    let mut killer = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+    killer
+        .execute("SELECT pg_terminate_backend($1)", &[&pid])
+        .unwrap();
+
+    let client = pool.get().unwrap();
+    assert_ne!(client.backend_pid(), pid);
+}
+
+#[test]
+fn with_transaction() {
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+
+    client
+        .simple_query("CREATE TEMPORARY TABLE foo (id SERIAL PRIMARY KEY)")
+        .unwrap();
+
+    let id = client
+        .with_transaction(|transaction| {
+            let row = transaction.query_one("INSERT INTO foo DEFAULT VALUES RETURNING id", &[])?;
+            Ok::<i32, Error>(row.get(0))
+        })
+        .unwrap();
+    assert_eq!(id, 1);
+
+    let err = client
+        .with_transaction(|transaction| {
+            transaction.execute("INSERT INTO foo DEFAULT VALUES", &[])?;
+            Err::<(), Box<dyn std::error::Error>>("oops".into())
+        })
+        .unwrap_err();
+    assert_eq!(err.to_string(), "oops");
+
+    let rows = client.query("SELECT * FROM foo", &[]).unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(client.transaction_status(), TransactionStatus::Idle);
+}
+
+#[test]
+fn with_transaction_retry() {
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+
+    client
+        .simple_query("CREATE TEMPORARY TABLE foo (id SERIAL PRIMARY KEY)")
+        .unwrap();
+
+    let fail = |transaction: &mut Transaction<'_>| {
+        transaction.batch_execute(
+            "DO $$ BEGIN RAISE EXCEPTION USING ERRCODE = 'serialization_failure'; END $$",
+        )
+    };
+
+    let mut attempts = 0;
+    client
+        .build_transaction()
+        .isolation_level(IsolationLevel::Serializable)
+        .max_retries(3)
+        .run(|transaction| {
+            attempts += 1;
+            transaction.execute("INSERT INTO foo DEFAULT VALUES", &[])?;
+            if attempts < 3 {
+                fail(transaction)?;
+            }
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(attempts, 3);
+
+    let rows = client.query("SELECT * FROM foo", &[]).unwrap();
+    assert_eq!(rows.len(), 1);
+
+    // retries are disabled by default
+    let mut attempts = 0;
+    let err = client
+        .with_transaction(|transaction| {
+            attempts += 1;
+            fail(transaction)
+        })
+        .unwrap_err();
+    assert_eq!(attempts, 1);
+    assert_eq!(err.code(), Some(&SqlState::T_R_SERIALIZATION_FAILURE));
+
+    // other errors are never retried
+    let mut attempts = 0;
+    client
+        .build_transaction()
+        .max_retries(3)
+        .run(|transaction| {
+            attempts += 1;
+            transaction.batch_execute("SELECT * FROM missing_table")
+        })
+        .unwrap_err();
+    assert_eq!(attempts, 1);
+
+    // errors which wrap an `Error` are retried according to the classifier
+    #[derive(Debug)]
+    enum AppError {
+        Db(Error),
+    }
+
+    impl From<Error> for AppError {
+        fn from(e: Error) -> AppError {
+            AppError::Db(e)
+        }
+    }
+
+    let mut attempts = 0;
+    client
+        .build_transaction()
+        .max_retries(3)
+        .run_retrying(
+            |e| matches!(e, AppError::Db(e) if e.is_serialization_failure()),
+            |transaction| {
+                attempts += 1;
+                if attempts < 2 {
+                    fail(transaction)?;
+                }
+                Ok::<_, AppError>(())
+            },
+        )
+        .unwrap();
+    assert_eq!(attempts, 2);
+}
+
+#[test]
+fn query_timeout() {
+    let mut config = "host=localhost port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap();
+    config.query_timeout(Duration::from_millis(100));
+    let mut client = config.connect(NoTls).unwrap();
+
+    let start = Instant::now();
+    let err = client.batch_execute("SELECT pg_sleep(10)").unwrap_err();
+    assert_eq!(err.code(), Some(&SqlState::QUERY_CANCELED));
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    let row = client.query_one("SELECT 1::INT", &[]).unwrap();
+    assert_eq!(row.get::<_, i32>(0), 1);
+}
+
+#[test]
+fn query_timeout_notifications() {
+    let mut config = "host=localhost port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap();
+    config.query_timeout(Duration::from_millis(100));
+    let mut client = config.connect(NoTls).unwrap();
+
+    client.batch_execute("LISTEN query_timeout").unwrap();
+    let start = Instant::now();
+    let mut notifications = client.notifications();
+    let mut it = notifications.timeout_iter(Duration::from_millis(500));
+    assert!(it.next().unwrap().is_none());
+    assert!(start.elapsed() >= Duration::from_millis(500));
+}
@@ -0,0 +1,103 @@
+//! Utilities for working with the PostgreSQL text and CSV copy formats.
+//!
+//! See [`tokio_postgres::text_copy`] for details.
+
+use crate::connection::ConnectionRef;
+use crate::types::{BorrowToSql, ToSql, Type};
+use crate::{CopyInWriter, CopyOutReader, Error};
+use fallible_iterator::FallibleIterator;
+use futures_util::StreamExt;
+use std::pin::Pin;
+use tokio_postgres::text_copy::{self, TextCopyOutStream};
+#[doc(inline)]
+pub use tokio_postgres::text_copy::{TextCopyFormat, TextCopyOutRow};
+
+/// A type which serializes rows of values into the PostgreSQL text or CSV copy format.
+///
+/// The copy *must* be explicitly completed via the `finish` method. If it is not, the copy will be aborted.
+pub struct TextCopyInWriter<'a> {
+    connection: ConnectionRef<'a>,
+    sink: Pin<Box<text_copy::TextCopyInWriter>>,
+}
+
+impl<'a> TextCopyInWriter<'a> {
+    /// Creates a new writer which will write rows of the provided types in the given format.
+    pub fn new(
+        writer: CopyInWriter<'a>,
+        types: &[Type],
+        format: TextCopyFormat,
+    ) -> TextCopyInWriter<'a> {
+        let stream = writer
+            .sink
+            .into_unpinned()
+            .expect("writer has already been written to");
+
+        TextCopyInWriter {
+            connection: writer.connection,
+            sink: Box::pin(text_copy::TextCopyInWriter::new(stream, types, format)),
+        }
+    }
+
+    /// Writes a single row.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of values provided does not match the number expected.
+    pub fn write(&mut self, values: &[&(dyn ToSql + Sync)]) -> Result<(), Error> {
+        self.connection.block_on(self.sink.as_mut().write(values))
+    }
+
+    /// A maximally-flexible version of `write`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of values provided does not match the number expected.
+    pub fn write_raw<P, I>(&mut self, values: I) -> Result<(), Error>
+    where
+        P: BorrowToSql,
+        I: IntoIterator<Item = P>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        self.connection
+            .block_on(self.sink.as_mut().write_raw(values))
+    }
+
+    /// Completes the copy, returning the number of rows added.
+    ///
+    /// This method *must* be used to complete the copy process. If it is not, the copy will be aborted.
+    pub fn finish(mut self) -> Result<u64, Error> {
+        self.connection.block_on(self.sink.as_mut().finish())
+    }
+}
+
+/// An iterator of rows parsed from the PostgreSQL text or CSV copy format.
+pub struct TextCopyOutIter<'a> {
+    connection: ConnectionRef<'a>,
+    stream: Pin<Box<TextCopyOutStream>>,
+}
+
+impl<'a> TextCopyOutIter<'a> {
+    /// Creates a new iterator from a raw copy out reader and the format of the data being returned.
+    pub fn new(reader: CopyOutReader<'a>, format: TextCopyFormat) -> TextCopyOutIter<'a> {
+        let stream = reader
+            .stream
+            .into_unpinned()
+            .expect("reader has already been read from");
+
+        TextCopyOutIter {
+            connection: reader.connection,
+            stream: Box::pin(TextCopyOutStream::new(stream, format)),
+        }
+    }
+}
+
+impl FallibleIterator for TextCopyOutIter<'_> {
+    type Item = TextCopyOutRow;
+    type Error = Error;
+
+    fn next(&mut self) -> Result<Option<TextCopyOutRow>, Error> {
+        let stream = &mut self.stream;
+        self.connection
+            .block_on(async { stream.next().await.transpose() })
+    }
+}
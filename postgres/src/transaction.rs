@@ -1,5 +1,9 @@
 use crate::connection::ConnectionRef;
-use crate::{CancelToken, CopyInWriter, CopyOutReader, Portal, RowIter, Statement, ToStatement};
+use crate::{
+    BulkInsert, CancelToken, CopyInWriter, CopyOutReader, Portal, RowIter, Statement, ToStatement,
+};
+#[cfg(feature = "explain")]
+use tokio_postgres::explain::{Explain, ExplainOptions};
 use tokio_postgres::types::{BorrowToSql, ToSql, Type};
 use tokio_postgres::{Error, Row, SimpleQueryMessage};
 
@@ -84,6 +88,27 @@ impl<'a> Transaction<'a> {
         )
     }
 
+    /// Like `Client::execute_many`.
+    pub fn execute_many<T, I, J, P>(
+        &mut self,
+        statement: &T,
+        param_sets: I,
+    ) -> Result<Vec<u64>, Error>
+    where
+        T: ?Sized + ToStatement,
+        I: IntoIterator<Item = J>,
+        J: IntoIterator<Item = P>,
+        J::IntoIter: ExactSizeIterator,
+        P: BorrowToSql,
+    {
+        self.connection.block_on(
+            self.transaction
+                .as_ref()
+                .unwrap()
+                .execute_many(statement, param_sets),
+        )
+    }
+
     /// Like `Client::query`.
     pub fn query<T>(&mut self, query: &T, params: &[&(dyn ToSql + Sync)]) -> Result<Vec<Row>, Error>
     where
@@ -232,6 +257,33 @@ impl<'a> Transaction<'a> {
         Ok(RowIter::new(self.connection.as_ref(), stream))
     }
 
+    /// Like `Client::explain`.
+    #[cfg(feature = "explain")]
+    pub fn explain(
+        &mut self,
+        query: &str,
+        params: &[&(dyn ToSql + Sync)],
+        options: ExplainOptions,
+    ) -> Result<Explain, Error> {
+        self.connection.block_on(
+            self.transaction
+                .as_ref()
+                .unwrap()
+                .explain(query, params, options),
+        )
+    }
+
+    /// Like `Client::bulk_insert`.
+    pub fn bulk_insert(&mut self, table: &str, columns: &[&str]) -> BulkInsert<'_> {
+        BulkInsert::new(
+            self.connection.as_ref(),
+            self.transaction
+                .as_ref()
+                .unwrap()
+                .bulk_insert(table, columns),
+        )
+    }
+
     /// Like `Client::copy_in`.
     pub fn copy_in<T>(&mut self, query: &T) -> Result<CopyInWriter<'_>, Error>
     where
@@ -1,32 +1,41 @@
 use crate::connection::ConnectionRef;
 use crate::{Error, IsolationLevel, Transaction};
+use log::debug;
 
 /// A builder for database transactions.
 pub struct TransactionBuilder<'a> {
     connection: ConnectionRef<'a>,
-    builder: tokio_postgres::TransactionBuilder<'a>,
+    client: &'a mut tokio_postgres::Client,
+    isolation_level: Option<IsolationLevel>,
+    read_only: Option<bool>,
+    deferrable: Option<bool>,
+    max_retries: u32,
 }
 
 impl<'a> TransactionBuilder<'a> {
     pub(crate) fn new(
         connection: ConnectionRef<'a>,
-        builder: tokio_postgres::TransactionBuilder<'a>,
+        client: &'a mut tokio_postgres::Client,
     ) -> TransactionBuilder<'a> {
         TransactionBuilder {
             connection,
-            builder,
+            client,
+            isolation_level: None,
+            read_only: None,
+            deferrable: None,
+            max_retries: 0,
         }
     }
 
     /// Sets the isolation level of the transaction.
     pub fn isolation_level(mut self, isolation_level: IsolationLevel) -> Self {
-        self.builder = self.builder.isolation_level(isolation_level);
+        self.isolation_level = Some(isolation_level);
         self
     }
 
     /// Sets the access mode of the transaction.
     pub fn read_only(mut self, read_only: bool) -> Self {
-        self.builder = self.builder.read_only(read_only);
+        self.read_only = Some(read_only);
         self
     }
 
@@ -36,15 +45,151 @@ impl<'a> TransactionBuilder<'a> {
     /// completes the transaction is able to run with less overhead and a guarantee that it will not be aborted due to
     /// serialization failure.
     pub fn deferrable(mut self, deferrable: bool) -> Self {
-        self.builder = self.builder.deferrable(deferrable);
+        self.deferrable = Some(deferrable);
+        self
+    }
+
+    /// Sets the number of times [`run`](TransactionBuilder::run) retries a transaction which fails due to a
+    /// serialization failure or deadlock.
+    ///
+    /// Defaults to 0.
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
         self
     }
 
     /// Begins the transaction.
     ///
     /// The transaction will roll back by default - use the `commit` method to commit it.
-    pub fn start(mut self) -> Result<Transaction<'a>, Error> {
-        let transaction = self.connection.block_on(self.builder.start())?;
-        Ok(Transaction::new(self.connection, transaction))
+    pub fn start(self) -> Result<Transaction<'a>, Error> {
+        let TransactionBuilder {
+            mut connection,
+            client,
+            isolation_level,
+            read_only,
+            deferrable,
+            ..
+        } = self;
+
+        let builder = configure(
+            client.build_transaction(),
+            isolation_level,
+            read_only,
+            deferrable,
+        );
+        let transaction = connection.block_on(builder.start())?;
+        Ok(Transaction::new(connection, transaction))
+    }
+
+    /// Runs a closure inside of the transaction.
+    ///
+    /// The transaction is committed if the closure returns `Ok`, and rolled back if it returns `Err`.
+    ///
+    /// If [retries](TransactionBuilder::max_retries) are enabled, the closure is run again in a new transaction when
+    /// it or the commit fails with a serialization failure or deadlock. Use
+    /// [`run_retrying`](TransactionBuilder::run_retrying) if the closure returns a different error type.
+    pub fn run<F, T>(self, f: F) -> Result<T, Error>
+    where
+        F: FnMut(&mut Transaction<'_>) -> Result<T, Error>,
+    {
+        self.run_retrying(|e| e.is_serialization_failure() || e.is_deadlock(), f)
+    }
+
+    /// Like [`run`](TransactionBuilder::run), but with a function which determines whether a failed attempt should be
+    /// retried.
+    ///
+    /// `is_retryable` is called with the error returned by the closure, or by the commit, when retries are still
+    /// available. This allows errors which wrap an [`Error`] to be recognized.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use postgres::{Client, Error, IsolationLevel, NoTls};
+    ///
+    /// enum AppError {
+    ///     Db(Error),
+    ///     InsufficientFunds,
+    /// }
+    ///
+    /// impl From<Error> for AppError {
+    ///     fn from(e: Error) -> AppError {
+    ///         AppError::Db(e)
+    ///     }
+    /// }
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let mut client = Client::connect("host=localhost user=postgres", NoTls)?;
+    ///
+    /// let result = client
+    ///     .build_transaction()
+    ///     .isolation_level(IsolationLevel::Serializable)
+    ///     .max_retries(3)
+    ///     .run_retrying(
+    ///         |e| matches!(e, AppError::Db(e) if e.is_serialization_failure()),
+    ///         |transaction| {
+    ///             let row = transaction.query_one("SELECT balance FROM accounts WHERE id = 1", &[])?;
+    ///             if row.get::<_, i64>(0) < 10 {
+    ///                 return Err(AppError::InsufficientFunds);
+    ///             }
+    ///             transaction.execute("UPDATE accounts SET balance = balance - 10 WHERE id = 1", &[])?;
+    ///             Ok(())
+    ///         },
+    ///     );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn run_retrying<F, T, E, R>(mut self, mut is_retryable: R, mut f: F) -> Result<T, E>
+    where
+        F: FnMut(&mut Transaction<'_>) -> Result<T, E>,
+        E: From<Error>,
+        R: FnMut(&E) -> bool,
+    {
+        let mut retries = 0;
+        loop {
+            match self.run_once(&mut f) {
+                Err(e) if retries < self.max_retries && is_retryable(&e) => {
+                    retries += 1;
+                    debug!("retrying transaction, attempt {}", retries + 1);
+                }
+                r => return r,
+            }
+        }
+    }
+
+    fn run_once<F, T, E>(&mut self, f: &mut F) -> Result<T, E>
+    where
+        F: FnMut(&mut Transaction<'_>) -> Result<T, E>,
+        E: From<Error>,
+    {
+        let builder = configure(
+            self.client.build_transaction(),
+            self.isolation_level,
+            self.read_only,
+            self.deferrable,
+        );
+        let transaction = self.connection.block_on(builder.start())?;
+        let mut transaction = Transaction::new(self.connection.as_ref(), transaction);
+
+        let value = f(&mut transaction)?;
+        transaction.commit()?;
+        Ok(value)
+    }
+}
+
+fn configure(
+    mut builder: tokio_postgres::TransactionBuilder<'_>,
+    isolation_level: Option<IsolationLevel>,
+    read_only: Option<bool>,
+    deferrable: Option<bool>,
+) -> tokio_postgres::TransactionBuilder<'_> {
+    if let Some(isolation_level) = isolation_level {
+        builder = builder.isolation_level(isolation_level);
+    }
+    if let Some(read_only) = read_only {
+        builder = builder.read_only(read_only);
+    }
+    if let Some(deferrable) = deferrable {
+        builder = builder.deferrable(deferrable);
     }
+    builder
 }
@@ -0,0 +1,56 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::time::Instant;
+use tokio_postgres::uring::UringStream;
+use tokio_postgres::{Client, Config, NoTls};
+
+async fn connect_epoll() -> Client {
+    let (client, conn) = tokio_postgres::connect("host=localhost port=5433 user=postgres", NoTls)
+        .await
+        .unwrap();
+    tokio_uring_04::spawn(async { conn.await.unwrap() });
+    client
+}
+
+async fn connect_uring() -> Client {
+    let config = "user=postgres".parse::<Config>().unwrap();
+    let stream = UringStream::connect("127.0.0.1:5433".parse().unwrap())
+        .await
+        .unwrap();
+    let (client, conn) = config.connect_raw(stream, NoTls).await.unwrap();
+    tokio_uring_04::spawn(async { conn.await.unwrap() });
+    client
+}
+
+// tokio-uring runs a Tokio runtime internally, so both transports are measured on the same executor
+fn query_prepared(c: &mut Criterion) {
+    c.bench_function("epoll", |b| {
+        b.iter_custom(|iters| {
+            tokio_uring_04::start(async {
+                let client = connect_epoll().await;
+                let statement = client.prepare("SELECT $1::INT8").await.unwrap();
+                let start = Instant::now();
+                for _ in 0..iters {
+                    client.query(&statement, &[&1i64]).await.unwrap();
+                }
+                start.elapsed()
+            })
+        })
+    });
+
+    c.bench_function("io_uring", |b| {
+        b.iter_custom(|iters| {
+            tokio_uring_04::start(async {
+                let client = connect_uring().await;
+                let statement = client.prepare("SELECT $1::INT8").await.unwrap();
+                let start = Instant::now();
+                for _ in 0..iters {
+                    client.query(&statement, &[&1i64]).await.unwrap();
+                }
+                start.elapsed()
+            })
+        })
+    });
+}
+
+criterion_group!(benches, query_prepared);
+criterion_main!(benches);
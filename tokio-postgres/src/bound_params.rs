@@ -0,0 +1,206 @@
+//! Parameters bound to a statement, recorded in errors.
+
+use crate::config::ParamRedactor;
+use crate::debug_sql;
+use crate::types::{Format, IsNull, ToSql, Type, to_sql_checked};
+use bytes::{BufMut, Bytes, BytesMut};
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+
+/// The parameters bound to a statement, kept so that they can be rendered if it fails.
+///
+/// Only a reference to the encoded `Bind` message is held. The parameters are decoded from it and rendered when an
+/// error is built.
+pub(crate) struct BoundParams {
+    message: Bytes,
+    types: Vec<Type>,
+    redactor: Option<Arc<ParamRedactor>>,
+}
+
+impl BoundParams {
+    pub(crate) fn new(
+        message: Bytes,
+        types: Vec<Type>,
+        redactor: Option<Arc<ParamRedactor>>,
+    ) -> BoundParams {
+        BoundParams {
+            message,
+            types,
+            redactor,
+        }
+    }
+
+    /// Renders the parameters, or returns `None` if the message doesn't contain them.
+    pub(crate) fn render(&self) -> Option<Vec<String>> {
+        let params = bind_values(&self.message, &self.types)?
+            .into_iter()
+            .enumerate()
+            .map(|(idx, param)| {
+                self.redactor
+                    .as_ref()
+                    .and_then(|redactor| redactor(idx, param.type_, &param))
+                    .unwrap_or_else(|| format!("{param:?}"))
+            })
+            .collect();
+        Some(params)
+    }
+}
+
+/// Finds the values in the first `Bind` message of a buffer of frontend messages.
+fn bind_values<'a>(mut buf: &'a [u8], types: &'a [Type]) -> Option<Vec<Encoded<'a>>> {
+    let mut body = loop {
+        let (&tag, rest) = buf.split_first()?;
+        let len = usize::try_from(i32::from_be_bytes(rest.get(..4)?.try_into().ok()?)).ok()?;
+        let (body, rest) = rest.split_at_checked(len)?;
+        if tag == b'B' {
+            break body.get(4..)?;
+        }
+        buf = rest;
+    };
+
+    cstr(&mut body)?;
+    cstr(&mut body)?;
+    let formats = (0..u16(&mut body)?)
+        .map(|_| Some(i16::from_be_bytes(take(&mut body, 2)?.try_into().ok()?)))
+        .collect::<Option<Vec<_>>>()?;
+    if usize::from(u16(&mut body)?) != types.len() {
+        return None;
+    }
+
+    let mut values = Vec::with_capacity(types.len());
+    for (i, type_) in types.iter().enumerate() {
+        let format = match &*formats {
+            [] => 0,
+            [format] => *format,
+            formats => *formats.get(i)?,
+        };
+        let len = i32::from_be_bytes(take(&mut body, 4)?.try_into().ok()?);
+        let raw = match usize::try_from(len) {
+            Ok(len) => Some(take(&mut body, len)?),
+            Err(_) => None,
+        };
+        values.push(Encoded { type_, format, raw });
+    }
+    Some(values)
+}
+
+fn take<'a>(buf: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+    let (head, rest) = buf.split_at_checked(len)?;
+    *buf = rest;
+    Some(head)
+}
+
+fn u16(buf: &mut &[u8]) -> Option<u16> {
+    Some(u16::from_be_bytes(take(buf, 2)?.try_into().ok()?))
+}
+
+fn cstr<'a>(buf: &mut &'a [u8]) -> Option<&'a [u8]> {
+    let end = buf.iter().position(|&b| b == 0)?;
+    let s = take(buf, end)?;
+    take(buf, 1)?;
+    Some(s)
+}
+
+/// A parameter value as it was sent to the server.
+///
+/// Its `Debug` implementation renders it as a SQL literal where the type is supported.
+struct Encoded<'a> {
+    type_: &'a Type,
+    format: i16,
+    raw: Option<&'a [u8]>,
+}
+
+impl fmt::Debug for Encoded<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(raw) = self.raw else {
+            return f.write_str("NULL");
+        };
+
+        let literal = if self.format == 0 {
+            Some(debug_sql::quote(&String::from_utf8_lossy(raw)))
+        } else {
+            debug_sql::literal(self.type_, raw)
+        };
+        match literal {
+            Some(literal) => f.write_str(&literal),
+            None => write!(f, "<{} bytes>", raw.len()),
+        }
+    }
+}
+
+impl ToSql for Encoded<'_> {
+    fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        match self.raw {
+            Some(raw) => {
+                out.put_slice(raw);
+                Ok(IsNull::No)
+            }
+            None => Ok(IsNull::Yes),
+        }
+    }
+
+    fn accepts(_: &Type) -> bool {
+        true
+    }
+
+    fn encode_format(&self, _: &Type) -> Format {
+        if self.format == 0 {
+            Format::Text
+        } else {
+            Format::Binary
+        }
+    }
+
+    to_sql_checked!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BoundParams;
+    use crate::types::{IsNull, Kind, ToSql, Type};
+    use bytes::BytesMut;
+    use postgres_protocol::message::frontend;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_render() {
+        let hstore = Type::new("hstore".to_string(), 0, Kind::Simple, "public".to_string());
+        let types = [Type::TEXT, Type::INT4, Type::INT8, hstore, Type::TEXT];
+        let map = HashMap::from([("a".to_string(), Some("b".to_string()))]);
+        let params: [&(dyn ToSql + Sync); 5] = [&"it's", &-1i32, &None::<i64>, &map, &"secret"];
+
+        let mut buf = BytesMut::new();
+        frontend::parse("", "SELECT 1", [], &mut buf).unwrap();
+        frontend::bind(
+            "",
+            "",
+            params
+                .iter()
+                .zip(&types)
+                .map(|(p, t)| p.encode_format(t) as i16),
+            params.iter().zip(&types),
+            |(p, t), buf| match p.to_sql_checked(t, buf).unwrap() {
+                IsNull::No => Ok(postgres_protocol::IsNull::No),
+                IsNull::Yes => Ok(postgres_protocol::IsNull::Yes),
+            },
+            Some(1),
+            &mut buf,
+        )
+        .unwrap_or_else(|_| panic!("bind failed"));
+        frontend::sync(&mut buf);
+
+        let params = BoundParams::new(
+            buf.freeze(),
+            types.to_vec(),
+            Some(Arc::new(|idx, _, _| {
+                (idx == 4).then(|| "<redacted>".to_string())
+            })),
+        );
+        assert_eq!(
+            params.render().unwrap(),
+            ["'it''s'", "-1", "NULL", "<14 bytes>", "<redacted>"]
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use crate::types::BorrowToSql;
+use crate::{Client, Error, Statement};
+use std::cmp;
+use std::fmt::Write;
+
+/// The maximum number of parameters a statement can have.
+const MAX_PARAMS: usize = u16::MAX as usize;
+
+/// A builder for inserting many rows with multi-row `INSERT` statements.
+///
+/// Rows are inserted with statements of the form `INSERT INTO table (columns) VALUES ($1, $2), ($3, $4), ...`, each
+/// inserting as many rows as fit under the limit of 65535 parameters per statement. This is slower than `COPY`, but
+/// works everywhere `INSERT` does and supports `ON CONFLICT` clauses.
+///
+/// Each statement is executed separately, so if one fails, the rows inserted by the previous ones remain unless the
+/// insert is performed in a transaction.
+pub struct BulkInsert<'a> {
+    client: &'a Client,
+    table: String,
+    columns: Vec<String>,
+    on_conflict: Option<String>,
+    max_rows: usize,
+}
+
+impl<'a> BulkInsert<'a> {
+    pub(crate) fn new(client: &'a Client, table: &str, columns: &[&str]) -> BulkInsert<'a> {
+        BulkInsert {
+            client,
+            table: table.to_string(),
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            on_conflict: None,
+            max_rows: usize::MAX,
+        }
+    }
+
+    /// Sets an `ON CONFLICT` clause for the statements, such as `(id) DO NOTHING`.
+    ///
+    /// The clause is inserted verbatim after `ON CONFLICT`.
+    pub fn on_conflict(mut self, clause: &str) -> Self {
+        self.on_conflict = Some(clause.to_string());
+        self
+    }
+
+    /// Sets the maximum number of rows inserted by each statement.
+    ///
+    /// Statements are also always limited to 65535 parameters. Defaults to no limit beyond that.
+    pub fn max_rows_per_statement(mut self, max_rows: usize) -> Self {
+        self.max_rows = max_rows;
+        self
+    }
+
+    /// Inserts the rows, returning the number of rows inserted.
+    ///
+    /// Each row must contain one value for each column. Rows skipped by an `ON CONFLICT DO NOTHING` clause aren't
+    /// counted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no columns were specified.
+    pub async fn execute<I, R, P>(self, rows: I) -> Result<u64, Error>
+    where
+        I: IntoIterator<Item = R>,
+        R: IntoIterator<Item = P>,
+        P: BorrowToSql,
+    {
+        let columns = self.columns.len();
+        assert!(columns > 0, "a bulk insert requires at least one column");
+        let max_rows = self.max_rows.clamp(1, cmp::max(MAX_PARAMS / columns, 1));
+
+        let mut rows = rows.into_iter();
+        let mut full_statement: Option<Statement> = None;
+        let mut inserted = 0;
+        loop {
+            let mut params = Vec::with_capacity(cmp::min(max_rows, 1024) * columns);
+            let mut count = 0;
+            for row in rows.by_ref() {
+                let len = params.len();
+                params.extend(row);
+                if params.len() - len != columns {
+                    return Err(Error::parameters(params.len() - len, columns));
+                }
+
+                count += 1;
+                if count == max_rows {
+                    break;
+                }
+            }
+            if count == 0 {
+                break;
+            }
+
+            let statement = match &full_statement {
+                Some(statement) if count == max_rows => statement.clone(),
+                _ => {
+                    let statement = self.client.prepare(&self.query(count)).await?;
+                    if count == max_rows {
+                        full_statement = Some(statement.clone());
+                    }
+                    statement
+                }
+            };
+            inserted += self.client.execute_raw(&statement, params).await?;
+
+            if count < max_rows {
+                break;
+            }
+        }
+
+        Ok(inserted)
+    }
+
+    fn query(&self, rows: usize) -> String {
+        let mut query = format!(
+            "INSERT INTO {} ({}) VALUES ",
+            self.table,
+            self.columns.join(", ")
+        );
+        let columns = self.columns.len();
+        for row in 0..rows {
+            if row > 0 {
+                query.push_str(", ");
+            }
+            query.push('(');
+            for column in 0..columns {
+                if column > 0 {
+                    query.push_str(", ");
+                }
+                let _ = write!(query, "${}", row * columns + column + 1);
+            }
+            query.push(')');
+        }
+        if let Some(clause) = &self.on_conflict {
+            let _ = write!(query, " ON CONFLICT {clause}");
+        }
+        query
+    }
+}
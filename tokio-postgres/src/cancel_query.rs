@@ -1,21 +1,12 @@
-use crate::client::SocketConfig;
-use crate::config::{SslMode, SslNegotiation};
 use crate::tls::MakeTlsConnect;
-use crate::{Error, Socket, cancel_query_raw, connect_socket};
+use crate::{CancelToken, Error, Socket, cancel_query_raw, connect_socket};
 use std::io;
 
-pub(crate) async fn cancel_query<T>(
-    config: Option<SocketConfig>,
-    ssl_mode: SslMode,
-    ssl_negotiation: SslNegotiation,
-    mut tls: T,
-    process_id: i32,
-    secret_key: i32,
-) -> Result<(), Error>
+pub(crate) async fn cancel_query<T>(token: &CancelToken, mut tls: T) -> Result<(), Error>
 where
     T: MakeTlsConnect<Socket>,
 {
-    let config = match config {
+    let config = match &token.socket_config {
         Some(config) => config,
         None => {
             return Err(Error::connect(io::Error::new(
@@ -36,17 +27,10 @@ where
         config.connect_timeout,
         config.tcp_user_timeout,
         config.keepalive.as_ref(),
+        config.socket_callback.as_ref(),
+        config.runtime.as_ref().map(|r| &*r.0),
     )
     .await?;
 
-    cancel_query_raw::cancel_query_raw(
-        socket,
-        ssl_mode,
-        ssl_negotiation,
-        tls,
-        has_hostname,
-        process_id,
-        secret_key,
-    )
-    .await
+    cancel_query_raw::cancel_query_raw(socket, token, tls, has_hostname).await
 }
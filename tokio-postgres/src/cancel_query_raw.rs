@@ -1,27 +1,23 @@
-use crate::config::{SslMode, SslNegotiation};
 use crate::tls::TlsConnect;
-use crate::{Error, connect_tls};
+use crate::{CancelToken, Error, connect_tls};
 use bytes::BytesMut;
 use postgres_protocol::message::frontend;
 use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
 
 pub async fn cancel_query_raw<S, T>(
     stream: S,
-    mode: SslMode,
-    negotiation: SslNegotiation,
+    token: &CancelToken,
     tls: T,
     has_hostname: bool,
-    process_id: i32,
-    secret_key: i32,
 ) -> Result<(), Error>
 where
     S: AsyncRead + AsyncWrite + Unpin,
     T: TlsConnect<S>,
 {
-    let mut stream = connect_tls::connect_tls(stream, mode, negotiation, tls, has_hostname).await?;
+    let mut stream = connect_tls::connect_tls(stream, &token.tls_config, tls, has_hostname).await?;
 
     let mut buf = BytesMut::new();
-    frontend::cancel_request(process_id, secret_key, &mut buf);
+    frontend::cancel_request(token.process_id, token.secret_key, &mut buf);
 
     stream.write_all(&buf).await.map_err(Error::io)?;
     stream.flush().await.map_err(Error::io)?;
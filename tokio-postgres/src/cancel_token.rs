@@ -1,4 +1,4 @@
-use crate::config::{SslMode, SslNegotiation};
+use crate::connect_tls::TlsConfig;
 use crate::tls::TlsConnect;
 use crate::{Error, cancel_query_raw};
 #[cfg(feature = "runtime")]
@@ -11,8 +11,7 @@ use tokio::io::{AsyncRead, AsyncWrite};
 pub struct CancelToken {
     #[cfg(feature = "runtime")]
     pub(crate) socket_config: Option<SocketConfig>,
-    pub(crate) ssl_mode: SslMode,
-    pub(crate) ssl_negotiation: SslNegotiation,
+    pub(crate) tls_config: TlsConfig,
     pub(crate) process_id: i32,
     pub(crate) secret_key: i32,
 }
@@ -35,15 +34,7 @@ impl CancelToken {
     where
         T: MakeTlsConnect<Socket>,
     {
-        cancel_query::cancel_query(
-            self.socket_config.clone(),
-            self.ssl_mode,
-            self.ssl_negotiation,
-            tls,
-            self.process_id,
-            self.secret_key,
-        )
-        .await
+        cancel_query::cancel_query(self, tls).await
     }
 
     /// Like `cancel_query`, but uses a stream which is already connected to the server rather than opening a new
@@ -53,15 +44,6 @@ impl CancelToken {
         S: AsyncRead + AsyncWrite + Unpin,
         T: TlsConnect<S>,
     {
-        cancel_query_raw::cancel_query_raw(
-            stream,
-            self.ssl_mode,
-            self.ssl_negotiation,
-            tls,
-            true,
-            self.process_id,
-            self.secret_key,
-        )
-        .await
+        cancel_query_raw::cancel_query_raw(stream, self, tls, true).await
     }
 }
@@ -1,43 +1,66 @@
 #[cfg(feature = "runtime")]
 use crate::Socket;
+use crate::bound_params::BoundParams;
+use crate::bulk_insert::BulkInsert;
 use crate::codec::{BackendMessages, FrontendMessage};
-use crate::config::{SslMode, SslNegotiation};
+use crate::config::{Config, ParamRedactor, SlowQueryHandler, SqlCommenter};
+#[cfg(feature = "runtime")]
+use crate::config::{Proxy, Shared, SocketCallback};
+use crate::connect_tls::TlsConfig;
 use crate::connection::{Request, RequestMessages};
 use crate::copy_out::CopyOutStream;
+#[cfg(feature = "explain")]
+use crate::explain::{Explain, ExplainOptions};
+use crate::instrument::ClientAttributes;
 #[cfg(feature = "runtime")]
 use crate::keepalive::KeepaliveConfig;
 use crate::query::RowStream;
+#[cfg(feature = "runtime")]
+use crate::rt::Runtime;
 use crate::simple_query::SimpleQueryStream;
+use crate::sqlcommenter;
 #[cfg(feature = "runtime")]
 use crate::tls::MakeTlsConnect;
-use crate::tls::TlsConnect;
+use crate::tls::{TlsConnect, TlsInfo};
+use crate::type_cache::TypeCache;
+use crate::type_registry::{CustomParam, TypeHandler, TypeKey, TypeRegistry};
+#[cfg(feature = "explain")]
+use crate::types::Json;
 use crate::types::{Oid, ToSql, Type};
 use crate::{
-    CancelToken, CopyInSink, Error, Row, SimpleQueryMessage, Statement, ToStatement, Transaction,
-    TransactionBuilder, copy_in, copy_out, prepare, query, simple_query, slice_iter,
+    CancelToken, ClientMetrics, CopyInSink, Error, Row, SimpleQueryMessage, Statement, ToStatement,
+    Transaction, TransactionBuilder, copy_in, copy_out, prepare, query, simple_query, slice_iter,
 };
-use bytes::{Buf, BytesMut};
+use bytes::{Buf, Bytes, BytesMut};
 use fallible_iterator::FallibleIterator;
-use futures_channel::mpsc;
+use futures_channel::{mpsc, oneshot};
 use futures_util::{StreamExt, TryStreamExt};
 use parking_lot::Mutex;
 use postgres_protocol::message::backend::Message;
 use postgres_protocol::message::frontend;
 use postgres_types::{BorrowToSql, FromSqlOwned};
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fmt;
 use std::future;
+use std::mem;
 #[cfg(feature = "runtime")]
 use std::net::IpAddr;
 #[cfg(feature = "runtime")]
 use std::path::PathBuf;
 use std::pin::pin;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU8, AtomicU64, Ordering};
 use std::task::{Context, Poll, ready};
-#[cfg(feature = "runtime")]
 use std::time::Duration;
 use tokio::io::{AsyncRead, AsyncWrite};
 
+/// The maximum length of an identifier, plus one for its null terminator.
+const NAMEDATALEN: usize = 64;
+
+/// The exclusive upper bound on the length of a notification payload with the default block size.
+const MAX_NOTIFY_PAYLOAD_LEN: usize = 8000;
+
 pub struct Responses {
     receiver: mpsc::Receiver<BackendMessages>,
     cur: BackendMessages,
@@ -79,14 +102,70 @@ struct CachedTypeInfo {
     /// Corresponds to [TYPEINFO_QUERY](prepare::TYPEINFO_COMPOSITE_QUERY) (or
     /// its fallback).
     typeinfo_enum: Option<Statement>,
+}
 
-    /// Cache of types already looked up.
-    types: HashMap<Oid, Type>,
+/// Session state reported by the server, shared between a `Client` and its `Connection`.
+pub struct SessionState {
+    pub parameters: Mutex<HashMap<String, String>>,
+    transaction_status: AtomicU8,
+    resets: AtomicU64,
+}
+
+impl SessionState {
+    pub fn new(parameters: HashMap<String, String>) -> SessionState {
+        SessionState {
+            parameters: Mutex::new(parameters),
+            transaction_status: AtomicU8::new(b'I'),
+            resets: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the number of times the session has been reset with `Client::reset`.
+    pub fn resets(&self) -> u64 {
+        self.resets.load(Ordering::Acquire)
+    }
+
+    pub fn set_transaction_status(&self, status: u8) {
+        self.transaction_status.store(status, Ordering::Relaxed);
+    }
+
+    fn transaction_status(&self) -> TransactionStatus {
+        match self.transaction_status.load(Ordering::Relaxed) {
+            b'T' => TransactionStatus::InTransaction,
+            b'E' => TransactionStatus::Failed,
+            _ => TransactionStatus::Idle,
+        }
+    }
+}
+
+/// The transaction status of a session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TransactionStatus {
+    /// The session is not in a transaction block.
+    Idle,
+    /// The session is in a transaction block.
+    InTransaction,
+    /// The session is in a failed transaction block, and will reject queries until the transaction is ended.
+    Failed,
 }
 
 pub struct InnerClient {
     sender: mpsc::UnboundedSender<Request>,
     cached_typeinfo: Mutex<CachedTypeInfo>,
+    /// Cache of types already looked up, possibly shared with other clients.
+    type_cache: Mutex<TypeCache>,
+    session: Arc<SessionState>,
+    type_registry: Arc<TypeRegistry>,
+    attributes: ClientAttributes,
+    query_in_errors: bool,
+    params_in_errors: bool,
+    #[cfg(feature = "tracing")]
+    trace_query_text: bool,
+    param_redactor: Option<Arc<ParamRedactor>>,
+    metrics: Option<Arc<dyn ClientMetrics>>,
+    slow_query: Option<(Duration, Arc<SlowQueryHandler>)>,
+    sql_commenter: Option<Arc<SqlCommenter>>,
 
     /// A buffer to use when writing out postgres commands.
     buffer: Mutex<BytesMut>,
@@ -131,15 +210,106 @@ impl InnerClient {
     }
 
     pub fn type_(&self, oid: Oid) -> Option<Type> {
-        self.cached_typeinfo.lock().types.get(&oid).cloned()
+        self.type_cache.lock().get(oid)
     }
 
-    pub fn set_type(&self, oid: Oid, type_: &Type) {
-        self.cached_typeinfo.lock().types.insert(oid, type_.clone());
+    pub fn set_type(&self, type_: &Type) {
+        self.type_cache.lock().insert(type_.clone());
     }
 
     pub fn clear_type_cache(&self) {
-        self.cached_typeinfo.lock().types.clear();
+        self.type_cache.lock().clear();
+    }
+
+    pub fn type_cache(&self) -> TypeCache {
+        self.type_cache.lock().clone()
+    }
+
+    pub fn set_type_cache(&self, cache: TypeCache) {
+        *self.type_cache.lock() = cache;
+    }
+
+    pub(crate) fn type_registry(&self) -> &Arc<TypeRegistry> {
+        &self.type_registry
+    }
+
+    #[cfg_attr(not(any(feature = "runtime", feature = "tracing")), allow(dead_code))]
+    pub(crate) fn attributes(&self) -> &ClientAttributes {
+        &self.attributes
+    }
+
+    pub(crate) fn metrics(&self) -> Option<&Arc<dyn ClientMetrics>> {
+        self.metrics.as_ref()
+    }
+
+    pub(crate) fn slow_query(&self) -> Option<&(Duration, Arc<SlowQueryHandler>)> {
+        self.slow_query.as_ref()
+    }
+
+    /// Returns the text of a statement with the configured sqlcommenter tags appended, if any.
+    pub(crate) fn comment_query<'a>(&self, query: &'a str) -> Cow<'a, str> {
+        match self
+            .sql_commenter
+            .as_ref()
+            .and_then(|f| sqlcommenter::append(query, f()))
+        {
+            Some(query) => Cow::Owned(query),
+            None => Cow::Borrowed(query),
+        }
+    }
+
+    pub(crate) fn query_in_errors(&self) -> bool {
+        self.query_in_errors
+    }
+
+    /// Records the text of the statement which caused an error if the connection is configured to do so.
+    pub(crate) fn query_error(&self, error: Error, query: &str) -> Error {
+        if self.query_in_errors {
+            error.with_query(query)
+        } else {
+            error
+        }
+    }
+
+    pub(crate) fn params_in_errors(&self) -> bool {
+        self.params_in_errors
+    }
+
+    #[cfg(feature = "tracing")]
+    pub(crate) fn trace_query_text(&self) -> bool {
+        self.trace_query_text
+    }
+
+    /// Captures the parameters encoded in a message for inclusion in errors if the connection is configured to do so.
+    pub(crate) fn bound_params(&self, message: &Bytes, types: &[Type]) -> Option<BoundParams> {
+        if !self.params_in_errors {
+            return None;
+        }
+
+        Some(BoundParams::new(
+            message.clone(),
+            types.to_vec(),
+            self.param_redactor.clone(),
+        ))
+    }
+
+    /// Records the text and rendered parameters of the statement which caused an error.
+    pub(crate) fn annotate_error(
+        &self,
+        error: Error,
+        query: &str,
+        params: Option<&BoundParams>,
+    ) -> Error {
+        let error = self.query_error(error, query);
+        match params.and_then(BoundParams::render) {
+            Some(params) => error.with_params(params),
+            None => error,
+        }
+    }
+
+    pub fn clear_typeinfo_statements(&self) {
+        // drop the cached statements outside of the lock
+        let _cached = mem::take(&mut *self.cached_typeinfo.lock());
     }
 
     /// Call the given function with a buffer to be used when writing out
@@ -164,12 +334,15 @@ pub(crate) struct SocketConfig {
     pub connect_timeout: Option<Duration>,
     pub tcp_user_timeout: Option<Duration>,
     pub keepalive: Option<KeepaliveConfig>,
+    pub socket_callback: Option<Shared<SocketCallback>>,
+    pub runtime: Option<Shared<dyn Runtime>>,
 }
 
 #[cfg(feature = "runtime")]
 #[derive(Clone)]
 pub(crate) enum Addr {
     Tcp(IpAddr),
+    Proxy(Proxy, String),
     #[cfg(unix)]
     Unix(PathBuf),
 }
@@ -182,32 +355,51 @@ pub struct Client {
     inner: Arc<InnerClient>,
     #[cfg(feature = "runtime")]
     socket_config: Option<SocketConfig>,
-    ssl_mode: SslMode,
-    ssl_negotiation: SslNegotiation,
+    tls_config: TlsConfig,
     process_id: i32,
     secret_key: i32,
+    server_version: Option<(u32, u32)>,
+    tls_info: Option<TlsInfo>,
 }
 
 impl Client {
     pub(crate) fn new(
         sender: mpsc::UnboundedSender<Request>,
-        ssl_mode: SslMode,
-        ssl_negotiation: SslNegotiation,
+        tls_config: TlsConfig,
         process_id: i32,
         secret_key: i32,
+        session: Arc<SessionState>,
+        config: &Config,
+        attributes: ClientAttributes,
     ) -> Client {
         Client {
             inner: Arc::new(InnerClient {
                 sender,
                 cached_typeinfo: Default::default(),
+                type_cache: Default::default(),
+                session,
+                type_registry: Default::default(),
+                attributes,
+                query_in_errors: config.query_in_errors,
+                params_in_errors: config.params_in_errors,
+                #[cfg(feature = "tracing")]
+                trace_query_text: config.trace_query_text,
+                param_redactor: config.param_redactor.as_ref().map(|r| r.0.clone()),
+                metrics: config.metrics.as_ref().map(|m| m.0.clone()),
+                slow_query: config
+                    .slow_query
+                    .as_ref()
+                    .map(|(threshold, handler)| (*threshold, handler.0.clone())),
+                sql_commenter: config.sql_commenter.as_ref().map(|f| f.0.clone()),
                 buffer: Default::default(),
             }),
             #[cfg(feature = "runtime")]
             socket_config: None,
-            ssl_mode,
-            ssl_negotiation,
+            tls_config,
             process_id,
             secret_key,
+            server_version: None,
+            tls_info: None,
         }
     }
 
@@ -220,6 +412,63 @@ impl Client {
         self.socket_config = Some(socket_config);
     }
 
+    pub(crate) fn set_server_version(&mut self, version: &str) {
+        self.server_version = parse_server_version(version);
+    }
+
+    pub(crate) fn set_tls_info(&mut self, tls_info: Option<TlsInfo>) {
+        self.tls_info = tls_info;
+    }
+
+    /// Returns the version of the server as a `(major, minor)` pair.
+    ///
+    /// This is parsed from the `server_version` parameter reported by the server at startup, so no extra round trip
+    /// is required. For servers older than PostgreSQL 10, this is the first two components of the version (e.g.
+    /// `(9, 6)` for 9.6.24). Pre-release versions like `16beta1` are reported with a minor version of 0.
+    ///
+    /// Returns `None` if the server did not report its version, or reported one in an unrecognized format.
+    pub fn server_version(&self) -> Option<(u32, u32)> {
+        self.server_version
+    }
+
+    /// Returns the current value of a runtime parameter reported by the server.
+    ///
+    /// The server reports the values of certain parameters (such as `TimeZone`, `application_name`, or
+    /// `standard_conforming_strings`) at startup and whenever they change. Changes are also surfaced as
+    /// [`AsyncMessage::ParameterStatus`](crate::AsyncMessage::ParameterStatus) messages by the `Connection`, which
+    /// must be polled for this value to stay up to date.
+    pub fn parameter(&self, name: &str) -> Option<String> {
+        self.inner.session.parameters.lock().get(name).cloned()
+    }
+
+    /// Returns the transaction status of the session.
+    ///
+    /// This reflects the status reported by the server at the end of the most recently completed request, so it is
+    /// only meaningful when no other requests are in flight. Note that a request which fails resolves as soon as the
+    /// error is received, possibly before the server reports its final status; [`check_connection`] can be used to
+    /// wait for it.
+    ///
+    /// [`check_connection`]: Client::check_connection
+    pub fn transaction_status(&self) -> TransactionStatus {
+        self.inner.session.transaction_status()
+    }
+
+    /// Returns the process ID of the backend server process handling this connection.
+    ///
+    /// This is the value reported by the server in its `BackendKeyData` message at startup, and matches the value of
+    /// `pg_backend_pid()` and the `pid` column of `pg_stat_activity`.
+    pub fn backend_pid(&self) -> i32 {
+        self.process_id
+    }
+
+    /// Returns details of the TLS session negotiated with the server, such as its protocol version, cipher suite, and
+    /// certificate.
+    ///
+    /// Returns `None` if the connection is not encrypted.
+    pub fn tls_info(&self) -> Option<&TlsInfo> {
+        self.tls_info.as_ref()
+    }
+
     /// Creates a new prepared statement.
     ///
     /// Prepared statements can be executed repeatedly, and may contain query parameters (indicated by `$1`, `$2`, etc),
@@ -237,7 +486,7 @@ impl Client {
         query: &str,
         parameter_types: &[Type],
     ) -> Result<Statement, Error> {
-        prepare::prepare(&self.inner, query, parameter_types).await
+        prepare::prepare(&self.inner, query, parameter_types, false).await
     }
 
     /// Executes a statement, returning a vector of the resulting rows.
@@ -623,6 +872,94 @@ impl Client {
         query::execute(self.inner(), statement, params).await
     }
 
+    /// Executes a statement once for each of several sets of parameters, returning the number of rows modified by each
+    /// execution.
+    ///
+    /// All of the executions are sent to the server together and complete in a single round trip, which is much faster
+    /// than calling [`execute`] repeatedly. They run in a single implicit transaction unless they are already inside an
+    /// explicit one, so if any of them fails, none of their changes are applied and the error is returned.
+    ///
+    /// The `statement` argument can either be a `Statement`, or a raw query string. A raw query string is only
+    /// prepared once. All of the parameter sets are encoded up front, so very large batches should be split up.
+    ///
+    /// [`execute`]: #method.execute
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn f(client: &tokio_postgres::Client) -> Result<(), tokio_postgres::Error> {
+    /// use tokio_postgres::types::ToSql;
+    ///
+    /// let statement = client.prepare("UPDATE people SET name = $2 WHERE id = $1").await?;
+    /// let rows = client
+    ///     .execute_many(
+    ///         &statement,
+    ///         [
+    ///             [&1i32 as &(dyn ToSql + Sync), &"john"],
+    ///             [&2i32, &"jane"],
+    ///         ],
+    ///     )
+    ///     .await?;
+    /// assert_eq!(rows.len(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn execute_many<T, I, J, P>(
+        &self,
+        statement: &T,
+        param_sets: I,
+    ) -> Result<Vec<u64>, Error>
+    where
+        T: ?Sized + ToStatement,
+        I: IntoIterator<Item = J>,
+        J: IntoIterator<Item = P>,
+        J::IntoIter: ExactSizeIterator,
+        P: BorrowToSql,
+    {
+        let statement = statement.__convert().into_statement(&self.inner).await?;
+        query::execute_many(self.inner(), statement, param_sets).await
+    }
+
+    /// Runs `EXPLAIN` on a statement, returning its query plan.
+    ///
+    /// The statement is explained in the JSON format with the provided options, and its plan is parsed into an
+    /// [`Explain`]. A statement may contain parameters, specified by `$n` as with [`query`](Client::query), which are
+    /// used for planning and, when analyzing, for executing it.
+    ///
+    /// **If [`analyze`](ExplainOptions::analyze) is enabled, the statement is actually executed**, so statements which
+    /// modify data should be analyzed in a transaction which is rolled back.
+    ///
+    /// Requires the `explain` Cargo feature.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tokio_postgres::explain::ExplainOptions;
+    ///
+    /// # async fn f(client: &tokio_postgres::Client) -> Result<(), tokio_postgres::Error> {
+    /// let options = ExplainOptions::new().analyze(true);
+    /// let explain = client
+    ///     .explain("SELECT * FROM people WHERE id = $1", &[&1i32], options)
+    ///     .await?;
+    /// println!("{} took {:?}ms", explain.plan().node_type(), explain.execution_time());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "explain")]
+    pub async fn explain(
+        &self,
+        query: &str,
+        params: &[&(dyn ToSql + Sync)],
+        options: ExplainOptions,
+    ) -> Result<Explain, Error> {
+        let row = self.query_one(&options.statement(query), params).await?;
+        let Json(explains) = row.try_get::<_, Json<Vec<Explain>>>(0)?;
+        explains
+            .into_iter()
+            .next()
+            .ok_or_else(Error::unexpected_message)
+    }
+
     /// Executes a `COPY FROM STDIN` statement, returning a sink used to write the copy data.
     ///
     /// PostgreSQL does not support parameters in `COPY` statements, so this method does not take any. The copy *must*
@@ -636,6 +973,35 @@ impl Client {
         copy_in::copy_in(self.inner(), statement).await
     }
 
+    /// Executes a `COPY FROM STDIN` statement, copying all of the data from a reader and returning the number of rows
+    /// created.
+    ///
+    /// The data is read in chunks and sent to the server as it is read, waiting for the connection to accept each chunk
+    /// before reading the next one. It is passed along verbatim, so it must be in the format the statement expects. If
+    /// reading fails, the copy is aborted and the error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn f(client: &tokio_postgres::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// // Any reader works here, such as a `tokio::fs::File`.
+    /// let data: &[u8] = b"1,john\n2,jane\n";
+    /// let rows = client
+    ///     .copy_in_from("COPY people FROM STDIN (FORMAT csv)", data)
+    ///     .await?;
+    /// assert_eq!(rows, 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn copy_in_from<T, R>(&self, statement: &T, reader: R) -> Result<u64, Error>
+    where
+        T: ?Sized + ToStatement,
+        R: AsyncRead,
+    {
+        let statement = statement.__convert().into_statement(&self.inner).await?;
+        copy_in::copy_in_from(self.inner(), statement, reader).await
+    }
+
     /// Executes a `COPY TO STDOUT` statement, returning a stream of the resulting data.
     ///
     /// PostgreSQL does not support parameters in `COPY` statements, so this method does not take any.
@@ -647,6 +1013,65 @@ impl Client {
         copy_out::copy_out(self.inner(), statement).await
     }
 
+    /// Executes a `COPY TO STDOUT` statement, writing all of the resulting data to a writer and returning the number of
+    /// rows copied.
+    ///
+    /// The data is written as it is received, waiting for the writer to accept each chunk before receiving the next
+    /// one, and the writer is flushed once the copy completes. If writing fails, the error is returned and the rest of
+    /// the data is discarded.
+    ///
+    /// PostgreSQL does not support parameters in `COPY` statements, so this method does not take any.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn f(client: &tokio_postgres::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// // Any writer works here, such as a `tokio::fs::File`.
+    /// let mut data = vec![];
+    /// let rows = client
+    ///     .copy_out_to("COPY people TO STDOUT (FORMAT csv)", &mut data)
+    ///     .await?;
+    /// println!("copied {rows} rows in {} bytes", data.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn copy_out_to<T, W>(&self, statement: &T, writer: W) -> Result<u64, Error>
+    where
+        T: ?Sized + ToStatement,
+        W: AsyncWrite,
+    {
+        let statement = statement.__convert().into_statement(&self.inner).await?;
+        copy_out::copy_out_to(self.inner(), statement, writer).await
+    }
+
+    /// Returns a builder for inserting many rows into a table with multi-row `INSERT` statements.
+    ///
+    /// This is useful where `COPY` can't be used, such as for upserts. The table and column names are inserted into
+    /// the statements verbatim, so they must be quoted if necessary. See [`BulkInsert`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn f(client: &tokio_postgres::Client) -> Result<(), tokio_postgres::Error> {
+    /// use tokio_postgres::types::ToSql;
+    ///
+    /// let people = vec![(1i32, "john"), (2, "jane")];
+    /// let inserted = client
+    ///     .bulk_insert("people", &["id", "name"])
+    ///     .on_conflict("(id) DO UPDATE SET name = EXCLUDED.name")
+    ///     .execute(
+    ///         people
+    ///             .iter()
+    ///             .map(|(id, name)| [id as &(dyn ToSql + Sync), name]),
+    ///     )
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn bulk_insert(&self, table: &str, columns: &[&str]) -> BulkInsert<'_> {
+        BulkInsert::new(self, table, columns)
+    }
+
     /// Executes a sequence of SQL statements using the simple query protocol, returning the resulting rows.
     ///
     /// Statements should be separated by semicolons. If an error occurs, execution of the sequence will stop at that
@@ -678,7 +1103,7 @@ impl Client {
     /// functionality to safely embed that data in the request. Do not form statements via string concatenation and pass
     /// them to this method!
     pub async fn simple_query_raw(&self, query: &str) -> Result<SimpleQueryStream, Error> {
-        simple_query::simple_query(self.inner(), query).await
+        simple_query::simple_query(self.inner(), query, true).await
     }
 
     /// Executes a sequence of SQL statements using the simple query protocol.
@@ -692,7 +1117,12 @@ impl Client {
     /// functionality to safely embed that data in the request. Do not form statements via string concatenation and pass
     /// them to this method!
     pub async fn batch_execute(&self, query: &str) -> Result<(), Error> {
-        simple_query::batch_execute(self.inner(), query).await
+        simple_query::batch_execute(self.inner(), query, true).await
+    }
+
+    /// Like `batch_execute`, but for statements issued by the crate itself, which aren't tagged by the sqlcommenter.
+    pub(crate) async fn batch_execute_internal(&self, query: &str) -> Result<(), Error> {
+        simple_query::batch_execute(self.inner(), query, false).await
     }
 
     /// Check that the connection is alive and wait for the confirmation.
@@ -701,6 +1131,75 @@ impl Client {
         query::sync(self.inner()).await
     }
 
+    /// Sends a notification to all sessions listening on a channel.
+    ///
+    /// The channel and payload are passed to the server's `pg_notify` function as parameters, so no quoting or
+    /// escaping is required. Note that unlike the `LISTEN` and `NOTIFY` commands, the channel name is not folded to
+    /// lowercase.
+    ///
+    /// The channel name must be nonempty and shorter than 64 bytes, and the payload must be shorter than 8000 bytes.
+    /// An error is returned without contacting the server if either of those limits is exceeded.
+    pub async fn notify(&self, channel: &str, payload: &str) -> Result<(), Error> {
+        if channel.is_empty() || channel.len() >= NAMEDATALEN {
+            return Err(Error::notify(
+                format!(
+                    "channel name {:?} must be between 1 and {} bytes long",
+                    channel,
+                    NAMEDATALEN - 1
+                )
+                .into(),
+            ));
+        }
+        if payload.len() >= MAX_NOTIFY_PAYLOAD_LEN {
+            return Err(Error::notify(
+                format!(
+                    "payload of {} bytes for channel {:?} must be shorter than {} bytes",
+                    payload.len(),
+                    channel,
+                    MAX_NOTIFY_PAYLOAD_LEN
+                )
+                .into(),
+            ));
+        }
+
+        self.query_typed(
+            "SELECT pg_notify($1, $2)",
+            &[(&channel, Type::TEXT), (&payload, Type::TEXT)],
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Resets the session to a pristine state.
+    ///
+    /// This executes `DISCARD ALL`, which releases all session state held by the server (prepared statements,
+    /// temporary tables, session-level settings, `LISTEN` registrations, advisory locks, etc.), and then drops the
+    /// client's prepared statements used to look up type information. Connection pools can use this to safely hand a
+    /// connection to a new user.
+    ///
+    /// The type information cache is kept, since `DISCARD ALL` doesn't change type OIDs, so a cache shared with other
+    /// clients through [`set_type_cache`](Client::set_type_cache) is unaffected.
+    ///
+    /// Any `Statement`s prepared on this client before the reset are deallocated by the server and can no longer be
+    /// used.
+    ///
+    /// Notifications received before the reset which are still buffered in the connection's [`Notifications`]
+    /// stream are discarded rather than delivered to the connection's next user. Notifications which have already
+    /// been read from that stream, or returned by [`Connection::poll_message`], are unaffected.
+    ///
+    /// [`Notifications`]: crate::Notifications
+    /// [`Connection::poll_message`]: crate::Connection::poll_message
+    ///
+    /// `DISCARD ALL` cannot be executed inside of a transaction block.
+    pub async fn reset(&self) -> Result<(), Error> {
+        self.batch_execute_internal("DISCARD ALL").await?;
+        self.inner.clear_typeinfo_statements();
+        // `DISCARD ALL` ends all `LISTEN` registrations, so the connection has received every notification sent to
+        // the previous session by the time its response arrives.
+        self.inner.session.resets.fetch_add(1, Ordering::AcqRel);
+        Ok(())
+    }
+
     /// Begins a new database transaction.
     ///
     /// The transaction will roll back by default - use the `commit` method to commit it.
@@ -722,8 +1221,7 @@ impl Client {
         CancelToken {
             #[cfg(feature = "runtime")]
             socket_config: self.socket_config.clone(),
-            ssl_mode: self.ssl_mode,
-            ssl_negotiation: self.ssl_negotiation,
+            tls_config: self.tls_config.clone(),
             process_id: self.process_id,
             secret_key: self.secret_key,
         }
@@ -764,6 +1262,121 @@ impl Client {
         self.inner().clear_type_cache();
     }
 
+    /// Returns a handle to the client's type information cache.
+    ///
+    /// The handle can be passed to [`set_type_cache`](Client::set_type_cache) on other clients connected to the same
+    /// database so they share the types this client has already looked up.
+    pub fn type_cache(&self) -> TypeCache {
+        self.inner().type_cache()
+    }
+
+    /// Replaces the client's type information cache with a shared one.
+    ///
+    /// Types this client looks up are added to the shared cache, and types already in it are used without querying
+    /// the database. Clearing the cache with [`clear_type_cache`](Client::clear_type_cache) clears it for every client
+    /// sharing it.
+    ///
+    /// The cache must only be shared between clients connected to the same database, since type OIDs differ between
+    /// databases.
+    pub fn set_type_cache(&self, cache: TypeCache) {
+        self.inner().set_type_cache(cache);
+    }
+
+    /// Returns the metrics hooks the client was configured with, if any.
+    ///
+    /// Layers built on top of the client, such as statement caches, can use this to report their own events. See
+    /// [`Config::metrics`] for details.
+    pub fn metrics(&self) -> Option<&dyn ClientMetrics> {
+        self.inner().metrics().map(|m| &**m)
+    }
+
+    /// Registers a handler converting values of a Postgres type at runtime.
+    ///
+    /// Values of the type can then be read with [`Row::get_custom`] and passed as parameters with
+    /// [`Client::custom_param`]. A handler registered for a type's OID takes precedence over one registered for its
+    /// name, and registering a handler for a key which already has one replaces it.
+    ///
+    /// Handlers are only used by those two methods. They don't affect [`Row::get`] and [`Row::try_get`], which always
+    /// use the `FromSql` implementation of the requested type, or parameters passed without `custom_param`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use bytes::BytesMut;
+    /// use std::error::Error;
+    /// use tokio_postgres::types::{IsNull, Type};
+    /// use tokio_postgres::{Client, TypeHandler};
+    ///
+    /// // The binary format of the pgvector extension's `vector` type.
+    /// struct VectorHandler;
+    ///
+    /// impl TypeHandler for VectorHandler {
+    ///     type Value = Vec<f32>;
+    ///
+    ///     fn from_sql(&self, _: &Type, raw: &[u8]) -> Result<Vec<f32>, Box<dyn Error + Sync + Send>> {
+    ///         let values = raw.get(4..).ok_or("invalid message length")?;
+    ///         Ok(values
+    ///             .chunks_exact(4)
+    ///             .map(|c| f32::from_be_bytes(c.try_into().unwrap()))
+    ///             .collect())
+    ///     }
+    ///
+    ///     fn to_sql(
+    ///         &self,
+    ///         _: &Type,
+    ///         value: &Vec<f32>,
+    ///         out: &mut BytesMut,
+    ///     ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+    ///         out.extend_from_slice(&u16::try_from(value.len())?.to_be_bytes());
+    ///         out.extend_from_slice(&[0, 0]);
+    ///         for v in value {
+    ///             out.extend_from_slice(&v.to_be_bytes());
+    ///         }
+    ///         Ok(IsNull::No)
+    ///     }
+    /// }
+    ///
+    /// # async fn f(client: &Client) -> Result<(), tokio_postgres::Error> {
+    /// client.register_type_handler("vector", VectorHandler);
+    ///
+    /// let query = vec![1.0f32, 0.0, 0.5];
+    /// let row = client
+    ///     .query_one(
+    ///         "SELECT embedding FROM items ORDER BY embedding <-> $1 LIMIT 1",
+    ///         &[&client.custom_param(query)],
+    ///     )
+    ///     .await?;
+    /// let embedding: Option<Vec<f32>> = row.get_custom(0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn register_type_handler<K, H>(&self, key: K, handler: H)
+    where
+        K: Into<TypeKey>,
+        H: TypeHandler,
+    {
+        self.inner().type_registry().register(key.into(), handler);
+    }
+
+    /// Wraps a value so that it is passed as a query parameter using the [`TypeHandler`] registered for the
+    /// parameter's type.
+    pub fn custom_param<T>(&self, value: T) -> CustomParam<T> {
+        CustomParam::new(self.inner().type_registry().clone(), value)
+    }
+
+    /// Gracefully closes the connection to the server.
+    ///
+    /// Requests already issued by this client are allowed to complete, after which a `Terminate` message is sent, the
+    /// socket is flushed and shut down, and this method waits for the associated `Connection` to finish. Any error
+    /// encountered by the connection along the way is returned here rather than from the `Connection` itself.
+    ///
+    /// The `Connection` must continue to be polled for this method to complete.
+    pub async fn close(self) -> Result<(), Error> {
+        let (sender, receiver) = oneshot::channel();
+        self.inner.send(RequestMessages::Close(sender))?;
+        receiver.await.unwrap_or_else(|_| Err(Error::closed()))
+    }
+
     /// Determines if the connection to the server has already closed.
     ///
     /// In that case, all future queries will fail.
@@ -792,6 +1405,21 @@ impl Client {
     }
 }
 
+fn parse_server_version(version: &str) -> Option<(u32, u32)> {
+    fn number(s: &str) -> (&str, &str) {
+        let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        s.split_at(end)
+    }
+
+    let (major, rest) = number(version);
+    let major = major.parse().ok()?;
+    let minor = match rest.strip_prefix('.') {
+        Some(rest) => number(rest).0.parse().ok()?,
+        None => 0,
+    };
+    Some((major, minor))
+}
+
 impl fmt::Debug for Client {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Client").finish()
@@ -1,8 +1,10 @@
+use crate::ClientMetrics;
 use bytes::{Buf, Bytes, BytesMut};
 use fallible_iterator::FallibleIterator;
 use postgres_protocol::message::backend;
 use postgres_protocol::message::frontend::CopyData;
 use std::io;
+use std::sync::Arc;
 use tokio_util::codec::{Decoder, Encoder};
 
 pub enum FrontendMessage {
@@ -24,6 +26,13 @@ impl BackendMessages {
     pub fn empty() -> BackendMessages {
         BackendMessages(BytesMut::new())
     }
+
+    /// Returns the transaction status indicator of the trailing `ReadyForQuery` message.
+    ///
+    /// This is only meaningful if the messages complete a request.
+    pub fn ready_status(&self) -> Option<u8> {
+        self.0.last().copied()
+    }
 }
 
 impl FallibleIterator for BackendMessages {
@@ -35,17 +44,30 @@ impl FallibleIterator for BackendMessages {
     }
 }
 
-pub struct PostgresCodec;
+pub struct PostgresCodec {
+    metrics: Option<Arc<dyn ClientMetrics>>,
+}
+
+impl PostgresCodec {
+    pub fn new(metrics: Option<Arc<dyn ClientMetrics>>) -> PostgresCodec {
+        PostgresCodec { metrics }
+    }
+}
 
 impl Encoder<FrontendMessage> for PostgresCodec {
     type Error = io::Error;
 
     fn encode(&mut self, item: FrontendMessage, dst: &mut BytesMut) -> io::Result<()> {
+        let len = dst.len();
         match item {
             FrontendMessage::Raw(buf) => dst.extend_from_slice(&buf),
             FrontendMessage::CopyData(data) => data.write(dst),
         }
 
+        if let Some(metrics) = &self.metrics {
+            metrics.bytes_sent(dst.len() - len);
+        }
+
         Ok(())
     }
 }
@@ -55,44 +77,57 @@ impl Decoder for PostgresCodec {
     type Error = io::Error;
 
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<BackendMessage>, io::Error> {
-        let mut idx = 0;
-        let mut request_complete = false;
+        let len = src.len();
+        let message = decode(src)?;
 
-        while let Some(header) = backend::Header::parse(&src[idx..])? {
-            let len = header.len() as usize + 1;
-            if src[idx..].len() < len {
-                break;
+        if let Some(metrics) = &self.metrics {
+            if src.len() != len {
+                metrics.bytes_received(len - src.len());
             }
+        }
 
-            match header.tag() {
-                backend::NOTICE_RESPONSE_TAG
-                | backend::NOTIFICATION_RESPONSE_TAG
-                | backend::PARAMETER_STATUS_TAG => {
-                    if idx == 0 {
-                        let message = backend::Message::parse(src)?.unwrap();
-                        return Ok(Some(BackendMessage::Async(message)));
-                    } else {
-                        break;
-                    }
-                }
-                _ => {}
-            }
+        Ok(message)
+    }
+}
 
-            idx += len;
+fn decode(src: &mut BytesMut) -> Result<Option<BackendMessage>, io::Error> {
+    let mut idx = 0;
+    let mut request_complete = false;
+
+    while let Some(header) = backend::Header::parse(&src[idx..])? {
+        let len = header.len() as usize + 1;
+        if src[idx..].len() < len {
+            break;
+        }
 
-            if header.tag() == backend::READY_FOR_QUERY_TAG {
-                request_complete = true;
-                break;
+        match header.tag() {
+            backend::NOTICE_RESPONSE_TAG
+            | backend::NOTIFICATION_RESPONSE_TAG
+            | backend::PARAMETER_STATUS_TAG => {
+                if idx == 0 {
+                    let message = backend::Message::parse(src)?.unwrap();
+                    return Ok(Some(BackendMessage::Async(message)));
+                } else {
+                    break;
+                }
             }
+            _ => {}
         }
 
-        if idx == 0 {
-            Ok(None)
-        } else {
-            Ok(Some(BackendMessage::Normal {
-                messages: BackendMessages(src.split_to(idx)),
-                request_complete,
-            }))
+        idx += len;
+
+        if header.tag() == backend::READY_FOR_QUERY_TAG {
+            request_complete = true;
+            break;
         }
     }
+
+    if idx == 0 {
+        Ok(None)
+    } else {
+        Ok(Some(BackendMessage::Normal {
+            messages: BackendMessages(src.split_to(idx)),
+            request_complete,
+        }))
+    }
 }
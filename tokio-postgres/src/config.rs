@@ -7,29 +7,57 @@ use crate::Socket;
 #[cfg(feature = "runtime")]
 use crate::connect::connect;
 use crate::connect_raw::connect_raw;
+use crate::error::DbError;
+use crate::instrument::OperationSpan;
 #[cfg(not(target_arch = "wasm32"))]
 use crate::keepalive::KeepaliveConfig;
 #[cfg(feature = "runtime")]
+use crate::rt::Runtime;
+use crate::service;
+#[cfg(feature = "runtime")]
 use crate::tls::MakeTlsConnect;
 use crate::tls::TlsConnect;
-use crate::{Client, Connection, Error};
+use crate::types::{ToSql, Type};
+use crate::{Client, ClientMetrics, Connection, Error};
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, percent_encode, utf8_percent_encode};
 use std::borrow::Cow;
 #[cfg(unix)]
 use std::ffi::OsStr;
+#[cfg(feature = "runtime")]
+use std::future::Future;
+#[cfg(feature = "runtime")]
+use std::io;
 use std::net::IpAddr;
+#[cfg(feature = "runtime")]
+use std::net::SocketAddr;
 use std::ops::Deref;
 #[cfg(unix)]
 use std::os::unix::ffi::OsStrExt;
-#[cfg(unix)]
 use std::path::{Path, PathBuf};
+#[cfg(feature = "runtime")]
+use std::pin::Pin;
+use std::ptr;
 use std::str;
 use std::str::FromStr;
+use std::sync::Arc;
 use std::time::Duration;
 use std::{error, fmt, iter, mem};
 use tokio::io::{AsyncRead, AsyncWrite};
+#[cfg(feature = "runtime")]
+use tokio::net::TcpStream;
+#[cfg(all(feature = "runtime", unix))]
+use tokio::net::UnixStream;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) const DEFAULT_KEEPALIVE_IDLE: Duration = Duration::from_secs(2 * 60 * 60);
 
 /// Properties required of a session.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "with-serde-1",
+    derive(serde_1::Serialize, serde_1::Deserialize),
+    serde(crate = "serde_1", rename_all = "kebab-case")
+)]
 #[non_exhaustive]
 pub enum TargetSessionAttrs {
     /// No special properties are required.
@@ -42,6 +70,11 @@ pub enum TargetSessionAttrs {
 
 /// TLS configuration.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "with-serde-1",
+    derive(serde_1::Serialize, serde_1::Deserialize),
+    serde(crate = "serde_1", rename_all = "kebab-case")
+)]
 #[non_exhaustive]
 pub enum SslMode {
     /// Do not use TLS.
@@ -50,6 +83,11 @@ pub enum SslMode {
     Prefer,
     /// Require the use of TLS.
     Require,
+    /// Require the use of TLS, and verify that the server's certificate is signed by a trusted certificate authority.
+    VerifyCa,
+    /// Require the use of TLS, and verify that the server's certificate is signed by a trusted certificate authority
+    /// and matches the server's hostname.
+    VerifyFull,
 }
 
 /// TLS negotiation configuration
@@ -57,6 +95,11 @@ pub enum SslMode {
 /// See more information at
 /// https://www.postgresql.org/docs/current/libpq-connect.html#LIBPQ-CONNECT-SSLNEGOTIATION
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "with-serde-1",
+    derive(serde_1::Serialize, serde_1::Deserialize),
+    serde(crate = "serde_1", rename_all = "kebab-case")
+)]
 #[non_exhaustive]
 pub enum SslNegotiation {
     /// Use PostgreSQL SslRequest for Ssl negotiation
@@ -68,6 +111,11 @@ pub enum SslNegotiation {
 
 /// Channel binding configuration.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "with-serde-1",
+    derive(serde_1::Serialize, serde_1::Deserialize),
+    serde(crate = "serde_1", rename_all = "kebab-case")
+)]
 #[non_exhaustive]
 pub enum ChannelBinding {
     /// Do not use channel binding.
@@ -80,6 +128,11 @@ pub enum ChannelBinding {
 
 /// Load balancing configuration.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "with-serde-1",
+    derive(serde_1::Serialize, serde_1::Deserialize),
+    serde(crate = "serde_1", rename_all = "kebab-case")
+)]
 #[non_exhaustive]
 pub enum LoadBalanceHosts {
     /// Make connection attempts to hosts in the order provided.
@@ -88,6 +141,151 @@ pub enum LoadBalanceHosts {
     Random,
 }
 
+/// A resolver used to look up the addresses of TCP hosts.
+///
+/// By default, hosts are resolved with the system resolver via [`tokio::net::lookup_host`]. Implementations of this
+/// trait can be registered with [`Config::dns_resolver`] to use an alternate resolver, a cache, or a service discovery
+/// system instead.
+///
+/// Requires the `runtime` Cargo feature (enabled by default).
+#[cfg(feature = "runtime")]
+pub trait DnsResolver: Send + Sync {
+    /// Resolves a hostname and port to a list of socket addresses.
+    ///
+    /// Connection attempts are made to the addresses in the order they are returned.
+    fn resolve<'a>(
+        &'a self,
+        host: &'a str,
+        port: u16,
+    ) -> Pin<Box<dyn Future<Output = io::Result<Vec<SocketAddr>>> + Send + 'a>>;
+}
+
+/// A newly connected socket, passed to the callback registered with [`Config::socket_callback`].
+///
+/// Requires the `runtime` Cargo feature (enabled by default).
+#[cfg(feature = "runtime")]
+#[derive(Debug, Copy, Clone)]
+#[non_exhaustive]
+pub enum SocketRef<'a> {
+    /// A TCP socket.
+    Tcp(&'a TcpStream),
+    /// A Unix socket.
+    ///
+    /// This variant is only available on Unix platforms.
+    #[cfg(unix)]
+    Unix(&'a UnixStream),
+}
+
+#[cfg(feature = "runtime")]
+pub(crate) type SocketCallback = dyn Fn(SocketRef<'_>) -> io::Result<()> + Send + Sync;
+
+pub(crate) type NoticeHandler = dyn Fn(DbError) + Send + Sync;
+
+pub(crate) type ParamRedactor = dyn Fn(usize, &Type, &dyn ToSql) -> Option<String> + Send + Sync;
+
+pub(crate) type SlowQueryHandler = dyn Fn(&str, Duration, Option<u64>) + Send + Sync;
+
+pub(crate) type SqlCommenter = dyn Fn() -> Vec<(String, String)> + Send + Sync;
+
+/// A shared value which compares equal only to clones of itself.
+pub(crate) struct Shared<T: ?Sized>(pub(crate) Arc<T>);
+
+impl<T: ?Sized> Clone for Shared<T> {
+    fn clone(&self) -> Shared<T> {
+        Shared(self.0.clone())
+    }
+}
+
+impl<T: ?Sized> PartialEq for Shared<T> {
+    fn eq(&self, other: &Shared<T>) -> bool {
+        ptr::addr_eq(Arc::as_ptr(&self.0), Arc::as_ptr(&other.0))
+    }
+}
+
+impl<T: ?Sized> Eq for Shared<T> {}
+
+/// A proxy server through which connections to TCP hosts are made.
+///
+/// Hostnames are resolved by the proxy rather than locally.
+///
+/// Requires the `runtime` Cargo feature (enabled by default).
+#[cfg(feature = "runtime")]
+#[derive(Clone, PartialEq, Eq)]
+pub struct Proxy {
+    pub(crate) kind: ProxyKind,
+    pub(crate) host: String,
+    pub(crate) port: u16,
+    pub(crate) credentials: Option<(String, String)>,
+}
+
+#[cfg(feature = "runtime")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum ProxyKind {
+    Socks5,
+    HttpConnect,
+}
+
+#[cfg(feature = "runtime")]
+impl Proxy {
+    /// Creates a configuration for a SOCKS5 proxy.
+    pub fn socks5(host: impl Into<String>, port: u16) -> Proxy {
+        Proxy::new(ProxyKind::Socks5, host.into(), port)
+    }
+
+    /// Creates a configuration for an HTTP proxy which supports the `CONNECT` method.
+    pub fn http_connect(host: impl Into<String>, port: u16) -> Proxy {
+        Proxy::new(ProxyKind::HttpConnect, host.into(), port)
+    }
+
+    fn new(kind: ProxyKind, host: String, port: u16) -> Proxy {
+        Proxy {
+            kind,
+            host,
+            port,
+            credentials: None,
+        }
+    }
+
+    /// Sets the username and password used to authenticate with the proxy.
+    ///
+    /// SOCKS5 proxies use username/password authentication, and HTTP proxies use basic authentication.
+    pub fn credentials(
+        mut self,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Proxy {
+        self.credentials = Some((username.into(), password.into()));
+        self
+    }
+
+    /// Gets the hostname of the proxy.
+    pub fn get_host(&self) -> &str {
+        &self.host
+    }
+
+    /// Gets the port of the proxy.
+    pub fn get_port(&self) -> u16 {
+        self.port
+    }
+
+    /// Gets the username used to authenticate with the proxy, if one has been set.
+    pub fn get_username(&self) -> Option<&str> {
+        self.credentials.as_ref().map(|(username, _)| &**username)
+    }
+}
+
+#[cfg(feature = "runtime")]
+impl fmt::Debug for Proxy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Proxy")
+            .field("kind", &self.kind)
+            .field("host", &self.host)
+            .field("port", &self.port)
+            .field("username", &self.get_username())
+            .finish()
+    }
+}
+
 /// A host specification.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Host {
@@ -113,11 +311,26 @@ pub enum Host {
 ///
 /// * `user` - The username to authenticate with. Defaults to the user executing this process.
 /// * `password` - The password to authenticate with.
+/// * `passfile` - The path of the file to look up passwords in when none is configured. Defaults to the value of the
+///     `PGPASSFILE` environment variable, or `~/.pgpass` (`%APPDATA%\postgresql\pgpass.conf` on Windows).
 /// * `dbname` - The name of the database to connect to. Defaults to the username.
 /// * `options` - Command line options used to configure the server.
 /// * `application_name` - Sets the `application_name` parameter on the server.
 /// * `sslmode` - Controls usage of TLS. If set to `disable`, TLS will not be used. If set to `prefer`, TLS will be used
-///     if available, but not used otherwise. If set to `require`, TLS will be forced to be used. Defaults to `prefer`.
+///     if available, but not used otherwise. If set to `require`, TLS will be forced to be used. If set to `verify-ca`,
+///     TLS will be forced to be used and the server's certificate must be signed by a trusted certificate authority.
+///     If set to `verify-full`, the certificate must additionally match the server's hostname. The verification modes
+///     must be supported by the TLS connector. Defaults to `prefer`.
+/// * `sslrootcert` - The path of a PEM file containing the certificate authorities trusted by the `verify-ca` and
+///     `verify-full` modes. If set to `system`, the TLS connector's default trust roots are used, which is only
+///     allowed with `verify-full`. Defaults to `~/.postgresql/root.crt` (`%APPDATA%\postgresql\root.crt` on
+///     Windows) if it exists; otherwise `verify-full` uses the TLS connector's default trust roots and `verify-ca`
+///     fails.
+/// * `sslcert` - The path of the client certificate to present to the server, in PEM or DER format. Client
+///     certificates must be supported by the TLS connector.
+/// * `sslkey` - The path of the private key of the client certificate, in PEM or DER format. Defaults to the
+///     `sslcert` file, which must then be in PEM format and contain the key.
+/// * `sslpassword` - The password used to decrypt the private key of the client certificate.
 /// * `host` - The host to connect to. On Unix platforms, if the host starts with a `/` character it is treated as the
 ///     path to the directory containing Unix domain sockets. Otherwise, it is treated as a hostname. Multiple hosts
 ///     can be specified, separated by commas. Each host will be tried in turn when connecting. Required if connecting
@@ -149,6 +362,11 @@ pub enum Host {
 ///     omitted or the empty string.
 /// * `connect_timeout` - The time limit in seconds applied to each socket-level connection attempt. Note that hostnames
 ///     can resolve to multiple IP addresses, and this limit is applied to each address. Defaults to no timeout.
+/// * `total_connect_timeout` - The time limit in seconds applied to the entire connection process, across all hosts
+///     and retries. Defaults to no timeout.
+/// * `connect_retries` - The number of times a failed connection attempt to each host is retried before moving on to
+///     the next host. Only I/O errors and timeouts are retried; errors reported by the server, such as an
+///     authentication failure, are not. Defaults to 0.
 /// * `tcp_user_timeout` - The time limit that transmitted data may remain unacknowledged before a connection is forcibly closed.
 ///     This is ignored for Unix domain socket connections. It is only supported on systems where TCP_USER_TIMEOUT is available
 ///     and will default to the system default if omitted or set to 0; on other systems, it has no effect.
@@ -166,6 +384,11 @@ pub enum Host {
 /// * `channel_binding` - Controls usage of channel binding in the authentication process. If set to `disable`, channel
 ///     binding will not be used. If set to `prefer`, channel binding will be used if available, but not used otherwise.
 ///     If set to `require`, the authentication process will fail if channel binding is not used. Defaults to `prefer`.
+/// * `service` - The name of a service in the connection service file to take settings from. Settings specified
+///     directly take precedence over those from the service.
+/// * `servicefile` - The path of the connection service file. Defaults to the value of the `PGSERVICEFILE` environment
+///     variable, or `~/.pg_service.conf` (`%APPDATA%\postgresql\.pg_service.conf` on Windows). If the service is not
+///     defined there, `pg_service.conf` in the directory named by the `PGSYSCONFDIR` environment variable is used.
 /// * `load_balance_hosts` - Controls the order in which the client tries to connect to the available hosts and
 ///     addresses. Once a connection attempt is successful no other hosts and addresses will be tried. This parameter
 ///     is typically used in combination with multiple host names or a DNS record that returns multiple IPs. If set to
@@ -215,19 +438,47 @@ pub enum Host {
 /// ```not_rust
 /// postgresql:///mydb?user=user&host=/var/run/postgresql
 /// ```
-#[derive(Clone, PartialEq, Eq)]
+///
+/// # Serde
+///
+/// With the `with-serde-1` Cargo feature, `Config` implements `Serialize` and `Deserialize` so it can be embedded in
+/// an application's configuration file. The fields have the same names and values as the keys described above, except
+/// that `host`, `hostaddr`, and `port` are lists (though a single value is also accepted), `keepalives` is a boolean,
+/// and timeouts are numbers of seconds. Settings which cannot be expressed as data, such as the DNS resolver, are not
+/// serialized.
+///
+/// ```toml
+/// user = "postgres"
+/// host = ["host1", "host2"]
+/// port = 5432
+/// sslmode = "require"
+/// connect_timeout = 10
+/// keepalives_idle = 300
+/// target_session_attrs = "read-write"
+/// ```
+#[derive(Clone)]
 pub struct Config {
     pub(crate) user: Option<String>,
     pub(crate) password: Option<Vec<u8>>,
+    pub(crate) passfile: Option<PathBuf>,
+    pub(crate) service: Option<String>,
+    pub(crate) servicefile: Option<PathBuf>,
     pub(crate) dbname: Option<String>,
     pub(crate) options: Option<String>,
     pub(crate) application_name: Option<String>,
     pub(crate) ssl_mode: SslMode,
+    pub(crate) ssl_root_cert: Option<PathBuf>,
+    pub(crate) ssl_cert: Option<PathBuf>,
+    pub(crate) ssl_key: Option<PathBuf>,
+    pub(crate) ssl_password: Option<Vec<u8>>,
     pub(crate) ssl_negotiation: SslNegotiation,
     pub(crate) host: Vec<Host>,
     pub(crate) hostaddr: Vec<IpAddr>,
     pub(crate) port: Vec<u16>,
     pub(crate) connect_timeout: Option<Duration>,
+    pub(crate) total_connect_timeout: Option<Duration>,
+    pub(crate) connect_retries: u32,
+    pub(crate) connect_retry_backoff: Duration,
     pub(crate) tcp_user_timeout: Option<Duration>,
     pub(crate) keepalives: bool,
     #[cfg(not(target_arch = "wasm32"))]
@@ -235,8 +486,143 @@ pub struct Config {
     pub(crate) target_session_attrs: TargetSessionAttrs,
     pub(crate) channel_binding: ChannelBinding,
     pub(crate) load_balance_hosts: LoadBalanceHosts,
+    pub(crate) query_in_errors: bool,
+    pub(crate) params_in_errors: bool,
+    pub(crate) trace_query_text: bool,
+    pub(crate) param_redactor: Option<Shared<ParamRedactor>>,
+    pub(crate) notice_handler: Option<Shared<NoticeHandler>>,
+    pub(crate) metrics: Option<Shared<dyn ClientMetrics>>,
+    pub(crate) slow_query: Option<(Duration, Shared<SlowQueryHandler>)>,
+    pub(crate) sql_commenter: Option<Shared<SqlCommenter>>,
+    #[cfg(feature = "runtime")]
+    pub(crate) dns_resolver: Option<Shared<dyn DnsResolver>>,
+    #[cfg(feature = "runtime")]
+    pub(crate) proxy: Option<Proxy>,
+    #[cfg(feature = "runtime")]
+    pub(crate) socket_callback: Option<Shared<SocketCallback>>,
+    #[cfg(feature = "runtime")]
+    pub(crate) runtime: Option<Shared<dyn Runtime>>,
+    /// The settings without an `Option` which have been configured, as a mask of `Explicit` bits.
+    explicit: u16,
+}
+
+/// Settings which always have a value, tracked separately so that they count as configured even when set to their
+/// defaults.
+#[derive(Copy, Clone)]
+enum Explicit {
+    SslMode,
+    SslNegotiation,
+    ConnectRetries,
+    Keepalives,
+    KeepalivesIdle,
+    TargetSessionAttrs,
+    ChannelBinding,
+    LoadBalanceHosts,
+}
+
+// the mask of explicitly set settings only affects how configs are merged, so it isn't part of equality
+impl PartialEq for Config {
+    fn eq(&self, other: &Config) -> bool {
+        let Config {
+            user,
+            password,
+            passfile,
+            service,
+            servicefile,
+            dbname,
+            options,
+            application_name,
+            ssl_mode,
+            ssl_root_cert,
+            ssl_cert,
+            ssl_key,
+            ssl_password,
+            ssl_negotiation,
+            host,
+            hostaddr,
+            port,
+            connect_timeout,
+            total_connect_timeout,
+            connect_retries,
+            connect_retry_backoff,
+            tcp_user_timeout,
+            keepalives,
+            #[cfg(not(target_arch = "wasm32"))]
+            keepalive_config,
+            target_session_attrs,
+            channel_binding,
+            load_balance_hosts,
+            query_in_errors,
+            params_in_errors,
+            trace_query_text,
+            param_redactor,
+            notice_handler,
+            metrics,
+            slow_query,
+            sql_commenter,
+            #[cfg(feature = "runtime")]
+            dns_resolver,
+            #[cfg(feature = "runtime")]
+            proxy,
+            #[cfg(feature = "runtime")]
+            socket_callback,
+            #[cfg(feature = "runtime")]
+            runtime,
+            explicit: _,
+        } = self;
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if *keepalive_config != other.keepalive_config {
+            return false;
+        }
+        #[cfg(feature = "runtime")]
+        if *dns_resolver != other.dns_resolver
+            || *proxy != other.proxy
+            || *socket_callback != other.socket_callback
+            || *runtime != other.runtime
+        {
+            return false;
+        }
+
+        *user == other.user
+            && *password == other.password
+            && *passfile == other.passfile
+            && *service == other.service
+            && *servicefile == other.servicefile
+            && *dbname == other.dbname
+            && *options == other.options
+            && *application_name == other.application_name
+            && *ssl_mode == other.ssl_mode
+            && *ssl_root_cert == other.ssl_root_cert
+            && *ssl_cert == other.ssl_cert
+            && *ssl_key == other.ssl_key
+            && *ssl_password == other.ssl_password
+            && *ssl_negotiation == other.ssl_negotiation
+            && *host == other.host
+            && *hostaddr == other.hostaddr
+            && *port == other.port
+            && *connect_timeout == other.connect_timeout
+            && *total_connect_timeout == other.total_connect_timeout
+            && *connect_retries == other.connect_retries
+            && *connect_retry_backoff == other.connect_retry_backoff
+            && *tcp_user_timeout == other.tcp_user_timeout
+            && *keepalives == other.keepalives
+            && *target_session_attrs == other.target_session_attrs
+            && *channel_binding == other.channel_binding
+            && *load_balance_hosts == other.load_balance_hosts
+            && *query_in_errors == other.query_in_errors
+            && *params_in_errors == other.params_in_errors
+            && *trace_query_text == other.trace_query_text
+            && *param_redactor == other.param_redactor
+            && *notice_handler == other.notice_handler
+            && *metrics == other.metrics
+            && *slow_query == other.slow_query
+            && *sql_commenter == other.sql_commenter
+    }
 }
 
+impl Eq for Config {}
+
 impl Default for Config {
     fn default() -> Config {
         Config::new()
@@ -249,29 +635,114 @@ impl Config {
         Config {
             user: None,
             password: None,
+            passfile: None,
+            service: None,
+            servicefile: None,
             dbname: None,
             options: None,
             application_name: None,
             ssl_mode: SslMode::Prefer,
+            ssl_root_cert: None,
+            ssl_cert: None,
+            ssl_key: None,
+            ssl_password: None,
             ssl_negotiation: SslNegotiation::Postgres,
             host: vec![],
             hostaddr: vec![],
             port: vec![],
             connect_timeout: None,
+            total_connect_timeout: None,
+            connect_retries: 0,
+            connect_retry_backoff: Duration::from_millis(100),
             tcp_user_timeout: None,
             keepalives: true,
             #[cfg(not(target_arch = "wasm32"))]
             keepalive_config: KeepaliveConfig {
-                idle: Duration::from_secs(2 * 60 * 60),
+                idle: DEFAULT_KEEPALIVE_IDLE,
                 interval: None,
                 retries: None,
             },
             target_session_attrs: TargetSessionAttrs::Any,
             channel_binding: ChannelBinding::Prefer,
             load_balance_hosts: LoadBalanceHosts::Disable,
+            query_in_errors: false,
+            params_in_errors: false,
+            trace_query_text: false,
+            param_redactor: None,
+            notice_handler: None,
+            metrics: None,
+            slow_query: None,
+            sql_commenter: None,
+            #[cfg(feature = "runtime")]
+            dns_resolver: None,
+            #[cfg(feature = "runtime")]
+            proxy: None,
+            #[cfg(feature = "runtime")]
+            socket_callback: None,
+            #[cfg(feature = "runtime")]
+            runtime: None,
+            explicit: 0,
         }
     }
 
+    /// Creates a new configuration from the environment variables used by libpq.
+    ///
+    /// The following variables are supported, each corresponding to a connection string key:
+    ///
+    /// | Variable | Key |
+    /// | -------- | --- |
+    /// | `PGHOST` | `host` |
+    /// | `PGHOSTADDR` | `hostaddr` |
+    /// | `PGPORT` | `port` |
+    /// | `PGDATABASE` | `dbname` |
+    /// | `PGUSER` | `user` |
+    /// | `PGPASSWORD` | `password` |
+    /// | `PGOPTIONS` | `options` |
+    /// | `PGAPPNAME` | `application_name` |
+    /// | `PGSSLMODE` | `sslmode` |
+    /// | `PGSSLROOTCERT` | `sslrootcert` |
+    /// | `PGSSLCERT` | `sslcert` |
+    /// | `PGSSLKEY` | `sslkey` |
+    /// | `PGSSLNEGOTIATION` | `sslnegotiation` |
+    /// | `PGCONNECT_TIMEOUT` | `connect_timeout` |
+    /// | `PGTARGETSESSIONATTRS` | `target_session_attrs` |
+    /// | `PGCHANNELBINDING` | `channel_binding` |
+    /// | `PGLOADBALANCEHOSTS` | `load_balance_hosts` |
+    /// | `PGSERVICE` | `service` |
+    ///
+    /// An error is returned if a variable holds a value which is invalid for its key.
+    pub fn from_env() -> Result<Config, Error> {
+        let mut config = Config::new();
+        config.apply_env()?;
+        Ok(config)
+    }
+
+    /// Fills in settings which have not been configured from the environment variables used by libpq.
+    ///
+    /// This is typically used after parsing a connection string, so that settings it specifies take precedence over
+    /// the environment, as they do in libpq. A setting which has been explicitly configured is kept even if it was set
+    /// to its default value. See [`from_env`](Config::from_env) for the supported variables.
+    pub fn apply_env(&mut self) -> Result<&mut Config, Error> {
+        self.apply_env_with(|name| std::env::var(name).ok())?;
+        Ok(self)
+    }
+
+    fn apply_env_with<F>(&mut self, var: F) -> Result<(), Error>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        for (name, key) in ENV_VARS {
+            if !self.is_unset(key) {
+                continue;
+            }
+            if let Some(value) = var(name) {
+                self.param(key, &value)?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Sets the user to authenticate with.
     ///
     /// Defaults to the user executing this process.
@@ -301,6 +772,24 @@ impl Config {
         self.password.as_deref()
     }
 
+    /// Sets the path of the password file.
+    ///
+    /// If no password is configured, the password for each host is looked up in this file when connecting, as it is
+    /// by libpq. Entries have the form `hostname:port:database:username:password`, where any of the first four
+    /// fields may be `*` to match anything. The file is ignored if it grants any access to group or other users.
+    ///
+    /// Defaults to the value of the `PGPASSFILE` environment variable, or `~/.pgpass`
+    /// (`%APPDATA%\postgresql\pgpass.conf` on Windows).
+    pub fn passfile(&mut self, passfile: impl Into<PathBuf>) -> &mut Config {
+        self.passfile = Some(passfile.into());
+        self
+    }
+
+    /// Gets the path of the password file, if one has been configured with the `passfile` method.
+    pub fn get_passfile(&self) -> Option<&Path> {
+        self.passfile.as_deref()
+    }
+
     /// Sets the name of the database to connect to.
     ///
     /// Defaults to the user.
@@ -344,6 +833,7 @@ impl Config {
     /// Defaults to `prefer`.
     pub fn ssl_mode(&mut self, ssl_mode: SslMode) -> &mut Config {
         self.ssl_mode = ssl_mode;
+        self.set_explicit(Explicit::SslMode);
         self
     }
 
@@ -357,6 +847,7 @@ impl Config {
     /// Defaults to `postgres`.
     pub fn ssl_negotiation(&mut self, ssl_negotiation: SslNegotiation) -> &mut Config {
         self.ssl_negotiation = ssl_negotiation;
+        self.set_explicit(Explicit::SslNegotiation);
         self
     }
 
@@ -365,6 +856,65 @@ impl Config {
         self.ssl_negotiation
     }
 
+    /// Sets the path of the file containing the certificate authorities trusted by the `verify-ca` and `verify-full`
+    /// SSL modes.
+    ///
+    /// If set to `system`, the TLS connector's default trust roots are used, which is only allowed with the
+    /// `verify-full` mode. Defaults to `~/.postgresql/root.crt` if it exists; otherwise the `verify-full` mode uses the
+    /// TLS connector's default trust roots, and the `verify-ca` mode fails to connect.
+    pub fn ssl_root_cert(&mut self, ssl_root_cert: impl Into<PathBuf>) -> &mut Config {
+        self.ssl_root_cert = Some(ssl_root_cert.into());
+        self
+    }
+
+    /// Gets the path of the file containing trusted certificate authorities, if one has been set with the
+    /// `ssl_root_cert` method.
+    pub fn get_ssl_root_cert(&self) -> Option<&Path> {
+        self.ssl_root_cert.as_deref()
+    }
+
+    /// Sets the path of the client certificate to present to the server.
+    ///
+    /// The file may be in PEM or DER format. Client certificates must be supported by the TLS connector.
+    pub fn ssl_cert(&mut self, ssl_cert: impl Into<PathBuf>) -> &mut Config {
+        self.ssl_cert = Some(ssl_cert.into());
+        self
+    }
+
+    /// Gets the path of the client certificate, if one has been set with the `ssl_cert` method.
+    pub fn get_ssl_cert(&self) -> Option<&Path> {
+        self.ssl_cert.as_deref()
+    }
+
+    /// Sets the path of the private key of the client certificate.
+    ///
+    /// The file may be in PEM or DER format. Defaults to the file set with the `ssl_cert` method, which must then be in
+    /// PEM format and contain the key.
+    pub fn ssl_key(&mut self, ssl_key: impl Into<PathBuf>) -> &mut Config {
+        self.ssl_key = Some(ssl_key.into());
+        self
+    }
+
+    /// Gets the path of the private key of the client certificate, if one has been set with the `ssl_key` method.
+    pub fn get_ssl_key(&self) -> Option<&Path> {
+        self.ssl_key.as_deref()
+    }
+
+    /// Sets the password used to decrypt the private key of the client certificate.
+    pub fn ssl_password<T>(&mut self, ssl_password: T) -> &mut Config
+    where
+        T: AsRef<[u8]>,
+    {
+        self.ssl_password = Some(ssl_password.as_ref().to_vec());
+        self
+    }
+
+    /// Gets the password used to decrypt the private key of the client certificate, if one has been set with the
+    /// `ssl_password` method.
+    pub fn get_ssl_password(&self) -> Option<&[u8]> {
+        self.ssl_password.as_deref()
+    }
+
     /// Adds a host to the configuration.
     ///
     /// Multiple hosts can be specified by calling this method multiple times, and each will be tried in order. On Unix
@@ -445,11 +995,59 @@ impl Config {
         self.connect_timeout.as_ref()
     }
 
+    /// Sets the timeout applied to the entire connection process.
+    ///
+    /// Unlike `connect_timeout`, this limit covers every host, address, and retry, including authentication and
+    /// session checks. Defaults to no limit.
+    pub fn total_connect_timeout(&mut self, total_connect_timeout: Duration) -> &mut Config {
+        self.total_connect_timeout = Some(total_connect_timeout);
+        self
+    }
+
+    /// Gets the total connection timeout, if one has been set with the
+    /// `total_connect_timeout` method.
+    pub fn get_total_connect_timeout(&self) -> Option<&Duration> {
+        self.total_connect_timeout.as_ref()
+    }
+
+    /// Sets the number of times a failed connection attempt to each host is retried before moving on to the next host.
+    ///
+    /// Only attempts which fail to reach the server, with an I/O error or a timeout, are retried. Errors reported by
+    /// the server, such as an authentication failure or a nonexistent database, move on to the next host immediately.
+    ///
+    /// Defaults to 0.
+    pub fn connect_retries(&mut self, connect_retries: u32) -> &mut Config {
+        self.connect_retries = connect_retries;
+        self.set_explicit(Explicit::ConnectRetries);
+        self
+    }
+
+    /// Gets the number of times a failed connection attempt to each host is retried.
+    pub fn get_connect_retries(&self) -> u32 {
+        self.connect_retries
+    }
+
+    /// Sets the delay before the first retry of a failed connection attempt to a host.
+    ///
+    /// The delay doubles after each subsequent failed attempt to the same host. Defaults to 100 milliseconds.
+    pub fn connect_retry_backoff(&mut self, connect_retry_backoff: Duration) -> &mut Config {
+        self.connect_retry_backoff = connect_retry_backoff;
+        self
+    }
+
+    /// Gets the delay before the first retry of a failed connection attempt to a host.
+    pub fn get_connect_retry_backoff(&self) -> Duration {
+        self.connect_retry_backoff
+    }
+
     /// Sets the TCP user timeout.
     ///
     /// This is ignored for Unix domain socket connections. It is only supported on systems where
     /// TCP_USER_TIMEOUT is available and will default to the system default if omitted or set to 0;
     /// on other systems, it has no effect.
+    ///
+    /// This can't be applied to sockets opened by a custom [runtime](Config::runtime); connecting fails with a
+    /// configuration error if both are set.
     pub fn tcp_user_timeout(&mut self, tcp_user_timeout: Duration) -> &mut Config {
         self.tcp_user_timeout = Some(tcp_user_timeout);
         self
@@ -464,8 +1062,12 @@ impl Config {
     /// Controls the use of TCP keepalive.
     ///
     /// This is ignored for Unix domain socket connections. Defaults to `true`.
+    ///
+    /// Keepalives are not enabled on sockets opened by a custom [runtime](Config::runtime); the runtime is
+    /// responsible for configuring them.
     pub fn keepalives(&mut self, keepalives: bool) -> &mut Config {
         self.keepalives = keepalives;
+        self.set_explicit(Explicit::Keepalives);
         self
     }
 
@@ -477,9 +1079,13 @@ impl Config {
     /// Sets the amount of idle time before a keepalive packet is sent on the connection.
     ///
     /// This is ignored for Unix domain sockets, or if the `keepalives` option is disabled. Defaults to 2 hours.
+    ///
+    /// This can't be applied to sockets opened by a custom [runtime](Config::runtime); connecting fails with a
+    /// configuration error if both are set.
     #[cfg(not(target_arch = "wasm32"))]
     pub fn keepalives_idle(&mut self, keepalives_idle: Duration) -> &mut Config {
         self.keepalive_config.idle = keepalives_idle;
+        self.set_explicit(Explicit::KeepalivesIdle);
         self
     }
 
@@ -494,6 +1100,9 @@ impl Config {
     /// On Windows, this sets the value of the tcp_keepalive struct’s keepaliveinterval field.
     ///
     /// This is ignored for Unix domain sockets, or if the `keepalives` option is disabled.
+    ///
+    /// This can't be applied to sockets opened by a custom [runtime](Config::runtime); connecting fails with a
+    /// configuration error if both are set.
     #[cfg(not(target_arch = "wasm32"))]
     pub fn keepalives_interval(&mut self, keepalives_interval: Duration) -> &mut Config {
         self.keepalive_config.interval = Some(keepalives_interval);
@@ -509,6 +1118,9 @@ impl Config {
     /// Sets the maximum number of TCP keepalive probes that will be sent before dropping a connection.
     ///
     /// This is ignored for Unix domain sockets, or if the `keepalives` option is disabled.
+    ///
+    /// This can't be applied to sockets opened by a custom [runtime](Config::runtime); connecting fails with a
+    /// configuration error if both are set.
     #[cfg(not(target_arch = "wasm32"))]
     pub fn keepalives_retries(&mut self, keepalives_retries: u32) -> &mut Config {
         self.keepalive_config.retries = Some(keepalives_retries);
@@ -530,6 +1142,7 @@ impl Config {
         target_session_attrs: TargetSessionAttrs,
     ) -> &mut Config {
         self.target_session_attrs = target_session_attrs;
+        self.set_explicit(Explicit::TargetSessionAttrs);
         self
     }
 
@@ -543,6 +1156,7 @@ impl Config {
     /// Defaults to `prefer`.
     pub fn channel_binding(&mut self, channel_binding: ChannelBinding) -> &mut Config {
         self.channel_binding = channel_binding;
+        self.set_explicit(Explicit::ChannelBinding);
         self
     }
 
@@ -556,6 +1170,7 @@ impl Config {
     /// Defaults to `disable`.
     pub fn load_balance_hosts(&mut self, load_balance_hosts: LoadBalanceHosts) -> &mut Config {
         self.load_balance_hosts = load_balance_hosts;
+        self.set_explicit(Explicit::LoadBalanceHosts);
         self
     }
 
@@ -564,7 +1179,359 @@ impl Config {
         self.load_balance_hosts
     }
 
-    fn param(&mut self, key: &str, value: &str) -> Result<(), Error> {
+    /// Controls whether errors record the text of the statement which caused them.
+    ///
+    /// When enabled, errors returned while preparing or running a statement make its SQL available through
+    /// [`Error::query`](crate::Error::query). This makes failures easier to trace back to the code which issued
+    /// them, but the text ends up wherever the error is logged, so it is disabled by default in case queries contain
+    /// sensitive literals.
+    ///
+    /// Defaults to `false`.
+    pub fn query_in_errors(&mut self, query_in_errors: bool) -> &mut Config {
+        self.query_in_errors = query_in_errors;
+        self
+    }
+
+    /// Gets whether errors record the text of the statement which caused them.
+    pub fn get_query_in_errors(&self) -> bool {
+        self.query_in_errors
+    }
+
+    /// Controls whether errors record the values of the parameters bound to the statement which caused them.
+    ///
+    /// When enabled, errors returned while running a statement make renderings of its parameters available through
+    /// [`Error::params`](crate::Error::params), so that, for example, a constraint violation can be traced back to
+    /// the row which caused it. Parameters are rendered as SQL literals, which will include secrets such as passwords
+    /// or tokens, so sensitive values should be hidden with a [`param_redactor`](Config::param_redactor). Values of
+    /// types which can't be rendered are replaced by their length in bytes.
+    ///
+    /// Statements keep a reference to their encoded parameters, which are only decoded and rendered once an error
+    /// occurs. Parameters bound to portals are not recorded.
+    ///
+    /// Defaults to `false`.
+    pub fn params_in_errors(&mut self, params_in_errors: bool) -> &mut Config {
+        self.params_in_errors = params_in_errors;
+        self
+    }
+
+    /// Gets whether errors record the values of the parameters bound to the statement which caused them.
+    pub fn get_params_in_errors(&self) -> bool {
+        self.params_in_errors
+    }
+
+    /// Controls whether tracing spans record the text of the statements they cover.
+    ///
+    /// When enabled, the spans created with the `tracing` Cargo feature record the SQL of each statement in their
+    /// `db.query.text` field. It is disabled by default since the text ends up wherever spans are exported, and
+    /// queries may contain sensitive literals. This has no effect without the `tracing` feature.
+    ///
+    /// Defaults to `false`.
+    pub fn trace_query_text(&mut self, trace_query_text: bool) -> &mut Config {
+        self.trace_query_text = trace_query_text;
+        self
+    }
+
+    /// Gets whether tracing spans record the text of the statements they cover.
+    pub fn get_trace_query_text(&self) -> bool {
+        self.trace_query_text
+    }
+
+    /// Sets a function which renders parameters recorded in errors.
+    ///
+    /// The function is called with the index, type, and value of each parameter, where the value is the parameter as
+    /// it was encoded and sent to the server. It can return a replacement for the parameter's rendering, or `None` to
+    /// use it unchanged. Has no effect unless
+    /// [`params_in_errors`](Config::params_in_errors) is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tokio_postgres::Config;
+    /// use tokio_postgres::types::Type;
+    ///
+    /// let mut config = Config::new();
+    /// config
+    ///     .params_in_errors(true)
+    ///     .param_redactor(|_, type_, _| (*type_ == Type::BYTEA).then(|| "<redacted>".to_string()));
+    /// ```
+    pub fn param_redactor<F>(&mut self, f: F) -> &mut Config
+    where
+        F: Fn(usize, &Type, &dyn ToSql) -> Option<String> + Send + Sync + 'static,
+    {
+        self.param_redactor = Some(Shared(Arc::new(f)));
+        self
+    }
+
+    /// Sets a function which is called with each notice received from the server.
+    ///
+    /// By default, notices are returned by [`Connection::poll_message`] and logged by the connection's `Future`
+    /// implementation. See [`Connection::set_notice_handler`] for details.
+    pub fn notice_handler<F>(&mut self, f: F) -> &mut Config
+    where
+        F: Fn(DbError) + Send + Sync + 'static,
+    {
+        self.notice_handler = Some(Shared(Arc::new(f)));
+        self
+    }
+
+    /// Sets the hooks which are notified of queries, errors, and network traffic on the connection.
+    ///
+    /// See [`ClientMetrics`] for details.
+    pub fn metrics<M>(&mut self, metrics: M) -> &mut Config
+    where
+        M: ClientMetrics + 'static,
+    {
+        self.metrics = Some(Shared(Arc::new(metrics)));
+        self
+    }
+
+    /// Gets the metrics hooks, if they have been set with the `metrics` method.
+    pub fn get_metrics(&self) -> Option<&dyn ClientMetrics> {
+        self.metrics.as_ref().map(|m| &*m.0)
+    }
+
+    /// Sets a function which is called with each statement which takes at least `threshold` to run.
+    ///
+    /// The function is called with the text of the statement, how long it took, and the number of rows it returned or
+    /// affected, if known. Statements are timed from when they are sent until their last row is received, so the
+    /// time includes any delay in consuming the rows of a query. Statements which fail are reported without a row
+    /// count. Preparing a statement is not reported.
+    ///
+    /// The function is called inline while the statement's results are processed, so it should not block.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use tokio_postgres::Config;
+    ///
+    /// let mut config = Config::new();
+    /// config.slow_query_handler(Duration::from_millis(500), |query, duration, _| {
+    ///     eprintln!("slow query ({duration:?}): {query}");
+    /// });
+    /// ```
+    pub fn slow_query_handler<F>(&mut self, threshold: Duration, f: F) -> &mut Config
+    where
+        F: Fn(&str, Duration, Option<u64>) + Send + Sync + 'static,
+    {
+        self.slow_query = Some((threshold, Shared(Arc::new(f))));
+        self
+    }
+
+    /// Gets the slow query threshold, if a handler has been set with the `slow_query_handler` method.
+    pub fn get_slow_query_threshold(&self) -> Option<Duration> {
+        self.slow_query.as_ref().map(|(threshold, _)| *threshold)
+    }
+
+    /// Sets a function which returns tags to attach to statements as [sqlcommenter] comments.
+    ///
+    /// The function is called each time a query string is sent, such as by `query`, `execute`, `query_typed`,
+    /// `simple_query`, or `batch_execute`, and the key-value pairs it returns are appended to the query's text in a
+    /// comment such as
+    /// `/*application='billing',traceparent='00-5bd6...-01'*/`. This lets statements in the server's logs and in
+    /// `pg_stat_activity` be correlated with the distributed traces and application components which issued them.
+    /// A function can, for example, return the W3C `traceparent` of the current span along with fixed tags naming
+    /// the application.
+    ///
+    /// Statements prepared with `prepare` or `prepare_typed` are not tagged, since they may be executed long after the
+    /// context which the tags describe has ended, and neither are the statements issued by the client itself, such as
+    /// `BEGIN` and type lookups. Queries which already contain a `/*` comment are sent unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tokio_postgres::Config;
+    ///
+    /// let mut config = Config::new();
+    /// config.sql_commenter(|| vec![("application".to_string(), "billing".to_string())]);
+    /// ```
+    ///
+    /// [sqlcommenter]: https://google.github.io/sqlcommenter/spec/
+    pub fn sql_commenter<F>(&mut self, f: F) -> &mut Config
+    where
+        F: Fn() -> Vec<(String, String)> + Send + Sync + 'static,
+    {
+        self.sql_commenter = Some(Shared(Arc::new(f)));
+        self
+    }
+
+    /// Sets the name of a service in the connection service file to take settings from.
+    ///
+    /// The service file groups connection settings into named sections, as used by libpq:
+    ///
+    /// ```not_rust
+    /// [mydb]
+    /// host=db.example.com
+    /// dbname=app
+    /// ```
+    ///
+    /// The service is loaded when connecting, and its settings are used for anything which hasn't been configured
+    /// directly. See [`servicefile`](Config::servicefile) for how the file is found.
+    pub fn service(&mut self, service: impl Into<String>) -> &mut Config {
+        self.service = Some(service.into());
+        self
+    }
+
+    /// Gets the name of the connection service, if one has been configured with the `service` method.
+    pub fn get_service(&self) -> Option<&str> {
+        self.service.as_deref()
+    }
+
+    /// Sets the path of the connection service file.
+    ///
+    /// Defaults to the value of the `PGSERVICEFILE` environment variable, or `~/.pg_service.conf`
+    /// (`%APPDATA%\postgresql\.pg_service.conf` on Windows). Services which are not defined in this file are looked
+    /// up in `pg_service.conf` in the directory named by the `PGSYSCONFDIR` environment variable.
+    pub fn servicefile(&mut self, servicefile: impl Into<PathBuf>) -> &mut Config {
+        self.servicefile = Some(servicefile.into());
+        self
+    }
+
+    /// Gets the path of the connection service file, if one has been configured with the `servicefile` method.
+    pub fn get_servicefile(&self) -> Option<&Path> {
+        self.servicefile.as_deref()
+    }
+
+    /// Sets the resolver used to look up the addresses of TCP hosts.
+    ///
+    /// Addresses specified with `hostaddr` are used directly and are not resolved.
+    ///
+    /// Defaults to the system resolver.
+    ///
+    /// Requires the `runtime` Cargo feature (enabled by default).
+    #[cfg(feature = "runtime")]
+    pub fn dns_resolver<R>(&mut self, resolver: R) -> &mut Config
+    where
+        R: DnsResolver + 'static,
+    {
+        self.dns_resolver = Some(Shared(Arc::new(resolver)));
+        self
+    }
+
+    /// Gets the resolver used to look up the addresses of TCP hosts, if one has been set.
+    #[cfg(feature = "runtime")]
+    pub fn get_dns_resolver(&self) -> Option<&dyn DnsResolver> {
+        self.dns_resolver.as_ref().map(|r| &*r.0)
+    }
+
+    /// Sets a proxy server through which connections to TCP hosts are made.
+    ///
+    /// Connections to Unix sockets do not use the proxy. Hostnames are resolved by the proxy, so any
+    /// [DNS resolver](Config::dns_resolver) is not used.
+    ///
+    /// Requires the `runtime` Cargo feature (enabled by default).
+    #[cfg(feature = "runtime")]
+    pub fn proxy(&mut self, proxy: Proxy) -> &mut Config {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Gets the proxy server through which connections are made, if one has been set.
+    #[cfg(feature = "runtime")]
+    pub fn get_proxy(&self) -> Option<&Proxy> {
+        self.proxy.as_ref()
+    }
+
+    /// Sets a callback which is invoked with each newly connected socket before the Postgres handshake begins.
+    ///
+    /// This can be used to set platform-specific socket options which aren't otherwise exposed, for example via
+    /// the `socket2` crate. If the callback returns an error, the connection attempt fails with that error. The
+    /// callback is also invoked for the sockets used to cancel queries.
+    ///
+    /// When connecting through a [proxy](Config::proxy), the callback receives the socket connected to the proxy.
+    ///
+    /// Sockets opened by a custom [runtime](Config::runtime) can't be passed to the callback; connecting fails with a
+    /// configuration error if both are set.
+    ///
+    /// Requires the `runtime` Cargo feature (enabled by default).
+    #[cfg(feature = "runtime")]
+    pub fn socket_callback<F>(&mut self, f: F) -> &mut Config
+    where
+        F: Fn(SocketRef<'_>) -> io::Result<()> + Send + Sync + 'static,
+    {
+        self.socket_callback = Some(Shared(Arc::new(f)));
+        self
+    }
+
+    /// Sets the runtime used to open sockets, resolve hostnames, and wait on timers while connecting.
+    ///
+    /// See the [`rt`](crate::rt) module for details. Defaults to Tokio.
+    ///
+    /// Streams opened by the runtime are used as-is, so TCP keepalives are not enabled on them. Connecting fails with
+    /// a configuration error if a [socket callback](Config::socket_callback), a
+    /// [TCP user timeout](Config::tcp_user_timeout), or keepalive parameters are also set.
+    ///
+    /// Requires the `runtime` Cargo feature (enabled by default).
+    #[cfg(feature = "runtime")]
+    pub fn runtime<R>(&mut self, runtime: R) -> &mut Config
+    where
+        R: Runtime + 'static,
+    {
+        self.runtime = Some(Shared(Arc::new(runtime)));
+        self
+    }
+
+    /// Gets the runtime used while connecting, if one has been set.
+    #[cfg(feature = "runtime")]
+    pub fn get_runtime(&self) -> Option<&dyn Runtime> {
+        self.runtime.as_ref().map(|r| &*r.0)
+    }
+
+    #[doc(hidden)]
+    #[cfg(feature = "runtime")]
+    pub fn __private_api_runtime(&self) -> Option<Arc<dyn Runtime>> {
+        self.runtime.as_ref().map(|r| r.0.clone())
+    }
+
+    fn set_explicit(&mut self, setting: Explicit) {
+        self.explicit |= 1 << setting as u16;
+    }
+
+    fn is_explicit(&self, setting: Explicit) -> bool {
+        self.explicit & (1 << setting as u16) != 0
+    }
+
+    /// Determines if the setting for a connection string key has not been configured.
+    ///
+    /// A setting which has been explicitly set counts as configured even if it was set to its default value.
+    pub(crate) fn is_unset(&self, key: &str) -> bool {
+        match key {
+            "user" => self.user.is_none(),
+            "password" => self.password.is_none(),
+            "passfile" => self.passfile.is_none(),
+            "dbname" => self.dbname.is_none(),
+            "options" => self.options.is_none(),
+            "application_name" => self.application_name.is_none(),
+            "sslmode" => !self.is_explicit(Explicit::SslMode),
+            "sslrootcert" => self.ssl_root_cert.is_none(),
+            "sslcert" => self.ssl_cert.is_none(),
+            "sslkey" => self.ssl_key.is_none(),
+            "sslpassword" => self.ssl_password.is_none(),
+            "sslnegotiation" => !self.is_explicit(Explicit::SslNegotiation),
+            "host" => self.host.is_empty(),
+            "hostaddr" => self.hostaddr.is_empty(),
+            "port" => self.port.is_empty(),
+            "connect_timeout" => self.connect_timeout.is_none(),
+            "total_connect_timeout" => self.total_connect_timeout.is_none(),
+            "connect_retries" => !self.is_explicit(Explicit::ConnectRetries),
+            "tcp_user_timeout" => self.tcp_user_timeout.is_none(),
+            "keepalives" => !self.is_explicit(Explicit::Keepalives),
+            #[cfg(not(target_arch = "wasm32"))]
+            "keepalives_idle" => !self.is_explicit(Explicit::KeepalivesIdle),
+            #[cfg(not(target_arch = "wasm32"))]
+            "keepalives_interval" => self.keepalive_config.interval.is_none(),
+            #[cfg(not(target_arch = "wasm32"))]
+            "keepalives_retries" => self.keepalive_config.retries.is_none(),
+            "target_session_attrs" => !self.is_explicit(Explicit::TargetSessionAttrs),
+            "channel_binding" => !self.is_explicit(Explicit::ChannelBinding),
+            "load_balance_hosts" => !self.is_explicit(Explicit::LoadBalanceHosts),
+            "service" => self.service.is_none(),
+            "servicefile" => self.servicefile.is_none(),
+            _ => true,
+        }
+    }
+
+    pub(crate) fn param(&mut self, key: &str, value: &str) -> Result<(), Error> {
         match key {
             "user" => {
                 self.user(value);
@@ -572,6 +1539,9 @@ impl Config {
             "password" => {
                 self.password(value);
             }
+            "passfile" => {
+                self.passfile(value);
+            }
             "dbname" => {
                 self.dbname(value);
             }
@@ -586,10 +1556,24 @@ impl Config {
                     "disable" => SslMode::Disable,
                     "prefer" => SslMode::Prefer,
                     "require" => SslMode::Require,
+                    "verify-ca" => SslMode::VerifyCa,
+                    "verify-full" => SslMode::VerifyFull,
                     _ => return Err(Error::config_parse(Box::new(InvalidValue("sslmode")))),
                 };
                 self.ssl_mode(mode);
             }
+            "sslrootcert" => {
+                self.ssl_root_cert(value);
+            }
+            "sslcert" => {
+                self.ssl_cert(value);
+            }
+            "sslkey" => {
+                self.ssl_key(value);
+            }
+            "sslpassword" => {
+                self.ssl_password(value);
+            }
             "sslnegotiation" => {
                 let mode = match value {
                     "postgres" => SslNegotiation::Postgres,
@@ -634,6 +1618,20 @@ impl Config {
                     self.connect_timeout(Duration::from_secs(timeout as u64));
                 }
             }
+            "total_connect_timeout" => {
+                let timeout = value.parse::<i64>().map_err(|_| {
+                    Error::config_parse(Box::new(InvalidValue("total_connect_timeout")))
+                })?;
+                if timeout > 0 {
+                    self.total_connect_timeout(Duration::from_secs(timeout as u64));
+                }
+            }
+            "connect_retries" => {
+                let retries = value
+                    .parse::<u32>()
+                    .map_err(|_| Error::config_parse(Box::new(InvalidValue("connect_retries"))))?;
+                self.connect_retries(retries);
+            }
             "tcp_user_timeout" => {
                 let timeout = value
                     .parse::<i64>()
@@ -712,6 +1710,12 @@ impl Config {
                 };
                 self.load_balance_hosts(load_balance_hosts);
             }
+            "service" => {
+                self.service(value);
+            }
+            "servicefile" => {
+                self.servicefile(value);
+            }
             key => {
                 return Err(Error::config_parse(Box::new(UnknownOption(
                     key.to_string(),
@@ -735,7 +1739,7 @@ impl Config {
 
     /// Connects to a PostgreSQL database over an arbitrary stream.
     ///
-    /// All of the settings other than `user`, `password`, `dbname`, `options`, and `application_name` name are ignored.
+    /// All of the settings other than `user`, `password`, `dbname`, `options`, and `application_name` are ignored.
     pub async fn connect_raw<S, T>(
         &self,
         stream: S,
@@ -745,10 +1749,296 @@ impl Config {
         S: AsyncRead + AsyncWrite + Unpin,
         T: TlsConnect<S>,
     {
-        connect_raw(stream, tls, true, self).await
+        let config = service::resolve(self).await?;
+        let namespace = config.dbname.as_deref().or(config.user.as_deref());
+        let mut span = OperationSpan::connect(namespace.unwrap_or(""), None);
+
+        let result = span
+            .instrument(connect_raw(stream, tls, true, &config))
+            .await;
+        span.record(&result);
+        result
+    }
+
+    /// Formats the configuration as a key-value connection string.
+    ///
+    /// Only settings which have been configured are included, and settings which cannot be expressed in a
+    /// connection string, such as the DNS resolver, are omitted. Durations are rounded up to whole seconds.
+    ///
+    /// The output includes the password and `sslpassword`, so it should be treated as a secret. The
+    /// [`Display`](fmt::Display) implementation produces the same string without them, and is suitable for logging.
+    pub fn to_connection_string(&self) -> String {
+        self.params(true)
+            .into_iter()
+            .map(|(key, value)| format!("{}={}", key, quote(&value)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Formats the configuration as a `postgresql://` URL.
+    ///
+    /// The same settings are included as in [`to_connection_string`](Config::to_connection_string), including the
+    /// password.
+    pub fn to_url(&self) -> String {
+        let mut url = "postgresql://".to_string();
+
+        if let Some(user) = &self.user {
+            url.push_str(&utf8_percent_encode(user, URL_ENCODE_SET).to_string());
+            if let Some(password) = &self.password {
+                url.push(':');
+                url.push_str(&percent_encode(password, URL_ENCODE_SET).to_string());
+            }
+            url.push('@');
+        }
+
+        // The parser assigns a port to each host in the authority, so the hosts can only go there if the ports line up.
+        let hosts_in_authority =
+            !self.host.is_empty() && (self.port.len() <= 1 || self.port.len() == self.host.len());
+        if hosts_in_authority {
+            for (i, host) in self.host.iter().enumerate() {
+                if i > 0 {
+                    url.push(',');
+                }
+                match host {
+                    Host::Tcp(host) if host.contains(':') => {
+                        url.push('[');
+                        url.push_str(host);
+                        url.push(']');
+                    }
+                    Host::Tcp(host) => {
+                        url.push_str(&utf8_percent_encode(host, URL_ENCODE_SET).to_string())
+                    }
+                    #[cfg(unix)]
+                    Host::Unix(path) => url.push_str(
+                        &percent_encode(path.as_os_str().as_bytes(), URL_ENCODE_SET).to_string(),
+                    ),
+                }
+                if let Some(port) = self.port.get(i).or_else(|| self.port.first()) {
+                    url.push_str(&format!(":{port}"));
+                }
+            }
+        }
+
+        if let Some(dbname) = &self.dbname {
+            url.push('/');
+            url.push_str(&utf8_percent_encode(dbname, URL_ENCODE_SET).to_string());
+        }
+
+        let mut params = vec![];
+        if !hosts_in_authority {
+            params.extend(self.host.iter().map(|host| ("host", host_str(host))));
+        }
+        params.extend(self.params(true).into_iter().filter(|(key, _)| match *key {
+            "user" | "dbname" | "host" => false,
+            "password" => self.user.is_none(),
+            "port" => !hosts_in_authority,
+            _ => true,
+        }));
+        for (i, (key, value)) in params.iter().enumerate() {
+            url.push(if i == 0 { '?' } else { '&' });
+            url.push_str(key);
+            url.push('=');
+            url.push_str(&utf8_percent_encode(value, URL_ENCODE_SET).to_string());
+        }
+
+        url
+    }
+
+    /// Returns the connection string keys and values of the settings which differ from their defaults.
+    fn params(&self, include_password: bool) -> Vec<(&'static str, String)> {
+        fn join<T: ToString>(values: &[T]) -> String {
+            values
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        }
+
+        fn secs(duration: &Duration) -> String {
+            (duration.as_secs() + u64::from(duration.subsec_nanos() > 0)).to_string()
+        }
+
+        let mut params = vec![];
+        if let Some(user) = &self.user {
+            params.push(("user", user.clone()));
+        }
+        if let Some(password) = &self.password {
+            if include_password {
+                params.push(("password", String::from_utf8_lossy(password).into_owned()));
+            }
+        }
+        if let Some(passfile) = &self.passfile {
+            params.push(("passfile", passfile.to_string_lossy().into_owned()));
+        }
+        if let Some(dbname) = &self.dbname {
+            params.push(("dbname", dbname.clone()));
+        }
+        if let Some(options) = &self.options {
+            params.push(("options", options.clone()));
+        }
+        if let Some(application_name) = &self.application_name {
+            params.push(("application_name", application_name.clone()));
+        }
+        if !self.is_unset("sslmode") {
+            let ssl_mode = match self.ssl_mode {
+                SslMode::Disable => "disable",
+                SslMode::Prefer => "prefer",
+                SslMode::Require => "require",
+                SslMode::VerifyCa => "verify-ca",
+                SslMode::VerifyFull => "verify-full",
+            };
+            params.push(("sslmode", ssl_mode.to_string()));
+        }
+        if let Some(ssl_root_cert) = &self.ssl_root_cert {
+            params.push(("sslrootcert", ssl_root_cert.to_string_lossy().into_owned()));
+        }
+        if let Some(ssl_cert) = &self.ssl_cert {
+            params.push(("sslcert", ssl_cert.to_string_lossy().into_owned()));
+        }
+        if let Some(ssl_key) = &self.ssl_key {
+            params.push(("sslkey", ssl_key.to_string_lossy().into_owned()));
+        }
+        if let Some(ssl_password) = &self.ssl_password {
+            if include_password {
+                params.push((
+                    "sslpassword",
+                    String::from_utf8_lossy(ssl_password).into_owned(),
+                ));
+            }
+        }
+        if !self.is_unset("sslnegotiation") {
+            let ssl_negotiation = match self.ssl_negotiation {
+                SslNegotiation::Postgres => "postgres",
+                SslNegotiation::Direct => "direct",
+            };
+            params.push(("sslnegotiation", ssl_negotiation.to_string()));
+        }
+        if !self.host.is_empty() {
+            let hosts = self.host.iter().map(host_str).collect::<Vec<_>>();
+            params.push(("host", hosts.join(",")));
+        }
+        if !self.hostaddr.is_empty() {
+            params.push(("hostaddr", join(&self.hostaddr)));
+        }
+        if !self.port.is_empty() {
+            params.push(("port", join(&self.port)));
+        }
+        if let Some(connect_timeout) = &self.connect_timeout {
+            params.push(("connect_timeout", secs(connect_timeout)));
+        }
+        if let Some(total_connect_timeout) = &self.total_connect_timeout {
+            params.push(("total_connect_timeout", secs(total_connect_timeout)));
+        }
+        if !self.is_unset("connect_retries") {
+            params.push(("connect_retries", self.connect_retries.to_string()));
+        }
+        if let Some(tcp_user_timeout) = &self.tcp_user_timeout {
+            params.push(("tcp_user_timeout", secs(tcp_user_timeout)));
+        }
+        if !self.keepalives {
+            params.push(("keepalives", "0".to_string()));
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if !self.is_unset("keepalives_idle") {
+                params.push(("keepalives_idle", secs(&self.keepalive_config.idle)));
+            }
+            if let Some(keepalives_interval) = &self.keepalive_config.interval {
+                params.push(("keepalives_interval", secs(keepalives_interval)));
+            }
+            if let Some(keepalives_retries) = self.keepalive_config.retries {
+                params.push(("keepalives_retries", keepalives_retries.to_string()));
+            }
+        }
+        if !self.is_unset("target_session_attrs") {
+            let target_session_attrs = match self.target_session_attrs {
+                TargetSessionAttrs::Any => "any",
+                TargetSessionAttrs::ReadWrite => "read-write",
+                TargetSessionAttrs::ReadOnly => "read-only",
+            };
+            params.push(("target_session_attrs", target_session_attrs.to_string()));
+        }
+        if !self.is_unset("channel_binding") {
+            let channel_binding = match self.channel_binding {
+                ChannelBinding::Disable => "disable",
+                ChannelBinding::Prefer => "prefer",
+                ChannelBinding::Require => "require",
+            };
+            params.push(("channel_binding", channel_binding.to_string()));
+        }
+        if !self.is_unset("load_balance_hosts") {
+            let load_balance_hosts = match self.load_balance_hosts {
+                LoadBalanceHosts::Disable => "disable",
+                LoadBalanceHosts::Random => "random",
+            };
+            params.push(("load_balance_hosts", load_balance_hosts.to_string()));
+        }
+        if let Some(service) = &self.service {
+            params.push(("service", service.clone()));
+        }
+        if let Some(servicefile) = &self.servicefile {
+            params.push(("servicefile", servicefile.to_string_lossy().into_owned()));
+        }
+
+        params
+    }
+}
+
+/// Characters which are percent-encoded in the components of a URL.
+const URL_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+fn host_str(host: &Host) -> String {
+    match host {
+        Host::Tcp(host) => host.clone(),
+        #[cfg(unix)]
+        Host::Unix(path) => path.to_string_lossy().into_owned(),
+    }
+}
+
+/// Quotes a value in a key-value connection string if necessary.
+fn quote(value: &str) -> Cow<'_, str> {
+    if !value.is_empty() && !value.contains(|c: char| c.is_whitespace() || c == '\'' || c == '\\') {
+        return Cow::Borrowed(value);
+    }
+
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('\'');
+    for c in value.chars() {
+        if c == '\'' || c == '\\' {
+            quoted.push('\\');
+        }
+        quoted.push(c);
     }
+    quoted.push('\'');
+    Cow::Owned(quoted)
 }
 
+/// The libpq environment variables and their connection string keys.
+const ENV_VARS: &[(&str, &str)] = &[
+    ("PGHOST", "host"),
+    ("PGHOSTADDR", "hostaddr"),
+    ("PGPORT", "port"),
+    ("PGDATABASE", "dbname"),
+    ("PGUSER", "user"),
+    ("PGPASSWORD", "password"),
+    ("PGOPTIONS", "options"),
+    ("PGAPPNAME", "application_name"),
+    ("PGSSLMODE", "sslmode"),
+    ("PGSSLROOTCERT", "sslrootcert"),
+    ("PGSSLCERT", "sslcert"),
+    ("PGSSLKEY", "sslkey"),
+    ("PGSSLNEGOTIATION", "sslnegotiation"),
+    ("PGCONNECT_TIMEOUT", "connect_timeout"),
+    ("PGTARGETSESSIONATTRS", "target_session_attrs"),
+    ("PGCHANNELBINDING", "channel_binding"),
+    ("PGLOADBALANCEHOSTS", "load_balance_hosts"),
+    ("PGSERVICE", "service"),
+];
+
 impl FromStr for Config {
     type Err = Error;
 
@@ -774,14 +2064,25 @@ impl fmt::Debug for Config {
         config_dbg = config_dbg
             .field("user", &self.user)
             .field("password", &self.password.as_ref().map(|_| Redaction {}))
+            .field("passfile", &self.passfile)
             .field("dbname", &self.dbname)
             .field("options", &self.options)
             .field("application_name", &self.application_name)
             .field("ssl_mode", &self.ssl_mode)
+            .field("ssl_root_cert", &self.ssl_root_cert)
+            .field("ssl_cert", &self.ssl_cert)
+            .field("ssl_key", &self.ssl_key)
+            .field(
+                "ssl_password",
+                &self.ssl_password.as_ref().map(|_| Redaction {}),
+            )
             .field("host", &self.host)
             .field("hostaddr", &self.hostaddr)
             .field("port", &self.port)
             .field("connect_timeout", &self.connect_timeout)
+            .field("total_connect_timeout", &self.total_connect_timeout)
+            .field("connect_retries", &self.connect_retries)
+            .field("connect_retry_backoff", &self.connect_retry_backoff)
             .field("tcp_user_timeout", &self.tcp_user_timeout)
             .field("keepalives", &self.keepalives);
 
@@ -793,11 +2094,61 @@ impl fmt::Debug for Config {
                 .field("keepalives_retries", &self.keepalive_config.retries);
         }
 
-        config_dbg
+        config_dbg = config_dbg
             .field("target_session_attrs", &self.target_session_attrs)
             .field("channel_binding", &self.channel_binding)
             .field("load_balance_hosts", &self.load_balance_hosts)
-            .finish()
+            .field("query_in_errors", &self.query_in_errors)
+            .field("params_in_errors", &self.params_in_errors)
+            .field("trace_query_text", &self.trace_query_text)
+            .field(
+                "param_redactor",
+                &self.param_redactor.as_ref().map(|_| Redaction {}),
+            )
+            .field(
+                "notice_handler",
+                &self.notice_handler.as_ref().map(|_| Redaction {}),
+            )
+            .field("metrics", &self.metrics.as_ref().map(|_| Redaction {}))
+            .field("slow_query_threshold", &self.get_slow_query_threshold())
+            .field(
+                "sql_commenter",
+                &self.sql_commenter.as_ref().map(|_| Redaction {}),
+            )
+            .field("service", &self.service)
+            .field("servicefile", &self.servicefile);
+
+        #[cfg(feature = "runtime")]
+        {
+            config_dbg = config_dbg
+                .field(
+                    "dns_resolver",
+                    &self.dns_resolver.as_ref().map(|_| Redaction {}),
+                )
+                .field("proxy", &self.proxy)
+                .field(
+                    "socket_callback",
+                    &self.socket_callback.as_ref().map(|_| Redaction {}),
+                )
+                .field("runtime", &self.runtime.as_ref().map(|_| Redaction {}));
+        }
+
+        config_dbg.finish()
+    }
+}
+
+/// Formats the configuration as a key-value connection string, omitting the password and `sslpassword`.
+///
+/// See [`Config::to_connection_string`] for details.
+impl fmt::Display for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (key, value)) in self.params(false).iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{}={}", key, quote(value))?;
+        }
+        Ok(())
     }
 }
 
@@ -1169,8 +2520,12 @@ impl<'a> UrlParser<'a> {
 #[cfg(test)]
 mod tests {
     use std::net::IpAddr;
+    use std::time::Duration;
 
-    use crate::{Config, config::Host};
+    use crate::{
+        Config,
+        config::{Host, SslMode},
+    };
 
     #[test]
     fn test_simple_parsing() {
@@ -1197,6 +2552,42 @@ mod tests {
         assert_eq!(1, 1);
     }
 
+    #[test]
+    fn test_apply_env() {
+        let env = |name: &str| match name {
+            "PGHOST" => Some("envhost".to_string()),
+            "PGPORT" => Some("5433".to_string()),
+            "PGUSER" => Some("envuser".to_string()),
+            "PGSSLMODE" => Some("require".to_string()),
+            "PGCONNECT_TIMEOUT" => Some("10".to_string()),
+            _ => None,
+        };
+
+        let mut config = Config::new();
+        config.apply_env_with(env).unwrap();
+        assert_eq!([Host::Tcp("envhost".to_string())], config.get_hosts());
+        assert_eq!([5433], config.get_ports());
+        assert_eq!(Some("envuser"), config.get_user());
+        assert_eq!(SslMode::Require, config.get_ssl_mode());
+        assert_eq!(Some(&Duration::from_secs(10)), config.get_connect_timeout());
+
+        // explicitly configured settings take precedence
+        let mut config = "host=localhost user=postgres".parse::<Config>().unwrap();
+        config.apply_env_with(env).unwrap();
+        assert_eq!([Host::Tcp("localhost".to_string())], config.get_hosts());
+        assert_eq!([5433], config.get_ports());
+        assert_eq!(Some("postgres"), config.get_user());
+
+        // even when they are set to their default values
+        let mut config = "sslmode=prefer".parse::<Config>().unwrap();
+        config.apply_env_with(env).unwrap();
+        assert_eq!(SslMode::Prefer, config.get_ssl_mode());
+
+        Config::new()
+            .apply_env_with(|name| (name == "PGSSLMODE").then(|| "bogus".to_string()))
+            .unwrap_err();
+    }
+
     #[test]
     fn test_invalid_hostaddr_parsing() {
         let s = "user=pass_user dbname=postgres host=host1 hostaddr=127.0.0 port=26257";
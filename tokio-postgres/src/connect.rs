@@ -1,11 +1,16 @@
 use crate::client::{Addr, SocketConfig};
-use crate::config::{Host, LoadBalanceHosts, TargetSessionAttrs};
+use crate::config::{DEFAULT_KEEPALIVE_IDLE, Host, LoadBalanceHosts, TargetSessionAttrs};
 use crate::connect_raw::connect_raw;
 use crate::connect_socket::connect_socket;
+use crate::instrument::OperationSpan;
+use crate::pgpass::PassFile;
 use crate::tls::MakeTlsConnect;
 use crate::{Client, Config, Connection, Error, SimpleQueryMessage, Socket};
+use crate::{rt, service, simple_query};
 use futures_util::{FutureExt, Stream};
+use log::debug;
 use rand::seq::SliceRandom;
+use std::borrow::Cow;
 use std::future::{self, Future};
 use std::pin::pin;
 use std::task::Poll;
@@ -13,6 +18,49 @@ use std::{cmp, io};
 use tokio::net;
 
 pub async fn connect<T>(
+    tls: T,
+    config: &Config,
+) -> Result<(Client, Connection<Socket, T::Stream>), Error>
+where
+    T: MakeTlsConnect<Socket>,
+{
+    let config = &*service::resolve(config).await?;
+
+    match config.total_connect_timeout {
+        Some(timeout) => rt::timeout(config.get_runtime(), timeout, connect_hosts(tls, config))
+            .await
+            .unwrap_or_else(|| {
+                Err(Error::connect(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "total connection timeout elapsed",
+                )))
+            }),
+        None => connect_hosts(tls, config).await,
+    }
+}
+
+// Streams opened by a custom runtime are opaque, so socket-level options can't be applied to them.
+fn check_runtime_options(config: &Config) -> Result<(), Error> {
+    let option = if config.socket_callback.is_some() {
+        "socket_callback"
+    } else if config.tcp_user_timeout.is_some() {
+        "tcp_user_timeout"
+    } else if config.keepalive_config.idle != DEFAULT_KEEPALIVE_IDLE {
+        "keepalives_idle"
+    } else if config.keepalive_config.interval.is_some() {
+        "keepalives_interval"
+    } else if config.keepalive_config.retries.is_some() {
+        "keepalives_retries"
+    } else {
+        return Ok(());
+    };
+
+    Err(Error::config(
+        format!("{option} cannot be used with a custom runtime").into(),
+    ))
+}
+
+async fn connect_hosts<T>(
     mut tls: T,
     config: &Config,
 ) -> Result<(Client, Connection<Socket, T::Stream>), Error>
@@ -44,11 +92,21 @@ where
         return Err(Error::config("invalid number of ports".into()));
     }
 
+    if config.runtime.is_some() {
+        check_runtime_options(config)?;
+    }
+
     let mut indices = (0..num_hosts).collect::<Vec<_>>();
     if config.load_balance_hosts == LoadBalanceHosts::Random {
         indices.shuffle(&mut rand::rng());
     }
 
+    // The password file is read once for all hosts.
+    let passfile = match config.password {
+        Some(_) => None,
+        None => PassFile::load(config).await,
+    };
+
     let mut error = None;
     for i in indices {
         let host = config.host.get(i);
@@ -76,9 +134,39 @@ where
             None => host.cloned().unwrap(),
         };
 
-        match connect_host(addr, hostname, port, &mut tls, config).await {
-            Ok((client, connection)) => return Ok((client, connection)),
-            Err(e) => error = Some(e),
+        let config = match &config.password {
+            Some(_) => Cow::Borrowed(config),
+            None => match passfile
+                .as_ref()
+                .and_then(|passfile| passfile.password(config, host, hostaddr, port))
+            {
+                Some(password) => {
+                    let mut config = config.clone();
+                    config.password = Some(password);
+                    Cow::Owned(config)
+                }
+                None => Cow::Borrowed(config),
+            },
+        };
+
+        let mut backoff = config.connect_retry_backoff;
+        for attempt in 0..=config.connect_retries {
+            if attempt > 0 {
+                debug!("retrying connection to host {} in {:?}", i, backoff);
+                rt::sleep(config.get_runtime(), backoff).await;
+                backoff = backoff.saturating_mul(2);
+            }
+
+            match connect_host(addr.clone(), hostname.clone(), port, &mut tls, &config).await {
+                Ok((client, connection)) => return Ok((client, connection)),
+                Err(e) => {
+                    let retryable = e.is_connect_retryable();
+                    error = Some(e);
+                    if !retryable {
+                        break;
+                    }
+                }
+            }
         }
     }
 
@@ -97,10 +185,19 @@ where
 {
     match host {
         Host::Tcp(host) => {
-            let mut addrs = net::lookup_host((&*host, port))
-                .await
-                .map_err(Error::connect)?
-                .collect::<Vec<_>>();
+            if let Some(proxy) = &config.proxy {
+                let addr = Addr::Proxy(proxy.clone(), host);
+                return connect_once(addr, hostname.as_deref(), port, tls, config).await;
+            }
+
+            let mut addrs = match (&config.dns_resolver, config.get_runtime()) {
+                (Some(resolver), _) => resolver.0.resolve(&host, port).await,
+                (None, Some(runtime)) => runtime.resolve(&host, port).await,
+                (None, None) => net::lookup_host((&*host, port))
+                    .await
+                    .map(|addrs| addrs.collect()),
+            }
+            .map_err(Error::connect)?;
 
             if config.load_balance_hosts == LoadBalanceHosts::Random {
                 addrs.shuffle(&mut rand::rng());
@@ -140,6 +237,38 @@ async fn connect_once<T>(
     tls: &mut T,
     config: &Config,
 ) -> Result<(Client, Connection<Socket, T::Stream>), Error>
+where
+    T: MakeTlsConnect<Socket>,
+{
+    let (address, server_port) = match (&addr, hostname) {
+        #[cfg(unix)]
+        (Addr::Unix(path), None) => (path.display().to_string(), None),
+        #[cfg(unix)]
+        (Addr::Unix(_), Some(hostname)) => (hostname.to_string(), None),
+        (_, Some(hostname)) => (hostname.to_string(), Some(port)),
+        (Addr::Tcp(ip), None) => (ip.to_string(), Some(port)),
+        (Addr::Proxy(_, host), None) => (host.clone(), Some(port)),
+    };
+    let namespace = config.dbname.as_deref().or(config.user.as_deref());
+    let mut span = OperationSpan::connect(namespace.unwrap_or(""), Some((&address, server_port)));
+
+    let result = span
+        .instrument(connect_once_inner(addr, hostname, port, tls, config))
+        .await;
+    span.record(&result);
+    let (client, connection) = result?;
+    client.inner().attributes().set_server(address, server_port);
+
+    Ok((client, connection))
+}
+
+async fn connect_once_inner<T>(
+    addr: Addr,
+    hostname: Option<&str>,
+    port: u16,
+    tls: &mut T,
+    config: &Config,
+) -> Result<(Client, Connection<Socket, T::Stream>), Error>
 where
     T: MakeTlsConnect<Socket>,
 {
@@ -153,6 +282,8 @@ where
         } else {
             None
         },
+        config.socket_callback.as_ref(),
+        config.get_runtime(),
     )
     .await?;
 
@@ -163,7 +294,11 @@ where
     let (mut client, mut connection) = connect_raw(socket, tls, has_hostname, config).await?;
 
     if config.target_session_attrs != TargetSessionAttrs::Any {
-        let mut rows = pin!(client.simple_query_raw("SHOW transaction_read_only"));
+        let mut rows = pin!(simple_query::simple_query(
+            client.inner(),
+            "SHOW transaction_read_only",
+            false
+        ));
 
         let mut rows = pin!(
             future::poll_fn(|cx| {
@@ -223,6 +358,8 @@ where
         } else {
             None
         },
+        socket_callback: config.socket_callback.clone(),
+        runtime: config.runtime.clone(),
     });
 
     Ok((client, connection))
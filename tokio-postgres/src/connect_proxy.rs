@@ -0,0 +1,192 @@
+use crate::config::{Proxy, ProxyKind};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use std::io;
+use std::net::IpAddr;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+const MAX_HTTP_RESPONSE_LEN: usize = 8192;
+
+pub(crate) async fn connect_proxy(proxy: &Proxy, host: &str, port: u16) -> io::Result<TcpStream> {
+    let mut stream = TcpStream::connect((&*proxy.host, proxy.port)).await?;
+    handshake(&mut stream, proxy, host, port).await?;
+    Ok(stream)
+}
+
+/// Asks a proxy to open a tunnel to the target host over an established connection to it.
+pub(crate) async fn handshake<S>(
+    stream: &mut S,
+    proxy: &Proxy,
+    host: &str,
+    port: u16,
+) -> io::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    match proxy.kind {
+        ProxyKind::Socks5 => socks5(stream, proxy, host, port).await,
+        ProxyKind::HttpConnect => http_connect(stream, proxy, host, port).await,
+    }
+}
+
+async fn socks5<S>(stream: &mut S, proxy: &Proxy, host: &str, port: u16) -> io::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    // RFC 1928 method negotiation, offering username/password authentication (RFC 1929) if we have credentials
+    let methods: &[u8] = match proxy.credentials {
+        Some(_) => &[0x00, 0x02],
+        None => &[0x00],
+    };
+    let mut buf = vec![0x05, methods.len() as u8];
+    buf.extend_from_slice(methods);
+    stream.write_all(&buf).await?;
+
+    let mut reply = [0; 2];
+    stream.read_exact(&mut reply).await?;
+    if reply[0] != 0x05 {
+        return Err(proxy_error("invalid SOCKS5 server response"));
+    }
+
+    match (reply[1], &proxy.credentials) {
+        (0x00, _) => {}
+        (0x02, Some((username, password))) => {
+            if username.len() > 255 || password.len() > 255 {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "SOCKS5 username and password must be at most 255 bytes",
+                ));
+            }
+
+            let mut buf = vec![0x01, username.len() as u8];
+            buf.extend_from_slice(username.as_bytes());
+            buf.push(password.len() as u8);
+            buf.extend_from_slice(password.as_bytes());
+            stream.write_all(&buf).await?;
+
+            let mut reply = [0; 2];
+            stream.read_exact(&mut reply).await?;
+            if reply[1] != 0x00 {
+                return Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    "SOCKS5 proxy authentication failed",
+                ));
+            }
+        }
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "SOCKS5 proxy requires an unsupported authentication method",
+            ));
+        }
+    }
+
+    let mut buf = vec![0x05, 0x01, 0x00];
+    match host.parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => {
+            buf.push(0x01);
+            buf.extend_from_slice(&ip.octets());
+        }
+        Ok(IpAddr::V6(ip)) => {
+            buf.push(0x04);
+            buf.extend_from_slice(&ip.octets());
+        }
+        Err(_) => {
+            if host.len() > 255 {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "hostname is too long for a SOCKS5 proxy",
+                ));
+            }
+            buf.push(0x03);
+            buf.push(host.len() as u8);
+            buf.extend_from_slice(host.as_bytes());
+        }
+    }
+    buf.extend_from_slice(&port.to_be_bytes());
+    stream.write_all(&buf).await?;
+
+    let mut reply = [0; 4];
+    stream.read_exact(&mut reply).await?;
+    if reply[0] != 0x05 {
+        return Err(proxy_error("invalid SOCKS5 server response"));
+    }
+    if reply[1] != 0x00 {
+        let msg = match reply[1] {
+            0x01 => "general SOCKS server failure",
+            0x02 => "connection not allowed by ruleset",
+            0x03 => "network unreachable",
+            0x04 => "host unreachable",
+            0x05 => "connection refused",
+            0x06 => "TTL expired",
+            0x07 => "command not supported",
+            0x08 => "address type not supported",
+            _ => "unknown error",
+        };
+        return Err(proxy_error(&format!("SOCKS5 proxy error: {msg}")));
+    }
+
+    // skip the bound address, which we have no use for
+    let len = match reply[3] {
+        0x01 => 4,
+        0x04 => 16,
+        0x03 => stream.read_u8().await? as usize,
+        _ => return Err(proxy_error("invalid SOCKS5 server response")),
+    };
+    let mut bound = vec![0; len + 2];
+    stream.read_exact(&mut bound).await?;
+
+    Ok(())
+}
+
+async fn http_connect<S>(stream: &mut S, proxy: &Proxy, host: &str, port: u16) -> io::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let authority = match host.parse::<IpAddr>() {
+        Ok(IpAddr::V6(ip)) => format!("[{ip}]:{port}"),
+        _ => format!("{host}:{port}"),
+    };
+
+    let mut request = format!("CONNECT {authority} HTTP/1.1\r\nHost: {authority}\r\n");
+    if let Some((username, password)) = &proxy.credentials {
+        let token = STANDARD.encode(format!("{username}:{password}"));
+        request.push_str(&format!("Proxy-Authorization: Basic {token}\r\n"));
+    }
+    request.push_str("\r\n");
+    stream.write_all(request.as_bytes()).await?;
+
+    // read the response a byte at a time so we don't consume anything sent by the server after it
+    let mut response = vec![];
+    while !response.ends_with(b"\r\n\r\n") {
+        if response.len() >= MAX_HTTP_RESPONSE_LEN {
+            return Err(proxy_error("HTTP proxy response is too long"));
+        }
+        response.push(stream.read_u8().await?);
+    }
+
+    let status_line = response
+        .split(|&b| b == b'\r')
+        .next()
+        .and_then(|line| std::str::from_utf8(line).ok())
+        .unwrap_or("");
+    let mut parts = status_line.splitn(3, ' ');
+    match (parts.next(), parts.next()) {
+        (Some(version), Some(status)) if version.starts_with("HTTP/1.") => {
+            if !status.starts_with('2') {
+                return Err(proxy_error(&format!(
+                    "HTTP proxy error: {}",
+                    &status_line[version.len() + 1..]
+                )));
+            }
+        }
+        _ => return Err(proxy_error("invalid HTTP proxy response")),
+    }
+
+    Ok(())
+}
+
+fn proxy_error(msg: &str) -> io::Error {
+    io::Error::other(msg.to_string())
+}
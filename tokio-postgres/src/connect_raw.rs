@@ -1,6 +1,8 @@
+use crate::client::SessionState;
 use crate::codec::{BackendMessage, BackendMessages, FrontendMessage, PostgresCodec};
 use crate::config::{self, Config};
-use crate::connect_tls::connect_tls;
+use crate::connect_tls::{TlsConfig, connect_tls};
+use crate::instrument::ClientAttributes;
 use crate::maybe_tls_stream::MaybeTlsStream;
 use crate::tls::{TlsConnect, TlsStream};
 use crate::{Client, Connection, Error};
@@ -17,6 +19,7 @@ use std::borrow::Cow;
 use std::collections::{HashMap, VecDeque};
 use std::io;
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::{Context, Poll, ready};
 use tokio::io::{AsyncRead, AsyncWrite};
 use tokio_util::codec::Framed;
@@ -89,17 +92,18 @@ where
     S: AsyncRead + AsyncWrite + Unpin,
     T: TlsConnect<S>,
 {
-    let stream = connect_tls(
-        stream,
-        config.ssl_mode,
-        config.ssl_negotiation,
-        tls,
-        has_hostname,
-    )
-    .await?;
+    let tls_config = TlsConfig::new(config);
+    let stream = connect_tls(stream, &tls_config, tls, has_hostname).await?;
+    let tls_info = match &stream {
+        MaybeTlsStream::Raw(_) => None,
+        MaybeTlsStream::Tls(s) => Some(s.tls_info()),
+    };
 
     let mut stream = StartupStream {
-        inner: Framed::new(stream, PostgresCodec),
+        inner: Framed::new(
+            stream,
+            PostgresCodec::new(config.metrics.as_ref().map(|m| m.0.clone())),
+        ),
         buf: BackendMessages::empty(),
         delayed: VecDeque::new(),
     };
@@ -114,14 +118,28 @@ where
     let (process_id, secret_key, parameters) = read_info(&mut stream).await?;
 
     let (sender, receiver) = mpsc::unbounded();
-    let client = Client::new(
+    let session = Arc::new(SessionState::new(parameters.clone()));
+    let mut client = Client::new(
         sender,
-        config.ssl_mode,
-        config.ssl_negotiation,
+        tls_config,
         process_id,
         secret_key,
+        session.clone(),
+        config,
+        ClientAttributes::new(config.dbname.as_deref().unwrap_or(&user)),
+    );
+    if let Some(version) = parameters.get("server_version") {
+        client.set_server_version(version);
+    }
+    client.set_tls_info(tls_info);
+    let connection = Connection::new(
+        stream.inner,
+        stream.delayed,
+        parameters,
+        session,
+        receiver,
+        config.notice_handler.as_ref().map(|h| h.0.clone()),
     );
-    let connection = Connection::new(stream.inner, stream.delayed, parameters, receiver);
 
     Ok((client, connection))
 }
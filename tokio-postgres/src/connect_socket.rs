@@ -1,66 +1,138 @@
 use crate::client::Addr;
+use crate::config::{Shared, SocketCallback, SocketRef};
+use crate::connect_proxy::{connect_proxy, handshake};
 use crate::keepalive::KeepaliveConfig;
+use crate::rt::{self, Runtime, RuntimeStream};
 use crate::{Error, Socket};
 use socket2::{SockRef, TcpKeepalive};
 use std::future::Future;
 use std::io;
+use std::net::SocketAddr;
 use std::time::Duration;
 use tokio::net::TcpStream;
 #[cfg(unix)]
 use tokio::net::UnixStream;
-use tokio::time;
 
 pub(crate) async fn connect_socket(
     addr: &Addr,
     port: u16,
     connect_timeout: Option<Duration>,
-    #[cfg_attr(not(target_os = "linux"), allow(unused_variables))] tcp_user_timeout: Option<
-        Duration,
-    >,
+    tcp_user_timeout: Option<Duration>,
     keepalive_config: Option<&KeepaliveConfig>,
+    socket_callback: Option<&Shared<SocketCallback>>,
+    runtime: Option<&dyn Runtime>,
 ) -> Result<Socket, Error> {
-    match addr {
-        Addr::Tcp(ip) => {
-            let stream =
-                connect_with_timeout(TcpStream::connect((*ip, port)), connect_timeout).await?;
+    if let Some(runtime) = runtime {
+        let stream = connect_with_timeout(
+            Some(runtime),
+            connect_runtime(runtime, addr, port),
+            connect_timeout,
+        )
+        .await?;
+        return Ok(Socket::new_custom(stream));
+    }
 
-            stream.set_nodelay(true).map_err(Error::connect)?;
+    let stream = match addr {
+        Addr::Tcp(ip) => {
+            connect_with_timeout(None, TcpStream::connect((*ip, port)), connect_timeout).await?
+        }
+        Addr::Proxy(proxy, host) => {
+            connect_with_timeout(None, connect_proxy(proxy, host, port), connect_timeout).await?
+        }
+        #[cfg(unix)]
+        Addr::Unix(dir) => {
+            let path = dir.join(format!(".s.PGSQL.{port}"));
+            let socket =
+                connect_with_timeout(None, UnixStream::connect(path), connect_timeout).await?;
+            if let Some(callback) = socket_callback {
+                (callback.0)(SocketRef::Unix(&socket)).map_err(Error::connect)?;
+            }
+            return Ok(Socket::new_unix(socket));
+        }
+    };
 
-            let sock_ref = SockRef::from(&stream);
+    configure_tcp(&stream, tcp_user_timeout, keepalive_config)?;
+    if let Some(callback) = socket_callback {
+        (callback.0)(SocketRef::Tcp(&stream)).map_err(Error::connect)?;
+    }
 
-            #[cfg(target_os = "linux")]
-            if let Some(tcp_user_timeout) = tcp_user_timeout {
-                sock_ref
-                    .set_tcp_user_timeout(Some(tcp_user_timeout))
-                    .map_err(Error::connect)?;
-            }
+    Ok(Socket::new_tcp(stream))
+}
 
-            if let Some(keepalive_config) = keepalive_config {
-                sock_ref
-                    .set_tcp_keepalive(&TcpKeepalive::from(keepalive_config))
-                    .map_err(Error::connect)?;
+async fn connect_runtime(
+    runtime: &dyn Runtime,
+    addr: &Addr,
+    port: u16,
+) -> io::Result<Box<dyn RuntimeStream>> {
+    match addr {
+        Addr::Tcp(ip) => runtime.connect_tcp(SocketAddr::new(*ip, port)).await,
+        Addr::Proxy(proxy, host) => {
+            let mut last_err = None;
+            for addr in runtime.resolve(&proxy.host, proxy.port).await? {
+                match runtime.connect_tcp(addr).await {
+                    Ok(mut stream) => {
+                        handshake(&mut stream, proxy, host, port).await?;
+                        return Ok(stream);
+                    }
+                    Err(e) => last_err = Some(e),
+                }
             }
 
-            Ok(Socket::new_tcp(stream))
+            Err(last_err.unwrap_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "could not resolve any addresses",
+                )
+            }))
         }
         #[cfg(unix)]
         Addr::Unix(dir) => {
             let path = dir.join(format!(".s.PGSQL.{port}"));
-            let socket = connect_with_timeout(UnixStream::connect(path), connect_timeout).await?;
-            Ok(Socket::new_unix(socket))
+            runtime.connect_unix(&path).await
         }
     }
 }
 
-async fn connect_with_timeout<F, T>(connect: F, timeout: Option<Duration>) -> Result<T, Error>
+fn configure_tcp(
+    stream: &TcpStream,
+    #[cfg_attr(not(target_os = "linux"), allow(unused_variables))] tcp_user_timeout: Option<
+        Duration,
+    >,
+    keepalive_config: Option<&KeepaliveConfig>,
+) -> Result<(), Error> {
+    stream.set_nodelay(true).map_err(Error::connect)?;
+
+    let sock_ref = SockRef::from(stream);
+
+    #[cfg(target_os = "linux")]
+    if let Some(tcp_user_timeout) = tcp_user_timeout {
+        sock_ref
+            .set_tcp_user_timeout(Some(tcp_user_timeout))
+            .map_err(Error::connect)?;
+    }
+
+    if let Some(keepalive_config) = keepalive_config {
+        sock_ref
+            .set_tcp_keepalive(&TcpKeepalive::from(keepalive_config))
+            .map_err(Error::connect)?;
+    }
+
+    Ok(())
+}
+
+async fn connect_with_timeout<F, T>(
+    runtime: Option<&dyn Runtime>,
+    connect: F,
+    timeout: Option<Duration>,
+) -> Result<T, Error>
 where
     F: Future<Output = io::Result<T>>,
 {
     match timeout {
-        Some(timeout) => match time::timeout(timeout, connect).await {
-            Ok(Ok(socket)) => Ok(socket),
-            Ok(Err(e)) => Err(Error::connect(e)),
-            Err(_) => Err(Error::connect(io::Error::new(
+        Some(timeout) => match rt::timeout(runtime, timeout, connect).await {
+            Some(Ok(socket)) => Ok(socket),
+            Some(Err(e)) => Err(Error::connect(e)),
+            None => Err(Error::connect(io::Error::new(
                 io::ErrorKind::TimedOut,
                 "connection timed out",
             ))),
@@ -1,23 +1,52 @@
-use crate::Error;
 use crate::config::{SslMode, SslNegotiation};
 use crate::maybe_tls_stream::MaybeTlsStream;
-use crate::tls::TlsConnect;
 use crate::tls::private::ForcePrivateApi;
+use crate::tls::{ClientCertificate, TlsConnect, Verification};
+use crate::{Config, Error};
 use bytes::BytesMut;
 use postgres_protocol::message::frontend;
+use std::borrow::Cow;
+use std::env;
+use std::path::{Path, PathBuf};
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 
+/// The TLS settings of a `Config`, which are retained by clients for use in cancellation requests.
+#[derive(Clone)]
+pub(crate) struct TlsConfig {
+    pub(crate) mode: SslMode,
+    pub(crate) negotiation: SslNegotiation,
+    pub(crate) root_cert: Option<PathBuf>,
+    pub(crate) cert: Option<PathBuf>,
+    pub(crate) key: Option<PathBuf>,
+    pub(crate) password: Option<Vec<u8>>,
+}
+
+impl TlsConfig {
+    pub(crate) fn new(config: &Config) -> TlsConfig {
+        TlsConfig {
+            mode: config.ssl_mode,
+            negotiation: config.ssl_negotiation,
+            root_cert: config.ssl_root_cert.clone(),
+            cert: config.ssl_cert.clone(),
+            key: config.ssl_key.clone(),
+            password: config.ssl_password.clone(),
+        }
+    }
+}
+
 pub async fn connect_tls<S, T>(
     mut stream: S,
-    mode: SslMode,
-    negotiation: SslNegotiation,
-    tls: T,
+    config: &TlsConfig,
+    mut tls: T,
     has_hostname: bool,
 ) -> Result<MaybeTlsStream<S, T::Stream>, Error>
 where
     S: AsyncRead + AsyncWrite + Unpin,
     T: TlsConnect<S>,
 {
+    let mode = config.mode;
+    let negotiation = config.negotiation;
+
     match mode {
         SslMode::Disable => return Ok(MaybeTlsStream::Raw(stream)),
         SslMode::Prefer if !tls.can_connect(ForcePrivateApi) => {
@@ -28,6 +57,28 @@ where
                 .into(),
         )),
         SslMode::Prefer | SslMode::Require => {}
+        SslMode::VerifyCa | SslMode::VerifyFull => {
+            let root_cert = match config.root_cert.as_deref() {
+                Some(path) if path == Path::new("system") => None,
+                Some(path) => Some(Cow::Borrowed(path)),
+                None => default_root_cert().map(Cow::Owned),
+            };
+            // without a root certificate file, any certificate from a public authority would be accepted for any name
+            if mode == SslMode::VerifyCa && root_cert.is_none() {
+                return Err(Error::tls(
+                    "sslmode \"verify-ca\" requires a root certificate file".into(),
+                ));
+            }
+            let verification = Verification::new(mode == SslMode::VerifyFull, root_cert.as_deref());
+            tls.set_verification(&verification).map_err(Error::tls)?;
+        }
+    }
+
+    if let Some(cert) = &config.cert {
+        let certificate =
+            ClientCertificate::new(cert, config.key.as_deref(), config.password.as_deref());
+        tls.set_client_certificate(&certificate)
+            .map_err(Error::tls)?;
     }
 
     if negotiation == SslNegotiation::Postgres {
@@ -39,7 +90,7 @@ where
         stream.read_exact(&mut buf).await.map_err(Error::io)?;
 
         if buf[0] != b'S' {
-            if SslMode::Require == mode {
+            if mode != SslMode::Prefer {
                 return Err(Error::tls("server does not support TLS".into()));
             } else {
                 return Ok(MaybeTlsStream::Raw(stream));
@@ -58,3 +109,16 @@ where
 
     Ok(MaybeTlsStream::Tls(stream))
 }
+
+/// Returns the path of libpq's default root certificate file, if it exists.
+fn default_root_cert() -> Option<PathBuf> {
+    #[cfg(windows)]
+    let path = PathBuf::from(env::var_os("APPDATA")?)
+        .join("postgresql")
+        .join("root.crt");
+    #[cfg(not(windows))]
+    let path = PathBuf::from(env::var_os("HOME")?)
+        .join(".postgresql")
+        .join("root.crt");
+    path.is_file().then_some(path)
+}
@@ -1,18 +1,24 @@
+use crate::client::SessionState;
 use crate::codec::{BackendMessage, BackendMessages, FrontendMessage, PostgresCodec};
+use crate::config::NoticeHandler;
 use crate::copy_in::CopyInReceiver;
 use crate::error::DbError;
 use crate::maybe_tls_stream::MaybeTlsStream;
-use crate::{AsyncMessage, Error, Notification};
+use crate::notifications::{ConnectionDriver, Notices, Notifications};
+use crate::tls::{TlsInfo, TlsStream};
+use crate::{AsyncMessage, Error, Notification, ParameterStatus};
 use bytes::BytesMut;
 use fallible_iterator::FallibleIterator;
-use futures_channel::mpsc;
+use futures_channel::{mpsc, oneshot};
 use futures_util::{Sink, Stream, StreamExt, stream::FusedStream};
 use log::{info, trace};
+use parking_lot::Mutex;
 use postgres_protocol::message::backend::Message;
 use postgres_protocol::message::frontend;
 use std::collections::{HashMap, VecDeque};
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::{Context, Poll, ready};
 use tokio::io::{AsyncRead, AsyncWrite};
 use tokio_util::codec::Framed;
@@ -20,6 +26,7 @@ use tokio_util::codec::Framed;
 pub enum RequestMessages {
     Single(FrontendMessage),
     CopyIn(CopyInReceiver),
+    Close(oneshot::Sender<Result<(), Error>>),
 }
 
 pub struct Request {
@@ -49,10 +56,13 @@ enum State {
 pub struct Connection<S, T> {
     stream: Framed<MaybeTlsStream<S, T>, PostgresCodec>,
     parameters: HashMap<String, String>,
+    session: Arc<SessionState>,
     receiver: mpsc::UnboundedReceiver<Request>,
     pending_request: Option<RequestMessages>,
     pending_responses: VecDeque<BackendMessage>,
     responses: VecDeque<Response>,
+    close_waiter: Option<oneshot::Sender<Result<(), Error>>>,
+    notice_handler: Option<Arc<NoticeHandler>>,
     state: State,
 }
 
@@ -65,19 +75,28 @@ where
         stream: Framed<MaybeTlsStream<S, T>, PostgresCodec>,
         pending_responses: VecDeque<BackendMessage>,
         parameters: HashMap<String, String>,
+        session: Arc<SessionState>,
         receiver: mpsc::UnboundedReceiver<Request>,
+        notice_handler: Option<Arc<NoticeHandler>>,
     ) -> Connection<S, T> {
         Connection {
             stream,
             parameters,
+            session,
             receiver,
             pending_request: None,
             pending_responses,
             responses: VecDeque::new(),
+            close_waiter: None,
+            notice_handler,
             state: State::Active,
         }
     }
 
+    pub(crate) fn session(&self) -> &Arc<SessionState> {
+        &self.session
+    }
+
     fn poll_response(
         &mut self,
         cx: &mut Context<'_>,
@@ -111,7 +130,13 @@ where
             let (mut messages, request_complete) = match message {
                 BackendMessage::Async(Message::NoticeResponse(body)) => {
                     let error = DbError::parse(&mut body.fields()).map_err(Error::parse)?;
-                    return Ok(Some(AsyncMessage::Notice(error)));
+                    match &self.notice_handler {
+                        Some(handler) => {
+                            handler(error);
+                            continue;
+                        }
+                        None => return Ok(Some(AsyncMessage::Notice(error))),
+                    }
                 }
                 BackendMessage::Async(Message::NotificationResponse(body)) => {
                     let notification = Notification {
@@ -122,11 +147,17 @@ where
                     return Ok(Some(AsyncMessage::Notification(notification)));
                 }
                 BackendMessage::Async(Message::ParameterStatus(body)) => {
-                    self.parameters.insert(
-                        body.name().map_err(Error::parse)?.to_string(),
-                        body.value().map_err(Error::parse)?.to_string(),
-                    );
-                    continue;
+                    let status = ParameterStatus {
+                        name: body.name().map_err(Error::parse)?.to_string(),
+                        value: body.value().map_err(Error::parse)?.to_string(),
+                    };
+                    self.parameters
+                        .insert(status.name.clone(), status.value.clone());
+                    self.session
+                        .parameters
+                        .lock()
+                        .insert(status.name.clone(), status.value.clone());
+                    return Ok(Some(AsyncMessage::ParameterStatus(status)));
                 }
                 BackendMessage::Async(_) => unreachable!(),
                 BackendMessage::Normal {
@@ -135,6 +166,12 @@ where
                 } => (messages, request_complete),
             };
 
+            if request_complete {
+                if let Some(status) = messages.ready_status() {
+                    self.session.set_transaction_status(status);
+                }
+            }
+
             let mut response = match self.responses.pop_front() {
                 Some(response) => response,
                 None => match messages.next().map_err(Error::parse)? {
@@ -175,20 +212,31 @@ where
             return Poll::Ready(Some(messages));
         }
 
-        if self.receiver.is_terminated() {
-            return Poll::Ready(None);
-        }
+        loop {
+            if self.receiver.is_terminated() {
+                return Poll::Ready(None);
+            }
 
-        match self.receiver.poll_next_unpin(cx) {
-            Poll::Ready(Some(request)) => {
-                trace!("polled new request");
-                self.responses.push_back(Response {
-                    sender: request.sender,
-                });
-                Poll::Ready(Some(request.messages))
+            match self.receiver.poll_next_unpin(cx) {
+                Poll::Ready(Some(Request {
+                    messages: RequestMessages::Close(waiter),
+                    ..
+                })) => {
+                    trace!("polled close request");
+                    // requests queued before the close are still processed before terminating
+                    self.close_waiter = Some(waiter);
+                    self.receiver.close();
+                }
+                Poll::Ready(Some(request)) => {
+                    trace!("polled new request");
+                    self.responses.push_back(Response {
+                        sender: request.sender,
+                    });
+                    return Poll::Ready(Some(request.messages));
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
             }
-            Poll::Ready(None) => Poll::Ready(None),
-            Poll::Pending => Poll::Pending,
         }
     }
 
@@ -258,6 +306,7 @@ where
                         .map_err(Error::io)?;
                     self.pending_request = Some(RequestMessages::CopyIn(receiver));
                 }
+                RequestMessages::Close(_) => unreachable!(),
             }
         }
     }
@@ -298,6 +347,19 @@ where
         self.parameters.get(name).map(|s| &**s)
     }
 
+    /// Returns details of the TLS session negotiated with the server.
+    ///
+    /// Returns `None` if the connection is not encrypted.
+    pub fn tls_info(&self) -> Option<TlsInfo>
+    where
+        T: TlsStream,
+    {
+        match self.stream.get_ref() {
+            MaybeTlsStream::Raw(_) => None,
+            MaybeTlsStream::Tls(s) => Some(s.tls_info()),
+        }
+    }
+
     fn poll_message_inner(
         &mut self,
         cx: &mut Context<'_>,
@@ -317,6 +379,49 @@ where
         }
     }
 
+    /// Sets a function which is called with each notice received from the server.
+    ///
+    /// Notices passed to the handler are not returned by [`poll_message`](Connection::poll_message) or logged by the
+    /// connection's `Future` implementation. This replaces any handler set previously, including one configured with
+    /// [`Config::notice_handler`](crate::Config::notice_handler).
+    pub fn set_notice_handler<F>(&mut self, f: F)
+    where
+        F: Fn(DbError) + Send + Sync + 'static,
+    {
+        self.notice_handler = Some(Arc::new(f));
+    }
+
+    /// Returns a stream of the notices received from the server.
+    ///
+    /// This sets a [notice handler](Connection::set_notice_handler) which buffers up to `capacity` notices for the
+    /// stream. Notices which arrive while the buffer is full are logged and discarded rather than blocking the
+    /// connection. A `capacity` of 0 is treated as 1. The stream ends once the connection has been dropped.
+    pub fn notices(&mut self, capacity: usize) -> Notices {
+        // futures' channels reserve one slot per sender on top of the requested buffer
+        let (sender, receiver) = mpsc::channel(capacity.saturating_sub(1));
+        let sender = Mutex::new(sender);
+        self.set_notice_handler(move |notice| {
+            if let Err(e) = sender.lock().try_send(notice) {
+                if e.is_full() {
+                    let notice = e.into_inner();
+                    info!("{}: {}", notice.severity(), notice.message());
+                }
+            }
+        });
+        Notices::new(receiver)
+    }
+
+    /// Splits the connection into a future which drives it and a stream of the notifications it receives.
+    ///
+    /// This is a convenience for applications that use `LISTEN`, which otherwise need to drive the connection with
+    /// [`poll_message`](Connection::poll_message) themselves. The returned [`ConnectionDriver`] should be spawned off
+    /// onto an executor just like the `Connection` itself would be.
+    pub fn split(self) -> (ConnectionDriver<S, T>, Notifications) {
+        let (sender, receiver) = mpsc::unbounded();
+        let notifications = Notifications::new(receiver, self.session.clone());
+        (ConnectionDriver::new(self, sender), notifications)
+    }
+
     /// Polls for asynchronous messages from the server.
     ///
     /// The server can send notices as well as notifications asynchronously to the client. Applications that wish to
@@ -324,6 +429,9 @@ where
     ///
     /// Return values of `None` or `Some(Err(_))` are "terminal"; callers should not invoke this method again after
     /// receiving one of those values.
+    ///
+    /// If the connection was shut down by [`Client::close`](crate::Client::close), any error encountered while closing
+    /// is reported to that method instead, and this method returns `None`.
     pub fn poll_message(
         &mut self,
         cx: &mut Context<'_>,
@@ -332,7 +440,18 @@ where
             nominal @ (Poll::Pending | Poll::Ready(Some(Ok(_)))) => nominal,
             terminal @ (Poll::Ready(None) | Poll::Ready(Some(Err(_)))) => {
                 self.receiver.close();
-                terminal
+                let Some(waiter) = self.close_waiter.take() else {
+                    return terminal;
+                };
+                let result = match terminal {
+                    Poll::Ready(Some(Err(e))) => Err(e),
+                    _ => Ok(()),
+                };
+                // if the caller of close has gone away, fall back to reporting the error here
+                match waiter.send(result) {
+                    Err(Err(e)) => Poll::Ready(Some(Err(e))),
+                    _ => Poll::Ready(None),
+                }
             }
         }
     }
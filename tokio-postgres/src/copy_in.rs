@@ -1,9 +1,10 @@
 use crate::client::{InnerClient, Responses};
 use crate::codec::FrontendMessage;
 use crate::connection::RequestMessages;
+use crate::instrument::{Operation, OperationSpan};
 use crate::query::extract_row_affected;
 use crate::{Error, Statement, query, slice_iter};
-use bytes::{Buf, BufMut, BytesMut};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
 use futures_channel::mpsc;
 use futures_util::{Sink, SinkExt, Stream, StreamExt};
 use log::debug;
@@ -13,8 +14,11 @@ use postgres_protocol::message::frontend;
 use postgres_protocol::message::frontend::CopyData;
 use std::future;
 use std::marker::PhantomData;
-use std::pin::Pin;
+use std::pin::{Pin, pin};
 use std::task::{Context, Poll, ready};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+const READ_CHUNK_SIZE: usize = 64 * 1024;
 
 enum CopyInMessage {
     Message(FrontendMessage),
@@ -81,6 +85,7 @@ pin_project! {
         responses: Responses,
         buf: BytesMut,
         state: SinkState,
+        span: OperationSpan,
         _p2: PhantomData<T>,
     }
 }
@@ -91,6 +96,15 @@ where
 {
     /// A poll-based version of `finish`.
     pub fn poll_finish(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<u64, Error>> {
+        let result = ready!(self.as_mut().poll_finish_inner(cx));
+        self.project().span.record_rows(&result);
+        Poll::Ready(result)
+    }
+
+    fn poll_finish_inner(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<u64, Error>> {
         loop {
             match self.state {
                 SinkState::Active => {
@@ -194,7 +208,52 @@ where
 {
     debug!("executing copy in statement {}", statement.name());
 
-    let buf = query::encode(client, &statement, slice_iter(&[]))?;
+    let mut span = OperationSpan::new(client, Operation::CopyIn, statement.query());
+    match span.instrument(start(client, &statement)).await {
+        Ok((sender, responses)) => Ok(CopyInSink {
+            sender,
+            responses,
+            buf: BytesMut::new(),
+            state: SinkState::Active,
+            span,
+            _p2: PhantomData,
+        }),
+        Err(e) => {
+            span.fail(&e);
+            Err(e)
+        }
+    }
+}
+
+pub async fn copy_in_from<R>(
+    client: &InnerClient,
+    statement: Statement,
+    reader: R,
+) -> Result<u64, Error>
+where
+    R: AsyncRead,
+{
+    let mut reader = pin!(reader);
+    let mut sink = pin!(copy_in::<Bytes>(client, statement).await?);
+
+    let mut buf = BytesMut::new();
+    loop {
+        buf.reserve(READ_CHUNK_SIZE);
+        // If the read fails, dropping the sink aborts the copy.
+        if reader.read_buf(&mut buf).await.map_err(Error::io)? == 0 {
+            break;
+        }
+        sink.send(buf.split().freeze()).await?;
+    }
+
+    sink.finish().await
+}
+
+async fn start(
+    client: &InnerClient,
+    statement: &Statement,
+) -> Result<(mpsc::Sender<CopyInMessage>, Responses), Error> {
+    let buf = query::encode(client, statement, slice_iter(&[]))?;
 
     let (mut sender, receiver) = mpsc::channel(1);
     let receiver = CopyInReceiver::new(receiver);
@@ -215,11 +274,5 @@ where
         _ => return Err(Error::unexpected_message()),
     }
 
-    Ok(CopyInSink {
-        sender,
-        responses,
-        buf: BytesMut::new(),
-        state: SinkState::Active,
-        _p2: PhantomData,
-    })
+    Ok((sender, responses))
 }
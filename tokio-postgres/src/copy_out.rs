@@ -1,21 +1,57 @@
 use crate::client::{InnerClient, Responses};
 use crate::codec::FrontendMessage;
 use crate::connection::RequestMessages;
+use crate::instrument::{Operation, OperationSpan};
+use crate::query::extract_row_affected;
 use crate::{Error, Statement, query, slice_iter};
 use bytes::Bytes;
-use futures_util::Stream;
+use futures_util::{Stream, StreamExt};
 use log::debug;
 use pin_project_lite::pin_project;
 use postgres_protocol::message::backend::Message;
-use std::pin::Pin;
+use std::pin::{Pin, pin};
 use std::task::{Context, Poll, ready};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
 
 pub async fn copy_out(client: &InnerClient, statement: Statement) -> Result<CopyOutStream, Error> {
     debug!("executing copy out statement {}", statement.name());
 
-    let buf = query::encode(client, &statement, slice_iter(&[]))?;
-    let responses = start(client, buf).await?;
-    Ok(CopyOutStream { responses })
+    let mut span = OperationSpan::new(client, Operation::CopyOut, statement.query());
+    let result = match query::encode(client, &statement, slice_iter(&[])) {
+        Ok(buf) => span.instrument(start(client, buf)).await,
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(responses) => Ok(CopyOutStream {
+            responses,
+            span,
+            rows_affected: None,
+        }),
+        Err(e) => {
+            span.fail(&e);
+            Err(e)
+        }
+    }
+}
+
+pub async fn copy_out_to<W>(
+    client: &InnerClient,
+    statement: Statement,
+    writer: W,
+) -> Result<u64, Error>
+where
+    W: AsyncWrite,
+{
+    let mut writer = pin!(writer);
+    let mut stream = pin!(copy_out(client, statement).await?);
+
+    while let Some(chunk) = stream.next().await {
+        writer.write_all(&chunk?).await.map_err(Error::io)?;
+    }
+    writer.flush().await.map_err(Error::io)?;
+
+    stream.rows_affected().ok_or_else(Error::unexpected_message)
 }
 
 async fn start(client: &InnerClient, buf: Bytes) -> Result<Responses, Error> {
@@ -39,6 +75,17 @@ pin_project! {
     #[project(!Unpin)]
     pub struct CopyOutStream {
         responses: Responses,
+        span: OperationSpan,
+        rows_affected: Option<u64>,
+    }
+}
+
+impl CopyOutStream {
+    /// Returns the number of rows copied.
+    ///
+    /// This function will return `None` until the stream has been exhausted.
+    pub fn rows_affected(&self) -> Option<u64> {
+        self.rows_affected
     }
 }
 
@@ -47,11 +94,27 @@ impl Stream for CopyOutStream {
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         let this = self.project();
-
-        match ready!(this.responses.poll_next(cx)?) {
-            Message::CopyData(body) => Poll::Ready(Some(Ok(body.into_bytes()))),
-            Message::CopyDone => Poll::Ready(None),
-            _ => Poll::Ready(Some(Err(Error::unexpected_message()))),
+        if this.rows_affected.is_some() {
+            return Poll::Ready(None);
         }
+
+        let error = loop {
+            match ready!(this.responses.poll_next(cx)) {
+                Ok(Message::CopyData(body)) => return Poll::Ready(Some(Ok(body.into_bytes()))),
+                Ok(Message::CopyDone) => {}
+                Ok(Message::CommandComplete(body)) => match extract_row_affected(&body) {
+                    Ok(rows) => {
+                        *this.rows_affected = Some(rows);
+                        this.span.record_rows(&Ok(rows));
+                        return Poll::Ready(None);
+                    }
+                    Err(e) => break e,
+                },
+                Ok(_) => break Error::unexpected_message(),
+                Err(e) => break e,
+            }
+        };
+        this.span.fail(&error);
+        Poll::Ready(Some(Err(error)))
     }
 }
@@ -0,0 +1,334 @@
+//! Rendering of statements with their parameters inlined, for debugging.
+
+use crate::types::{Format, IsNull, Kind, ToSql, Type};
+use crate::{Error, text_format};
+use bytes::BytesMut;
+use fallible_iterator::FallibleIterator;
+use postgres_protocol::escape::{escape_identifier, escape_literal};
+use postgres_protocol::types;
+
+/// Replaces the parameter placeholders of a query with SQL literals of the given parameters.
+pub(crate) fn render(
+    query: &str,
+    types: &[Type],
+    params: &[&(dyn ToSql + Sync)],
+) -> Result<String, Error> {
+    if params.len() != types.len() {
+        return Err(Error::parameters(params.len(), types.len()));
+    }
+
+    let values = params
+        .iter()
+        .zip(types)
+        .enumerate()
+        .map(|(idx, (param, ty))| render_param(*param, ty).map_err(|e| Error::to_sql(e, idx)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(substitute(query, &values))
+}
+
+fn render_param(
+    param: &(dyn ToSql + Sync),
+    ty: &Type,
+) -> Result<String, Box<dyn std::error::Error + Sync + Send>> {
+    let mut buf = BytesMut::new();
+    let literal = match param.to_sql_checked(ty, &mut buf)? {
+        IsNull::Yes => "NULL".to_string(),
+        IsNull::No => match param.encode_format(ty) {
+            Format::Text => quote(&String::from_utf8_lossy(&buf)),
+            Format::Binary => literal(ty, &buf).unwrap_or_else(|| quote(&format!("{param:?}"))),
+        },
+    };
+
+    // A leading minus sign could otherwise combine with a preceding one into a comment.
+    let literal = if literal.starts_with('-') {
+        format!("({literal})")
+    } else {
+        literal
+    };
+    Ok(format!("{}::{}", literal, type_name(ty)))
+}
+
+/// Quotes a string as a SQL literal.
+///
+/// `escape_literal` puts a space before the `E` prefix it adds to literals containing backslashes, which isn't wanted
+/// inside a rendered statement.
+pub(crate) fn quote(value: &str) -> String {
+    let literal = escape_literal(value);
+    match literal.strip_prefix(' ') {
+        Some(literal) => literal.to_string(),
+        None => literal,
+    }
+}
+
+fn type_name(ty: &Type) -> String {
+    if let Kind::Array(member) = ty.kind() {
+        return format!("{}[]", type_name(member));
+    }
+
+    let mut name = String::new();
+    match ty.schema() {
+        "public" | "pg_catalog" => {}
+        schema => {
+            name.push_str(&identifier(schema));
+            name.push('.');
+        }
+    }
+    name.push_str(&identifier(ty.name()));
+    name
+}
+
+fn identifier(name: &str) -> String {
+    let plain = name.starts_with(|c: char| c.is_ascii_lowercase() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '$');
+    // The internal `"char"` type must be quoted to distinguish it from `char`, an alias of `bpchar`.
+    if plain && name != "char" {
+        name.to_string()
+    } else {
+        escape_identifier(name)
+    }
+}
+
+/// Renders the binary encoding of a value as a literal, or returns `None` if the type isn't supported.
+pub(crate) fn literal(ty: &Type, raw: &[u8]) -> Option<String> {
+    if let Kind::Array(member) = ty.kind() {
+        return array_literal(member, raw);
+    }
+
+    let text = text_format::scalar(ty, raw)?;
+    let numeric = matches!(
+        *ty,
+        Type::INT2
+            | Type::INT4
+            | Type::INT8
+            | Type::OID
+            | Type::FLOAT4
+            | Type::FLOAT8
+            | Type::NUMERIC
+    );
+    // Special values such as `NaN` and `Infinity` have to be quoted.
+    if numeric
+        && text
+            .bytes()
+            .all(|b| b.is_ascii_digit() || b == b'-' || b == b'.')
+    {
+        Some(text)
+    } else {
+        Some(quote(&text))
+    }
+}
+
+fn array_literal(member: &Type, raw: &[u8]) -> Option<String> {
+    let array = types::array_from_sql(raw).ok()?;
+    let dimensions = array
+        .dimensions()
+        .map(|d| Ok(d.len as usize))
+        .collect::<Vec<_>>()
+        .ok()?;
+    if dimensions.is_empty() {
+        return Some(quote("{}"));
+    }
+
+    let mut values = array
+        .values()
+        .map(|v| {
+            Ok(match v {
+                Some(v) => literal(member, v),
+                None => Some("NULL".to_string()),
+            })
+        })
+        .collect::<Vec<_>>()
+        .ok()?
+        .into_iter()
+        .collect::<Option<Vec<_>>>()?
+        .into_iter();
+
+    let mut literal = String::from("ARRAY");
+    nest_array(&mut literal, &dimensions, &mut values);
+    Some(literal)
+}
+
+fn nest_array(out: &mut String, dimensions: &[usize], values: &mut impl Iterator<Item = String>) {
+    out.push('[');
+    for i in 0..dimensions[0] {
+        if i > 0 {
+            out.push(',');
+        }
+        if dimensions.len() > 1 {
+            nest_array(out, &dimensions[1..], values);
+        } else if let Some(value) = values.next() {
+            out.push_str(&value);
+        }
+    }
+    out.push(']');
+}
+
+/// Replaces `$n` placeholders with the corresponding values.
+///
+/// Placeholders inside string literals, quoted identifiers, comments and dollar-quoted strings are left alone, as are
+/// placeholders with no corresponding value.
+fn substitute(query: &str, values: &[String]) -> String {
+    let bytes = query.as_bytes();
+    let mut out = String::with_capacity(query.len());
+    let mut last = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\'' => {
+                let escapes = i > 0
+                    && matches!(bytes[i - 1], b'e' | b'E')
+                    && (i == 1 || !is_identifier(bytes[i - 2]));
+                i = skip_quoted(bytes, i, b'\'', escapes);
+            }
+            b'"' => i = skip_quoted(bytes, i, b'"', false),
+            b'-' if bytes.get(i + 1) == Some(&b'-') => {
+                i = query[i..].find('\n').map_or(bytes.len(), |end| i + end);
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => i = skip_block_comment(bytes, i),
+            b'$' if i == 0 || !is_identifier(bytes[i - 1]) => {
+                let digits = bytes[i + 1..]
+                    .iter()
+                    .take_while(|b| b.is_ascii_digit())
+                    .count();
+                if digits > 0 {
+                    let end = i + 1 + digits;
+                    let value = query[i + 1..end]
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|n| n.checked_sub(1))
+                        .and_then(|n| values.get(n));
+                    if let Some(value) = value {
+                        out.push_str(&query[last..i]);
+                        out.push_str(value);
+                        last = end;
+                    }
+                    i = end;
+                } else {
+                    i = skip_dollar_quoted(query, i);
+                }
+            }
+            _ => i += 1,
+        }
+    }
+
+    out.push_str(&query[last..]);
+    out
+}
+
+fn is_identifier(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_' || b == b'$' || b >= 0x80
+}
+
+fn skip_quoted(bytes: &[u8], start: usize, quote: u8, escapes: bool) -> usize {
+    let mut i = start + 1;
+    while i < bytes.len() {
+        if escapes && bytes[i] == b'\\' {
+            i += 2;
+        } else if bytes[i] == quote {
+            if bytes.get(i + 1) == Some(&quote) {
+                i += 2;
+            } else {
+                return i + 1;
+            }
+        } else {
+            i += 1;
+        }
+    }
+    bytes.len()
+}
+
+fn skip_block_comment(bytes: &[u8], start: usize) -> usize {
+    let mut depth = 0;
+    let mut i = start;
+    while i + 1 < bytes.len() {
+        match (bytes[i], bytes[i + 1]) {
+            (b'/', b'*') => {
+                depth += 1;
+                i += 2;
+            }
+            (b'*', b'/') => {
+                depth -= 1;
+                i += 2;
+                if depth == 0 {
+                    return i;
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+fn skip_dollar_quoted(query: &str, start: usize) -> usize {
+    let bytes = query.as_bytes();
+    let tag_len = bytes[start + 1..]
+        .iter()
+        .take_while(|&&b| is_identifier(b) && b != b'$')
+        .count();
+    let tag_end = start + 1 + tag_len;
+    if bytes.get(tag_end) != Some(&b'$') {
+        return start + 1;
+    }
+
+    let tag = &query[start..=tag_end];
+    query[tag_end + 1..]
+        .find(tag)
+        .map_or(bytes.len(), |end| tag_end + 1 + end + tag.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render, substitute};
+    use crate::types::Type;
+
+    #[test]
+    fn test_substitute() {
+        let values = ["1::int4".to_string(), "'a'::text".to_string()];
+        assert_eq!(
+            substitute("SELECT $1, $2, $3 FROM foo WHERE a$1 = $1", &values),
+            "SELECT 1::int4, 'a'::text, $3 FROM foo WHERE a$1 = 1::int4"
+        );
+        assert_eq!(
+            substitute(
+                "SELECT '$1', E'\\'$1', \"$1\", $$ $1 $$, $x$ $1 $x$ -- $1\n, $2 /* $1 /* $1 */ */",
+                &values
+            ),
+            "SELECT '$1', E'\\'$1', \"$1\", $$ $1 $$, $x$ $1 $x$ -- $1\n, 'a'::text /* $1 /* $1 */ */"
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let rendered = render(
+            "SELECT $1, $2, $3, $4, $5, $6",
+            &[
+                Type::TEXT,
+                Type::INT8,
+                Type::INT4_ARRAY,
+                Type::BYTEA,
+                Type::FLOAT8,
+                Type::TEXT,
+            ],
+            &[
+                &"it's",
+                &-5i64,
+                &vec![Some(1i32), None],
+                &&b"\x01\xff"[..],
+                &f64::NAN,
+                &None::<&str>,
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            rendered,
+            "SELECT 'it''s'::text, (-5)::int8, ARRAY[1,NULL]::int4[], E'\\\\x01ff'::bytea, \
+             'NaN'::float8, NULL::text"
+        );
+
+        assert!(render("SELECT $1", &[Type::INT4], &[]).is_err());
+        assert!(render("SELECT $1", &[Type::INT4], &[&"foo"]).is_err());
+    }
+}
@@ -243,6 +243,13 @@ impl DbError {
         self.position.as_ref()
     }
 
+    /// Returns the line and column of the error cursor position.
+    ///
+    /// `query` is the text of the query which caused the error. See [`ErrorPosition::location`] for details.
+    pub fn location(&self, query: &str) -> Option<QueryLocation> {
+        self.position.as_ref()?.location(query)
+    }
+
     /// An indication of the context in which the error occurred.
     ///
     /// Presently this includes a call stack traceback of active procedural
@@ -302,12 +309,140 @@ impl DbError {
         self.line
     }
 
+    /// Returns a builder for an error with the given severity, SQLSTATE code and message.
+    ///
+    /// Errors are normally created by the server, but this can be used to construct realistic errors when testing
+    /// code which handles them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tokio_postgres::error::{DbError, Severity, SqlState};
+    ///
+    /// let error = DbError::builder(
+    ///     Severity::Error,
+    ///     SqlState::UNIQUE_VIOLATION,
+    ///     "duplicate key value violates unique constraint \"users_email_key\"",
+    /// )
+    /// .table("users")
+    /// .constraint("users_email_key")
+    /// .build();
+    ///
+    /// assert_eq!(error.code(), &SqlState::UNIQUE_VIOLATION);
+    /// assert_eq!(error.constraint(), Some("users_email_key"));
+    /// ```
+    pub fn builder(severity: Severity, code: SqlState, message: &str) -> DbErrorBuilder {
+        DbErrorBuilder(DbError {
+            severity: severity.to_string(),
+            parsed_severity: Some(severity),
+            code,
+            message: message.to_string(),
+            detail: None,
+            hint: None,
+            position: None,
+            where_: None,
+            schema: None,
+            table: None,
+            column: None,
+            datatype: None,
+            constraint: None,
+            file: None,
+            line: None,
+            routine: None,
+        })
+    }
+
     /// The name of the source-code routine reporting the error.
     pub fn routine(&self) -> Option<&str> {
         self.routine.as_deref()
     }
 }
 
+/// A builder for a [`DbError`].
+///
+/// Created with [`DbError::builder`].
+#[derive(Debug, Clone)]
+pub struct DbErrorBuilder(DbError);
+
+impl DbErrorBuilder {
+    /// Sets the secondary error message.
+    pub fn detail(&mut self, detail: &str) -> &mut DbErrorBuilder {
+        self.0.detail = Some(detail.to_string());
+        self
+    }
+
+    /// Sets the suggestion what to do about the problem.
+    pub fn hint(&mut self, hint: &str) -> &mut DbErrorBuilder {
+        self.0.hint = Some(hint.to_string());
+        self
+    }
+
+    /// Sets the error cursor position.
+    pub fn position(&mut self, position: ErrorPosition) -> &mut DbErrorBuilder {
+        self.0.position = Some(position);
+        self
+    }
+
+    /// Sets the context in which the error occurred.
+    pub fn where_(&mut self, where_: &str) -> &mut DbErrorBuilder {
+        self.0.where_ = Some(where_.to_string());
+        self
+    }
+
+    /// Sets the name of the schema containing the object associated with the error.
+    pub fn schema(&mut self, schema: &str) -> &mut DbErrorBuilder {
+        self.0.schema = Some(schema.to_string());
+        self
+    }
+
+    /// Sets the name of the table associated with the error.
+    pub fn table(&mut self, table: &str) -> &mut DbErrorBuilder {
+        self.0.table = Some(table.to_string());
+        self
+    }
+
+    /// Sets the name of the table column associated with the error.
+    pub fn column(&mut self, column: &str) -> &mut DbErrorBuilder {
+        self.0.column = Some(column.to_string());
+        self
+    }
+
+    /// Sets the name of the data type associated with the error.
+    pub fn datatype(&mut self, datatype: &str) -> &mut DbErrorBuilder {
+        self.0.datatype = Some(datatype.to_string());
+        self
+    }
+
+    /// Sets the name of the constraint associated with the error.
+    pub fn constraint(&mut self, constraint: &str) -> &mut DbErrorBuilder {
+        self.0.constraint = Some(constraint.to_string());
+        self
+    }
+
+    /// Sets the file name of the source-code location where the error was reported.
+    pub fn file(&mut self, file: &str) -> &mut DbErrorBuilder {
+        self.0.file = Some(file.to_string());
+        self
+    }
+
+    /// Sets the line number of the source-code location where the error was reported.
+    pub fn line(&mut self, line: u32) -> &mut DbErrorBuilder {
+        self.0.line = Some(line);
+        self
+    }
+
+    /// Sets the name of the source-code routine reporting the error.
+    pub fn routine(&mut self, routine: &str) -> &mut DbErrorBuilder {
+        self.0.routine = Some(routine.to_string());
+        self
+    }
+
+    /// Creates the error.
+    pub fn build(&self) -> DbError {
+        self.0.clone()
+    }
+}
+
 impl fmt::Display for DbError {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(fmt, "{}: {}", self.severity, self.message)?;
@@ -337,6 +472,104 @@ pub enum ErrorPosition {
     },
 }
 
+impl ErrorPosition {
+    /// Returns the line and column of the position.
+    ///
+    /// `query` is the text of the query which caused the error. It is only used for `Original` positions, since
+    /// `Internal` positions carry the text of the query they refer to. Returns `None` if the position is outside of
+    /// the query, which usually means that the wrong query text was provided.
+    pub fn location(&self, query: &str) -> Option<QueryLocation> {
+        match self {
+            ErrorPosition::Original(position) => QueryLocation::new(query, *position),
+            ErrorPosition::Internal { position, query } => QueryLocation::new(query, *position),
+        }
+    }
+}
+
+/// A line and column in the text of a query.
+///
+/// This converts the position of an error reported by the server into a form suitable for diagnostics:
+///
+/// ```
+/// use tokio_postgres::error::QueryLocation;
+///
+/// let location = QueryLocation::new("SELECT *\nFORM foo", 10).unwrap();
+/// assert_eq!(location.line(), 2);
+/// assert_eq!(location.column(), 1);
+/// assert_eq!(location.excerpt(), "FORM foo\n^");
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct QueryLocation {
+    line: usize,
+    column: usize,
+    line_text: String,
+}
+
+impl QueryLocation {
+    /// Locates a position in the text of a query.
+    ///
+    /// Positions are counted in characters starting from 1, as in the positions reported by the server. A position
+    /// just past the end of the query, as reported for errors such as an unexpected end of input, refers to the end of
+    /// the last line. Returns `None` if the position is outside of the query.
+    pub fn new(query: &str, position: u32) -> Option<QueryLocation> {
+        let index = usize::try_from(position).ok()?.checked_sub(1)?;
+        let offset = match query.char_indices().nth(index) {
+            Some((offset, _)) => offset,
+            None if query.chars().count() == index => query.len(),
+            None => return None,
+        };
+
+        let before = &query[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let line_end = query[line_start..]
+            .find('\n')
+            .map_or(query.len(), |i| line_start + i);
+
+        Some(QueryLocation {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            line_text: query[line_start..line_end]
+                .trim_end_matches('\r')
+                .to_string(),
+        })
+    }
+
+    /// Returns the line number, starting from 1.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns the column number in characters, starting from 1.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Returns the text of the line containing the location.
+    pub fn line_text(&self) -> &str {
+        &self.line_text
+    }
+
+    /// Returns the line containing the location, followed by a line with a caret pointing at the location.
+    ///
+    /// Tabs before the location are preserved in the second line so that the caret lines up with the text.
+    pub fn excerpt(&self) -> String {
+        let padding = self
+            .line_text
+            .chars()
+            .chain(std::iter::repeat(' '))
+            .take(self.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect::<String>();
+        format!("{}\n{padding}^", self.line_text)
+    }
+}
+
+impl fmt::Display for QueryLocation {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "line {}, column {}", self.line, self.column)
+    }
+}
+
 #[derive(Debug, PartialEq)]
 enum Kind {
     Io,
@@ -355,6 +588,7 @@ enum Kind {
     ConfigParse,
     Config,
     RowCount,
+    Notify,
     #[cfg(feature = "runtime")]
     Connect,
     Timeout,
@@ -363,6 +597,8 @@ enum Kind {
 struct ErrorInner {
     kind: Kind,
     cause: Option<Box<dyn error::Error + Sync + Send>>,
+    query: Option<String>,
+    params: Option<Vec<String>>,
 }
 
 /// An error communicating with the Postgres server.
@@ -373,6 +609,8 @@ impl fmt::Debug for Error {
         fmt.debug_struct("Error")
             .field("kind", &self.0.kind)
             .field("cause", &self.0.cause)
+            .field("query", &self.0.query)
+            .field("params", &self.0.params)
             .finish()
     }
 }
@@ -398,6 +636,7 @@ impl fmt::Display for Error {
             Kind::ConfigParse => fmt.write_str("invalid connection string"),
             Kind::Config => fmt.write_str("invalid configuration"),
             Kind::RowCount => fmt.write_str("query returned an unexpected number of rows"),
+            Kind::Notify => fmt.write_str("invalid notification"),
             #[cfg(feature = "runtime")]
             Kind::Connect => fmt.write_str("error connecting to server"),
             Kind::Timeout => fmt.write_str("timeout waiting for server"),
@@ -436,8 +675,138 @@ impl Error {
         self.as_db_error().map(DbError::code)
     }
 
+    /// Determines if the error was caused by a unique constraint violation (SQLSTATE `23505`).
+    pub fn is_unique_violation(&self) -> bool {
+        self.code() == Some(&SqlState::UNIQUE_VIOLATION)
+    }
+
+    /// Determines if the error was caused by a foreign key constraint violation (SQLSTATE `23503`).
+    pub fn is_foreign_key_violation(&self) -> bool {
+        self.code() == Some(&SqlState::FOREIGN_KEY_VIOLATION)
+    }
+
+    /// Determines if the error was caused by a not-null constraint violation (SQLSTATE `23502`).
+    pub fn is_not_null_violation(&self) -> bool {
+        self.code() == Some(&SqlState::NOT_NULL_VIOLATION)
+    }
+
+    /// Determines if the error was caused by a check constraint violation (SQLSTATE `23514`).
+    pub fn is_check_violation(&self) -> bool {
+        self.code() == Some(&SqlState::CHECK_VIOLATION)
+    }
+
+    /// Determines if the error was caused by a serialization failure (SQLSTATE `40001`).
+    ///
+    /// These are raised by transactions using the `REPEATABLE READ` or `SERIALIZABLE` isolation levels when they
+    /// conflict with concurrent transactions.
+    pub fn is_serialization_failure(&self) -> bool {
+        self.code() == Some(&SqlState::T_R_SERIALIZATION_FAILURE)
+    }
+
+    /// Determines if the error was caused by a detected deadlock (SQLSTATE `40P01`).
+    pub fn is_deadlock(&self) -> bool {
+        self.code() == Some(&SqlState::T_R_DEADLOCK_DETECTED)
+    }
+
+    /// Determines if the operation which caused the error might succeed if retried.
+    ///
+    /// This is the case for serialization failures, deadlocks, and closed connections (when retrying on a new
+    /// connection). It's a heuristic: the server rolls back the whole transaction in each case, so the entire
+    /// transaction must be retried, and it's up to the caller to decide whether doing so is safe. In particular, a
+    /// connection may have closed after the server committed a transaction but before the client saw the result.
+    pub fn is_retryable(&self) -> bool {
+        self.is_closed() || self.is_serialization_failure() || self.is_deadlock()
+    }
+
+    /// Returns the text of the statement which caused the error.
+    ///
+    /// This is only recorded if the [`Config::query_in_errors`] option was enabled for the connection, and only for
+    /// errors returned while preparing or running a statement.
+    ///
+    /// [`Config::query_in_errors`]: crate::Config::query_in_errors
+    pub fn query(&self) -> Option<&str> {
+        self.0.query.as_deref()
+    }
+
+    /// Returns renderings of the parameters bound to the statement which caused the error.
+    ///
+    /// This is only recorded if the [`Config::params_in_errors`] option was enabled for the connection, and only for
+    /// errors returned while running a statement with parameters. Each parameter is rendered as a SQL literal, or
+    /// `NULL`, unless a [`Config::param_redactor`] replaces it.
+    ///
+    /// [`Config::params_in_errors`]: crate::Config::params_in_errors
+    /// [`Config::param_redactor`]: crate::Config::param_redactor
+    pub fn params(&self) -> Option<&[String]> {
+        self.0.params.as_deref()
+    }
+
+    /// Creates an error wrapping a `DbError`, in the same way as errors returned by the server.
+    ///
+    /// This is intended for testing code which handles errors, along with [`DbError::builder`].
+    pub fn from_db_error(e: DbError) -> Error {
+        Error::new(Kind::Db, Some(Box::new(e)))
+    }
+
     fn new(kind: Kind, cause: Option<Box<dyn error::Error + Sync + Send>>) -> Error {
-        Error(Box::new(ErrorInner { kind, cause }))
+        Error(Box::new(ErrorInner {
+            kind,
+            cause,
+            query: None,
+            params: None,
+        }))
+    }
+
+    /// Determines if a failed connection attempt may succeed if retried.
+    ///
+    /// Only failures to reach the server are retried. Errors reported by the server, such as a failed authentication
+    /// or a nonexistent database, will recur on every attempt.
+    #[cfg(feature = "runtime")]
+    pub(crate) fn is_connect_retryable(&self) -> bool {
+        matches!(
+            self.0.kind,
+            Kind::Io | Kind::Connect | Kind::Closed | Kind::Timeout
+        )
+    }
+
+    /// Returns a short name for the kind of error, for use in traces.
+    #[cfg(feature = "tracing")]
+    pub(crate) fn kind_name(&self) -> &'static str {
+        match &self.0.kind {
+            Kind::Io => "io",
+            Kind::UnexpectedMessage => "unexpected_message",
+            Kind::Tls => "tls",
+            Kind::ToSql(_) => "to_sql",
+            Kind::FromSql(_) => "from_sql",
+            Kind::Column(_) => "column",
+            Kind::ColumnCount => "column_count",
+            Kind::Parameters(_, _) => "parameters",
+            Kind::Closed => "closed",
+            Kind::Db => "db",
+            Kind::Parse => "parse",
+            Kind::Encode => "encode",
+            Kind::Authentication => "authentication",
+            Kind::ConfigParse => "config_parse",
+            Kind::Config => "config",
+            Kind::RowCount => "row_count",
+            Kind::Notify => "notify",
+            #[cfg(feature = "runtime")]
+            Kind::Connect => "connect",
+            Kind::Timeout => "timeout",
+        }
+    }
+
+    /// Records the text of the statement which caused the error.
+    ///
+    /// This replaces any text recorded by an internal query, such as a type lookup, made on behalf of the statement.
+    pub(crate) fn with_query(mut self, query: &str) -> Error {
+        self.0.query = Some(query.to_string());
+        self
+    }
+
+    /// Records the rendered parameters of the statement which caused the error.
+    pub(crate) fn with_params(mut self, params: Vec<String>) -> Error {
+        self.0.params = Some(params);
+        self
     }
 
     pub(crate) fn closed() -> Error {
@@ -509,6 +878,10 @@ impl Error {
         Error::new(Kind::RowCount, None)
     }
 
+    pub(crate) fn notify(e: Box<dyn error::Error + Sync + Send>) -> Error {
+        Error::new(Kind::Notify, Some(e))
+    }
+
     #[cfg(feature = "runtime")]
     pub(crate) fn connect(e: io::Error) -> Error {
         Error::new(Kind::Connect, Some(Box::new(e)))
@@ -518,4 +891,96 @@ impl Error {
     pub fn __private_api_timeout() -> Error {
         Error::new(Kind::Timeout, None)
     }
+
+    #[doc(hidden)]
+    pub fn __private_api_io(e: io::Error) -> Error {
+        Error::io(e)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn db_error_builder() {
+        let db_error = DbError::builder(
+            Severity::Error,
+            SqlState::T_R_SERIALIZATION_FAILURE,
+            "could not serialize access",
+        )
+        .detail("concurrent update")
+        .position(ErrorPosition::Original(7))
+        .line(42)
+        .build();
+
+        assert_eq!(db_error.severity(), "ERROR");
+        assert_eq!(db_error.parsed_severity(), Some(Severity::Error));
+        assert_eq!(db_error.code(), &SqlState::T_R_SERIALIZATION_FAILURE);
+        assert_eq!(db_error.message(), "could not serialize access");
+        assert_eq!(db_error.detail(), Some("concurrent update"));
+        assert_eq!(db_error.position(), Some(&ErrorPosition::Original(7)));
+        assert_eq!(db_error.line(), Some(42));
+        assert_eq!(db_error.hint(), None);
+
+        let error = Error::from_db_error(db_error.clone());
+        assert_eq!(error.as_db_error(), Some(&db_error));
+        assert_eq!(error.code(), Some(&SqlState::T_R_SERIALIZATION_FAILURE));
+        assert_eq!(error.to_string(), "db error");
+    }
+
+    #[test]
+    fn classification() {
+        let error =
+            |code| Error::from_db_error(DbError::builder(Severity::Error, code, "").build());
+
+        let unique = error(SqlState::UNIQUE_VIOLATION);
+        assert!(unique.is_unique_violation());
+        assert!(!unique.is_foreign_key_violation());
+        assert!(!unique.is_retryable());
+
+        assert!(error(SqlState::FOREIGN_KEY_VIOLATION).is_foreign_key_violation());
+        assert!(error(SqlState::NOT_NULL_VIOLATION).is_not_null_violation());
+        assert!(error(SqlState::CHECK_VIOLATION).is_check_violation());
+
+        let serialization = error(SqlState::T_R_SERIALIZATION_FAILURE);
+        assert!(serialization.is_serialization_failure());
+        assert!(serialization.is_retryable());
+
+        let deadlock = error(SqlState::T_R_DEADLOCK_DETECTED);
+        assert!(deadlock.is_deadlock());
+        assert!(deadlock.is_retryable());
+
+        assert!(Error::closed().is_retryable());
+        assert!(!Error::row_count().is_retryable());
+        assert!(!Error::row_count().is_unique_violation());
+    }
+
+    #[test]
+    fn query_location() {
+        let query = "SELECT a,\r\n\tb\nFROM ä";
+
+        let location = QueryLocation::new(query, 1).unwrap();
+        assert_eq!((location.line(), location.column()), (1, 1));
+        assert_eq!(location.excerpt(), "SELECT a,\n^");
+
+        let location = QueryLocation::new(query, 13).unwrap();
+        assert_eq!((location.line(), location.column()), (2, 2));
+        assert_eq!(location.line_text(), "\tb");
+        assert_eq!(location.excerpt(), "\tb\n\t^");
+        assert_eq!(location.to_string(), "line 2, column 2");
+
+        let location = QueryLocation::new(query, 21).unwrap();
+        assert_eq!((location.line(), location.column()), (3, 7));
+        assert_eq!(location.excerpt(), "FROM ä\n      ^");
+
+        assert_eq!(QueryLocation::new(query, 0), None);
+        assert_eq!(QueryLocation::new(query, 22), None);
+
+        let position = ErrorPosition::Internal {
+            position: 3,
+            query: "a\nbc".to_string(),
+        };
+        assert_eq!(position.location("ignored").unwrap().line_text(), "bc");
+    }
 }
@@ -0,0 +1,337 @@
+//! Typed query plans from `EXPLAIN`.
+//!
+//! [`Client::explain`](crate::Client::explain) runs `EXPLAIN (FORMAT JSON)` on a statement and parses the plan into
+//! an [`Explain`], so that tools can inspect plans without parsing the text format.
+//!
+//! Requires the `explain` Cargo feature.
+
+use serde_1::Deserialize;
+use serde_json_1::{Map, Value};
+
+/// Options controlling what `EXPLAIN` reports.
+///
+/// By default, only the plan and its estimated costs are reported.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ExplainOptions {
+    analyze: bool,
+    verbose: bool,
+    costs: bool,
+    buffers: bool,
+    settings: bool,
+    wal: bool,
+    timing: Option<bool>,
+    summary: Option<bool>,
+}
+
+impl Default for ExplainOptions {
+    fn default() -> ExplainOptions {
+        ExplainOptions::new()
+    }
+}
+
+impl ExplainOptions {
+    /// Creates a new set of options with the server's defaults.
+    pub fn new() -> ExplainOptions {
+        ExplainOptions {
+            analyze: false,
+            verbose: false,
+            costs: true,
+            buffers: false,
+            settings: false,
+            wal: false,
+            timing: None,
+            summary: None,
+        }
+    }
+
+    /// Controls whether the statement is executed, reporting the actual row counts and run times of each node.
+    ///
+    /// **The statement is actually executed**, so any changes it makes are applied unless it is run in a transaction
+    /// which is rolled back.
+    ///
+    /// Defaults to `false`.
+    pub fn analyze(mut self, analyze: bool) -> ExplainOptions {
+        self.analyze = analyze;
+        self
+    }
+
+    /// Controls whether additional information, such as the output columns of each node, is reported.
+    ///
+    /// Defaults to `false`.
+    pub fn verbose(mut self, verbose: bool) -> ExplainOptions {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Controls whether the estimated costs and row counts of each node are reported.
+    ///
+    /// Defaults to `true`.
+    pub fn costs(mut self, costs: bool) -> ExplainOptions {
+        self.costs = costs;
+        self
+    }
+
+    /// Controls whether buffer usage is reported.
+    ///
+    /// Defaults to `false`.
+    pub fn buffers(mut self, buffers: bool) -> ExplainOptions {
+        self.buffers = buffers;
+        self
+    }
+
+    /// Controls whether configuration parameters which affect planning are reported.
+    ///
+    /// Requires Postgres 12 or newer. Defaults to `false`.
+    pub fn settings(mut self, settings: bool) -> ExplainOptions {
+        self.settings = settings;
+        self
+    }
+
+    /// Controls whether WAL record generation is reported. Requires [`analyze`](ExplainOptions::analyze).
+    ///
+    /// Requires Postgres 13 or newer. Defaults to `false`.
+    pub fn wal(mut self, wal: bool) -> ExplainOptions {
+        self.wal = wal;
+        self
+    }
+
+    /// Controls whether the actual run time of each node is reported when analyzing.
+    ///
+    /// Defaults to the server's default, which reports times when analyzing.
+    pub fn timing(mut self, timing: bool) -> ExplainOptions {
+        self.timing = Some(timing);
+        self
+    }
+
+    /// Controls whether the total planning and execution times are reported.
+    ///
+    /// Defaults to the server's default, which reports them when analyzing.
+    pub fn summary(mut self, summary: bool) -> ExplainOptions {
+        self.summary = Some(summary);
+        self
+    }
+
+    pub(crate) fn statement(&self, query: &str) -> String {
+        let mut options = vec!["FORMAT JSON"];
+        if self.analyze {
+            options.push("ANALYZE");
+        }
+        if self.verbose {
+            options.push("VERBOSE");
+        }
+        if !self.costs {
+            options.push("COSTS FALSE");
+        }
+        if self.buffers {
+            options.push("BUFFERS");
+        }
+        if self.settings {
+            options.push("SETTINGS");
+        }
+        if self.wal {
+            options.push("WAL");
+        }
+        match self.timing {
+            Some(true) => options.push("TIMING TRUE"),
+            Some(false) => options.push("TIMING FALSE"),
+            None => {}
+        }
+        match self.summary {
+            Some(true) => options.push("SUMMARY TRUE"),
+            Some(false) => options.push("SUMMARY FALSE"),
+            None => {}
+        }
+
+        format!("EXPLAIN ({}) {}", options.join(", "), query)
+    }
+}
+
+/// The output of `EXPLAIN` for a statement.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(crate = "serde_1")]
+pub struct Explain {
+    #[serde(rename = "Plan")]
+    plan: Plan,
+    #[serde(rename = "Planning Time")]
+    planning_time: Option<f64>,
+    #[serde(rename = "Execution Time")]
+    execution_time: Option<f64>,
+    #[serde(flatten)]
+    extra: Map<String, Value>,
+}
+
+impl Explain {
+    /// Returns the root node of the plan.
+    pub fn plan(&self) -> &Plan {
+        &self.plan
+    }
+
+    /// Returns the time taken to plan the statement in milliseconds, if it was reported.
+    pub fn planning_time(&self) -> Option<f64> {
+        self.planning_time
+    }
+
+    /// Returns the time taken to execute the statement in milliseconds, if it was analyzed.
+    pub fn execution_time(&self) -> Option<f64> {
+        self.execution_time
+    }
+
+    /// Returns another property of the output by its name in the JSON format, such as `"Triggers"` or `"Settings"`.
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.extra.get(name)
+    }
+}
+
+/// A node of a query plan.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(crate = "serde_1")]
+pub struct Plan {
+    #[serde(rename = "Node Type")]
+    node_type: String,
+    #[serde(rename = "Parent Relationship")]
+    parent_relationship: Option<String>,
+    #[serde(rename = "Relation Name")]
+    relation_name: Option<String>,
+    #[serde(rename = "Schema")]
+    schema: Option<String>,
+    #[serde(rename = "Alias")]
+    alias: Option<String>,
+    #[serde(rename = "Index Name")]
+    index_name: Option<String>,
+    #[serde(rename = "Join Type")]
+    join_type: Option<String>,
+    #[serde(rename = "Filter")]
+    filter: Option<String>,
+    #[serde(rename = "Startup Cost")]
+    startup_cost: Option<f64>,
+    #[serde(rename = "Total Cost")]
+    total_cost: Option<f64>,
+    #[serde(rename = "Plan Rows")]
+    plan_rows: Option<f64>,
+    #[serde(rename = "Plan Width")]
+    plan_width: Option<u64>,
+    #[serde(rename = "Actual Startup Time")]
+    actual_startup_time: Option<f64>,
+    #[serde(rename = "Actual Total Time")]
+    actual_total_time: Option<f64>,
+    #[serde(rename = "Actual Rows")]
+    actual_rows: Option<f64>,
+    #[serde(rename = "Actual Loops")]
+    actual_loops: Option<f64>,
+    #[serde(rename = "Output")]
+    output: Option<Vec<String>>,
+    #[serde(rename = "Plans", default)]
+    plans: Vec<Plan>,
+    #[serde(flatten)]
+    extra: Map<String, Value>,
+}
+
+impl Plan {
+    /// Returns the type of the node, such as `Seq Scan` or `Hash Join`.
+    pub fn node_type(&self) -> &str {
+        &self.node_type
+    }
+
+    /// Returns the relationship of the node to its parent, such as `Outer` or `Inner`.
+    pub fn parent_relationship(&self) -> Option<&str> {
+        self.parent_relationship.as_deref()
+    }
+
+    /// Returns the name of the table the node scans, if any.
+    pub fn relation_name(&self) -> Option<&str> {
+        self.relation_name.as_deref()
+    }
+
+    /// Returns the schema of the table the node scans, if it was reported.
+    ///
+    /// This is only reported when [`verbose`](ExplainOptions::verbose) is enabled.
+    pub fn schema(&self) -> Option<&str> {
+        self.schema.as_deref()
+    }
+
+    /// Returns the alias of the table the node scans, if any.
+    pub fn alias(&self) -> Option<&str> {
+        self.alias.as_deref()
+    }
+
+    /// Returns the name of the index the node scans, if any.
+    pub fn index_name(&self) -> Option<&str> {
+        self.index_name.as_deref()
+    }
+
+    /// Returns the type of join the node performs, such as `Inner` or `Left`, if it is a join.
+    pub fn join_type(&self) -> Option<&str> {
+        self.join_type.as_deref()
+    }
+
+    /// Returns the condition the node filters rows with, if any.
+    pub fn filter(&self) -> Option<&str> {
+        self.filter.as_deref()
+    }
+
+    /// Returns the estimated cost of producing the node's first row, if costs were reported.
+    pub fn startup_cost(&self) -> Option<f64> {
+        self.startup_cost
+    }
+
+    /// Returns the estimated cost of producing all of the node's rows, if costs were reported.
+    pub fn total_cost(&self) -> Option<f64> {
+        self.total_cost
+    }
+
+    /// Returns the estimated number of rows the node produces, if costs were reported.
+    pub fn plan_rows(&self) -> Option<f64> {
+        self.plan_rows
+    }
+
+    /// Returns the estimated average width of the node's rows in bytes, if costs were reported.
+    pub fn plan_width(&self) -> Option<u64> {
+        self.plan_width
+    }
+
+    /// Returns the time taken to produce the node's first row in milliseconds, averaged over its loops.
+    ///
+    /// This is only reported when analyzing with timing enabled.
+    pub fn actual_startup_time(&self) -> Option<f64> {
+        self.actual_startup_time
+    }
+
+    /// Returns the time taken to produce all of the node's rows in milliseconds, averaged over its loops.
+    ///
+    /// This is only reported when analyzing with timing enabled.
+    pub fn actual_total_time(&self) -> Option<f64> {
+        self.actual_total_time
+    }
+
+    /// Returns the number of rows the node produced, averaged over its loops.
+    ///
+    /// This is only reported when analyzing.
+    pub fn actual_rows(&self) -> Option<f64> {
+        self.actual_rows
+    }
+
+    /// Returns the number of times the node was executed.
+    ///
+    /// This is only reported when analyzing.
+    pub fn actual_loops(&self) -> Option<f64> {
+        self.actual_loops
+    }
+
+    /// Returns the expressions the node outputs.
+    ///
+    /// This is only reported when [`verbose`](ExplainOptions::verbose) is enabled.
+    pub fn output(&self) -> Option<&[String]> {
+        self.output.as_deref()
+    }
+
+    /// Returns the child nodes of the node.
+    pub fn plans(&self) -> &[Plan] {
+        &self.plans
+    }
+
+    /// Returns another property of the node by its name in the JSON format, such as `"Index Cond"` or
+    /// `"Shared Hit Blocks"`.
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.extra.get(name)
+    }
+}
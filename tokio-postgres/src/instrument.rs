@@ -0,0 +1,434 @@
+//! Tracing spans, metrics, and slow query reports for database operations.
+//!
+//! With the `tracing` Cargo feature enabled, connections, prepares, queries, and copies each run in a span whose
+//! fields follow the OpenTelemetry semantic conventions for database clients. The text of the statement is only
+//! recorded if the client is configured with `trace_query_text`, since it may contain sensitive literals. Without the
+//! feature, the trace types here are empty and their methods do nothing. Independently of the feature, statements
+//! are reported to the client's [`ClientMetrics`] and slow query handler, if it has any.
+
+pub(crate) use self::imp::ClientAttributes;
+use self::imp::Trace;
+use crate::client::InnerClient;
+use crate::config::SlowQueryHandler;
+use crate::{ClientMetrics, Error};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// The kinds of operation which are traced.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Operation {
+    Prepare,
+    Query,
+    Execute,
+    SimpleQuery,
+    CopyIn,
+    CopyOut,
+}
+
+/// The span of a single operation.
+pub(crate) struct OperationSpan {
+    trace: Trace,
+    hooks: Option<Hooks>,
+}
+
+/// A statement which is timed for the client's metrics or slow query hooks and hasn't completed yet.
+struct Hooks {
+    metrics: Option<Arc<dyn ClientMetrics>>,
+    slow_query: Option<(Duration, Arc<SlowQueryHandler>)>,
+    query: String,
+    start: Instant,
+    rows: u64,
+}
+
+impl Hooks {
+    fn end(self, rows: Option<u64>) {
+        let duration = self.start.elapsed();
+        if let Some(metrics) = &self.metrics {
+            metrics.query_end(&self.query, duration);
+        }
+        self.report_slow(duration, rows);
+    }
+
+    fn fail(self, error: &Error) {
+        let duration = self.start.elapsed();
+        if let Some(metrics) = &self.metrics {
+            metrics.query_error(&self.query, error, duration);
+        }
+        self.report_slow(duration, None);
+    }
+
+    fn cancel(self) {
+        let duration = self.start.elapsed();
+        if let Some(metrics) = &self.metrics {
+            metrics.query_cancelled(&self.query, duration);
+        }
+        self.report_slow(duration, None);
+    }
+
+    fn report_slow(&self, duration: Duration, rows: Option<u64>) {
+        if let Some((threshold, handler)) = &self.slow_query {
+            if duration >= *threshold {
+                handler(&self.query, duration, rows);
+            }
+        }
+    }
+}
+
+impl OperationSpan {
+    pub(crate) fn new(client: &InnerClient, operation: Operation, query: &str) -> OperationSpan {
+        let metrics = client.metrics();
+        let slow_query = client.slow_query();
+        let hooks =
+            if operation != Operation::Prepare && (metrics.is_some() || slow_query.is_some()) {
+                if let Some(metrics) = metrics {
+                    metrics.query_start(query);
+                }
+                Some(Hooks {
+                    metrics: metrics.cloned(),
+                    slow_query: slow_query.cloned(),
+                    query: query.to_string(),
+                    start: Instant::now(),
+                    rows: 0,
+                })
+            } else {
+                None
+            };
+
+        OperationSpan {
+            trace: Trace::new(client, operation, query),
+            hooks,
+        }
+    }
+
+    pub(crate) fn connect(namespace: &str, server: Option<(&str, Option<u16>)>) -> OperationSpan {
+        OperationSpan {
+            trace: Trace::connect(namespace, server),
+            hooks: None,
+        }
+    }
+
+    /// Runs a future in the span.
+    pub(crate) async fn instrument<F>(&self, f: F) -> F::Output
+    where
+        F: Future,
+    {
+        self.trace.instrument(f).await
+    }
+
+    /// Counts a row returned by the operation.
+    pub(crate) fn row(&mut self) {
+        self.trace.row();
+        if let Some(hooks) = &mut self.hooks {
+            hooks.rows += 1;
+        }
+    }
+
+    /// Records the successful completion of an operation which returns rows.
+    pub(crate) fn finish_rows(&mut self) {
+        self.trace.finish_rows();
+        if let Some(hooks) = self.hooks.take() {
+            let rows = hooks.rows;
+            hooks.end(Some(rows));
+        }
+    }
+
+    /// Records the successful completion of the operation.
+    pub(crate) fn finish(&mut self) {
+        self.trace.finish();
+        if let Some(hooks) = self.hooks.take() {
+            hooks.end(None);
+        }
+    }
+
+    /// Records the failure of the operation.
+    pub(crate) fn fail(&mut self, error: &Error) {
+        self.trace.fail(error);
+        if let Some(hooks) = self.hooks.take() {
+            hooks.fail(error);
+        }
+    }
+
+    /// Records the outcome of the operation.
+    pub(crate) fn record<T>(&mut self, result: &Result<T, Error>) {
+        match result {
+            Ok(_) => self.finish(),
+            Err(e) => self.fail(e),
+        }
+    }
+
+    /// Records the outcome of an operation which reports the number of rows it affected.
+    pub(crate) fn record_rows(&mut self, result: &Result<u64, Error>) {
+        match result {
+            Ok(rows) => {
+                self.trace.finish();
+                if let Some(hooks) = self.hooks.take() {
+                    hooks.end(Some(*rows));
+                }
+            }
+            Err(e) => self.fail(e),
+        }
+    }
+}
+
+impl Drop for OperationSpan {
+    fn drop(&mut self) {
+        // an operation which hasn't finished, such as a stream of rows dropped early, was cancelled
+        if !self.trace.is_finished() {
+            self.trace.cancel();
+        }
+        if let Some(hooks) = self.hooks.take() {
+            hooks.cancel();
+        }
+    }
+}
+
+#[cfg(feature = "tracing")]
+mod imp {
+    use super::Operation;
+    use crate::Error;
+    use crate::client::InnerClient;
+    use std::future::Future;
+    use std::sync::OnceLock;
+    use std::time::Instant;
+    use tracing::field::Empty;
+    use tracing::{Instrument, Span, info_span};
+
+    /// Attributes of a client which are attached to the spans of its operations.
+    pub(crate) struct ClientAttributes {
+        namespace: String,
+        server: OnceLock<(String, Option<u16>)>,
+    }
+
+    impl ClientAttributes {
+        pub(crate) fn new(namespace: &str) -> ClientAttributes {
+            ClientAttributes {
+                namespace: namespace.to_string(),
+                server: OnceLock::new(),
+            }
+        }
+
+        #[cfg_attr(not(feature = "runtime"), allow(dead_code))]
+        pub(crate) fn set_server(&self, address: String, port: Option<u16>) {
+            let _ = self.server.set((address, port));
+        }
+    }
+
+    // `db.query.text` is recorded separately, since it's only included when enabled.
+    macro_rules! operation_span {
+        ($name:literal, $namespace:expr, $query:expr) => {
+            info_span!(
+                $name,
+                otel.kind = "client",
+                otel.status_code = Empty,
+                db.system.name = "postgresql",
+                db.namespace = $namespace,
+                db.operation.name = operation_name($query),
+                db.query.text = Empty,
+                db.query.fingerprint = %fingerprint($query),
+                db.response.returned_rows = Empty,
+                db.response.status_code = Empty,
+                db.client.operation.duration = Empty,
+                error.type = Empty,
+                server.address = Empty,
+                server.port = Empty,
+            )
+        };
+    }
+
+    /// The tracing span of a single operation.
+    pub(crate) struct Trace {
+        span: Span,
+        start: Instant,
+        rows: u64,
+        finished: bool,
+    }
+
+    impl Trace {
+        pub(crate) fn new(client: &InnerClient, operation: Operation, query: &str) -> Trace {
+            let attributes = client.attributes();
+            let namespace = &*attributes.namespace;
+            let span = match operation {
+                Operation::Prepare => operation_span!("prepare", namespace, query),
+                Operation::Query => operation_span!("query", namespace, query),
+                Operation::Execute => operation_span!("execute", namespace, query),
+                Operation::SimpleQuery => operation_span!("simple_query", namespace, query),
+                Operation::CopyIn => operation_span!("copy_in", namespace, query),
+                Operation::CopyOut => operation_span!("copy_out", namespace, query),
+            };
+            if client.trace_query_text() {
+                span.record("db.query.text", query);
+            }
+            if let Some((address, port)) = attributes.server.get() {
+                span.record("server.address", &**address);
+                if let Some(port) = port {
+                    span.record("server.port", port);
+                }
+            }
+
+            Trace {
+                span,
+                start: Instant::now(),
+                rows: 0,
+                finished: false,
+            }
+        }
+
+        pub(crate) fn connect(namespace: &str, server: Option<(&str, Option<u16>)>) -> Trace {
+            let span = info_span!(
+                "connect",
+                otel.kind = "client",
+                otel.status_code = Empty,
+                db.system.name = "postgresql",
+                db.namespace = namespace,
+                db.client.operation.duration = Empty,
+                error.type = Empty,
+                server.address = Empty,
+                server.port = Empty,
+            );
+            if let Some((address, port)) = server {
+                span.record("server.address", address);
+                if let Some(port) = port {
+                    span.record("server.port", port);
+                }
+            }
+
+            Trace {
+                span,
+                start: Instant::now(),
+                rows: 0,
+                finished: false,
+            }
+        }
+
+        /// Runs a future in the span.
+        pub(crate) async fn instrument<F>(&self, f: F) -> F::Output
+        where
+            F: Future,
+        {
+            f.instrument(self.span.clone()).await
+        }
+
+        /// Counts a row returned by the operation.
+        pub(crate) fn row(&mut self) {
+            self.rows += 1;
+        }
+
+        /// Records the successful completion of an operation which returns rows.
+        pub(crate) fn finish_rows(&mut self) {
+            self.span.record("db.response.returned_rows", self.rows);
+            self.finish();
+        }
+
+        /// Records the successful completion of the operation.
+        pub(crate) fn finish(&mut self) {
+            self.span.record(
+                "db.client.operation.duration",
+                self.start.elapsed().as_secs_f64(),
+            );
+            self.finished = true;
+        }
+
+        pub(crate) fn is_finished(&self) -> bool {
+            self.finished
+        }
+
+        /// Records that the operation was abandoned before it completed.
+        pub(crate) fn cancel(&mut self) {
+            self.span.record("error.type", "cancelled");
+            self.finish();
+        }
+
+        /// Records the failure of the operation.
+        pub(crate) fn fail(&mut self, error: &Error) {
+            self.span.record("otel.status_code", "ERROR");
+            match error.code() {
+                Some(code) => {
+                    self.span.record("db.response.status_code", code.code());
+                    self.span.record("error.type", code.code());
+                }
+                None => {
+                    self.span.record("error.type", error.kind_name());
+                }
+            }
+            self.finish();
+        }
+    }
+
+    /// Returns the first keyword of a query, such as `SELECT`.
+    fn operation_name(query: &str) -> String {
+        query
+            .split(|c: char| !c.is_ascii_alphabetic())
+            .find(|s| !s.is_empty())
+            .unwrap_or("")
+            .to_ascii_uppercase()
+    }
+
+    /// Returns a stable hash of a query which ignores differences in whitespace.
+    ///
+    /// This is the 64-bit FNV-1a hash of the words of the query separated by single spaces.
+    fn fingerprint(query: &str) -> String {
+        let mut hash = 0xcbf29ce484222325u64;
+        for (i, word) in query.split_whitespace().enumerate() {
+            let separator = if i == 0 { &b""[..] } else { &b" "[..] };
+            for byte in separator.iter().chain(word.as_bytes()) {
+                hash ^= u64::from(*byte);
+                hash = hash.wrapping_mul(0x100000001b3);
+            }
+        }
+        format!("{hash:016x}")
+    }
+}
+
+#[cfg(not(feature = "tracing"))]
+mod imp {
+    use super::Operation;
+    use crate::Error;
+    use crate::client::InnerClient;
+    use std::future::Future;
+
+    pub(crate) struct ClientAttributes;
+
+    impl ClientAttributes {
+        pub(crate) fn new(_: &str) -> ClientAttributes {
+            ClientAttributes
+        }
+
+        #[cfg_attr(not(feature = "runtime"), allow(dead_code))]
+        pub(crate) fn set_server(&self, _: String, _: Option<u16>) {}
+    }
+
+    /// The tracing span of a single operation.
+    pub(crate) struct Trace;
+
+    impl Trace {
+        pub(crate) fn new(_: &InnerClient, _: Operation, _: &str) -> Trace {
+            Trace
+        }
+
+        pub(crate) fn connect(_: &str, _: Option<(&str, Option<u16>)>) -> Trace {
+            Trace
+        }
+
+        pub(crate) async fn instrument<F>(&self, f: F) -> F::Output
+        where
+            F: Future,
+        {
+            f.await
+        }
+
+        pub(crate) fn row(&mut self) {}
+
+        pub(crate) fn finish_rows(&mut self) {}
+
+        pub(crate) fn finish(&mut self) {}
+
+        pub(crate) fn is_finished(&self) -> bool {
+            true
+        }
+
+        pub(crate) fn cancel(&mut self) {}
+
+        pub(crate) fn fail(&mut self, _: &Error) {}
+    }
+}
@@ -104,28 +104,44 @@
 //! | Feature | Description | Extra dependencies | Default |
 //! | ------- | ----------- | ------------------ | ------- |
 //! | `runtime` | Enable convenience API for the connection process based on the `tokio` crate. | [tokio](https://crates.io/crates/tokio) 1.0 with the features `net` and `time` | yes |
+//! | `derive` | Enable `#[derive(FromRow)]`. | - | no |
+//! | `test-util` | Enable constructors for `Row`, `SimpleQueryRow`, `Statement` and `Column` for use in tests. | - | no |
+//! | `tracing` | Enable spans for connections, queries, and copies following the OpenTelemetry database semantic conventions. | [tracing](https://crates.io/crates/tracing) 0.1 | no |
+//! | `explain` | Enable [`Client::explain`] for parsed `EXPLAIN (FORMAT JSON)` plans. | [serde](https://crates.io/crates/serde) 1.0, [serde_json](https://crates.io/crates/serde_json) 1.0 | no |
 //! | `array-impls` | Enables `ToSql` and `FromSql` trait impls for arrays | - | no |
 //! | `with-bit-vec-0_6` | Enable support for the `bit-vec` crate. | [bit-vec](https://crates.io/crates/bit-vec) 0.6 | no |
 //! | `with-bit-vec-0_7` | Enable support for the `bit-vec` crate. | [bit-vec](https://crates.io/crates/bit-vec) 0.7 | no |
 //! | `with-bit-vec-0_8` | Enable support for the `bit-vec` crate. | [bit-vec](https://crates.io/crates/bit-vec) 0.8 | no |
 //! | `with-bit-vec-0_9` | Enable support for the `bit-vec` crate. | [bit-vec](https://crates.io/crates/bit-vec) 0.9 | no |
+//! | `with-bitvec-1` | Enable support for the `bitvec` crate. | [bitvec](https://crates.io/crates/bitvec) 1.0 | no |
 //! | `with-chrono-0_4` | Enable support for the `chrono` crate. | [chrono](https://crates.io/crates/chrono) 0.4 | no |
+//! | `with-chrono-tz-0_10` | Enable support for the `chrono-tz` crate. | [chrono-tz](https://crates.io/crates/chrono-tz) 0.10 | no |
 //! | `with-cidr-0_3` | Enable support for the `cidr` crate. | [cidr](https://crates.io/crates/cidr) 0.3 | no |
+//! | `with-compact_str-0_9` | Enable support for the `compact_str` crate. | [compact_str](https://crates.io/crates/compact_str) 0.9 | no |
 //! | `with-eui48-0_4` | Enable support for the 0.4 version of the `eui48` crate. This is deprecated and will be removed. | [eui48](https://crates.io/crates/eui48) 0.4 | no |
 //! | `with-eui48-1` | Enable support for the 1.0 version of the `eui48` crate. | [eui48](https://crates.io/crates/eui48) 1.0 | no |
 //! | `with-geo-types-0_6` | Enable support for the 0.6 version of the `geo-types` crate. | [geo-types](https://crates.io/crates/geo-types/0.6.0) 0.6 | no |
 //! | `with-geo-types-0_7` | Enable support for the 0.7 version of the `geo-types` crate. | [geo-types](https://crates.io/crates/geo-types/0.7.0) 0.7 | no |
+//! | `with-ipnetwork-0_21` | Enable support for the 0.21 version of the `ipnetwork` crate. | [ipnetwork](https://crates.io/crates/ipnetwork) 0.21 | no |
 //! | `with-jiff-0_1` | Enable support for the 0.1 version of the `jiff` crate. | [jiff](https://crates.io/crates/jiff/0.1.0) 0.1 | no |
 //! | `with-jiff-0_2` | Enable support for the 0.2 version of the `jiff` crate. | [jiff](https://crates.io/crates/jiff/0.2.16) 0.2 | no |
+//! | `with-macaddr-1` | Enable support for the `macaddr` crate. | [macaddr](https://crates.io/crates/macaddr) 1.0 | no |
 //! | `with-serde_json-1` | Enable support for the `serde_json` crate. | [serde_json](https://crates.io/crates/serde_json) 1.0 | no |
+//! | `with-smol_str-01` | Enable support for the `smol_str` crate. | [smol_str](https://crates.io/crates/smol_str) 0.1 | no |
+//! | `with-simd-json-0_15` | Enable support for the `simd-json` crate. | [simd-json](https://crates.io/crates/simd-json) 0.15 | no |
+//! | `with-smallvec-1` | Enable support for the `smallvec` crate. | [smallvec](https://crates.io/crates/smallvec) 1.0 | no |
 //! | `with-uuid-0_8` | Enable support for the `uuid` crate. | [uuid](https://crates.io/crates/uuid) 0.8 | no |
 //! | `with-uuid-1` | Enable support for the `uuid` crate. | [uuid](https://crates.io/crates/uuid) 1.0 | no |
 //! | `with-time-0_2` | Enable support for the 0.2 version of the `time` crate. | [time](https://crates.io/crates/time/0.2.0) 0.2 | no |
 //! | `with-time-0_3` | Enable support for the 0.3 version of the `time` crate. | [time](https://crates.io/crates/time/0.3.0) 0.3 | no |
+//! | `with-smol-2` | Enable a `smol` based implementation of the `rt::Runtime` trait. | [smol](https://crates.io/crates/smol) 2.0 | no |
+//! | `with-tokio-uring-0_4` | Enable an `io_uring` based transport on Linux. | [tokio-uring](https://crates.io/crates/tokio-uring) 0.4 | no |
+//! | `with-serde-1` | Enable `Serialize` and `Deserialize` implementations for `Config`. | [serde](https://crates.io/crates/serde) 1.0 | no |
 #![warn(rust_2018_idioms, clippy::all, missing_docs)]
 
+pub use crate::bulk_insert::BulkInsert;
 pub use crate::cancel_token::CancelToken;
-pub use crate::client::Client;
+pub use crate::client::{Client, TransactionStatus};
 pub use crate::config::Config;
 pub use crate::connection::Connection;
 pub use crate::copy_in::CopyInSink;
@@ -133,9 +149,13 @@ pub use crate::copy_out::CopyOutStream;
 use crate::error::DbError;
 pub use crate::error::Error;
 pub use crate::generic_client::GenericClient;
+pub use crate::metrics::ClientMetrics;
+pub use crate::notifications::{
+    ConnectionDriver, Notices, NotificationRouter, Notifications, Subscription,
+};
 pub use crate::portal::Portal;
 pub use crate::query::RowStream;
-pub use crate::row::{Row, SimpleQueryRow};
+pub use crate::row::{FromRow, Row, SimpleQueryRow};
 pub use crate::simple_query::{SimpleColumn, SimpleQueryStream};
 #[cfg(feature = "runtime")]
 pub use crate::socket::Socket;
@@ -146,12 +166,18 @@ pub use crate::tls::NoTls;
 pub use crate::to_statement::ToStatement;
 pub use crate::transaction::Transaction;
 pub use crate::transaction_builder::{IsolationLevel, TransactionBuilder};
+pub use crate::type_cache::TypeCache;
+pub use crate::type_registry::{CustomParam, TypeHandler, TypeKey};
 use crate::types::ToSql;
 pub use fallible_iterator;
+#[cfg(feature = "derive")]
+pub use postgres_derive::FromRow;
 use std::sync::Arc;
 
 pub mod binary_copy;
 mod bind;
+mod bound_params;
+mod bulk_insert;
 #[cfg(feature = "runtime")]
 mod cancel_query;
 mod cancel_query_raw;
@@ -161,6 +187,8 @@ mod codec;
 pub mod config;
 #[cfg(feature = "runtime")]
 mod connect;
+#[cfg(feature = "runtime")]
+mod connect_proxy;
 mod connect_raw;
 #[cfg(feature = "runtime")]
 mod connect_socket;
@@ -168,24 +196,46 @@ mod connect_tls;
 mod connection;
 mod copy_in;
 mod copy_out;
+mod debug_sql;
 pub mod error;
+#[cfg(feature = "explain")]
+pub mod explain;
 mod generic_client;
+mod instrument;
 #[cfg(not(target_arch = "wasm32"))]
 mod keepalive;
+#[cfg(feature = "runtime")]
+pub mod listener;
 mod maybe_tls_stream;
+mod metrics;
+mod notifications;
+#[cfg(feature = "runtime")]
+mod pgpass;
 mod portal;
 mod prepare;
 mod query;
 pub mod row;
+#[cfg(feature = "runtime")]
+pub mod rt;
+#[cfg(feature = "with-serde-1")]
+mod serde_1;
+mod service;
 mod simple_query;
 #[cfg(feature = "runtime")]
 mod socket;
+mod sqlcommenter;
 mod statement;
+pub mod text_copy;
+mod text_format;
 pub mod tls;
 mod to_statement;
 mod transaction;
 mod transaction_builder;
+mod type_cache;
+mod type_registry;
 pub mod types;
+#[cfg(all(feature = "with-tokio-uring-0_4", target_os = "linux"))]
+pub mod uring;
 
 /// A convenience function which parses a connection string and connects to the database.
 ///
@@ -231,6 +281,25 @@ impl Notification {
     }
 }
 
+/// A change to a runtime parameter reported by the server.
+#[derive(Clone, Debug)]
+pub struct ParameterStatus {
+    name: String,
+    value: String,
+}
+
+impl ParameterStatus {
+    /// The name of the parameter.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The new value of the parameter.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
+
 /// An asynchronous message from the server.
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone)]
@@ -244,6 +313,11 @@ pub enum AsyncMessage {
     ///
     /// Connections can subscribe to notifications with the `LISTEN` command.
     Notification(Notification),
+    /// A change to a runtime parameter.
+    ///
+    /// The server reports the new value of certain parameters (such as `TimeZone` or `application_name`) whenever
+    /// they're changed, for example by a `SET` command.
+    ParameterStatus(ParameterStatus),
 }
 
 /// Message returned by the `SimpleQuery` stream.
@@ -0,0 +1,294 @@
+//! A `LISTEN` manager which transparently reconnects.
+//!
+//! Requires the `runtime` Cargo feature (enabled by default).
+
+use crate::tls::MakeTlsConnect;
+use crate::{AsyncMessage, Client, Config, Connection, Error, Notification, Socket};
+use futures_util::{Stream, stream};
+use log::{debug, info};
+use postgres_protocol::escape::escape_identifier;
+use std::collections::{BTreeSet, VecDeque};
+use std::future::{self, Future};
+use std::pin::pin;
+use std::task::Poll;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
+
+/// An event produced by a [`Listener`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum ListenerEvent {
+    /// A notification was received on one of the subscribed channels.
+    Notification(Notification),
+    /// The listener lost its connection and has since reconnected and resubscribed to all of its channels.
+    ///
+    /// Notifications sent while the listener was disconnected have been lost, so consumers should treat this as a
+    /// gap in the notification stream and resynchronize any state that depends on it.
+    Reconnected,
+}
+
+struct Connected<S> {
+    client: Client,
+    connection: Connection<Socket, S>,
+}
+
+/// A connection dedicated to receiving notifications via `LISTEN`.
+///
+/// The listener tracks the set of channels it is subscribed to. If its connection to the server fails, it reconnects
+/// with exponential backoff, reissues `LISTEN` for every channel, and reports a [`ListenerEvent::Reconnected`] gap
+/// marker before resuming delivery of notifications.
+///
+/// The listener connects lazily the first time it is used.
+///
+/// # Examples
+///
+/// ```no_run
+/// use tokio_postgres::NoTls;
+/// use tokio_postgres::listener::{Listener, ListenerEvent};
+///
+/// # async fn f() -> Result<(), tokio_postgres::Error> {
+/// let config = "host=localhost user=postgres".parse()?;
+/// let mut listener = Listener::new(config, NoTls);
+/// listener.listen("jobs").await?;
+///
+/// loop {
+///     match listener.next().await {
+///         Ok(ListenerEvent::Notification(notification)) => println!("{}", notification.payload()),
+///         Ok(ListenerEvent::Reconnected) => println!("reconnected, some notifications may have been missed"),
+///         Ok(_) => {}
+///         Err(e) => eprintln!("listener error: {}", e),
+///     }
+/// }
+/// # }
+/// ```
+pub struct Listener<T>
+where
+    T: MakeTlsConnect<Socket>,
+{
+    config: Config,
+    tls: T,
+    channels: BTreeSet<String>,
+    connected: Option<Connected<T::Stream>>,
+    pending: VecDeque<Notification>,
+    has_connected: bool,
+    reconnected: bool,
+    min_backoff: Duration,
+    max_backoff: Duration,
+    backoff: Option<Duration>,
+}
+
+impl<T> Listener<T>
+where
+    T: MakeTlsConnect<Socket> + Clone,
+{
+    /// Creates a new listener which will connect to the database described by `config`.
+    pub fn new(config: Config, tls: T) -> Listener<T> {
+        Listener {
+            config,
+            tls,
+            channels: BTreeSet::new(),
+            connected: None,
+            pending: VecDeque::new(),
+            has_connected: false,
+            reconnected: false,
+            min_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            backoff: None,
+        }
+    }
+
+    /// Sets the range of delays between consecutive reconnection attempts.
+    ///
+    /// The first attempt after a connection failure is made immediately. After that, the delay starts at `min` and
+    /// doubles after each failed attempt up to `max`.
+    ///
+    /// Defaults to 1 second and 60 seconds.
+    pub fn reconnect_backoff(&mut self, min: Duration, max: Duration) -> &mut Listener<T> {
+        self.min_backoff = min;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Returns an iterator over the channels the listener is subscribed to.
+    pub fn channels(&self) -> impl Iterator<Item = &str> {
+        self.channels.iter().map(|s| &**s)
+    }
+
+    /// Subscribes to notifications on a channel.
+    ///
+    /// If the listener is disconnected from the server, the subscription is still recorded and will be issued
+    /// when the connection is reestablished, but the connection error is returned.
+    pub async fn listen(&mut self, channel: &str) -> Result<(), Error> {
+        if !self.channels.insert(channel.to_string()) {
+            return Ok(());
+        }
+
+        let connected = match &mut self.connected {
+            Some(connected) => connected,
+            None => return self.reconnect().await,
+        };
+
+        let query = format!("LISTEN {}", escape_identifier(channel));
+        match execute(connected, &mut self.pending, &query).await {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                if connected.client.is_closed() {
+                    self.connected = None;
+                } else {
+                    self.channels.remove(channel);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Unsubscribes from notifications on a channel.
+    pub async fn unlisten(&mut self, channel: &str) -> Result<(), Error> {
+        if !self.channels.remove(channel) {
+            return Ok(());
+        }
+
+        let Some(connected) = &mut self.connected else {
+            return Ok(());
+        };
+
+        let query = format!("UNLISTEN {}", escape_identifier(channel));
+        let result = execute(connected, &mut self.pending, &query).await;
+        if result.is_err() && connected.client.is_closed() {
+            // the subscription won't be reissued on reconnect, so there's nothing left to do
+            self.connected = None;
+            return Ok(());
+        }
+        result
+    }
+
+    /// Waits for the next event.
+    ///
+    /// If the connection to the server fails, the error is returned, and the next call will attempt to reconnect.
+    /// Failed reconnection attempts are also returned as errors, and are retried according to the
+    /// [reconnect backoff](Listener::reconnect_backoff).
+    pub async fn next(&mut self) -> Result<ListenerEvent, Error> {
+        loop {
+            if self.reconnected {
+                self.reconnected = false;
+                return Ok(ListenerEvent::Reconnected);
+            }
+
+            if let Some(notification) = self.pending.pop_front() {
+                return Ok(ListenerEvent::Notification(notification));
+            }
+
+            let connected = match &mut self.connected {
+                Some(connected) => connected,
+                None => {
+                    self.reconnect().await?;
+                    continue;
+                }
+            };
+
+            match future::poll_fn(|cx| connected.connection.poll_message(cx)).await {
+                Some(Ok(AsyncMessage::Notification(notification))) => {
+                    return Ok(ListenerEvent::Notification(notification));
+                }
+                Some(Ok(AsyncMessage::Notice(notice))) => {
+                    info!("{}: {}", notice.severity(), notice.message());
+                }
+                Some(Ok(_)) => {}
+                Some(Err(e)) => {
+                    self.connected = None;
+                    return Err(e);
+                }
+                None => self.connected = None,
+            }
+        }
+    }
+
+    /// Converts the listener into a stream of events.
+    ///
+    /// The stream never ends; errors are yielded as they occur and the listener keeps trying to reconnect.
+    pub fn into_stream(self) -> impl Stream<Item = Result<ListenerEvent, Error>> {
+        stream::unfold(self, |mut listener| async move {
+            let event = listener.next().await;
+            Some((event, listener))
+        })
+    }
+
+    async fn reconnect(&mut self) -> Result<(), Error> {
+        if let Some(backoff) = self.backoff {
+            debug!("waiting {:?} before reconnecting listener", backoff);
+            tokio::time::sleep(backoff).await;
+        }
+
+        match self.connect().await {
+            Ok(connected) => {
+                self.connected = Some(connected);
+                self.reconnected = self.has_connected;
+                self.has_connected = true;
+                self.backoff = None;
+                Ok(())
+            }
+            Err(e) => {
+                self.backoff = Some(match self.backoff {
+                    Some(backoff) => (backoff * 2).min(self.max_backoff),
+                    None => self.min_backoff,
+                });
+                Err(e)
+            }
+        }
+    }
+
+    async fn connect(&mut self) -> Result<Connected<T::Stream>, Error> {
+        let (client, connection) = self.config.connect(self.tls.clone()).await?;
+        let mut connected = Connected { client, connection };
+
+        if !self.channels.is_empty() {
+            let query = self
+                .channels
+                .iter()
+                .map(|channel| format!("LISTEN {};", escape_identifier(channel)))
+                .collect::<String>();
+            execute(&mut connected, &mut self.pending, &query).await?;
+        }
+
+        Ok(connected)
+    }
+}
+
+/// Runs a query while driving the connection, buffering any notifications received in the meantime.
+async fn execute<S>(
+    connected: &mut Connected<S>,
+    pending: &mut VecDeque<Notification>,
+    query: &str,
+) -> Result<(), Error>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let Connected { client, connection } = connected;
+    let mut query = pin!(client.batch_execute_internal(query));
+    let mut error = None;
+
+    future::poll_fn(|cx| {
+        while error.is_none() {
+            match connection.poll_message(cx) {
+                Poll::Ready(Some(Ok(AsyncMessage::Notification(notification)))) => {
+                    pending.push_back(notification)
+                }
+                Poll::Ready(Some(Ok(_))) => {}
+                Poll::Ready(Some(Err(e))) => error = Some(e),
+                Poll::Ready(None) => error = Some(Error::closed()),
+                Poll::Pending => break,
+            }
+        }
+
+        match query.as_mut().poll(cx) {
+            // prefer the connection's error over the client's less specific one
+            Poll::Ready(Err(e)) => Poll::Ready(Err(error.take().unwrap_or(e))),
+            Poll::Ready(Ok(())) => Poll::Ready(Ok(())),
+            Poll::Pending => match error.take() {
+                Some(e) => Poll::Ready(Err(e)),
+                None => Poll::Pending,
+            },
+        }
+    })
+    .await
+}
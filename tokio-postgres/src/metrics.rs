@@ -0,0 +1,141 @@
+//! Metrics hooks.
+
+use crate::Error;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Callbacks reporting the activity of a client, for export to a metrics system such as Prometheus or StatsD.
+///
+/// An implementation is installed with [`Config::metrics`](crate::Config::metrics) and is shared by the client and
+/// connection of every connection made with that configuration. Every method has an empty default implementation, so
+/// implementations only need to override the events they are interested in.
+///
+/// The trait is implemented for `Arc<T>`, so an implementation can be installed while keeping a handle to read its
+/// values back.
+///
+/// The callbacks are invoked inline while requests are processed, so they should be cheap and must not block. Typical
+/// implementations increment counters or record observations in histograms.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::atomic::{AtomicU64, Ordering};
+/// use std::time::Duration;
+/// use tokio_postgres::{ClientMetrics, Config, Error};
+///
+/// #[derive(Default)]
+/// struct Counters {
+///     queries: AtomicU64,
+///     errors: AtomicU64,
+/// }
+///
+/// impl ClientMetrics for Counters {
+///     fn query_end(&self, _: &str, _: Duration) {
+///         self.queries.fetch_add(1, Ordering::Relaxed);
+///     }
+///
+///     fn query_error(&self, _: &str, _: &Error, _: Duration) {
+///         self.errors.fetch_add(1, Ordering::Relaxed);
+///     }
+/// }
+///
+/// let mut config = Config::new();
+/// config.metrics(Counters::default());
+/// ```
+pub trait ClientMetrics: Send + Sync {
+    /// Called when a statement is sent to the server.
+    ///
+    /// This includes simple queries and `COPY` statements, as well as the catalog queries the client makes to look up
+    /// user-defined types. Preparing a statement is not reported.
+    fn query_start(&self, query: &str) {
+        let _ = query;
+    }
+
+    /// Called when a statement completes successfully.
+    ///
+    /// For statements which return rows, this is when the last row is received.
+    fn query_end(&self, query: &str, duration: Duration) {
+        let _ = (query, duration);
+    }
+
+    /// Called when a statement fails.
+    ///
+    /// Each statement reported to [`query_start`](ClientMetrics::query_start) is followed by exactly one call to
+    /// either this method, [`query_end`](ClientMetrics::query_end), or
+    /// [`query_cancelled`](ClientMetrics::query_cancelled).
+    fn query_error(&self, query: &str, error: &Error, duration: Duration) {
+        let _ = (query, error, duration);
+    }
+
+    /// Called when a statement is abandoned before it completes.
+    ///
+    /// This happens when the future running the statement, the stream of its rows, or the sink of a
+    /// `COPY ... FROM STDIN` statement is dropped early.
+    fn query_cancelled(&self, query: &str, duration: Duration) {
+        let _ = (query, duration);
+    }
+
+    /// Called when protocol messages are written to the connection's socket buffer.
+    ///
+    /// Bytes exchanged while negotiating TLS are not included.
+    fn bytes_sent(&self, bytes: usize) {
+        let _ = bytes;
+    }
+
+    /// Called when protocol messages are read from the connection's socket.
+    ///
+    /// Bytes exchanged while negotiating TLS are not included.
+    fn bytes_received(&self, bytes: usize) {
+        let _ = bytes;
+    }
+
+    /// Called when a statement is found in a cache of prepared statements.
+    ///
+    /// The client itself doesn't cache statements; this is reported by caches built on top of it, such as the one in
+    /// `postgres-pool`, through [`Client::metrics`](crate::Client::metrics).
+    fn prepare_cache_hit(&self, query: &str) {
+        let _ = query;
+    }
+
+    /// Called when a statement is not found in a cache of prepared statements and has to be prepared.
+    fn prepare_cache_miss(&self, query: &str) {
+        let _ = query;
+    }
+}
+
+impl<T> ClientMetrics for Arc<T>
+where
+    T: ClientMetrics + ?Sized,
+{
+    fn query_start(&self, query: &str) {
+        (**self).query_start(query)
+    }
+
+    fn query_end(&self, query: &str, duration: Duration) {
+        (**self).query_end(query, duration)
+    }
+
+    fn query_error(&self, query: &str, error: &Error, duration: Duration) {
+        (**self).query_error(query, error, duration)
+    }
+
+    fn query_cancelled(&self, query: &str, duration: Duration) {
+        (**self).query_cancelled(query, duration)
+    }
+
+    fn bytes_sent(&self, bytes: usize) {
+        (**self).bytes_sent(bytes)
+    }
+
+    fn bytes_received(&self, bytes: usize) {
+        (**self).bytes_received(bytes)
+    }
+
+    fn prepare_cache_hit(&self, query: &str) {
+        (**self).prepare_cache_hit(query)
+    }
+
+    fn prepare_cache_miss(&self, query: &str) {
+        (**self).prepare_cache_miss(query)
+    }
+}
@@ -0,0 +1,243 @@
+use crate::client::SessionState;
+use crate::error::DbError;
+use crate::{AsyncMessage, Connection, Error, Notification};
+use futures_channel::mpsc;
+use futures_util::{Stream, StreamExt};
+use log::info;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::{Pin, pin};
+use std::sync::Arc;
+use std::task::{Context, Poll, ready};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::sync::mpsc as bounded;
+
+/// A future which drives a [`Connection`] which has been split with [`Connection::split`].
+///
+/// It behaves like the `Connection` itself, except that notifications received from the server are forwarded to the
+/// associated [`Notifications`] stream.
+#[must_use = "futures do nothing unless polled"]
+pub struct ConnectionDriver<S, T> {
+    connection: Connection<S, T>,
+    sender: mpsc::UnboundedSender<(u64, Notification)>,
+}
+
+impl<S, T> ConnectionDriver<S, T> {
+    pub(crate) fn new(
+        connection: Connection<S, T>,
+        sender: mpsc::UnboundedSender<(u64, Notification)>,
+    ) -> ConnectionDriver<S, T> {
+        ConnectionDriver { connection, sender }
+    }
+}
+
+impl<S, T> Future for ConnectionDriver<S, T>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+    T: AsyncRead + AsyncWrite + Unpin,
+{
+    type Output = Result<(), Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        while let Some(message) = ready!(self.connection.poll_message(cx)?) {
+            match message {
+                AsyncMessage::Notice(notice) => {
+                    info!("{}: {}", notice.severity(), notice.message());
+                }
+                AsyncMessage::Notification(notification) => {
+                    // the notifications stream may have been dropped, in which case we just discard them
+                    let resets = self.connection.session().resets();
+                    let _ = self.sender.unbounded_send((resets, notification));
+                }
+                _ => {}
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// A stream of asynchronous notifications received by a [`Connection`].
+///
+/// Created by [`Connection::split`]. Notifications are buffered without limit until they are read, and those still
+/// buffered when the session is reset with [`Client::reset`](crate::Client::reset) are discarded. The stream ends
+/// once the associated [`ConnectionDriver`] has completed.
+pub struct Notifications {
+    // each notification is paired with the number of times the session had been reset when it was received
+    receiver: mpsc::UnboundedReceiver<(u64, Notification)>,
+    session: Arc<SessionState>,
+}
+
+impl Notifications {
+    pub(crate) fn new(
+        receiver: mpsc::UnboundedReceiver<(u64, Notification)>,
+        session: Arc<SessionState>,
+    ) -> Notifications {
+        Notifications { receiver, session }
+    }
+}
+
+impl Stream for Notifications {
+    type Item = Notification;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Notification>> {
+        loop {
+            match ready!(self.receiver.poll_next_unpin(cx)) {
+                Some((resets, _)) if resets != self.session.resets() => {}
+                item => return Poll::Ready(item.map(|(_, notification)| notification)),
+            }
+        }
+    }
+}
+
+/// A stream of notices received by a [`Connection`].
+///
+/// Created by [`Connection::notices`].
+#[derive(Debug)]
+pub struct Notices {
+    receiver: mpsc::Receiver<DbError>,
+}
+
+impl Notices {
+    pub(crate) fn new(receiver: mpsc::Receiver<DbError>) -> Notices {
+        Notices { receiver }
+    }
+}
+
+impl Stream for Notices {
+    type Item = DbError;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<DbError>> {
+        self.receiver.poll_next_unpin(cx)
+    }
+}
+
+/// A dispatcher which routes notifications to independent subscribers by channel.
+///
+/// This allows a single `LISTEN` connection to serve many consumers in an application. Each call to
+/// [`subscribe`](NotificationRouter::subscribe) returns a bounded stream of the notifications for one channel, and
+/// [`run`](NotificationRouter::run) feeds the router from a source of notifications such as [`Notifications`].
+///
+/// The router does not issue `LISTEN` commands itself; that is the responsibility of the owner of the connection.
+///
+/// Routers are cheap to clone; all clones share the same set of subscribers.
+#[derive(Clone)]
+pub struct NotificationRouter {
+    routes: Arc<Mutex<HashMap<String, Vec<bounded::Sender<Notification>>>>>,
+    capacity: usize,
+}
+
+impl NotificationRouter {
+    /// Creates a new router, where each subscriber buffers up to `capacity` notifications.
+    ///
+    /// A subscriber with a full buffer applies backpressure to the router, delaying delivery to all other
+    /// subscribers until it catches up. A `capacity` of 0 is treated as 1.
+    pub fn new(capacity: usize) -> NotificationRouter {
+        NotificationRouter {
+            routes: Arc::new(Mutex::new(HashMap::new())),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Registers a new subscriber for notifications on a channel.
+    ///
+    /// Once the returned stream is dropped, the subscriber is removed from the router the next time a notification is
+    /// dispatched on its channel. This does not affect the connection: the channel stays registered on the server
+    /// until the owner of the connection issues `UNLISTEN`, and notifications on it are discarded by the router in the
+    /// meantime.
+    pub fn subscribe(&self, channel: &str) -> Subscription {
+        let (sender, receiver) = bounded::channel(self.capacity);
+        self.routes
+            .lock()
+            .entry(channel.to_string())
+            .or_default()
+            .push(sender);
+        Subscription { receiver }
+    }
+
+    /// Delivers a notification to every subscriber of its channel.
+    ///
+    /// This waits for space in the buffer of each subscriber. Notifications on channels without any subscribers are
+    /// discarded.
+    pub async fn dispatch(&self, notification: Notification) {
+        // clones of a sender share the capacity of its channel, so this can't overrun a subscriber's buffer
+        let senders = match self.routes.lock().get(notification.channel()) {
+            Some(senders) => senders.clone(),
+            None => return,
+        };
+
+        let mut closed = false;
+        for sender in senders {
+            closed |= sender.send(notification.clone()).await.is_err();
+        }
+
+        if closed {
+            let mut routes = self.routes.lock();
+            if let Some(senders) = routes.get_mut(notification.channel()) {
+                senders.retain(|s| !s.is_closed());
+                if senders.is_empty() {
+                    routes.remove(notification.channel());
+                }
+            }
+        }
+    }
+
+    /// Dispatches every notification from a stream until it ends.
+    pub async fn run<S>(&self, notifications: S)
+    where
+        S: Stream<Item = Notification>,
+    {
+        let mut notifications = pin!(notifications);
+        while let Some(notification) = notifications.next().await {
+            self.dispatch(notification).await;
+        }
+    }
+}
+
+/// A stream of notifications for a single channel, created by [`NotificationRouter::subscribe`].
+///
+/// The stream ends once every clone of the router has been dropped.
+pub struct Subscription {
+    receiver: bounded::Receiver<Notification>,
+}
+
+impl Stream for Subscription {
+    type Item = Notification;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Notification>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NotificationRouter;
+    use crate::Notification;
+    use futures_util::{FutureExt, StreamExt};
+
+    fn notification(payload: &str) -> Notification {
+        Notification {
+            process_id: 0,
+            channel: "jobs".to_string(),
+            payload: payload.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn dispatch_waits_for_full_subscriber() {
+        let router = NotificationRouter::new(2);
+        let mut jobs = router.subscribe("jobs");
+
+        router.dispatch(notification("1")).await;
+        router.dispatch(notification("2")).await;
+        let mut third = Box::pin(router.dispatch(notification("3")));
+        assert!((&mut third).now_or_never().is_none());
+
+        assert_eq!(jobs.next().await.unwrap().payload(), "1");
+        third.await;
+        drop(router);
+
+        let payloads = jobs.map(|n| n.payload().to_string()).collect::<Vec<_>>();
+        assert_eq!(payloads.await, ["2", "3"]);
+    }
+}
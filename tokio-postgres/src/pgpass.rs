@@ -0,0 +1,290 @@
+//! Password lookup from libpq's password file.
+
+use crate::config::Host;
+use crate::{Config, rt};
+use log::{debug, warn};
+use std::env;
+use std::fs::{self, Metadata};
+use std::io;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+
+/// The directories in which the server listens on a Unix socket by default.
+///
+/// libpq's compiled-in default is `/tmp`, but distributions commonly change it to `/var/run/postgresql`.
+#[cfg(unix)]
+const DEFAULT_SOCKET_DIRS: &[&str] = &["/tmp", "/var/run/postgresql", "/run/postgresql"];
+
+/// The contents of the password file.
+pub(crate) struct PassFile {
+    path: PathBuf,
+    contents: String,
+}
+
+impl PassFile {
+    /// Reads the password file.
+    ///
+    /// The file is read with Tokio's asynchronous filesystem API, or on the blocking thread pool of a custom
+    /// [`Runtime`](crate::rt::Runtime).
+    pub(crate) async fn load(config: &Config) -> Option<PassFile> {
+        let path = passfile(config)?;
+        match config.get_runtime() {
+            Some(runtime) => {
+                rt::spawn_blocking(runtime, move || {
+                    let contents = read(&path)?;
+                    Some(PassFile { path, contents })
+                })
+                .await
+            }
+            None => {
+                let contents = read_async(&path).await?;
+                Some(PassFile { path, contents })
+            }
+        }
+    }
+
+    /// Looks up the password for a host.
+    pub(crate) fn password(
+        &self,
+        config: &Config,
+        host: Option<&Host>,
+        hostaddr: Option<&IpAddr>,
+        port: u16,
+    ) -> Option<Vec<u8>> {
+        let user = match &config.user {
+            Some(user) => user.clone(),
+            None => whoami::username().ok()?,
+        };
+        let dbname = config.dbname.as_deref().unwrap_or(&user);
+        let host = host_name(host, hostaddr);
+
+        let password = find(&self.contents, &host, port, dbname, &user)?;
+        debug!("using password from {}", self.path.display());
+        Some(password.into_bytes())
+    }
+}
+
+/// Returns the name a host is matched by in the password file.
+///
+/// As in libpq, a Unix socket in a default socket directory is matched as `localhost`.
+fn host_name(host: Option<&Host>, hostaddr: Option<&IpAddr>) -> String {
+    match (host, hostaddr) {
+        (Some(Host::Tcp(host)), _) if !host.is_empty() => host.clone(),
+        #[cfg(unix)]
+        (Some(Host::Unix(path)), _) => {
+            if DEFAULT_SOCKET_DIRS.iter().any(|dir| path == Path::new(dir)) {
+                "localhost".to_string()
+            } else {
+                path.to_string_lossy().into_owned()
+            }
+        }
+        (_, Some(hostaddr)) => hostaddr.to_string(),
+        _ => "localhost".to_string(),
+    }
+}
+
+fn passfile(config: &Config) -> Option<PathBuf> {
+    if let Some(path) = &config.passfile {
+        return Some(path.clone());
+    }
+    if let Some(path) = env::var_os("PGPASSFILE") {
+        return Some(PathBuf::from(path));
+    }
+
+    #[cfg(windows)]
+    let path = PathBuf::from(env::var_os("APPDATA")?)
+        .join("postgresql")
+        .join("pgpass.conf");
+    #[cfg(not(windows))]
+    let path = PathBuf::from(env::var_os("HOME")?).join(".pgpass");
+    Some(path)
+}
+
+fn read(path: &Path) -> Option<String> {
+    let metadata = fs::metadata(path);
+    if !check(path, metadata) {
+        return None;
+    }
+
+    read_result(path, fs::read_to_string(path))
+}
+
+async fn read_async(path: &Path) -> Option<String> {
+    let metadata = tokio::fs::metadata(path).await;
+    if !check(path, metadata) {
+        return None;
+    }
+
+    read_result(path, tokio::fs::read_to_string(path).await)
+}
+
+/// Returns whether the password file exists and is safe to read.
+fn check(path: &Path, metadata: io::Result<Metadata>) -> bool {
+    let metadata = match metadata {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return false,
+        Err(e) => {
+            warn!("unable to read password file {}: {}", path.display(), e);
+            return false;
+        }
+    };
+
+    if !metadata.is_file() {
+        warn!("password file {} is not a plain file", path.display());
+        return false;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        if metadata.permissions().mode() & 0o077 != 0 {
+            warn!(
+                "password file {} has group or world access; permissions should be u=rw (0600) or less",
+                path.display()
+            );
+            return false;
+        }
+    }
+
+    true
+}
+
+fn read_result(path: &Path, contents: io::Result<String>) -> Option<String> {
+    match contents {
+        Ok(contents) => Some(contents),
+        Err(e) => {
+            warn!("unable to read password file {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// Finds the password in the first entry matching the connection parameters.
+///
+/// Entries have the form `hostname:port:database:username:password`. Any of the first four fields may be `*` to match
+/// anything, and `:` and `\` are escaped with a backslash.
+fn find(contents: &str, host: &str, port: u16, dbname: &str, user: &str) -> Option<String> {
+    let port = port.to_string();
+
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+        .find_map(|line| {
+            let mut fields = Fields(line);
+            for expected in [host, &port, dbname, user] {
+                if !fields.next_matches(expected)? {
+                    return None;
+                }
+            }
+            Some(fields.rest())
+        })
+}
+
+struct Fields<'a>(&'a str);
+
+impl Fields<'_> {
+    /// Consumes the next field, returning whether it matches a value, or `None` if there are no more fields.
+    fn next_matches(&mut self, value: &str) -> Option<bool> {
+        let mut chars = self.0.char_indices();
+        let mut field = String::new();
+        let mut escaped = false;
+        let end = loop {
+            let (i, c) = chars.next()?;
+            match c {
+                '\\' => {
+                    if let Some((_, c)) = chars.next() {
+                        field.push(c);
+                        escaped = true;
+                    }
+                }
+                ':' => break i,
+                c => field.push(c),
+            }
+        };
+        let wildcard = field == "*" && !escaped;
+
+        self.0 = &self.0[end + 1..];
+        Some(wildcard || field == value)
+    }
+
+    /// Returns the password field, unescaped.
+    fn rest(self) -> String {
+        let mut rest = String::new();
+        let mut chars = self.0.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => rest.extend(chars.next()),
+                ':' => break,
+                c => rest.push(c),
+            }
+        }
+        rest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find, host_name};
+    use crate::config::Host;
+
+    #[test]
+    fn test_find() {
+        let contents = "\
+# a comment
+db.example.com:5432:app:alice:first
+*:5433:*:bob:second\\:half
+host\\:colon:*:*:carol:third
+*:*:*:\\*:fourth
+*:*:*:*:fallback:ignored
+";
+
+        let check = |host, port, dbname, user| find(contents, host, port, dbname, user);
+        assert_eq!(
+            check("db.example.com", 5432, "app", "alice").as_deref(),
+            Some("first")
+        );
+        assert_eq!(
+            check("localhost", 5433, "postgres", "bob").as_deref(),
+            Some("second:half")
+        );
+        assert_eq!(
+            check("host:colon", 5432, "postgres", "carol").as_deref(),
+            Some("third")
+        );
+        assert_eq!(
+            check("localhost", 5432, "postgres", "*").as_deref(),
+            Some("fourth")
+        );
+        assert_eq!(
+            check("db.example.com", 5432, "other", "alice").as_deref(),
+            Some("fallback")
+        );
+        assert_eq!(find("*:*:*:*", "localhost", 5432, "postgres", "dave"), None);
+        assert_eq!(
+            find("*:*:*:\\*:x", "localhost", 5432, "postgres", "dave"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_host_name() {
+        let tcp = Host::Tcp("db.example.com".to_string());
+        assert_eq!(host_name(Some(&tcp), None), "db.example.com");
+        let addr = "10.0.0.1".parse().unwrap();
+        assert_eq!(
+            host_name(Some(&Host::Tcp(String::new())), Some(&addr)),
+            "10.0.0.1"
+        );
+        assert_eq!(host_name(None, None), "localhost");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unix_host_name() {
+        let default = Host::Unix("/var/run/postgresql/".into());
+        assert_eq!(host_name(Some(&default), None), "localhost");
+        let other = Host::Unix("/srv/pg".into());
+        assert_eq!(host_name(Some(&other), None), "/srv/pg");
+    }
+}
@@ -2,6 +2,7 @@ use crate::client::InnerClient;
 use crate::codec::FrontendMessage;
 use crate::connection::RequestMessages;
 use crate::error::SqlState;
+use crate::instrument::{Operation, OperationSpan};
 use crate::types::{Field, Kind, Oid, Type};
 use crate::{Column, Error, Statement};
 use crate::{query, slice_iter};
@@ -11,6 +12,7 @@ use futures_util::TryStreamExt;
 use log::debug;
 use postgres_protocol::message::backend::Message;
 use postgres_protocol::message::frontend;
+use std::borrow::Cow;
 use std::future::Future;
 use std::pin::{Pin, pin};
 use std::sync::Arc;
@@ -58,13 +60,29 @@ ORDER BY attnum
 
 static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
 
+/// Prepares a statement, appending the configured sqlcommenter tags to its text if `comment` is set.
 pub async fn prepare(
     client: &Arc<InnerClient>,
     query: &str,
     types: &[Type],
+    comment: bool,
+) -> Result<Statement, Error> {
+    let mut span = OperationSpan::new(client, Operation::Prepare, query);
+    let result = span
+        .instrument(prepare_inner(client, query, types, comment))
+        .await;
+    span.record(&result);
+    result.map_err(|e| client.query_error(e, query))
+}
+
+async fn prepare_inner(
+    client: &Arc<InnerClient>,
+    query: &str,
+    types: &[Type],
+    comment: bool,
 ) -> Result<Statement, Error> {
     let name = format!("s{}", NEXT_ID.fetch_add(1, Ordering::SeqCst));
-    let buf = encode(client, &name, query, types)?;
+    let buf = encode(client, &name, query, types, comment)?;
     let mut responses = client.send(RequestMessages::Single(FrontendMessage::Raw(buf)))?;
 
     match responses.next().await? {
@@ -106,7 +124,13 @@ pub async fn prepare(
         }
     }
 
-    Ok(Statement::new(client, name, parameters, columns))
+    Ok(Statement::new(
+        client,
+        name,
+        query.to_string(),
+        parameters,
+        columns,
+    ))
 }
 
 fn prepare_rec<'a>(
@@ -114,10 +138,16 @@ fn prepare_rec<'a>(
     query: &'a str,
     types: &'a [Type],
 ) -> Pin<Box<dyn Future<Output = Result<Statement, Error>> + 'a + Send>> {
-    Box::pin(prepare(client, query, types))
+    Box::pin(prepare(client, query, types, false))
 }
 
-fn encode(client: &InnerClient, name: &str, query: &str, types: &[Type]) -> Result<Bytes, Error> {
+fn encode(
+    client: &InnerClient,
+    name: &str,
+    query: &str,
+    types: &[Type],
+    comment: bool,
+) -> Result<Bytes, Error> {
     if types.is_empty() {
         debug!("preparing query {name}: {query}");
     } else {
@@ -125,7 +155,12 @@ fn encode(client: &InnerClient, name: &str, query: &str, types: &[Type]) -> Resu
     }
 
     client.with_buf(|buf| {
-        frontend::parse(name, query, types.iter().map(Type::oid), buf).map_err(Error::encode)?;
+        let query = if comment {
+            client.comment_query(query)
+        } else {
+            Cow::Borrowed(query)
+        };
+        frontend::parse(name, &query, types.iter().map(Type::oid), buf).map_err(Error::encode)?;
         frontend::describe(b'S', name, buf).map_err(Error::encode)?;
         frontend::sync(buf);
         Ok(buf.split().freeze())
@@ -180,7 +215,7 @@ pub(crate) async fn get_type(client: &Arc<InnerClient>, oid: Oid) -> Result<Type
     };
 
     let type_ = Type::new(name, oid, kind, schema);
-    client.set_type(oid, &type_);
+    client.set_type(&type_);
 
     Ok(type_)
 }
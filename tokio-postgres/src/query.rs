@@ -1,6 +1,8 @@
+use crate::bound_params::BoundParams;
 use crate::client::{InnerClient, Responses};
 use crate::codec::FrontendMessage;
 use crate::connection::RequestMessages;
+use crate::instrument::{Operation, OperationSpan};
 use crate::prepare::get_type;
 use crate::types::{BorrowToSql, IsNull};
 use crate::{Column, Error, Portal, Row, Statement};
@@ -40,23 +42,27 @@ where
     I: IntoIterator<Item = P>,
     I::IntoIter: ExactSizeIterator,
 {
-    let buf = if log_enabled!(Level::Debug) {
-        let params = params.into_iter().collect::<Vec<_>>();
-        debug!(
-            "executing statement {} with parameters: {:?}",
-            statement.name(),
-            BorrowToSqlParamsDebug(params.as_slice()),
-        );
-        encode(client, &statement, params)?
-    } else {
-        encode(client, &statement, params)?
+    let mut span = OperationSpan::new(client, Operation::Query, statement.query());
+    let (buf, params) = encode_logged(client, &statement, params);
+    let result = match buf {
+        Ok(buf) => span.instrument(start(client, buf)).await,
+        Err(e) => Err(e),
     };
-    let responses = start(client, buf).await?;
-    Ok(RowStream {
-        statement,
-        responses,
-        rows_affected: None,
-    })
+
+    match result {
+        Ok(responses) => Ok(RowStream {
+            statement,
+            responses,
+            rows_affected: None,
+            query_in_errors: client.query_in_errors(),
+            params,
+            span,
+        }),
+        Err(e) => {
+            span.fail(&e);
+            Err(client.annotate_error(e, statement.query(), params.as_ref()))
+        }
+    }
 }
 
 pub async fn query_typed<P, I>(
@@ -68,33 +74,44 @@ where
     P: BorrowToSql,
     I: IntoIterator<Item = (P, Type)>,
 {
-    let buf = {
-        let params = params.into_iter().collect::<Vec<_>>();
-        let param_oids = params.iter().map(|(_, t)| t.oid()).collect::<Vec<_>>();
-
-        client.with_buf(|buf| {
-            frontend::parse("", query, param_oids, buf).map_err(Error::parse)?;
-            encode_bind_raw("", params, "", buf)?;
-            frontend::describe(b'S', "", buf).map_err(Error::encode)?;
-            frontend::execute("", 0, buf).map_err(Error::encode)?;
-            frontend::sync(buf);
-
-            Ok(buf.split().freeze())
-        })?
+    let mut span = OperationSpan::new(client, Operation::Query, query);
+    let (buf, params) = encode_typed(client, query, params);
+    let result = match buf {
+        Ok(buf) => span.instrument(start_typed(client, buf)).await,
+        Err(e) => Err(e),
     };
 
+    match result {
+        Ok((responses, columns)) => Ok(RowStream {
+            statement: Statement::unnamed(
+                client.type_registry().clone(),
+                query.to_string(),
+                vec![],
+                columns,
+            ),
+            responses,
+            rows_affected: None,
+            query_in_errors: client.query_in_errors(),
+            params,
+            span,
+        }),
+        Err(e) => {
+            span.fail(&e);
+            Err(client.annotate_error(e, query, params.as_ref()))
+        }
+    }
+}
+
+async fn start_typed(
+    client: &Arc<InnerClient>,
+    buf: Bytes,
+) -> Result<(Responses, Vec<Column>), Error> {
     let mut responses = client.send(RequestMessages::Single(FrontendMessage::Raw(buf)))?;
 
     loop {
         match responses.next().await? {
             Message::ParseComplete | Message::BindComplete | Message::ParameterDescription(_) => {}
-            Message::NoData => {
-                return Ok(RowStream {
-                    statement: Statement::unnamed(vec![], vec![]),
-                    responses,
-                    rows_affected: None,
-                });
-            }
+            Message::NoData => return Ok((responses, vec![])),
             Message::RowDescription(row_description) => {
                 let mut columns: Vec<Column> = vec![];
                 let mut it = row_description.fields();
@@ -109,11 +126,7 @@ where
                     };
                     columns.push(column);
                 }
-                return Ok(RowStream {
-                    statement: Statement::unnamed(vec![], columns),
-                    responses,
-                    rows_affected: None,
-                });
+                return Ok((responses, columns));
             }
             _ => return Err(Error::unexpected_message()),
         }
@@ -129,21 +142,17 @@ where
     P: BorrowToSql,
     I: IntoIterator<Item = (P, Type)>,
 {
-    let buf = {
-        let params = params.into_iter().collect::<Vec<_>>();
-        let param_oids = params.iter().map(|(_, t)| t.oid()).collect::<Vec<_>>();
-
-        client.with_buf(|buf| {
-            frontend::parse("", query, param_oids, buf).map_err(Error::parse)?;
-            encode_bind_raw("", params, "", buf)?;
-            frontend::describe(b'S', "", buf).map_err(Error::encode)?;
-            frontend::execute("", 0, buf).map_err(Error::encode)?;
-            frontend::sync(buf);
-
-            Ok(buf.split().freeze())
-        })?
+    let mut span = OperationSpan::new(client, Operation::Execute, query);
+    let (buf, params) = encode_typed(client, query, params);
+    let result = match buf {
+        Ok(buf) => span.instrument(execute_typed_inner(client, buf)).await,
+        Err(e) => Err(e),
     };
+    span.record_rows(&result);
+    result.map_err(|e| client.annotate_error(e, query, params.as_ref()))
+}
 
+async fn execute_typed_inner(client: &InnerClient, buf: Bytes) -> Result<u64, Error> {
     let mut responses = client.send(RequestMessages::Single(FrontendMessage::Raw(buf)))?;
 
     let mut rows = 0;
@@ -177,19 +186,29 @@ pub async fn query_portal(
     portal: &Portal,
     max_rows: i32,
 ) -> Result<RowStream, Error> {
-    let buf = client.with_buf(|buf| {
-        frontend::execute(portal.name(), max_rows, buf).map_err(Error::encode)?;
-        frontend::sync(buf);
-        Ok(buf.split().freeze())
-    })?;
-
-    let responses = client.send(RequestMessages::Single(FrontendMessage::Raw(buf)))?;
-
-    Ok(RowStream {
-        statement: portal.statement().clone(),
-        responses,
-        rows_affected: None,
-    })
+    let mut span = OperationSpan::new(client, Operation::Query, portal.statement().query());
+    let result = client
+        .with_buf(|buf| {
+            frontend::execute(portal.name(), max_rows, buf).map_err(Error::encode)?;
+            frontend::sync(buf);
+            Ok(buf.split().freeze())
+        })
+        .and_then(|buf| client.send(RequestMessages::Single(FrontendMessage::Raw(buf))));
+
+    match result {
+        Ok(responses) => Ok(RowStream {
+            statement: portal.statement().clone(),
+            responses,
+            rows_affected: None,
+            query_in_errors: client.query_in_errors(),
+            params: None,
+            span,
+        }),
+        Err(e) => {
+            span.fail(&e);
+            Err(e)
+        }
+    }
 }
 
 /// Extract the number of rows affected from [`CommandCompleteBody`].
@@ -215,17 +234,17 @@ where
     I: IntoIterator<Item = P>,
     I::IntoIter: ExactSizeIterator,
 {
-    let buf = if log_enabled!(Level::Debug) {
-        let params = params.into_iter().collect::<Vec<_>>();
-        debug!(
-            "executing statement {} with parameters: {:?}",
-            statement.name(),
-            BorrowToSqlParamsDebug(params.as_slice()),
-        );
-        encode(client, &statement, params)?
-    } else {
-        encode(client, &statement, params)?
+    let mut span = OperationSpan::new(client, Operation::Execute, statement.query());
+    let (buf, params) = encode_logged(client, &statement, params);
+    let result = match buf {
+        Ok(buf) => span.instrument(execute_inner(client, buf)).await,
+        Err(e) => Err(e),
     };
+    span.record_rows(&result);
+    result.map_err(|e| client.annotate_error(e, statement.query(), params.as_ref()))
+}
+
+async fn execute_inner(client: &InnerClient, buf: Bytes) -> Result<u64, Error> {
     let mut responses = start(client, buf).await?;
 
     let mut rows = 0;
@@ -242,6 +261,71 @@ where
     }
 }
 
+pub async fn execute_many<I, J, P>(
+    client: &InnerClient,
+    statement: Statement,
+    param_sets: I,
+) -> Result<Vec<u64>, Error>
+where
+    I: IntoIterator<Item = J>,
+    J: IntoIterator<Item = P>,
+    J::IntoIter: ExactSizeIterator,
+    P: BorrowToSql,
+{
+    let mut span = OperationSpan::new(client, Operation::Execute, statement.query());
+    let result = match encode_many(client, &statement, param_sets) {
+        Ok(Some(buf)) => span.instrument(execute_many_inner(client, buf)).await,
+        Ok(None) => Ok(vec![]),
+        Err(e) => Err(e),
+    };
+    match &result {
+        Ok(rows) => span.record_rows(&Ok(rows.iter().sum())),
+        Err(e) => span.fail(e),
+    }
+    result.map_err(|e| client.annotate_error(e, statement.query(), None))
+}
+
+/// Encodes a `Bind` and `Execute` message for each set of parameters followed by a single `Sync`, or returns `None` if
+/// there are no sets.
+fn encode_many<I, J, P>(
+    client: &InnerClient,
+    statement: &Statement,
+    param_sets: I,
+) -> Result<Option<Bytes>, Error>
+where
+    I: IntoIterator<Item = J>,
+    J: IntoIterator<Item = P>,
+    J::IntoIter: ExactSizeIterator,
+    P: BorrowToSql,
+{
+    client.with_buf(|buf| {
+        for params in param_sets {
+            encode_bind(statement, params, "", buf)?;
+            frontend::execute("", 0, buf).map_err(Error::encode)?;
+        }
+        if buf.is_empty() {
+            return Ok(None);
+        }
+        frontend::sync(buf);
+        Ok(Some(buf.split().freeze()))
+    })
+}
+
+async fn execute_many_inner(client: &InnerClient, buf: Bytes) -> Result<Vec<u64>, Error> {
+    let mut responses = client.send(RequestMessages::Single(FrontendMessage::Raw(buf)))?;
+
+    let mut rows = vec![];
+    loop {
+        match responses.next().await? {
+            Message::BindComplete | Message::DataRow(_) => {}
+            Message::CommandComplete(body) => rows.push(extract_row_affected(&body)?),
+            Message::EmptyQueryResponse => rows.push(0),
+            Message::ReadyForQuery(_) => return Ok(rows),
+            _ => return Err(Error::unexpected_message()),
+        }
+    }
+}
+
 async fn start(client: &InnerClient, buf: Bytes) -> Result<Responses, Error> {
     let mut responses = client.send(RequestMessages::Single(FrontendMessage::Raw(buf)))?;
 
@@ -253,6 +337,68 @@ async fn start(client: &InnerClient, buf: Bytes) -> Result<Responses, Error> {
     Ok(responses)
 }
 
+/// Encodes a statement's parameters, logging them if enabled and capturing them for errors.
+fn encode_logged<P, I>(
+    client: &InnerClient,
+    statement: &Statement,
+    params: I,
+) -> (Result<Bytes, Error>, Option<BoundParams>)
+where
+    P: BorrowToSql,
+    I: IntoIterator<Item = P>,
+    I::IntoIter: ExactSizeIterator,
+{
+    let buf = if log_enabled!(Level::Debug) {
+        let params = params.into_iter().collect::<Vec<_>>();
+        debug!(
+            "executing statement {} with parameters: {:?}",
+            statement.name(),
+            BorrowToSqlParamsDebug(params.as_slice()),
+        );
+        encode(client, statement, params)
+    } else {
+        encode(client, statement, params)
+    };
+    let params = match &buf {
+        Ok(buf) => client.bound_params(buf, statement.params()),
+        Err(_) => None,
+    };
+    (buf, params)
+}
+
+fn encode_typed<P, I>(
+    client: &InnerClient,
+    query: &str,
+    params: I,
+) -> (Result<Bytes, Error>, Option<BoundParams>)
+where
+    P: BorrowToSql,
+    I: IntoIterator<Item = (P, Type)>,
+{
+    let params = params.into_iter().collect::<Vec<_>>();
+    let param_oids = params.iter().map(|(_, t)| t.oid()).collect::<Vec<_>>();
+    let types = if client.params_in_errors() {
+        params.iter().map(|(_, t)| t.clone()).collect()
+    } else {
+        vec![]
+    };
+
+    let buf = client.with_buf(|buf| {
+        frontend::parse("", &client.comment_query(query), param_oids, buf).map_err(Error::parse)?;
+        encode_bind_raw("", params, "", buf)?;
+        frontend::describe(b'S', "", buf).map_err(Error::encode)?;
+        frontend::execute("", 0, buf).map_err(Error::encode)?;
+        frontend::sync(buf);
+
+        Ok(buf.split().freeze())
+    });
+    let params = match &buf {
+        Ok(buf) => client.bound_params(buf, &types),
+        Err(_) => None,
+    };
+    (buf, params)
+}
+
 pub fn encode<P, I>(client: &InnerClient, statement: &Statement, params: I) -> Result<Bytes, Error>
 where
     P: BorrowToSql,
@@ -338,6 +484,9 @@ pin_project! {
         statement: Statement,
         responses: Responses,
         rows_affected: Option<u64>,
+        query_in_errors: bool,
+        params: Option<BoundParams>,
+        span: OperationSpan,
     }
 }
 
@@ -346,18 +495,52 @@ impl Stream for RowStream {
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         let this = self.project();
-        loop {
-            match ready!(this.responses.poll_next(cx)?) {
-                Message::DataRow(body) => {
-                    return Poll::Ready(Some(Ok(Row::new(this.statement.clone(), body)?)));
+        let row = ready!(poll_row(
+            this.statement,
+            this.responses,
+            this.rows_affected,
+            cx
+        ));
+        match row {
+            Some(Ok(row)) => {
+                this.span.row();
+                Poll::Ready(Some(Ok(row)))
+            }
+            None => {
+                this.span.finish_rows();
+                Poll::Ready(None)
+            }
+            Some(Err(mut e)) => {
+                this.span.fail(&e);
+                if *this.query_in_errors {
+                    e = e.with_query(this.statement.query());
                 }
-                Message::CommandComplete(body) => {
-                    *this.rows_affected = Some(extract_row_affected(&body)?);
+                if let Some(params) = this.params.as_ref().and_then(BoundParams::render) {
+                    e = e.with_params(params);
                 }
-                Message::EmptyQueryResponse | Message::PortalSuspended => {}
-                Message::ReadyForQuery(_) => return Poll::Ready(None),
-                _ => return Poll::Ready(Some(Err(Error::unexpected_message()))),
+                Poll::Ready(Some(Err(e)))
+            }
+        }
+    }
+}
+
+fn poll_row(
+    statement: &Statement,
+    responses: &mut Responses,
+    rows_affected: &mut Option<u64>,
+    cx: &mut Context<'_>,
+) -> Poll<Option<Result<Row, Error>>> {
+    loop {
+        match ready!(responses.poll_next(cx)?) {
+            Message::DataRow(body) => {
+                return Poll::Ready(Some(Ok(Row::new(statement.clone(), body)?)));
+            }
+            Message::CommandComplete(body) => {
+                *rows_affected = Some(extract_row_affected(&body)?);
             }
+            Message::EmptyQueryResponse | Message::PortalSuspended => {}
+            Message::ReadyForQuery(_) => return Poll::Ready(None),
+            _ => return Poll::Ready(Some(Err(Error::unexpected_message()))),
         }
     }
 }
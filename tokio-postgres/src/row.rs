@@ -4,9 +4,16 @@ use crate::row::sealed::{AsName, Sealed};
 use crate::simple_query::SimpleColumn;
 use crate::statement::Column;
 use crate::types::{FromSql, Type, WrongType};
+#[cfg(feature = "test-util")]
+use crate::types::{IsNull, ToSql};
 use crate::{Error, Statement};
+use bytes::Bytes;
+#[cfg(feature = "test-util")]
+use bytes::{BufMut, BytesMut};
 use fallible_iterator::FallibleIterator;
 use postgres_protocol::message::backend::DataRowBody;
+#[cfg(feature = "test-util")]
+use postgres_protocol::message::backend::Message;
 use std::fmt;
 use std::io;
 use std::ops::Range;
@@ -131,6 +138,48 @@ impl Row {
         Ok(row)
     }
 
+    /// Creates a row of a statement from the values of its columns, without a connection.
+    ///
+    /// Each value is converted to the type of the corresponding column in the same way as a query parameter. This
+    /// is intended for unit testing code which works with rows, together with [`Statement::new_test`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tokio_postgres::{Column, Row, Statement};
+    /// use tokio_postgres::types::Type;
+    ///
+    /// # fn main() -> Result<(), tokio_postgres::Error> {
+    /// let statement = Statement::new_test(
+    ///     vec![],
+    ///     vec![Column::new("id", Type::INT4), Column::new("name", Type::TEXT)],
+    /// );
+    /// let row = Row::new_test(statement, &[&1i32, &None::<String>])?;
+    ///
+    /// assert_eq!(row.get::<_, i32>("id"), 1);
+    /// assert_eq!(row.get::<_, Option<String>>("name"), None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "test-util")]
+    pub fn new_test(statement: Statement, values: &[&(dyn ToSql + Sync)]) -> Result<Row, Error> {
+        if values.len() != statement.columns().len() {
+            return Err(value_count());
+        }
+
+        let mut encoded = vec![];
+        for (i, (value, column)) in values.iter().zip(statement.columns()).enumerate() {
+            let mut buf = BytesMut::new();
+            match value.to_sql_checked(column.type_(), &mut buf) {
+                Ok(IsNull::No) => encoded.push(Some(buf)),
+                Ok(IsNull::Yes) => encoded.push(None),
+                Err(e) => return Err(Error::to_sql(e, i)),
+            }
+        }
+
+        Row::new(statement, data_row(encoded.iter().map(|v| v.as_deref())))
+    }
+
     /// Returns information about the columns of data in the row.
     pub fn columns(&self) -> &[Column] {
         self.statement.columns()
@@ -148,11 +197,15 @@ impl Row {
 
     /// Deserializes a value from the row.
     ///
-    /// The value can be specified either by its numeric index in the row, or by its column name.
+    /// The value can be specified either by its numeric index in the row, or by its column name. Values of types with
+    /// a [`TypeHandler`] registered on the client are still converted with `FromSql`; use [`Row::get_custom`] to
+    /// convert them with the handler.
     ///
     /// # Panics
     ///
     /// Panics if the index is out of bounds or if the value cannot be converted to the specified type.
+    ///
+    /// [`TypeHandler`]: crate::TypeHandler
     #[track_caller]
     pub fn get<'a, I, T>(&'a self, idx: I) -> T
     where
@@ -175,6 +228,99 @@ impl Row {
     }
 
     fn get_inner<'a, I, T>(&'a self, idx: &I) -> Result<T, Error>
+    where
+        I: RowIndex + fmt::Display,
+        T: FromSql<'a>,
+    {
+        let idx = match idx.__idx(self.columns()) {
+            Some(idx) => idx,
+            None => return Err(Error::column(idx.to_string())),
+        };
+        let ty = self.columns()[idx].type_();
+        let raw = self.col_buffer(idx);
+
+        if !T::accepts(ty) {
+            return Err(Error::from_sql(
+                Box::new(WrongType::new::<T>(ty.clone())),
+                idx,
+            ));
+        }
+        FromSql::from_sql_nullable(ty, raw).map_err(|e| Error::from_sql(e, idx))
+    }
+
+    /// Returns a `BYTEA` value from the row without copying it.
+    ///
+    /// The returned `Bytes` shares the buffer the row was received in, which stays alive for as long as the value
+    /// does. `None` is returned for a `NULL` value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds or if the value is not a `BYTEA`.
+    #[track_caller]
+    pub fn get_bytes<I>(&self, idx: I) -> Option<Bytes>
+    where
+        I: RowIndex + fmt::Display,
+    {
+        match self.try_get_bytes(&idx) {
+            Ok(ok) => ok,
+            Err(err) => panic!("error retrieving column {}: {}", idx, err),
+        }
+    }
+
+    /// Like `Row::get_bytes`, but returns a `Result` rather than panicking.
+    pub fn try_get_bytes<I>(&self, idx: I) -> Result<Option<Bytes>, Error>
+    where
+        I: RowIndex + fmt::Display,
+    {
+        let idx = self.checked_idx::<I, Bytes>(&idx)?;
+        Ok(self.ranges[idx]
+            .clone()
+            .map(|range| self.body.buffer_bytes().slice(range)))
+    }
+
+    /// Deserializes a value from the row using the [`TypeHandler`] registered on the client for the column's type.
+    ///
+    /// Unlike `Row::get`, this doesn't fall back to `FromSql` implementations when no handler applies. `None` is
+    /// returned for a `NULL` value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds, if no handler is registered for the column's type, or if the value cannot
+    /// be converted to the specified type.
+    ///
+    /// [`TypeHandler`]: crate::TypeHandler
+    #[track_caller]
+    pub fn get_custom<I, T>(&self, idx: I) -> Option<T>
+    where
+        I: RowIndex + fmt::Display,
+        T: 'static,
+    {
+        match self.try_get_custom(&idx) {
+            Ok(ok) => ok,
+            Err(err) => panic!("error retrieving column {}: {}", idx, err),
+        }
+    }
+
+    /// Like `Row::get_custom`, but returns a `Result` rather than panicking.
+    pub fn try_get_custom<I, T>(&self, idx: I) -> Result<Option<T>, Error>
+    where
+        I: RowIndex + fmt::Display,
+        T: 'static,
+    {
+        let idx = match idx.__idx(self.columns()) {
+            Some(idx) => idx,
+            None => return Err(Error::column(idx.to_string())),
+        };
+
+        let ty = self.columns()[idx].type_();
+        self.statement
+            .type_registry()
+            .decode(ty, self.col_buffer(idx))
+            .map_err(|e| Error::from_sql(e, idx))
+    }
+
+    // Looks up a column, checking that its type is compatible with `T`.
+    fn checked_idx<'a, I, T>(&self, idx: &I) -> Result<usize, Error>
     where
         I: RowIndex + fmt::Display,
         T: FromSql<'a>,
@@ -192,7 +338,7 @@ impl Row {
             ));
         }
 
-        FromSql::from_sql_nullable(ty, self.col_buffer(idx)).map_err(|e| Error::from_sql(e, idx))
+        Ok(idx)
     }
 
     /// Returns the raw size of the row in bytes.
@@ -207,6 +353,54 @@ impl Row {
     }
 }
 
+/// A type which can be created from the columns of a row.
+///
+/// With the `derive` Cargo feature, this can be derived for structs with named fields. Each field is read from the
+/// column with the same name, which can be changed with `#[postgres(name = "...")]` on the field or
+/// `#[postgres(rename_all = "...")]` on the struct. Fields marked `#[postgres(skip)]` are set to their `Default` value,
+/// and fields marked `#[postgres(default)]` are set to it when the row has no column for them.
+///
+/// A field whose type itself implements `FromRow` can be read from the same row with `#[postgres(flatten)]`, or with
+/// `#[postgres(prefix = "...")]` to read it from columns whose names start with the prefix. This maps the results of
+/// joins without renaming each column by hand:
+///
+/// ```rust
+/// # #[cfg(feature = "derive")]
+/// # fn f() {
+/// use tokio_postgres::FromRow;
+///
+/// #[derive(FromRow)]
+/// struct Author {
+///     id: i32,
+///     name: String,
+/// }
+///
+/// // SELECT p.id, p.title, a.id AS author_id, a.name AS author_name
+/// // FROM posts p JOIN authors a ON a.id = p.author_id
+/// #[derive(FromRow)]
+/// struct Post {
+///     id: i32,
+///     title: String,
+///     #[postgres(prefix = "author_")]
+///     author: Author,
+/// }
+/// # }
+/// ```
+///
+/// The derived implementation refers to this crate as `tokio_postgres`. When it is used through another crate which
+/// re-exports it, such as `postgres`, specify the path with `#[postgres(crate = "postgres")]` on the struct.
+pub trait FromRow: Sized {
+    /// Creates a value from a row.
+    fn from_row(row: &Row) -> Result<Self, Error> {
+        Self::from_row_prefixed(row, "")
+    }
+
+    /// Creates a value from the columns of a row whose names start with `prefix`.
+    ///
+    /// The column for each field is looked up by its name with `prefix` prepended.
+    fn from_row_prefixed(row: &Row, prefix: &str) -> Result<Self, Error>;
+}
+
 impl AsName for SimpleColumn {
     fn as_name(&self) -> &str {
         self.name()
@@ -245,6 +439,23 @@ impl SimpleQueryRow {
         Ok(row)
     }
 
+    /// Creates a row from column names and values in the text format, without a connection.
+    ///
+    /// This is intended for unit testing code which works with the results of simple queries.
+    #[cfg(feature = "test-util")]
+    pub fn new_test(columns: &[&str], values: &[Option<&str>]) -> Result<SimpleQueryRow, Error> {
+        if values.len() != columns.len() {
+            return Err(value_count());
+        }
+
+        let columns = columns
+            .iter()
+            .map(|name| SimpleColumn::new(name.to_string()))
+            .collect();
+        let body = data_row(values.iter().map(|v| v.map(str::as_bytes)));
+        SimpleQueryRow::new(columns, body)
+    }
+
     /// Returns information about the columns of data in the row.
     pub fn columns(&self) -> &[SimpleColumn] {
         &self.columns
@@ -300,6 +511,42 @@ impl SimpleQueryRow {
     }
 }
 
+#[cfg(feature = "test-util")]
+fn value_count() -> Error {
+    Error::parse(io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "the number of values does not match the number of columns",
+    ))
+}
+
+// Encodes values as a DataRow message and parses it back, since the body can't be built directly.
+#[cfg(feature = "test-util")]
+fn data_row<'a, I>(values: I) -> DataRowBody
+where
+    I: ExactSizeIterator<Item = Option<&'a [u8]>>,
+{
+    let mut buf = BytesMut::new();
+    buf.put_u8(b'D');
+    buf.put_i32(0);
+    buf.put_i16(values.len() as i16);
+    for value in values {
+        match value {
+            Some(value) => {
+                buf.put_i32(value.len() as i32);
+                buf.put_slice(value);
+            }
+            None => buf.put_i32(-1),
+        }
+    }
+    let len = (buf.len() - 1) as i32;
+    buf[1..5].copy_from_slice(&len.to_be_bytes());
+
+    match Message::parse(&mut buf) {
+        Ok(Some(Message::DataRow(body))) => body,
+        _ => unreachable!("invalid DataRow message"),
+    }
+}
+
 #[cfg(test)]
 mod test {
     use bytes::BytesMut;
@@ -341,14 +588,24 @@ mod test {
         // a server advertising two columns but sending a DataRow with a single
         // field would make column accessors index out of bounds and panic.
         let body = data_row(1, &[b""]);
-        let statement = Statement::unnamed(vec![], vec![column("a"), column("b")]);
+        let statement = Statement::unnamed(
+            Default::default(),
+            String::new(),
+            vec![],
+            vec![column("a"), column("b")],
+        );
         assert!(Row::new(statement, body).is_err());
     }
 
     #[test]
     fn matching_data_row_field_count_is_accepted() {
         let body = data_row(2, &[b"x", b"y"]);
-        let statement = Statement::unnamed(vec![], vec![column("a"), column("b")]);
+        let statement = Statement::unnamed(
+            Default::default(),
+            String::new(),
+            vec![],
+            vec![column("a"), column("b")],
+        );
         assert!(Row::new(statement, body).is_ok());
     }
 }
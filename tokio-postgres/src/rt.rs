@@ -0,0 +1,211 @@
+//! Support for async runtimes other than Tokio.
+//!
+//! The [`Client`](crate::Client) and [`Connection`](crate::Connection) types only require an I/O stream and do not
+//! depend on any particular runtime. The connection process, however, needs to open sockets, resolve hostnames, and
+//! wait on timers, which by default is done with Tokio. Registering a [`Runtime`] with [`Config::runtime`] routes those
+//! operations through another runtime instead, so `Config::connect` can be used from applications which do not run a
+//! Tokio reactor.
+//!
+//! An implementation for [smol](https://crates.io/crates/smol), which also works under `async-std`, is provided
+//! behind the `with-smol-2` Cargo feature.
+//!
+//! Requires the `runtime` Cargo feature (enabled by default).
+//!
+//! [`Config::runtime`]: crate::Config::runtime
+
+use futures_channel::oneshot;
+use futures_util::future::{self, Either};
+use std::future::Future;
+use std::io;
+use std::net::SocketAddr;
+#[cfg(unix)]
+use std::path::Path;
+use std::pin::{Pin, pin};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
+
+/// A stream produced by a [`Runtime`].
+///
+/// This is implemented for all types which implement Tokio's [`AsyncRead`] and [`AsyncWrite`] traits.
+pub trait RuntimeStream: AsyncRead + AsyncWrite + Send + Sync + Unpin {}
+
+impl<T> RuntimeStream for T where T: AsyncRead + AsyncWrite + Send + Sync + Unpin {}
+
+/// The future returned by a [`Runtime`] when opening a connection.
+pub type ConnectFuture<'a> =
+    Pin<Box<dyn Future<Output = io::Result<Box<dyn RuntimeStream>>> + Send + 'a>>;
+
+/// The runtime-specific operations used to establish connections.
+///
+/// Streams returned by a runtime are used as-is: TCP keepalives are not enabled on them. Connecting fails with a
+/// configuration error if the [`Config`](crate::Config) also sets a socket callback, a TCP user timeout, or
+/// keepalive parameters, since those can't be applied.
+pub trait Runtime: Send + Sync {
+    /// Opens a TCP connection to an address.
+    fn connect_tcp(&self, addr: SocketAddr) -> ConnectFuture<'_>;
+
+    /// Opens a connection to a Unix socket.
+    ///
+    /// This method is only available on Unix platforms.
+    #[cfg(unix)]
+    fn connect_unix<'a>(&'a self, path: &'a Path) -> ConnectFuture<'a>;
+
+    /// Resolves a hostname and port to a list of socket addresses.
+    ///
+    /// A [DNS resolver](crate::Config::dns_resolver) configured separately takes precedence over this method.
+    fn resolve<'a>(
+        &'a self,
+        host: &'a str,
+        port: u16,
+    ) -> Pin<Box<dyn Future<Output = io::Result<Vec<SocketAddr>>> + Send + 'a>>;
+
+    /// Returns a future which completes after a duration has elapsed.
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>>;
+
+    /// Runs a blocking function, such as reading the password file, and returns a future which completes once it has
+    /// finished.
+    ///
+    /// The function must be run on a thread where blocking is acceptable, such as the runtime's blocking thread pool,
+    /// rather than on the thread polling the returned future.
+    fn spawn_blocking(
+        &self,
+        f: Box<dyn FnOnce() + Send + 'static>,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send>>;
+}
+
+pub(crate) async fn sleep(runtime: Option<&dyn Runtime>, duration: Duration) {
+    match runtime {
+        Some(runtime) => runtime.sleep(duration).await,
+        None => tokio::time::sleep(duration).await,
+    }
+}
+
+/// Runs a blocking function on a runtime's blocking thread pool.
+pub(crate) async fn spawn_blocking<F, T>(runtime: &dyn Runtime, f: F) -> T
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let (sender, receiver) = oneshot::channel();
+    runtime
+        .spawn_blocking(Box::new(move || {
+            let _ = sender.send(f());
+        }))
+        .await;
+    receiver.await.expect("blocking function panicked")
+}
+
+/// Runs a future with a time limit, returning `None` if it expires.
+pub(crate) async fn timeout<F>(
+    runtime: Option<&dyn Runtime>,
+    duration: Duration,
+    future: F,
+) -> Option<F::Output>
+where
+    F: Future,
+{
+    match runtime {
+        Some(runtime) => match future::select(pin!(future), runtime.sleep(duration)).await {
+            Either::Left((output, _)) => Some(output),
+            Either::Right(_) => None,
+        },
+        None => tokio::time::timeout(duration, future).await.ok(),
+    }
+}
+
+/// A [`Runtime`] backed by [smol](https://crates.io/crates/smol).
+///
+/// `async-std` shares smol's reactor, so this runtime can also be used there.
+///
+/// Requires the `with-smol-2` Cargo feature.
+#[cfg(feature = "with-smol-2")]
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Smol;
+
+#[cfg(feature = "with-smol-2")]
+impl Runtime for Smol {
+    fn connect_tcp(&self, addr: SocketAddr) -> ConnectFuture<'_> {
+        Box::pin(async move {
+            let stream = smol_2::net::TcpStream::connect(addr).await?;
+            stream.set_nodelay(true)?;
+            Ok(Box::new(compat::Compat(stream)) as Box<dyn RuntimeStream>)
+        })
+    }
+
+    #[cfg(unix)]
+    fn connect_unix<'a>(&'a self, path: &'a Path) -> ConnectFuture<'a> {
+        Box::pin(async move {
+            let stream = smol_2::net::unix::UnixStream::connect(path).await?;
+            Ok(Box::new(compat::Compat(stream)) as Box<dyn RuntimeStream>)
+        })
+    }
+
+    fn resolve<'a>(
+        &'a self,
+        host: &'a str,
+        port: u16,
+    ) -> Pin<Box<dyn Future<Output = io::Result<Vec<SocketAddr>>> + Send + 'a>> {
+        Box::pin(smol_2::net::resolve((host, port)))
+    }
+
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        Box::pin(async move {
+            smol_2::Timer::after(duration).await;
+        })
+    }
+
+    fn spawn_blocking(
+        &self,
+        f: Box<dyn FnOnce() + Send + 'static>,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        Box::pin(smol_2::unblock(f))
+    }
+}
+
+#[cfg(feature = "with-smol-2")]
+mod compat {
+    use smol_2::io::{AsyncRead as FuturesRead, AsyncWrite as FuturesWrite};
+    use std::io;
+    use std::pin::Pin;
+    use std::task::{Context, Poll, ready};
+    use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+    /// Adapts a stream implementing the `futures-io` traits to Tokio's.
+    pub(super) struct Compat<S>(pub(super) S);
+
+    impl<S> AsyncRead for Compat<S>
+    where
+        S: FuturesRead + Unpin,
+    {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<io::Result<()>> {
+            let n = ready!(Pin::new(&mut self.0).poll_read(cx, buf.initialize_unfilled()))?;
+            buf.advance(n);
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    impl<S> AsyncWrite for Compat<S>
+    where
+        S: FuturesWrite + Unpin,
+    {
+        fn poll_write(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            Pin::new(&mut self.0).poll_write(cx, buf)
+        }
+
+        fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.0).poll_flush(cx)
+        }
+
+        fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.0).poll_close(cx)
+        }
+    }
+}
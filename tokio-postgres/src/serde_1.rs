@@ -0,0 +1,293 @@
+use crate::config::{
+    ChannelBinding, Config, Host, LoadBalanceHosts, SslMode, SslNegotiation, TargetSessionAttrs,
+};
+use serde_1::de::{self, Deserialize, Deserializer};
+use serde_1::ser::{self, Serialize, Serializer};
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::str;
+use std::time::Duration;
+
+/// The serialized form of a `Config`, using the connection string key names.
+#[derive(serde_1::Serialize, serde_1::Deserialize, Default)]
+#[serde(crate = "serde_1", default, deny_unknown_fields)]
+struct Settings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    password: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    passfile: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dbname: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    options: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    application_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sslmode: Option<SslMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sslrootcert: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sslcert: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sslkey: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sslpassword: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sslnegotiation: Option<SslNegotiation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    host: Option<OneOrMany<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hostaddr: Option<OneOrMany<IpAddr>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    port: Option<OneOrMany<u16>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    connect_timeout: Option<Seconds>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total_connect_timeout: Option<Seconds>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    connect_retries: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    connect_retry_backoff: Option<Seconds>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tcp_user_timeout: Option<Seconds>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keepalives: Option<bool>,
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keepalives_idle: Option<Seconds>,
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keepalives_interval: Option<Seconds>,
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keepalives_retries: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target_session_attrs: Option<TargetSessionAttrs>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    channel_binding: Option<ChannelBinding>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    load_balance_hosts: Option<LoadBalanceHosts>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    service: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    servicefile: Option<PathBuf>,
+}
+
+/// A list which may also be written as a single value.
+#[derive(serde_1::Serialize, serde_1::Deserialize)]
+#[serde(crate = "serde_1", untagged)]
+enum OneOrMany<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+impl<T> OneOrMany<T> {
+    fn from_vec(values: Vec<T>) -> Option<OneOrMany<T>> {
+        if values.is_empty() {
+            None
+        } else {
+            Some(OneOrMany::Many(values))
+        }
+    }
+
+    fn into_vec(self) -> Vec<T> {
+        match self {
+            OneOrMany::One(value) => vec![value],
+            OneOrMany::Many(values) => values,
+        }
+    }
+}
+
+/// A duration, written as a number of seconds.
+struct Seconds(Duration);
+
+impl Serialize for Seconds {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.0.subsec_nanos() == 0 {
+            serializer.serialize_u64(self.0.as_secs())
+        } else {
+            serializer.serialize_f64(self.0.as_secs_f64())
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Seconds {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let secs = f64::deserialize(deserializer)?;
+        Duration::try_from_secs_f64(secs)
+            .map(Seconds)
+            .map_err(|_| de::Error::invalid_value(de::Unexpected::Float(secs), &"a duration"))
+    }
+}
+
+impl Serialize for Config {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let password = match &self.password {
+            Some(password) => Some(
+                str::from_utf8(password)
+                    .map_err(|_| ser::Error::custom("password is not valid UTF-8"))?
+                    .to_string(),
+            ),
+            None => None,
+        };
+        let ssl_password = match &self.ssl_password {
+            Some(ssl_password) => Some(
+                str::from_utf8(ssl_password)
+                    .map_err(|_| ser::Error::custom("sslpassword is not valid UTF-8"))?
+                    .to_string(),
+            ),
+            None => None,
+        };
+
+        let host = self
+            .host
+            .iter()
+            .map(|host| match host {
+                Host::Tcp(host) => Ok(host.clone()),
+                #[cfg(unix)]
+                Host::Unix(path) => path
+                    .to_str()
+                    .map(|path| path.to_string())
+                    .ok_or_else(|| ser::Error::custom("host path is not valid UTF-8")),
+            })
+            .collect::<Result<Vec<_>, S::Error>>()?;
+
+        let settings = Settings {
+            user: self.user.clone(),
+            password,
+            passfile: self.passfile.clone(),
+            dbname: self.dbname.clone(),
+            options: self.options.clone(),
+            application_name: self.application_name.clone(),
+            sslmode: Some(self.ssl_mode),
+            sslrootcert: self.ssl_root_cert.clone(),
+            sslcert: self.ssl_cert.clone(),
+            sslkey: self.ssl_key.clone(),
+            sslpassword: ssl_password,
+            sslnegotiation: Some(self.ssl_negotiation),
+            host: OneOrMany::from_vec(host),
+            hostaddr: OneOrMany::from_vec(self.hostaddr.clone()),
+            port: OneOrMany::from_vec(self.port.clone()),
+            connect_timeout: self.connect_timeout.map(Seconds),
+            total_connect_timeout: self.total_connect_timeout.map(Seconds),
+            connect_retries: Some(self.connect_retries),
+            connect_retry_backoff: Some(Seconds(self.connect_retry_backoff)),
+            tcp_user_timeout: self.tcp_user_timeout.map(Seconds),
+            keepalives: Some(self.keepalives),
+            #[cfg(not(target_arch = "wasm32"))]
+            keepalives_idle: Some(Seconds(self.keepalive_config.idle)),
+            #[cfg(not(target_arch = "wasm32"))]
+            keepalives_interval: self.keepalive_config.interval.map(Seconds),
+            #[cfg(not(target_arch = "wasm32"))]
+            keepalives_retries: self.keepalive_config.retries,
+            target_session_attrs: Some(self.target_session_attrs),
+            channel_binding: Some(self.channel_binding),
+            load_balance_hosts: Some(self.load_balance_hosts),
+            service: self.service.clone(),
+            servicefile: self.servicefile.clone(),
+        };
+        settings.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Config {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let settings = Settings::deserialize(deserializer)?;
+
+        let mut config = Config::new();
+        if let Some(user) = settings.user {
+            config.user(user);
+        }
+        if let Some(password) = settings.password {
+            config.password(password);
+        }
+        if let Some(passfile) = settings.passfile {
+            config.passfile(passfile);
+        }
+        if let Some(dbname) = settings.dbname {
+            config.dbname(dbname);
+        }
+        if let Some(options) = settings.options {
+            config.options(options);
+        }
+        if let Some(application_name) = settings.application_name {
+            config.application_name(application_name);
+        }
+        if let Some(ssl_mode) = settings.sslmode {
+            config.ssl_mode(ssl_mode);
+        }
+        if let Some(ssl_root_cert) = settings.sslrootcert {
+            config.ssl_root_cert(ssl_root_cert);
+        }
+        if let Some(ssl_cert) = settings.sslcert {
+            config.ssl_cert(ssl_cert);
+        }
+        if let Some(ssl_key) = settings.sslkey {
+            config.ssl_key(ssl_key);
+        }
+        if let Some(ssl_password) = settings.sslpassword {
+            config.ssl_password(ssl_password);
+        }
+        if let Some(ssl_negotiation) = settings.sslnegotiation {
+            config.ssl_negotiation(ssl_negotiation);
+        }
+        for host in settings.host.map_or_else(Vec::new, OneOrMany::into_vec) {
+            config.host(host);
+        }
+        for hostaddr in settings.hostaddr.map_or_else(Vec::new, OneOrMany::into_vec) {
+            config.hostaddr(hostaddr);
+        }
+        for port in settings.port.map_or_else(Vec::new, OneOrMany::into_vec) {
+            config.port(port);
+        }
+        if let Some(Seconds(connect_timeout)) = settings.connect_timeout {
+            config.connect_timeout(connect_timeout);
+        }
+        if let Some(Seconds(total_connect_timeout)) = settings.total_connect_timeout {
+            config.total_connect_timeout(total_connect_timeout);
+        }
+        if let Some(connect_retries) = settings.connect_retries {
+            config.connect_retries(connect_retries);
+        }
+        if let Some(Seconds(connect_retry_backoff)) = settings.connect_retry_backoff {
+            config.connect_retry_backoff(connect_retry_backoff);
+        }
+        if let Some(Seconds(tcp_user_timeout)) = settings.tcp_user_timeout {
+            config.tcp_user_timeout(tcp_user_timeout);
+        }
+        if let Some(keepalives) = settings.keepalives {
+            config.keepalives(keepalives);
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if let Some(Seconds(keepalives_idle)) = settings.keepalives_idle {
+                config.keepalives_idle(keepalives_idle);
+            }
+            if let Some(Seconds(keepalives_interval)) = settings.keepalives_interval {
+                config.keepalives_interval(keepalives_interval);
+            }
+            if let Some(keepalives_retries) = settings.keepalives_retries {
+                config.keepalives_retries(keepalives_retries);
+            }
+        }
+        if let Some(target_session_attrs) = settings.target_session_attrs {
+            config.target_session_attrs(target_session_attrs);
+        }
+        if let Some(channel_binding) = settings.channel_binding {
+            config.channel_binding(channel_binding);
+        }
+        if let Some(load_balance_hosts) = settings.load_balance_hosts {
+            config.load_balance_hosts(load_balance_hosts);
+        }
+        if let Some(service) = settings.service {
+            config.service(service);
+        }
+        if let Some(servicefile) = settings.servicefile {
+            config.servicefile(servicefile);
+        }
+
+        Ok(config)
+    }
+}
@@ -0,0 +1,189 @@
+//! Settings from libpq's connection service file.
+
+#[cfg(feature = "runtime")]
+use crate::rt;
+use crate::{Config, Error};
+use std::borrow::Cow;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+type Settings = Vec<(String, String)>;
+
+/// Fills in settings from the configured service, if there is one.
+pub(crate) async fn resolve(config: &Config) -> Result<Cow<'_, Config>, Error> {
+    let Some(name) = &config.service else {
+        return Ok(Cow::Borrowed(config));
+    };
+
+    let mut config = config.clone();
+    for (key, value) in load(&config, name).await? {
+        if key == "service" {
+            return Err(Error::config(
+                "nested service specifications not supported in service file".into(),
+            ));
+        }
+        if config.is_unset(&key) {
+            config.param(&key, &value)?;
+        }
+    }
+
+    Ok(Cow::Owned(config))
+}
+
+async fn load(config: &Config, name: &str) -> Result<Settings, Error> {
+    for path in files(config) {
+        let contents = match read(config, path.clone()).await {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => {
+                return Err(Error::config(
+                    format!("unable to read service file {}: {}", path.display(), e).into(),
+                ));
+            }
+        };
+
+        let service = parse(&contents, name).map_err(|(line, msg)| {
+            Error::config(
+                format!("{} in service file {}, line {}", msg, path.display(), line).into(),
+            )
+        })?;
+        if let Some(service) = service {
+            return Ok(service);
+        }
+    }
+
+    Err(Error::config(
+        format!("definition of service \"{name}\" not found").into(),
+    ))
+}
+
+/// Reads a service file.
+///
+/// The file is read with Tokio's asynchronous filesystem API, or on the blocking thread pool of a custom
+/// [`Runtime`](crate::rt::Runtime). Without the `runtime` feature it is read directly.
+async fn read(config: &Config, path: PathBuf) -> io::Result<String> {
+    #[cfg(feature = "runtime")]
+    match config.get_runtime() {
+        Some(runtime) => rt::spawn_blocking(runtime, move || fs::read_to_string(path)).await,
+        None => tokio::fs::read_to_string(path).await,
+    }
+
+    #[cfg(not(feature = "runtime"))]
+    {
+        let _ = config;
+        fs::read_to_string(path)
+    }
+}
+
+/// Returns the service files to search, in order.
+fn files(config: &Config) -> Vec<PathBuf> {
+    let mut files = vec![];
+
+    if let Some(path) = &config.servicefile {
+        files.push(path.clone());
+    } else if let Some(path) = env::var_os("PGSERVICEFILE") {
+        files.push(PathBuf::from(path));
+    } else {
+        #[cfg(windows)]
+        let path = env::var_os("APPDATA").map(|dir| {
+            PathBuf::from(dir)
+                .join("postgresql")
+                .join(".pg_service.conf")
+        });
+        #[cfg(not(windows))]
+        let path = env::var_os("HOME").map(|dir| PathBuf::from(dir).join(".pg_service.conf"));
+        files.extend(path);
+    }
+
+    if let Some(dir) = env::var_os("PGSYSCONFDIR") {
+        files.push(PathBuf::from(dir).join("pg_service.conf"));
+    }
+
+    files
+}
+
+/// Finds the settings of a service in the contents of a service file.
+///
+/// On error, the line number and a description of the problem are returned.
+fn parse(contents: &str, name: &str) -> Result<Option<Settings>, (usize, String)> {
+    let mut service = None::<Settings>;
+
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(section) = line.strip_prefix('[') {
+            if service.is_some() {
+                break;
+            }
+            let section = section
+                .strip_suffix(']')
+                .ok_or_else(|| (i + 1, "syntax error".to_string()))?;
+            if section == name {
+                service = Some(vec![]);
+            }
+            continue;
+        }
+
+        let Some(settings) = &mut service else {
+            continue;
+        };
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| (i + 1, "syntax error".to_string()))?;
+        settings.push((key.trim().to_string(), value.trim().to_string()));
+    }
+
+    Ok(service)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+
+    #[test]
+    fn test_parse() {
+        let contents = "\
+# a comment
+[first]
+host=db.example.com
+
+[second]
+host = other.example.com
+port=5433
+  # indented comment
+dbname=app=1
+
+[third]
+bogus
+";
+
+        let settings = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            parse(contents, "first"),
+            Ok(Some(settings(&[("host", "db.example.com")])))
+        );
+        assert_eq!(
+            parse(contents, "second"),
+            Ok(Some(settings(&[
+                ("host", "other.example.com"),
+                ("port", "5433"),
+                ("dbname", "app=1"),
+            ])))
+        );
+        assert_eq!(parse(contents, "missing"), Ok(None));
+        assert_eq!(
+            parse(contents, "third"),
+            Err((12, "syntax error".to_string()))
+        );
+    }
+}
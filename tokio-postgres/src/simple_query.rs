@@ -1,6 +1,7 @@
 use crate::client::{InnerClient, Responses};
 use crate::codec::FrontendMessage;
 use crate::connection::RequestMessages;
+use crate::instrument::{Operation, OperationSpan};
 use crate::query::extract_row_affected;
 use crate::{Error, SimpleQueryMessage, SimpleQueryRow};
 use bytes::Bytes;
@@ -10,6 +11,7 @@ use log::debug;
 use pin_project_lite::pin_project;
 use postgres_protocol::message::backend::Message;
 use postgres_protocol::message::frontend;
+use std::borrow::Cow;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll, ready};
@@ -31,22 +33,46 @@ impl SimpleColumn {
     }
 }
 
-pub async fn simple_query(client: &InnerClient, query: &str) -> Result<SimpleQueryStream, Error> {
+pub async fn simple_query(
+    client: &InnerClient,
+    query: &str,
+    comment: bool,
+) -> Result<SimpleQueryStream, Error> {
     debug!("executing simple query: {query}");
 
-    let buf = encode(client, query)?;
-    let responses = client.send(RequestMessages::Single(FrontendMessage::Raw(buf)))?;
+    let mut span = OperationSpan::new(client, Operation::SimpleQuery, query);
+    let responses = encode(client, query, comment)
+        .and_then(|buf| client.send(RequestMessages::Single(FrontendMessage::Raw(buf))))
+        .map_err(|e| {
+            span.fail(&e);
+            client.query_error(e, query)
+        })?;
 
     Ok(SimpleQueryStream {
         responses,
         columns: None,
+        query: client.query_in_errors().then(|| query.to_string()),
+        span,
     })
 }
 
-pub async fn batch_execute(client: &InnerClient, query: &str) -> Result<(), Error> {
+pub async fn batch_execute(client: &InnerClient, query: &str, comment: bool) -> Result<(), Error> {
     debug!("executing statement batch: {query}");
 
-    let buf = encode(client, query)?;
+    let mut span = OperationSpan::new(client, Operation::SimpleQuery, query);
+    let result = span
+        .instrument(batch_execute_inner(client, query, comment))
+        .await;
+    span.record(&result);
+    result.map_err(|e| client.query_error(e, query))
+}
+
+async fn batch_execute_inner(
+    client: &InnerClient,
+    query: &str,
+    comment: bool,
+) -> Result<(), Error> {
+    let buf = encode(client, query, comment)?;
     let mut responses = client.send(RequestMessages::Single(FrontendMessage::Raw(buf)))?;
 
     loop {
@@ -61,9 +87,15 @@ pub async fn batch_execute(client: &InnerClient, query: &str) -> Result<(), Erro
     }
 }
 
-fn encode(client: &InnerClient, query: &str) -> Result<Bytes, Error> {
+/// Encodes a simple query, appending the configured sqlcommenter tags to its text if `comment` is set.
+fn encode(client: &InnerClient, query: &str, comment: bool) -> Result<Bytes, Error> {
     client.with_buf(|buf| {
-        frontend::query(query, buf).map_err(Error::encode)?;
+        let query = if comment {
+            client.comment_query(query)
+        } else {
+            Cow::Borrowed(query)
+        };
+        frontend::query(&query, buf).map_err(Error::encode)?;
         Ok(buf.split().freeze())
     })
 }
@@ -74,6 +106,9 @@ pin_project! {
     pub struct SimpleQueryStream {
         responses: Responses,
         columns: Option<Arc<[SimpleColumn]>>,
+        // The query text to record in errors, if the connection is configured to do so.
+        query: Option<String>,
+        span: OperationSpan,
     }
 }
 
@@ -82,34 +117,52 @@ impl Stream for SimpleQueryStream {
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         let this = self.project();
-        match ready!(this.responses.poll_next(cx)?) {
-            Message::CommandComplete(body) => {
-                let rows = extract_row_affected(&body)?;
-                Poll::Ready(Some(Ok(SimpleQueryMessage::CommandComplete(rows))))
-            }
-            Message::EmptyQueryResponse => {
-                Poll::Ready(Some(Ok(SimpleQueryMessage::CommandComplete(0))))
-            }
-            Message::RowDescription(body) => {
-                let columns: Arc<[SimpleColumn]> = body
-                    .fields()
-                    .map(|f| Ok(SimpleColumn::new(f.name().to_string())))
-                    .collect::<Vec<_>>()
-                    .map_err(Error::parse)?
-                    .into();
-
-                *this.columns = Some(columns.clone());
-                Poll::Ready(Some(Ok(SimpleQueryMessage::RowDescription(columns))))
-            }
-            Message::DataRow(body) => {
-                let row = match &this.columns {
-                    Some(columns) => SimpleQueryRow::new(columns.clone(), body)?,
-                    None => return Poll::Ready(Some(Err(Error::unexpected_message()))),
-                };
-                Poll::Ready(Some(Ok(SimpleQueryMessage::Row(row))))
-            }
-            Message::ReadyForQuery(_) => Poll::Ready(None),
-            _ => Poll::Ready(Some(Err(Error::unexpected_message()))),
+        let message = ready!(poll_message(this.responses, this.columns, cx));
+        match &message {
+            Some(Ok(SimpleQueryMessage::Row(_))) => this.span.row(),
+            Some(Ok(_)) => {}
+            Some(Err(e)) => this.span.fail(e),
+            None => this.span.finish_rows(),
+        }
+        match (message, &*this.query) {
+            (Some(Err(e)), Some(query)) => Poll::Ready(Some(Err(e.with_query(query)))),
+            (message, _) => Poll::Ready(message),
+        }
+    }
+}
+
+fn poll_message(
+    responses: &mut Responses,
+    columns: &mut Option<Arc<[SimpleColumn]>>,
+    cx: &mut Context<'_>,
+) -> Poll<Option<Result<SimpleQueryMessage, Error>>> {
+    match ready!(responses.poll_next(cx)?) {
+        Message::CommandComplete(body) => {
+            let rows = extract_row_affected(&body)?;
+            Poll::Ready(Some(Ok(SimpleQueryMessage::CommandComplete(rows))))
+        }
+        Message::EmptyQueryResponse => {
+            Poll::Ready(Some(Ok(SimpleQueryMessage::CommandComplete(0))))
+        }
+        Message::RowDescription(body) => {
+            let description: Arc<[SimpleColumn]> = body
+                .fields()
+                .map(|f| Ok(SimpleColumn::new(f.name().to_string())))
+                .collect::<Vec<_>>()
+                .map_err(Error::parse)?
+                .into();
+
+            *columns = Some(description.clone());
+            Poll::Ready(Some(Ok(SimpleQueryMessage::RowDescription(description))))
+        }
+        Message::DataRow(body) => {
+            let row = match columns {
+                Some(columns) => SimpleQueryRow::new(columns.clone(), body)?,
+                None => return Poll::Ready(Some(Err(Error::unexpected_message()))),
+            };
+            Poll::Ready(Some(Ok(SimpleQueryMessage::Row(row))))
         }
+        Message::ReadyForQuery(_) => Poll::Ready(None),
+        _ => Poll::Ready(Some(Err(Error::unexpected_message()))),
     }
 }
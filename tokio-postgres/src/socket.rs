@@ -1,6 +1,7 @@
-use std::io;
+use crate::rt::RuntimeStream;
 use std::pin::Pin;
 use std::task::{Context, Poll};
+use std::{fmt, io};
 use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
 use tokio::net::TcpStream;
 #[cfg(unix)]
@@ -11,6 +12,15 @@ enum Inner {
     Tcp(TcpStream),
     #[cfg(unix)]
     Unix(UnixStream),
+    Custom(CustomStream),
+}
+
+struct CustomStream(Box<dyn RuntimeStream>);
+
+impl fmt::Debug for CustomStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomStream").finish_non_exhaustive()
+    }
 }
 
 /// The standard stream type used by the crate.
@@ -28,6 +38,10 @@ impl Socket {
     pub(crate) fn new_unix(stream: UnixStream) -> Socket {
         Socket(Inner::Unix(stream))
     }
+
+    pub(crate) fn new_custom(stream: Box<dyn RuntimeStream>) -> Socket {
+        Socket(Inner::Custom(CustomStream(stream)))
+    }
 }
 
 impl AsyncRead for Socket {
@@ -40,6 +54,7 @@ impl AsyncRead for Socket {
             Inner::Tcp(s) => Pin::new(s).poll_read(cx, buf),
             #[cfg(unix)]
             Inner::Unix(s) => Pin::new(s).poll_read(cx, buf),
+            Inner::Custom(s) => Pin::new(&mut s.0).poll_read(cx, buf),
         }
     }
 }
@@ -54,6 +69,7 @@ impl AsyncWrite for Socket {
             Inner::Tcp(s) => Pin::new(s).poll_write(cx, buf),
             #[cfg(unix)]
             Inner::Unix(s) => Pin::new(s).poll_write(cx, buf),
+            Inner::Custom(s) => Pin::new(&mut s.0).poll_write(cx, buf),
         }
     }
 
@@ -62,6 +78,7 @@ impl AsyncWrite for Socket {
             Inner::Tcp(s) => Pin::new(s).poll_flush(cx),
             #[cfg(unix)]
             Inner::Unix(s) => Pin::new(s).poll_flush(cx),
+            Inner::Custom(s) => Pin::new(&mut s.0).poll_flush(cx),
         }
     }
 
@@ -70,6 +87,7 @@ impl AsyncWrite for Socket {
             Inner::Tcp(s) => Pin::new(s).poll_shutdown(cx),
             #[cfg(unix)]
             Inner::Unix(s) => Pin::new(s).poll_shutdown(cx),
+            Inner::Custom(s) => Pin::new(&mut s.0).poll_shutdown(cx),
         }
     }
 }
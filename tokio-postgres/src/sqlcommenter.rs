@@ -0,0 +1,80 @@
+//! Support for appending [sqlcommenter](https://google.github.io/sqlcommenter/spec/) comments to statements.
+
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
+use std::fmt::Write;
+
+const ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// Appends a comment carrying the given tags to a statement.
+///
+/// Tags are sorted by key, and keys and values are URL-encoded as required by the specification. If the statement
+/// ends with a semicolon, the comment is inserted before it so that it stays part of the final statement. Statements
+/// which already contain a comment are left unchanged, since they may have been annotated by another layer.
+pub(crate) fn append(query: &str, mut tags: Vec<(String, String)>) -> Option<String> {
+    if tags.is_empty() || query.contains("/*") {
+        return None;
+    }
+    tags.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut comment = "/*".to_string();
+    for (i, (key, value)) in tags.iter().enumerate() {
+        if i > 0 {
+            comment.push(',');
+        }
+        let _ = write!(
+            comment,
+            "{}='{}'",
+            utf8_percent_encode(key, ENCODE_SET),
+            utf8_percent_encode(value, ENCODE_SET),
+        );
+    }
+    comment.push_str("*/");
+
+    let trimmed = query.trim_end();
+    let commented = match trimmed.strip_suffix(';') {
+        Some(statement) => format!("{statement} {comment};"),
+        None => format!("{trimmed} {comment}"),
+    };
+    Some(commented)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::append;
+
+    fn tags(tags: &[(&str, &str)]) -> Vec<(String, String)> {
+        tags.iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_append() {
+        let tags = tags(&[
+            (
+                "traceparent",
+                "00-5bd66ef5095369c7b0d1f8f4bd33716a-c532cb4098ac3dd2-01",
+            ),
+            ("application", "billing service"),
+            ("route", "/users/{id}"),
+            ("quote", "it's"),
+        ]);
+
+        assert_eq!(
+            append("SELECT 1", tags.clone()).unwrap(),
+            "SELECT 1 /*application='billing%20service',quote='it%27s',route='%2Fusers%2F%7Bid%7D',\
+             traceparent='00-5bd66ef5095369c7b0d1f8f4bd33716a-c532cb4098ac3dd2-01'*/"
+        );
+        assert!(
+            append("SELECT 1;\n", tags.clone())
+                .unwrap()
+                .ends_with("'*/;")
+        );
+        assert_eq!(append("SELECT 1 /* existing */", tags.clone()), None);
+        assert_eq!(append("SELECT 1", vec![]), None);
+    }
+}
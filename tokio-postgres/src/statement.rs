@@ -1,13 +1,18 @@
+use crate::Error;
 use crate::client::InnerClient;
 use crate::codec::FrontendMessage;
 use crate::connection::RequestMessages;
-use crate::types::Type;
+use crate::debug_sql;
+use crate::type_registry::TypeRegistry;
+use crate::types::{ToSql, Type};
 use postgres_protocol::message::frontend;
 use std::sync::{Arc, Weak};
 
 struct StatementInner {
     client: Weak<InnerClient>,
+    type_registry: Arc<TypeRegistry>,
     name: String,
+    query: String,
     params: Vec<Type>,
     columns: Vec<Column>,
 }
@@ -39,30 +44,64 @@ impl Statement {
     pub(crate) fn new(
         inner: &Arc<InnerClient>,
         name: String,
+        query: String,
         params: Vec<Type>,
         columns: Vec<Column>,
     ) -> Statement {
         Statement(Arc::new(StatementInner {
             client: Arc::downgrade(inner),
+            type_registry: inner.type_registry().clone(),
             name,
+            query,
             params,
             columns,
         }))
     }
 
-    pub(crate) fn unnamed(params: Vec<Type>, columns: Vec<Column>) -> Statement {
+    pub(crate) fn unnamed(
+        type_registry: Arc<TypeRegistry>,
+        query: String,
+        params: Vec<Type>,
+        columns: Vec<Column>,
+    ) -> Statement {
         Statement(Arc::new(StatementInner {
             client: Weak::new(),
+            type_registry,
             name: String::new(),
+            query,
             params,
             columns,
         }))
     }
 
+    /// Creates a statement with the given parameter and column types which isn't associated with a connection.
+    ///
+    /// This is intended for building rows with [`Row::new_test`] in unit tests of code which works with rows. The
+    /// statement can't be used to execute queries.
+    ///
+    /// [`Row::new_test`]: crate::Row::new_test
+    #[cfg(feature = "test-util")]
+    pub fn new_test(params: Vec<Type>, columns: Vec<Column>) -> Statement {
+        Statement::unnamed(
+            Arc::new(TypeRegistry::default()),
+            String::new(),
+            params,
+            columns,
+        )
+    }
+
     pub(crate) fn name(&self) -> &str {
         &self.0.name
     }
 
+    pub(crate) fn query(&self) -> &str {
+        &self.0.query
+    }
+
+    pub(crate) fn type_registry(&self) -> &TypeRegistry {
+        &self.0.type_registry
+    }
+
     /// Returns the expected types of the statement's parameters.
     pub fn params(&self) -> &[Type] {
         &self.0.params
@@ -72,6 +111,35 @@ impl Statement {
     pub fn columns(&self) -> &[Column] {
         &self.0.columns
     }
+
+    /// Renders the statement's query with the given parameters inlined as SQL literals.
+    ///
+    /// **This is intended for debugging only**, for example to reproduce a failing query in `psql`. The parameters are
+    /// never sent this way when the statement is executed, and the output must not be executed with untrusted values.
+    ///
+    /// Each parameter is rendered as a quoted and escaped literal with an explicit cast to the parameter's type, such as
+    /// `'it''s'::text` or `ARRAY[1,NULL]::int4[]`. Values of types the renderer doesn't know how to decode are rendered
+    /// from their `Debug` output, which may need to be adjusted by hand. Placeholders inside string literals, quoted
+    /// identifiers and comments are left alone.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the number of parameters doesn't match the statement, or if a parameter can't be converted
+    /// to its type.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn f(client: &tokio_postgres::Client) -> Result<(), tokio_postgres::Error> {
+    /// let statement = client.prepare("SELECT * FROM people WHERE name = $1").await?;
+    /// let sql = statement.debug_sql(&[&"O'Brien"])?;
+    /// assert_eq!(sql, "SELECT * FROM people WHERE name = 'O''Brien'::text");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn debug_sql(&self, params: &[&(dyn ToSql + Sync)]) -> Result<String, Error> {
+        debug_sql::render(&self.0.query, &self.0.params, params)
+    }
 }
 
 impl std::fmt::Debug for Statement {
@@ -95,6 +163,20 @@ pub struct Column {
 }
 
 impl Column {
+    /// Creates a column which doesn't come from a table.
+    ///
+    /// This is intended for building statements with [`Statement::new_test`].
+    #[cfg(feature = "test-util")]
+    pub fn new(name: &str, type_: Type) -> Column {
+        Column {
+            name: name.to_string(),
+            table_oid: None,
+            column_id: None,
+            type_modifier: -1,
+            r#type: type_,
+        }
+    }
+
     /// Returns the name of the column.
     pub fn name(&self) -> &str {
         &self.name
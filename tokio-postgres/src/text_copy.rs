@@ -0,0 +1,519 @@
+//! Utilities for working with the PostgreSQL text and CSV copy formats.
+//!
+//! These formats are useful where the binary format can't be used, such as with some connection poolers and foreign
+//! data wrappers. Only the default options of each format are supported: the `COPY` statement must not override the
+//! `DELIMITER`, `NULL`, `QUOTE` or `ESCAPE` options, and must not enable `HEADER`.
+
+use crate::types::{BorrowToSql, ToSql, Type};
+use crate::{CopyInSink, CopyOutStream, Error, slice_iter, text_format};
+use bytes::{BufMut, Bytes, BytesMut};
+use futures_util::{SinkExt, Stream};
+use pin_project_lite::pin_project;
+use std::error;
+use std::io;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::task::{Context, Poll, ready};
+
+/// A non-binary `COPY` format.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TextCopyFormat {
+    /// The text format, used by `COPY ... (FORMAT text)`.
+    ///
+    /// Columns are separated by tabs, `NULL` is represented as `\N`, and special characters are escaped with
+    /// backslashes.
+    Text,
+    /// The CSV format, used by `COPY ... (FORMAT csv)`.
+    ///
+    /// Columns are separated by commas, `NULL` is represented as an unquoted empty value, and values containing
+    /// special characters are quoted with double quotes.
+    Csv,
+}
+
+pin_project! {
+    /// A type which serializes rows of values into the PostgreSQL text or CSV copy format.
+    ///
+    /// Values are converted to the text representation the server would output for them. This supports the built-in
+    /// scalar types commonly used as parameters, arrays of them, domains and enums; writing values of other types
+    /// returns an error.
+    ///
+    /// The copy *must* be explicitly completed via the `finish` method. If it is not, the copy will be aborted.
+    pub struct TextCopyInWriter {
+        #[pin]
+        sink: CopyInSink<Bytes>,
+        types: Vec<Type>,
+        format: TextCopyFormat,
+        buf: BytesMut,
+    }
+}
+
+impl TextCopyInWriter {
+    /// Creates a new writer which will write rows of the provided types to the provided sink in the given format.
+    pub fn new(
+        sink: CopyInSink<Bytes>,
+        types: &[Type],
+        format: TextCopyFormat,
+    ) -> TextCopyInWriter {
+        TextCopyInWriter {
+            sink,
+            types: types.to_vec(),
+            format,
+            buf: BytesMut::new(),
+        }
+    }
+
+    /// Writes a single row.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of values provided does not match the number expected.
+    pub async fn write(self: Pin<&mut Self>, values: &[&(dyn ToSql + Sync)]) -> Result<(), Error> {
+        self.write_raw(slice_iter(values)).await
+    }
+
+    /// A maximally-flexible version of `write`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of values provided does not match the number expected.
+    pub async fn write_raw<P, I>(self: Pin<&mut Self>, values: I) -> Result<(), Error>
+    where
+        P: BorrowToSql,
+        I: IntoIterator<Item = P>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let mut this = self.project();
+
+        let values = values.into_iter();
+        assert!(
+            values.len() == this.types.len(),
+            "expected {} values but got {}",
+            this.types.len(),
+            values.len(),
+        );
+
+        for (i, (value, type_)) in values.zip(this.types.iter()).enumerate() {
+            if i > 0 {
+                this.buf.put_u8(match this.format {
+                    TextCopyFormat::Text => b'\t',
+                    TextCopyFormat::Csv => b',',
+                });
+            }
+            let value = text_format::encode(value.borrow_to_sql(), type_)
+                .map_err(|e| Error::to_sql(e, i))?;
+            match this.format {
+                TextCopyFormat::Text => write_text(this.buf, value.as_deref()),
+                TextCopyFormat::Csv => write_csv(this.buf, value.as_deref()),
+            }
+        }
+        this.buf.put_u8(b'\n');
+
+        if this.buf.len() > 4096 {
+            this.sink.send(this.buf.split().freeze()).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Completes the copy, returning the number of rows added.
+    ///
+    /// This method *must* be used to complete the copy process. If it is not, the copy will be aborted.
+    pub async fn finish(self: Pin<&mut Self>) -> Result<u64, Error> {
+        let mut this = self.project();
+
+        if !this.buf.is_empty() {
+            this.sink.send(this.buf.split().freeze()).await?;
+        }
+        this.sink.finish().await
+    }
+}
+
+fn write_text(buf: &mut BytesMut, value: Option<&str>) {
+    let Some(value) = value else {
+        buf.put_slice(b"\\N");
+        return;
+    };
+
+    for &b in value.as_bytes() {
+        match b {
+            b'\\' => buf.put_slice(b"\\\\"),
+            b'\n' => buf.put_slice(b"\\n"),
+            b'\r' => buf.put_slice(b"\\r"),
+            b'\t' => buf.put_slice(b"\\t"),
+            b => buf.put_u8(b),
+        }
+    }
+}
+
+fn write_csv(buf: &mut BytesMut, value: Option<&str>) {
+    let Some(value) = value else {
+        return;
+    };
+
+    // Empty strings must be quoted to distinguish them from `NULL`, and a lone `\.` from the end-of-data marker.
+    let needs_quotes = value.is_empty()
+        || value == "\\."
+        || value
+            .bytes()
+            .any(|b| matches!(b, b',' | b'"' | b'\n' | b'\r'));
+    if !needs_quotes {
+        buf.put_slice(value.as_bytes());
+        return;
+    }
+
+    buf.put_u8(b'"');
+    for &b in value.as_bytes() {
+        if b == b'"' {
+            buf.put_u8(b'"');
+        }
+        buf.put_u8(b);
+    }
+    buf.put_u8(b'"');
+}
+
+pin_project! {
+    /// A stream of rows parsed from the PostgreSQL text or CSV copy format.
+    ///
+    /// Rows may be split across, or share, the chunks of the underlying stream.
+    pub struct TextCopyOutStream {
+        #[pin]
+        stream: CopyOutStream,
+        format: TextCopyFormat,
+        buf: BytesMut,
+        scanned: usize,
+        in_quotes: bool,
+    }
+}
+
+impl TextCopyOutStream {
+    /// Creates a stream from a raw copy out stream and the format of the data being returned.
+    pub fn new(stream: CopyOutStream, format: TextCopyFormat) -> TextCopyOutStream {
+        TextCopyOutStream {
+            stream,
+            format,
+            buf: BytesMut::new(),
+            scanned: 0,
+            in_quotes: false,
+        }
+    }
+}
+
+impl Stream for TextCopyOutStream {
+    type Item = Result<TextCopyOutRow, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        loop {
+            if let Some(end) = find_row_end(this.buf, *this.format, this.scanned, this.in_quotes) {
+                let line = this.buf.split_to(end + 1);
+                *this.scanned = 0;
+                *this.in_quotes = false;
+                let line = &line[..end];
+                let values = match this.format {
+                    TextCopyFormat::Text => parse_text(line),
+                    TextCopyFormat::Csv => parse_csv(line),
+                };
+                return Poll::Ready(Some(values.map(|values| TextCopyOutRow { values })));
+            }
+
+            match ready!(this.stream.as_mut().poll_next(cx)) {
+                Some(Ok(chunk)) => this.buf.extend_from_slice(&chunk),
+                Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                None if this.buf.is_empty() => return Poll::Ready(None),
+                None => {
+                    this.buf.clear();
+                    return Poll::Ready(Some(Err(invalid_data("unexpected EOF"))));
+                }
+            }
+        }
+    }
+}
+
+/// Returns the position of the newline ending the first row in the buffer, if it is complete.
+///
+/// The scan resumes from where the previous call left off, so that rows spanning many chunks aren't rescanned.
+fn find_row_end(
+    buf: &[u8],
+    format: TextCopyFormat,
+    scanned: &mut usize,
+    in_quotes: &mut bool,
+) -> Option<usize> {
+    for (i, &b) in buf.iter().enumerate().skip(*scanned) {
+        match b {
+            b'"' if format == TextCopyFormat::Csv => *in_quotes = !*in_quotes,
+            b'\n' if !*in_quotes => return Some(i),
+            _ => {}
+        }
+    }
+    *scanned = buf.len();
+    None
+}
+
+fn parse_text(line: &[u8]) -> Result<Vec<Option<String>>, Error> {
+    line.split(|&b| b == b'\t')
+        .map(|field| {
+            if field == b"\\N" {
+                return Ok(None);
+            }
+
+            let mut value = Vec::with_capacity(field.len());
+            let mut bytes = field.iter().copied().peekable();
+            while let Some(b) = bytes.next() {
+                if b != b'\\' {
+                    value.push(b);
+                    continue;
+                }
+
+                let b = bytes
+                    .next()
+                    .ok_or_else(|| invalid_data("trailing backslash"))?;
+                value.push(match b {
+                    b'b' => 0x08,
+                    b'f' => 0x0c,
+                    b'n' => b'\n',
+                    b'r' => b'\r',
+                    b't' => b'\t',
+                    b'v' => 0x0b,
+                    b'0'..=b'7' => {
+                        let mut byte = b - b'0';
+                        for _ in 0..2 {
+                            match bytes.next_if(|b| matches!(b, b'0'..=b'7')) {
+                                Some(digit) => byte = byte.wrapping_mul(8) + (digit - b'0'),
+                                None => break,
+                            }
+                        }
+                        byte
+                    }
+                    b'x' if bytes.peek().is_some_and(u8::is_ascii_hexdigit) => {
+                        let mut byte = 0;
+                        for _ in 0..2 {
+                            match bytes.next_if(u8::is_ascii_hexdigit) {
+                                Some(digit) => byte = byte * 16 + hex_value(digit),
+                                None => break,
+                            }
+                        }
+                        byte
+                    }
+                    b => b,
+                });
+            }
+
+            utf8(value).map(Some)
+        })
+        .collect()
+}
+
+fn hex_value(digit: u8) -> u8 {
+    match digit {
+        b'0'..=b'9' => digit - b'0',
+        b'a'..=b'f' => digit - b'a' + 10,
+        _ => digit - b'A' + 10,
+    }
+}
+
+fn parse_csv(line: &[u8]) -> Result<Vec<Option<String>>, Error> {
+    let mut values = vec![];
+    let mut value = vec![];
+    let mut quoted = false;
+    let mut in_quotes = false;
+
+    let mut bytes = line.iter().copied().peekable();
+    while let Some(b) = bytes.next() {
+        match b {
+            b'"' if in_quotes => {
+                if bytes.next_if_eq(&b'"').is_some() {
+                    value.push(b'"');
+                } else {
+                    in_quotes = false;
+                }
+            }
+            b'"' => {
+                quoted = true;
+                in_quotes = true;
+            }
+            b',' if !in_quotes => {
+                values.push(csv_value(&mut value, quoted)?);
+                quoted = false;
+            }
+            b => value.push(b),
+        }
+    }
+    // A carriage return is only kept if it is quoted, in case the data uses Windows line endings.
+    if !quoted && value.last() == Some(&b'\r') {
+        value.pop();
+    }
+    values.push(csv_value(&mut value, quoted)?);
+
+    Ok(values)
+}
+
+fn csv_value(value: &mut Vec<u8>, quoted: bool) -> Result<Option<String>, Error> {
+    // Only unquoted empty values are `NULL`.
+    if value.is_empty() && !quoted {
+        return Ok(None);
+    }
+    utf8(std::mem::take(value)).map(Some)
+}
+
+fn utf8(value: Vec<u8>) -> Result<String, Error> {
+    String::from_utf8(value)
+        .map_err(|e| Error::parse(io::Error::new(io::ErrorKind::InvalidData, e)))
+}
+
+fn invalid_data(message: &str) -> Error {
+    Error::parse(io::Error::new(io::ErrorKind::InvalidData, message))
+}
+
+/// A row of data parsed from a text or CSV copy out stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextCopyOutRow {
+    values: Vec<Option<String>>,
+}
+
+impl TextCopyOutRow {
+    /// Returns the number of values in the row.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Determines if the row contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns a value from the row in its text representation, or `None` if it is `NULL`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    pub fn get(&self, idx: usize) -> Option<&str> {
+        self.values[idx].as_deref()
+    }
+
+    /// Like `parse`, but returns a `Result` rather than panicking.
+    pub fn try_parse<T>(&self, idx: usize) -> Result<Option<T>, Error>
+    where
+        T: FromStr,
+        T::Err: Into<Box<dyn error::Error + Sync + Send>>,
+    {
+        match self.values.get(idx) {
+            Some(Some(value)) => value
+                .parse()
+                .map(Some)
+                .map_err(|e: T::Err| Error::from_sql(e.into(), idx)),
+            Some(None) => Ok(None),
+            None => Err(Error::column(idx.to_string())),
+        }
+    }
+
+    /// Parses a value from the row with its `FromStr` implementation, returning `None` if it is `NULL`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds or if the value cannot be parsed.
+    pub fn parse<T>(&self, idx: usize) -> Option<T>
+    where
+        T: FromStr,
+        T::Err: Into<Box<dyn error::Error + Sync + Send>>,
+    {
+        match self.try_parse(idx) {
+            Ok(value) => value,
+            Err(e) => panic!("error retrieving column {}: {}", idx, e),
+        }
+    }
+
+    /// Returns the values of the row.
+    pub fn values(&self) -> &[Option<String>] {
+        &self.values
+    }
+
+    /// Consumes the row, returning its values.
+    pub fn into_values(self) -> Vec<Option<String>> {
+        self.values
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TextCopyFormat, find_row_end, parse_csv, parse_text, write_csv, write_text};
+    use bytes::BytesMut;
+
+    #[test]
+    fn test_write_text() {
+        let mut buf = BytesMut::new();
+        write_text(&mut buf, Some("a\tb\\c\r\nd"));
+        buf.extend_from_slice(b"|");
+        write_text(&mut buf, None);
+        assert_eq!(&buf[..], b"a\\tb\\\\c\\r\\nd|\\N");
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let mut buf = BytesMut::new();
+        for value in [
+            Some("plain"),
+            Some(""),
+            None,
+            Some("a,\"b\"\n"),
+            Some("\\."),
+        ] {
+            write_csv(&mut buf, value);
+            buf.extend_from_slice(b"|");
+        }
+        assert_eq!(&buf[..], b"plain|\"\"||\"a,\"\"b\"\"\n\"|\"\\.\"|");
+    }
+
+    #[test]
+    fn test_parse_text() {
+        assert_eq!(
+            parse_text(b"a\\tb\\\\c\\r\\nd\t\\N\t\t\\101\\x42\\q").unwrap(),
+            [
+                Some("a\tb\\c\r\nd".to_string()),
+                None,
+                Some(String::new()),
+                Some("ABq".to_string()),
+            ]
+        );
+        assert!(parse_text(b"\\").is_err());
+        assert!(parse_text(b"\\377").is_err());
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        assert_eq!(
+            parse_csv(b"plain,\"\",,\"a,\"\"b\"\"\n\",\"\\.\"").unwrap(),
+            [
+                Some("plain".to_string()),
+                Some(String::new()),
+                None,
+                Some("a,\"b\"\n".to_string()),
+                Some("\\.".to_string()),
+            ]
+        );
+        assert_eq!(parse_csv(b"").unwrap(), [None]);
+    }
+
+    #[test]
+    fn test_find_row_end() {
+        let mut scanned = 0;
+        let mut in_quotes = false;
+        let buf = b"1,\"a\nb";
+        assert_eq!(
+            find_row_end(buf, TextCopyFormat::Csv, &mut scanned, &mut in_quotes),
+            None
+        );
+        assert_eq!((scanned, in_quotes), (buf.len(), true));
+
+        let buf = b"1,\"a\nb\"\n2";
+        assert_eq!(
+            find_row_end(buf, TextCopyFormat::Csv, &mut scanned, &mut in_quotes),
+            Some(7)
+        );
+        assert_eq!(
+            find_row_end(b"a\"\nb", TextCopyFormat::Text, &mut 0, &mut false),
+            Some(2)
+        );
+    }
+}
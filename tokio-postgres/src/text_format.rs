@@ -0,0 +1,318 @@
+//! Conversion of values to the Postgres text format.
+//!
+//! Values are serialized with their `ToSql` implementations, which usually use the binary format, and then converted to
+//! the text format the server would output for them. Only the built-in types which are commonly used as parameters are
+//! supported.
+
+use crate::types::{Format, IsNull, Kind, PgNumeric, ToSql, Type};
+use bytes::BytesMut;
+use fallible_iterator::FallibleIterator;
+use postgres_protocol::types;
+use std::error::Error;
+use std::fmt::Write;
+
+const USECS_PER_SEC: i64 = 1_000_000;
+const USECS_PER_DAY: i64 = 86_400 * USECS_PER_SEC;
+// Days from 0000-03-01 to 2000-01-01, the Postgres epoch.
+const EPOCH_DAYS: i64 = 730_425;
+
+/// Serializes a value in the text format, returning `None` if it is `NULL`.
+pub(crate) fn encode(
+    value: &dyn ToSql,
+    ty: &Type,
+) -> Result<Option<String>, Box<dyn Error + Sync + Send>> {
+    let mut buf = BytesMut::new();
+    match value.to_sql_checked(ty, &mut buf)? {
+        IsNull::Yes => Ok(None),
+        IsNull::No => match value.encode_format(ty) {
+            Format::Text => Ok(Some(String::from_utf8(buf.to_vec())?)),
+            Format::Binary => match decode(ty, &buf) {
+                Some(text) => Ok(Some(text)),
+                None => Err(format!("type {ty} is not supported by the text format").into()),
+            },
+        },
+    }
+}
+
+/// Converts the binary encoding of a value to the text format, or returns `None` if the type isn't supported.
+pub(crate) fn decode(ty: &Type, raw: &[u8]) -> Option<String> {
+    match ty.kind() {
+        Kind::Array(member) => array(member, raw),
+        _ => scalar(ty, raw),
+    }
+}
+
+/// Like `decode`, but doesn't support arrays.
+pub(crate) fn scalar(ty: &Type, raw: &[u8]) -> Option<String> {
+    match ty.kind() {
+        Kind::Domain(base) => return scalar(base, raw),
+        Kind::Enum(_) => return types::text_from_sql(raw).ok().map(str::to_string),
+        _ => {}
+    }
+
+    let text = match *ty {
+        Type::BOOL => {
+            if types::bool_from_sql(raw).ok()? {
+                "t".to_string()
+            } else {
+                "f".to_string()
+            }
+        }
+        Type::CHAR => match types::char_from_sql(raw).ok()? {
+            c @ 1.. => char::from(c as u8).to_string(),
+            _ => return None,
+        },
+        Type::INT2 => types::int2_from_sql(raw).ok()?.to_string(),
+        Type::INT4 => types::int4_from_sql(raw).ok()?.to_string(),
+        Type::INT8 => types::int8_from_sql(raw).ok()?.to_string(),
+        Type::OID => types::oid_from_sql(raw).ok()?.to_string(),
+        Type::FLOAT4 => float(types::float4_from_sql(raw).ok()?.into()),
+        Type::FLOAT8 => float(types::float8_from_sql(raw).ok()?),
+        Type::NUMERIC => <PgNumeric as crate::types::FromSql<'_>>::from_sql(ty, raw)
+            .ok()?
+            .to_string(),
+        Type::TEXT
+        | Type::VARCHAR
+        | Type::BPCHAR
+        | Type::NAME
+        | Type::UNKNOWN
+        | Type::JSON
+        | Type::XML => types::text_from_sql(raw).ok()?.to_string(),
+        Type::JSONB => match raw.split_first()? {
+            (1, json) => std::str::from_utf8(json).ok()?.to_string(),
+            _ => return None,
+        },
+        Type::BYTEA => {
+            let mut hex = String::from("\\x");
+            for byte in types::bytea_from_sql(raw) {
+                let _ = write!(hex, "{byte:02x}");
+            }
+            hex
+        }
+        Type::UUID => {
+            let bytes = types::uuid_from_sql(raw).ok()?;
+            let mut uuid = String::new();
+            for (i, byte) in bytes.iter().enumerate() {
+                if matches!(i, 4 | 6 | 8 | 10) {
+                    uuid.push('-');
+                }
+                let _ = write!(uuid, "{byte:02x}");
+            }
+            uuid
+        }
+        Type::INET | Type::CIDR => {
+            let inet = types::inet_from_sql(raw).ok()?;
+            format!("{}/{}", inet.addr(), inet.netmask())
+        }
+        Type::DATE => match types::date_from_sql(raw).ok()? {
+            i32::MAX => "infinity".to_string(),
+            i32::MIN => "-infinity".to_string(),
+            days => date(days.into(), ""),
+        },
+        Type::TIMESTAMP | Type::TIMESTAMPTZ => match types::timestamp_from_sql(raw).ok()? {
+            i64::MAX => "infinity".to_string(),
+            i64::MIN => "-infinity".to_string(),
+            usecs => {
+                let days = usecs.div_euclid(USECS_PER_DAY);
+                let time = time(usecs.rem_euclid(USECS_PER_DAY));
+                let offset = if *ty == Type::TIMESTAMPTZ { "+00" } else { "" };
+                date(days, &format!(" {time}{offset}"))
+            }
+        },
+        Type::TIME => time(types::time_from_sql(raw).ok()?),
+        _ => return None,
+    };
+    Some(text)
+}
+
+fn float(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value == f64::INFINITY {
+        "Infinity".to_string()
+    } else if value == f64::NEG_INFINITY {
+        "-Infinity".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+fn array(member: &Type, raw: &[u8]) -> Option<String> {
+    let array = types::array_from_sql(raw).ok()?;
+    let dimensions = array.dimensions().collect::<Vec<_>>().ok()?;
+    if dimensions.is_empty() {
+        return Some("{}".to_string());
+    }
+
+    let mut text = String::new();
+    if dimensions.iter().any(|d| d.lower_bound != 1) {
+        for dimension in &dimensions {
+            let upper_bound = dimension.lower_bound + dimension.len - 1;
+            let _ = write!(text, "[{}:{}]", dimension.lower_bound, upper_bound);
+        }
+        text.push('=');
+    }
+
+    let lens = dimensions
+        .iter()
+        .map(|d| d.len as usize)
+        .collect::<Vec<_>>();
+    let mut values = array.values();
+    nest_array(&mut text, &lens, &mut || {
+        Some(match values.next().ok()?? {
+            Some(raw) => array_element(&scalar(member, raw)?),
+            None => "NULL".to_string(),
+        })
+    })?;
+    Some(text)
+}
+
+fn nest_array(
+    out: &mut String,
+    lens: &[usize],
+    next: &mut impl FnMut() -> Option<String>,
+) -> Option<()> {
+    out.push('{');
+    for i in 0..lens[0] {
+        if i > 0 {
+            out.push(',');
+        }
+        if lens.len() > 1 {
+            nest_array(out, &lens[1..], next)?;
+        } else {
+            out.push_str(&next()?);
+        }
+    }
+    out.push('}');
+    Some(())
+}
+
+fn array_element(value: &str) -> String {
+    let needs_quotes = value.is_empty()
+        || value.eq_ignore_ascii_case("NULL")
+        || value
+            .chars()
+            .any(|c| matches!(c, '{' | '}' | ',' | '"' | '\\') || c.is_ascii_whitespace());
+    if !needs_quotes {
+        return value.to_string();
+    }
+
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        if matches!(c, '"' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Formats a number of days relative to the Postgres epoch as an ISO 8601 date.
+fn date(days: i64, suffix: &str) -> String {
+    // See http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let days = days + EPOCH_DAYS;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    if year > 0 {
+        format!("{year:04}-{month:02}-{day:02}{suffix}")
+    } else {
+        format!("{:04}-{month:02}-{day:02}{suffix} BC", 1 - year)
+    }
+}
+
+/// Formats a number of microseconds since midnight as a time of day.
+fn time(usecs: i64) -> String {
+    let secs = usecs / USECS_PER_SEC;
+    let mut time = format!("{:02}:{:02}:{:02}", secs / 3_600, secs / 60 % 60, secs % 60);
+    let fraction = usecs % USECS_PER_SEC;
+    if fraction != 0 {
+        let fraction = format!("{fraction:06}");
+        time.push('.');
+        time.push_str(fraction.trim_end_matches('0'));
+    }
+    time
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{date, decode, encode, time};
+    use crate::types::Type;
+    use bytes::BytesMut;
+    use postgres_protocol::types;
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(encode(&true, &Type::BOOL).unwrap().unwrap(), "t");
+        assert_eq!(encode(&-1.5f64, &Type::FLOAT8).unwrap().unwrap(), "-1.5");
+        assert_eq!(
+            encode(&&b"\x00\xff"[..], &Type::BYTEA).unwrap().unwrap(),
+            "\\x00ff"
+        );
+        assert_eq!(
+            encode(
+                &vec![Some("a b"), None, Some("NULL"), Some("x"), Some("\"\\")],
+                &Type::TEXT_ARRAY
+            )
+            .unwrap()
+            .unwrap(),
+            r#"{"a b",NULL,"NULL",x,"\"\\"}"#
+        );
+        assert_eq!(encode(&None::<i32>, &Type::INT4).unwrap(), None);
+        assert!(encode(&"foo", &Type::INT4).is_err());
+    }
+
+    #[test]
+    fn test_array_dimensions() {
+        let mut buf = BytesMut::new();
+        types::array_to_sql(
+            [
+                types::ArrayDimension {
+                    len: 2,
+                    lower_bound: 0,
+                },
+                types::ArrayDimension {
+                    len: 1,
+                    lower_bound: 1,
+                },
+            ],
+            Type::INT4.oid(),
+            [1, 2],
+            |v, buf| {
+                types::int4_to_sql(v, buf);
+                Ok(postgres_protocol::IsNull::No)
+            },
+            &mut buf,
+        )
+        .unwrap();
+        assert_eq!(
+            decode(&Type::INT4_ARRAY, &buf).unwrap(),
+            "[0:1][1:1]={{1},{2}}"
+        );
+    }
+
+    #[test]
+    fn test_date() {
+        assert_eq!(date(0, ""), "2000-01-01");
+        assert_eq!(date(-1, ""), "1999-12-31");
+        assert_eq!(date(60, " 12:00:00"), "2000-03-01 12:00:00");
+        assert_eq!(date(-730_119, ""), "0001-01-01");
+        assert_eq!(date(-730_120, ""), "0001-12-31 BC");
+    }
+
+    #[test]
+    fn test_time() {
+        assert_eq!(time(0), "00:00:00");
+        assert_eq!(time(86_399_500_000), "23:59:59.5");
+        assert_eq!(time(1), "00:00:00.000001");
+    }
+}
@@ -2,6 +2,7 @@
 
 use std::error::Error;
 use std::future::Future;
+use std::path::Path;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 use std::{fmt, io};
@@ -32,6 +33,130 @@ impl ChannelBinding {
     }
 }
 
+/// Details of a negotiated TLS session.
+///
+/// The format of the protocol version and cipher suite names depends on the TLS implementation.
+#[derive(Debug, Clone, Default)]
+pub struct TlsInfo {
+    protocol_version: Option<String>,
+    cipher_suite: Option<String>,
+    peer_certificate: Option<Vec<u8>>,
+}
+
+impl TlsInfo {
+    /// Creates a `TlsInfo` containing no information.
+    pub fn none() -> TlsInfo {
+        TlsInfo::default()
+    }
+
+    /// Creates a new `TlsInfo`.
+    pub fn new(
+        protocol_version: Option<String>,
+        cipher_suite: Option<String>,
+        peer_certificate: Option<Vec<u8>>,
+    ) -> TlsInfo {
+        TlsInfo {
+            protocol_version,
+            cipher_suite,
+            peer_certificate,
+        }
+    }
+
+    /// Returns the name of the negotiated protocol version, such as `TLSv1.3`.
+    pub fn protocol_version(&self) -> Option<&str> {
+        self.protocol_version.as_deref()
+    }
+
+    /// Returns the name of the negotiated cipher suite.
+    pub fn cipher_suite(&self) -> Option<&str> {
+        self.cipher_suite.as_deref()
+    }
+
+    /// Returns the server's certificate, DER-encoded.
+    pub fn peer_certificate(&self) -> Option<&[u8]> {
+        self.peer_certificate.as_deref()
+    }
+}
+
+/// The server certificate verification required by the `verify-ca` and `verify-full` SSL modes.
+#[derive(Debug, Copy, Clone)]
+pub struct Verification<'a> {
+    verify_hostname: bool,
+    root_cert: Option<&'a Path>,
+}
+
+impl<'a> Verification<'a> {
+    pub(crate) fn new(verify_hostname: bool, root_cert: Option<&'a Path>) -> Verification<'a> {
+        Verification {
+            verify_hostname,
+            root_cert,
+        }
+    }
+
+    /// Determines if the server's certificate must match its hostname, as in the `verify-full` mode.
+    pub fn verify_hostname(&self) -> bool {
+        self.verify_hostname
+    }
+
+    /// Returns the path of a PEM file containing the certificate authorities which must have signed the server's
+    /// certificate.
+    ///
+    /// If `None`, the connector's default trust roots should be used. This is only the case when the server's hostname
+    /// must also be verified.
+    pub fn root_cert(&self) -> Option<&'a Path> {
+        self.root_cert
+    }
+}
+
+/// The client certificate configured by the `sslcert`, `sslkey`, and `sslpassword` settings.
+#[derive(Copy, Clone)]
+pub struct ClientCertificate<'a> {
+    cert: &'a Path,
+    key: Option<&'a Path>,
+    password: Option<&'a [u8]>,
+}
+
+impl<'a> ClientCertificate<'a> {
+    pub(crate) fn new(
+        cert: &'a Path,
+        key: Option<&'a Path>,
+        password: Option<&'a [u8]>,
+    ) -> ClientCertificate<'a> {
+        ClientCertificate {
+            cert,
+            key,
+            password,
+        }
+    }
+
+    /// Returns the path of the certificate file, in PEM or DER format.
+    pub fn cert(&self) -> &'a Path {
+        self.cert
+    }
+
+    /// Returns the path of the private key file, in PEM or DER format.
+    ///
+    /// If `None`, the key is contained in the PEM certificate file.
+    pub fn key(&self) -> Option<&'a Path> {
+        self.key
+    }
+
+    /// Returns the password used to decrypt the private key, if it is encrypted.
+    pub fn password(&self) -> Option<&'a [u8]> {
+        self.password
+    }
+}
+
+// Omit password from debug output
+impl fmt::Debug for ClientCertificate<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClientCertificate")
+            .field("cert", &self.cert)
+            .field("key", &self.key)
+            .finish()
+    }
+}
+
 /// A constructor of `TlsConnect`ors.
 ///
 /// Requires the `runtime` Cargo feature (enabled by default).
@@ -62,6 +187,31 @@ pub trait TlsConnect<S> {
     /// Returns a future performing a TLS handshake over the stream.
     fn connect(self, stream: S) -> Self::Future;
 
+    /// Requires the server's certificate to be verified during the handshake.
+    ///
+    /// This is called before `connect` when the `verify-ca` or `verify-full` SSL mode is configured. Implementations
+    /// must either enforce the requested verification, overriding any settings of their own which would weaken it, or
+    /// return an error. The default implementation returns an error.
+    fn set_verification(
+        &mut self,
+        verification: &Verification<'_>,
+    ) -> Result<(), Box<dyn Error + Sync + Send>> {
+        let _ = verification;
+        Err("TLS connector does not support certificate verification".into())
+    }
+
+    /// Presents a client certificate to the server during the handshake.
+    ///
+    /// This is called before `connect` when the `sslcert` setting is configured, replacing any client certificate the
+    /// connector was created with. The default implementation returns an error.
+    fn set_client_certificate(
+        &mut self,
+        certificate: &ClientCertificate<'_>,
+    ) -> Result<(), Box<dyn Error + Sync + Send>> {
+        let _ = certificate;
+        Err("TLS connector does not support client certificates".into())
+    }
+
     #[doc(hidden)]
     fn can_connect(&self, _: private::ForcePrivateApi) -> bool {
         true
@@ -72,6 +222,13 @@ pub trait TlsConnect<S> {
 pub trait TlsStream: AsyncRead + AsyncWrite {
     /// Returns channel binding information for the session.
     fn channel_binding(&self) -> ChannelBinding;
+
+    /// Returns details of the negotiated session.
+    ///
+    /// The default implementation returns no information.
+    fn tls_info(&self) -> TlsInfo {
+        TlsInfo::none()
+    }
 }
 
 /// A `MakeTlsConnect` and `TlsConnect` implementation which simply returns an error.
@@ -17,7 +17,7 @@ mod private {
         pub async fn into_statement(self, client: &Arc<InnerClient>) -> Result<Statement, Error> {
             match self {
                 ToStatementType::Statement(s) => Ok(s.clone()),
-                ToStatementType::Query(s) => prepare::prepare(client, s, &[]).await,
+                ToStatementType::Query(s) => prepare::prepare(client, s, &[], true).await,
             }
         }
     }
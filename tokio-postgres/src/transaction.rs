@@ -1,6 +1,9 @@
 #[cfg(feature = "runtime")]
 use crate::Socket;
+use crate::bulk_insert::BulkInsert;
 use crate::copy_out::CopyOutStream;
+#[cfg(feature = "explain")]
+use crate::explain::{Explain, ExplainOptions};
 use crate::query::RowStream;
 #[cfg(feature = "runtime")]
 use crate::tls::MakeTlsConnect;
@@ -58,7 +61,7 @@ impl<'a> Transaction<'a> {
         } else {
             "COMMIT".to_string()
         };
-        self.client.batch_execute(&query).await
+        self.client.batch_execute_internal(&query).await
     }
 
     /// Rolls the transaction back, discarding all changes made within it.
@@ -71,7 +74,7 @@ impl<'a> Transaction<'a> {
         } else {
             "ROLLBACK".to_string()
         };
-        self.client.batch_execute(&query).await
+        self.client.batch_execute_internal(&query).await
     }
 
     /// Like `Client::prepare`.
@@ -261,6 +264,35 @@ impl<'a> Transaction<'a> {
         query::query_portal(self.client.inner(), portal, max_rows).await
     }
 
+    /// Like `Client::execute_many`.
+    pub async fn execute_many<T, I, J, P>(
+        &self,
+        statement: &T,
+        param_sets: I,
+    ) -> Result<Vec<u64>, Error>
+    where
+        T: ?Sized + ToStatement,
+        I: IntoIterator<Item = J>,
+        J: IntoIterator<Item = P>,
+        J::IntoIter: ExactSizeIterator,
+        P: BorrowToSql,
+    {
+        self.client.execute_many(statement, param_sets).await
+    }
+
+    /// Like `Client::explain`.
+    ///
+    /// Analyzing a statement in a transaction which is rolled back measures it without keeping its changes.
+    #[cfg(feature = "explain")]
+    pub async fn explain(
+        &self,
+        query: &str,
+        params: &[&(dyn ToSql + Sync)],
+        options: ExplainOptions,
+    ) -> Result<Explain, Error> {
+        self.client.explain(query, params, options).await
+    }
+
     /// Like `Client::copy_in`.
     pub async fn copy_in<T, U>(&self, statement: &T) -> Result<CopyInSink<U>, Error>
     where
@@ -270,6 +302,15 @@ impl<'a> Transaction<'a> {
         self.client.copy_in(statement).await
     }
 
+    /// Like `Client::copy_in_from`.
+    pub async fn copy_in_from<T, R>(&self, statement: &T, reader: R) -> Result<u64, Error>
+    where
+        T: ?Sized + ToStatement,
+        R: AsyncRead,
+    {
+        self.client.copy_in_from(statement, reader).await
+    }
+
     /// Like `Client::copy_out`.
     pub async fn copy_out<T>(&self, statement: &T) -> Result<CopyOutStream, Error>
     where
@@ -278,6 +319,20 @@ impl<'a> Transaction<'a> {
         self.client.copy_out(statement).await
     }
 
+    /// Like `Client::copy_out_to`.
+    pub async fn copy_out_to<T, W>(&self, statement: &T, writer: W) -> Result<u64, Error>
+    where
+        T: ?Sized + ToStatement,
+        W: AsyncWrite,
+    {
+        self.client.copy_out_to(statement, writer).await
+    }
+
+    /// Like `Client::bulk_insert`.
+    pub fn bulk_insert(&self, table: &str, columns: &[&str]) -> BulkInsert<'_> {
+        self.client.bulk_insert(table, columns)
+    }
+
     /// Like `Client::simple_query`.
     pub async fn simple_query(&self, query: &str) -> Result<Vec<SimpleQueryMessage>, Error> {
         self.client.simple_query(query).await
@@ -332,7 +387,7 @@ impl<'a> Transaction<'a> {
         let depth = self.savepoint.as_ref().map_or(0, |sp| sp.depth) + 1;
         let name = name.unwrap_or_else(|| format!("sp_{depth}"));
         let query = format!("SAVEPOINT {name}");
-        self.batch_execute(&query).await?;
+        self.client.batch_execute_internal(&query).await?;
 
         Ok(Transaction {
             client: self.client,
@@ -131,7 +131,7 @@ impl<'a> TransactionBuilder<'a> {
                 client: self.client,
                 done: false,
             };
-            self.client.batch_execute(&query).await?;
+            self.client.batch_execute_internal(&query).await?;
             cleaner.done = true;
         }
 
@@ -0,0 +1,64 @@
+//! A cache of type information.
+
+use crate::types::{Oid, Type};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// A cache of the definitions of user-defined types, keyed by OID.
+///
+/// When a query uses a type which isn't built in, such as an enum, composite or domain, the client looks up its
+/// definition in the database catalog and stores it in its type cache. Each client has its own cache by default, but
+/// a cache can be shared between clients with [`Client::set_type_cache`] so that types resolved by one connection
+/// don't have to be looked up again by the others. This is mostly useful for connection pools, where each new
+/// connection would otherwise repeat the same catalog queries.
+///
+/// The cache is a cheap handle; clones refer to the same set of types. OIDs are only meaningful within a single
+/// database, so a cache must only be shared between clients connected to the same database or its replicas.
+///
+/// [`Client::set_type_cache`]: crate::Client::set_type_cache
+#[derive(Clone, Default)]
+pub struct TypeCache {
+    types: Arc<Mutex<HashMap<Oid, Type>>>,
+}
+
+impl fmt::Debug for TypeCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypeCache")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+impl TypeCache {
+    /// Creates a new, empty cache.
+    pub fn new() -> TypeCache {
+        TypeCache::default()
+    }
+
+    /// Returns the cached type with the specified OID.
+    pub fn get(&self, oid: Oid) -> Option<Type> {
+        self.types.lock().get(&oid).cloned()
+    }
+
+    /// Adds a type to the cache, replacing any type with the same OID.
+    pub fn insert(&self, type_: Type) {
+        self.types.lock().insert(type_.oid(), type_);
+    }
+
+    /// Removes all types from the cache.
+    pub fn clear(&self) {
+        self.types.lock().clear();
+    }
+
+    /// Returns the number of types in the cache.
+    pub fn len(&self) -> usize {
+        self.types.lock().len()
+    }
+
+    /// Determines if the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.types.lock().is_empty()
+    }
+}
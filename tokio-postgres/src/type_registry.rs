@@ -0,0 +1,232 @@
+//! Runtime type handlers.
+
+use crate::types::{IsNull, Oid, ToSql, Type, WrongType};
+use bytes::BytesMut;
+use parking_lot::RwLock;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+
+/// A conversion between a Rust type and a Postgres type, registered on a client at runtime.
+///
+/// This is an alternative to implementing `FromSql` and `ToSql` for types which are only known at runtime, such as
+/// extension types whose OIDs differ between databases. Handlers are registered with
+/// [`Client::register_type_handler`], values are read with [`Row::get_custom`], and parameters are passed with
+/// [`Client::custom_param`]. Handlers don't affect `Row::get`, which always uses `FromSql`.
+///
+/// [`Client::register_type_handler`]: crate::Client::register_type_handler
+/// [`Client::custom_param`]: crate::Client::custom_param
+/// [`Row::get_custom`]: crate::Row::get_custom
+pub trait TypeHandler: Send + Sync + 'static {
+    /// The Rust type values are converted to and from.
+    type Value: Send + Sync + 'static;
+
+    /// Creates a value from a buffer of data in the binary format of the Postgres type.
+    #[allow(clippy::wrong_self_convention)]
+    fn from_sql(&self, ty: &Type, raw: &[u8]) -> Result<Self::Value, Box<dyn Error + Sync + Send>>;
+
+    /// Converts a value into the binary format of the Postgres type, appending it to `out`.
+    fn to_sql(
+        &self,
+        ty: &Type,
+        value: &Self::Value,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>>;
+}
+
+/// Identifies the Postgres types a [`TypeHandler`] applies to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TypeKey {
+    /// The type with this OID.
+    Oid(Oid),
+    /// Any type with this name, in any schema.
+    Name(String),
+}
+
+impl From<Oid> for TypeKey {
+    fn from(oid: Oid) -> TypeKey {
+        TypeKey::Oid(oid)
+    }
+}
+
+impl From<&str> for TypeKey {
+    fn from(name: &str) -> TypeKey {
+        TypeKey::Name(name.to_string())
+    }
+}
+
+impl From<String> for TypeKey {
+    fn from(name: String) -> TypeKey {
+        TypeKey::Name(name)
+    }
+}
+
+trait ErasedHandler: Send + Sync {
+    // The `TypeId` of the handler's value type.
+    fn value_type(&self) -> TypeId;
+
+    #[allow(clippy::wrong_self_convention)]
+    fn from_sql(
+        &self,
+        ty: &Type,
+        raw: &[u8],
+    ) -> Result<Box<dyn Any + Send + Sync>, Box<dyn Error + Sync + Send>>;
+
+    fn to_sql(
+        &self,
+        ty: &Type,
+        value: &(dyn Any + Send + Sync),
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>>;
+}
+
+impl<H> ErasedHandler for H
+where
+    H: TypeHandler,
+{
+    fn value_type(&self) -> TypeId {
+        TypeId::of::<H::Value>()
+    }
+
+    fn from_sql(
+        &self,
+        ty: &Type,
+        raw: &[u8],
+    ) -> Result<Box<dyn Any + Send + Sync>, Box<dyn Error + Sync + Send>> {
+        TypeHandler::from_sql(self, ty, raw).map(|v| Box::new(v) as _)
+    }
+
+    fn to_sql(
+        &self,
+        ty: &Type,
+        value: &(dyn Any + Send + Sync),
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        match value.downcast_ref::<H::Value>() {
+            Some(value) => TypeHandler::to_sql(self, ty, value, out),
+            None => Err(Box::new(WrongType::new::<H::Value>(ty.clone()))),
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct TypeRegistry {
+    handlers: RwLock<Handlers>,
+}
+
+#[derive(Default)]
+struct Handlers {
+    by_oid: HashMap<Oid, Arc<dyn ErasedHandler>>,
+    by_name: HashMap<String, Arc<dyn ErasedHandler>>,
+}
+
+impl TypeRegistry {
+    pub(crate) fn register<H>(&self, key: TypeKey, handler: H)
+    where
+        H: TypeHandler,
+    {
+        let mut handlers = self.handlers.write();
+        match key {
+            TypeKey::Oid(oid) => handlers.by_oid.insert(oid, Arc::new(handler)),
+            TypeKey::Name(name) => handlers.by_name.insert(name, Arc::new(handler)),
+        };
+    }
+
+    // Handlers registered by OID take precedence over those registered by name.
+    fn handler(&self, ty: &Type) -> Option<Arc<dyn ErasedHandler>> {
+        let handlers = self.handlers.read();
+        handlers
+            .by_oid
+            .get(&ty.oid())
+            .or_else(|| handlers.by_name.get(ty.name()))
+            .cloned()
+    }
+
+    pub(crate) fn decode<T>(
+        &self,
+        ty: &Type,
+        raw: Option<&[u8]>,
+    ) -> Result<Option<T>, Box<dyn Error + Sync + Send>>
+    where
+        T: 'static,
+    {
+        let wrong_type = || Box::new(WrongType::new::<T>(ty.clone()));
+        let handler = self.handler(ty).ok_or_else(wrong_type)?;
+        let Some(raw) = raw else {
+            return Ok(None);
+        };
+
+        match handler.from_sql(ty, raw)?.downcast::<T>() {
+            Ok(value) => Ok(Some(*value)),
+            Err(_) => Err(wrong_type()),
+        }
+    }
+}
+
+/// A query parameter converted by a [`TypeHandler`] registered on the client.
+///
+/// Created with [`Client::custom_param`]. The handler is looked up by the type of the parameter when the query is
+/// executed, and the conversion fails if no handler is registered for it or the handler's value type is not `T`.
+///
+/// [`Client::custom_param`]: crate::Client::custom_param
+pub struct CustomParam<T> {
+    registry: Arc<TypeRegistry>,
+    value: T,
+}
+
+impl<T> CustomParam<T> {
+    pub(crate) fn new(registry: Arc<TypeRegistry>, value: T) -> CustomParam<T> {
+        CustomParam { registry, value }
+    }
+
+    /// Returns the wrapped value.
+    pub fn get(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> fmt::Debug for CustomParam<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CustomParam").field(&self.value).finish()
+    }
+}
+
+impl<T> ToSql for CustomParam<T>
+where
+    T: fmt::Debug + Send + Sync + 'static,
+{
+    fn to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        match self.registry.handler(ty) {
+            Some(handler) => handler.to_sql(ty, &self.value, out),
+            None => Err(Box::new(WrongType::new::<T>(ty.clone()))),
+        }
+    }
+
+    // The handler can only be looked up once the parameter's type is known, so the type is checked in
+    // `to_sql_checked` instead.
+    fn accepts(_: &Type) -> bool {
+        true
+    }
+
+    fn to_sql_checked(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        match self.registry.handler(ty) {
+            Some(handler) if handler.value_type() == TypeId::of::<T>() => {
+                handler.to_sql(ty, &self.value, out)
+            }
+            _ => Err(Box::new(WrongType::new::<T>(ty.clone()))),
+        }
+    }
+}
@@ -0,0 +1,186 @@
+//! An `io_uring` based transport.
+//!
+//! [`UringStream`] adapts a [`tokio_uring`](tokio_uring_04) TCP stream to the [`AsyncRead`] and [`AsyncWrite`]
+//! traits, so that a connection's reads and writes are submitted through `io_uring` rather than driven by readiness
+//! notifications from `epoll`. Connections made over it are established with [`Config::connect_raw`] and must be
+//! driven from within a `tokio_uring` runtime:
+//!
+//! ```no_run
+//! use tokio_postgres::NoTls;
+//! use tokio_postgres::uring::UringStream;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! tokio_uring_04::start(async {
+//!     let config = "user=postgres".parse::<tokio_postgres::Config>()?;
+//!     let stream = UringStream::connect("127.0.0.1:5432".parse()?).await?;
+//!     let (client, connection) = config.connect_raw(stream, NoTls).await?;
+//!     tokio_uring_04::spawn(connection);
+//!
+//!     client.batch_execute("SELECT 1").await?;
+//!     Ok(())
+//! })
+//! # }
+//! ```
+//!
+//! Since the stream is not connected through `Config::connect`, [`Client::cancel_token`] cannot be used to cancel
+//! queries on connections made this way.
+//!
+//! Requires the `with-tokio-uring-0_4` Cargo feature, and is only available on Linux.
+//!
+//! [`Config::connect_raw`]: crate::Config::connect_raw
+//! [`Client::cancel_token`]: crate::Client::cancel_token
+
+use std::future::Future;
+use std::io;
+use std::mem;
+use std::net::{Shutdown, SocketAddr};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, ready};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio_uring_04::BufResult;
+use tokio_uring_04::net::TcpStream;
+
+const READ_BUF_SIZE: usize = 8 * 1024;
+
+type Op = Pin<Box<dyn Future<Output = BufResult<usize, Vec<u8>>>>>;
+
+/// A TCP stream whose I/O is performed with `io_uring`.
+///
+/// `io_uring` operations take ownership of their buffers, so reads are staged through an internal buffer and writes
+/// copy the data they are given.
+pub struct UringStream {
+    stream: Rc<TcpStream>,
+    read_buf: Vec<u8>,
+    read_pos: usize,
+    read: Option<Op>,
+    write: Option<Op>,
+    written: Option<BufResult<usize, Vec<u8>>>,
+}
+
+impl UringStream {
+    /// Opens a TCP connection to a server.
+    ///
+    /// This must be called from within a `tokio_uring` runtime.
+    pub async fn connect(addr: SocketAddr) -> io::Result<UringStream> {
+        let stream = TcpStream::connect(addr).await?;
+        Ok(UringStream::from(stream))
+    }
+}
+
+impl From<TcpStream> for UringStream {
+    fn from(stream: TcpStream) -> UringStream {
+        UringStream {
+            stream: Rc::new(stream),
+            read_buf: Vec::with_capacity(READ_BUF_SIZE),
+            read_pos: 0,
+            read: None,
+            write: None,
+            written: None,
+        }
+    }
+}
+
+impl AsyncRead for UringStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = &mut *self;
+
+        if this.read_pos == this.read_buf.len() {
+            let read = this.read.get_or_insert_with(|| {
+                let stream = this.stream.clone();
+                let mut read_buf = mem::take(&mut this.read_buf);
+                read_buf.clear();
+                this.read_pos = 0;
+                Box::pin(async move { stream.read(read_buf).await })
+            });
+
+            let (result, read_buf) = ready!(read.as_mut().poll(cx));
+            this.read = None;
+            this.read_buf = read_buf;
+            this.read_pos = 0;
+            result?;
+        }
+
+        let available = &this.read_buf[this.read_pos..];
+        let len = available.len().min(buf.remaining());
+        buf.put_slice(&available[..len]);
+        this.read_pos += len;
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncWrite for UringStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+
+        let written = match this.written.take() {
+            Some(written) => written,
+            None => {
+                let write = this.write.get_or_insert_with(|| {
+                    let stream = this.stream.clone();
+                    let buf = buf.to_vec();
+                    Box::pin(async move { stream.write(buf).await })
+                });
+                let written = ready!(write.as_mut().poll(cx));
+                this.write = None;
+                written
+            }
+        };
+
+        Poll::Ready(complete_write(written, buf))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        // the result of a write completed here is reported by the next call to `poll_write`
+        if let Some(write) = &mut self.write {
+            let written = ready!(write.as_mut().poll(cx));
+            self.write = None;
+            self.written = Some(written);
+        }
+
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        ready!(self.as_mut().poll_flush(cx))?;
+        Poll::Ready(self.stream.shutdown(Shutdown::Write))
+    }
+}
+
+/// Reports the result of a write to a caller which may have retried with a different buffer than the one submitted.
+///
+/// A submitted write can't be cancelled, so the bytes it wrote are only reported if the caller's buffer starts with
+/// them. Otherwise, the stream now contains data the caller didn't ask to write, and an error is returned.
+fn complete_write((result, data): BufResult<usize, Vec<u8>>, buf: &[u8]) -> io::Result<usize> {
+    let len = result?;
+    if buf.get(..len) == data.get(..len) {
+        Ok(len)
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "write retried with different data while a previous write was in flight",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::complete_write;
+
+    #[test]
+    fn test_complete_write() {
+        assert_eq!(complete_write((Ok(3), b"abc".to_vec()), b"abc").unwrap(), 3);
+        assert_eq!(complete_write((Ok(2), b"abc".to_vec()), b"abd").unwrap(), 2);
+        assert!(complete_write((Ok(3), b"abc".to_vec()), b"xyz").is_err());
+        assert!(complete_write((Ok(3), b"abc".to_vec()), b"ab").is_err());
+    }
+}
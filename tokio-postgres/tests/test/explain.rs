@@ -0,0 +1,68 @@
+use crate::connect;
+use tokio_postgres::explain::ExplainOptions;
+
+#[tokio::test]
+async fn explain() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute("CREATE TEMPORARY TABLE foo (id INT PRIMARY KEY, name TEXT)")
+        .await
+        .unwrap();
+
+    let explain = client
+        .explain(
+            "SELECT * FROM foo WHERE name = $1",
+            &[&"joe"],
+            ExplainOptions::new(),
+        )
+        .await
+        .unwrap();
+    let plan = explain.plan();
+    assert_eq!(plan.node_type(), "Seq Scan");
+    assert_eq!(plan.relation_name(), Some("foo"));
+    assert!(plan.filter().is_some());
+    assert!(plan.total_cost().is_some());
+    assert_eq!(plan.actual_rows(), None);
+    assert_eq!(explain.execution_time(), None);
+
+    let explain = client
+        .explain("SELECT * FROM foo", &[], ExplainOptions::new().costs(false))
+        .await
+        .unwrap();
+    assert_eq!(explain.plan().total_cost(), None);
+}
+
+#[tokio::test]
+async fn explain_analyze() {
+    let mut client = connect("user=postgres").await;
+
+    client
+        .batch_execute("CREATE TEMPORARY TABLE foo (id INT PRIMARY KEY)")
+        .await
+        .unwrap();
+
+    let transaction = client.transaction().await.unwrap();
+    let explain = transaction
+        .explain(
+            "INSERT INTO foo SELECT generate_series(1, 10)",
+            &[],
+            ExplainOptions::new().analyze(true).verbose(true),
+        )
+        .await
+        .unwrap();
+    assert!(explain.execution_time().is_some());
+    let plan = explain.plan();
+    assert_eq!(plan.node_type(), "ModifyTable");
+    assert_eq!(plan.schema().map(|s| s.starts_with("pg_temp")), Some(true));
+    assert_eq!(plan.plans().len(), 1);
+    assert_eq!(plan.plans()[0].actual_rows(), Some(10.));
+    transaction.rollback().await.unwrap();
+
+    let count = client
+        .query_one("SELECT COUNT(*) FROM foo", &[])
+        .await
+        .unwrap()
+        .get::<_, i64>(0);
+    assert_eq!(count, 0);
+}
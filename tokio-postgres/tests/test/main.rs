@@ -4,25 +4,41 @@ use bytes::{Bytes, BytesMut};
 use futures_channel::mpsc;
 use futures_util::{FutureExt, SinkExt, StreamExt, TryStreamExt, join, stream, try_join};
 use pin_project_lite::pin_project;
+use std::error::Error as _;
 use std::fmt::Write;
 use std::future::{self, Future};
 use std::pin::{Pin, pin};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::task::{Context, Poll};
 use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, ReadBuf};
 use tokio::net::TcpStream;
 use tokio::time;
 use tokio_postgres::error::SqlState;
 use tokio_postgres::tls::{NoTls, NoTlsStream};
-use tokio_postgres::types::{Kind, Type};
+use tokio_postgres::types::{IsNull, Kind, PgRange, ToSql, Type, WrongType};
 use tokio_postgres::{
-    AsyncMessage, Client, Config, Connection, Error, IsolationLevel, SimpleQueryMessage,
+    AsyncMessage, Client, ClientMetrics, Config, Connection, Error, IsolationLevel,
+    NotificationRouter, SimpleQueryMessage, Subscription, TransactionStatus, TypeHandler,
 };
 
 mod binary_copy;
+#[cfg(feature = "explain")]
+mod explain;
 mod parse;
 #[cfg(feature = "runtime")]
 mod runtime;
+#[cfg(feature = "with-smol-2")]
+mod smol;
+#[cfg(feature = "test-util")]
+mod test_util;
+mod text_copy;
+#[cfg(feature = "tracing")]
+mod tracing;
 mod types;
+#[cfg(all(feature = "with-tokio-uring-0_4", target_os = "linux"))]
+mod uring;
 
 pin_project! {
     /// Polls `F` at most `polls_left` times returning `Some(F::Output)` if
@@ -80,6 +96,20 @@ async fn in_transaction(client: &Client) -> bool {
     current_transaction_id(client).await == current_transaction_id(client).await
 }
 
+#[tokio::test]
+async fn verify_full_unsupported() {
+    let err = connect_raw("user=postgres sslmode=verify-full")
+        .await
+        .err()
+        .unwrap();
+    assert!(
+        std::error::Error::source(&err)
+            .unwrap()
+            .to_string()
+            .contains("does not support certificate verification")
+    );
+}
+
 #[tokio::test]
 async fn plain_password_missing() {
     connect_raw("user=pass_user dbname=postgres")
@@ -261,6 +291,71 @@ async fn custom_domain() {
     assert_eq!(&Kind::Domain(Type::BYTEA), ty.kind());
 }
 
+struct CaseHandler {
+    upper: bool,
+}
+
+impl TypeHandler for CaseHandler {
+    type Value = String;
+
+    fn from_sql(
+        &self,
+        _: &Type,
+        raw: &[u8],
+    ) -> Result<String, Box<dyn std::error::Error + Sync + Send>> {
+        let s = std::str::from_utf8(raw)?;
+        Ok(if self.upper {
+            s.to_uppercase()
+        } else {
+            s.to_lowercase()
+        })
+    }
+
+    fn to_sql(
+        &self,
+        _: &Type,
+        value: &String,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>> {
+        out.extend_from_slice(value.as_bytes());
+        Ok(IsNull::No)
+    }
+}
+
+#[tokio::test]
+async fn type_handler() {
+    let client = connect("user=postgres").await;
+    client.register_type_handler("citext", CaseHandler { upper: false });
+
+    let row = client
+        .query_one(
+            "SELECT $1::citext, NULL::citext, 1::INT4",
+            &[&client.custom_param("Hello".to_string())],
+        )
+        .await
+        .unwrap();
+    assert_eq!(row.get_custom::<_, String>(0).as_deref(), Some("hello"));
+    assert_eq!(row.get_custom::<_, String>(1), None);
+    // `Row::get` always uses `FromSql`
+    assert_eq!(row.get::<_, String>(0), "Hello");
+    assert!(row.try_get_custom::<_, i32>(0).is_err());
+    assert!(row.try_get_custom::<_, i32>(2).is_err());
+
+    let oid = row.columns()[0].type_().oid();
+    client.register_type_handler(oid, CaseHandler { upper: true });
+    let row = client
+        .query_one("SELECT 'Hello'::citext", &[])
+        .await
+        .unwrap();
+    assert_eq!(row.get_custom::<_, String>(0).as_deref(), Some("HELLO"));
+
+    let err = client
+        .query_one("SELECT $1::citext", &[&client.custom_param(1i32)])
+        .await
+        .unwrap_err();
+    assert!(err.source().unwrap().is::<WrongType>());
+}
+
 #[tokio::test]
 async fn custom_array() {
     let client = connect("user=postgres").await;
@@ -329,6 +424,10 @@ async fn custom_range() {
     let ty = &select.params()[0];
     assert_eq!("floatrange", ty.name());
     assert_eq!(&Kind::Range(Type::FLOAT8), ty.kind());
+
+    let range = PgRange::from(0.5f64..1.5);
+    let row = client.query_one(&select, &[&range]).await.unwrap();
+    assert_eq!(row.get::<_, PgRange<f64>>(0), range);
 }
 
 #[tokio::test]
@@ -761,6 +860,78 @@ async fn copy_in_error() {
     assert_eq!(rows.len(), 0);
 }
 
+#[tokio::test]
+async fn copy_in_from() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute(
+            "CREATE TEMPORARY TABLE foo (
+                id INTEGER,
+                name TEXT
+            )",
+        )
+        .await
+        .unwrap();
+
+    let mut data = BytesMut::new();
+    for i in 0..10_000 {
+        writeln!(data, "{i}\tname{i}").unwrap();
+    }
+    let rows = client
+        .copy_in_from("COPY foo FROM STDIN", &data[..])
+        .await
+        .unwrap();
+    assert_eq!(rows, 10_000);
+
+    let rows = client
+        .query("SELECT id, name FROM foo ORDER BY id", &[])
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 10_000);
+    assert_eq!(rows[9_999].get::<_, &str>(1), "name9999");
+}
+
+#[tokio::test]
+async fn copy_in_from_error() {
+    struct FailingReader;
+
+    impl AsyncRead for FailingReader {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            _: &mut Context<'_>,
+            _: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Err(std::io::Error::other("boom")))
+        }
+    }
+
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute(
+            "CREATE TEMPORARY TABLE foo (
+                id INTEGER,
+                name TEXT
+            )",
+        )
+        .await
+        .unwrap();
+
+    let reader = (&b"1\tsteven\n"[..]).chain(FailingReader);
+    let err = client
+        .copy_in_from("COPY foo FROM STDIN", reader)
+        .await
+        .unwrap_err();
+    assert_eq!(err.source().unwrap().to_string(), "boom");
+
+    let rows = client
+        .query("SELECT id, name FROM foo ORDER BY id", &[])
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 0);
+}
+
 #[tokio::test]
 async fn copy_out() {
     let client = connect("user=postgres").await;
@@ -791,6 +962,220 @@ async fn copy_out() {
     assert_eq!(&data[..], b"1\tjim\n2\tjoe\n");
 }
 
+#[tokio::test]
+async fn copy_out_to() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute(
+            "CREATE TEMPORARY TABLE foo (
+                id INTEGER,
+                name TEXT
+            );
+
+            INSERT INTO foo SELECT i, 'name' || i FROM generate_series(0, 9999) i;",
+        )
+        .await
+        .unwrap();
+
+    let mut data = vec![];
+    let rows = client
+        .copy_out_to("COPY foo TO STDOUT", &mut data)
+        .await
+        .unwrap();
+    assert_eq!(rows, 10_000);
+    assert!(data.starts_with(b"0\tname0\n1\tname1\n"));
+    assert!(data.ends_with(b"9999\tname9999\n"));
+
+    let mut stream = pin!(client.copy_out("COPY foo TO STDOUT").await.unwrap());
+    while stream.try_next().await.unwrap().is_some() {
+        assert_eq!(stream.rows_affected(), None);
+    }
+    assert_eq!(stream.rows_affected(), Some(10_000));
+}
+
+#[tokio::test]
+async fn copy_out_to_error() {
+    struct FailingWriter;
+
+    impl AsyncWrite for FailingWriter {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _: &mut Context<'_>,
+            _: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            Poll::Ready(Err(std::io::Error::other("boom")))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    let client = connect("user=postgres").await;
+
+    let err = client
+        .copy_out_to(
+            "COPY (SELECT generate_series(1, 10000)) TO STDOUT",
+            FailingWriter,
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err.source().unwrap().to_string(), "boom");
+
+    let row = client.query_one("SELECT 1", &[]).await.unwrap();
+    assert_eq!(row.get::<_, i32>(0), 1);
+}
+
+#[tokio::test]
+async fn bulk_insert() {
+    let mut client = connect("user=postgres").await;
+
+    client
+        .batch_execute(
+            "CREATE TEMPORARY TABLE foo (
+                id INTEGER PRIMARY KEY,
+                name TEXT,
+                score FLOAT8
+            )",
+        )
+        .await
+        .unwrap();
+
+    // 3 columns allow 21845 rows per statement, so this takes two statements
+    let rows = (0..30_000i32)
+        .map(|i| (i, format!("name{i}"), f64::from(i) / 2.))
+        .collect::<Vec<_>>();
+    let inserted = client
+        .bulk_insert("foo", &["id", "name", "score"])
+        .execute(
+            rows.iter()
+                .map(|(id, name, score)| [id as &(dyn ToSql + Sync), name, score]),
+        )
+        .await
+        .unwrap();
+    assert_eq!(inserted, 30_000);
+
+    let transaction = client.transaction().await.unwrap();
+    let inserted = transaction
+        .bulk_insert("foo", &["id", "name"])
+        .on_conflict("(id) DO UPDATE SET name = EXCLUDED.name")
+        .max_rows_per_statement(2)
+        .execute([
+            [&0i32 as &(dyn ToSql + Sync), &"zero"],
+            [&1i32, &"one"],
+            [&-1i32, &"minus one"],
+        ])
+        .await
+        .unwrap();
+    assert_eq!(inserted, 3);
+    transaction.commit().await.unwrap();
+
+    let rows = client
+        .query(
+            "SELECT id, name, score FROM foo WHERE id < 2 ORDER BY id",
+            &[],
+        )
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[0].get::<_, &str>(1), "minus one");
+    assert_eq!(rows[0].get::<_, Option<f64>>(2), None);
+    assert_eq!(rows[1].get::<_, &str>(1), "zero");
+    assert_eq!(rows[2].get::<_, &str>(1), "one");
+    assert_eq!(rows[2].get::<_, f64>(2), 0.5);
+
+    let inserted = client
+        .bulk_insert("foo", &["id"])
+        .execute(Vec::<[i32; 1]>::new())
+        .await
+        .unwrap();
+    assert_eq!(inserted, 0);
+
+    client
+        .bulk_insert("foo", &["id", "name"])
+        .execute([vec![&50_000i32 as &(dyn ToSql + Sync)]])
+        .await
+        .unwrap_err();
+}
+
+#[tokio::test]
+async fn execute_many() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute("CREATE TEMPORARY TABLE foo (id INT PRIMARY KEY, name TEXT)")
+        .await
+        .unwrap();
+
+    let rows = client
+        .execute_many(
+            "INSERT INTO foo (id, name) VALUES ($1, $2)",
+            [
+                [&1i32 as &(dyn ToSql + Sync), &"steven"],
+                [&2i32, &"timothy"],
+                [&3i32, &"joe"],
+            ],
+        )
+        .await
+        .unwrap();
+    assert_eq!(rows, [1, 1, 1]);
+
+    let statement = client
+        .prepare("UPDATE foo SET name = $2 WHERE id <= $1")
+        .await
+        .unwrap();
+    let rows = client
+        .execute_many(
+            &statement,
+            [
+                [&0i32 as &(dyn ToSql + Sync), &"none"],
+                [&2i32, &"first"],
+                [&1i32, &"second"],
+            ],
+        )
+        .await
+        .unwrap();
+    assert_eq!(rows, [0, 2, 1]);
+
+    let rows = client
+        .execute_many(&statement, Vec::<[&(dyn ToSql + Sync); 2]>::new())
+        .await
+        .unwrap();
+    assert!(rows.is_empty());
+
+    // the sets run in a single implicit transaction, so the failure undoes the first insert
+    let err = client
+        .execute_many(
+            "INSERT INTO foo (id, name) VALUES ($1, $2)",
+            [
+                [&4i32 as &(dyn ToSql + Sync), &"four"],
+                [&1i32, &"duplicate"],
+            ],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Some(&SqlState::UNIQUE_VIOLATION));
+
+    client
+        .execute_many(&statement, [vec![&1i32 as &(dyn ToSql + Sync)]])
+        .await
+        .unwrap_err();
+
+    let rows = client
+        .query("SELECT id, name FROM foo ORDER BY id", &[])
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[0].get::<_, &str>(1), "second");
+    assert_eq!(rows[1].get::<_, &str>(1), "first");
+    assert_eq!(rows[2].get::<_, &str>(1), "joe");
+}
+
 #[tokio::test]
 async fn notices() {
     let long_name = "x".repeat(65);
@@ -1360,3 +1745,690 @@ async fn query_scalar() {
         .unwrap();
     assert_eq!(age, Some(20));
 }
+
+#[tokio::test]
+async fn reset() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute(
+            "SET application_name = 'reset';
+             CREATE TEMPORARY TABLE reset_temp (id INT)",
+        )
+        .await
+        .unwrap();
+    // populates the type info cache
+    client.query("SELECT 'a=>1'::HSTORE", &[]).await.unwrap();
+
+    client.reset().await.unwrap();
+
+    let rows = client.query("SHOW application_name", &[]).await.unwrap();
+    assert_eq!(rows[0].get::<_, &str>(0), "");
+
+    let err = client
+        .query("SELECT * FROM reset_temp", &[])
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Some(&SqlState::UNDEFINED_TABLE));
+
+    client.query("SELECT 'a=>1'::HSTORE", &[]).await.unwrap();
+}
+
+#[tokio::test]
+async fn reset_discards_notifications() {
+    let (client, connection) = connect_raw("user=postgres").await.unwrap();
+    let (driver, mut notifications) = connection.split();
+    tokio::spawn(driver);
+
+    client
+        .batch_execute("LISTEN reset_notifications; NOTIFY reset_notifications, 'before'")
+        .await
+        .unwrap();
+    client.reset().await.unwrap();
+    client
+        .batch_execute("LISTEN reset_notifications; NOTIFY reset_notifications, 'after'")
+        .await
+        .unwrap();
+
+    let notification = notifications.next().await.unwrap();
+    assert_eq!(notification.payload(), "after");
+}
+
+#[tokio::test]
+async fn reset_in_transaction() {
+    let mut client = connect("user=postgres").await;
+
+    let transaction = client.transaction().await.unwrap();
+    let err = transaction.client().reset().await.unwrap_err();
+    assert_eq!(err.code(), Some(&SqlState::ACTIVE_SQL_TRANSACTION));
+}
+
+#[tokio::test]
+async fn close() {
+    let (client, connection) = connect_raw("user=postgres").await.unwrap();
+    let connection = tokio::spawn(connection);
+
+    // rows still in flight when the client is closed are delivered before the connection terminates
+    let rows = client
+        .query_raw("SELECT generate_series(1, 100)", std::iter::empty::<i32>())
+        .await
+        .unwrap();
+    let (rows, close) = join!(rows.try_collect::<Vec<_>>(), client.close());
+    assert_eq!(rows.unwrap().len(), 100);
+    close.unwrap();
+
+    connection.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn close_after_connection_closed() {
+    let (client, connection) = connect_raw("user=postgres").await.unwrap();
+    drop(connection);
+
+    let err = client.close().await.unwrap_err();
+    assert!(err.is_closed());
+}
+
+#[tokio::test]
+async fn server_version() {
+    let client = connect("user=postgres").await;
+
+    let version_num = client
+        .query_one("SHOW server_version_num", &[])
+        .await
+        .unwrap()
+        .get::<_, String>(0)
+        .parse::<u32>()
+        .unwrap();
+    // before 10, the version number encodes major.minor.patch as MMmmpp rather than MMmmmm
+    let expected = if version_num >= 100000 {
+        (version_num / 10000, version_num % 10000)
+    } else {
+        (version_num / 10000, version_num / 100 % 100)
+    };
+    assert_eq!(client.server_version(), Some(expected));
+}
+
+#[tokio::test]
+async fn backend_pid() {
+    let client = connect("user=postgres").await;
+
+    let pid = client
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await
+        .unwrap()
+        .get::<_, i32>(0);
+    assert_eq!(client.backend_pid(), pid);
+}
+
+#[tokio::test]
+async fn tls_info_unencrypted() {
+    let (client, connection) = connect_raw("user=postgres").await.unwrap();
+    assert!(client.tls_info().is_none());
+    assert!(connection.tls_info().is_none());
+}
+
+#[tokio::test]
+async fn parameter_status() {
+    let (client, mut connection) = connect_raw("user=postgres").await.unwrap();
+
+    let (tx, rx) = mpsc::unbounded();
+    let stream =
+        stream::poll_fn(move |cx| connection.poll_message(cx)).map_err(|e| panic!("{}", e));
+    let connection = stream.forward(tx).map(|r| r.unwrap());
+    tokio::spawn(connection);
+
+    assert_eq!(client.parameter("application_name").as_deref(), Some(""));
+    assert_eq!(client.parameter("not_a_parameter"), None);
+
+    client
+        .batch_execute("SET application_name = 'parameter_status'")
+        .await
+        .unwrap();
+    assert_eq!(
+        client.parameter("application_name").as_deref(),
+        Some("parameter_status")
+    );
+
+    drop(client);
+
+    let changes = rx
+        .filter_map(|m| match m {
+            AsyncMessage::ParameterStatus(p) => future::ready(Some(p)),
+            _ => future::ready(None),
+        })
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].name(), "application_name");
+    assert_eq!(changes[0].value(), "parameter_status");
+}
+
+#[tokio::test]
+async fn split_notifications() {
+    let (client, connection) = connect_raw("user=postgres").await.unwrap();
+    let (driver, notifications) = connection.split();
+    let driver = tokio::spawn(driver);
+
+    client
+        .batch_execute(
+            "LISTEN test_split_notifications;
+             NOTIFY test_split_notifications, 'hello';
+             NOTIFY test_split_notifications, 'world';",
+        )
+        .await
+        .unwrap();
+
+    drop(client);
+    driver.await.unwrap().unwrap();
+
+    let notifications = notifications.collect::<Vec<_>>().await;
+    assert_eq!(notifications.len(), 2);
+    assert_eq!(notifications[0].channel(), "test_split_notifications");
+    assert_eq!(notifications[0].payload(), "hello");
+    assert_eq!(notifications[1].payload(), "world");
+}
+
+#[tokio::test]
+async fn notice_stream() {
+    let (client, mut connection) = connect_raw("user=postgres").await.unwrap();
+    let notices = connection.notices(2);
+
+    let (tx, rx) = mpsc::unbounded();
+    let stream =
+        stream::poll_fn(move |cx| connection.poll_message(cx)).map_err(|e| panic!("{}", e));
+    tokio::spawn(stream.forward(tx).map(|r| r.unwrap()));
+
+    client
+        .batch_execute(
+            "DROP TABLE IF EXISTS noexist1;
+             DROP TABLE IF EXISTS noexist2;
+             DROP TABLE IF EXISTS noexist3;",
+        )
+        .await
+        .unwrap();
+    drop(client);
+
+    // the third notice doesn't fit in the buffer
+    let notices = notices.collect::<Vec<_>>().await;
+    assert_eq!(notices.len(), 2);
+    assert_eq!(
+        notices[0].message(),
+        "table \"noexist1\" does not exist, skipping"
+    );
+    assert_eq!(
+        notices[1].message(),
+        "table \"noexist2\" does not exist, skipping"
+    );
+
+    let messages = rx.collect::<Vec<_>>().await;
+    assert!(
+        !messages
+            .iter()
+            .any(|m| matches!(m, AsyncMessage::Notice(_)))
+    );
+}
+
+#[tokio::test]
+async fn config_notice_handler() {
+    let (tx, rx) = mpsc::unbounded();
+    let socket = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+    let mut config = "user=postgres".parse::<Config>().unwrap();
+    config.notice_handler(move |notice| tx.unbounded_send(notice).unwrap());
+    let (client, connection) = config.connect_raw(socket, NoTls).await.unwrap();
+    tokio::spawn(connection);
+    // the configuration shares the handler with the connection
+    drop(config);
+
+    client
+        .batch_execute("DROP TABLE IF EXISTS noexist")
+        .await
+        .unwrap();
+    drop(client);
+
+    let notices = rx.collect::<Vec<_>>().await;
+    assert_eq!(notices.len(), 1);
+    assert_eq!(
+        notices[0].message(),
+        "table \"noexist\" does not exist, skipping"
+    );
+}
+
+#[tokio::test]
+async fn notification_router() {
+    let (client, connection) = connect_raw("user=postgres").await.unwrap();
+    let (driver, notifications) = connection.split();
+    tokio::spawn(driver);
+
+    let router = NotificationRouter::new(4);
+    let jobs = router.subscribe("router_jobs");
+    let events = router.subscribe("router_events");
+    let events2 = router.subscribe("router_events");
+    drop(router.subscribe("router_jobs"));
+    let dispatch = tokio::spawn({
+        let router = router.clone();
+        async move { router.run(notifications).await }
+    });
+    drop(router);
+
+    client
+        .batch_execute(
+            "LISTEN router_jobs;
+             LISTEN router_events;
+             LISTEN router_ignored;
+             NOTIFY router_jobs, 'job';
+             NOTIFY router_ignored, 'ignored';
+             NOTIFY router_events, 'event';",
+        )
+        .await
+        .unwrap();
+    drop(client);
+    dispatch.await.unwrap();
+
+    let payloads = |s: Subscription| s.map(|n| n.payload().to_string()).collect::<Vec<_>>();
+    assert_eq!(payloads(jobs).await, ["job"]);
+    assert_eq!(payloads(events).await, ["event"]);
+    assert_eq!(payloads(events2).await, ["event"]);
+}
+
+#[tokio::test]
+async fn notify() {
+    let (client, connection) = connect_raw("user=postgres").await.unwrap();
+    let (driver, notifications) = connection.split();
+    tokio::spawn(driver);
+
+    client
+        .batch_execute("LISTEN \"Notify Test\"")
+        .await
+        .unwrap();
+    client
+        .notify("Notify Test", "it's a \"payload\"")
+        .await
+        .unwrap();
+
+    let err = client.notify("", "payload").await.unwrap_err();
+    assert_eq!(err.to_string(), "invalid notification");
+    let err = client.notify(&"a".repeat(64), "payload").await.unwrap_err();
+    let source = std::error::Error::source(&err).unwrap().to_string();
+    assert!(source.contains(&format!("channel name \"{}\"", "a".repeat(64))));
+    let err = client
+        .notify("Notify Test", &"a".repeat(8000))
+        .await
+        .unwrap_err();
+    let source = std::error::Error::source(&err).unwrap().to_string();
+    assert!(source.contains("payload of 8000 bytes for channel \"Notify Test\""));
+    client
+        .notify("Notify Test", &"a".repeat(7999))
+        .await
+        .unwrap();
+
+    drop(client);
+    let notifications = notifications.collect::<Vec<_>>().await;
+    assert_eq!(notifications.len(), 2);
+    assert_eq!(notifications[0].channel(), "Notify Test");
+    assert_eq!(notifications[0].payload(), "it's a \"payload\"");
+    assert_eq!(notifications[1].payload().len(), 7999);
+}
+
+#[tokio::test]
+async fn transaction_status() {
+    let client = connect("user=postgres").await;
+    assert_eq!(client.transaction_status(), TransactionStatus::Idle);
+
+    client.batch_execute("BEGIN").await.unwrap();
+    assert_eq!(
+        client.transaction_status(),
+        TransactionStatus::InTransaction
+    );
+
+    client.batch_execute("SELECT 1/0").await.unwrap_err();
+    client.check_connection().await.unwrap();
+    assert_eq!(client.transaction_status(), TransactionStatus::Failed);
+
+    client.batch_execute("ROLLBACK").await.unwrap();
+    assert_eq!(client.transaction_status(), TransactionStatus::Idle);
+}
+
+#[tokio::test]
+async fn error_classification() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute(
+            "CREATE TEMPORARY TABLE parents (id INT PRIMARY KEY);
+            CREATE TEMPORARY TABLE children (parent_id INT REFERENCES parents (id));
+            INSERT INTO parents (id) VALUES (1);",
+        )
+        .await
+        .unwrap();
+
+    let err = client
+        .execute("INSERT INTO parents (id) VALUES (1)", &[])
+        .await
+        .unwrap_err();
+    assert!(err.is_unique_violation());
+    assert!(!err.is_retryable());
+
+    let err = client
+        .execute("INSERT INTO children (parent_id) VALUES (2)", &[])
+        .await
+        .unwrap_err();
+    assert!(err.is_foreign_key_violation());
+    assert!(!err.is_unique_violation());
+}
+
+#[tokio::test]
+async fn query_in_errors() {
+    let socket = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+    let mut config = "user=postgres".parse::<Config>().unwrap();
+    config.query_in_errors(true);
+    let (client, connection) = config.connect_raw(socket, NoTls).await.unwrap();
+    tokio::spawn(connection.map(|r| r.unwrap()));
+
+    let err = client.prepare("SELEC 1").await.unwrap_err();
+    assert_eq!(err.query(), Some("SELEC 1"));
+
+    let err = client.query_one("SELECT 1 / 0", &[]).await.unwrap_err();
+    assert_eq!(err.query(), Some("SELECT 1 / 0"));
+
+    let err = client
+        .execute("SELECT $1::INT / 0", &[&1i32])
+        .await
+        .unwrap_err();
+    assert_eq!(err.query(), Some("SELECT $1::INT / 0"));
+
+    let err = client
+        .query_typed("SELECT $1 / 0", &[(&1i32, Type::INT4)])
+        .await
+        .unwrap_err();
+    assert_eq!(err.query(), Some("SELECT $1 / 0"));
+
+    let err = client.batch_execute("SELECT 1 / 0").await.unwrap_err();
+    assert_eq!(err.query(), Some("SELECT 1 / 0"));
+
+    let err = client.simple_query("SELECT 1 / 0").await.unwrap_err();
+    assert_eq!(err.query(), Some("SELECT 1 / 0"));
+
+    let client = connect("user=postgres").await;
+    let err = client.query_one("SELECT 1 / 0", &[]).await.unwrap_err();
+    assert_eq!(err.query(), None);
+}
+
+#[tokio::test]
+async fn params_in_errors() {
+    let socket = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+    let mut config = "user=postgres".parse::<Config>().unwrap();
+    config
+        .params_in_errors(true)
+        .param_redactor(|idx, _, _| (idx == 1).then(|| "<redacted>".to_string()));
+    let (client, connection) = config.connect_raw(socket, NoTls).await.unwrap();
+    tokio::spawn(connection.map(|r| r.unwrap()));
+
+    client
+        .batch_execute("CREATE TEMPORARY TABLE users (name TEXT PRIMARY KEY, password TEXT)")
+        .await
+        .unwrap();
+    let insert = client
+        .prepare("INSERT INTO users (name, password) VALUES ($1, $2)")
+        .await
+        .unwrap();
+    client
+        .execute(&insert, &[&"alice", &"hunter2"])
+        .await
+        .unwrap();
+
+    let err = client
+        .execute(&insert, &[&"alice", &"hunter2"])
+        .await
+        .unwrap_err();
+    assert!(err.is_unique_violation());
+    assert_eq!(err.query(), None);
+    assert_eq!(
+        err.params(),
+        Some(&["'alice'".to_string(), "<redacted>".to_string()][..])
+    );
+
+    let err = client
+        .query_one("SELECT $1::INT / $2::INT", &[&1i32, &0i32])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.params(),
+        Some(&["1".to_string(), "<redacted>".to_string()][..])
+    );
+
+    let err = client
+        .query_typed("SELECT $1 / 0", &[(&2i32, Type::INT4)])
+        .await
+        .unwrap_err();
+    assert_eq!(err.params(), Some(&["2".to_string()][..]));
+
+    let client = connect("user=postgres").await;
+    let err = client
+        .query_one("SELECT $1::INT / 0", &[&1i32])
+        .await
+        .unwrap_err();
+    assert_eq!(err.params(), None);
+}
+
+#[tokio::test]
+async fn error_location() {
+    let client = connect("user=postgres").await;
+
+    let query = "SELECT 1\nFROM nonexistent_table";
+    let err = client.prepare(query).await.unwrap_err();
+    let location = err.as_db_error().unwrap().location(query).unwrap();
+    assert_eq!(location.line(), 2);
+    assert_eq!(location.column(), 6);
+    assert_eq!(location.excerpt(), "FROM nonexistent_table\n     ^");
+}
+
+#[tokio::test]
+async fn metrics() {
+    #[derive(Default)]
+    struct Recorder {
+        events: std::sync::Mutex<Vec<String>>,
+        bytes_sent: AtomicUsize,
+        bytes_received: AtomicUsize,
+    }
+
+    impl ClientMetrics for Recorder {
+        fn query_start(&self, query: &str) {
+            self.events.lock().unwrap().push(format!("start {query}"));
+        }
+
+        fn query_end(&self, query: &str, _: Duration) {
+            self.events.lock().unwrap().push(format!("end {query}"));
+        }
+
+        fn query_error(&self, query: &str, error: &Error, _: Duration) {
+            let code = error.code().unwrap().code();
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("error {query} {code}"));
+        }
+
+        fn query_cancelled(&self, query: &str, _: Duration) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("cancelled {query}"));
+        }
+
+        fn bytes_sent(&self, bytes: usize) {
+            self.bytes_sent.fetch_add(bytes, Ordering::Relaxed);
+        }
+
+        fn bytes_received(&self, bytes: usize) {
+            self.bytes_received.fetch_add(bytes, Ordering::Relaxed);
+        }
+    }
+
+    let recorder = Arc::new(Recorder::default());
+    let socket = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+    let mut config = "user=postgres".parse::<Config>().unwrap();
+    config.metrics(recorder.clone());
+    let (client, connection) = config.connect_raw(socket, NoTls).await.unwrap();
+    tokio::spawn(connection.map(|r| r.unwrap()));
+    assert!(client.metrics().is_some());
+
+    let stmt = client
+        .prepare("SELECT generate_series(1, 3)")
+        .await
+        .unwrap();
+    client.query(&stmt, &[]).await.unwrap();
+    client.execute("SELECT 1 / 0", &[]).await.unwrap_err();
+    let stream = client
+        .query_raw(&stmt, std::iter::empty::<i32>())
+        .await
+        .unwrap();
+    drop(stream);
+
+    assert_eq!(
+        *recorder.events.lock().unwrap(),
+        [
+            "start SELECT generate_series(1, 3)",
+            "end SELECT generate_series(1, 3)",
+            "start SELECT 1 / 0",
+            "error SELECT 1 / 0 22012",
+            "start SELECT generate_series(1, 3)",
+            "cancelled SELECT generate_series(1, 3)",
+        ]
+    );
+    assert!(recorder.bytes_sent.load(Ordering::Relaxed) > 0);
+    assert!(recorder.bytes_received.load(Ordering::Relaxed) > 0);
+}
+
+#[tokio::test]
+async fn slow_query_handler() {
+    let (tx, rx) = mpsc::unbounded();
+    let socket = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+    let mut config = "user=postgres".parse::<Config>().unwrap();
+    config.slow_query_handler(Duration::from_millis(100), move |query, duration, rows| {
+        tx.unbounded_send((query.to_string(), duration, rows))
+            .unwrap()
+    });
+    let (client, connection) = config.connect_raw(socket, NoTls).await.unwrap();
+    tokio::spawn(connection.map(|r| r.unwrap()));
+    drop(config);
+
+    client.query("SELECT 1", &[]).await.unwrap();
+    client
+        .query("SELECT pg_sleep(0.2), generate_series(1, 2)", &[])
+        .await
+        .unwrap();
+    client
+        .execute("SELECT pg_sleep(0.2) FROM generate_series(1, 3)", &[])
+        .await
+        .unwrap();
+    client
+        .batch_execute("SELECT pg_sleep(0.2); SELECT 1 / 0")
+        .await
+        .unwrap_err();
+    drop(client);
+
+    let reports = rx.collect::<Vec<_>>().await;
+    assert_eq!(reports.len(), 3);
+    assert_eq!(reports[0].0, "SELECT pg_sleep(0.2), generate_series(1, 2)");
+    assert!(reports[0].1 >= Duration::from_millis(100));
+    assert_eq!(reports[0].2, Some(2));
+    assert_eq!(reports[1].2, Some(3));
+    assert_eq!(reports[2].0, "SELECT pg_sleep(0.2); SELECT 1 / 0");
+    assert_eq!(reports[2].2, None);
+}
+
+#[tokio::test]
+async fn sql_commenter() {
+    let socket = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+    let mut config = "user=postgres".parse::<Config>().unwrap();
+    config.sql_commenter(|| {
+        vec![
+            (
+                "traceparent".to_string(),
+                "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01".to_string(),
+            ),
+            ("application".to_string(), "test suite".to_string()),
+        ]
+    });
+    let (client, connection) = config.connect_raw(socket, NoTls).await.unwrap();
+    tokio::spawn(connection.map(|r| r.unwrap()));
+
+    let comment = "/*application='test%20suite',traceparent='00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01'*/";
+
+    let row = client
+        .query_one("SELECT current_query()", &[])
+        .await
+        .unwrap();
+    assert_eq!(
+        row.get::<_, &str>(0),
+        format!("SELECT current_query() {comment}")
+    );
+
+    let row = client
+        .query_typed_one("SELECT current_query();", &[])
+        .await
+        .unwrap();
+    assert_eq!(
+        row.get::<_, &str>(0),
+        format!("SELECT current_query() {comment};")
+    );
+
+    let messages = client.simple_query("SELECT current_query()").await.unwrap();
+    match &messages[1] {
+        SimpleQueryMessage::Row(row) => {
+            assert_eq!(
+                row.get(0),
+                Some(&*format!("SELECT current_query() {comment}"))
+            )
+        }
+        _ => panic!("unexpected message"),
+    }
+
+    let row = client
+        .query_one("SELECT current_query() /* mine */", &[])
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, &str>(0), "SELECT current_query() /* mine */");
+
+    // explicitly prepared statements outlive the context the tags describe
+    let statement = client.prepare("SELECT current_query()").await.unwrap();
+    let row = client.query_one(&statement, &[]).await.unwrap();
+    assert_eq!(row.get::<_, &str>(0), "SELECT current_query()");
+}
+
+#[tokio::test]
+async fn statement_debug_sql() {
+    let client = connect("user=postgres").await;
+
+    let statement = client
+        .prepare(
+            "SELECT $1::TEXT, $2::INT8, $3::FLOAT8[], $4::BYTEA, $5::BOOL, $6::INT4, \
+             '$1' AS \"$2\" -- $3",
+        )
+        .await
+        .unwrap();
+    let params: &[&(dyn ToSql + Sync)] = &[
+        &"it's a \\ test",
+        &-5i64,
+        &vec![Some(1.5f64), None, Some(f64::INFINITY)],
+        &&b"\x00\xff"[..],
+        &true,
+        &None::<i32>,
+    ];
+    let sql = statement.debug_sql(params).unwrap();
+    assert!(sql.ends_with("'$1' AS \"$2\" -- $3"));
+
+    let expected = client.query_one(&statement, params).await.unwrap();
+    let messages = client.simple_query(&sql).await.unwrap();
+    let row = match &messages[1] {
+        SimpleQueryMessage::Row(row) => row,
+        _ => panic!("unexpected message"),
+    };
+    assert_eq!(row.get(0), Some(expected.get::<_, &str>(0)));
+    assert_eq!(row.get(1), Some("-5"));
+    assert_eq!(row.get(2), Some("{1.5,NULL,Infinity}"));
+    assert_eq!(row.get(3), Some("\\x00ff"));
+    assert_eq!(row.get(4), Some("t"));
+    assert_eq!(row.get(5), None);
+}
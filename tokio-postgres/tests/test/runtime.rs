@@ -1,8 +1,18 @@
 use futures_util::{FutureExt, join};
+use std::future::Future;
+use std::io;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
 use tokio::time;
+use tokio_postgres::config::{DnsResolver, Proxy, SocketRef};
 use tokio_postgres::error::SqlState;
-use tokio_postgres::{Client, NoTls};
+use tokio_postgres::listener::{Listener, ListenerEvent};
+use tokio_postgres::{Client, Config, NoTls};
 
 async fn connect(s: &str) -> Client {
     let (client, connection) = tokio_postgres::connect(s, NoTls).await.unwrap();
@@ -49,6 +59,242 @@ async fn wrong_port_count() {
         .unwrap();
 }
 
+#[tokio::test]
+async fn dns_resolver() {
+    struct StaticResolver(Arc<AtomicUsize>);
+
+    impl DnsResolver for StaticResolver {
+        fn resolve<'a>(
+            &'a self,
+            host: &'a str,
+            port: u16,
+        ) -> Pin<Box<dyn Future<Output = io::Result<Vec<SocketAddr>>> + Send + 'a>> {
+            Box::pin(async move {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                match host {
+                    "postgres.test" => Ok(vec![(Ipv4Addr::LOCALHOST, port).into()]),
+                    _ => Err(io::Error::new(io::ErrorKind::NotFound, "unknown host")),
+                }
+            })
+        }
+    }
+
+    let lookups = Arc::new(AtomicUsize::new(0));
+    let mut config = "host=foobar.invalid,postgres.test port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap();
+    config.dns_resolver(StaticResolver(lookups.clone()));
+
+    let (client, connection) = config.connect(NoTls).await.unwrap();
+    tokio::spawn(connection.map(|r| r.unwrap()));
+    client.batch_execute("SELECT 1").await.unwrap();
+    assert_eq!(lookups.load(Ordering::SeqCst), 2);
+}
+
+/// Runs a minimal proxy which accepts a single connection, checks the requested target, and forwards it to the
+/// test database.
+async fn proxy_server<F, Fut>(handshake: F) -> u16
+where
+    F: FnOnce(TcpStream) -> Fut + Send + 'static,
+    Fut: Future<Output = TcpStream> + Send,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut stream = handshake(stream).await;
+        let mut upstream = TcpStream::connect("localhost:5433").await.unwrap();
+        let _ = tokio::io::copy_bidirectional(&mut stream, &mut upstream).await;
+    });
+    port
+}
+
+#[tokio::test]
+async fn socks5_proxy() {
+    let port = proxy_server(|mut stream| async move {
+        let mut buf = [0; 4];
+        stream.read_exact(&mut buf).await.unwrap();
+        assert_eq!(buf, [0x05, 0x02, 0x00, 0x02]);
+        stream.write_all(&[0x05, 0x02]).await.unwrap();
+
+        let mut buf = [0; 11];
+        stream.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"\x01\x04user\x04pass");
+        stream.write_all(&[0x01, 0x00]).await.unwrap();
+
+        let mut buf = [0; 5];
+        stream.read_exact(&mut buf).await.unwrap();
+        assert_eq!(buf, [0x05, 0x01, 0x00, 0x03, 14]);
+        let mut buf = [0; 16];
+        stream.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"db.example.com\x15\x39");
+        stream
+            .write_all(&[0x05, 0x00, 0x00, 0x01, 0, 0, 0, 0, 0, 0])
+            .await
+            .unwrap();
+        stream
+    })
+    .await;
+
+    let mut config = "host=db.example.com port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap();
+    config.proxy(Proxy::socks5("127.0.0.1", port).credentials("user", "pass"));
+
+    let (client, connection) = config.connect(NoTls).await.unwrap();
+    tokio::spawn(connection.map(|r| r.unwrap()));
+    client.batch_execute("SELECT 1").await.unwrap();
+}
+
+#[tokio::test]
+async fn http_connect_proxy() {
+    let port = proxy_server(|mut stream| async move {
+        let mut request = vec![];
+        while !request.ends_with(b"\r\n\r\n") {
+            request.push(stream.read_u8().await.unwrap());
+        }
+        assert_eq!(
+            String::from_utf8(request).unwrap(),
+            "CONNECT db.example.com:5433 HTTP/1.1\r\n\
+             Host: db.example.com:5433\r\n\
+             Proxy-Authorization: Basic dXNlcjpwYXNz\r\n\r\n",
+        );
+        stream
+            .write_all(b"HTTP/1.1 200 Connection established\r\n\r\n")
+            .await
+            .unwrap();
+        stream
+    })
+    .await;
+
+    let mut config = "host=db.example.com port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap();
+    config.proxy(Proxy::http_connect("127.0.0.1", port).credentials("user", "pass"));
+
+    let (client, connection) = config.connect(NoTls).await.unwrap();
+    tokio::spawn(connection.map(|r| r.unwrap()));
+    client.batch_execute("SELECT 1").await.unwrap();
+}
+
+#[tokio::test]
+async fn http_connect_proxy_error() {
+    let port = proxy_server(|mut stream| async move {
+        stream
+            .write_all(b"HTTP/1.1 403 Forbidden\r\n\r\n")
+            .await
+            .unwrap();
+        stream
+    })
+    .await;
+
+    let mut config = "host=db.example.com port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap();
+    config.proxy(Proxy::http_connect("127.0.0.1", port));
+
+    let err = config.connect(NoTls).await.err().unwrap();
+    let cause = std::error::Error::source(&err).unwrap().to_string();
+    assert_eq!(cause, "HTTP proxy error: 403 Forbidden");
+}
+
+#[tokio::test]
+async fn socket_callback() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let mut config = "host=localhost port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap();
+    config.socket_callback({
+        let calls = calls.clone();
+        move |socket| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            match socket {
+                SocketRef::Tcp(stream) => stream.set_ttl(32),
+                _ => panic!("expected a TCP socket"),
+            }
+        }
+    });
+
+    let (client, connection) = config.connect(NoTls).await.unwrap();
+    tokio::spawn(connection.map(|r| r.unwrap()));
+    client.batch_execute("SELECT 1").await.unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    config.socket_callback(|_| Err(io::Error::other("rejected")));
+    let err = config.connect(NoTls).await.err().unwrap();
+    let cause = std::error::Error::source(&err).unwrap().to_string();
+    assert_eq!(cause, "rejected");
+}
+
+#[tokio::test]
+async fn connect_retries() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let accepts = Arc::new(AtomicUsize::new(0));
+    tokio::spawn({
+        let accepts = accepts.clone();
+        async move {
+            loop {
+                // close the connection immediately to fail the startup handshake
+                drop(listener.accept().await.unwrap());
+                accepts.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+    });
+
+    let mut config = Config::new();
+    config
+        .host("127.0.0.1")
+        .port(port)
+        .user("postgres")
+        .connect_retries(2)
+        .connect_retry_backoff(Duration::from_millis(10));
+    config.connect(NoTls).await.err().unwrap();
+    assert_eq!(accepts.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn connect_retries_db_error() {
+    let mut config = "host=localhost port=5433 user=postgres dbname=does_not_exist"
+        .parse::<Config>()
+        .unwrap();
+    config
+        .connect_retries(3)
+        .connect_retry_backoff(Duration::from_secs(10));
+
+    let err = time::timeout(Duration::from_secs(5), config.connect(NoTls))
+        .await
+        .unwrap()
+        .err()
+        .unwrap();
+    assert_eq!(err.code(), Some(&SqlState::INVALID_CATALOG_NAME));
+}
+
+#[tokio::test]
+async fn total_connect_timeout() {
+    // a server which accepts connections but never responds
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        let mut streams = vec![];
+        loop {
+            streams.push(listener.accept().await.unwrap());
+        }
+    });
+
+    let mut config = Config::new();
+    config
+        .host("127.0.0.1")
+        .host("localhost")
+        .port(port)
+        .port(5433)
+        .user("postgres")
+        .total_connect_timeout(Duration::from_millis(100));
+    let err = config.connect(NoTls).await.err().unwrap();
+    let cause = std::error::Error::source(&err).unwrap().to_string();
+    assert_eq!(cause, "total connection timeout elapsed");
+}
+
 #[tokio::test]
 async fn target_session_attrs_ok() {
     smoke_test("host=localhost port=5433 user=postgres target_session_attrs=read-write").await;
@@ -133,3 +379,118 @@ async fn cancel_query() {
         t => panic!("unexpected return: {:?}", t),
     }
 }
+
+#[tokio::test]
+async fn listener_reconnects() {
+    let config = "host=localhost port=5433 user=postgres application_name=listener_reconnects"
+        .parse()
+        .unwrap();
+    let mut listener = Listener::new(config, NoTls);
+    listener.reconnect_backoff(Duration::from_millis(10), Duration::from_millis(100));
+    listener.listen("listener_reconnects").await.unwrap();
+    listener.listen("listener_reconnects").await.unwrap();
+    assert_eq!(
+        listener.channels().collect::<Vec<_>>(),
+        ["listener_reconnects"]
+    );
+
+    let client = connect("host=localhost port=5433 user=postgres").await;
+    client
+        .batch_execute("NOTIFY listener_reconnects, 'before'")
+        .await
+        .unwrap();
+    match listener.next().await.unwrap() {
+        ListenerEvent::Notification(n) => assert_eq!(n.payload(), "before"),
+        e => panic!("unexpected event {:?}", e),
+    }
+
+    client
+        .batch_execute(
+            "SELECT pg_terminate_backend(pid) FROM pg_stat_activity
+             WHERE application_name = 'listener_reconnects'",
+        )
+        .await
+        .unwrap();
+    listener.next().await.unwrap_err();
+    match listener.next().await.unwrap() {
+        ListenerEvent::Reconnected => {}
+        e => panic!("unexpected event {:?}", e),
+    }
+
+    client
+        .batch_execute("NOTIFY listener_reconnects, 'after'")
+        .await
+        .unwrap();
+    match listener.next().await.unwrap() {
+        ListenerEvent::Notification(n) => assert_eq!(n.payload(), "after"),
+        e => panic!("unexpected event {:?}", e),
+    }
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn passfile() {
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = std::env::temp_dir().join(format!("tokio-postgres-pgpass-{}", std::process::id()));
+    fs::write(
+        &path,
+        "otherhost:*:*:pass_user:wrong\nlocalhost:5433:postgres:pass_user:password\n",
+    )
+    .unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+
+    let config = format!(
+        "host=localhost port=5433 user=pass_user dbname=postgres passfile={}",
+        path.display()
+    );
+    smoke_test(&config).await;
+
+    // files readable by other users are ignored
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+    let err = tokio_postgres::connect(&config, NoTls).await.err().unwrap();
+    let source = std::error::Error::source(&err).unwrap().to_string();
+    assert_eq!(source, "password missing");
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn service() {
+    use std::fs;
+
+    let path = std::env::temp_dir().join(format!(
+        "tokio-postgres-service-{}.conf",
+        std::process::id()
+    ));
+    fs::write(
+        &path,
+        "[test]\nhost=localhost\nport=5433\nuser=pass_user\npassword=password\ndbname=postgres\n",
+    )
+    .unwrap();
+
+    smoke_test(&format!("service=test servicefile={}", path.display())).await;
+
+    // explicit settings take precedence over the service
+    let err = tokio_postgres::connect(
+        &format!("service=test servicefile={} password=wrong", path.display()),
+        NoTls,
+    )
+    .await
+    .err()
+    .unwrap();
+    assert_eq!(err.code(), Some(&SqlState::INVALID_PASSWORD));
+
+    let err = tokio_postgres::connect(
+        &format!("service=missing servicefile={}", path.display()),
+        NoTls,
+    )
+    .await
+    .err()
+    .unwrap();
+    let source = std::error::Error::source(&err).unwrap().to_string();
+    assert_eq!(source, "definition of service \"missing\" not found");
+
+    fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,124 @@
+use futures_util::join;
+use std::error::Error;
+use std::time::Duration;
+use tokio_postgres::error::SqlState;
+use tokio_postgres::rt::Smol;
+use tokio_postgres::{Config, NoTls};
+
+// no Tokio runtime is running in these tests, so any use of Tokio's reactor or timers would panic
+
+#[test]
+fn smoke_test() {
+    smol_2::block_on(async {
+        let mut config = "host=foobar.invalid,localhost port=5433 user=postgres"
+            .parse::<Config>()
+            .unwrap();
+        config
+            .runtime(Smol)
+            .connect_timeout(Duration::from_secs(10))
+            .total_connect_timeout(Duration::from_secs(30));
+
+        let (client, connection) = config.connect(NoTls).await.unwrap();
+        smol_2::spawn(async { connection.await.unwrap() }).detach();
+
+        let row = client.query_one("SELECT 1::INT", &[]).await.unwrap();
+        assert_eq!(row.get::<_, i32>(0), 1);
+    });
+}
+
+#[test]
+fn cancel_query() {
+    smol_2::block_on(async {
+        let mut config = "host=localhost port=5433 user=postgres"
+            .parse::<Config>()
+            .unwrap();
+        config.runtime(Smol);
+
+        let (client, connection) = config.connect(NoTls).await.unwrap();
+        smol_2::spawn(async { connection.await.unwrap() }).detach();
+
+        let cancel_token = client.cancel_token();
+        let cancel = async {
+            smol_2::Timer::after(Duration::from_millis(100)).await;
+            cancel_token.cancel_query(NoTls).await
+        };
+        let sleep = client.batch_execute("SELECT pg_sleep(100)");
+
+        let (sleep, cancel) = join!(sleep, cancel);
+        cancel.unwrap();
+        assert_eq!(sleep.unwrap_err().code(), Some(&SqlState::QUERY_CANCELED));
+    });
+}
+
+#[test]
+fn socket_options_rejected() {
+    smol_2::block_on(async {
+        let mut config = "host=localhost port=5433 user=postgres"
+            .parse::<Config>()
+            .unwrap();
+        config
+            .runtime(Smol)
+            .tcp_user_timeout(Duration::from_secs(10));
+
+        let err = config.connect(NoTls).await.err().unwrap();
+        assert_eq!(
+            err.source().unwrap().to_string(),
+            "tcp_user_timeout cannot be used with a custom runtime"
+        );
+    });
+}
+
+#[cfg(unix)]
+#[test]
+fn passfile() {
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+
+    let path =
+        std::env::temp_dir().join(format!("tokio-postgres-smol-pgpass-{}", std::process::id()));
+    fs::write(&path, "localhost:5433:postgres:pass_user:password\n").unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+
+    smol_2::block_on(async {
+        let mut config = "host=localhost port=5433 user=pass_user dbname=postgres"
+            .parse::<Config>()
+            .unwrap();
+        config.runtime(Smol).passfile(&path);
+
+        let (client, connection) = config.connect(NoTls).await.unwrap();
+        smol_2::spawn(async { connection.await.unwrap() }).detach();
+
+        let row = client.query_one("SELECT 1::INT", &[]).await.unwrap();
+        assert_eq!(row.get::<_, i32>(0), 1);
+    });
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn service() {
+    use std::fs;
+
+    let path = std::env::temp_dir().join(format!(
+        "tokio-postgres-smol-service-{}.conf",
+        std::process::id()
+    ));
+    fs::write(
+        &path,
+        "[test]\nhost=localhost\nport=5433\nuser=pass_user\npassword=password\ndbname=postgres\n",
+    )
+    .unwrap();
+
+    smol_2::block_on(async {
+        let mut config = Config::new();
+        config.runtime(Smol).service("test").servicefile(&path);
+
+        let (client, connection) = config.connect(NoTls).await.unwrap();
+        smol_2::spawn(async { connection.await.unwrap() }).detach();
+
+        let row = client.query_one("SELECT 1::INT", &[]).await.unwrap();
+        assert_eq!(row.get::<_, i32>(0), 1);
+    });
+
+    fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,48 @@
+use tokio_postgres::types::Type;
+use tokio_postgres::{Column, Row, SimpleQueryRow, Statement};
+
+#[test]
+fn row() {
+    let statement = Statement::new_test(
+        vec![],
+        vec![
+            Column::new("id", Type::INT4),
+            Column::new("name", Type::TEXT),
+            Column::new("tags", Type::TEXT_ARRAY),
+        ],
+    );
+    let row = Row::new_test(
+        statement,
+        &[
+            &7i32,
+            &None::<&str>,
+            &vec!["a".to_string(), "b".to_string()],
+        ],
+    )
+    .unwrap();
+
+    assert_eq!(row.len(), 3);
+    assert_eq!(row.columns()[1].name(), "name");
+    assert_eq!(row.get::<_, i32>("id"), 7);
+    assert_eq!(row.get::<_, Option<&str>>(1), None);
+    assert_eq!(row.get::<_, Vec<String>>("tags"), ["a", "b"]);
+}
+
+#[test]
+fn row_wrong_type() {
+    let statement = Statement::new_test(vec![], vec![Column::new("id", Type::INT4)]);
+
+    assert!(Row::new_test(statement.clone(), &[&"seven"]).is_err());
+    assert!(Row::new_test(statement, &[&1i32, &2i32]).is_err());
+}
+
+#[test]
+fn simple_query_row() {
+    let row = SimpleQueryRow::new_test(&["id", "name"], &[Some("7"), None]).unwrap();
+
+    assert_eq!(row.len(), 2);
+    assert_eq!(row.get("id"), Some("7"));
+    assert_eq!(row.get(1), None);
+
+    assert!(SimpleQueryRow::new_test(&["id"], &[]).is_err());
+}
@@ -0,0 +1,183 @@
+use crate::connect;
+use futures_util::TryStreamExt;
+use std::pin::pin;
+use tokio_postgres::text_copy::{TextCopyFormat, TextCopyInWriter, TextCopyOutStream};
+use tokio_postgres::types::{PgLsn, Type};
+
+#[tokio::test]
+async fn write_text() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute("CREATE TEMPORARY TABLE foo (id INT, bar TEXT, baz FLOAT8[])")
+        .await
+        .unwrap();
+
+    let sink = client
+        .copy_in("COPY foo (id, bar, baz) FROM STDIN")
+        .await
+        .unwrap();
+    let mut writer = pin!(TextCopyInWriter::new(
+        sink,
+        &[Type::INT4, Type::TEXT, Type::FLOAT8_ARRAY],
+        TextCopyFormat::Text,
+    ));
+    writer
+        .as_mut()
+        .write(&[
+            &1i32,
+            &"tab\there\\ and\nnewline",
+            &vec![Some(1.5f64), None],
+        ])
+        .await
+        .unwrap();
+    writer
+        .as_mut()
+        .write(&[&2i32, &None::<&str>, &Vec::<f64>::new()])
+        .await
+        .unwrap();
+    assert_eq!(writer.finish().await.unwrap(), 2);
+
+    let rows = client
+        .query("SELECT id, bar, baz::TEXT FROM foo ORDER BY id", &[])
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(
+        rows[0].get::<_, Option<&str>>(1),
+        Some("tab\there\\ and\nnewline")
+    );
+    assert_eq!(rows[0].get::<_, &str>(2), "{1.5,NULL}");
+    assert_eq!(rows[1].get::<_, Option<&str>>(1), None);
+    assert_eq!(rows[1].get::<_, &str>(2), "{}");
+}
+
+#[tokio::test]
+async fn write_csv() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute("CREATE TEMPORARY TABLE foo (id INT, bar TEXT, baz BYTEA)")
+        .await
+        .unwrap();
+
+    let sink = client
+        .copy_in("COPY foo (id, bar, baz) FROM STDIN (FORMAT csv)")
+        .await
+        .unwrap();
+    let mut writer = pin!(TextCopyInWriter::new(
+        sink,
+        &[Type::INT4, Type::TEXT, Type::BYTEA],
+        TextCopyFormat::Csv,
+    ));
+    for i in 0..1_000i32 {
+        writer
+            .as_mut()
+            .write(&[&i, &format!("\"{i}\",\n"), &&b"\x00\xff"[..]])
+            .await
+            .unwrap();
+    }
+    writer
+        .as_mut()
+        .write(&[&1_000i32, &"", &None::<&[u8]>])
+        .await
+        .unwrap();
+    writer
+        .as_mut()
+        .write(&[&1_001i32, &None::<&str>, &None::<&[u8]>])
+        .await
+        .unwrap();
+    assert_eq!(writer.finish().await.unwrap(), 1_002);
+
+    let rows = client
+        .query("SELECT id, bar, baz FROM foo ORDER BY id", &[])
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 1_002);
+    for (i, row) in rows[..1_000].iter().enumerate() {
+        assert_eq!(row.get::<_, &str>(1), format!("\"{i}\",\n"));
+        assert_eq!(row.get::<_, &[u8]>(2), b"\x00\xff");
+    }
+    assert_eq!(rows[1_000].get::<_, Option<&str>>(1), Some(""));
+    assert_eq!(rows[1_001].get::<_, Option<&str>>(1), None);
+}
+
+#[tokio::test]
+async fn write_unsupported_type() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute("CREATE TEMPORARY TABLE foo (bar PG_LSN)")
+        .await
+        .unwrap();
+
+    let sink = client.copy_in("COPY foo (bar) FROM STDIN").await.unwrap();
+    let mut writer = pin!(TextCopyInWriter::new(
+        sink,
+        &[Type::PG_LSN],
+        TextCopyFormat::Text
+    ));
+    let lsn = PgLsn::from(0x16B3748);
+    assert!(writer.as_mut().write(&[&lsn]).await.is_err());
+}
+
+#[tokio::test]
+async fn read_text() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute(
+            "CREATE TEMPORARY TABLE foo (id INT, bar TEXT, baz INT[]);
+             INSERT INTO foo (id, bar, baz) VALUES
+                (1, E'tab\\there\\\\ and\\nnewline', '{1,NULL}'),
+                (2, NULL, '{}');",
+        )
+        .await
+        .unwrap();
+
+    let stream = client
+        .copy_out("COPY (SELECT id, bar, baz FROM foo ORDER BY id) TO STDOUT")
+        .await
+        .unwrap();
+    let rows = TextCopyOutStream::new(stream, TextCopyFormat::Text)
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].parse::<i32>(0), Some(1));
+    assert_eq!(rows[0].get(1), Some("tab\there\\ and\nnewline"));
+    assert_eq!(rows[0].get(2), Some("{1,NULL}"));
+    assert_eq!(rows[1].get(1), None);
+    assert!(rows[1].try_parse::<i32>(2).is_err());
+    assert!(rows[1].try_parse::<i32>(3).is_err());
+}
+
+#[tokio::test]
+async fn read_csv_many_rows() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute(
+            "CREATE TEMPORARY TABLE foo (id INT, bar TEXT);
+             INSERT INTO foo (id, bar) SELECT i, E'\"' || i || E'\",\n' FROM generate_series(0, 9999) i;
+             INSERT INTO foo (id, bar) VALUES (10000, ''), (10001, NULL);",
+        )
+        .await
+        .unwrap();
+
+    let stream = client
+        .copy_out("COPY (SELECT id, bar FROM foo ORDER BY id) TO STDOUT (FORMAT csv)")
+        .await
+        .unwrap();
+    let rows = TextCopyOutStream::new(stream, TextCopyFormat::Csv)
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 10_002);
+    for (i, row) in rows[..10_000].iter().enumerate() {
+        assert_eq!(row.parse::<usize>(0), Some(i));
+        assert_eq!(row.get(1), Some(&*format!("\"{i}\",\n")));
+    }
+    assert_eq!(rows[10_000].get(1), Some(""));
+    assert_eq!(rows[10_001].get(1), None);
+}
@@ -0,0 +1,210 @@
+use crate::connect;
+use futures_util::{FutureExt, SinkExt, TryStreamExt};
+use std::collections::HashMap;
+use std::fmt;
+use std::pin::pin;
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpStream;
+use tokio_postgres::{Config, NoTls};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+#[derive(Debug)]
+struct SpanData {
+    name: &'static str,
+    fields: HashMap<String, String>,
+}
+
+#[derive(Clone, Default)]
+struct Recorder {
+    spans: Arc<Mutex<Vec<SpanData>>>,
+}
+
+impl Recorder {
+    fn span(&self, name: &str) -> HashMap<String, String> {
+        self.spans
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|s| s.name == name)
+            .map(|s| s.fields.clone())
+            .unwrap_or_else(|| panic!("no {name} span"))
+    }
+}
+
+struct Visitor<'a>(&'a mut HashMap<String, String>);
+
+impl Visit for Visitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{value:?}"));
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut fields = HashMap::new();
+        span.record(&mut Visitor(&mut fields));
+        let mut spans = self.spans.lock().unwrap();
+        spans.push(SpanData {
+            name: span.metadata().name(),
+            fields,
+        });
+        Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        let mut spans = self.spans.lock().unwrap();
+        let span = &mut spans[span.into_u64() as usize - 1];
+        values.record(&mut Visitor(&mut span.fields));
+    }
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, _: &Event<'_>) {}
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+#[tokio::test]
+async fn query_spans() {
+    let recorder = Recorder::default();
+    let _guard = tracing::subscriber::set_default(recorder.clone());
+
+    let client = connect("user=postgres").await;
+    let rows = client
+        .query("SELECT  generate_series(1, 3)", &[])
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 3);
+
+    let span = recorder.span("query");
+    assert_eq!(span["db.system.name"], "postgresql");
+    assert_eq!(span["db.namespace"], "postgres");
+    assert_eq!(span["db.operation.name"], "SELECT");
+    assert!(!span.contains_key("db.query.text"));
+    assert_eq!(span["db.response.returned_rows"], "3");
+    assert!(span.contains_key("db.client.operation.duration"));
+    assert!(!span.contains_key("error.type"));
+
+    client
+        .query("SELECT generate_series(1, 3)", &[])
+        .await
+        .unwrap();
+    let spans = recorder.spans.lock().unwrap();
+    let fingerprints = spans
+        .iter()
+        .filter(|s| s.name == "query")
+        .map(|s| &s.fields["db.query.fingerprint"])
+        .collect::<Vec<_>>();
+    assert_eq!(fingerprints.len(), 2);
+    assert_eq!(fingerprints[0], fingerprints[1]);
+}
+
+#[tokio::test]
+async fn query_text() {
+    let recorder = Recorder::default();
+    let _guard = tracing::subscriber::set_default(recorder.clone());
+
+    let socket = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+    let mut config = "user=postgres".parse::<Config>().unwrap();
+    config.trace_query_text(true);
+    let (client, connection) = config.connect_raw(socket, NoTls).await.unwrap();
+    tokio::spawn(connection.map(|r| r.unwrap()));
+
+    client.batch_execute("SELECT 'secret'").await.unwrap();
+    assert_eq!(
+        recorder.span("simple_query")["db.query.text"],
+        "SELECT 'secret'"
+    );
+}
+
+#[tokio::test]
+async fn cancelled_span() {
+    let recorder = Recorder::default();
+    let _guard = tracing::subscriber::set_default(recorder.clone());
+
+    let client = connect("user=postgres").await;
+    let stream = client
+        .query_raw("SELECT generate_series(1, 3)", std::iter::empty::<i32>())
+        .await
+        .unwrap();
+    drop(stream);
+
+    let span = recorder.span("query");
+    assert_eq!(span["error.type"], "cancelled");
+    assert!(!span.contains_key("db.response.returned_rows"));
+    assert!(span.contains_key("db.client.operation.duration"));
+}
+
+#[tokio::test]
+async fn error_span() {
+    let recorder = Recorder::default();
+    let _guard = tracing::subscriber::set_default(recorder.clone());
+
+    let client = connect("user=postgres").await;
+    client.execute("SELECT 1 / 0", &[]).await.unwrap_err();
+
+    let span = recorder.span("execute");
+    assert_eq!(span["otel.status_code"], "ERROR");
+    assert_eq!(span["error.type"], "22012");
+    assert_eq!(span["db.response.status_code"], "22012");
+}
+
+#[tokio::test]
+async fn copy_spans() {
+    let recorder = Recorder::default();
+    let _guard = tracing::subscriber::set_default(recorder.clone());
+
+    let client = connect("user=postgres").await;
+    client
+        .batch_execute("CREATE TEMPORARY TABLE foo (id INT)")
+        .await
+        .unwrap();
+    assert!(
+        recorder
+            .spans
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|s| s.name == "simple_query")
+    );
+
+    let mut sink = pin!(
+        client
+            .copy_in::<_, &[u8]>("COPY foo FROM STDIN")
+            .await
+            .unwrap()
+    );
+    sink.send(&b"1\n2\n"[..]).await.unwrap();
+    assert_eq!(sink.finish().await.unwrap(), 2);
+    let span = recorder.span("copy_in");
+    assert_eq!(span["db.operation.name"], "COPY");
+    assert!(span.contains_key("db.client.operation.duration"));
+
+    let data = client
+        .copy_out("COPY foo TO STDOUT")
+        .await
+        .unwrap()
+        .map_ok(|b| b.to_vec())
+        .try_concat()
+        .await
+        .unwrap();
+    assert_eq!(&data[..], b"1\n2\n");
+    assert!(
+        recorder
+            .span("copy_out")
+            .contains_key("db.client.operation.duration")
+    );
+}
@@ -0,0 +1,36 @@
+use bitvec_1::bitvec;
+use bitvec_1::order::{Lsb0, Msb0};
+use bitvec_1::vec::BitVec;
+
+use crate::types::test_type;
+
+#[tokio::test]
+async fn test_bit_params() {
+    test_type(
+        "BIT(14)",
+        &[
+            (
+                Some(bitvec![u8, Msb0; 0, 1, 1, 0, 1, 0, 0, 1, 0, 0, 0, 0, 0, 1]),
+                "B'01101001000001'",
+            ),
+            (None, "NULL"),
+        ],
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_varbit_params() {
+    test_type(
+        "VARBIT",
+        &[
+            (
+                Some(bitvec![usize, Lsb0; 0, 1, 1, 0, 1, 0, 0, 1, 0, 0, 0, 0, 0, 1]),
+                "B'01101001000001'",
+            ),
+            (Some(BitVec::new()), "B''"),
+            (None, "NULL"),
+        ],
+    )
+    .await
+}
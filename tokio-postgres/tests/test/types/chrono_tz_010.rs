@@ -0,0 +1,53 @@
+use chrono_04::{DateTime, TimeZone, Utc};
+use chrono_tz_010::Tz;
+use tokio_postgres::types::{InZone, Zone};
+
+use crate::connect;
+use crate::types::test_type;
+
+struct Tokyo;
+
+impl Zone for Tokyo {
+    const TZ: Tz = Tz::Asia__Tokyo;
+}
+
+#[tokio::test]
+async fn test_date_time_tz_params() {
+    let time = Tz::UTC.with_ymd_and_hms(2010, 2, 9, 23, 11, 45).unwrap();
+    test_type(
+        "TIMESTAMP WITH TIME ZONE",
+        &[(Some(time), "'2010-02-09 23:11:45Z'"), (None, "NULL")],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn zoned_date_time() {
+    let client = connect("user=postgres").await;
+
+    let time = Tz::America__New_York
+        .with_ymd_and_hms(2024, 3, 10, 1, 30, 0)
+        .unwrap();
+    let row = client
+        .query_one("SELECT $1::TIMESTAMPTZ, $1::TIMESTAMPTZ", &[&time])
+        .await
+        .unwrap();
+
+    let utc = row.get::<_, DateTime<Tz>>(0);
+    assert_eq!(utc.timezone(), Tz::UTC);
+    assert_eq!(utc, time);
+
+    let tokyo = row.get::<_, InZone<Tokyo>>(1);
+    assert_eq!(tokyo.get().timezone(), Tz::Asia__Tokyo);
+    assert_eq!(tokyo.get().to_rfc3339(), "2024-03-10T15:30:00+09:00");
+    assert_eq!(
+        tokyo.into_inner().with_timezone(&Utc),
+        time.with_timezone(&Utc)
+    );
+
+    let row = client
+        .query_one("SELECT $1::TIMESTAMPTZ = $2", &[&tokyo, &time])
+        .await
+        .unwrap();
+    assert!(row.get::<_, bool>(0));
+}
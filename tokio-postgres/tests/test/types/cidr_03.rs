@@ -0,0 +1,33 @@
+use cidr_03::{IpCidr, IpInet};
+
+use crate::types::test_type;
+
+#[tokio::test]
+async fn test_inet_params() {
+    test_type(
+        "INET",
+        &[
+            (Some("127.0.0.1".parse::<IpInet>().unwrap()), "'127.0.0.1'"),
+            (Some("10.1.2.3/8".parse().unwrap()), "'10.1.2.3/8'"),
+            (Some("2001:db8::1/64".parse().unwrap()), "'2001:db8::1/64'"),
+            (None, "NULL"),
+        ],
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_cidr_params() {
+    test_type(
+        "CIDR",
+        &[
+            (
+                Some("10.0.0.0/8".parse::<IpCidr>().unwrap()),
+                "'10.0.0.0/8'",
+            ),
+            (Some("2001:db8::/32".parse().unwrap()), "'2001:db8::/32'"),
+            (None, "NULL"),
+        ],
+    )
+    .await
+}
@@ -0,0 +1,19 @@
+use compact_str_09::CompactString;
+
+use crate::types::test_type;
+
+#[tokio::test]
+async fn test_compact_str() {
+    test_type(
+        "VARCHAR",
+        &[
+            (Some(CompactString::new("hello world")), "'hello world'"),
+            (
+                Some(CompactString::new("イロハニホヘト チリヌルヲ")),
+                "'イロハニホヘト チリヌルヲ'",
+            ),
+            (None, "NULL"),
+        ],
+    )
+    .await;
+}
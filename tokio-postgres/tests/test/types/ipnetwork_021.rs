@@ -0,0 +1,36 @@
+use ipnetwork_021::IpNetwork;
+
+use crate::types::test_type;
+
+#[tokio::test]
+async fn test_inet_params() {
+    test_type(
+        "INET",
+        &[
+            (
+                Some("127.0.0.1".parse::<IpNetwork>().unwrap()),
+                "'127.0.0.1'",
+            ),
+            (Some("10.1.2.3/8".parse().unwrap()), "'10.1.2.3/8'"),
+            (Some("2001:db8::1/64".parse().unwrap()), "'2001:db8::1/64'"),
+            (None, "NULL"),
+        ],
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_cidr_params() {
+    test_type(
+        "CIDR",
+        &[
+            (
+                Some("10.0.0.0/8".parse::<IpNetwork>().unwrap()),
+                "'10.0.0.0/8'",
+            ),
+            (Some("2001:db8::/32".parse().unwrap()), "'2001:db8::/32'"),
+            (None, "NULL"),
+        ],
+    )
+    .await
+}
@@ -0,0 +1,35 @@
+use macaddr_1::{MacAddr6, MacAddr8};
+
+use crate::types::test_type;
+
+#[tokio::test]
+async fn test_macaddr6_params() {
+    test_type(
+        "MACADDR",
+        &[
+            (
+                Some(MacAddr6::new(0x12, 0x34, 0x56, 0xab, 0xcd, 0xef)),
+                "'12:34:56:ab:cd:ef'",
+            ),
+            (None, "NULL"),
+        ],
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_macaddr8_params() {
+    test_type(
+        "MACADDR8",
+        &[
+            (
+                Some(MacAddr8::new(
+                    0x08, 0x00, 0x2b, 0x01, 0x02, 0x03, 0x04, 0x05,
+                )),
+                "'08:00:2b:01:02:03:04:05'",
+            ),
+            (None, "NULL"),
+        ],
+    )
+    .await
+}
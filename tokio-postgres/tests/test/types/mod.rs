@@ -5,13 +5,19 @@ use std::f32;
 use std::f64;
 use std::fmt;
 use std::net::IpAddr;
+use std::ops::Bound;
 use std::result;
 use std::str::FromStr;
-use std::time::{Duration, UNIX_EPOCH};
-use tokio_postgres::types::{FromSql, FromSqlOwned, IsNull, Kind, PgLsn, ToSql, Type, WrongType};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio_postgres::types::{
+    AclItem, AclPrivileges, ArrayDimension, BpChar, Composite, EnumValue, FromSql, FromSqlOwned,
+    IsNull, JsonPath, Kind, LQuery, LTree, LTxtQuery, Lenient, Lexeme, LexemePosition, NumericSign,
+    PgArray, PgCube, PgLsn, PgNumeric, PgRange, PgSnapshot, RegClass, RegNamespace, RegProcedure,
+    RegType, Timestamp, ToSql, TsQuery, TsVector, TsWeight, Type, WrongType,
+};
 
 use crate::connect;
-use bytes::BytesMut;
+use bytes::{Bytes, BytesMut};
 
 #[cfg(feature = "with-bit-vec-0_6")]
 mod bit_vec_06;
@@ -21,20 +27,36 @@ mod bit_vec_07;
 mod bit_vec_08;
 #[cfg(feature = "with-bit-vec-0_9")]
 mod bit_vec_09;
+#[cfg(feature = "with-bitvec-1")]
+mod bitvec_1;
 #[cfg(feature = "with-chrono-0_4")]
 mod chrono_04;
+#[cfg(feature = "with-chrono-tz-0_10")]
+mod chrono_tz_010;
+#[cfg(feature = "with-cidr-0_3")]
+mod cidr_03;
+#[cfg(feature = "with-compact_str-0_9")]
+mod compact_str_09;
 #[cfg(feature = "with-eui48-1")]
 mod eui48_1;
 #[cfg(feature = "with-geo-types-0_6")]
 mod geo_types_06;
 #[cfg(feature = "with-geo-types-0_7")]
 mod geo_types_07;
+#[cfg(feature = "with-ipnetwork-0_21")]
+mod ipnetwork_021;
 #[cfg(feature = "with-jiff-0_1")]
 mod jiff_01;
 #[cfg(feature = "with-jiff-0_2")]
 mod jiff_02;
+#[cfg(feature = "with-macaddr-1")]
+mod macaddr_1;
 #[cfg(feature = "with-serde_json-1")]
 mod serde_json_1;
+#[cfg(feature = "with-simd-json-0_15")]
+mod simd_json_015;
+#[cfg(feature = "with-smallvec-1")]
+mod smallvec_1;
 #[cfg(feature = "with-smol_str-01")]
 mod smol_str_01;
 #[cfg(feature = "with-time-0_2")]
@@ -152,6 +174,66 @@ async fn test_i64_params() {
     .await;
 }
 
+#[tokio::test]
+async fn test_u64_params() {
+    test_type(
+        "BIGINT",
+        &[
+            (Some(9_223_372_036_854_775_807u64), "9223372036854775807"),
+            (Some(0u64), "0"),
+            (None, "NULL"),
+        ],
+    )
+    .await;
+    test_type(
+        "NUMERIC",
+        &[
+            (Some(u64::MAX), "18446744073709551615"),
+            (Some(0u64), "0"),
+            (None, "NULL"),
+        ],
+    )
+    .await;
+
+    let client = connect("user=postgres").await;
+    assert!(
+        client
+            .query_one("SELECT $1::BIGINT", &[&u64::MAX])
+            .await
+            .is_err()
+    );
+    let row = client
+        .query_one("SELECT -1::BIGINT, -1::NUMERIC, 1.5::NUMERIC", &[])
+        .await
+        .unwrap();
+    assert!(row.try_get::<_, u64>(0).is_err());
+    assert!(row.try_get::<_, u64>(1).is_err());
+    assert!(row.try_get::<_, u64>(2).is_err());
+}
+
+#[tokio::test]
+async fn test_u128_params() {
+    test_type(
+        "NUMERIC",
+        &[
+            (Some(u128::MAX), "340282366920938463463374607431768211455"),
+            (Some(0u128), "0"),
+            (None, "NULL"),
+        ],
+    )
+    .await;
+
+    let client = connect("user=postgres").await;
+    let row = client
+        .query_one(
+            "SELECT 340282366920938463463374607431768211456::NUMERIC",
+            &[],
+        )
+        .await
+        .unwrap();
+    assert!(row.try_get::<_, u128>(0).is_err());
+}
+
 #[tokio::test]
 async fn test_lsn_params() {
     test_type(
@@ -164,6 +246,280 @@ async fn test_lsn_params() {
     .await
 }
 
+#[tokio::test]
+async fn reg_types() {
+    test_type(
+        "REGCLASS",
+        &[(Some(RegClass(1259)), "'pg_class'"), (None, "NULL")],
+    )
+    .await;
+    test_type("REGTYPE", &[(Some(RegType(25)), "'text'"), (None, "NULL")]).await;
+    test_type(
+        "REGNAMESPACE",
+        &[(Some(RegNamespace(11)), "'pg_catalog'"), (None, "NULL")],
+    )
+    .await;
+    test_type(
+        "REGPROCEDURE",
+        &[
+            (Some(RegProcedure(1242)), "'boolin(cstring)'"),
+            (None, "NULL"),
+        ],
+    )
+    .await;
+
+    let client = connect("user=postgres").await;
+    let row = client
+        .query_one(
+            "SELECT $1::regclass::text, attrelid::regclass, atttypid::regtype FROM pg_attribute \
+             WHERE attrelid = $1 AND attname = 'relname'",
+            &[&RegClass(1259)],
+        )
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, &str>(0), "pg_class");
+    assert_eq!(row.get::<_, RegClass>(1), RegClass(1259));
+    assert_eq!(row.get::<_, RegType>(2), RegType(19));
+}
+
+#[tokio::test]
+async fn snapshot() {
+    let snapshot = PgSnapshot {
+        xmin: 10,
+        xmax: 20,
+        xip: vec![10, 12, 15],
+    };
+    test_type(
+        "PG_SNAPSHOT",
+        &[
+            (Some(snapshot.clone()), "'10:20:10,12,15'"),
+            (
+                Some(PgSnapshot {
+                    xmin: 5,
+                    xmax: 5,
+                    xip: vec![],
+                }),
+                "'5:5:'",
+            ),
+            (None, "NULL"),
+        ],
+    )
+    .await;
+    test_type(
+        "TXID_SNAPSHOT",
+        &[(Some(snapshot.clone()), "'10:20:10,12,15'"), (None, "NULL")],
+    )
+    .await;
+
+    assert!(snapshot.is_visible(9));
+    assert!(!snapshot.is_visible(10));
+    assert!(snapshot.is_visible(11));
+    assert!(!snapshot.is_visible(20));
+
+    let client = connect("user=postgres").await;
+    let row = client
+        .query_one(
+            "SELECT pg_current_snapshot(), pg_current_xact_id()::text::int8",
+            &[],
+        )
+        .await
+        .unwrap();
+    let snapshot = row.get::<_, PgSnapshot>(0);
+    let xid = row.get::<_, i64>(1) as u64;
+    assert!(!snapshot.is_visible(xid));
+
+    let row = client
+        .query_one(
+            "SELECT pg_visible_in_snapshot('11'::xid8, $1), pg_visible_in_snapshot('12'::xid8, $1)",
+            &[&PgSnapshot {
+                xmin: 10,
+                xmax: 20,
+                xip: vec![10, 12, 15],
+            }],
+        )
+        .await
+        .unwrap();
+    assert!(row.get::<_, bool>(0));
+    assert!(!row.get::<_, bool>(1));
+}
+
+#[tokio::test]
+async fn lsn_text() {
+    let client = connect("user=postgres").await;
+
+    let row = client
+        .query_one(
+            "SELECT pg_current_wal_lsn(), pg_current_wal_lsn()::text",
+            &[],
+        )
+        .await
+        .unwrap();
+    let lsn = row.get::<_, PgLsn>(0);
+    assert_eq!(lsn.to_string(), row.get::<_, &str>(1));
+    assert_eq!(row.get::<_, &str>(1).parse::<PgLsn>().unwrap(), lsn);
+
+    let lsn = "FFFFFFFF/FFFFFFFF".parse::<PgLsn>().unwrap();
+    assert_eq!(u64::from(lsn), u64::MAX);
+    assert!("0/100000000".parse::<PgLsn>().is_err());
+    assert!("0/".parse::<PgLsn>().is_err());
+    assert!("16/B374D848".parse::<PgLsn>().unwrap() > "16/B374D847".parse().unwrap());
+}
+
+#[tokio::test]
+async fn test_numeric_params() {
+    let checks = [
+        "0",
+        "0.000",
+        "1",
+        "-1",
+        "10000",
+        "12345678.9012",
+        "-0.00012",
+        "0.1",
+        "1000000000000000000000000000000000000000",
+        "0.000000000000000000000000000000000000001",
+        "3.14159265358979323846264338327950288419716939937510",
+        "NaN",
+        "Infinity",
+        "-Infinity",
+    ];
+    let mut checks = checks
+        .iter()
+        .map(|s| (Some(s.parse::<PgNumeric>().unwrap()), format!("'{s}'")))
+        .collect::<Vec<_>>();
+    checks.push((None, "NULL".to_string()));
+    test_type("NUMERIC", &checks).await;
+
+    let client = connect("user=postgres").await;
+    for s in ["12345678.9012", "-0.00012", "1e-20", "1.50E+3", "Infinity"] {
+        let row = client
+            .query_one(
+                &*format!("SELECT '{s}'::NUMERIC, '{s}'::NUMERIC::TEXT"),
+                &[],
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            row.get::<_, PgNumeric>(0).to_string(),
+            row.get::<_, &str>(1)
+        );
+    }
+}
+
+#[test]
+fn numeric_conversions() {
+    let n = "-12345678.9012".parse::<PgNumeric>().unwrap();
+    assert_eq!(n.sign(), NumericSign::Negative);
+    assert_eq!(n.weight(), 1);
+    assert_eq!(n.scale(), 4);
+    assert_eq!(n.digits(), [1234, 5678, 9012]);
+    assert_eq!(
+        PgNumeric::new(NumericSign::Negative, 2, 4, vec![0, 1234, 5678, 9012, 0]),
+        Some(n.clone())
+    );
+    assert_eq!(
+        PgNumeric::new(NumericSign::Positive, 0, 0, vec![10000]),
+        None
+    );
+
+    assert_eq!(f64::from(n.clone()), -12345678.9012);
+    assert!(i64::try_from(n).is_err());
+    assert_eq!(PgNumeric::from(0.1f64).to_string(), "0.1");
+    assert!(PgNumeric::from(f64::NAN).is_nan());
+
+    for v in [0, 1, -1, 10000, i64::MIN, i64::MAX] {
+        assert_eq!(i64::try_from(PgNumeric::from(v)).unwrap(), v);
+        assert_eq!(PgNumeric::from(v).to_string(), v.to_string());
+    }
+    assert_eq!(
+        i128::try_from(PgNumeric::from(i128::MIN)).unwrap(),
+        i128::MIN
+    );
+    assert_eq!(
+        u128::try_from(PgNumeric::from(u128::MAX)).unwrap(),
+        u128::MAX
+    );
+    assert_eq!(
+        i64::try_from("100.000".parse::<PgNumeric>().unwrap()).unwrap(),
+        100
+    );
+    assert!(u8::try_from(PgNumeric::from(256)).is_err());
+    assert!(u8::try_from(PgNumeric::from(-1)).is_err());
+    assert!(i32::try_from(PgNumeric::nan()).is_err());
+
+    for s in ["", ".", "1e", "e1", "1.2.3", "--1", "-NaN", "1e200000"] {
+        assert!(s.parse::<PgNumeric>().is_err(), "{s}");
+    }
+    assert_eq!("-0".parse::<PgNumeric>().unwrap().to_string(), "0");
+    assert_eq!(
+        " +1.5e-3 ".parse::<PgNumeric>().unwrap().to_string(),
+        "0.0015"
+    );
+    assert_eq!("inf".parse::<PgNumeric>().unwrap(), PgNumeric::infinity());
+}
+
+#[tokio::test]
+async fn test_range_params() {
+    test_type(
+        "INT4RANGE",
+        &[
+            (Some(PgRange::from(1i32..10)), "'[1,10)'"),
+            (Some(PgRange::from(..10i32)), "'(,10)'"),
+            (Some(PgRange::from(1i32..)), "'[1,)'"),
+            (Some(PgRange::from(..)), "'(,)'"),
+            (Some(PgRange::Empty), "'empty'"),
+            (None, "NULL"),
+        ],
+    )
+    .await;
+
+    test_type(
+        "INT8RANGE",
+        &[(
+            Some(PgRange::from(i64::MIN..i64::MAX)),
+            &*format!("'[{},{})'", i64::MIN, i64::MAX),
+        )],
+    )
+    .await;
+
+    let num = |s: &str| s.parse::<PgNumeric>().unwrap();
+    test_type(
+        "NUMRANGE",
+        &[
+            (
+                Some(PgRange::new(
+                    Bound::Excluded(num("1.5")),
+                    Bound::Included(num("2.25")),
+                )),
+                "'(1.5,2.25]'",
+            ),
+            (Some(PgRange::from(num("-1")..)), "'[-1,)'"),
+        ],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn range_canonicalization() {
+    let client = connect("user=postgres").await;
+
+    let row = client
+        .query_one("SELECT $1::INT4RANGE", &[&PgRange::from(1i32..=10)])
+        .await
+        .unwrap();
+    let range = row.get::<_, PgRange<i32>>(0);
+    assert_eq!(range, PgRange::from(1..11));
+    assert!(range.contains(&10));
+    assert!(!range.contains(&11));
+    assert_eq!(std::ops::Range::try_from(range).unwrap(), 1..11);
+
+    let row = client
+        .query_one("SELECT $1::INT4RANGE", &[&PgRange::from(5i32..5)])
+        .await
+        .unwrap();
+    assert!(row.get::<_, PgRange<i32>>(0).is_empty());
+}
+
 #[tokio::test]
 async fn test_f32_params() {
     test_type(
@@ -313,6 +669,74 @@ async fn test_citext_params() {
     assert_eq!(vec!["foobar".to_string(), "FooBar".to_string()], rows,);
 }
 
+#[tokio::test]
+async fn test_citext_owned_and_array_params() {
+    test_type(
+        "CITEXT",
+        &[(Some("FooBar".to_string()), "'FooBar'"), (None, "NULL")],
+    )
+    .await;
+    test_type(
+        "CITEXT[]",
+        &[
+            (
+                Some(vec!["foo".to_string(), "BAR".to_string()]),
+                "ARRAY['foo', 'BAR']",
+            ),
+            (None, "NULL"),
+        ],
+    )
+    .await;
+
+    let client = connect("user=postgres").await;
+    let row = client
+        .query_one(
+            "SELECT $1::CITEXT = 'FOOBAR', 'BAR' = ANY($2::CITEXT[])",
+            &[&"foobar".to_string(), &vec!["foo", "bar"]],
+        )
+        .await
+        .unwrap();
+    assert!(row.get::<_, bool>(0));
+    assert!(row.get::<_, bool>(1));
+}
+
+#[tokio::test]
+async fn bpchar() {
+    test_type(
+        "CHAR(5)",
+        &[
+            (Some(BpChar::new("ab")), "'ab'"),
+            (Some(BpChar::new("abcde")), "'abcde'"),
+            (Some(BpChar::new("")), "''"),
+            (None, "NULL"),
+        ],
+    )
+    .await;
+
+    let client = connect("user=postgres").await;
+    client
+        .batch_execute("CREATE TEMPORARY TABLE foo (c CHAR(5)); INSERT INTO foo VALUES ('ab')")
+        .await
+        .unwrap();
+
+    let row = client
+        .query_one(
+            "SELECT c, c FROM foo WHERE c = $1",
+            &[&BpChar::new("ab   ")],
+        )
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, BpChar>(0).as_str(), "ab");
+    assert_eq!(row.get::<_, &str>(1), "ab   ");
+
+    assert!(
+        client
+            .execute("INSERT INTO foo VALUES ($1)", &[&BpChar::new("abcdef")])
+            .await
+            .is_err()
+    );
+}
+
 #[tokio::test]
 async fn test_bytea_params() {
     test_type(
@@ -325,6 +749,36 @@ async fn test_bytea_params() {
     .await;
 }
 
+#[tokio::test]
+async fn bytes() {
+    test_type(
+        "BYTEA",
+        &[
+            (
+                Some(Bytes::from_static(&[0, 1, 2, 3, 254, 255])),
+                "'\\x00010203feff'",
+            ),
+            (None, "NULL"),
+        ],
+    )
+    .await;
+
+    let client = connect("user=postgres").await;
+    let row = client
+        .query_one(
+            "SELECT $1::BYTEA, NULL::BYTEA, 'a'::TEXT",
+            &[&Bytes::from("hello")],
+        )
+        .await
+        .unwrap();
+
+    let bytes = row.get_bytes(0).unwrap();
+    assert_eq!(bytes, "hello");
+    assert_eq!(bytes.as_ptr(), row.get::<_, &[u8]>(0).as_ptr());
+    assert_eq!(row.get_bytes(1), None);
+    assert!(row.try_get_bytes(2).is_err());
+}
+
 #[tokio::test]
 async fn test_borrowed_bytea() {
     let client = connect("user=postgres").await;
@@ -377,6 +831,55 @@ async fn test_array_vec_params() {
     .await;
 }
 
+#[tokio::test]
+async fn test_pg_array_params() {
+    let dim = |len, lower_bound| ArrayDimension { len, lower_bound };
+
+    test_type(
+        "integer[]",
+        &[
+            (Some(PgArray::from_vec(vec![1i32, 2], 1)), "ARRAY[1,2]"),
+            (
+                Some(PgArray::from_vec(vec![1i32, 2, 3], 0)),
+                "'[0:2]={1,2,3}'",
+            ),
+            (
+                Some(PgArray::new(vec![dim(2, -1), dim(3, 1)], vec![1i32, 2, 3, 4, 5, 6]).unwrap()),
+                "'[-1:0][1:3]={{1,2,3},{4,5,6}}'",
+            ),
+            (Some(PgArray::from_vec(vec![], 1)), "ARRAY[]"),
+            (None, "NULL"),
+        ],
+    )
+    .await;
+    test_type(
+        "text[]",
+        &[(
+            Some(PgArray::from_vec(vec![Some("a".to_string()), None], 5)),
+            "'[5:6]={a,NULL}'",
+        )],
+    )
+    .await;
+
+    let array = PgArray::new(vec![dim(2, -1), dim(3, 1)], vec![1i32, 2, 3, 4, 5, 6]).unwrap();
+    assert_eq!(array.get(&[-1, 1]), Some(&1));
+    assert_eq!(array.get(&[0, 3]), Some(&6));
+    assert_eq!(array.get(&[1, 1]), None);
+    assert_eq!(array.get(&[0]), None);
+    assert!(PgArray::new(vec![dim(2, 1)], vec![1i32]).is_none());
+
+    let client = connect("user=postgres").await;
+    let row = client
+        .query_one(
+            "SELECT array_lower($1::integer[], 1), $1[0]",
+            &[&PgArray::from_vec(vec![10i32, 20], 0)],
+        )
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, i32>(0), 0);
+    assert_eq!(row.get::<_, i32>(1), 10);
+}
+
 #[cfg(feature = "array-impls")]
 #[tokio::test]
 async fn test_array_array_params() {
@@ -387,6 +890,14 @@ async fn test_array_array_params() {
         &[(Some([] as [i32; 0]), "ARRAY[]"), (None, "NULL")],
     )
     .await;
+
+    let client = connect("user=postgres").await;
+    let row = client
+        .query_one("SELECT ARRAY[1, 2, 3], ARRAY[1]", &[])
+        .await
+        .unwrap();
+    assert!(row.try_get::<_, [i32; 2]>(0).is_err());
+    assert!(row.try_get::<_, [i32; 2]>(1).is_err());
 }
 
 #[allow(clippy::eq_op)]
@@ -584,6 +1095,53 @@ async fn composite() {
     }
 }
 
+#[tokio::test]
+async fn dynamic_composite() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute(
+            "CREATE TYPE pg_temp.supplier AS (
+                id INTEGER,
+                name TEXT
+            );
+            CREATE TYPE pg_temp.inventory_item AS (
+                name TEXT,
+                supplier supplier,
+                price NUMERIC
+            )",
+        )
+        .await
+        .unwrap();
+
+    let row = client
+        .query_one(
+            "SELECT ROW('fuzzy dice', ROW(42, 'ACME'), NULL)::inventory_item",
+            &[],
+        )
+        .await
+        .unwrap();
+    let item = row.get::<_, Composite<'_>>(0);
+    assert_eq!(item.type_().name(), "inventory_item");
+    assert_eq!(item.len(), 3);
+    assert_eq!(
+        item.fields().iter().map(|f| f.name()).collect::<Vec<_>>(),
+        ["name", "supplier", "price"]
+    );
+    assert_eq!(item.try_get::<&str>("name").unwrap(), "fuzzy dice");
+    assert_eq!(item.try_get::<Option<PgNumeric>>("price").unwrap(), None);
+    assert!(item.raw(2).is_none());
+
+    let supplier = item.try_get::<Composite<'_>>("supplier").unwrap();
+    assert_eq!(supplier.try_get_idx::<i32>(0).unwrap(), 42);
+    assert_eq!(supplier.try_get::<String>("name").unwrap(), "ACME");
+
+    assert!(item.try_get::<i32>("name").is_err());
+    assert!(item.try_get::<&str>("missing").is_err());
+    assert!(item.try_get_idx::<&str>(3).is_err());
+    assert!(item.try_get::<PgNumeric>("price").is_err());
+}
+
 #[tokio::test]
 async fn enum_() {
     let client = connect("user=postgres").await;
@@ -607,6 +1165,37 @@ async fn enum_() {
     }
 }
 
+#[tokio::test]
+async fn dynamic_enum() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute("CREATE TYPE pg_temp.mood AS ENUM ('sad', 'ok', 'happy')")
+        .await
+        .unwrap();
+
+    let stmt = client.prepare("SELECT $1::mood").await.unwrap();
+    let row = client
+        .query_one(&stmt, &[&EnumValue::from("happy")])
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, EnumValue>(0).label(), "happy");
+
+    let err = client
+        .query_one(&stmt, &[&EnumValue::from("ecstatic")])
+        .await
+        .unwrap_err();
+    let cause = err.source().unwrap().to_string();
+    assert!(cause.contains("ecstatic"), "{cause}");
+
+    let row = client
+        .query_one("SELECT 'sad'::mood, 'sad'::TEXT", &[])
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, EnumValue>(0), EnumValue::from("sad"));
+    assert!(row.try_get::<_, EnumValue>(1).is_err());
+}
+
 #[tokio::test]
 async fn system_time() {
     test_type(
@@ -630,6 +1219,23 @@ async fn system_time() {
     .await;
 }
 
+#[tokio::test]
+async fn system_time_infinity() {
+    test_type(
+        "TIMESTAMP",
+        &[
+            (
+                Some(Timestamp::Value(UNIX_EPOCH + Duration::from_millis(1_010))),
+                "'1970-01-01 00:00:01.01'",
+            ),
+            (Some(Timestamp::<SystemTime>::PosInfinity), "'infinity'"),
+            (Some(Timestamp::<SystemTime>::NegInfinity), "'-infinity'"),
+            (None, "NULL"),
+        ],
+    )
+    .await;
+}
+
 #[tokio::test]
 async fn inet() {
     test_type(
@@ -748,6 +1354,270 @@ async fn ltxtquery_any() {
     .await;
 }
 
+#[tokio::test]
+async fn ltree_newtypes() {
+    test_type(
+        "ltree",
+        &[
+            (
+                Some(LTree::from("Top.Science.Astronomy")),
+                "'Top.Science.Astronomy'",
+            ),
+            (Some(LTree::from("")), "''"),
+            (None, "NULL"),
+        ],
+    )
+    .await;
+    test_type(
+        "lquery",
+        &[(Some(LQuery::from("*.Astronomy.*")), "'*.Astronomy.*'")],
+    )
+    .await;
+    test_type(
+        "ltxtquery",
+        &[(
+            Some(LTxtQuery::from("Europe & Russia*")),
+            "'Europe & Russia*'",
+        )],
+    )
+    .await;
+
+    let path = LTree::from("Top.Science.Astronomy");
+    assert_eq!(
+        path.labels().collect::<Vec<_>>(),
+        ["Top", "Science", "Astronomy"]
+    );
+    assert_eq!(LTree::from("").labels().count(), 0);
+
+    let client = connect("user=postgres").await;
+    let row = client
+        .query_one(
+            "SELECT $1::ltree ~ $2::lquery, $1 @ $3::ltxtquery",
+            &[
+                &path,
+                &LQuery::from("*.Astronomy"),
+                &LTxtQuery::from("Science"),
+            ],
+        )
+        .await
+        .unwrap();
+    assert!(row.get::<_, bool>(0));
+    assert!(row.get::<_, bool>(1));
+
+    let row = client.query_one("SELECT 'a.b'::TEXT", &[]).await.unwrap();
+    assert!(row.try_get::<_, LTree>(0).is_err());
+}
+
+#[tokio::test]
+async fn test_tsvector_params() {
+    let lexeme = |word: &str, positions: &[(u16, TsWeight)]| Lexeme {
+        word: word.to_string(),
+        positions: positions
+            .iter()
+            .map(|&(position, weight)| LexemePosition { position, weight })
+            .collect(),
+    };
+
+    test_type(
+        "tsvector",
+        &[
+            (
+                Some(TsVector {
+                    lexemes: vec![
+                        lexeme("a", &[(1, TsWeight::A), (3, TsWeight::D)]),
+                        lexeme("b", &[(2, TsWeight::C)]),
+                        lexeme("c d", &[]),
+                    ],
+                }),
+                "'a:1A,3 b:2C ''c d'''",
+            ),
+            (Some(TsVector { lexemes: vec![] }), "''"),
+            (None, "NULL"),
+        ],
+    )
+    .await;
+
+    let client = connect("user=postgres").await;
+    let vector = TsVector {
+        lexemes: vec![lexeme("x", &[(5, TsWeight::B), (2, TsWeight::A)])],
+    };
+    let row = client
+        .query_one("SELECT $1::tsvector::text", &[&vector])
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, &str>(0), "'x':2A,5B");
+
+    let vector = TsVector {
+        lexemes: vec![lexeme("x", &[(0, TsWeight::D)])],
+    };
+    assert!(
+        client
+            .query_one("SELECT $1::tsvector", &[&vector])
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_tsquery_params() {
+    let lexeme = |word: &str, weights: &[TsWeight], prefix| {
+        Box::new(TsQuery::Lexeme {
+            word: word.to_string(),
+            weights: weights.to_vec(),
+            prefix,
+        })
+    };
+
+    test_type(
+        "tsquery",
+        &[
+            (
+                Some(TsQuery::And(
+                    lexeme("a", &[], false),
+                    Box::new(TsQuery::Phrase(
+                        Box::new(TsQuery::Not(lexeme("b", &[], false))),
+                        lexeme("c", &[TsWeight::A, TsWeight::B], true),
+                        1,
+                    )),
+                )),
+                "'a & !b <-> c:*AB'",
+            ),
+            (
+                Some(TsQuery::Or(
+                    lexeme("a", &[TsWeight::D], false),
+                    Box::new(TsQuery::Phrase(
+                        lexeme("b", &[], false),
+                        lexeme("c", &[], false),
+                        3,
+                    )),
+                )),
+                "'a:D | b <3> c'",
+            ),
+            (None, "NULL"),
+        ],
+    )
+    .await;
+
+    let client = connect("user=postgres").await;
+    let row = client
+        .query_one("SELECT to_tsquery('english', 'the')", &[])
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, TsQuery>(0), TsQuery::Empty);
+    assert!(
+        client
+            .query_one("SELECT $1::tsquery", &[&TsQuery::Empty])
+            .await
+            .is_err()
+    );
+
+    let query = TsQuery::And(lexeme("quick", &[], false), lexeme("fo", &[], true));
+    let row = client
+        .query_one(
+            "SELECT to_tsvector('english', 'the quick brown fox') @@ $1",
+            &[&query],
+        )
+        .await
+        .unwrap();
+    assert!(row.get::<_, bool>(0));
+}
+
+#[tokio::test]
+async fn cube() {
+    test_type(
+        "cube",
+        &[
+            (Some(PgCube::point(vec![1.0, 2.5])), "'(1, 2.5)'"),
+            (
+                Some(PgCube::new(vec![0.0, -1.0, 2.0], vec![1.0, 1.0, 3.0]).unwrap()),
+                "'(0, -1, 2),(1, 1, 3)'",
+            ),
+            (Some(PgCube::point(vec![])), "'()'"),
+            (None, "NULL"),
+        ],
+    )
+    .await;
+
+    assert!(PgCube::new(vec![0.0], vec![1.0, 2.0]).is_none());
+
+    let client = connect("user=postgres").await;
+    let cube = PgCube::new(vec![0.0, 0.0], vec![2.0, 2.0]).unwrap();
+    let row = client
+        .query_one(
+            "SELECT $1::cube @> $2::cube, cube_dim($1), cube_distance($1, $2)",
+            &[&cube, &PgCube::point(vec![1.0, 3.0])],
+        )
+        .await
+        .unwrap();
+    assert!(!row.get::<_, bool>(0));
+    assert_eq!(row.get::<_, i32>(1), 2);
+    assert_eq!(row.get::<_, f64>(2), 1.0);
+
+    let cube = PgCube::point(vec![0.0; 101]);
+    assert!(client.query_one("SELECT $1::cube", &[&cube]).await.is_err());
+}
+
+#[tokio::test]
+async fn jsonpath() {
+    test_type(
+        "JSONPATH",
+        &[
+            (
+                Some(JsonPath::from("$.\"a\"[*]?(@ > 1)")),
+                "'$.a[*] ? (@ > 1)'",
+            ),
+            (Some(JsonPath::from("strict $.\"b\"")), "'strict $.b'"),
+            (None, "NULL"),
+        ],
+    )
+    .await;
+
+    let client = connect("user=postgres").await;
+    let row = client
+        .query_one(
+            "SELECT jsonb_path_query_array('{\"a\": [1, 2, 3]}', $1)::text",
+            &[&JsonPath::from("$.a[*] ? (@ > 1)")],
+        )
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, &str>(0), "[2, 3]");
+
+    assert!(
+        client
+            .query_one("SELECT $1::jsonpath", &[&JsonPath::from("$.a[")])
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn lenient_numeric() {
+    let client = connect("user=postgres").await;
+
+    let row = client
+        .query_one(
+            "SELECT 5::INT8, 1.0::NUMERIC, 40000::INT4, 2.5::NUMERIC, 3::INT2, 0.5::FLOAT4",
+            &[],
+        )
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, Lenient<i32>>(0), Lenient(5));
+    assert_eq!(row.get::<_, Lenient<i64>>(1), Lenient(1));
+    assert!(row.try_get::<_, Lenient<i16>>(2).is_err());
+    assert!(row.try_get::<_, Lenient<i32>>(3).is_err());
+    assert_eq!(row.get::<_, Lenient<f64>>(0), Lenient(5.0));
+    assert_eq!(row.get::<_, Lenient<f64>>(3), Lenient(2.5));
+    assert_eq!(row.get::<_, Lenient<f64>>(4), Lenient(3.0));
+    assert_eq!(row.get::<_, Lenient<f64>>(5), Lenient(0.5));
+
+    let row = client
+        .query_one("SELECT NULL::INT8, 'a'::TEXT", &[])
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, Option<Lenient<i32>>>(0), None);
+    assert!(row.try_get::<_, Lenient<f64>>(1).is_err());
+}
+
 #[tokio::test]
 async fn oidvector() {
     test_type(
@@ -769,3 +1639,92 @@ async fn int2vector() {
     )
     .await;
 }
+
+#[tokio::test]
+async fn catalog_vectors() {
+    let client = connect("user=postgres").await;
+
+    let row = client
+        .query_one(
+            "SELECT proargtypes FROM pg_proc WHERE oid = 'pg_catalog.textcat'::regproc",
+            &[],
+        )
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, Vec<u32>>(0), vec![25, 25]);
+
+    client
+        .batch_execute(
+            "CREATE TEMPORARY TABLE foo (a INT, b INT, c INT);
+             CREATE INDEX foo_idx ON foo (c, a)",
+        )
+        .await
+        .unwrap();
+    let row = client
+        .query_one(
+            "SELECT indkey FROM pg_index WHERE indexrelid = 'foo_idx'::regclass",
+            &[],
+        )
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, Vec<i16>>(0), vec![3, 1]);
+}
+
+#[tokio::test]
+async fn acl_items() {
+    let client = connect("user=postgres").await;
+    client
+        .batch_execute(
+            "CREATE TEMPORARY TABLE foo (id INT);
+             REVOKE ALL ON foo FROM postgres;
+             GRANT SELECT, UPDATE ON foo TO postgres WITH GRANT OPTION;
+             GRANT INSERT ON foo TO PUBLIC",
+        )
+        .await
+        .unwrap();
+
+    let row = client
+        .query_one(
+            "SELECT relacl::text[] FROM pg_class WHERE oid = 'foo'::regclass",
+            &[],
+        )
+        .await
+        .unwrap();
+    let items = row
+        .get::<_, Vec<String>>(0)
+        .iter()
+        .map(|s| s.parse::<AclItem>().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        items,
+        vec![
+            AclItem {
+                grantee: Some("postgres".to_string()),
+                grantor: "postgres".to_string(),
+                privileges: AclPrivileges::SELECT | AclPrivileges::UPDATE,
+                grant_options: AclPrivileges::SELECT | AclPrivileges::UPDATE,
+            },
+            AclItem {
+                grantee: None,
+                grantor: "postgres".to_string(),
+                privileges: AclPrivileges::INSERT,
+                grant_options: AclPrivileges::empty(),
+            },
+        ]
+    );
+    assert_eq!(items[0].to_string(), "postgres=r*w*/postgres");
+    assert_eq!(items[1].to_string(), "=a/postgres");
+
+    let item = "\"a \"\"b\"=Xc*/postgres".parse::<AclItem>().unwrap();
+    assert_eq!(item.grantee.as_deref(), Some("a \"b"));
+    assert_eq!(
+        item.privileges,
+        AclPrivileges::EXECUTE | AclPrivileges::CONNECT
+    );
+    assert_eq!(item.grant_options, AclPrivileges::CONNECT);
+    assert_eq!(item.to_string(), "\"a \"\"b\"=Xc*/postgres");
+
+    assert!("postgres=r".parse::<AclItem>().is_err());
+    assert!("postgres=q/postgres".parse::<AclItem>().is_err());
+    assert!("postgres=*/postgres".parse::<AclItem>().is_err());
+}
@@ -1,5 +1,8 @@
 use serde_json_1::Value;
+use serde_json_1::value::RawValue;
+use tokio_postgres::types::Json;
 
+use crate::connect;
 use crate::types::test_type;
 
 #[tokio::test]
@@ -39,3 +42,26 @@ async fn test_jsonb_params() {
     )
     .await
 }
+
+#[tokio::test]
+async fn raw_value() {
+    let client = connect("user=postgres").await;
+
+    let raw = RawValue::from_string("{\"a\": [1, 2],  \"b\": null}".to_string()).unwrap();
+    let row = client
+        .query_one("SELECT $1::JSON, $2::JSONB", &[&raw, &&*raw])
+        .await
+        .unwrap();
+    assert_eq!(
+        row.get::<_, &RawValue>(0).get(),
+        "{\"a\": [1, 2],  \"b\": null}"
+    );
+    assert_eq!(
+        row.get::<_, Box<RawValue>>(1).get(),
+        "{\"a\": [1, 2], \"b\": null}"
+    );
+    assert_eq!(
+        row.get::<_, Json<&RawValue>>(1).0.get(),
+        "{\"a\": [1, 2], \"b\": null}"
+    );
+}
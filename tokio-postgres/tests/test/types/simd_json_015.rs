@@ -0,0 +1,43 @@
+use simd_json_015::{BorrowedValue, json};
+
+use crate::connect;
+use crate::types::test_type;
+
+#[tokio::test]
+async fn test_json_params() {
+    test_type(
+        "JSON",
+        &[
+            (Some(json!([10, 11, 12])), "'[10, 11, 12]'"),
+            (Some(json!({"f": "asd"})), "'{\"f\": \"asd\"}'"),
+            (None, "NULL"),
+        ],
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_jsonb_params() {
+    test_type(
+        "JSONB",
+        &[
+            (Some(json!([10, 11, 12])), "'[10, 11, 12]'"),
+            (Some(json!({"f": "asd"})), "'{\"f\": \"asd\"}'"),
+            (None, "NULL"),
+        ],
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_borrowed_value() {
+    let client = connect("user=postgres").await;
+
+    let value = BorrowedValue::from(json!({"a": [1, 2.5, null], "b": "c"}));
+    let row = client
+        .query_one("SELECT $1::JSONB, $1::JSON", &[&value])
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, BorrowedValue<'_>>(0), value);
+    assert_eq!(row.get::<_, BorrowedValue<'_>>(1), value);
+}
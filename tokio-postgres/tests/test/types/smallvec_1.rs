@@ -0,0 +1,28 @@
+use smallvec_1::{SmallVec, smallvec};
+
+use crate::types::test_type;
+
+#[tokio::test]
+async fn test_smallvec_params() {
+    test_type(
+        "integer[]",
+        &[
+            (Some(smallvec![1i32, 2]), "ARRAY[1,2]"),
+            (Some(smallvec![1i32, 2, 3, 4, 5]), "ARRAY[1,2,3,4,5]"),
+            (Some(SmallVec::<[i32; 4]>::new()), "ARRAY[]"),
+            (None, "NULL"),
+        ],
+    )
+    .await;
+    test_type(
+        "text[]",
+        &[(
+            Some(SmallVec::<[Option<String>; 2]>::from_vec(vec![
+                Some("a".to_string()),
+                None,
+            ])),
+            "ARRAY['a', NULL]",
+        )],
+    )
+    .await;
+}
@@ -0,0 +1,22 @@
+use tokio_postgres::uring::UringStream;
+use tokio_postgres::{Config, NoTls};
+
+#[test]
+fn smoke_test() {
+    tokio_uring_04::start(async {
+        let config = "user=postgres".parse::<Config>().unwrap();
+        let stream = UringStream::connect("127.0.0.1:5433".parse().unwrap())
+            .await
+            .unwrap();
+        let (client, connection) = config.connect_raw(stream, NoTls).await.unwrap();
+        tokio_uring_04::spawn(async { connection.await.unwrap() });
+
+        let statement = client.prepare("SELECT $1::TEXT").await.unwrap();
+        // large enough to span several reads and writes
+        let value = "a".repeat(100_000);
+        let row = client.query_one(&statement, &[&value]).await.unwrap();
+        assert_eq!(row.get::<_, &str>(0), value);
+
+        drop(client);
+    });
+}